@@ -0,0 +1,120 @@
+//! Benchmarks for the core editing paths
+//!
+//! Run with `cargo bench --bench editing`. See docs/BENCHMARKS.md for comparing
+//! against a saved baseline.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use fresh::model::buffer::Buffer;
+use fresh::model::event::Event;
+use fresh::state::EditorState;
+use fresh::view::stream::build_base_stream;
+use fresh::view::viewport::Viewport;
+use std::hint::black_box;
+use std::io::Write;
+
+/// Files at least this big are loaded lazily
+const LARGE_FILE_THRESHOLD: usize = 1024 * 1024;
+
+/// Source-like text with `lines` lines
+fn sample_text(lines: usize) -> String {
+    (0..lines)
+        .map(|i| {
+            format!(
+                "    let value_{i} = compute(value_{}, \"line {i}\");\n",
+                i / 2
+            )
+        })
+        .collect()
+}
+
+fn state_with_text(text: &str) -> EditorState {
+    let mut state = EditorState::new(120, 40, LARGE_FILE_THRESHOLD);
+    state.buffer = Buffer::from_str(text, LARGE_FILE_THRESHOLD);
+    state
+}
+
+/// Typing characters one at a time in the middle of a file
+fn insert_at_cursor(c: &mut Criterion) {
+    let text = sample_text(10_000);
+    let mut group = c.benchmark_group("insert_at_cursor");
+    group.throughput(Throughput::Elements(100));
+    group.bench_function("100_chars_mid_file", |b| {
+        b.iter_batched(
+            || state_with_text(&text),
+            |mut state| {
+                let cursor_id = state.cursors.primary_id();
+                let start = text.len() / 2;
+                for position in start..start + 100 {
+                    state.apply(&Event::Insert {
+                        position,
+                        text: "x".to_string(),
+                        cursor_id,
+                    });
+                }
+                state
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+/// Paging through a 100k line file
+fn large_file_scroll(c: &mut Criterion) {
+    let text = sample_text(100_000);
+    let mut buffer = Buffer::from_str(&text, LARGE_FILE_THRESHOLD);
+    c.bench_function("large_file_scroll/page_down_up", |b| {
+        b.iter(|| {
+            let mut viewport = Viewport::new(120, 40);
+            for _ in 0..50 {
+                viewport.scroll_down(&mut buffer, 40);
+            }
+            for _ in 0..50 {
+                viewport.scroll_up(&mut buffer, 40);
+            }
+            black_box(viewport.top_byte)
+        })
+    });
+}
+
+/// Building the view stream for one screen of text
+fn view_stream_build(c: &mut Criterion) {
+    let text = sample_text(10_000);
+    let mut state = state_with_text(&text);
+    let start = text.len() / 2;
+    let end = start + 40 * 80;
+    c.bench_function("view_stream_build/one_screen", |b| {
+        b.iter(|| black_box(build_base_stream(&mut state, start, end)))
+    });
+}
+
+/// Reading from a lazily loaded file, which loads (and splits out) a chunk
+/// for each region first touched
+fn large_file_chunk_loads(c: &mut Criterion) {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(sample_text(200_000).as_bytes()).unwrap();
+    let len = file.as_file().metadata().unwrap().len() as usize;
+
+    c.bench_function("large_file_chunk_loads/scattered_reads", |b| {
+        b.iter_batched(
+            || Buffer::load_from_file(file.path(), LARGE_FILE_THRESHOLD).unwrap(),
+            |mut buffer| {
+                for i in 0..16 {
+                    let offset = (i * 7919 * 4096) % (len - 4096);
+                    black_box(buffer.get_text_range_mut(offset, 4096).unwrap());
+                }
+                buffer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(
+    benches,
+    insert_at_cursor,
+    large_file_scroll,
+    view_stream_build,
+    large_file_chunk_loads
+);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Benchmarks for fuzzy filtering in pickers
+//!
+//! Run with `cargo bench --bench fuzzy_filter`. See docs/BENCHMARKS.md for
+//! comparing against a saved baseline.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use fresh::config::{Config, FuzzyMatcherKind};
+use fresh::input::command_registry::CommandRegistry;
+use fresh::input::commands::{Command, CommandSource};
+use fresh::input::fuzzy::fuzzy_match;
+use fresh::input::keybindings::{Action, KeyContext, KeyContextStack, KeybindingResolver};
+use fresh::input::when_clause::WhenContext;
+use std::hint::black_box;
+
+const ENTRIES: usize = 10_000;
+
+/// Paths shaped like a mid-sized project's file list
+fn sample_paths() -> Vec<String> {
+    let dirs = [
+        "src/app",
+        "src/input",
+        "src/view/ui",
+        "tests/e2e",
+        "plugins/lib",
+    ];
+    (0..ENTRIES)
+        .map(|i| format!("{}/module_{}/file_{i}.rs", dirs[i % dirs.len()], i % 97))
+        .collect()
+}
+
+/// Matching every file in a project against a typed query
+fn fuzzy_files(c: &mut Criterion) {
+    let paths = sample_paths();
+    let mut group = c.benchmark_group("fuzzy_filter");
+    group.throughput(Throughput::Elements(ENTRIES as u64));
+    group.bench_function("10k_files", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|path| fuzzy_match(black_box("viewfile12"), path).matched)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+/// Filtering the command palette with many plugin commands registered
+fn fuzzy_commands(c: &mut Criterion) {
+    let registry = CommandRegistry::new();
+    for i in 0..ENTRIES {
+        registry.register(Command {
+            name: format!("Plugin Command {i}"),
+            description: format!("Runs plugin action number {i}"),
+            action: Action::PluginAction(format!("action_{i}")),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Plugin("bench".to_string()),
+            when: None,
+            argument: None,
+        });
+    }
+    let resolver = KeybindingResolver::new(&Config::default());
+    let contexts = KeyContextStack::from(KeyContext::Normal);
+    let when = WhenContext::default();
+    let matcher = FuzzyMatcherKind::default().matcher();
+
+    let mut group = c.benchmark_group("fuzzy_filter");
+    group.throughput(Throughput::Elements(ENTRIES as u64));
+    group.bench_function("10k_commands", |b| {
+        b.iter(|| {
+            black_box(registry.filter(black_box("plgcmd 42"), &contexts, &resolver, &when, matcher))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fuzzy_files, fuzzy_commands);
+criterion_main!(benches);
@@ -133,6 +133,18 @@ fn rust_type_to_ts(rust_type: &str) -> String {
             "CreateVirtualBufferInExistingSplitOptions".to_string()
         }
         "TsTextPropertyEntry" => "TextPropertyEntry".to_string(),
+        "TsPanelOptions" => "PanelOptions".to_string(),
+        "TsPanelText" => "PanelText".to_string(),
+        "TsJobOptions" => "JobOptions".to_string(),
+        "TsDecorations" => "Decorations".to_string(),
+        "TsOverlayDecoration" => "OverlayDecoration".to_string(),
+        "TsVirtualTextDecoration" => "VirtualTextDecoration".to_string(),
+        "TsVirtualLineDecoration" => "VirtualLineDecoration".to_string(),
+        "TsTextObjectOptions" => "TextObjectOptions".to_string(),
+        "TsFileFilter" => "FileFilter".to_string(),
+        "TsSearchMatch" => "SearchMatch".to_string(),
+        "TsFilePreview" => "FilePreview".to_string(),
+        "TsTextEdit" => "TextEdit".to_string(),
 
         // Serde JSON value
         "serde_json::Value" => "unknown".to_string(),
@@ -485,6 +497,18 @@ fn parse_struct(lines: &[&str], struct_line_idx: usize) -> Option<StructInfo> {
         "TsSelectionRange" => "SelectionRange".to_string(),
         "TsSuggestion" => "PromptSuggestion".to_string(),
         "TsTextPropertyEntry" => "TextPropertyEntry".to_string(),
+        "TsPanelOptions" => "PanelOptions".to_string(),
+        "TsPanelText" => "PanelText".to_string(),
+        "TsJobOptions" => "JobOptions".to_string(),
+        "TsDecorations" => "Decorations".to_string(),
+        "TsOverlayDecoration" => "OverlayDecoration".to_string(),
+        "TsVirtualTextDecoration" => "VirtualTextDecoration".to_string(),
+        "TsVirtualLineDecoration" => "VirtualLineDecoration".to_string(),
+        "TsTextObjectOptions" => "TextObjectOptions".to_string(),
+        "TsFileFilter" => "FileFilter".to_string(),
+        "TsSearchMatch" => "SearchMatch".to_string(),
+        "TsFilePreview" => "FilePreview".to_string(),
+        "TsTextEdit" => "TextEdit".to_string(),
         _ => name.clone(),
     };
 
@@ -605,6 +629,7 @@ fn generate_typescript_types() -> Result<(), Box<dyn std::error::Error>> {
     categories.insert("path", Vec::new());
     categories.insert("event", Vec::new());
     categories.insert("virtual_buffer", Vec::new());
+    categories.insert("panel", Vec::new());
 
     for op in &ops {
         let category = categorize_op(&op.js_name, op.is_async);
@@ -679,6 +704,7 @@ interface EditorAPI {
         "Virtual Buffer Operations",
         &categories["virtual_buffer"],
     );
+    add_category_ops(&mut output, "Panel Operations", &categories["panel"]);
 
     output.push_str(
         r#"}
@@ -838,6 +864,7 @@ fn generate_markdown_docs(
         ("path", "Path Operations"),
         ("event", "Event/Hook Operations"),
         ("virtual_buffer", "Virtual Buffer Operations"),
+        ("panel", "Panel Operations"),
     ];
 
     md.push_str("## API Reference\n\n");
@@ -986,6 +1013,11 @@ fn categorize_op(js_name: &str, is_async: bool) -> &'static str {
         return "virtual_buffer";
     }
 
+    // Panel operations
+    if js_name.contains("Panel") {
+        return "panel";
+    }
+
     // Event operations
     if js_name == "on" || js_name == "off" || js_name == "getHandlers" {
         return "event";
@@ -997,12 +1029,17 @@ fn categorize_op(js_name: &str, is_async: bool) -> &'static str {
     }
 
     // Environment operations
-    if js_name == "getEnv" || js_name == "getCwd" {
+    if js_name == "getEnv"
+        || js_name == "getCwd"
+        || js_name == "getFileFilter"
+        || js_name == "filterPaths"
+    {
         return "environment";
     }
 
     // File system operations
     if js_name == "readFile"
+        || js_name == "readFilePreview"
         || js_name == "writeFile"
         || js_name == "fileExists"
         || js_name == "fileStat"
@@ -1017,7 +1054,10 @@ fn categorize_op(js_name: &str, is_async: bool) -> &'static str {
     }
 
     // Prompt operations
-    if js_name == "startPrompt" || js_name == "setPromptSuggestions" {
+    if js_name == "startPrompt"
+        || js_name == "setPromptSuggestions"
+        || js_name.starts_with("prompt")
+    {
         return "prompt";
     }
 
@@ -1040,13 +1080,16 @@ fn categorize_op(js_name: &str, is_async: bool) -> &'static str {
     if js_name.contains("Overlay")
         || js_name.contains("overlay")
         || js_name.contains("VirtualText")
+        || js_name == "createNamespace"
+        || js_name == "clearNamespaceInRange"
+        || js_name == "setDecorations"
         || js_name == "refreshLines"
     {
         return "overlay";
     }
 
     // Async operations (that aren't already categorized)
-    if is_async && js_name == "spawnProcess" {
+    if is_async && (js_name == "spawnProcess" || js_name == "searchProject") {
         return "async";
     }
 
@@ -7,7 +7,7 @@
  * This tracks in-memory changes, not git changes.
  *
  * This plugin uses a simpler approach: it marks lines as modified when edits happen
- * (after_insert/after_delete hooks), and clears all modified markers on save.
+ * (buffer_changed hook), and clears all modified markers on save.
  * It doesn't compare content - it just tracks which lines have been touched since save.
  *
  * Indicator symbols:
@@ -186,21 +186,26 @@ globalThis.onBufferModifiedAfterSave = function (args: {
 };
 
 /**
- * Handle after insert - mark affected lines as modified
+ * Handle buffer changes - mark the lines each change touched as modified
  *
  * Note: Line indicators automatically track position changes via byte-position markers.
  * We only need to add new indicators for the modified lines; existing indicators
- * will automatically shift to stay on the correct lines.
+ * will automatically shift to stay on the correct lines, and markers within deleted
+ * ranges are removed.
  */
-globalThis.onBufferModifiedAfterInsert = function (args: {
+globalThis.onBufferModifiedBufferChanged = function (args: {
   buffer_id: number;
-  position: number;
-  text: string;
-  affected_start: number;
-  affected_end: number;
-  start_line: number;
-  end_line: number;
-  lines_added: number;
+  revision: number;
+  changes: Array<{
+    start: number;
+    end: number;
+    start_line: number;
+    start_character: number;
+    end_line: number;
+    end_character: number;
+    old_text: string;
+    new_text: string;
+  }>;
 }): boolean {
   const bufferId = args.buffer_id;
 
@@ -208,40 +213,12 @@ globalThis.onBufferModifiedAfterInsert = function (args: {
     return true;
   }
 
-  // Mark all affected lines (from start_line to end_line inclusive)
-  // The indicator markers will automatically track their positions
-  markLinesModified(bufferId, args.start_line, args.end_line);
-  reapplyIndicatorsFromDiff(bufferId);
-
-  return true;
-};
-
-/**
- * Handle after delete - mark affected line as modified
- *
- * Note: Line indicators automatically track position changes via byte-position markers.
- * Markers within deleted ranges are automatically removed. We only need to mark the
- * line where the deletion occurred.
- */
-globalThis.onBufferModifiedAfterDelete = function (args: {
-  buffer_id: number;
-  range: { start: number; end: number };
-  deleted_text: string;
-  affected_start: number;
-  deleted_len: number;
-  start_line: number;
-  end_line: number;
-  lines_removed: number;
-}): boolean {
-  const bufferId = args.buffer_id;
-
-  if (!bufferStates.has(bufferId)) {
-    return true;
+  // Lines are those right after each change; later changes of the batch may
+  // have shifted them, which the diff against the saved text corrects
+  for (const change of args.changes) {
+    const linesAdded = change.new_text.split("\n").length - 1;
+    markLinesModified(bufferId, change.start_line, change.start_line + linesAdded);
   }
-
-  // Mark the line where deletion occurred
-  // Markers for deleted lines are automatically cleaned up
-  markLinesModified(bufferId, args.start_line, args.start_line);
   reapplyIndicatorsFromDiff(bufferId);
 
   return true;
@@ -265,8 +242,7 @@ globalThis.onBufferModifiedBufferClosed = function (args: {
 editor.on("after_file_open", "onBufferModifiedAfterFileOpen");
 editor.on("buffer_activated", "onBufferModifiedBufferActivated");
 editor.on("after_file_save", "onBufferModifiedAfterSave");
-editor.on("after-insert", "onBufferModifiedAfterInsert");
-editor.on("after-delete", "onBufferModifiedAfterDelete");
+editor.on("buffer_changed", "onBufferModifiedBufferChanged");
 editor.on("buffer_closed", "onBufferModifiedBufferClosed");
 
 // Initialize for the current buffer
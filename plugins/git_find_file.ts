@@ -12,101 +12,15 @@ let allFiles: string[] = [];
 let filteredFiles: string[] = [];
 let isLoading = false;
 
-// Simple fuzzy filter function
-function fuzzyMatch(str: string, pattern: string): boolean {
-  if (pattern === "") {
-    return true;
-  }
-
-  str = str.toLowerCase();
-  pattern = pattern.toLowerCase();
-
-  let strIdx = 0;
-  let patIdx = 0;
-
-  while (strIdx < str.length && patIdx < pattern.length) {
-    if (str[strIdx] === pattern[patIdx]) {
-      patIdx++;
-    }
-    strIdx++;
-  }
-
-  return patIdx >= pattern.length;
-}
-
-// Score a fuzzy match (higher is better)
-function fuzzyScore(str: string, pattern: string): number {
-  if (pattern === "") return 0;
-
-  str = str.toLowerCase();
-  pattern = pattern.toLowerCase();
-
-  let score = 0;
-  let strIdx = 0;
-  let patIdx = 0;
-  let consecutiveMatches = 0;
-  let lastMatchIdx = -1;
-
-  while (strIdx < str.length && patIdx < pattern.length) {
-    if (str[strIdx] === pattern[patIdx]) {
-      // Bonus for consecutive matches
-      if (lastMatchIdx === strIdx - 1) {
-        consecutiveMatches++;
-        score += consecutiveMatches * 10;
-      } else {
-        consecutiveMatches = 1;
-        score += 1;
-      }
-
-      // Bonus for matching at start of path segments
-      if (strIdx === 0 || str[strIdx - 1] === "/" || str[strIdx - 1] === "_" || str[strIdx - 1] === "-") {
-        score += 15;
-      }
-
-      // Bonus for matching filename (after last /)
-      const lastSlash = str.lastIndexOf("/");
-      if (strIdx > lastSlash) {
-        score += 5;
-      }
-
-      lastMatchIdx = strIdx;
-      patIdx++;
-    }
-    strIdx++;
-  }
-
-  // Penalty for longer paths
-  score -= str.length * 0.1;
-
-  return patIdx >= pattern.length ? score : -1;
-}
-
-// Filter and sort files by query using fuzzy matching
+// Filter and sort files by query with the editor's fuzzy matcher
 function filterFiles(files: string[], query: string): string[] {
   if (query === "" || query.trim() === "") {
     // Return first 100 files for empty query
     return files.slice(0, 100);
   }
 
-  const scored: Array<{ file: string; score: number }> = [];
-
-  for (const file of files) {
-    const score = fuzzyScore(file, query);
-    if (score > 0) {
-      scored.push({ file, score });
-    }
-
-    // Stop early if we have enough high-quality matches
-    if (scored.length >= 500) {
-      break;
-    }
-  }
-
-  // Sort by score descending
-  scored.sort((a, b) => b.score - a.score);
-
-  // Return top 100 results
-  return scored.slice(0, 100).map((s) => s.file);
+  // Top 100 results, best first
+  return editor.fuzzyFilter(query, files, 100).map((m) => files[m.index]);
 }
 
 // Load git-tracked files asynchronously
@@ -115,6 +29,15 @@ async function loadGitFiles(): Promise<void> {
     return;
   }
 
+  // The editor's project scan, when it is done, saves running git
+  const scanned = editor.listProjectFiles();
+  if (scanned !== null) {
+    allFiles = scanned;
+    editor.debug(`Loaded ${allFiles.length} scanned files`);
+    editor.setStatus(`Git Find File: ${allFiles.length} files indexed`);
+    return;
+  }
+
   isLoading = true;
   editor.setStatus("Loading git files...");
 
@@ -123,6 +46,8 @@ async function loadGitFiles(): Promise<void> {
 
     if (result.exit_code === 0) {
       allFiles = result.stdout.split("\n").filter((line) => line.trim() !== "");
+      // Leave out files excluded by the project's include/exclude globs
+      allFiles = editor.filterPaths(allFiles);
 
       editor.debug(`Loaded ${allFiles.length} git-tracked files`);
       editor.setStatus(`Git Find File: ${allFiles.length} files indexed`);
@@ -140,6 +65,13 @@ async function loadGitFiles(): Promise<void> {
   }
 }
 
+// Keys for the selected file besides Enter
+const FILE_ACTIONS: SuggestionAction[] = [
+  { key: "ctrl+enter", label: "open to the right", id: "right" },
+  { key: "alt+enter", label: "open below", id: "below" },
+  { key: "ctrl+d", label: "delete", id: "delete" },
+];
+
 // Convert filtered files to prompt suggestions
 function filesToSuggestions(files: string[]): PromptSuggestion[] {
   return files.map((file) => {
@@ -148,10 +80,25 @@ function filesToSuggestions(files: string[]): PromptSuggestion[] {
       description: undefined,
       value: file,
       disabled: false,
+      actions: FILE_ACTIONS,
     };
   });
 }
 
+// Delete a file after asking, and forget it
+async function deleteFile(file: string): Promise<void> {
+  if (!(await editor.promptConfirm(`Delete ${file}?`))) {
+    return;
+  }
+  try {
+    await editor.removeFile(file);
+    allFiles = allFiles.filter((f) => f !== file);
+    editor.setStatus(`Deleted ${file}`);
+  } catch (e) {
+    editor.setStatus(`Failed to delete ${file}: ${e}`);
+  }
+}
+
 // Global function to start file finder
 globalThis.start_git_find_file = async function (): Promise<void> {
   // Load files if not already loaded
@@ -245,6 +192,30 @@ globalThis.onGitFindFilePromptConfirmed = function (args: {
   return true;
 };
 
+// Handle the selected file's other keys
+globalThis.onGitFindFilePromptAction = function (args: {
+  prompt_type: string;
+  selected_index: number;
+  action: string;
+}): boolean {
+  if (args.prompt_type !== "git-find-file") {
+    return true; // Not our prompt
+  }
+
+  const file = filteredFiles[args.selected_index];
+  if (!file) {
+    return true;
+  }
+
+  if (args.action === "right" || args.action === "below") {
+    editor.openFileToSide(file, 0, 0, args.action === "below");
+  } else if (args.action === "delete") {
+    deleteFile(file);
+  }
+
+  return true;
+};
+
 // Handle prompt cancellation (user pressed Escape)
 globalThis.onGitFindFilePromptCancelled = function (args: { prompt_type: string }): boolean {
   if (args.prompt_type !== "git-find-file") {
@@ -261,6 +232,7 @@ globalThis.onGitFindFilePromptCancelled = function (args: { prompt_type: string
 // Register event handlers
 editor.on("prompt_changed", "onGitFindFilePromptChanged");
 editor.on("prompt_confirmed", "onGitFindFilePromptConfirmed");
+editor.on("prompt_action", "onGitFindFilePromptAction");
 editor.on("prompt_cancelled", "onGitFindFilePromptCancelled");
 
 // Reload git files command
@@ -39,10 +39,15 @@ function parseGitGrepOutput(stdout: string): {
   const results: GrepMatch[] = [];
   const suggestions: PromptSuggestion[] = [];
 
-  for (const line of stdout.split("\n")) {
-    if (!line.trim()) continue;
+  const lines = stdout.split("\n").filter((line) => line.trim() !== "");
+  // Leave out files excluded by the project's include/exclude globs
+  const included = new Set(
+    editor.filterPaths(lines.map((line) => line.split(":")[0]))
+  );
+
+  for (const line of lines) {
     const match = parseGitGrepLine(line);
-    if (match) {
+    if (match && included.has(match.file)) {
       results.push(match);
       suggestions.push({
         text: `${match.file}:${match.line}:${match.column}`,
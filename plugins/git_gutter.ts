@@ -396,6 +396,58 @@ globalThis.onGitGutterAfterSave = function (args: {
 // Line indicators automatically track position changes via byte-position markers.
 // A full re-diff happens on save. For unsaved changes, see buffer_modified plugin.
 
+/**
+ * Handle buffer changes - keep the known hunks on the lines their indicators
+ * moved to until the next re-diff
+ */
+globalThis.onGitGutterBufferChanged = function (args: {
+  buffer_id: number;
+  changes: Array<{
+    start_line: number;
+    end_line: number;
+    new_text: string;
+  }>;
+}): boolean {
+  const state = bufferStates.get(args.buffer_id);
+  if (!state) {
+    return true;
+  }
+
+  for (const change of args.changes) {
+    const linesAdded = change.new_text.split("\n").length - 1;
+    const delta = linesAdded - (change.end_line - change.start_line);
+    if (delta === 0) {
+      continue;
+    }
+    for (const hunk of state.hunks) {
+      // Hunk lines are 1-indexed, change lines 0-indexed
+      if (hunk.startLine - 1 > change.end_line) {
+        hunk.startLine += delta;
+      }
+    }
+  }
+
+  return true;
+};
+
+/**
+ * Handle buffer renamed (Save As, or a rename in the file explorer) -
+ * recompute indicators for the new path
+ */
+globalThis.onGitGutterBufferRenamed = function (args: {
+  buffer_id: number;
+  old_path: string | null;
+  new_path: string;
+}): boolean {
+  bufferStates.set(args.buffer_id, {
+    filePath: args.new_path,
+    hunks: [],
+    updating: false,
+  });
+  updateGitGutter(args.buffer_id);
+  return true;
+};
+
 /**
  * Handle buffer closed - cleanup state
  */
@@ -444,11 +496,13 @@ globalThis.git_gutter_refresh = function (): void {
 // =============================================================================
 
 // Register event handlers
-// Note: No need to register after-insert/after-delete hooks - indicators
-// automatically track position changes via byte-position markers in the editor.
+// Note: Indicators track position changes via byte-position markers in the
+// editor; buffer_changed only keeps the hunk list in step with them.
 editor.on("after_file_open", "onGitGutterAfterFileOpen");
 editor.on("buffer_activated", "onGitGutterBufferActivated");
 editor.on("after_file_save", "onGitGutterAfterSave");
+editor.on("buffer_renamed", "onGitGutterBufferRenamed");
+editor.on("buffer_changed", "onGitGutterBufferChanged");
 editor.on("buffer_closed", "onGitGutterBufferClosed");
 
 // Register commands
@@ -81,6 +81,74 @@ interface LayoutHints {
   column_guides?: number[] | null;
 }
 
+/** Edit for applyEdits: replace bytes start..end with text */
+interface TextEdit {
+  /** Start byte offset (inclusive) */
+  start: number;
+  /** End byte offset (exclusive); equal to start to insert */
+  end: number;
+  /** Replacement text (empty to delete) */
+  text: string;
+}
+
+/** Decorations set in one call by setDecorations */
+interface Decorations {
+  /** Only replace the namespace's decorations from this byte position */
+  start?: number | null;
+  /** Only replace the namespace's decorations up to this byte position */
+  end?: number | null;
+  /** Styled byte ranges */
+  overlays?: OverlayDecoration[] | null;
+  /** Inline texts */
+  virtual_texts?: VirtualTextDecoration[] | null;
+  /** Full lines above or below source lines */
+  virtual_lines?: VirtualLineDecoration[] | null;
+}
+
+/** Styled byte range */
+interface OverlayDecoration {
+  /** Start byte offset */
+  start: number;
+  /** End byte offset */
+  end: number;
+  /** Color as [r, g, b] */
+  color: [number, number, number];
+  /** Add underline decoration */
+  underline?: boolean | null;
+  /** Use bold text */
+  bold?: boolean | null;
+  /** Use italic text */
+  italic?: boolean | null;
+}
+
+/** Inline text that isn't part of the buffer */
+interface VirtualTextDecoration {
+  /** Byte position of the character the text is attached to */
+  position: number;
+  /** Text to display */
+  text: string;
+  /** Color as [r, g, b] */
+  color: [number, number, number];
+  /** Show before the character instead of after it */
+  before?: boolean | null;
+}
+
+/** Full line shown above or below a source line */
+interface VirtualLineDecoration {
+  /** Byte position in the source line */
+  position: number;
+  /** Line content */
+  text: string;
+  /** Foreground color as [r, g, b] */
+  fg: [number, number, number];
+  /** Background color as [r, g, b] (transparent if omitted) */
+  bg?: [number, number, number] | null;
+  /** Show above the source line instead of below it */
+  above?: boolean | null;
+  /** Ordering of lines at the same position (higher = later) */
+  priority?: number | null;
+}
+
 /** Result from spawnProcess */
 interface SpawnResult {
   /** Complete stdout as string. Newlines preserved; trailing newline included. */
@@ -91,12 +159,44 @@ interface SpawnResult {
   exit_code: number;
 }
 
+/** Line found by searchProject */
+interface SearchMatch {
+  /** File path, relative to the working directory */
+  file: string;
+  /** Line number (1-indexed) */
+  line: number;
+  /** Byte column of the match (1-indexed) */
+  column: number;
+  /** Text of the line */
+  content: string;
+}
+
+/** Item matched by fuzzyFilter */
+interface FuzzyMatch {
+  /** Index of the item in the list given to fuzzyFilter */
+  index: number;
+  /** Match quality (higher is better) */
+  score: number;
+  /** Character indices of the item that matched the query */
+  positions: number[];
+}
+
 /** Result from spawnBackgroundProcess - just the process ID */
 interface BackgroundProcessResult {
   /** Unique process ID for later reference (kill, status check) */
   process_id: number;
 }
 
+/** Options for spawnJob */
+interface JobOptions {
+  /** Working directory; null uses editor's cwd */
+  cwd?: string | null;
+  /** Environment variables to add for the process */
+  env?: Record<string, unknown> | null;
+  /** Shown in the status bar while the job runs (defaults to the command) */
+  title?: string | null;
+}
+
 /** File stat information */
 interface FileStat {
   /** Whether the path exists */
@@ -121,6 +221,8 @@ interface BufferInfo {
   modified: boolean;
   /** Buffer length in bytes */
   length: number;
+  /** Revision of the text, increased by every change (for applyEdits) */
+  revision: number;
 }
 
 /** Diff vs last save for a buffer */
@@ -170,6 +272,36 @@ interface PromptSuggestion {
   disabled?: boolean | null;
   /** Optional keybinding hint */
   keybinding?: string | null;
+  /** Actions run with their own keys while the suggestion is selected */
+  actions?: SuggestionAction[] | null;
+}
+
+/** Secondary action of a prompt suggestion, reported by the prompt_action hook */
+interface SuggestionAction {
+  /** Key that runs it (e.g. "ctrl+enter") */
+  key: string;
+  /** What it does, shown below the suggestions */
+  label: string;
+  /** Passed to the prompt_action hook as `action` */
+  id: string;
+  /** Leave the prompt open after running it */
+  keep_open?: boolean | null;
+}
+
+/** Lines read by readFilePreview */
+interface FilePreview {
+  /** Line number of the first line (1-indexed) */
+  first_line: number;
+  /** The lines, without line endings (very long lines are cut short) */
+  lines: string[];
+}
+
+/** Include/exclude globs of the project */
+interface FileFilter {
+  /** Only files matching one of these are part of the project (all if empty) */
+  include: string[];
+  /** Files and directories matching these are left out */
+  exclude: string[];
 }
 
 /** Directory entry from readDir */
@@ -258,6 +390,46 @@ interface CreateVirtualBufferInCurrentSplitOptions {
   editing_disabled?: boolean | null;
 }
 
+/** How a custom text object is found */
+interface TextObjectOptions {
+  /** Regex whose match around the cursor is the object; its first group is the inside */
+  pattern?: string | null;
+  /** Tree-sitter queries by language name ("rust", "python", ...), capturing @around and @inside */
+  queries?: Record<string, unknown> | null;
+}
+
+/** Options for registerPanel */
+interface PanelOptions {
+  /** Unique panel ID (registering an existing ID updates that panel) */
+  id: string;
+  /** Title shown in the panel border (defaults to the ID) */
+  title?: string | null;
+  /** Where to dock the panel: "left", "right" or "bottom" */
+  position: string;
+  /** Width (left/right) or height (bottom) in cells, borders included */
+  size: number;
+  /** Mode whose keybindings apply while the panel is focused; define with defineMode first */
+  mode?: string | null;
+  /** Name of the globalThis function that draws the panel, called with (rect, draw) */
+  render: string;
+}
+
+/** Text drawn in a panel */
+interface PanelText {
+  /** Column within the panel's content area (0-based) */
+  x: number;
+  /** Row within the panel's content area (0-based) */
+  y: number;
+  /** Text to draw (clipped at the panel's edge) */
+  text: string;
+  /** Foreground color as [r, g, b] */
+  fg?: [number, number, number] | null;
+  /** Background color as [r, g, b] */
+  bg?: [number, number, number] | null;
+  /** Draw in bold */
+  bold?: boolean | null;
+}
+
 /**
  * Main editor API interface
  */
@@ -384,15 +556,41 @@ interface EditorAPI {
    * Start an interactive prompt
    * @param label - Label to display (e.g., "Git grep: ")
    * @param prompt_type - Type identifier (e.g., "git-grep")
+   * @param initial_input - Text the input starts with (empty if omitted)
    * @returns true if prompt was started successfully
    */
-  startPrompt(label: string, prompt_type: string): boolean;
+  startPrompt(label: string, prompt_type: string, initial_input?: string | null): boolean;
   /**
    * Set suggestions for the current prompt
    * @param suggestions - Array of suggestions to display
    * @returns true if suggestions were set successfully
    */
   setPromptSuggestions(suggestions: PromptSuggestion[]): boolean;
+  /**
+   * Ask the user for text in the prompt line
+   *
+   * Opening a prompt cancels any prompt the user is typing in.
+   * @param title - Label to display (e.g., "Branch name")
+   * @param default_value - Initial text of the input
+   * @returns Promise resolving to the entered text, or null if cancelled
+   * @example
+   * const name = await editor.promptInput("Branch name", "main");
+   * if (name !== null) { ... }
+   */
+  promptInput(title: string, default_value: string): Promise<string | null>;
+  /**
+   * Ask the user to pick one of `items` (filtered as they type)
+   * @param title - Label to display (e.g., "Remote")
+   * @param items - Choices; each resolves to its value (or text if it has none)
+   * @returns Promise resolving to the chosen item's value, or null if cancelled or no item matches
+   */
+  promptSelect(title: string, items: PromptSuggestion[]): Promise<string | null>;
+  /**
+   * Ask the user a yes/no question
+   * @param message - Question to display (e.g., "Discard local changes?")
+   * @returns Promise resolving to true for yes, false for no or if cancelled
+   */
+  promptConfirm(message: string): Promise<boolean>;
 
   // === Buffer Mutations ===
   /**
@@ -425,6 +623,24 @@ interface EditorAPI {
    * @param end - End byte offset (exclusive)
    */
   deleteRange(buffer_id: number, start: number, end: number): boolean;
+  /**
+   * Apply edits computed against an earlier revision of a buffer
+   *
+   * Ranges refer to the text at `revision` (see getBufferInfo). They are moved
+   * over any changes made since, e.g. by the user typing while a formatter ran,
+   * and applied as one undoable change. If an edit overlaps a newer change,
+   * the promise is rejected and nothing is applied (with skip_conflicting,
+   * that edit is dropped instead). Resolves to the number of edits applied.
+   * @param buffer_id - Target buffer ID
+   * @param revision - Revision of the text the ranges refer to
+   * @param edits - Edits in any order (they must not overlap)
+   * @param skip_conflicting - Drop conflicting edits instead of rejecting all
+   * @example
+   * const revision = editor.getBufferInfo(bufferId).revision;
+   * const edits = await computeEdits(bufferId);
+   * await editor.applyEdits(bufferId, revision, edits);
+   */
+  applyEdits(buffer_id: number, revision: number, edits: TextEdit[], skip_conflicting: boolean): Promise<number>;
   /**
    * Clear all overlays in a namespace
    * @param buffer_id - The buffer ID
@@ -484,8 +700,10 @@ interface EditorAPI {
    * @param tokens - Array of tokens with source offsets
    * @param source_map - Array of source offsets (null for injected)
    * @param layout_hints - Optional layout hints (compose width, column guides)
+   * @param revision - Buffer revision the tokens came from (from view_transform_request);
+   *   the transform is dropped if the buffer has been edited since
    */
-  submitViewTransform(buffer_id: number, split_id?: number | null, start: number, end: number, tokens: ViewTokenWire[], layout_hints?: LayoutHints | null): boolean;
+  submitViewTransform(buffer_id: number, split_id?: number | null, start: number, end: number, tokens: ViewTokenWire[], layout_hints?: LayoutHints | null, revision?: number | null): boolean;
   /**
    * Clear view transform for a buffer/split (returns to normal rendering)
    * @param buffer_id - Buffer ID
@@ -505,16 +723,30 @@ interface EditorAPI {
    * @param description - Human-readable description
    * @param action - JavaScript function name to call when command is triggered
    * @param contexts - Comma-separated list of contexts (e.g., "normal,prompt")
+   * @param when - When clause the command is enabled under (e.g., "has_selection && !read_only"; empty for always)
    * @param source - Plugin source name (empty string for builtin)
-   * @returns true if command was registered
+   * @returns true if command was registered (false if the when clause is invalid)
    */
-  registerCommand(name: string, description: string, action: string, contexts: string, source: string): boolean;
+  registerCommand(name: string, description: string, action: string, contexts: string, when: string, source: string): boolean;
   /**
    * Unregister a custom command by name
    * @param name - The name of the command to unregister
    * @returns true if the command was successfully unregistered
    */
   unregisterCommand(name: string): boolean;
+  /**
+   * Run an action when a gutter column is clicked, instead of the configured one
+   *
+   * The cursor moves to the clicked line before the action runs. The
+   * "gutter_click" hook fires for every gutter click either way.
+   * @param column - "sign", "line_number" or "separator"
+   * @param action - Action name or plugin command to run (null to restore the default)
+   * @returns true if the request was sent successfully
+   * @example
+   * editor.registerCommand("Toggle Breakpoint", "Toggle a breakpoint", "toggle_breakpoint");
+   * editor.setGutterClickAction("sign", "toggle_breakpoint");
+   */
+  setGutterClickAction(column: string, action?: string | null): boolean;
   /**
    * Open a file in the editor, optionally at a specific location
    * @param path - File path to open
@@ -532,6 +764,23 @@ interface EditorAPI {
    * @returns true if file was opened
    */
   openFileInSplit(split_id: number, path: string, line: number, column: number): boolean;
+  /**
+   * Open a file in a new split next to the focused one
+   * @param path - File path to open
+   * @param vertical - Split side by side (true) or one above the other (false)
+   * @returns true if file was opened
+   */
+  openFileInNewSplit(path: string, vertical?: boolean): boolean;
+  /**
+   * Open a file in another pane, as "Open to the Right/Below" does: in a new
+   * split if there is one pane, else in the other pane or one the user picks
+   * @param path - File path to open
+   * @param line - Line number to jump to (0 for no jump)
+   * @param column - Column number to jump to (0 for no jump)
+   * @param below - Split one above the other (true) or side by side (false)
+   * @returns true if the request was sent
+   */
+  openFileToSide(path: string, line?: number, column?: number, below?: boolean): boolean;
   /**
    * Spawn a long-running background process
    *
@@ -560,6 +809,31 @@ interface EditorAPI {
    * @returns true if process was killed, false if not found
    */
   killProcess(#[bigint] process_id: number): Promise<boolean>;
+  /**
+   * Start a job whose output is streamed to plugins
+   *
+   * Unlike spawnProcess, output arrives line by line as the job runs: each line
+   * fires the "job_output" hook with `{ job_id, stream, line }` (stream is
+   * "stdout" or "stderr"), and "job_exit" follows with
+   * `{ job_id, exit_code, killed }`. Running jobs are shown in the status bar.
+   * @param command - Program name (searched in PATH) or absolute path
+   * @param args - Command arguments (each array element is one argument)
+   * @param options - Working directory, environment and status bar title
+   * @returns Job ID, passed to the hooks and to killJob
+   * @example
+   * globalThis.onJobOutput = (e) => { if (e.job_id === job) log.push(e.line); };
+   * editor.on("job_output", "onJobOutput");
+   * const job = editor.spawnJob("cargo", ["test"], { env: { RUST_BACKTRACE: "1" }, title: "cargo test" });
+   */
+  spawnJob(command: string, args: string[], options: JobOptions): number;
+  /**
+   * Kill a job started with spawnJob
+   *
+   * The "job_exit" hook still fires, with `killed` set.
+   * @param job_id - ID returned from spawnJob
+   * @returns true if the request was sent successfully
+   */
+  killJob(job_id: number): boolean;
   /**
    * Send an arbitrary LSP request and receive the raw JSON response
    * @param language - Language ID (e.g., "cpp")
@@ -568,6 +842,28 @@ interface EditorAPI {
    * @returns Promise resolving to the JSON response value
    */
   sendLspRequest(language: string, method: string, params?: unknown | null): Promise<unknown>;
+  /**
+   * Bind keys in a single buffer
+   *
+   * Buffer bindings take precedence over the buffer's mode and the global
+   * keymap, and are removed when the buffer closes.
+   * @param buffer_id - ID of the buffer
+   * @param bindings - Array of [key_string, command_name] pairs
+   * @returns true if the bindings were sent successfully
+   * @example
+   * editor.setBufferKeybindings(bufferId, [
+   * ["q", "close-buffer"],
+   * ["Return", "results_goto"]
+   * ]);
+   */
+  setBufferKeybindings(buffer_id: number, bindings: Vec<(String, String): boolean;
+  /**
+   * Remove keys bound in a single buffer
+   * @param buffer_id - ID of the buffer
+   * @param keys - Key strings to unbind, or null to remove all of the buffer's bindings
+   * @returns true if the request was sent successfully
+   */
+  removeBufferKeybindings(buffer_id: number, keys?: string[] | null): boolean;
   /**
    * Set the ratio of a split container
    * @param split_id - ID of the split
@@ -588,6 +884,29 @@ interface EditorAPI {
    * @returns true if the command was sent successfully
    */
   setBufferCursor(buffer_id: number, position: number): boolean;
+  /**
+   * Add a text object that selection commands can use
+   * For a buffer whose language has a query, the smallest @around capture
+   * containing the cursor is the object and the @inside capture of the same
+   * match its inside. Otherwise the pattern is used. The built-in objects
+   * ("quotes", "brackets", "function", "paragraph") can't be replaced.
+   * @param name - Name of the text object
+   * @param options - Its query per language and/or pattern
+   * @returns true if the request was sent successfully
+   * @example
+   * editor.registerTextObject("argument", {
+   * pattern: "[(,]\\s*([^,()]*)",
+   * queries: { rust: "(parameter) @around" },
+   * });
+   */
+  registerTextObject(name: string, options: TextObjectOptions): boolean;
+  /**
+   * Select a text object around each cursor in the active buffer
+   * @param name - Built-in ("quotes", "brackets", "function", "paragraph") or registered text object
+   * @param around - Include the delimiters (quotes, brackets, function signature, trailing blank lines)
+   * @returns true if the request was sent successfully
+   */
+  selectTextObject(name: string, around: boolean): boolean;
 
   // === Async Operations ===
   /**
@@ -606,6 +925,52 @@ interface EditorAPI {
    * }
    */
   spawnProcess(command: string, args: string[], cwd?: string | null): Promise<SpawnResult>;
+  /**
+   * Search the project's files for plain text using the search index
+   *
+   * Only files containing every 3-character sequence of the query are read,
+   * plus files changed on disk since they were indexed, so results come back
+   * quickly even on large projects. Matching uses smart case, like `rg -S`.
+   * Returns null if the project isn't indexed (see the `editor.search_index`
+   * setting and "Rebuild Search Index") or the query has regex characters;
+   * run a grep tool then.
+   * @param query - Text to search for
+   * @param max_results - Stop after this many matching lines
+   * @param dir - Only search under this directory, relative to the working directory
+   * @example
+   * const matches = await editor.searchProject("TODO", 100);
+   * if (matches === null) {
+   * // Fall back to: editor.spawnProcess("rg", ["--column", "TODO"])
+   * }
+   */
+  searchProject(query: string, max_results: number, dir?: string | null): Promise<SearchMatch[] | null>;
+  /**
+   * List the project's files, relative to the working directory
+   *
+   * The list comes from the editor's scan of the project, which leaves out
+   * hidden files and files ignored by .gitignore, .ignore or the project's
+   * file globs, and is scanned again when files are added or removed.
+   * Returns null while the project is being scanned.
+   * @example
+   * const files = editor.listProjectFiles();
+   * if (files === null) {
+   * // Fall back to: editor.spawnProcess("git", ["ls-files"])
+   * }
+   */
+  listProjectFiles(): string[] | null;
+  /**
+   * Fuzzy match a query against a list of strings
+   *
+   * Uses the matcher picked by the `editor.fuzzy_matcher` setting, so
+   * plugin pickers rank like the command palette. Items that don't match are
+   * left out; the others are sorted best first.
+   * @param query - Text typed by the user
+   * @param items - Strings to match
+   * @param max_results - Most matches returned
+   * @example
+   * const best = editor.fuzzyFilter("main", files, 100).map(m => files[m.index]);
+   */
+  fuzzyFilter(query: string, items: string[], max_results: number): FuzzyMatch[];
 
   // === Overlay Operations ===
   /**
@@ -625,9 +990,11 @@ interface EditorAPI {
    * @param underline - Add underline decoration
    * @param bold - Use bold text
    * @param italic - Use italic text
+   * @param revision - Buffer revision the offsets refer to (e.g. from lines_changed);
+   *   they are mapped over later edits, and the overlay is dropped if its text changed
    * @returns true if overlay was added
    */
-  addOverlay(buffer_id: number, namespace: string, start: number, end: number, r: number, g: number, b: number, underline: boolean, bold: boolean, italic: boolean): boolean;
+  addOverlay(buffer_id: number, namespace: string, start: number, end: number, r: number, g: number, b: number, underline: boolean, bold: boolean, italic: boolean, revision?: number | null): boolean;
   /**
    * Remove a specific overlay by its handle
    * @param buffer_id - The buffer ID
@@ -689,6 +1056,48 @@ interface EditorAPI {
    * @returns true if namespace was cleared
    */
   clearVirtualTextNamespace(buffer_id: number, namespace: string): boolean;
+  /**
+   * Create a namespace for a plugin's overlays and virtual texts
+   *
+   * The returned namespace is unique, so two plugins (or two features of one
+   * plugin) asking for the same name never clear each other's decorations.
+   * Pass it to addOverlay, addVirtualLine, setDecorations and the clear
+   * functions.
+   * @param name - Readable name included in the namespace (e.g. "eslint")
+   * @returns The namespace
+   */
+  createNamespace(name: string): string;
+  /**
+   * Clear a namespace's overlays and virtual texts in a byte range
+   *
+   * Overlays overlapping the range and virtual texts anchored in it are
+   * removed; the namespace's decorations elsewhere are kept.
+   * @param buffer_id - The buffer ID
+   * @param namespace - The namespace to clear
+   * @param start - Start byte position (inclusive)
+   * @param end - End byte position (exclusive)
+   * @returns true if the request was sent successfully
+   */
+  clearNamespaceInRange(buffer_id: number, namespace: string, start: number, end: number): boolean;
+  /**
+   * Replace a namespace's decorations in one step
+   *
+   * The namespace's overlays and virtual texts are removed (only those
+   * between `start` and `end` when given) and the new ones added in the same
+   * frame, so a linter or test runner can publish fresh results without
+   * flicker and without touching other namespaces.
+   * @param buffer_id - The buffer ID
+   * @param namespace - Namespace of the decorations (see createNamespace)
+   * @param decorations - The new decorations
+   * @returns true if the request was sent successfully
+   * @example
+   * const ns = editor.createNamespace("lint");
+   * editor.setDecorations(bufferId, ns, {
+   * overlays: [{ start: 10, end: 14, color: [255, 80, 80], underline: true }],
+   * virtual_texts: [{ position: 14, text: " unused variable", color: [150, 150, 150] }],
+   * });
+   */
+  setDecorations(buffer_id: number, namespace: string, decorations: Decorations): boolean;
   /**
    * Force a refresh of line display for a buffer
    * @param buffer_id - The buffer ID
@@ -705,6 +1114,20 @@ interface EditorAPI {
    * @param path - File path (absolute or relative to cwd)
    */
   readFile(path: string): Promise<string>;
+  /**
+   * Read the lines around a line of a file, without reading the whole file
+   *
+   * Meant for previews of large files: the file is only read up to the last
+   * line returned. Returns null for binary files; throws if the file can't
+   * be read.
+   * @param path - File path (absolute or relative to cwd)
+   * @param line - Line to show (1-indexed); 0 reads the start of the file
+   * @param context - Number of lines before and after the line (the number of lines for line 0)
+   * @example
+   * const preview = await editor.readFilePreview("src/main.rs", 120, 5);
+   * // preview.lines[120 - preview.first_line] is line 120
+   */
+  readFilePreview(path: string, line: number, context: number): Promise<FilePreview | null>;
   /**
    * Write string content to a file, creating or overwriting
    *
@@ -714,6 +1137,13 @@ interface EditorAPI {
    * @param content - UTF-8 string to write
    */
   writeFile(path: string, content: string): Promise<[]>;
+  /**
+   * Delete a file
+   *
+   * Buffers showing the file are left open.
+   * @param path - File to delete (absolute or relative to cwd)
+   */
+  removeFile(path: string): Promise<[]>;
   /**
    * Check if a path exists (file, directory, or symlink)
    *
@@ -761,6 +1191,24 @@ interface EditorAPI {
    * which is important for test isolation.
    */
   getCwd(): string;
+  /**
+   * Get the project's include/exclude globs
+   *
+   * Combines the `files` section of the config with the project's
+   * `.fresh/project.toml`. Patterns use .gitignore syntax, relative to the
+   * working directory. Tools that take globs (like `rg -g`) can use them
+   * directly; otherwise use filterPaths.
+   */
+  getFileFilter(): FileFilter;
+  /**
+   * Remove the paths left out by the project's include/exclude globs
+   *
+   * Paths are files, absolute or relative to the working directory; the
+   * order of the others is kept.
+   * @param paths - Paths to filter
+   * @returns The paths that are part of the project
+   */
+  filterPaths(paths: string[]): string[];
 
   // === Path Operations ===
   /**
@@ -952,6 +1400,56 @@ interface EditorAPI {
    */
   setVirtualBufferContent(buffer_id: number, entries: TextPropertyEntry[]): boolean;
 
+  // === Panel Operations ===
+  /**
+   * Dock a panel drawn by the plugin
+   *
+   * The render function is called with the size of the panel's content area
+   * (`rect`) and a draw handle whenever the panel is shown, resized, focused
+   * or refreshed. `draw.text(x, y, text, style)` and
+   * `draw.fill(x, y, width, height, char, style)` place text (style has
+   * optional `fg`/`bg` [r, g, b] colors and `bold`); `draw.focused` tells if
+   * the panel has the keyboard focus. A focused panel gets keys through its
+   * mode; Escape returns the focus to the buffer.
+   * @param options - Panel configuration
+   * @returns true if the panel was registered
+   * @example
+   * globalThis.renderPlayer = (rect, draw) => {
+   * draw.text(0, 0, "Now playing", { bold: true });
+   * draw.text(0, 1, track.slice(0, rect.width));
+   * };
+   * editor.registerPanel({ id: "player", title: "Music", position: "bottom", size: 4, render: "renderPlayer" });
+   */
+  registerPanel(options: PanelOptions): boolean;
+  /**
+   * Remove a panel
+   * @param id - ID the panel was registered with
+   * @returns true if the request was sent successfully
+   */
+  unregisterPanel(id: string): boolean;
+  /**
+   * Replace what a panel shows
+   *
+   * Called with the draw handle's text after each render; use refreshPanel
+   * to redraw instead of calling this directly.
+   * @param id - ID of the panel
+   * @param content - Text to draw
+   * @returns true if the content was sent successfully
+   */
+  setPanelContent(id: string, content: PanelText[]): boolean;
+  /**
+   * Draw a panel again (e.g., after its data changed)
+   * @param id - ID of the panel
+   * @returns true if the request was sent successfully
+   */
+  refreshPanel(id: string): boolean;
+  /**
+   * Give a panel the keyboard focus
+   * @param id - ID of the panel
+   * @returns true if the request was sent successfully
+   */
+  focusPanel(id: string): boolean;
+
 }
 
 // Export for module compatibility
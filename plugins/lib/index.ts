@@ -19,6 +19,10 @@ export { PanelManager } from "./panel-manager.ts";
 // Navigation
 export { NavigationController } from "./navigation-controller.ts";
 
+// Results Lists
+export { ResultsPanel, getRelativePath } from "./results-panel.ts";
+export type { ResultItem, ResultsPanelOptions } from "./results-panel.ts";
+
 // Buffer Creation
 export { VirtualBufferFactory } from "./virtual-buffer-factory.ts";
 export type { VirtualBufferOptions, SplitBufferOptions } from "./virtual-buffer-factory.ts";
@@ -0,0 +1,251 @@
+/// <reference path="../../types/fresh.d.ts" />
+
+import type { Location } from "./types.ts";
+
+/**
+ * A location listed in a results panel, with the text of its line
+ */
+export interface ResultItem extends Location {
+  lineText?: string;
+}
+
+/**
+ * Options for a results panel
+ */
+export interface ResultsPanelOptions {
+  /** Buffer name (e.g., "*References*") */
+  name: string;
+  /** Panel ID, so showing results again reuses the split */
+  panelId: string;
+  /** Mode whose keybindings apply in the panel */
+  mode: string;
+  /** Most items listed */
+  maxResults: number;
+  /** Type property of item lines (e.g., "reference") */
+  itemType: string;
+  /** Width of the location column, default 50 */
+  locationWidth?: number;
+  /** Width of the line preview, default 60 */
+  previewWidth?: number;
+  /** Line shown when there are no items */
+  emptyText?: string;
+}
+
+/**
+ * Relative path of a file in the working directory, for display
+ */
+export function getRelativePath(filePath: string): string {
+  const cwd = editor.getCwd();
+  if (filePath.startsWith(cwd)) {
+    return filePath.slice(cwd.length + 1); // Remove cwd and leading /
+  }
+  return filePath;
+}
+
+/**
+ * ResultsPanel - A list of locations in a split below the source
+ *
+ * Handles the pattern shared by find references and quickfix: a header,
+ * one line per location with a preview of its text, a help footer, and
+ * jumping to the location under the cursor in the source split.
+ * Navigation uses normal cursor movement (Up/Down/j/k work naturally).
+ *
+ * @example
+ * ```typescript
+ * const panel = new ResultsPanel({
+ *   name: "*Quickfix*",
+ *   panelId: "quickfix-panel",
+ *   mode: "quickfix-list",
+ *   maxResults: 1000,
+ *   itemType: "item",
+ * });
+ *
+ * await panel.show(`Matches for '${query}'`, matches);
+ * ```
+ */
+export class ResultsPanel {
+  private bufferId: number | null = null;
+  private sourceSplitId: number | null = null;
+  private splitId: number | null = null;
+  private currentItems: ResultItem[] = [];
+
+  constructor(private readonly options: ResultsPanelOptions) {}
+
+  /**
+   * Check if the panel is currently open
+   */
+  get isOpen(): boolean {
+    return this.bufferId !== null;
+  }
+
+  /**
+   * Items listed in the panel
+   */
+  get items(): ResultItem[] {
+    return this.currentItems;
+  }
+
+  /**
+   * Show `items` under a `title` header, opening the panel if needed
+   *
+   * Lists at most `maxResults` items. Returns false if the panel couldn't
+   * be opened.
+   */
+  async show(title: string, items: ResultItem[]): Promise<boolean> {
+    // Only save the source split ID if the panel is not already open
+    // (avoid overwriting it with the panel's split ID on subsequent calls)
+    if (!this.isOpen) {
+      this.sourceSplitId = editor.getActiveSplitId();
+    }
+    this.currentItems = items.slice(0, this.options.maxResults);
+
+    // The panel_id mechanism will reuse the existing buffer/split if it exists
+    try {
+      this.bufferId = await editor.createVirtualBufferInSplit({
+        name: this.options.name,
+        mode: this.options.mode,
+        read_only: true,
+        entries: this.buildEntries(title, items.length),
+        ratio: 0.7, // Original pane takes 70%, the panel takes 30%
+        panel_id: this.options.panelId,
+        show_line_numbers: false,
+        show_cursors: true, // Enable cursor for navigation
+      });
+      // The panel's split becomes active after creation
+      this.splitId = editor.getActiveSplitId();
+      return true;
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      editor.debug(`ERROR: createVirtualBufferInSplit failed: ${errorMessage}`);
+      return false;
+    }
+  }
+
+  /**
+   * Index of the item on the cursor line of `bufferId`, if it's the panel's
+   */
+  selectedIndex(bufferId: number): number | null {
+    if (this.bufferId === null || bufferId !== this.bufferId) {
+      return null;
+    }
+    // Line 0 is the header, lines 1 to N are the items
+    const index = editor.getCursorLine() - 1;
+    return index >= 0 && index < this.currentItems.length ? index : null;
+  }
+
+  /**
+   * Close the panel and the split it opened
+   *
+   * Returns false if the panel wasn't open.
+   */
+  close(): boolean {
+    if (!this.isOpen) {
+      return false;
+    }
+
+    if (this.bufferId !== null) {
+      editor.closeBuffer(this.bufferId);
+    }
+    // Close the split we created (if it exists and is different from source)
+    if (this.splitId !== null && this.splitId !== this.sourceSplitId) {
+      editor.closeSplit(this.splitId);
+    }
+
+    this.bufferId = null;
+    this.sourceSplitId = null;
+    this.splitId = null;
+    this.currentItems = [];
+    return true;
+  }
+
+  /**
+   * Open the item under the cursor in the source split
+   *
+   * Returns the status message to show.
+   */
+  goto(): string {
+    if (this.bufferId === null || this.currentItems.length === 0) {
+      return "Nothing to jump to";
+    }
+    if (this.sourceSplitId === null) {
+      return "Source split not available";
+    }
+
+    const props = editor.getTextPropertiesAtCursor(this.bufferId);
+    const location = props.length > 0
+      ? (props[0].location as Location | undefined)
+      : undefined;
+    if (!location) {
+      return "Move cursor to a result line";
+    }
+
+    // Open file in the source split, not the panel's split
+    editor.openFileInSplit(
+      this.sourceSplitId,
+      location.file,
+      location.line,
+      location.column || 0
+    );
+    return `Jumped to ${getRelativePath(location.file)}:${location.line}`;
+  }
+
+  private buildEntries(title: string, totalCount: number): TextPropertyEntry[] {
+    const { maxResults, itemType, emptyText } = this.options;
+    const entries: TextPropertyEntry[] = [];
+
+    const limitNote = totalCount > maxResults ? ` (limited to ${maxResults})` : "";
+    entries.push({
+      text: `═══ ${title} (${totalCount}${limitNote}) ═══\n`,
+      properties: { type: "header" },
+    });
+
+    if (this.currentItems.length === 0 && emptyText) {
+      entries.push({
+        text: `  ${emptyText}\n`,
+        properties: { type: "empty" },
+      });
+    }
+    this.currentItems.forEach((item, index) => {
+      entries.push({
+        text: this.formatItem(item),
+        properties: {
+          type: itemType,
+          index,
+          location: {
+            file: item.file,
+            line: item.line,
+            column: item.column,
+          },
+        },
+      });
+    });
+
+    entries.push({
+      text: `───────────────────────────────────────────────────────────────────────────────\n`,
+      properties: { type: "separator" },
+    });
+    entries.push({
+      text: `[↑/↓] navigate  [RET] jump  [q/Esc] close\n`,
+      properties: { type: "help" },
+    });
+
+    return entries;
+  }
+
+  private formatItem(item: ResultItem): string {
+    const { locationWidth = 50, previewWidth = 60 } = this.options;
+    const location = `${getRelativePath(item.file)}:${item.line}:${item.column}`;
+
+    // Truncate location if too long, leaving room for line text
+    const truncatedLocation = location.length > locationWidth
+      ? "..." + location.slice(-(locationWidth - 3))
+      : location.padEnd(locationWidth);
+
+    const trimmedLine = (item.lineText || "").trim();
+    const displayLine = trimmedLine.length > previewWidth
+      ? trimmedLine.slice(0, previewWidth - 3) + "..."
+      : trimmedLine;
+
+    return `  ${truncatedLocation}  ${displayLine}\n`;
+  }
+}
@@ -7,6 +7,11 @@
  * - Type to search across all files
  * - Navigate results with Up/Down to see preview
  * - Press Enter to open file at location
+ * - "Search Only in Directory..." repeats the last query in one directory
+ *
+ * Files excluded by the project's include/exclude globs are not searched.
+ * Plain-text queries use the project's search index when it is built
+ * (editor.search_index), other queries run ripgrep.
  */
 
 interface GrepMatch {
@@ -24,6 +29,10 @@ let originalSplitId: number | null = null;
 let lastQuery: string = "";
 let searchDebounceTimer: number | null = null;
 let previewCreated: boolean = false;
+// Text typed in the prompt, kept for "Search Only in Directory..."
+let currentQuery: string = "";
+// Directory the search is limited to (null for the whole project)
+let searchDir: string | null = null;
 
 // Parse ripgrep output line
 // Format: file:line:column:content
@@ -40,13 +49,9 @@ function parseRipgrepLine(line: string): GrepMatch | null {
   return null;
 }
 
-// Parse ripgrep output into suggestions
-function parseRipgrepOutput(stdout: string): {
-  results: GrepMatch[];
-  suggestions: PromptSuggestion[];
-} {
+// Parse ripgrep output into matches
+function parseRipgrepOutput(stdout: string): GrepMatch[] {
   const results: GrepMatch[] = [];
-  const suggestions: PromptSuggestion[] = [];
 
   for (const line of stdout.split("\n")) {
     if (!line.trim()) continue;
@@ -54,19 +59,6 @@ function parseRipgrepOutput(stdout: string): {
     if (match) {
       results.push(match);
 
-      // Truncate long content for display
-      const displayContent =
-        match.content.length > 60
-          ? match.content.substring(0, 57) + "..."
-          : match.content;
-
-      suggestions.push({
-        text: `${match.file}:${match.line}`,
-        description: displayContent.trim(),
-        value: `${results.length - 1}`, // Store index as value
-        disabled: false,
-      });
-
       // Limit to 100 results for performance
       if (results.length >= 100) {
         break;
@@ -74,21 +66,45 @@ function parseRipgrepOutput(stdout: string): {
     }
   }
 
-  return { results, suggestions };
+  return results;
+}
+
+// Turn matches into prompt suggestions
+function toSuggestions(results: GrepMatch[]): PromptSuggestion[] {
+  return results.map((match, index) => {
+    // Truncate long content for display
+    const displayContent =
+      match.content.length > 60
+        ? match.content.substring(0, 57) + "..."
+        : match.content;
+
+    return {
+      text: `${match.file}:${match.line}`,
+      description: displayContent.trim(),
+      value: `${index}`, // Store index as value
+      disabled: false,
+    };
+  });
+}
+
+// Show matches in the prompt and preview the first one
+async function showResults(results: GrepMatch[]): Promise<void> {
+  grepResults = results;
+  editor.setPromptSuggestions(toSuggestions(results));
+
+  if (results.length > 0) {
+    editor.setStatus(`Found ${results.length} matches`);
+    await updatePreview(results[0]);
+  } else {
+    editor.setStatus("No matches found");
+  }
 }
 
 // Create or update preview buffer with file content
 async function updatePreview(match: GrepMatch): Promise<void> {
   try {
-    // Read the file content
-    const content = await editor.readFile(match.file);
-    const lines = content.split("\n");
-
-    // Calculate context window (5 lines before and after)
-    const contextBefore = 5;
-    const contextAfter = 5;
-    const startLine = Math.max(0, match.line - 1 - contextBefore);
-    const endLine = Math.min(lines.length, match.line + contextAfter);
+    // Read only the lines around the match (5 before and after)
+    const preview = await editor.readFilePreview(match.file, match.line, 5);
 
     // Build preview entries with highlighting
     const entries: TextPropertyEntry[] = [];
@@ -103,20 +119,23 @@ async function updatePreview(match: GrepMatch): Promise<void> {
       properties: { type: "separator" },
     });
 
-    // Content lines with line numbers
-    for (let i = startLine; i < endLine; i++) {
-      const lineNum = i + 1;
-      const lineContent = lines[i] || "";
-      const isMatchLine = lineNum === match.line;
-      const prefix = isMatchLine ? "▶ " : "  ";
-      const lineNumStr = String(lineNum).padStart(4, " ");
-
-      entries.push({
-        text: `${prefix}${lineNumStr} │ ${lineContent}\n`,
-        properties: {
-          type: isMatchLine ? "match" : "context",
-          line: lineNum,
-        },
+    if (preview === null) {
+      entries.push({ text: "  Binary file\n", properties: { type: "context" } });
+    } else {
+      // Content lines with line numbers
+      preview.lines.forEach((lineContent, i) => {
+        const lineNum = preview.first_line + i;
+        const isMatchLine = lineNum === match.line;
+        const prefix = isMatchLine ? "▶ " : "  ";
+        const lineNumStr = String(lineNum).padStart(4, " ");
+
+        entries.push({
+          text: `${prefix}${lineNumStr} │ ${lineContent}\n`,
+          properties: {
+            type: isMatchLine ? "match" : "context",
+            line: lineNum,
+          },
+        });
       });
     }
 
@@ -169,7 +188,20 @@ function closePreview(): void {
   }
 }
 
-// Run ripgrep search
+// ripgrep globs for the project's include/exclude patterns
+function fileFilterGlobs(): string[] {
+  const filter = editor.getFileFilter();
+  const globs: string[] = [];
+  for (const pattern of filter.include) {
+    globs.push("-g", pattern);
+  }
+  for (const pattern of filter.exclude) {
+    globs.push("-g", `!${pattern}`);
+  }
+  return globs;
+}
+
+// Search the project (with the search index or ripgrep)
 async function runSearch(query: string): Promise<void> {
   if (!query || query.trim().length < 2) {
     editor.setPromptSuggestions([]);
@@ -184,6 +216,13 @@ async function runSearch(query: string): Promise<void> {
   lastQuery = query;
 
   try {
+    // The search index answers plain-text queries without reading every file
+    const indexed = await editor.searchProject(query, 100, searchDir);
+    if (indexed !== null) {
+      await showResults(indexed);
+      return;
+    }
+
     const result = await editor.spawnProcess("rg", [
       "--line-number",
       "--column",
@@ -195,22 +234,14 @@ async function runSearch(query: string): Promise<void> {
       "-g", "!node_modules",
       "-g", "!target",
       "-g", "!*.lock",
+      ...fileFilterGlobs(),
       "--",
       query,
+      ...(searchDir !== null ? [searchDir] : []),
     ]);
 
     if (result.exit_code === 0) {
-      const { results, suggestions } = parseRipgrepOutput(result.stdout);
-      grepResults = results;
-      editor.setPromptSuggestions(suggestions);
-
-      if (results.length > 0) {
-        editor.setStatus(`Found ${results.length} matches`);
-        // Show preview of first result
-        await updatePreview(results[0]);
-      } else {
-        editor.setStatus("No matches found");
-      }
+      await showResults(parseRipgrepOutput(result.stdout));
     } else if (result.exit_code === 1) {
       // No matches
       grepResults = [];
@@ -224,8 +255,8 @@ async function runSearch(query: string): Promise<void> {
   }
 }
 
-// Start live grep
-globalThis.start_live_grep = function (): void {
+// Open the live grep prompt, starting with `query`
+function openLiveGrep(query: string): void {
   // Clear previous state
   grepResults = [];
   lastQuery = "";
@@ -235,8 +266,25 @@ globalThis.start_live_grep = function (): void {
   originalSplitId = editor.getActiveSplitId();
 
   // Start the prompt
-  editor.startPrompt("Live grep: ", "live-grep");
+  const label = searchDir !== null ? `Live grep in ${searchDir}: ` : "Live grep: ";
+  editor.startPrompt(label, "live-grep", query);
   editor.setStatus("Type to search (min 2 chars)...");
+}
+
+// Start live grep
+globalThis.start_live_grep = function (): void {
+  searchDir = null;
+  openLiveGrep("");
+};
+
+// Search for the last query again, only in a directory
+globalThis.start_live_grep_in_directory = async function (): Promise<void> {
+  const dir = await editor.promptInput("Search only in directory", searchDir ?? "");
+  if (dir === null) {
+    return;
+  }
+  searchDir = dir.trim() !== "" ? dir.trim() : null;
+  openLiveGrep(currentQuery);
 };
 
 // Handle prompt input changes
@@ -247,6 +295,7 @@ globalThis.onLiveGrepPromptChanged = function (args: {
   if (args.prompt_type !== "live-grep") {
     return true;
   }
+  currentQuery = args.input;
 
   // Debounce search to avoid too many requests while typing
   if (searchDebounceTimer !== null) {
@@ -337,6 +386,12 @@ editor.registerCommand(
   "start_live_grep",
   "normal"
 );
+editor.registerCommand(
+  "Search Only in Directory...",
+  "Live grep for the last query, limited to one directory",
+  "start_live_grep_in_directory",
+  "normal"
+);
 
 editor.debug("Live Grep plugin loaded");
 editor.setStatus("Live Grep ready - use command palette or bind 'start_live_grep'");
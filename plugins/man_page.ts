@@ -0,0 +1,499 @@
+/// <reference path="../types/fresh.d.ts" />
+
+/**
+ * Man Page Plugin
+ *
+ * "View Man Page" shows `man` output, or a command's `--help`, read-only:
+ * - Bold and underlined text keep their styles
+ * - An outline panel lists the sections; Enter jumps to one
+ * - Enter on a reference such as `grep(1)` opens that page
+ *
+ * Type `ls`, `ls(1)` or `1 ls` for a man page and `cargo --help` for a
+ * command's help; commands without a man page fall back to `--help`.
+ */
+
+// =============================================================================
+// Types and State
+// =============================================================================
+
+interface ManSection {
+  name: string;
+  /** Byte offset of the heading */
+  offset: number;
+  /** Subsections are indented in the outline */
+  nested: boolean;
+}
+
+interface ManLink {
+  start: number;
+  end: number;
+  /** Page to open, e.g. "grep(1)" */
+  topic: string;
+}
+
+interface ManPage {
+  title: string;
+  sections: ManSection[];
+  links: ManLink[];
+}
+
+interface StyleRun {
+  start: number;
+  end: number;
+  bold: boolean;
+  underline: boolean;
+}
+
+const OUTLINE_PANEL = "man-outline";
+const OUTLINE_WIDTH = 28;
+
+const colors = {
+  bold: [255, 203, 107] as [number, number, number],
+  underline: [130, 170, 255] as [number, number, number],
+  link: [86, 182, 194] as [number, number, number],
+  selection: [60, 70, 100] as [number, number, number],
+  heading: [150, 150, 150] as [number, number, number],
+};
+
+const pages = new Map<number, ManPage>();
+/** Page the outline shows */
+let outlineBufferId: number | null = null;
+let outlineSelected = 0;
+
+// =============================================================================
+// Mode Definitions
+// =============================================================================
+
+editor.defineMode(
+  "man-page",
+  "normal", // inherit from normal mode for cursor movement
+  [
+    ["Return", "man_page_open_link"],
+    ["o", "man_page_focus_outline"],
+    ["q", "man_page_close"],
+  ],
+  true // read-only
+);
+
+editor.defineMode(
+  "man-outline",
+  null,
+  [
+    ["Up", "man_outline_prev"],
+    ["k", "man_outline_prev"],
+    ["Down", "man_outline_next"],
+    ["j", "man_outline_next"],
+    ["Return", "man_outline_goto"],
+  ],
+  true
+);
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+/** Length of `text` in UTF-8 bytes, as buffer offsets count it */
+function utf8Length(text: string): number {
+  let length = 0;
+  for (const ch of text) {
+    const code = ch.codePointAt(0)!;
+    length += code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+  }
+  return length;
+}
+
+/**
+ * Turn formatted output into plain text and its styles
+ *
+ * `man` marks bold as `c\bc` and underline as `_\bc` (overstriking, as for
+ * a printer), or with SGR escapes when groff is asked for them.
+ */
+function parseFormatted(raw: string): { text: string; runs: StyleRun[] } {
+  const chars = Array.from(raw);
+  const cells: { ch: string; bold: boolean; underline: boolean }[] = [];
+  let bold = false;
+  let underline = false;
+
+  for (let i = 0; i < chars.length; i++) {
+    const ch = chars[i];
+    if (ch === "\b") {
+      const prev = cells[cells.length - 1];
+      const next = chars[i + 1];
+      if (prev && next !== undefined) {
+        if (prev.ch === next) {
+          prev.bold = true;
+        } else if (prev.ch === "_") {
+          prev.ch = next;
+          prev.underline = true;
+        } else if (next === "_") {
+          prev.underline = true;
+        } else {
+          prev.ch = next;
+        }
+        i++;
+      }
+      continue;
+    }
+    if (ch === "\x1b") {
+      // CSI sequence: ESC [ params final-byte; only SGR (final "m") is kept
+      if (chars[i + 1] !== "[") {
+        i++;
+        continue;
+      }
+      let j = i + 2;
+      let params = "";
+      while (j < chars.length && !/[@-~]/.test(chars[j])) {
+        params += chars[j];
+        j++;
+      }
+      if (chars[j] === "m") {
+        for (const param of (params || "0").split(";")) {
+          switch (parseInt(param || "0", 10)) {
+            case 0: bold = false; underline = false; break;
+            case 1: bold = true; break;
+            case 4: underline = true; break;
+            case 22: bold = false; break;
+            case 24: underline = false; break;
+          }
+        }
+      }
+      i = j;
+      continue;
+    }
+    cells.push({ ch, bold, underline });
+  }
+
+  let text = "";
+  const runs: StyleRun[] = [];
+  let offset = 0;
+  for (const cell of cells) {
+    const length = utf8Length(cell.ch);
+    if ((cell.bold || cell.underline) && cell.ch !== "\n") {
+      const last = runs[runs.length - 1];
+      if (last && last.end === offset && last.bold === cell.bold && last.underline === cell.underline) {
+        last.end += length;
+      } else {
+        runs.push({ start: offset, end: offset + length, bold: cell.bold, underline: cell.underline });
+      }
+    }
+    text += cell.ch;
+    offset += length;
+  }
+  return { text, runs };
+}
+
+/**
+ * Section headings and references to other pages in `text`
+ *
+ * Headings start a line and are bold or in capitals (man pages) or end
+ * with a colon (`--help` output); bold lines indented a little are
+ * subsections.
+ */
+function findSectionsAndLinks(
+  text: string,
+  runs: StyleRun[]
+): { sections: ManSection[]; links: ManLink[] } {
+  const sections: ManSection[] = [];
+  const links: ManLink[] = [];
+  const isBold = (start: number, end: number) =>
+    runs.some((run) => run.bold && run.start <= start && run.end >= end);
+
+  let lineStart = 0;
+  for (const line of text.split("\n")) {
+    const trimmed = line.trim();
+    const indent = line.length - line.trimStart().length;
+    if (trimmed !== "" && indent <= 4) {
+      const start = lineStart + utf8Length(line.slice(0, indent));
+      const end = start + utf8Length(trimmed);
+      const heading = indent === 0
+        ? isBold(start, end) || trimmed.endsWith(":") || /^[A-Z][A-Z0-9 ]*$/.test(trimmed)
+        : isBold(start, end);
+      if (heading) {
+        sections.push({ name: trimmed.replace(/:$/, ""), offset: start, nested: indent > 0 });
+      }
+    }
+
+    const reference = /([A-Za-z0-9_.:+-]+)\((\d[a-z]*)\)/g;
+    let match;
+    while ((match = reference.exec(line)) !== null) {
+      const start = lineStart + utf8Length(line.slice(0, match.index));
+      links.push({ start, end: start + utf8Length(match[0]), topic: match[0] });
+    }
+    lineStart += utf8Length(line) + 1;
+  }
+  return { sections, links };
+}
+
+// =============================================================================
+// Fetching Pages
+// =============================================================================
+
+/** Run `command` for its output, or null if it can't be run */
+async function run(command: string, args: string[]): Promise<string | null> {
+  try {
+    const result = await editor.spawnProcess(command, args);
+    if (result.exit_code === 0 && result.stdout.trim() !== "") {
+      return result.stdout;
+    }
+    // Some commands print their help to stderr
+    if (args.includes("--help") && result.stderr.trim() !== "") {
+      return result.stderr;
+    }
+  } catch (e) {
+    editor.debug(`man_page: ${command} failed: ${e}`);
+  }
+  return null;
+}
+
+/** `man` arguments for "ls", "ls(1)" or "1 ls" */
+function manArgs(topic: string): string[] {
+  const reference = topic.match(/^(\S+)\((\w+)\)$/);
+  if (reference) {
+    return [reference[2], reference[1]];
+  }
+  return topic.split(/\s+/);
+}
+
+/** Page width: the width of the view, less the outline if not shown yet */
+function pageWidth(): number {
+  const viewport = editor.getViewport();
+  let width = viewport ? viewport.width : 80;
+  if (pages.size === 0) {
+    width -= OUTLINE_WIDTH;
+  }
+  return Math.max(40, width - 2);
+}
+
+/** Title and formatted text of the page for `topic`, or null */
+async function fetchPage(topic: string): Promise<{ title: string; raw: string } | null> {
+  const words = topic.split(/\s+/);
+  const helpRequested = words.length > 1 && (words[words.length - 1] === "--help" || words[words.length - 1] === "-h");
+
+  if (!helpRequested) {
+    const raw = await run("env", [
+      "MAN_KEEP_FORMATTING=1",
+      "MANPAGER=cat",
+      "PAGER=cat",
+      `MANWIDTH=${pageWidth()}`,
+      "man",
+      ...manArgs(topic),
+    ]);
+    if (raw !== null) {
+      return { title: `man ${topic}`, raw };
+    }
+  }
+
+  // No man page: ask the command itself
+  const command = helpRequested ? words.slice(0, -1) : words;
+  const flag = helpRequested ? words[words.length - 1] : "--help";
+  const raw = await run(command[0], [...command.slice(1), flag]);
+  if (raw !== null) {
+    return { title: `${command.join(" ")} ${flag}`, raw };
+  }
+  return null;
+}
+
+// =============================================================================
+// Showing Pages
+// =============================================================================
+
+async function showPage(topic: string): Promise<void> {
+  editor.setStatus(`Loading ${topic}...`);
+  const page = await fetchPage(topic);
+  if (page === null) {
+    editor.setStatus(`No man page or --help for ${topic}`);
+    return;
+  }
+
+  const { text, runs } = parseFormatted(page.raw);
+  const { sections, links } = findSectionsAndLinks(text, runs);
+
+  const bufferId = await editor.createVirtualBuffer({
+    name: `*${page.title}*`,
+    mode: "man-page",
+    read_only: true,
+    entries: [{ text, properties: { type: "man-page" } }],
+    show_line_numbers: false,
+    show_cursors: true,
+    editing_disabled: true,
+  });
+  if (bufferId === null) {
+    editor.setStatus("Failed to open the man page");
+    return;
+  }
+
+  for (const run of runs) {
+    const color = run.bold ? colors.bold : colors.underline;
+    editor.addOverlay(bufferId, "man", run.start, run.end, color[0], color[1], color[2], run.underline, run.bold, false);
+  }
+  for (const link of links) {
+    editor.addOverlay(bufferId, "man", link.start, link.end, colors.link[0], colors.link[1], colors.link[2], true, false, false);
+  }
+
+  if (pages.size === 0) {
+    editor.registerPanel({
+      id: OUTLINE_PANEL,
+      title: "Outline",
+      position: "left",
+      size: OUTLINE_WIDTH,
+      mode: "man-outline",
+      render: "render_man_outline",
+    });
+  }
+  pages.set(bufferId, { title: page.title, sections, links });
+  showOutlineFor(bufferId);
+
+  editor.setStatus(`${page.title} | RET: open reference | o: outline | q: close`);
+}
+
+function showOutlineFor(bufferId: number): void {
+  if (outlineBufferId !== bufferId) {
+    outlineBufferId = bufferId;
+    outlineSelected = 0;
+  }
+  editor.refreshPanel(OUTLINE_PANEL);
+}
+
+// =============================================================================
+// Outline Panel
+// =============================================================================
+
+globalThis.render_man_outline = function(
+  rect: { width: number; height: number },
+  draw: {
+    text: (x: number, y: number, text: string, style?: Record<string, unknown>) => void;
+    focused: boolean;
+  }
+): void {
+  const page = outlineBufferId !== null ? pages.get(outlineBufferId) : undefined;
+  if (!page) {
+    return;
+  }
+  if (page.sections.length === 0) {
+    draw.text(0, 0, "No sections", { fg: colors.heading });
+    return;
+  }
+
+  // Keep the selected section in view
+  const top = Math.max(0, outlineSelected - rect.height + 1);
+  page.sections.slice(top, top + rect.height).forEach((section, row) => {
+    const label = (section.nested ? "  " : "") + section.name;
+    const selected = top + row === outlineSelected;
+    draw.text(0, row, label.padEnd(rect.width).slice(0, rect.width), {
+      bg: selected && draw.focused ? colors.selection : null,
+      bold: selected,
+    });
+  });
+};
+
+globalThis.man_outline_prev = function(): void {
+  if (outlineSelected > 0) {
+    outlineSelected--;
+    editor.refreshPanel(OUTLINE_PANEL);
+  }
+};
+
+globalThis.man_outline_next = function(): void {
+  const page = outlineBufferId !== null ? pages.get(outlineBufferId) : undefined;
+  if (page && outlineSelected < page.sections.length - 1) {
+    outlineSelected++;
+    editor.refreshPanel(OUTLINE_PANEL);
+  }
+};
+
+globalThis.man_outline_goto = function(): void {
+  const page = outlineBufferId !== null ? pages.get(outlineBufferId) : undefined;
+  const section = page?.sections[outlineSelected];
+  if (outlineBufferId === null || !section) {
+    return;
+  }
+  editor.setBufferCursor(outlineBufferId, section.offset);
+  editor.setStatus(`${section.name} | Esc: back to the page`);
+};
+
+// =============================================================================
+// Public Commands
+// =============================================================================
+
+globalThis.start_man_page = function(): void {
+  editor.startPrompt("Man page: ", "man-page");
+  editor.setStatus("Page name, e.g. ls, printf(3) or cargo --help");
+};
+
+globalThis.onManPagePromptConfirmed = function(args: {
+  prompt_type: string;
+  input: string;
+}): boolean {
+  if (args.prompt_type !== "man-page") {
+    return true; // Not our prompt
+  }
+  const topic = args.input.trim();
+  if (topic !== "") {
+    showPage(topic);
+  }
+  return true;
+};
+
+globalThis.man_page_open_link = function(): void {
+  const page = pages.get(editor.getActiveBufferId());
+  if (!page) {
+    return;
+  }
+  const cursor = editor.getCursorPosition();
+  const link = page.links.find((link) => link.start <= cursor && cursor < link.end);
+  if (link) {
+    showPage(link.topic);
+  } else {
+    editor.setStatus("No page reference under the cursor");
+  }
+};
+
+globalThis.man_page_focus_outline = function(): void {
+  const bufferId = editor.getActiveBufferId();
+  if (pages.has(bufferId)) {
+    showOutlineFor(bufferId);
+    editor.focusPanel(OUTLINE_PANEL);
+  }
+};
+
+globalThis.man_page_close = function(): void {
+  const bufferId = editor.getActiveBufferId();
+  if (pages.has(bufferId)) {
+    editor.closeBuffer(bufferId);
+  }
+};
+
+// =============================================================================
+// Event Handlers
+// =============================================================================
+
+globalThis.onManPageBufferActivated = function(data: { buffer_id: number }): void {
+  if (pages.has(data.buffer_id)) {
+    showOutlineFor(data.buffer_id);
+  }
+};
+
+globalThis.onManPageBufferClosed = function(data: { buffer_id: number }): void {
+  if (!pages.delete(data.buffer_id)) {
+    return;
+  }
+  if (pages.size === 0) {
+    outlineBufferId = null;
+    editor.unregisterPanel(OUTLINE_PANEL);
+  } else if (outlineBufferId === data.buffer_id) {
+    showOutlineFor(pages.keys().next().value as number);
+  }
+};
+
+editor.on("prompt_confirmed", "onManPagePromptConfirmed");
+editor.on("buffer_activated", "onManPageBufferActivated");
+editor.on("buffer_closed", "onManPageBufferClosed");
+
+editor.registerCommand(
+  "View Man Page",
+  "Show a man page or a command's --help with its sections in an outline",
+  "start_man_page",
+  "normal"
+);
+
+editor.debug("Man Page plugin loaded");
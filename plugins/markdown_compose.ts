@@ -530,6 +530,7 @@ function transformMarkdownTokens(
 globalThis.onMarkdownViewTransform = function(data: {
   buffer_id: number;
   split_id: number;
+  revision: number;
   viewport_start: number;
   viewport_end: number;
   tokens: ViewTokenWire[];
@@ -561,7 +562,8 @@ globalThis.onMarkdownViewTransform = function(data: {
     data.viewport_start,
     data.viewport_end,
     transformedTokens,
-    layoutHints
+    layoutHints,
+    data.revision
   );
 };
 
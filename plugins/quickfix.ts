@@ -0,0 +1,119 @@
+/// <reference path="../types/fresh.d.ts" />
+
+/**
+ * Quickfix Plugin (TypeScript)
+ *
+ * Displays a list of locations in a virtual buffer split view for later review.
+ * Listens for the search_matches hook (fired by the "Search Matches to Quickfix"
+ * command) and lists every match of the current search.
+ * Uses cursor movement for navigation (Up/Down/j/k work naturally).
+ */
+
+import { ResultsPanel } from "./lib/index.ts";
+
+// Maximum number of results to display
+const MAX_RESULTS = 1000;
+
+const panel = new ResultsPanel({
+  name: "*Quickfix*",
+  panelId: "quickfix-panel",
+  mode: "quickfix-list",
+  maxResults: MAX_RESULTS,
+  itemType: "item",
+  locationWidth: 40,
+  previewWidth: 70,
+});
+
+// Quickfix item structure
+interface QuickfixItem {
+  file: string;
+  line: number;
+  column: number;
+  line_text: string;
+}
+
+// Define the quickfix mode with minimal keybindings
+// Navigation uses normal cursor movement (arrows, j/k work naturally)
+editor.defineMode(
+  "quickfix-list",
+  null, // no parent mode
+  [
+    ["Return", "quickfix_goto"],
+    ["q", "quickfix_close"],
+    ["Escape", "quickfix_close"],
+  ],
+  true // read-only
+);
+
+// Show quickfix panel
+async function showQuickfixPanel(title: string, items: QuickfixItem[]): Promise<void> {
+  const results = items.map((item) => ({ ...item, lineText: item.line_text }));
+  if (!(await panel.show(title, results))) {
+    editor.setStatus("Failed to open quickfix panel");
+    return;
+  }
+
+  const limitMsg = items.length > MAX_RESULTS ? ` (showing first ${MAX_RESULTS})` : "";
+  editor.setStatus(
+    `${items.length} item(s)${limitMsg} in quickfix list - ↑/↓ navigate, RET jump, q close`
+  );
+}
+
+// Handle search_matches hook
+globalThis.on_quickfix_search_matches = function (data: {
+  query: string;
+  matches: QuickfixItem[];
+}): void {
+  if (data.matches.length === 0) {
+    editor.setStatus(`No matches found for '${data.query}'`);
+    return;
+  }
+
+  showQuickfixPanel(`Matches for '${data.query}'`, data.matches);
+};
+
+// Register the hook handler
+editor.on("search_matches", "on_quickfix_search_matches");
+
+// Handle cursor movement to show current item info
+globalThis.on_quickfix_cursor_moved = function (data: {
+  buffer_id: number;
+  cursor_id: number;
+  old_position: number;
+  new_position: number;
+}): void {
+  const itemIndex = panel.selectedIndex(data.buffer_id);
+  if (itemIndex !== null) {
+    editor.setStatus(`Item ${itemIndex + 1}/${panel.items.length}`);
+  }
+};
+
+// Register cursor movement handler
+editor.on("cursor_moved", "on_quickfix_cursor_moved");
+
+// Hide quickfix panel
+globalThis.hide_quickfix_panel = function (): void {
+  if (panel.close()) {
+    editor.setStatus("Quickfix panel closed");
+  }
+};
+
+// Navigation: go to selected item (based on cursor position)
+globalThis.quickfix_goto = function (): void {
+  editor.setStatus(panel.goto());
+};
+
+// Close the quickfix panel
+globalThis.quickfix_close = function (): void {
+  globalThis.hide_quickfix_panel();
+};
+
+// Register commands
+editor.registerCommand(
+  "Hide Quickfix Panel",
+  "Close the quickfix panel",
+  "hide_quickfix_panel",
+  "normal"
+);
+
+editor.debug("Quickfix plugin initialized");
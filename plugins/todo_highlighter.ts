@@ -29,7 +29,8 @@ const NAMESPACE = "todo";
 function highlightLine(
   bufferId: number,
   byteStart: number,
-  content: string
+  content: string,
+  revision: number
 ): void {
   // Search for keywords
   for (const keyword of config.keywords) {
@@ -56,7 +57,10 @@ function highlightLine(
           keyword.color[0],
           keyword.color[1],
           keyword.color[2],
-          false  // background color, not underline
+          false,  // background color, not underline
+          false,
+          false,
+          revision  // offsets are from this revision of the buffer
         );
       }
 
@@ -74,6 +78,7 @@ function clearHighlights(bufferId: number): void {
 // This is called for lines that need (re)processing
 globalThis.onLinesChanged = function(data: {
   buffer_id: number;
+  revision: number;
   lines: Array<{
     line_number: number;
     byte_start: number;
@@ -85,7 +90,7 @@ globalThis.onLinesChanged = function(data: {
 
   // Process all changed lines and create overlays for them
   for (const line of data.lines) {
-    highlightLine(data.buffer_id, line.byte_start, line.content);
+    highlightLine(data.buffer_id, line.byte_start, line.content, data.revision);
   }
 };
 
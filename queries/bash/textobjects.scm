@@ -0,0 +1,3 @@
+; Function definitions
+(function_definition
+  body: (_) @function.inside) @function.around
@@ -0,0 +1,3 @@
+; Function definitions
+(function_definition
+  body: (compound_statement) @function.inside) @function.around
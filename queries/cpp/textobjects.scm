@@ -0,0 +1,6 @@
+; Functions, methods and lambdas
+(function_definition
+  body: (compound_statement) @function.inside) @function.around
+
+(lambda_expression
+  body: (compound_statement) @function.inside) @function.around
@@ -0,0 +1,9 @@
+; Functions, methods and function literals
+(function_declaration
+  body: (block) @function.inside) @function.around
+
+(method_declaration
+  body: (block) @function.inside) @function.around
+
+(func_literal
+  body: (block) @function.inside) @function.around
@@ -0,0 +1,9 @@
+; Methods, constructors and lambdas
+(method_declaration
+  body: (block) @function.inside) @function.around
+
+(constructor_declaration
+  body: (constructor_body) @function.inside) @function.around
+
+(lambda_expression
+  body: (_) @function.inside) @function.around
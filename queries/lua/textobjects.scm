@@ -0,0 +1,6 @@
+; Functions
+(function_declaration
+  body: (_) @function.inside) @function.around
+
+(function_definition
+  body: (_) @function.inside) @function.around
@@ -0,0 +1,9 @@
+; Functions, methods and closures
+(function_definition
+  body: (compound_statement) @function.inside) @function.around
+
+(method_declaration
+  body: (compound_statement) @function.inside) @function.around
+
+(anonymous_function
+  body: (compound_statement) @function.inside) @function.around
@@ -0,0 +1,6 @@
+; Functions and methods
+(function_definition
+  body: (block) @function.inside) @function.around
+
+(lambda
+  body: (_) @function.inside) @function.around
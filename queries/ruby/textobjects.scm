@@ -0,0 +1,6 @@
+; Methods and blocks
+(method
+  body: (_) @function.inside) @function.around
+
+(singleton_method
+  body: (_) @function.inside) @function.around
@@ -0,0 +1,6 @@
+; Functions, methods and closures
+(function_item
+  body: (block) @function.inside) @function.around
+
+(closure_expression
+  body: (_) @function.inside) @function.around
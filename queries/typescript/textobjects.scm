@@ -0,0 +1,15 @@
+; Functions, methods and arrow functions
+(function_declaration
+  body: (statement_block) @function.inside) @function.around
+
+(function_expression
+  body: (statement_block) @function.inside) @function.around
+
+(generator_function_declaration
+  body: (statement_block) @function.inside) @function.around
+
+(method_definition
+  body: (statement_block) @function.inside) @function.around
+
+(arrow_function
+  body: (_) @function.inside) @function.around
@@ -0,0 +1,71 @@
+#!/usr/bin/env python3
+"""
+Fail if any criterion benchmark got slower than a saved baseline.
+
+Run after `cargo bench -- --baseline <name>`, which records each benchmark's
+change against the baseline in target/criterion/<bench>/change/estimates.json.
+
+Usage:
+    python check_bench_regressions.py [options]
+
+Options:
+    --criterion-dir DIR  Criterion output directory (default: target/criterion)
+    --threshold PCT      Largest allowed slowdown of the mean, in percent (default: 10)
+    --report-only        Mark regressions but always exit successfully
+"""
+
+import argparse
+import json
+import sys
+from pathlib import Path
+
+
+def find_changes(criterion_dir: Path) -> list[tuple[str, float]]:
+    """
+    Return (benchmark name, relative change of the mean) for every benchmark
+    that was compared against a baseline.
+    """
+    changes = []
+    for estimates in sorted(criterion_dir.glob("**/change/estimates.json")):
+        bench_dir = estimates.parent.parent
+        name = bench_dir.relative_to(criterion_dir).as_posix()
+        with open(estimates) as f:
+            mean = json.load(f)["mean"]["point_estimate"]
+        changes.append((name, mean))
+    return changes
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.strip().splitlines()[0])
+    parser.add_argument("--criterion-dir", type=Path, default=Path("target/criterion"))
+    parser.add_argument("--threshold", type=float, default=10.0)
+    parser.add_argument("--report-only", action="store_true")
+    args = parser.parse_args()
+
+    changes = find_changes(args.criterion_dir)
+    if not changes:
+        # Nothing to compare against yet (e.g., the baseline predates the benchmarks)
+        print(f"No baseline comparisons found in {args.criterion_dir}", file=sys.stderr)
+        return 0
+
+    regressions = []
+    for name, change in changes:
+        percent = change * 100
+        marker = ""
+        if percent > args.threshold:
+            regressions.append(name)
+            marker = "  <-- regression"
+        print(f"{percent:+7.2f}%  {name}{marker}")
+
+    if regressions:
+        print(
+            f"\n{len(regressions)} benchmark(s) slower than the baseline by more than "
+            f"{args.threshold:g}%",
+            file=sys.stderr,
+        )
+        return 0 if args.report_only else 1
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,217 @@
+//! Screen reader announcements of the editor's state
+//!
+//! After input is handled, the parts of the UI a screen reader user follows
+//! (the mode, the selected item and the cursor's line) are compared with what
+//! was last announced, and only what changed is announced.
+
+use super::Editor;
+use crate::config::{AccessibilityConfig, MenuItem};
+use crate::input::keybindings::KeyContext;
+use crate::model::event::BufferId;
+use crate::services::accessibility::Announcer;
+
+/// What the screen reader was last told
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Announced {
+    /// Description of the mode (e.g., "Editing main.rs", "Find file:")
+    mode: String,
+    /// Selected completion, suggestion, menu item or file explorer entry
+    selection: Option<String>,
+    /// Buffer and start byte of the cursor's line (while editing)
+    line: Option<(BufferId, usize)>,
+}
+
+/// Announcer and the last announced state
+#[derive(Debug, Default)]
+pub(crate) struct AnnouncementState {
+    announcer: Option<Announcer>,
+    last: Announced,
+}
+
+impl AnnouncementState {
+    pub(crate) fn new(config: &AccessibilityConfig) -> Self {
+        Self {
+            announcer: Announcer::from_config(config),
+            last: Announced::default(),
+        }
+    }
+}
+
+impl Editor {
+    /// Announce changes to the mode, the selection and the cursor's line
+    ///
+    /// Called from the event loop. Does nothing unless accessibility mode is on.
+    pub fn update_announcements(&mut self) {
+        if self.announcement_state.announcer.is_none() {
+            return;
+        }
+
+        let current = self.announced_state();
+        let last = std::mem::replace(&mut self.announcement_state.last, current.clone());
+
+        let mut messages = Vec::new();
+        let mode_changed = current.mode != last.mode;
+        if mode_changed {
+            messages.push(current.mode.clone());
+        }
+        if current.selection != last.selection {
+            messages.extend(current.selection.clone());
+        }
+        // Coming back to a buffer re-reads the line the cursor is on
+        if current.line.is_some() && (mode_changed || current.line != last.line) {
+            messages.push(self.cursor_line_announcement());
+        }
+
+        if let Some(announcer) = self.announcement_state.announcer.as_mut() {
+            for message in &messages {
+                announcer.announce(message);
+            }
+        }
+    }
+
+    /// Describe the parts of the UI that are announced
+    fn announced_state(&mut self) -> Announced {
+        match self.key_context_stack().top() {
+            KeyContext::Menu => {
+                let menus: Vec<_> = self
+                    .config
+                    .menu
+                    .menus
+                    .iter()
+                    .chain(self.menu_state.plugin_menus.iter())
+                    .collect();
+                let menu = self.menu_state.active_menu.and_then(|i| menus.get(i));
+                let item = menu
+                    .zip(self.menu_state.highlighted_item)
+                    .and_then(|(menu, i)| match menu.items.get(i)? {
+                        MenuItem::Action { label, .. } | MenuItem::Submenu { label, .. } => {
+                            Some(label.clone())
+                        }
+                        MenuItem::Separator { .. } => None,
+                    });
+                Announced {
+                    mode: menu
+                        .map_or_else(|| "Menu".to_string(), |menu| format!("{} menu", menu.label)),
+                    selection: item,
+                    line: None,
+                }
+            }
+            KeyContext::Prompt => {
+                let prompt = self.prompt.as_ref();
+                Announced {
+                    mode: prompt.map_or_else(String::new, |prompt| prompt.message.clone()),
+                    selection: prompt.and_then(|prompt| {
+                        let suggestion = prompt.suggestions.get(prompt.selected_suggestion?)?;
+                        Some(match &suggestion.description {
+                            Some(description) => format!("{}, {}", suggestion.text, description),
+                            None => suggestion.text.clone(),
+                        })
+                    }),
+                    line: None,
+                }
+            }
+            KeyContext::Popup => {
+                let popup = self.active_state().popups.top();
+                Announced {
+                    mode: popup
+                        .and_then(|popup| popup.title.clone())
+                        .unwrap_or_else(|| "Popup".to_string()),
+                    selection: popup.and_then(|popup| popup.selected_item()).map(
+                        |item| match &item.detail {
+                            Some(detail) => format!("{}, {}", item.text, detail),
+                            None => item.text.clone(),
+                        },
+                    ),
+                    line: None,
+                }
+            }
+            KeyContext::FileExplorer => Announced {
+                mode: "File explorer".to_string(),
+                selection: self
+                    .file_explorer
+                    .as_ref()
+                    .and_then(|explorer| explorer.get_selected_entry())
+                    .map(|entry| {
+                        if entry.is_dir() {
+                            format!("{} folder", entry.name)
+                        } else {
+                            entry.name.clone()
+                        }
+                    }),
+                line: None,
+            },
+            KeyContext::Normal
+            | KeyContext::Global
+            | KeyContext::VimNormal
+            | KeyContext::VimVisual => {
+                let buffer_id = self.active_buffer;
+                let state = self.active_state_mut();
+                let position = state.cursors.primary().position;
+                let line_start = state.buffer.line_iterator(position, 80).current_position();
+                Announced {
+                    mode: format!("Editing {}", self.get_buffer_display_name(buffer_id)),
+                    selection: None,
+                    line: Some((buffer_id, line_start)),
+                }
+            }
+        }
+    }
+
+    /// Line number and content of the cursor's line (e.g., "12: fn main() {")
+    fn cursor_line_announcement(&mut self) -> String {
+        let state = self.active_state_mut();
+        let position = state.cursors.primary().position;
+        let line_number = state.buffer.get_line_number(position) + 1;
+        let content = state
+            .buffer
+            .line_iterator(position, 80)
+            .next()
+            .map(|(_, content)| content)
+            .unwrap_or_default();
+        if content.trim().is_empty() {
+            format!("{}: blank", line_number)
+        } else {
+            format!(
+                "{}: {}",
+                line_number,
+                content.trim_end_matches(['\n', '\r'])
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AnnouncementBackend, Config};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_announces_changes_only() {
+        let dir = TempDir::new().unwrap();
+        let speech = dir.path().join("speech");
+        let mut config = Config::default();
+        config.accessibility = AccessibilityConfig {
+            enabled: true,
+            backend: AnnouncementBackend::Pipe,
+            pipe_path: Some(speech.clone()),
+        };
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+        let read_speech = || std::fs::read_to_string(&speech).unwrap_or_default();
+
+        editor.update_announcements();
+        assert_eq!(read_speech(), "Editing [No Name]\n1: blank\n");
+
+        // Nothing changed, nothing to say
+        editor.update_announcements();
+        assert_eq!(read_speech(), "Editing [No Name]\n1: blank\n");
+
+        // Typing on the same line isn't re-read; moving to a new line is
+        editor.active_state_mut().buffer.insert(0, "first\nsecond");
+        editor.update_announcements();
+        assert_eq!(read_speech(), "Editing [No Name]\n1: blank\n");
+        editor.active_state_mut().cursors.primary_mut().position = 8;
+        editor.update_announcements();
+        assert!(read_speech().ends_with("1: blank\n2: second\n"));
+    }
+}
@@ -10,9 +10,10 @@
 use crate::model::event::BufferId;
 use crate::services::async_bridge::{LspMessageType, LspProgressValue, LspServerStatus};
 use crate::view::file_tree::{FileTreeView, NodeId};
+use crate::view::status_message::StatusLevel;
 use lsp_types::{Diagnostic, InlayHint};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use super::types::{LspMessageEntry, LspProgressInfo};
@@ -47,6 +48,7 @@ impl Editor {
             state,
             diagnostics,
             &self.theme,
+            self.config.limits.max_diagnostics,
         );
         Some(buffer_id)
     }
@@ -58,13 +60,38 @@ impl Editor {
 
 impl Editor {
     /// Handle LSP diagnostics (push model)
-    pub(super) fn handle_lsp_diagnostics(&mut self, uri: String, diagnostics: Vec<Diagnostic>) {
+    ///
+    /// Diagnostics computed for an older version of the document are dropped:
+    /// their ranges refer to text that has since been edited.
+    pub(super) fn handle_lsp_diagnostics(
+        &mut self,
+        uri: String,
+        version: Option<i32>,
+        diagnostics: Vec<Diagnostic>,
+    ) {
         tracing::debug!(
-            "Processing {} LSP diagnostics for {}",
+            "Processing {} LSP diagnostics for {} (version: {:?})",
             diagnostics.len(),
-            uri
+            uri,
+            version
         );
 
+        let current = self
+            .find_buffer_by_uri(&uri)
+            .and_then(|buffer_id| self.buffers.get(&buffer_id))
+            .map(|state| state.lsp_version());
+        if let (Some(version), Some(current)) = (version, current) {
+            if version < current {
+                tracing::debug!(
+                    "Dropping stale diagnostics for {} (version {} < {})",
+                    uri,
+                    version,
+                    current
+                );
+                return;
+            }
+        }
+
         if let Some(buffer_id) = self.apply_diagnostics_to_buffer(&uri, &diagnostics) {
             tracing::info!(
                 "Applied {} diagnostics to buffer {:?}",
@@ -282,8 +309,17 @@ impl Editor {
 
         // Show important messages in status bar
         match message_type {
-            LspMessageType::Error | LspMessageType::Warning => {
-                self.status_message = Some(format!("LSP ({}): {}", language, message));
+            LspMessageType::Error => {
+                self.push_status(
+                    StatusLevel::Error,
+                    format!("LSP ({}): {}", language, message),
+                );
+            }
+            LspMessageType::Warning => {
+                self.push_status(
+                    StatusLevel::Warning,
+                    format!("LSP ({}): {}", language, message),
+                );
             }
             _ => {
                 // Info and Log messages are not shown in status bar
@@ -333,7 +369,7 @@ impl Editor {
             if was_running {
                 if let Some(lsp) = self.lsp.as_mut() {
                     let message = lsp.handle_server_crash(&language);
-                    self.status_message = Some(message);
+                    self.push_status(StatusLevel::Warning, message);
                 }
             }
         }
@@ -409,6 +445,9 @@ impl Editor {
         const DEBOUNCE_WINDOW: Duration = Duration::from_secs(10);
         const RAPID_REVERT_THRESHOLD: u32 = 10; // Require 10 reverts in 10 seconds to disable
 
+        // A file added to or removed from the project
+        self.project_path_changed(Path::new(&path));
+
         // Skip if auto-revert is disabled
         if !self.auto_revert_enabled {
             return false;
@@ -433,11 +472,12 @@ impl Editor {
                 *count += 1;
 
                 if *count >= RAPID_REVERT_THRESHOLD {
+                    let count = *count;
                     // Disable auto-revert and stop the file watcher
                     self.auto_revert_enabled = false;
                     self.file_watcher = None;
                     self.watched_dirs.clear();
-                    self.status_message = Some(format!(
+                    self.push_status(StatusLevel::Warning, format!(
                         "Auto-revert disabled: {} is updating too frequently (use Ctrl+Shift+R to re-enable)",
                         path_buf.file_name().unwrap_or_default().to_string_lossy()
                     ));
@@ -487,8 +527,19 @@ impl Editor {
             }
         }
 
+        let config = &self.config.file_explorer;
+        let patterns = view.ignore_patterns_mut();
+        patterns.set_respect_gitignore(config.respect_gitignore);
+        patterns.set_show_hidden(config.show_hidden);
+        patterns.set_show_gitignored(config.show_gitignored);
+        for pattern in &config.custom_ignore_patterns {
+            patterns.add_custom_pattern(pattern.clone());
+        }
+
+        view.set_file_filter((*self.file_filter).clone());
         self.file_explorer = Some(view);
         self.set_status_message("File explorer ready".to_string());
+        self.refresh_git_status();
     }
 
     /// Handle file explorer node toggle completed
@@ -505,6 +556,7 @@ impl Editor {
     /// Handle file explorer expanded to path
     pub(super) fn handle_file_explorer_expanded_to_path(&mut self, mut view: FileTreeView) {
         tracing::debug!("File explorer expanded to active file path");
+        view.load_expanded_gitignores();
         view.update_scroll_for_selection();
         self.file_explorer = Some(view);
     }
@@ -606,7 +658,7 @@ impl Editor {
         let restart_results = lsp.process_pending_restarts();
 
         for (language, success, message) in restart_results {
-            self.status_message = Some(message.clone());
+            self.push_status(StatusLevel::Info, message.clone());
 
             if success {
                 self.resend_did_open_for_language(&language);
@@ -640,6 +692,7 @@ impl Editor {
                     Some(c) => c,
                     None => continue, // Skip buffers that aren't fully loaded
                 };
+                let version = state.lsp_version();
                 let uri: Option<lsp_types::Uri> = url::Url::from_file_path(&path)
                     .ok()
                     .and_then(|u| u.as_str().parse::<lsp_types::Uri>().ok());
@@ -648,7 +701,7 @@ impl Editor {
                     if let Some(lang_id) = crate::services::lsp::manager::detect_language(&path) {
                         if let Some(lsp) = self.lsp.as_mut() {
                             if let Some(handle) = lsp.get_or_spawn(&lang_id) {
-                                let _ = handle.did_open(uri, content, lang_id);
+                                let _ = handle.did_open(uri, content, lang_id, version);
                             }
                         }
                     }
@@ -657,3 +710,45 @@ impl Editor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::model::event::Event;
+    use lsp_types::{Position, Range};
+
+    #[test]
+    fn test_stale_diagnostics_are_dropped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "first line\n").unwrap();
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        editor.open_file(&path).unwrap();
+        let uri = editor.buffer_metadata[&editor.active_buffer]
+            .file_uri()
+            .unwrap()
+            .as_str()
+            .to_string();
+
+        let old_version = editor.active_state().lsp_version();
+        let cursor_id = editor.active_state().cursors.primary_id();
+        editor.apply_event_to_active_buffer(&Event::Insert {
+            position: 0,
+            text: "// edited\n".to_string(),
+            cursor_id,
+        });
+        let diagnostic = Diagnostic {
+            range: Range::new(Position::new(0, 0), Position::new(0, 2)),
+            message: "stale diagnostics test".to_string(),
+            ..Default::default()
+        };
+
+        editor.handle_lsp_diagnostics(uri.clone(), Some(old_version), vec![diagnostic.clone()]);
+        assert!(editor.active_state().overlays.is_empty());
+
+        let version = editor.active_state().lsp_version();
+        editor.handle_lsp_diagnostics(uri, Some(version), vec![diagnostic]);
+        assert!(!editor.active_state().overlays.is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+//! Auditing saves (`editor.audit_log`)
+//!
+//! Saves are recorded in the audit file (see [`crate::services::audit_log`]);
+//! "Show Save History" lists the recorded saves of the active buffer's file.
+
+use super::Editor;
+use crate::services::audit_log::{self, SaveAudit, SaveRecord};
+use std::path::{Path, PathBuf};
+
+impl Editor {
+    /// The audit file, if saves are audited
+    fn audit_log_path(&self) -> Option<PathBuf> {
+        if !self.config.editor.audit_log {
+            return None;
+        }
+        self.config
+            .editor
+            .audit_log_path
+            .clone()
+            .or_else(|| audit_log::default_audit_log_path().ok())
+    }
+
+    /// Snapshot `path` before it is saved, if saves are audited
+    pub(super) fn begin_save_audit(&self, path: &Path) -> Option<SaveAudit> {
+        Some(SaveAudit::begin(self.audit_log_path()?, path))
+    }
+
+    /// Record a finished save in the audit file
+    pub(super) fn finish_save_audit(&mut self, audit: Option<SaveAudit>) {
+        let Some(audit) = audit else {
+            return;
+        };
+        if let Err(e) = audit.finish() {
+            tracing::warn!("Failed to write the audit log: {}", e);
+            self.set_status_warning(format!("Saved, but failed to write the audit log: {}", e));
+        }
+    }
+
+    /// Show the recorded saves of the active buffer's file, newest first
+    pub fn show_save_history(&mut self) {
+        let Some(log_path) = self.audit_log_path() else {
+            self.set_status_message("Save history is off (enable editor.audit_log)".to_string());
+            return;
+        };
+        let Some(path) = self
+            .active_state()
+            .buffer
+            .file_path()
+            .map(Path::to_path_buf)
+        else {
+            self.set_status_message("Buffer has no file".to_string());
+            return;
+        };
+        let records = match audit_log::save_history(&log_path, &path) {
+            Ok(records) => records,
+            Err(e) => {
+                self.set_status_error(format!("Failed to read the audit log: {}", e));
+                return;
+            }
+        };
+
+        let name = self.get_buffer_display_name(self.active_buffer);
+        let mut content = format!(
+            "Save history of {} ({} save{}, newest first)\n\n",
+            name,
+            records.len(),
+            if records.len() == 1 { "" } else { "s" }
+        );
+        for record in records.iter().rev() {
+            content.push_str(&format_save_record(record));
+        }
+
+        let buffer_name = "*Save History*".to_string();
+        let existing = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == buffer_name)
+            .map(|(id, _)| *id);
+        let buffer_id = existing.unwrap_or_else(|| {
+            self.create_virtual_buffer(buffer_name, "special".to_string(), true)
+        });
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let len = state.buffer.len();
+            if len > 0 {
+                state.buffer.delete_bytes(0, len);
+            }
+            state.buffer.insert(0, &content);
+            state.buffer.clear_modified();
+            state.editing_disabled = true;
+            state.cursors.primary_mut().position = 0;
+            state.cursors.primary_mut().anchor = None;
+        }
+        self.set_active_buffer(buffer_id);
+        self.set_status_message(format!("Save history of {}", name));
+    }
+}
+
+/// A save as shown in "Show Save History", e.g.
+/// "2026-01-02T10:00:00+01:00  alice@laptop  +12 bytes (100 -> 112)" followed
+/// by the hashes
+fn format_save_record(record: &SaveRecord) -> String {
+    let before_bytes = record
+        .before
+        .as_ref()
+        .map_or_else(|| "new".to_string(), |s| s.bytes.to_string());
+    let before_hash = record.before.as_ref().map_or("-", |s| s.sha256.as_str());
+    format!(
+        "{}  {}@{}  {:+} bytes ({} -> {})\n  before {}\n  after  {}\n\n",
+        record.timestamp,
+        record.user,
+        record.host,
+        record.byte_delta(),
+        before_bytes,
+        record.after.bytes,
+        before_hash,
+        record.after.sha256
+    )
+}
@@ -0,0 +1,271 @@
+//! Comparing two open buffers side by side
+//!
+//! "Diff Buffers" asks for a second buffer and shows it in a new pane to the
+//! right of the current one. Lines only on the left are colored as removed,
+//! lines only on the right as added and lines replaced by the one next to
+//! them as changed. While both panes are open, scrolling one scrolls the
+//! other to the line next to its top line. Running "Diff Buffers" again
+//! recomputes the diff after edits.
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::{BufferId, SplitId};
+use crate::model::line_diff::{paired_diff, PairedChange, PairedDiff};
+use crate::view::overlay::{Overlay, OverlayFace, OverlayNamespace};
+use crate::view::prompt::{Prompt, PromptType};
+
+/// Namespace of the overlays coloring the changed lines
+const DIFF_NAMESPACE: &str = "buffer-diff";
+
+/// A side-by-side diff being shown
+#[derive(Debug, Clone)]
+pub(crate) struct BufferDiff {
+    left_split: SplitId,
+    left_buffer: BufferId,
+    right_split: SplitId,
+    right_buffer: BufferId,
+    diff: PairedDiff,
+    /// Top lines of the two panes when they were last lined up
+    synced_top: (usize, usize),
+}
+
+impl Editor {
+    /// Ask which buffer to compare the active buffer with
+    pub(super) fn start_diff_buffers_prompt(&mut self) {
+        let mut ids: Vec<BufferId> = self
+            .buffers
+            .keys()
+            .copied()
+            .filter(|&id| id != self.active_buffer)
+            .collect();
+        ids.sort_by_key(|id| id.0);
+        if ids.is_empty() {
+            self.set_status_message("No other buffer to compare with".to_string());
+            return;
+        }
+
+        let suggestions = ids
+            .into_iter()
+            .map(|id| Suggestion {
+                text: self.get_buffer_display_name(id),
+                description: None,
+                value: Some(id.0.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+                match_indices: Vec::new(),
+                actions: Vec::new(),
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            format!(
+                "Diff {} with: ",
+                self.get_buffer_display_name(self.active_buffer)
+            ),
+            PromptType::DiffBuffers,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Compare the active buffer with `other`, showing `other` in a new pane
+    /// to the right
+    pub fn diff_buffers(&mut self, other: BufferId) {
+        let left = self.active_buffer;
+        if other == left || !self.buffers.contains_key(&other) {
+            self.set_status_message("Pick another buffer to compare with".to_string());
+            return;
+        }
+        let (Some(left_text), Some(right_text)) =
+            (self.buffer_bytes(left), self.buffer_bytes(other))
+        else {
+            self.set_status_error("Failed to read the buffers to compare".to_string());
+            return;
+        };
+        let Some(diff) = paired_diff(&left_text, &right_text) else {
+            self.set_status_warning("Buffers differ too much to compare".to_string());
+            return;
+        };
+
+        self.end_buffer_diff();
+        let left_split = self.split_manager.active_split();
+        self.split_pane_vertical();
+        let right_split = self.split_manager.active_split();
+        if right_split == left_split {
+            // Splitting failed and said why
+            return;
+        }
+        self.set_active_buffer(other);
+        // Keep working in the left pane; the right one follows it
+        self.save_current_split_view_state();
+        self.split_manager.set_active_split(left_split);
+        self.restore_current_split_view_state();
+
+        self.add_diff_overlays(left, &diff.left);
+        self.add_diff_overlays(other, &diff.right);
+        let status = if diff.is_empty() {
+            "Buffers are identical".to_string()
+        } else {
+            format!(
+                "{} changed line{} on the left, {} on the right",
+                diff.left.len(),
+                if diff.left.len() == 1 { "" } else { "s" },
+                diff.right.len()
+            )
+        };
+        self.buffer_diff = Some(BufferDiff {
+            left_split,
+            left_buffer: left,
+            right_split,
+            right_buffer: other,
+            diff,
+            synced_top: (usize::MAX, usize::MAX),
+        });
+        self.sync_buffer_diff_scroll();
+        self.set_status_message(status);
+    }
+
+    /// Scroll the other pane of the diff to line up with the active one.
+    /// Stops the diff once one of its panes shows something else.
+    pub(super) fn sync_buffer_diff_scroll(&mut self) {
+        let Some(diff) = &self.buffer_diff else {
+            return;
+        };
+        let showing = |split, buffer| self.split_manager.buffer_for_split(split) == Some(buffer);
+        if !showing(diff.left_split, diff.left_buffer)
+            || !showing(diff.right_split, diff.right_buffer)
+        {
+            self.end_buffer_diff();
+            return;
+        }
+
+        let active_split = self.split_manager.active_split();
+        let (left_top, right_top) = (
+            self.split_top_line(diff.left_split, diff.left_buffer),
+            self.split_top_line(diff.right_split, diff.right_buffer),
+        );
+        let (other_split, other_buffer, other_top) = if active_split == diff.left_split {
+            if left_top == diff.synced_top.0 {
+                return;
+            }
+            (
+                diff.right_split,
+                diff.right_buffer,
+                diff.diff.right_line(left_top),
+            )
+        } else if active_split == diff.right_split {
+            if right_top == diff.synced_top.1 {
+                return;
+            }
+            (
+                diff.left_split,
+                diff.left_buffer,
+                diff.diff.left_line(right_top),
+            )
+        } else {
+            return;
+        };
+
+        self.scroll_inactive_split_to_line(other_split, other_buffer, other_top);
+        if let Some(diff) = self.buffer_diff.as_mut() {
+            diff.synced_top = if active_split == diff.left_split {
+                (left_top, other_top)
+            } else {
+                (other_top, right_top)
+            };
+        }
+    }
+
+    /// Stop showing the diff, removing its coloring
+    fn end_buffer_diff(&mut self) {
+        let Some(diff) = self.buffer_diff.take() else {
+            return;
+        };
+        let namespace = OverlayNamespace::from_string(DIFF_NAMESPACE.to_string());
+        for buffer_id in [diff.left_buffer, diff.right_buffer] {
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state
+                    .overlays
+                    .clear_namespace(&namespace, &mut state.marker_list);
+            }
+        }
+    }
+
+    /// Top line of `buffer_id` as shown in `split_id`
+    pub(super) fn split_top_line(&self, split_id: SplitId, buffer_id: BufferId) -> usize {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return 0;
+        };
+        // The active split scrolls the buffer's own viewport
+        let top_byte = if split_id == self.split_manager.active_split() {
+            state.viewport.top_byte
+        } else {
+            self.split_view_states
+                .get(&split_id)
+                .map_or(state.viewport.top_byte, |view| view.viewport.top_byte)
+        };
+        state.buffer.get_line_number(top_byte)
+    }
+
+    /// Scroll `split_id`, an inactive split showing `buffer_id`, to show
+    /// `line` at the top
+    pub(super) fn scroll_inactive_split_to_line(
+        &mut self,
+        split_id: SplitId,
+        buffer_id: BufferId,
+        line: usize,
+    ) {
+        let Some(top_byte) = self.buffers.get(&buffer_id).map(|state| {
+            state
+                .buffer
+                .line_start_offset(line)
+                .unwrap_or(state.buffer.len())
+        }) else {
+            return;
+        };
+        if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+            view_state.viewport.top_byte = top_byte;
+            view_state.viewport.top_view_line_offset = 0;
+            view_state.viewport.set_skip_resize_sync();
+        }
+    }
+
+    /// Color the changed `lines` of `buffer_id`
+    fn add_diff_overlays(&mut self, buffer_id: BufferId, lines: &[(usize, PairedChange)]) {
+        let namespace = OverlayNamespace::from_string(DIFF_NAMESPACE.to_string());
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        for &(line, change) in lines {
+            let Some(start) = state.buffer.line_start_offset(line) else {
+                continue;
+            };
+            let end = state
+                .buffer
+                .line_start_offset(line + 1)
+                .unwrap_or(state.buffer.len());
+            let color = match change {
+                PairedChange::Added => self.theme.diff_added_fg,
+                PairedChange::Removed => self.theme.diff_removed_fg,
+                PairedChange::Changed => self.theme.diagnostic_warning_fg,
+            };
+            let overlay = Overlay::with_namespace(
+                &mut state.marker_list,
+                start..end,
+                OverlayFace::Foreground { color },
+                namespace.clone(),
+            )
+            .with_priority_value(10);
+            state.overlays.add(overlay);
+        }
+    }
+
+    /// All of `buffer_id`'s text
+    fn buffer_bytes(&mut self, buffer_id: BufferId) -> Option<Vec<u8>> {
+        let state = self.buffers.get_mut(&buffer_id)?;
+        let len = state.buffer.len();
+        state.buffer.get_text_range_mut(0, len).ok()
+    }
+}
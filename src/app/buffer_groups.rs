@@ -0,0 +1,219 @@
+//! Named groups of buffers
+//!
+//! Buffers can be gathered into named groups, e.g. "frontend" and "tests".
+//! "Create Buffer Group" starts a group with the current buffer and "Move
+//! Buffer to Group" moves the current buffer into an existing one; a buffer
+//! is in one group at most. "Switch Buffer Group" picks the group the tab
+//! bar, Next/Previous Buffer, "Switch to Tab by Name" and the Ctrl+Tab popup
+//! are limited to, or all buffers again. Buffers opened while a group is
+//! active join it.
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::BufferId;
+use crate::view::prompt::{Prompt, PromptType};
+
+/// A named group of buffers
+#[derive(Debug, Clone)]
+pub(crate) struct BufferGroup {
+    name: String,
+    buffers: Vec<BufferId>,
+}
+
+impl Editor {
+    /// Buffers of the active group, if one is active
+    pub(super) fn active_group_buffers(&self) -> Option<&[BufferId]> {
+        let name = self.active_buffer_group.as_deref()?;
+        self.buffer_groups
+            .iter()
+            .find(|group| group.name == name)
+            .map(|group| group.buffers.as_slice())
+    }
+
+    /// The tabs of `ids` shown with the active group: those in it, and the
+    /// active buffer
+    pub(super) fn group_tabs(&self, ids: Vec<BufferId>) -> Vec<BufferId> {
+        match self.active_group_buffers() {
+            Some(group) => ids
+                .into_iter()
+                .filter(|id| group.contains(id) || *id == self.active_buffer)
+                .collect(),
+            None => ids,
+        }
+    }
+
+    /// Add `buffer_id` to the active group if it isn't in a group yet
+    pub(super) fn join_active_buffer_group(&mut self, buffer_id: BufferId) {
+        let Some(name) = self.active_buffer_group.clone() else {
+            return;
+        };
+        if self
+            .buffer_groups
+            .iter()
+            .any(|group| group.buffers.contains(&buffer_id))
+        {
+            return;
+        }
+        if let Some(group) = self.buffer_groups.iter_mut().find(|g| g.name == name) {
+            group.buffers.push(buffer_id);
+        }
+    }
+
+    /// Take a closed buffer out of its group
+    pub(super) fn remove_from_buffer_groups(&mut self, buffer_id: BufferId) {
+        for group in &mut self.buffer_groups {
+            group.buffers.retain(|&id| id != buffer_id);
+        }
+    }
+
+    /// Move the active buffer into the group `name`, taking it out of the
+    /// one it was in
+    fn move_active_buffer_to_group(&mut self, name: &str) {
+        let buffer_id = self.active_buffer;
+        self.remove_from_buffer_groups(buffer_id);
+        if let Some(group) = self.buffer_groups.iter_mut().find(|g| g.name == name) {
+            group.buffers.push(buffer_id);
+        }
+    }
+
+    /// Start a group `name` with the active buffer and switch to it
+    pub(super) fn create_buffer_group(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.set_status_message("Group name can't be empty".to_string());
+            return;
+        }
+        if self.buffer_groups.iter().any(|group| group.name == name) {
+            self.set_status_message(format!("Group '{}' already exists", name));
+            return;
+        }
+        self.buffer_groups.push(BufferGroup {
+            name: name.to_string(),
+            buffers: Vec::new(),
+        });
+        self.move_active_buffer_to_group(name);
+        self.switch_buffer_group(name);
+    }
+
+    /// Group names as suggestions, with how many buffers each has
+    fn buffer_group_suggestions(&self) -> Vec<Suggestion> {
+        self.buffer_groups
+            .iter()
+            .map(|group| {
+                let is_active = self.active_buffer_group.as_deref() == Some(group.name.as_str());
+                let count = group.buffers.len();
+                let buffers = format!("{} buffer{}", count, if count == 1 { "" } else { "s" });
+                Suggestion {
+                    text: group.name.clone(),
+                    description: Some(if is_active {
+                        format!("(current) {}", buffers)
+                    } else {
+                        buffers
+                    }),
+                    value: Some(group.name.clone()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                    match_indices: Vec::new(),
+                    actions: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    /// Ask which group to move the active buffer to
+    pub(super) fn start_move_to_buffer_group_prompt(&mut self) {
+        if self.buffer_groups.is_empty() {
+            self.set_status_message("No buffer groups (use \"Create Buffer Group\")".to_string());
+            return;
+        }
+        let suggestions = self.buffer_group_suggestions();
+        self.prompt = Some(Prompt::with_suggestions(
+            "Move buffer to group: ".to_string(),
+            PromptType::MoveToBufferGroup,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Handle the group picked in the "Move Buffer to Group" prompt
+    pub(super) fn move_to_buffer_group(&mut self, name: &str) {
+        if !self.buffer_groups.iter().any(|group| group.name == name) {
+            self.set_status_message(format!("No buffer group '{}'", name));
+            return;
+        }
+        self.move_active_buffer_to_group(name);
+        self.set_status_message(format!(
+            "Moved {} to group '{}'",
+            self.get_buffer_display_name(self.active_buffer),
+            name
+        ));
+    }
+
+    /// Ask which group the tabs are limited to
+    pub(super) fn start_switch_buffer_group_prompt(&mut self) {
+        if self.buffer_groups.is_empty() {
+            self.set_status_message("No buffer groups (use \"Create Buffer Group\")".to_string());
+            return;
+        }
+        let mut suggestions = vec![Suggestion {
+            text: "All Buffers".to_string(),
+            description: self
+                .active_buffer_group
+                .is_none()
+                .then(|| "(current)".to_string()),
+            value: Some(String::new()),
+            disabled: false,
+            keybinding: None,
+            source: None,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
+        }];
+        suggestions.extend(self.buffer_group_suggestions());
+        self.prompt = Some(Prompt::with_suggestions(
+            "Switch buffer group: ".to_string(),
+            PromptType::SwitchBufferGroup,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Limit the tabs to the group `name` (all buffers if empty), switching
+    /// to its most recently used buffer if the active one isn't in it
+    pub(super) fn switch_buffer_group(&mut self, name: &str) {
+        if name.is_empty() {
+            self.active_buffer_group = None;
+            self.set_status_message("Showing all buffers".to_string());
+        } else {
+            let Some(group) = self.buffer_groups.iter().find(|g| g.name == name) else {
+                self.set_status_message(format!("No buffer group '{}'", name));
+                return;
+            };
+            let members = group.buffers.clone();
+            self.active_buffer_group = Some(name.to_string());
+            if !members.contains(&self.active_buffer) {
+                let target = self
+                    .recent_buffers
+                    .iter()
+                    .chain(&members)
+                    .copied()
+                    .find(|id| members.contains(id) && self.buffers.contains_key(id));
+                if let Some(target) = target {
+                    self.set_active_buffer(target);
+                }
+            }
+            self.set_status_message(format!("Buffer group '{}'", name));
+        }
+
+        let active_split = self.split_manager.active_split();
+        self.ensure_active_tab_visible(
+            active_split,
+            self.active_buffer,
+            self.effective_tabs_width(),
+        );
+    }
+}
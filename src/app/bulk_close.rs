@@ -0,0 +1,110 @@
+//! Closing several buffers at once
+//!
+//! "Close All Buffers" closes the tabs of every split, "Close Others" all but
+//! the current buffer, "Close to the Right" the tabs right of the current one
+//! in its split, and "Close Saved" those without unsaved changes. Unmodified
+//! buffers are closed right away; when some have unsaved changes the user is
+//! asked once for all of them rather than once per buffer.
+
+use super::Editor;
+use crate::model::event::BufferId;
+
+impl Editor {
+    /// Buffers open as tabs in any split, each once, in tab order
+    pub(super) fn tab_buffer_ids(&self) -> Vec<BufferId> {
+        let mut split_ids: Vec<_> = self.split_view_states.keys().copied().collect();
+        split_ids.sort_by_key(|id| id.0);
+
+        let mut ids = Vec::new();
+        for split_id in split_ids {
+            for &id in &self.split_view_states[&split_id].open_buffers {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Tabs of every split except the current buffer
+    pub(super) fn other_buffer_ids(&self) -> Vec<BufferId> {
+        let mut ids = self.tab_buffer_ids();
+        ids.retain(|&id| id != self.active_buffer);
+        ids
+    }
+
+    /// Tabs right of the current one in the active split
+    pub(super) fn buffer_ids_to_right(&self) -> Vec<BufferId> {
+        let active_split = self.split_manager.active_split();
+        let Some(view_state) = self.split_view_states.get(&active_split) else {
+            return Vec::new();
+        };
+        let tabs = self.group_tabs(view_state.open_buffers.clone());
+        match tabs.iter().position(|&id| id == self.active_buffer) {
+            Some(index) => tabs[index + 1..].to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Tabs of every split without unsaved changes
+    pub(super) fn saved_buffer_ids(&self) -> Vec<BufferId> {
+        let ids = self.tab_buffer_ids();
+        let unsaved = self.unsaved_buffer_ids(&ids);
+        ids.into_iter().filter(|id| !unsaved.contains(id)).collect()
+    }
+
+    /// The `ids` with unsaved changes
+    fn unsaved_buffer_ids(&self, ids: &[BufferId]) -> Vec<BufferId> {
+        ids.iter()
+            .copied()
+            .filter(|&id| self.buffer_needs_saving(id))
+            .collect()
+    }
+
+    /// What closing `ids` would do, e.g. "will close 7 buffers, 2 unsaved"
+    pub(super) fn close_dry_run(&self, ids: &[BufferId]) -> String {
+        let unsaved = self.unsaved_buffer_ids(ids).len();
+        match ids {
+            [] => "nothing to close".to_string(),
+            [id] if unsaved > 0 => {
+                format!("will close {}, unsaved", self.get_buffer_display_name(*id))
+            }
+            [id] => format!("will close {}", self.get_buffer_display_name(*id)),
+            _ if unsaved > 0 => format!("will close {} buffers, {} unsaved", ids.len(), unsaved),
+            _ => format!("will close {} buffers", ids.len()),
+        }
+    }
+
+    /// Close `ids`, asking first if that would lose unsaved changes
+    pub(super) fn close_buffers(&mut self, ids: Vec<BufferId>) {
+        if ids.is_empty() {
+            self.set_status_message("No buffers to close".to_string());
+            return;
+        }
+        let unsaved = self.unsaved_buffer_ids(&ids);
+        if unsaved.is_empty() {
+            self.force_close_buffers(&ids);
+        } else {
+            self.confirm_close_buffers(ids, unsaved);
+        }
+    }
+
+    /// Close `ids` without asking
+    pub(super) fn force_close_buffers(&mut self, ids: &[BufferId]) {
+        let mut closed = 0;
+        for &id in ids {
+            if !self.buffers.contains_key(&id) {
+                continue;
+            }
+            match self.force_close_buffer(id) {
+                Ok(()) => closed += 1,
+                Err(e) => tracing::warn!("Failed to close buffer {:?}: {}", id, e),
+            }
+        }
+        self.set_status_message(format!(
+            "Closed {} buffer{}",
+            closed,
+            if closed == 1 { "" } else { "s" }
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+//! Reopening buffers after they are closed
+//!
+//! Closing a buffer with a file remembers the file with its cursor and
+//! scroll position. "Reopen Closed Buffer" (Ctrl+Shift+T) opens the most
+//! recently closed one again where it was left; pressing it again goes on
+//! to the one closed before.
+
+use super::Editor;
+use crate::model::event::BufferId;
+use std::path::PathBuf;
+
+/// How many closed buffers are remembered
+const MAX_CLOSED_BUFFERS: usize = 20;
+
+/// A closed buffer and where it was left
+#[derive(Debug, Clone)]
+pub(crate) struct ClosedBuffer {
+    path: PathBuf,
+    position: usize,
+    anchor: Option<usize>,
+    sticky_column: usize,
+    top_byte: usize,
+    top_view_line_offset: usize,
+    left_column: usize,
+}
+
+impl Editor {
+    /// Remember `buffer_id`'s file and position before it is closed
+    pub(super) fn remember_closed_buffer(&mut self, buffer_id: BufferId) {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let Some(path) = state.buffer.file_path() else {
+            return;
+        };
+        // The pager's copy of stdin goes with it
+        if self.is_pager_buffer(buffer_id) {
+            return;
+        }
+        let cursor = state.cursors.primary();
+        let closed = ClosedBuffer {
+            path: path.to_path_buf(),
+            position: cursor.position,
+            anchor: cursor.anchor,
+            sticky_column: cursor.sticky_column,
+            top_byte: state.viewport.top_byte,
+            top_view_line_offset: state.viewport.top_view_line_offset,
+            left_column: state.viewport.left_column,
+        };
+        self.closed_buffers.retain(|c| c.path != closed.path);
+        self.closed_buffers.push(closed);
+        if self.closed_buffers.len() > MAX_CLOSED_BUFFERS {
+            self.closed_buffers.remove(0);
+        }
+    }
+
+    /// Open the most recently closed buffer again where it was left
+    pub(super) fn reopen_closed_buffer(&mut self) {
+        let Some(closed) = self.closed_buffers.pop() else {
+            self.set_status_message("No closed buffers to reopen".to_string());
+            return;
+        };
+        let buffer_id = match self.open_file(&closed.path) {
+            Ok(id) => id,
+            Err(e) => {
+                self.set_status_message(format!(
+                    "Failed to reopen {}: {}",
+                    closed.path.display(),
+                    e
+                ));
+                return;
+            }
+        };
+
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let max_pos = state.buffer.len();
+            let cursor = state.cursors.primary_mut();
+            cursor.position = closed.position.min(max_pos);
+            cursor.anchor = closed.anchor.map(|a| a.min(max_pos));
+            cursor.sticky_column = closed.sticky_column;
+
+            state.viewport.top_byte = closed.top_byte.min(max_pos);
+            state.viewport.top_view_line_offset = closed.top_view_line_offset;
+            state.viewport.left_column = closed.left_column;
+            // Keep ensure_visible from moving the restored scroll position
+            state.viewport.set_skip_resize_sync();
+        }
+        if self.active_buffer == buffer_id {
+            self.sync_editor_state_to_split_view_state();
+        }
+        self.set_status_message(format!(
+            "Reopened {}",
+            self.get_buffer_display_name(buffer_id)
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+//! Commands with arguments in the command palette
+//!
+//! A command can declare an argument (see [`CommandArgument`]) that is typed
+//! after its name, e.g. "Go to Line 42" or "Select Theme dark". Once the name
+//! is followed by a space the palette completes the argument instead of the
+//! command. Running the command answers the prompt its action opens with the
+//! argument, so the action itself needs no changes.
+//!
+//! [`CommandArgument`]: crate::input::commands::CommandArgument
+
+use super::Editor;
+use crate::input::commands::{ArgumentCompleter, Suggestion};
+use crate::input::keybindings::Action;
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Palette suggestions for the argument of the command named in `input`
+    ///
+    /// Returns None if the input doesn't start with the name of a command
+    /// that takes an argument.
+    pub(super) fn command_argument_suggestions(&self, input: &str) -> Option<Vec<Suggestion>> {
+        let (command, value) = self
+            .command_registry
+            .read()
+            .unwrap()
+            .split_argument(input)?;
+        let argument = command.argument.as_ref()?;
+
+        let candidates: Vec<String> = match argument.completer {
+            ArgumentCompleter::None => Vec::new(),
+            ArgumentCompleter::Themes => crate::view::theme::Theme::available_themes()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            ArgumentCompleter::Snippets => {
+                let mut names: Vec<String> = self.config.snippets.keys().cloned().collect();
+                names.sort();
+                names
+            }
+        };
+
+        let suggestion = |value: &str| {
+            Suggestion::with_description(
+                format!("{} {}", command.name, value),
+                command.description.clone(),
+            )
+        };
+
+        if !candidates.is_empty() {
+            let value_lower = value.to_lowercase();
+            return Some(
+                candidates
+                    .iter()
+                    .filter(|candidate| candidate.to_lowercase().contains(&value_lower))
+                    .map(|candidate| suggestion(candidate))
+                    .collect(),
+            );
+        }
+
+        Some(if value.is_empty() {
+            // Nothing typed yet: running the command opens its own prompt
+            vec![Suggestion::with_description(
+                command.name.clone(),
+                format!("Type the {} after the command name", argument.name),
+            )]
+        } else if argument.accepts(&value) {
+            vec![suggestion(&value)]
+        } else {
+            Vec::new()
+        })
+    }
+
+    /// After accepting a command that takes an argument, move on to the argument
+    pub(super) fn begin_command_argument(&mut self) {
+        let Some(prompt) = self.prompt.as_ref() else {
+            return;
+        };
+        if prompt.prompt_type != PromptType::Command {
+            return;
+        }
+        let takes_argument = self
+            .command_registry
+            .read()
+            .unwrap()
+            .get_all()
+            .iter()
+            .any(|cmd| cmd.name == prompt.input && cmd.argument.is_some());
+        if takes_argument {
+            if let Some(prompt) = self.prompt.as_mut() {
+                prompt.input.push(' ');
+                prompt.cursor_pos = prompt.input.len();
+            }
+        }
+    }
+
+    /// Run `action` and answer the prompt it opens with `argument`
+    pub(super) fn run_command_with_argument(
+        &mut self,
+        action: Action,
+        argument: &str,
+    ) -> std::io::Result<()> {
+        self.handle_action(action)?;
+        let Some(prompt) = self.prompt.as_mut() else {
+            // The action finished without asking (e.g. nothing to choose from)
+            return Ok(());
+        };
+        prompt.input = argument.to_string();
+        prompt.cursor_pos = prompt.input.len();
+        prompt.selected_suggestion = None;
+        self.handle_action(Action::PromptConfirm)
+    }
+}
@@ -0,0 +1,96 @@
+//! What destructive commands would do if run now
+//!
+//! In the command palette, closing buffers, reverting, replacing and deleting
+//! files show a "dry run" computed from the editor's state in place of their
+//! description, e.g. "will close 7 buffers, 2 unsaved". The commands still
+//! ask before losing anything (see `confirm_dialog`).
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::input::keybindings::Action;
+use std::collections::HashMap;
+
+impl Editor {
+    /// Replace the descriptions of destructive commands in `suggestions`
+    /// with what they would do
+    pub(super) fn add_command_dry_runs(&mut self, suggestions: &mut [Suggestion]) {
+        let actions: HashMap<String, Action> = self
+            .command_registry
+            .read()
+            .unwrap()
+            .get_all()
+            .into_iter()
+            .map(|command| (command.name, command.action))
+            .collect();
+        for suggestion in suggestions.iter_mut().filter(|s| !s.disabled) {
+            let Some(action) = actions.get(&suggestion.text) else {
+                continue;
+            };
+            if let Some(dry_run) = self.command_dry_run(action) {
+                suggestion.description = Some(dry_run);
+            }
+        }
+    }
+
+    /// What running `action` now would do, if it closes, discards, replaces
+    /// or deletes something
+    fn command_dry_run(&mut self, action: &Action) -> Option<String> {
+        match action {
+            Action::Close => Some(self.close_dry_run(&[self.active_buffer])),
+            Action::CloseAllBuffers => Some(self.close_dry_run(&self.tab_buffer_ids())),
+            Action::CloseOtherBuffers => Some(self.close_dry_run(&self.other_buffer_ids())),
+            Action::CloseBuffersToRight => Some(self.close_dry_run(&self.buffer_ids_to_right())),
+            Action::CloseSavedBuffers => Some(self.close_dry_run(&self.saved_buffer_ids())),
+            Action::Revert => {
+                let state = self.active_state();
+                let name = state.buffer.file_path()?.file_name()?.to_string_lossy();
+                Some(if state.buffer.is_modified() {
+                    format!("will discard unsaved changes to {}", name)
+                } else {
+                    format!("will reload {} from disk", name)
+                })
+            }
+            Action::Replace => {
+                let search = self.replace_dry_run_search()?;
+                let count = self.find_replace_matches(&search).len();
+                Some(format!(
+                    "{} occurrence{} of '{}' to replace",
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    search
+                ))
+            }
+            Action::FileExplorerDelete => {
+                let explorer = self.file_explorer.as_ref()?;
+                let selected = explorer.get_selected()?;
+                if selected == explorer.tree().root_id() {
+                    return None;
+                }
+                let node = explorer.tree().get_node(selected)?;
+                let name = node.entry.path.file_name()?.to_string_lossy();
+                Some(if node.is_dir() {
+                    format!("will delete {} and everything in it", name)
+                } else {
+                    format!("will delete {}", name)
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The text "Replace" would start searching for: the selection if it is
+    /// on one line, else the last search
+    fn replace_dry_run_search(&mut self) -> Option<String> {
+        let state = self.active_state_mut();
+        if let Some(range) = state.cursors.primary().selection_range() {
+            let text = state.get_text_range(range.start, range.end);
+            if !text.is_empty() && !text.contains('\n') {
+                return Some(text);
+            }
+        }
+        self.search_history
+            .last()
+            .filter(|search| !search.is_empty())
+            .map(|search| search.to_string())
+    }
+}
@@ -0,0 +1,293 @@
+//! Editing git commit messages
+//!
+//! Opening a `COMMIT_EDITMSG` file, as `git commit` does through
+//! `$GIT_EDITOR`, turns on commit mode (unless `editor.commit_mode` is off):
+//! rulers at columns 50 and 72, highlighting of the subject line past 50
+//! characters, a second line that isn't blank and body lines past 72, and the
+//! staged changes (`git diff --cached`) in a read-only pane to the right.
+//! With `editor.commit_spell_check` (on by default) words missing from the
+//! system word list are underlined. Comment lines (`#`) and everything below
+//! git's scissors line are left alone.
+
+use super::Editor;
+use crate::model::event::BufferId;
+use crate::view::overlay::{Overlay, OverlayFace, OverlayNamespace, UnderlineStyle};
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Longest subject line git tools show without truncating
+const SUBJECT_WIDTH: usize = 50;
+/// Width of the body, which `git log` indents by 4 columns
+const BODY_WIDTH: usize = 72;
+
+/// Namespace of the overlays marking problems in the message
+const COMMIT_NAMESPACE: &str = "commit-mode";
+
+/// Word list used for spell checking
+const WORD_LIST: &str = "/usr/share/dict/words";
+
+/// The commit message being edited
+#[derive(Debug)]
+pub(crate) struct CommitMode {
+    buffer_id: BufferId,
+    /// Revision of the buffer the overlays were made for
+    linted: Option<u64>,
+    /// Known words in lowercase, when spell checking
+    words: Option<HashSet<String>>,
+}
+
+/// A problem in a commit message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lint {
+    /// Text past the subject or body width
+    TooLong(usize),
+    /// The line between the subject and the body has text
+    NotBlank,
+    /// A word missing from the word list
+    Misspelled,
+}
+
+impl Lint {
+    fn message(self) -> String {
+        match self {
+            Lint::TooLong(width) => format!("Longer than {} characters", width),
+            Lint::NotBlank => "Leave a blank line after the subject".to_string(),
+            Lint::Misspelled => "Unknown word".to_string(),
+        }
+    }
+}
+
+impl Editor {
+    /// Turn on commit mode for the buffer just opened if `path` is a git
+    /// commit message
+    pub(super) fn start_commit_mode(&mut self, buffer_id: BufferId, path: &Path) {
+        if !self.config.editor.commit_mode || path.file_name() != Some(OsStr::new("COMMIT_EDITMSG"))
+        {
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.rulers = vec![SUBJECT_WIDTH as u16, BODY_WIDTH as u16];
+        }
+        let words = if self.config.editor.commit_spell_check {
+            let words = load_word_list();
+            if words.is_none() {
+                self.set_status_warning(format!(
+                    "No word list at {} for spell checking",
+                    WORD_LIST
+                ));
+            }
+            words
+        } else {
+            None
+        };
+        self.commit_mode = Some(CommitMode {
+            buffer_id,
+            linted: None,
+            words,
+        });
+
+        // COMMIT_EDITMSG is in the git directory, where git still finds the
+        // repository (and the index git commit prepared, from the
+        // environment it runs the editor in)
+        if let Some(diff) = path.parent().and_then(staged_diff) {
+            self.show_commit_diff(diff);
+        }
+    }
+
+    /// Show the changes being committed in a read-only pane to the right,
+    /// keeping the commit message focused
+    fn show_commit_diff(&mut self, diff: String) {
+        let message_split = self.split_manager.active_split();
+        self.split_pane_vertical();
+        let buffer_id = self.create_virtual_buffer(
+            "*Staged Changes*.diff".to_string(),
+            "special".to_string(),
+            true,
+        );
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.buffer.insert(0, &diff);
+            state.buffer.clear_modified();
+            state.editing_disabled = true;
+        }
+        self.set_active_buffer(buffer_id);
+
+        self.save_current_split_view_state();
+        self.split_manager.set_active_split(message_split);
+        self.restore_current_split_view_state();
+        self.set_status_message("Commit message: staged changes shown to the right".to_string());
+    }
+
+    /// Mark the problems in the commit message if it changed since last time
+    pub(super) fn update_commit_mode(&mut self) {
+        let Some(commit) = self.commit_mode.as_mut() else {
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&commit.buffer_id) else {
+            // The message was closed
+            self.commit_mode = None;
+            return;
+        };
+        let revision = state.edit_history.revision();
+        if commit.linted == Some(revision) {
+            return;
+        }
+        commit.linted = Some(revision);
+
+        let text = state.get_text_range(0, state.buffer.len());
+        let namespace = OverlayNamespace::from_string(COMMIT_NAMESPACE.to_string());
+        state
+            .overlays
+            .clear_namespace(&namespace, &mut state.marker_list);
+        for (range, lint) in lint(&text, commit.words.as_ref()) {
+            let face = match lint {
+                Lint::TooLong(_) | Lint::NotBlank => OverlayFace::Background {
+                    color: self.theme.diagnostic_warning_bg,
+                },
+                Lint::Misspelled => OverlayFace::Underline {
+                    color: self.theme.diagnostic_info_fg,
+                    style: UnderlineStyle::Wavy,
+                },
+            };
+            let overlay =
+                Overlay::with_namespace(&mut state.marker_list, range, face, namespace.clone())
+                    .with_priority_value(10)
+                    .with_message(lint.message());
+            state.overlays.add(overlay);
+        }
+    }
+}
+
+/// The staged changes of the repository whose git directory is `git_dir`,
+/// or None if there are none or git fails
+fn staged_diff(git_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--no-color"])
+        .current_dir(git_dir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let diff = String::from_utf8_lossy(&output.stdout).into_owned();
+    (output.status.success() && !diff.is_empty()).then_some(diff)
+}
+
+fn load_word_list() -> Option<HashSet<String>> {
+    let text = std::fs::read_to_string(WORD_LIST).ok()?;
+    Some(text.lines().map(str::to_lowercase).collect())
+}
+
+/// The problems in a commit message, as byte ranges of `text`
+fn lint(text: &str, words: Option<&HashSet<String>>) -> Vec<(Range<usize>, Lint)> {
+    let mut lints = Vec::new();
+    let mut start = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let line_start = start;
+        start += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.starts_with('#') {
+            // Git cuts the message at its scissors line
+            if line.contains(" >8 ") {
+                break;
+            }
+            continue;
+        }
+
+        let width = if index == 0 {
+            SUBJECT_WIDTH
+        } else {
+            BODY_WIDTH
+        };
+        if index == 1 && !line.trim().is_empty() {
+            lints.push((line_start..line_start + line.len(), Lint::NotBlank));
+        } else if let Some((overflow, _)) = line.char_indices().nth(width) {
+            lints.push((
+                line_start + overflow..line_start + line.len(),
+                Lint::TooLong(width),
+            ));
+        }
+        if let Some(words) = words {
+            for word in misspelled(line, words) {
+                lints.push((
+                    line_start + word.start..line_start + word.end,
+                    Lint::Misspelled,
+                ));
+            }
+        }
+    }
+    lints
+}
+
+/// The words of `line` missing from `words`. Only plain words are checked:
+/// not code in backticks, paths, identifiers, numbers or acronyms.
+fn misspelled(line: &str, words: &HashSet<String>) -> Vec<Range<usize>> {
+    let mut misspelled = Vec::new();
+    let mut start = 0;
+    for token in line.split(char::is_whitespace) {
+        let token_start = start;
+        start += token.len()
+            + line[start + token.len()..]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+        if token.contains('`') {
+            continue;
+        }
+
+        let trimmed = token.trim_start_matches(|c: char| !c.is_alphanumeric());
+        let word_start = token_start + token.len() - trimmed.len();
+        let word = trimmed.trim_end_matches(|c: char| !c.is_alphanumeric());
+        let plain = word.chars().count() > 1
+            && word.chars().all(|c| c.is_alphabetic() || c == '\'')
+            && word.chars().skip(1).all(|c| !c.is_uppercase());
+        if !plain {
+            continue;
+        }
+        let lower = word.to_lowercase();
+        let stem = lower.strip_suffix("'s").unwrap_or(&lower);
+        if !words.contains(&lower) && !words.contains(stem) {
+            misspelled.push(word_start..word_start + word.len());
+        }
+    }
+    misspelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_subject_blank_line_and_body() {
+        let subject = "a".repeat(52);
+        let body = "b".repeat(73);
+        let text = format!("{}\nsecond\n{}\n# {}\n", subject, body, body);
+        assert_eq!(
+            lint(&text, None),
+            vec![
+                (50..52, Lint::TooLong(50)),
+                (53..59, Lint::NotBlank),
+                (132..133, Lint::TooLong(72)),
+            ]
+        );
+
+        let scissors = "Fix\n# ------------------------ >8 ------------------------\n";
+        let text = format!("{}{}\n", scissors, body);
+        assert_eq!(lint(&text, None), vec![]);
+    }
+
+    #[test]
+    fn test_misspelled_skips_code_and_acronyms() {
+        let words: HashSet<String> = ["fix", "for", "parser", "user"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let line = "Fix teh parser (`parse_args`) for user's HTTP src/main.rs, 42 times";
+        let found: Vec<&str> = misspelled(line, &words)
+            .into_iter()
+            .map(|range| &line[range])
+            .collect();
+        assert_eq!(found, vec!["teh", "times"]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Composite commands
+//!
+//! A composite command runs other palette commands by name, in order, like a
+//! simple macro. They come from `composite_commands` in the config, and key
+//! bindings can run a sequence directly with the `run_commands` action. The
+//! chain stops at the first step that is unknown or reports an error.
+
+use super::Editor;
+use crate::input::commands::{Command, CommandSource};
+use crate::input::keybindings::Action;
+
+/// How deeply composite commands may run each other (guards against cycles)
+const MAX_SEQUENCE_DEPTH: usize = 8;
+
+impl Editor {
+    /// Add the configured composite commands to the command palette
+    pub(super) fn register_composite_commands(&mut self) {
+        let registry = self.command_registry.read().unwrap();
+        for composite in &self.config.composite_commands {
+            registry.register(Command {
+                name: composite.name.clone(),
+                description: composite
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| composite.commands.join(", ")),
+                action: Action::RunCommandSequence(composite.commands.clone()),
+                contexts: vec![],
+                source: CommandSource::Builtin,
+                when: None,
+                argument: None,
+            });
+        }
+    }
+
+    /// Run commands by name, stopping at the first one that fails
+    pub(super) fn run_command_sequence(&mut self, names: &[String]) -> std::io::Result<()> {
+        if self.command_sequence_depth >= MAX_SEQUENCE_DEPTH {
+            self.set_status_error("Composite commands are nested too deeply".to_string());
+            return Ok(());
+        }
+
+        self.command_sequence_depth += 1;
+        let result = self.run_command_steps(names);
+        self.command_sequence_depth -= 1;
+        result
+    }
+
+    fn run_command_steps(&mut self, names: &[String]) -> std::io::Result<()> {
+        let total = names.len();
+        for (index, name) in names.iter().enumerate() {
+            let step = index + 1;
+            let command = self.command_registry.read().unwrap().find_by_name(name);
+            let Some(command) = command else {
+                self.set_status_error(format!(
+                    "Step {}/{} failed: unknown command '{}'",
+                    step, total, name
+                ));
+                return Ok(());
+            };
+
+            let started = std::time::Instant::now();
+            let had_prompt = self.prompt.is_some();
+            if let Err(e) = self.handle_action(command.action) {
+                self.set_status_error(format!(
+                    "Step {}/{} ('{}') failed: {}",
+                    step, total, name, e
+                ));
+                return Ok(());
+            }
+            if let Some(error) = self.status_messages.error_since(started) {
+                let message = format!(
+                    "Step {}/{} ('{}') failed: {}",
+                    step, total, name, error.text
+                );
+                self.set_status_error(message);
+                return Ok(());
+            }
+            // The remaining steps can't wait for the user to answer a prompt
+            if !had_prompt && self.prompt.is_some() && step < total {
+                self.set_status_warning(format!(
+                    "Step {}/{} ('{}') is waiting for input; the remaining commands were not run",
+                    step, total, name
+                ));
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,554 @@
+//! Confirmation dialogs for actions that lose or overwrite data
+//!
+//! Quitting or closing with unsaved changes, overwriting a file, reverting,
+//! deleting files and replace-all ask first. The dialog is modal: until it is
+//! answered it takes all keyboard and mouse input.
+
+use super::Editor;
+use crate::model::event::BufferId;
+use crate::view::confirm_dialog::{
+    ConfirmChoice, ConfirmDialog, DetailLine, DetailStyle, DialogButton,
+};
+use crate::view::ui::{ConfirmDialogLayout, ConfirmDialogRenderer};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Action carried out once a confirmation dialog is answered
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ConfirmAction {
+    /// Quit with modified buffers (Yes = save all, No = discard all)
+    Quit,
+    /// Save or discard one buffer while reviewing them before quitting
+    ReviewQuit {
+        buffer_id: BufferId,
+        /// Buffers still to be reviewed after this one
+        remaining: Vec<BufferId>,
+        total: usize,
+    },
+    /// Save over a file that changed on disk
+    SaveConflict,
+    /// "Save As" onto an existing file
+    OverwriteFile(PathBuf),
+    /// Close a modified buffer (Yes = save, No = discard)
+    CloseBuffer(BufferId),
+    /// Close several buffers, some of them modified, discarding the changes
+    CloseBuffers(Vec<BufferId>),
+    /// Revert a modified buffer to the file on disk
+    Revert,
+    /// Delete a file or directory from the file explorer
+    DeleteFile { path: PathBuf, is_dir: bool },
+    /// Replace every occurrence of `search`
+    ReplaceAll { search: String, replacement: String },
+    /// Save the session over an existing named session
+    OverwriteSession(String),
+    /// Switch to a named session (Yes = stash the current one, No = close it)
+    SwitchSession(String),
+    /// A file locked by another editor was opened read-only
+    /// (Yes = steal the lock, No = close the buffer)
+    LockedFile { buffer_id: BufferId, path: PathBuf },
+    /// A paste of dropped file paths (Yes = open the files, No = insert `text`)
+    OpenDroppedFiles { paths: Vec<PathBuf>, text: String },
+    /// Save a binding made with "Bind Key" to the key bindings file
+    /// (No = keep it for this session only)
+    SaveKeybinding {
+        chord: String,
+        action: String,
+        args: HashMap<String, serde_json::Value>,
+    },
+}
+
+/// An open dialog and the action waiting on it
+#[derive(Debug)]
+pub(crate) struct PendingConfirm {
+    dialog: ConfirmDialog,
+    action: ConfirmAction,
+    /// Where the dialog was last drawn (for mouse clicks)
+    layout: Option<ConfirmDialogLayout>,
+}
+
+impl Editor {
+    /// Open a confirmation dialog; `action` runs with the chosen answer
+    pub(crate) fn show_confirm_dialog(&mut self, dialog: ConfirmDialog, action: ConfirmAction) {
+        self.confirm_dialog = Some(PendingConfirm {
+            dialog,
+            action,
+            layout: None,
+        });
+    }
+
+    /// The open confirmation dialog, if any
+    pub fn confirm_dialog(&self) -> Option<&ConfirmDialog> {
+        self.confirm_dialog.as_ref().map(|pending| &pending.dialog)
+    }
+
+    pub(crate) fn confirm_save_conflict(&mut self) {
+        let name = self.get_buffer_display_name(self.active_buffer);
+        let dialog = ConfirmDialog::new(
+            "File Changed on Disk",
+            format!(
+                "'{}' was changed by another program since it was opened. Overwrite it?",
+                name
+            ),
+            vec![
+                DialogButton::new("Overwrite", 'o', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::SaveConflict);
+    }
+
+    pub(crate) fn confirm_overwrite(&mut self, path: PathBuf) {
+        let dialog = ConfirmDialog::new(
+            "Overwrite File",
+            format!("'{}' already exists. Overwrite it?", path.display()),
+            vec![
+                DialogButton::new("Overwrite", 'o', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::OverwriteFile(path));
+    }
+
+    pub(crate) fn confirm_close_buffer(&mut self, buffer_id: BufferId) {
+        let name = self.get_buffer_display_name(buffer_id);
+        let dialog = ConfirmDialog::new(
+            "Unsaved Changes",
+            format!("'{}' has unsaved changes. Save before closing?", name),
+            vec![
+                DialogButton::new("Save", 's', ConfirmChoice::Yes),
+                DialogButton::new("Discard", 'd', ConfirmChoice::No),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::CloseBuffer(buffer_id));
+    }
+
+    /// Ask once before closing several buffers, listing the modified ones
+    pub(crate) fn confirm_close_buffers(&mut self, ids: Vec<BufferId>, unsaved: Vec<BufferId>) {
+        let message = if ids.len() == 1 {
+            "Close 1 buffer? Its unsaved changes will be lost:".to_string()
+        } else {
+            format!(
+                "Close {} buffers? Unsaved changes in {} of them will be lost:",
+                ids.len(),
+                unsaved.len()
+            )
+        };
+        let details = unsaved
+            .iter()
+            .map(|&id| {
+                let name = self.get_buffer_display_name(id);
+                DetailLine::new(format!("• {}", name), DetailStyle::Normal)
+            })
+            .collect();
+        let dialog = ConfirmDialog::new(
+            "Unsaved Changes",
+            message,
+            vec![
+                DialogButton::new("Discard", 'd', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_details(details)
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::CloseBuffers(ids));
+    }
+
+    pub(crate) fn confirm_revert(&mut self) {
+        let dialog = ConfirmDialog::new(
+            "Revert",
+            "The buffer has unsaved changes. Revert to the file on disk?",
+            vec![
+                DialogButton::new("Revert", 'r', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::Revert);
+    }
+
+    pub(crate) fn confirm_delete(&mut self, path: PathBuf, is_dir: bool) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let message = if is_dir {
+            format!("Delete the directory '{}' and everything in it?", name)
+        } else {
+            format!("Delete '{}'?", name)
+        };
+        let dialog = ConfirmDialog::new(
+            "Delete",
+            message,
+            vec![
+                DialogButton::new("Delete", 'd', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::DeleteFile { path, is_dir });
+    }
+
+    pub(crate) fn confirm_overwrite_session(&mut self, name: String) {
+        let dialog = ConfirmDialog::new(
+            "Overwrite Session",
+            format!("A session named '{}' already exists. Overwrite it?", name),
+            vec![
+                DialogButton::new("Overwrite", 'o', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::OverwriteSession(name));
+    }
+
+    /// Ask whether to stash or close the current session before switching
+    ///
+    /// Switching closes every file, so unsaved changes have to be dealt with first.
+    pub(crate) fn confirm_switch_session(&mut self, name: String) {
+        let modified_count = self.count_modified_buffers();
+        if modified_count > 0 {
+            self.set_status_warning(format!(
+                "Save or discard unsaved changes before switching sessions ({} modified)",
+                modified_count
+            ));
+            return;
+        }
+
+        let current = match &self.session_name {
+            Some(current) => format!("'{}'", current),
+            None => "the current session".to_string(),
+        };
+        let dialog = ConfirmDialog::new(
+            "Switch Session",
+            format!(
+                "Switch to session '{}'?\nStash saves {} so you can switch back; Close leaves it as it was last saved.",
+                name, current
+            ),
+            vec![
+                DialogButton::new("Stash", 's', ConfirmChoice::Yes),
+                DialogButton::new("Close", 'l', ConfirmChoice::No),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        );
+        self.show_confirm_dialog(dialog, ConfirmAction::SwitchSession(name));
+    }
+
+    /// Ask before replacing every occurrence (shows how many there are)
+    pub(crate) fn confirm_replace_all(&mut self, search: String, replacement: String) {
+        if search.is_empty() {
+            self.set_status_message("Replace: empty search query.".to_string());
+            self.clear_search_scope();
+            return;
+        }
+
+        let count = self.find_replace_matches(&search).len();
+        if count == 0 {
+            let msg = if self.search_scope().is_some() {
+                format!("No occurrences of '{}' found in selection.", search)
+            } else {
+                format!("No occurrences of '{}' found.", search)
+            };
+            self.set_status_message(msg);
+            self.clear_search_scope();
+            return;
+        }
+
+        let dialog = ConfirmDialog::new(
+            "Replace All",
+            format!(
+                "Replace {} occurrence{} of '{}' with '{}'{}?",
+                count,
+                if count == 1 { "" } else { "s" },
+                search,
+                replacement,
+                if self.search_scope().is_some() {
+                    " in the selection"
+                } else {
+                    ""
+                }
+            ),
+            vec![
+                DialogButton::new("Replace All", 'a', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        );
+        self.show_confirm_dialog(
+            dialog,
+            ConfirmAction::ReplaceAll {
+                search,
+                replacement,
+            },
+        );
+    }
+
+    /// Handle a key while a confirmation dialog is open
+    ///
+    /// Every key is consumed so nothing reaches the editor behind the dialog.
+    pub(super) fn handle_confirm_dialog_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(pending) = self.confirm_dialog.as_mut() else {
+            return;
+        };
+        let dialog = &mut pending.dialog;
+
+        let choice = match code {
+            KeyCode::Left | KeyCode::BackTab => {
+                dialog.focus_prev();
+                None
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                dialog.focus_prev();
+                None
+            }
+            KeyCode::Right | KeyCode::Tab => {
+                dialog.focus_next();
+                None
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => Some(dialog.focused_choice()),
+            KeyCode::Esc => Some(ConfirmChoice::Cancel),
+            KeyCode::Char(c) if (modifiers - KeyModifiers::SHIFT).is_empty() => {
+                dialog.choice_for_key(c)
+            }
+            _ => None,
+        };
+
+        if let Some(choice) = choice {
+            self.resolve_confirm_dialog(choice);
+        }
+    }
+
+    /// Handle a mouse event while a confirmation dialog is open
+    ///
+    /// Clicking a button presses it; everything else is ignored.
+    /// Returns true if the screen needs to be redrawn.
+    pub(super) fn handle_confirm_dialog_mouse(&mut self, mouse_event: MouseEvent) -> bool {
+        if mouse_event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return false;
+        }
+        let Some(pending) = self.confirm_dialog.as_ref() else {
+            return false;
+        };
+        let Some(layout) = pending.layout.as_ref() else {
+            return false;
+        };
+
+        let clicked = layout.buttons.iter().position(|rect| {
+            mouse_event.row == rect.y
+                && mouse_event.column >= rect.x
+                && mouse_event.column < rect.right()
+        });
+        match clicked.and_then(|index| pending.dialog.buttons.get(index)) {
+            Some(button) => {
+                let choice = button.choice;
+                self.resolve_confirm_dialog(choice);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draw the open dialog on top of everything else
+    pub(super) fn render_confirm_dialog(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        if let Some(pending) = self.confirm_dialog.as_mut() {
+            pending.layout = Some(ConfirmDialogRenderer::render(
+                frame,
+                screen,
+                &pending.dialog,
+                &self.theme,
+            ));
+        }
+    }
+
+    /// Close the dialog and carry out its action for `choice`
+    fn resolve_confirm_dialog(&mut self, choice: ConfirmChoice) {
+        let Some(pending) = self.confirm_dialog.take() else {
+            return;
+        };
+
+        match (pending.action, choice) {
+            (ConfirmAction::Quit, ConfirmChoice::Yes) => {
+                self.save_all_and_quit();
+            }
+            (ConfirmAction::Quit, ConfirmChoice::No) => {
+                self.should_quit = true;
+            }
+            (ConfirmAction::Quit, ConfirmChoice::Review) => {
+                self.start_quit_review();
+            }
+            (
+                ConfirmAction::ReviewQuit {
+                    buffer_id,
+                    remaining,
+                    total,
+                },
+                ConfirmChoice::Yes,
+            ) => {
+                self.save_and_continue_quit_review(buffer_id, remaining, total);
+            }
+            (
+                ConfirmAction::ReviewQuit {
+                    remaining, total, ..
+                },
+                ConfirmChoice::No,
+            ) => {
+                self.continue_quit_review(remaining, total);
+            }
+            (ConfirmAction::Quit | ConfirmAction::ReviewQuit { .. }, _) => {
+                self.set_status_message("Quit cancelled".to_string());
+            }
+            (ConfirmAction::SaveConflict, ConfirmChoice::Yes) => {
+                if let Err(e) = self.save() {
+                    self.set_status_error(format!("Failed to save: {}", e));
+                }
+            }
+            (ConfirmAction::OverwriteFile(path), ConfirmChoice::Yes) => {
+                self.save_as(path);
+            }
+            (ConfirmAction::SaveConflict | ConfirmAction::OverwriteFile(_), _) => {
+                self.pending_close_buffer = None;
+                self.pending_quit_review = None;
+                self.set_status_message("Save cancelled".to_string());
+            }
+            (ConfirmAction::CloseBuffer(buffer_id), ConfirmChoice::Yes) => {
+                self.save_and_close_buffer(buffer_id);
+            }
+            (ConfirmAction::CloseBuffer(buffer_id), ConfirmChoice::No) => {
+                if let Err(e) = self.force_close_buffer(buffer_id) {
+                    self.set_status_warning(format!("Cannot close buffer: {}", e));
+                } else {
+                    self.set_status_message("Buffer closed (changes discarded)".to_string());
+                }
+            }
+            (ConfirmAction::CloseBuffer(_), _) => {
+                self.set_status_message("Close cancelled".to_string());
+            }
+            (ConfirmAction::CloseBuffers(ids), ConfirmChoice::Yes) => {
+                self.force_close_buffers(&ids);
+            }
+            (ConfirmAction::CloseBuffers(_), _) => {
+                self.set_status_message("Close cancelled".to_string());
+            }
+            (ConfirmAction::Revert, ConfirmChoice::Yes) => {
+                if let Err(e) = self.revert_file() {
+                    self.set_status_error(format!("Failed to revert: {}", e));
+                }
+            }
+            (ConfirmAction::Revert, _) => {
+                self.set_status_message("Revert cancelled".to_string());
+            }
+            (ConfirmAction::DeleteFile { path, is_dir }, ConfirmChoice::Yes) => {
+                self.perform_file_explorer_delete(&path, is_dir);
+            }
+            (ConfirmAction::DeleteFile { .. }, _) => {
+                self.set_status_message("Delete cancelled".to_string());
+            }
+            (
+                ConfirmAction::ReplaceAll {
+                    search,
+                    replacement,
+                },
+                ConfirmChoice::Yes,
+            ) => {
+                self.perform_replace(&search, &replacement);
+                self.clear_search_scope();
+            }
+            (ConfirmAction::ReplaceAll { .. }, _) => {
+                self.clear_search_scope();
+                self.set_status_message("Replace cancelled".to_string());
+            }
+            (ConfirmAction::OverwriteSession(name), ConfirmChoice::Yes) => {
+                match self.save_session_as(&name) {
+                    Ok(()) => self.set_status_message(format!("Session saved as '{}'", name)),
+                    Err(e) => self.set_status_error(format!("Failed to save session: {}", e)),
+                }
+            }
+            (ConfirmAction::OverwriteSession(_), _) => {
+                self.set_status_message("Save cancelled".to_string());
+            }
+            (ConfirmAction::SwitchSession(name), ConfirmChoice::Yes | ConfirmChoice::No) => {
+                let stash = choice == ConfirmChoice::Yes;
+                match self.switch_session(&name, stash) {
+                    Ok(()) => self.set_status_message(format!("Switched to session '{}'", name)),
+                    Err(e) => self.set_status_error(format!("Failed to switch session: {}", e)),
+                }
+            }
+            (ConfirmAction::SwitchSession(_), _) => {
+                self.set_status_message("Switch cancelled".to_string());
+            }
+            (ConfirmAction::LockedFile { buffer_id, path }, ConfirmChoice::Yes) => {
+                self.steal_file_lock(buffer_id, &path);
+            }
+            (ConfirmAction::LockedFile { buffer_id, .. }, ConfirmChoice::No) => {
+                if let Err(e) = self.close_buffer(buffer_id) {
+                    self.set_status_warning(format!("Cannot close buffer: {}", e));
+                }
+            }
+            (ConfirmAction::LockedFile { buffer_id, .. }, _) => {
+                self.set_status_message(format!(
+                    "{} is read-only",
+                    self.get_buffer_display_name(buffer_id)
+                ));
+            }
+            (ConfirmAction::OpenDroppedFiles { paths, .. }, ConfirmChoice::Yes) => {
+                self.open_dropped_files(&paths);
+            }
+            (ConfirmAction::OpenDroppedFiles { text, .. }, ConfirmChoice::No) => {
+                self.insert_pasted_text(text);
+            }
+            (ConfirmAction::OpenDroppedFiles { .. }, _) => {}
+            (
+                ConfirmAction::SaveKeybinding {
+                    chord,
+                    action,
+                    args,
+                },
+                ConfirmChoice::Yes,
+            ) => {
+                self.save_keybinding(&chord, &action, &args);
+            }
+            (ConfirmAction::SaveKeybinding { .. }, _) => {}
+        }
+    }
+
+    /// Save a modified buffer, then close it
+    ///
+    /// Buffers without a file go through "Save As" first and are closed once
+    /// that completes.
+    fn save_and_close_buffer(&mut self, buffer_id: BufferId) {
+        let has_path = self
+            .buffers
+            .get(&buffer_id)
+            .is_some_and(|s| s.buffer.file_path().is_some());
+
+        if !has_path {
+            self.pending_close_buffer = Some(buffer_id);
+            self.start_prompt_with_initial_text(
+                "Save as: ".to_string(),
+                crate::view::prompt::PromptType::SaveFileAs,
+                String::new(),
+            );
+            return;
+        }
+
+        let old_active = self.active_buffer;
+        self.set_active_buffer(buffer_id);
+        let saved = self.save();
+        self.set_active_buffer(old_active);
+        if let Err(e) = saved {
+            self.set_status_error(format!("Failed to save: {}", e));
+            return;
+        }
+
+        if let Err(e) = self.force_close_buffer(buffer_id) {
+            self.set_status_warning(format!("Cannot close buffer: {}", e));
+        } else {
+            self.set_status_message("Saved and closed".to_string());
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Edit transactions in the editor
+//!
+//! An async producer reads a buffer's revision (`buffer_revision`), computes
+//! its edits against that text, and commits them with
+//! `commit_edit_transaction`. The edits are rebased over anything typed in the
+//! meantime (see `model::edit`) and applied as one undoable batch.
+
+use super::Editor;
+use crate::model::edit::{ConflictPolicy, EditTransaction, TransactionError};
+use crate::model::event::{BufferId, Event};
+use crate::services::plugins::api::PluginResponse;
+
+impl Editor {
+    /// Current revision of a buffer's text
+    pub fn buffer_revision(&self, buffer_id: BufferId) -> Option<u64> {
+        self.buffers
+            .get(&buffer_id)
+            .map(|state| state.edit_history.revision())
+    }
+
+    /// Start a transaction for edits against a buffer's current text
+    pub fn begin_edit_transaction(&self, buffer_id: BufferId) -> Option<EditTransaction> {
+        self.buffer_revision(buffer_id).map(EditTransaction::new)
+    }
+
+    /// Apply a transaction's edits to a buffer as one undoable change
+    ///
+    /// Returns the number of edits applied (fewer than the transaction's with
+    /// `ConflictPolicy::SkipConflicting`).
+    pub fn commit_edit_transaction(
+        &mut self,
+        buffer_id: BufferId,
+        transaction: &EditTransaction,
+        policy: ConflictPolicy,
+        description: &str,
+    ) -> Result<usize, TransactionError> {
+        let state = self
+            .buffers
+            .get_mut(&buffer_id)
+            .ok_or(TransactionError::BufferNotFound)?;
+        let edits = transaction.rebase(&state.edit_history, policy)?;
+
+        // Edits are sorted from the end of the text, so offsets stay valid
+        let cursor_id = state.cursors.primary_id();
+        let mut events = Vec::new();
+        for edit in &edits {
+            if !edit.range.is_empty() {
+                events.push(Event::Delete {
+                    range: edit.range.clone(),
+                    deleted_text: state.get_text_range(edit.range.start, edit.range.end),
+                    cursor_id,
+                });
+            }
+            if !edit.text.is_empty() {
+                events.push(Event::Insert {
+                    position: edit.range.start,
+                    text: edit.text.clone(),
+                    cursor_id,
+                });
+            }
+        }
+
+        if !events.is_empty() {
+            let batch = Event::Batch {
+                events,
+                description: description.to_string(),
+            };
+            self.apply_rename_batch_to_buffer(buffer_id, batch)
+                .map_err(|_| TransactionError::BufferNotFound)?;
+            self.invalidate_layouts_for_buffer(buffer_id);
+        }
+        Ok(edits.len())
+    }
+
+    /// Apply edits sent by a plugin and answer with the result
+    pub(super) fn handle_apply_edits(
+        &mut self,
+        buffer_id: BufferId,
+        transaction: EditTransaction,
+        policy: ConflictPolicy,
+        request_id: u64,
+    ) {
+        let result = self
+            .commit_edit_transaction(buffer_id, &transaction, policy, "Plugin edits")
+            .map_err(|e| e.to_string());
+        self.send_plugin_response(PluginResponse::EditsApplied { request_id, result });
+    }
+}
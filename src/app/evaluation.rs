@@ -0,0 +1,180 @@
+//! "Evaluate Selection": running code snippets through a language's evaluator
+//!
+//! The selection (or the cursor's line) is piped through the evaluator
+//! configured for the buffer's language in the background. The result is
+//! shown in a popup, or inserted below the evaluated code.
+
+use super::Editor;
+use crate::config::LanguageConfig;
+use crate::model::event::{BufferId, Event, PopupContentData, PopupData, PopupPositionData};
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::evaluation;
+use crate::services::project_tasks::TaskResult;
+use std::time::Duration;
+
+/// Most result lines shown in the popup
+const MAX_POPUP_LINES: usize = 20;
+
+impl Editor {
+    /// Language configuration of the active buffer, matched by file extension
+    fn active_language_config(&self) -> Option<(&String, &LanguageConfig)> {
+        let path = self.active_state().buffer.file_path()?;
+        let extension = path.extension()?.to_str()?;
+        self.config
+            .languages
+            .iter()
+            .find(|(_, language)| language.extensions.iter().any(|ext| ext == extension))
+    }
+
+    /// Evaluate the selection, or the cursor's line, in the background
+    ///
+    /// With `insert` the result is inserted on a new line below the evaluated
+    /// code, otherwise it is shown in a popup.
+    pub(super) fn evaluate_selection(&mut self, insert: bool) {
+        let Some((name, language)) = self.active_language_config() else {
+            self.set_status_message("No evaluator: unknown language".to_string());
+            return;
+        };
+        let Some(command) = language.evaluator.clone() else {
+            self.set_status_message(format!("No evaluator configured for {}", name));
+            return;
+        };
+        if insert && self.is_editing_disabled() {
+            self.set_status_message("Editing disabled in this buffer".to_string());
+            return;
+        }
+
+        let estimated_line_length = self.config.editor.estimated_line_length;
+        let state = self.active_state_mut();
+        let cursor = state.cursors.primary();
+        let range = match cursor.selection_range() {
+            Some(range) => range,
+            None => {
+                let mut lines = state
+                    .buffer
+                    .line_iterator(cursor.position, estimated_line_length);
+                match lines.next() {
+                    Some((start, content)) => start..start + content.len(),
+                    None => 0..0,
+                }
+            }
+        };
+        let code = state.get_text_range(range.start, range.end);
+        if code.trim().is_empty() {
+            self.set_status_message("Nothing to evaluate".to_string());
+            return;
+        }
+
+        // The end of the evaluated code's last line (before its newline)
+        let insert_at = insert.then(|| {
+            let last = range.end.saturating_sub(1).max(range.start);
+            state
+                .buffer
+                .line_iterator(last, estimated_line_length)
+                .next()
+                .map(|(start, content)| start + content.trim_end_matches(['\n', '\r']).len())
+                .unwrap_or(range.end)
+        });
+
+        let buffer_id = self.active_buffer;
+        let working_dir = self.working_dir.clone();
+        let project_env = self.project_env.clone();
+        let timeout = Duration::from_secs(self.config.editor.evaluation_timeout_secs);
+        self.spawn_task(
+            format!("Evaluating with {}", command),
+            move |_| async move {
+                let result = evaluation::evaluate(
+                    &command,
+                    code,
+                    &working_dir,
+                    project_env.as_deref(),
+                    timeout,
+                )
+                .await;
+                AsyncMessage::EvaluationOutput {
+                    buffer_id,
+                    insert_at,
+                    result,
+                }
+            },
+        );
+    }
+
+    /// Show or insert the result of an evaluation
+    pub(super) fn handle_evaluation_output(
+        &mut self,
+        buffer_id: BufferId,
+        insert_at: Option<usize>,
+        result: std::io::Result<TaskResult>,
+    ) {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.set_status_error(format!("Evaluation failed: {}", e));
+                return;
+            }
+        };
+        if result.exit_code != 0 {
+            let message = result
+                .stderr
+                .lines()
+                .chain(result.stdout.lines())
+                .map(str::trim)
+                .rfind(|line| !line.is_empty())
+                .unwrap_or("no output");
+            self.set_status_error(format!(
+                "Evaluation failed (exit code {}): {}",
+                result.exit_code, message
+            ));
+            return;
+        }
+
+        let output = result.stdout.trim_end_matches(['\n', '\r']);
+        if buffer_id != self.active_buffer {
+            // The user moved on; don't touch another buffer
+            let first_line = output.lines().next().unwrap_or("");
+            self.set_status_message(format!("Result: {}", first_line));
+            return;
+        }
+
+        match insert_at {
+            Some(position) => {
+                let state = self.active_state();
+                let event = Event::Insert {
+                    position: position.min(state.buffer.len()),
+                    text: format!("\n{}", output),
+                    cursor_id: state.cursors.primary_id(),
+                };
+                self.active_event_log_mut().append(event.clone());
+                self.apply_event_to_active_buffer(&event);
+                self.set_status_message("Inserted evaluation result".to_string());
+            }
+            None => {
+                let mut lines: Vec<String> = output.lines().map(String::from).collect();
+                if lines.is_empty() {
+                    lines.push("(no output)".to_string());
+                }
+                if lines.len() > MAX_POPUP_LINES {
+                    let hidden = lines.len() - MAX_POPUP_LINES;
+                    lines.truncate(MAX_POPUP_LINES);
+                    lines.push(format!("... {} more lines", hidden));
+                }
+                let width = lines
+                    .iter()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .clamp(20, 80) as u16
+                    + 2;
+                self.show_popup(PopupData {
+                    title: Some("Result".to_string()),
+                    content: PopupContentData::Text(lines),
+                    position: PopupPositionData::BelowCursor,
+                    width,
+                    max_height: MAX_POPUP_LINES as u16 + 3,
+                    bordered: true,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Exporting the active buffer, or its selection, as HTML or ANSI text
+
+use super::{normalize_path, Editor};
+use crate::input::path_completion::resolve_path;
+use crate::view::export::{export_ansi, export_html, ExportFormat};
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Prompt for the file to export to
+    ///
+    /// The default is the buffer's file with the format's extension appended
+    /// (e.g. `src/main.rs.html`).
+    pub(super) fn start_export_prompt(&mut self, format: ExportFormat) {
+        let default_path = self
+            .active_state()
+            .buffer
+            .file_path()
+            .map(|p| {
+                let relative = p.strip_prefix(&self.working_dir).unwrap_or(p);
+                format!("{}.{}", relative.display(), format.extension())
+            })
+            .unwrap_or_else(|| format!("export.{}", format.extension()));
+        let selection = self.active_state().cursors.primary().selection_range();
+        let what = if selection.is_some() {
+            "selection"
+        } else {
+            "buffer"
+        };
+        self.start_prompt_with_initial_text(
+            format!("Export {} as {} to: ", what, format.label()),
+            PromptType::Export { format },
+            default_path,
+        );
+    }
+
+    /// Write the selection (or the whole buffer) to `input` in `format`
+    pub(super) fn export_active_buffer(&mut self, input: &str, format: ExportFormat) {
+        let input = input.trim();
+        if input.is_empty() {
+            self.set_status_message("Export canceled: no file name".to_string());
+            return;
+        }
+        let path = normalize_path(&resolve_path(input, &self.working_dir));
+        let title = self.get_buffer_display_name(self.active_buffer);
+
+        let theme = &self.theme;
+        let Some(state) = self.buffers.get_mut(&self.active_buffer) else {
+            return;
+        };
+        let selection = state.cursors.primary().selection_range();
+        let range = selection.clone().unwrap_or(0..state.buffer.len());
+        let text = state.get_text_range(range.start, range.end);
+        let spans =
+            state
+                .highlighter
+                .highlight_viewport(&state.buffer, range.start, range.end, theme);
+        let exported = match format {
+            ExportFormat::Html => export_html(&text, range.start, &spans, theme, &title),
+            ExportFormat::Ansi => export_ansi(&text, range.start, &spans),
+        };
+
+        match std::fs::write(&path, exported) {
+            Ok(()) => self.set_status_message(format!(
+                "Exported {} to {}",
+                if selection.is_some() {
+                    "selection"
+                } else {
+                    "buffer"
+                },
+                path.display()
+            )),
+            Err(e) => {
+                self.set_status_error(format!("Failed to export to {}: {}", path.display(), e))
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::app::open_to_side::{OpenSide, SideOpen};
 use crate::view::file_tree::TreeNode;
 use std::path::PathBuf;
 
@@ -16,14 +17,6 @@ fn get_parent_dir_path(node: &TreeNode) -> PathBuf {
     }
 }
 
-/// Generate a timestamp suffix for naming new files/directories.
-fn timestamp_suffix() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-}
-
 /// Get the parent node ID for refreshing after file operations.
 /// If the node is a directory, the node itself is the parent. Otherwise, look up the actual parent.
 fn get_parent_node_id(
@@ -101,6 +94,28 @@ impl Editor {
         }
     }
 
+    /// Show a file that was created outside the explorer (e.g., by "Save As")
+    ///
+    /// Re-reads its directory if that is expanded in the tree, and selects the
+    /// file if it belongs to the active buffer.
+    pub(super) fn file_explorer_show_new_file(&mut self, path: &Path, is_active: bool) {
+        if let (Some(runtime), Some(explorer), Some(parent)) =
+            (&self.tokio_runtime, &mut self.file_explorer, path.parent())
+        {
+            let parent_id = explorer
+                .tree()
+                .get_node_by_path(parent)
+                .filter(|node| node.is_expanded())
+                .map(|node| node.id);
+            if let Some(parent_id) = parent_id {
+                let _ = runtime.block_on(explorer.tree_mut().refresh_node(parent_id));
+            }
+        }
+        if is_active {
+            self.sync_file_explorer_to_active_file();
+        }
+    }
+
     pub fn focus_file_explorer(&mut self) {
         if self.file_explorer_visible {
             // Cancel search/replace prompts when switching focus away from editor
@@ -280,7 +295,7 @@ impl Editor {
                     }
                 }
                 Err(e) => {
-                    self.set_status_message(format!("Error: {}", e));
+                    self.set_status_error(format!("Error: {}", e));
                 }
             }
         }
@@ -296,7 +311,7 @@ impl Editor {
         if let Some((is_dir, path, name)) = entry_type {
             if is_dir {
                 self.file_explorer_toggle_expand();
-            } else {
+            } else if !self.pick_file(&path) {
                 self.open_file(&path)?;
                 self.set_status_message(format!("Opened: {}", name));
                 self.focus_editor();
@@ -305,6 +320,21 @@ impl Editor {
         Ok(())
     }
 
+    /// Open the selected file in another pane (see `open_to_side`)
+    pub(super) fn file_explorer_open_to_side(&mut self, side: OpenSide) {
+        let selected = self
+            .file_explorer
+            .as_ref()
+            .and_then(|explorer| explorer.get_selected_entry())
+            .map(|entry| (entry.is_dir(), entry.path.clone()));
+
+        match selected {
+            Some((false, path)) => self.open_to_side(SideOpen::file(path), side),
+            Some((true, _)) => self.set_status_message("Select a file to open".to_string()),
+            None => {}
+        }
+    }
+
     pub fn file_explorer_refresh(&mut self) {
         let (selected_id, node_name) = if let Some(explorer) = &self.file_explorer {
             if let Some(selected_id) = explorer.get_selected() {
@@ -333,132 +363,175 @@ impl Editor {
             let result = runtime.block_on(tree.refresh_node(selected_id));
             match result {
                 Ok(()) => {
+                    // Its .gitignore may have changed too
+                    let dir = explorer
+                        .tree()
+                        .get_node(selected_id)
+                        .filter(|node| node.is_expanded())
+                        .map(|node| node.entry.path.clone());
+                    if let Some(dir) = dir {
+                        if let Err(e) = explorer.load_gitignore_for_dir(&dir) {
+                            tracing::warn!("Failed to load .gitignore from {:?}: {}", dir, e);
+                        }
+                    }
                     if let Some(name) = node_name {
                         self.set_status_message(format!("Refreshed: {}", name));
                     } else {
                         self.set_status_message("Refreshed".to_string());
                     }
+                    self.refresh_git_status();
                 }
                 Err(e) => {
-                    self.set_status_message(format!("Error refreshing: {}", e));
+                    self.set_status_error(format!("Error refreshing: {}", e));
                 }
             }
         }
     }
 
+    /// Ask for the name of a file to create in the selected directory (or
+    /// the selected file's directory)
     pub fn file_explorer_new_file(&mut self) {
-        if let Some(explorer) = &mut self.file_explorer {
-            if let Some(selected_id) = explorer.get_selected() {
-                let node = explorer.tree().get_node(selected_id);
-                if let Some(node) = node {
-                    let parent_path = get_parent_dir_path(node);
-                    let filename = format!("untitled_{}.txt", timestamp_suffix());
-                    let file_path = parent_path.join(&filename);
-
-                    if let Some(runtime) = &self.tokio_runtime {
-                        let path_clone = file_path.clone();
-                        let selected_id = selected_id;
-                        let result =
-                            runtime.block_on(async { tokio::fs::File::create(&path_clone).await });
-
-                        match result {
-                            Ok(_) => {
-                                let parent_id =
-                                    get_parent_node_id(explorer.tree(), selected_id, node.is_dir());
-                                let tree = explorer.tree_mut();
-                                let _ = runtime.block_on(tree.refresh_node(parent_id));
-                                self.set_status_message(format!("Created {}", filename));
-                            }
-                            Err(e) => {
-                                self.set_status_message(format!("Error creating file: {}", e));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        self.prompt_file_explorer_new(false);
     }
 
+    /// Ask for the name of a directory to create in the selected directory
+    /// (or the selected file's directory)
     pub fn file_explorer_new_directory(&mut self) {
-        if let Some(explorer) = &mut self.file_explorer {
-            if let Some(selected_id) = explorer.get_selected() {
-                let node = explorer.tree().get_node(selected_id);
-                if let Some(node) = node {
-                    let parent_path = get_parent_dir_path(node);
-                    let dirname = format!("New Folder {}", timestamp_suffix());
-                    let dir_path = parent_path.join(&dirname);
-
-                    if let Some(runtime) = &self.tokio_runtime {
-                        let path_clone = dir_path.clone();
-                        let dirname_clone = dirname.clone();
-                        let selected_id = selected_id;
-                        let result =
-                            runtime.block_on(async { tokio::fs::create_dir(&path_clone).await });
-
-                        match result {
-                            Ok(_) => {
-                                let parent_id =
-                                    get_parent_node_id(explorer.tree(), selected_id, node.is_dir());
-                                let tree = explorer.tree_mut();
-                                let _ = runtime.block_on(tree.refresh_node(parent_id));
-                                self.set_status_message(format!("Created {}", dirname_clone));
-
-                                // Enter rename mode for the new folder
-                                let prompt = crate::view::prompt::Prompt::with_initial_text(
-                                    "Rename to: ".to_string(),
-                                    crate::view::prompt::PromptType::FileExplorerRename {
-                                        original_path: path_clone,
-                                        original_name: dirname_clone,
-                                    },
-                                    dirname,
-                                );
-                                self.prompt = Some(prompt);
-                            }
-                            Err(e) => {
-                                self.set_status_message(format!("Error creating directory: {}", e));
-                            }
-                        }
-                    }
+        self.prompt_file_explorer_new(true);
+    }
+
+    fn prompt_file_explorer_new(&mut self, is_dir: bool) {
+        let Some(explorer) = &self.file_explorer else {
+            return;
+        };
+        let Some(node) = explorer
+            .get_selected()
+            .and_then(|id| explorer.tree().get_node(id))
+        else {
+            return;
+        };
+        let parent = get_parent_dir_path(node);
+        let (message, prompt_type) = if is_dir {
+            (
+                "New directory: ",
+                crate::view::prompt::PromptType::FileExplorerNewDirectory { parent },
+            )
+        } else {
+            (
+                "New file: ",
+                crate::view::prompt::PromptType::FileExplorerNewFile { parent },
+            )
+        };
+        self.prompt = Some(crate::view::prompt::Prompt::new(
+            message.to_string(),
+            prompt_type,
+        ));
+    }
+
+    /// Create the file or directory `name` (which may contain `/`) in
+    /// `parent` once its name was entered, and select it in the tree
+    pub(super) fn perform_file_explorer_create(&mut self, parent: &Path, name: &str, is_dir: bool) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.set_status_message("Cancelled".to_string());
+            return;
+        }
+        let path = parent.join(name);
+        if path.exists() {
+            self.set_status_error(format!("{} already exists", name));
+            return;
+        }
+
+        let (Some(runtime), Some(explorer)) = (&self.tokio_runtime, &mut self.file_explorer) else {
+            return;
+        };
+        let result = runtime.block_on(async {
+            if is_dir {
+                tokio::fs::create_dir_all(&path).await
+            } else {
+                if let Some(dir) = path.parent() {
+                    tokio::fs::create_dir_all(dir).await?;
                 }
+                tokio::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&path)
+                    .await
+                    .map(|_| ())
+            }
+        });
+
+        match result {
+            Ok(()) => {
+                let parent_id = explorer
+                    .tree()
+                    .get_node_by_path(parent)
+                    .map(|node| node.id)
+                    .unwrap_or_else(|| explorer.tree().root_id());
+                let tree = explorer.tree_mut();
+                let _ = runtime.block_on(tree.refresh_node(parent_id));
+                // Names with a `/` created directories to expand on the way
+                let _ = runtime.block_on(tree.expand_to_path(&path));
+                explorer.load_expanded_gitignores();
+                explorer.navigate_to_path(&path);
+                self.set_status_message(format!("Created {}", name));
+                self.refresh_git_status();
+            }
+            Err(e) => {
+                let what = if is_dir { "directory" } else { "file" };
+                self.set_status_error(format!("Error creating {}: {}", what, e));
             }
         }
     }
 
+    /// Ask for confirmation, then delete the selected file or directory
     pub fn file_explorer_delete(&mut self) {
-        if let Some(explorer) = &mut self.file_explorer {
+        if let Some(explorer) = &self.file_explorer {
             if let Some(selected_id) = explorer.get_selected() {
                 // Don't allow deleting the root directory
                 if selected_id == explorer.tree().root_id() {
-                    self.set_status_message("Cannot delete project root".to_string());
+                    self.set_status_warning("Cannot delete project root".to_string());
                     return;
                 }
 
-                let node = explorer.tree().get_node(selected_id);
-                if let Some(node) = node {
+                if let Some(node) = explorer.tree().get_node(selected_id) {
                     let path = node.entry.path.clone();
-                    let name = node.entry.name.clone();
+                    let is_dir = node.is_dir();
+                    self.confirm_delete(path, is_dir);
+                }
+            }
+        }
+    }
 
-                    if let Some(runtime) = &self.tokio_runtime {
-                        let result = if node.is_dir() {
-                            runtime.block_on(async { tokio::fs::remove_dir_all(&path).await })
-                        } else {
-                            runtime.block_on(async { tokio::fs::remove_file(&path).await })
-                        };
+    /// Delete a file or directory shown in the file explorer (once confirmed)
+    pub(super) fn perform_file_explorer_delete(&mut self, path: &Path, is_dir: bool) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
 
-                        match result {
-                            Ok(_) => {
-                                // For delete, always get the parent (the deleted item can't be refreshed)
-                                let parent_id =
-                                    get_parent_node_id(explorer.tree(), selected_id, false);
-                                let tree = explorer.tree_mut();
-                                let _ = runtime.block_on(tree.refresh_node(parent_id));
-                                self.set_status_message(format!("Deleted {}", name));
-                            }
-                            Err(e) => {
-                                self.set_status_message(format!("Error deleting: {}", e));
-                            }
-                        }
-                    }
+        if let (Some(explorer), Some(runtime)) = (&mut self.file_explorer, &self.tokio_runtime) {
+            let result = if is_dir {
+                runtime.block_on(async { tokio::fs::remove_dir_all(path).await })
+            } else {
+                runtime.block_on(async { tokio::fs::remove_file(path).await })
+            };
+
+            match result {
+                Ok(_) => {
+                    // The deleted item can't be refreshed, so refresh its parent
+                    let parent_id = path
+                        .parent()
+                        .and_then(|parent| explorer.tree().get_node_by_path(parent))
+                        .map(|parent| parent.id)
+                        .unwrap_or_else(|| explorer.tree().root_id());
+                    let tree = explorer.tree_mut();
+                    let _ = runtime.block_on(tree.refresh_node(parent_id));
+                    self.set_status_message(format!("Deleted {}", name));
+                    self.refresh_git_status();
+                }
+                Err(e) => {
+                    self.set_status_error(format!("Error deleting: {}", e));
                 }
             }
         }
@@ -469,7 +542,7 @@ impl Editor {
             if let Some(selected_id) = explorer.get_selected() {
                 // Don't allow renaming the root directory
                 if selected_id == explorer.tree().root_id() {
-                    self.set_status_message("Cannot rename project root".to_string());
+                    self.set_status_warning("Cannot rename project root".to_string());
                     return;
                 }
 
@@ -526,10 +599,30 @@ impl Editor {
                         // Navigate to the renamed file to restore selection
                         explorer.navigate_to_path(&new_path);
                     }
+
+                    // Rebind buffers of the renamed file (or of files inside a renamed directory)
+                    let renamed: Vec<(BufferId, PathBuf)> = self
+                        .buffer_metadata
+                        .iter()
+                        .filter_map(|(&id, metadata)| {
+                            let path = metadata.file_path()?;
+                            let rest = path.strip_prefix(&original_path).ok()?;
+                            if rest.as_os_str().is_empty() {
+                                Some((id, new_path.clone()))
+                            } else {
+                                Some((id, new_path.join(rest)))
+                            }
+                        })
+                        .collect();
+                    for (buffer_id, path) in renamed {
+                        self.rename_buffer(buffer_id, path);
+                    }
+
                     self.set_status_message(format!("Renamed {} to {}", original_name, new_name));
+                    self.refresh_git_status();
                 }
                 Err(e) => {
-                    self.set_status_message(format!("Error renaming: {}", e));
+                    self.set_status_error(format!("Error renaming: {}", e));
                 }
             }
         }
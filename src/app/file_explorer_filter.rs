@@ -0,0 +1,103 @@
+//! Filtering the file explorer as you type
+//!
+//! "/" in the file explorer asks for a query and, with each key typed,
+//! narrows the tree to the files and directories whose names fuzzy-match it
+//! (their paths, once the query has a `/`), expanding the directories holding
+//! them. Enter keeps the tree narrowed to browse the matches; Escape, in the
+//! prompt or then in the explorer, shows the whole tree again. Matches come
+//! from the project's scanned files (see `project_scan`).
+
+use super::project_scan::ProjectScanUser;
+use super::Editor;
+use crate::view::prompt::PromptType;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Most entries shown for a query, to keep expanding their directories quick
+const MAX_FILTER_MATCHES: usize = 500;
+
+impl Editor {
+    /// Ask for a query narrowing the file explorer as it is typed
+    pub fn file_explorer_filter(&mut self) {
+        let Some(explorer) = &self.file_explorer else {
+            return;
+        };
+        let query = explorer.filter_query().unwrap_or_default().to_string();
+        self.start_prompt_with_initial_text(
+            "Filter files: ".to_string(),
+            PromptType::FileExplorerFilter,
+            query,
+        );
+        self.update_file_explorer_filter();
+    }
+
+    /// Narrow the tree to the entries matching the filter prompt's query
+    pub(super) fn update_file_explorer_filter(&mut self) {
+        let Some(query) = self
+            .prompt
+            .as_ref()
+            .filter(|prompt| prompt.prompt_type == PromptType::FileExplorerFilter)
+            .map(|prompt| prompt.input.clone())
+        else {
+            return;
+        };
+        if query.is_empty() {
+            self.clear_file_explorer_filter();
+            return;
+        }
+        let Some(scan) = self.project_files_for(ProjectScanUser::FileExplorerFilter) else {
+            self.set_status_message("Scanning files...".to_string());
+            return;
+        };
+
+        let matcher = self.config.editor.fuzzy_matcher.matcher();
+        let by_path = query.contains('/');
+        let matches: HashSet<PathBuf> = scan
+            .files
+            .iter()
+            .chain(&scan.dirs)
+            .filter(|relative| {
+                let text = if by_path {
+                    relative.to_string_lossy()
+                } else {
+                    // The root is "" and has no name
+                    match relative.file_name() {
+                        Some(name) => name.to_string_lossy(),
+                        None => return false,
+                    }
+                };
+                matcher.fuzzy_match(&query, &text).matched
+            })
+            .take(MAX_FILTER_MATCHES)
+            .map(|relative| self.working_dir.join(relative))
+            .collect();
+
+        let (Some(runtime), Some(explorer)) = (&self.tokio_runtime, &mut self.file_explorer) else {
+            return;
+        };
+        runtime.block_on(async {
+            for path in &matches {
+                let _ = explorer.tree_mut().expand_to_path(path).await;
+            }
+        });
+        explorer.load_expanded_gitignores();
+        let count = matches.len();
+        explorer.set_filter(query, matches);
+        self.set_status_message(match count {
+            0 => "No matching files".to_string(),
+            MAX_FILTER_MATCHES => format!("First {} matches", count),
+            _ => format!("{} matches", count),
+        });
+    }
+
+    /// Show the whole tree again if it is filtered; returns whether it was
+    pub(super) fn clear_file_explorer_filter(&mut self) -> bool {
+        match &mut self.file_explorer {
+            Some(explorer) if explorer.filter_query().is_some() => {
+                explorer.clear_filter();
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Project include/exclude globs
+//!
+//! The filter combines the `files` section of the config with the project's
+//! `.fresh/project.toml`. The explorer hides what it leaves out, and plugins
+//! (file finder, project search) read it from the state snapshot.
+
+use super::Editor;
+use crate::services::file_filter::{self, FileFilter};
+use std::sync::Arc;
+
+impl Editor {
+    /// Load the include/exclude globs for the working directory
+    ///
+    /// On error the previous filter is kept.
+    pub(super) fn load_file_filter(&mut self) -> Result<(), String> {
+        let project = file_filter::load_project_files(&self.working_dir)?;
+        let filter = FileFilter::new(&self.working_dir, &self.config.files, &project)?;
+        if let Some(explorer) = &mut self.file_explorer {
+            explorer.set_file_filter(filter.clone());
+        }
+        self.file_filter = Arc::new(filter);
+        if self.project_scan.is_some() {
+            self.rescan_project();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//! Advisory file locks (`editor.lock_files`)
+//!
+//! Files are locked when they are opened and unlocked when their last buffer
+//! is closed or the editor exits. A file locked by another editor opens
+//! read-only, and a dialog offers to keep it that way, steal the lock or close
+//! the buffer. See [`crate::services::file_lock`] for the lock files.
+
+use super::confirm_dialog::ConfirmAction;
+use super::Editor;
+use crate::model::event::BufferId;
+use crate::services::file_lock::{self, LockHolder};
+use crate::view::confirm_dialog::{ConfirmChoice, ConfirmDialog, DialogButton};
+use std::path::{Path, PathBuf};
+
+impl Editor {
+    /// Lock a newly opened file, or open it read-only if it is locked elsewhere
+    pub(super) fn check_file_lock(&mut self, buffer_id: BufferId, path: &Path) {
+        if !self.config.editor.lock_files {
+            return;
+        }
+        let Some(holder) = file_lock::find_lock_holder(path) else {
+            self.lock_file(path);
+            return;
+        };
+
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.editing_disabled = true;
+        }
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+            metadata.read_only = true;
+        }
+
+        let name = self.get_buffer_display_name(buffer_id);
+        if self.confirm_dialog.is_some() {
+            // Don't replace a dialog that is waiting for an answer
+            self.set_status_warning(format!(
+                "{} is being edited by {}; opened read-only",
+                name,
+                holder.describe()
+            ));
+            return;
+        }
+        self.confirm_locked_file(buffer_id, path.to_path_buf(), &holder);
+    }
+
+    fn confirm_locked_file(&mut self, buffer_id: BufferId, path: PathBuf, holder: &LockHolder) {
+        let name = self.get_buffer_display_name(buffer_id);
+        let dialog = ConfirmDialog::new(
+            "File Locked",
+            format!(
+                "'{}' is being edited by {}. It was opened read-only.",
+                name,
+                holder.describe()
+            ),
+            vec![
+                DialogButton::new("Read-only", 'r', ConfirmChoice::Cancel),
+                DialogButton::new("Steal Lock", 's', ConfirmChoice::Yes),
+                DialogButton::new("Close", 'c', ConfirmChoice::No),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::LockedFile { buffer_id, path });
+    }
+
+    /// Take over the lock of a file opened read-only and make it editable
+    pub(super) fn steal_file_lock(&mut self, buffer_id: BufferId, path: &Path) {
+        if !self.lock_file(path) {
+            self.set_status_error(format!("Failed to lock {}", path.display()));
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.editing_disabled = false;
+        }
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+            metadata.read_only = false;
+        }
+        self.set_status_warning(format!(
+            "Took over the lock of {}; the other editor may still save it",
+            self.get_buffer_display_name(buffer_id)
+        ));
+    }
+
+    /// Lock `path` for this editor; returns false if the lock file can't be written
+    fn lock_file(&mut self, path: &Path) -> bool {
+        match file_lock::acquire(path) {
+            Ok(()) => {
+                self.file_locks.insert(path.to_path_buf());
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Failed to lock {:?}: {}", path, e);
+                false
+            }
+        }
+    }
+
+    /// Unlock `path` unless another buffer still edits it
+    pub(super) fn release_file_lock(&mut self, path: &Path) {
+        let still_open = self
+            .buffers
+            .values()
+            .any(|state| state.buffer.file_path() == Some(path));
+        if !still_open && self.file_locks.remove(path) {
+            file_lock::release(path);
+        }
+    }
+
+    /// Move this editor's lock from `old_path` to `new_path` (after a rename)
+    pub(super) fn move_file_lock(&mut self, old_path: &Path, new_path: &Path) {
+        if self.file_locks.remove(old_path) {
+            file_lock::release(old_path);
+            self.lock_file(new_path);
+        }
+    }
+
+    /// Unlock every file this editor locked (when exiting)
+    pub fn release_file_locks(&mut self) {
+        for path in self.file_locks.drain() {
+            file_lock::release(&path);
+        }
+    }
+}
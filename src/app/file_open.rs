@@ -4,7 +4,7 @@
 //! It renders a structured popup above the prompt with sortable columns,
 //! navigation shortcuts, and filtering.
 
-use crate::input::fuzzy::fuzzy_match;
+use crate::config::FuzzyMatcherKind;
 use crate::services::fs::{FsEntry, FsEntryType};
 use std::cmp::Ordering;
 use std::path::PathBuf;
@@ -93,6 +93,9 @@ pub struct FileOpenState {
 
     /// Whether to show hidden files
     pub show_hidden: bool,
+
+    /// Matcher the filter uses
+    pub fuzzy_matcher: FuzzyMatcherKind,
 }
 
 impl FileOpenState {
@@ -113,6 +116,7 @@ impl FileOpenState {
             shortcuts,
             selected_shortcut: 0,
             show_hidden: false,
+            fuzzy_matcher: FuzzyMatcherKind::default(),
         }
     }
 
@@ -305,7 +309,10 @@ impl FileOpenState {
                 entry.matches_filter = true;
                 entry.match_score = 0;
             } else {
-                let result = fuzzy_match(&self.filter, &entry.fs_entry.name);
+                let result = self
+                    .fuzzy_matcher
+                    .matcher()
+                    .fuzzy_match(&self.filter, &entry.fs_entry.name);
                 entry.matches_filter = result.matched;
                 entry.match_score = result.score;
             }
@@ -4,8 +4,9 @@
 //! browser popup when the Open File prompt is active.
 
 use super::file_open::{FileOpenSection, SortMode};
-use super::Editor;
+use super::{normalize_path, Editor};
 use crate::input::keybindings::Action;
+use crate::input::path_completion::{expand_path, is_rooted, resolve_path, split_last_component};
 use crate::view::prompt::PromptType;
 
 impl Editor {
@@ -28,14 +29,19 @@ impl Editor {
         match action {
             // Navigation actions - Up/Down in file list
             Action::PromptSelectPrev => {
-                if let Some(state) = &mut self.file_open_state {
-                    state.select_prev();
+                // Up on an empty filter recalls previously opened files
+                if !self.navigate_prompt_history(true) {
+                    if let Some(state) = &mut self.file_open_state {
+                        state.select_prev();
+                    }
                 }
                 true
             }
             Action::PromptSelectNext => {
-                if let Some(state) = &mut self.file_open_state {
-                    state.select_next();
+                if !self.navigate_prompt_history(false) {
+                    if let Some(state) = &mut self.file_open_state {
+                        state.select_next();
+                    }
                 }
                 true
             }
@@ -51,6 +57,18 @@ impl Editor {
                 }
                 true
             }
+            Action::PromptSelectFirst => {
+                if let Some(state) = &mut self.file_open_state {
+                    state.select_first();
+                }
+                true
+            }
+            Action::PromptSelectLast => {
+                if let Some(state) = &mut self.file_open_state {
+                    state.select_last();
+                }
+                true
+            }
             // Let Home/End pass through to normal prompt cursor handling
             // PromptMoveStart and PromptMoveEnd are NOT intercepted here
 
@@ -62,6 +80,10 @@ impl Editor {
 
             // Tab to autocomplete to selected item (and navigate into dir if it's a directory)
             Action::PromptAcceptSuggestion => {
+                if self.file_open_complete_rooted_path() {
+                    return true;
+                }
+
                 // Get the selected entry info
                 let selected_info = self.file_open_state.as_ref().and_then(|s| {
                     s.selected_index
@@ -135,18 +157,10 @@ impl Editor {
             .map(|p| p.input.clone())
             .unwrap_or_default();
 
-        // If the input looks like an absolute path, try to open/navigate to it directly
-        if prompt_input.starts_with('/') || prompt_input.starts_with('~') {
-            let expanded_path = if prompt_input.starts_with('~') {
-                if let Some(home) = dirs::home_dir() {
-                    home.join(&prompt_input[1..].trim_start_matches('/'))
-                } else {
-                    std::path::PathBuf::from(&prompt_input)
-                }
-            } else {
-                std::path::PathBuf::from(&prompt_input)
-            };
-
+        // If the input is a path of its own (e.g., "/etc/hosts", "~/notes", "$HOME/src"),
+        // try to open/navigate to it directly
+        let expanded_path = std::path::PathBuf::from(expand_path(&prompt_input));
+        if is_rooted(&prompt_input) && expanded_path.is_absolute() {
             if expanded_path.is_dir() {
                 self.file_open_navigate_to(expanded_path);
                 return;
@@ -157,6 +171,24 @@ impl Editor {
             }
         }
 
+        // A path below the directory being browsed (e.g., "src/components")
+        // navigates there or opens the file directly
+        if prompt_input.contains('/') {
+            let path = self
+                .file_open_state
+                .as_ref()
+                .map(|s| normalize_path(&s.current_dir.join(&prompt_input)));
+            if let Some(path) = path {
+                if path.is_dir() {
+                    self.file_open_navigate_to(path);
+                    return;
+                } else if path.is_file() {
+                    self.file_open_open_file(path);
+                    return;
+                }
+            }
+        }
+
         // Otherwise, use the selected entry from the file list
         let (path, is_dir) = {
             let state = match &self.file_open_state {
@@ -181,13 +213,48 @@ impl Editor {
         }
     }
 
+    /// Complete a path typed from the root or home directory (e.g., "~/Doc")
+    ///
+    /// Navigates to the directory part and keeps the rest as the filter.
+    /// Returns false if the input isn't such a path.
+    fn file_open_complete_rooted_path(&mut self) -> bool {
+        let input = match &self.prompt {
+            Some(prompt) if is_rooted(&prompt.input) => prompt.input.clone(),
+            _ => return false,
+        };
+
+        let whole = resolve_path(&input, &self.working_dir);
+        let (dir, filter) = if whole.is_dir() {
+            (whole, "")
+        } else {
+            let (dir_part, filter) = split_last_component(&input);
+            (resolve_path(dir_part, &self.working_dir), filter)
+        };
+        if !dir.is_absolute() || !dir.is_dir() {
+            return false;
+        }
+
+        self.file_open_navigate_to(dir);
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.input = filter.to_string();
+            prompt.cursor_pos = prompt.input.len();
+        }
+        if let Some(state) = &mut self.file_open_state {
+            state.filter = filter.to_string();
+        }
+        true
+    }
+
     /// Navigate to a directory in the file browser
     fn file_open_navigate_to(&mut self, path: std::path::PathBuf) {
-        // Clear prompt input
+        // Clear prompt input and the filter it set
         if let Some(prompt) = self.prompt.as_mut() {
             prompt.input.clear();
             prompt.cursor_pos = 0;
         }
+        if let Some(state) = &mut self.file_open_state {
+            state.filter.clear();
+        }
 
         // Load the new directory
         self.load_file_open_directory(path);
@@ -198,10 +265,11 @@ impl Editor {
         // Close the file browser
         self.file_open_state = None;
         self.prompt = None;
+        self.record_prompt_input(&PromptType::OpenFile, &path.to_string_lossy());
 
         // Open the file
         if let Err(e) = self.open_file(&path) {
-            self.set_status_message(format!("Error opening file: {}", e));
+            self.set_status_error(format!("Error opening file: {}", e));
         } else {
             self.set_status_message(format!("Opened {}", path.display()));
         }
@@ -253,13 +321,13 @@ impl Editor {
 
                 // If no exact match, try fuzzy match - navigate to best matching directory
                 let best_match_dir = self.file_open_state.as_ref().and_then(|state| {
-                    use crate::input::fuzzy::fuzzy_match;
+                    let matcher = state.fuzzy_matcher.matcher();
                     state
                         .entries
                         .iter()
                         .filter(|e| e.fs_entry.is_dir() && e.fs_entry.name != "..")
                         .filter_map(|e| {
-                            let result = fuzzy_match(dir_name, &e.fs_entry.name);
+                            let result = matcher.fuzzy_match(dir_name, &e.fs_entry.name);
                             if result.matched {
                                 Some((e, result.score))
                             } else {
@@ -0,0 +1,167 @@
+//! Focus mode for writing prose
+//!
+//! "Toggle Focus Mode" dims the active buffer's text except the paragraph
+//! around the cursor (the lines between blank lines), or only the sentence
+//! with `editor.focus_mode_unit` set to "sentence". The focused text follows
+//! the cursor, and goes well with line wrapping and typewriter scrolling.
+
+use super::Editor;
+use crate::config::FocusUnit;
+use crate::model::event::BufferId;
+use crate::view::overlay::{Overlay, OverlayFace, OverlayNamespace};
+use std::ops::Range;
+
+/// Namespace of the overlays dimming the text out of focus
+const FOCUS_NAMESPACE: &str = "focus-mode";
+
+/// Focus mode, while on
+#[derive(Debug, Default)]
+pub(crate) struct FocusMode {
+    /// Buffer dimmed, focused range and the buffer's length when dimmed
+    dimmed: Option<(BufferId, Range<usize>, usize)>,
+}
+
+impl Editor {
+    /// Turn focus mode on or off
+    pub fn toggle_focus_mode(&mut self) {
+        if let Some(focus) = self.focus_mode.take() {
+            if let Some((buffer_id, _, _)) = focus.dimmed {
+                self.clear_focus_dimming(buffer_id);
+            }
+            self.set_status_message("Focus mode off".to_string());
+        } else {
+            self.focus_mode = Some(FocusMode::default());
+            self.update_focus_mode();
+            self.set_status_message("Focus mode on".to_string());
+        }
+    }
+
+    /// Dim the active buffer's text outside the paragraph or sentence at
+    /// the cursor, if focus mode is on and the cursor left the focused text
+    pub(super) fn update_focus_mode(&mut self) {
+        let Some(previous) = self.focus_mode.as_ref().map(|focus| focus.dimmed.clone()) else {
+            return;
+        };
+        let buffer_id = self.active_buffer;
+        let unit = self.config.editor.focus_mode_unit;
+        let state = self.active_state_mut();
+        let len = state.buffer.len();
+        let cursor = state.cursors.primary().position;
+        let mut range = paragraph_at(&mut state.buffer, cursor);
+        if unit == FocusUnit::Sentence {
+            let text = state.get_text_range(range.start, range.end);
+            let sentence = sentence_at(&text, cursor - range.start);
+            range = range.start + sentence.start..range.start + sentence.end;
+        }
+
+        let dimmed = Some((buffer_id, range.clone(), len));
+        if previous == dimmed {
+            return;
+        }
+        if let Some((previous_buffer, _, _)) = previous {
+            self.clear_focus_dimming(previous_buffer);
+        }
+
+        let namespace = OverlayNamespace::from_string(FOCUS_NAMESPACE.to_string());
+        let color = self.theme.line_number_fg;
+        let state = self.active_state_mut();
+        for dim in [0..range.start, range.end..len] {
+            if dim.is_empty() {
+                continue;
+            }
+            let overlay = Overlay::with_namespace(
+                &mut state.marker_list,
+                dim,
+                OverlayFace::Foreground { color },
+                namespace.clone(),
+            )
+            .with_priority_value(5);
+            state.overlays.add(overlay);
+        }
+        if let Some(focus) = self.focus_mode.as_mut() {
+            focus.dimmed = dimmed;
+        }
+    }
+
+    fn clear_focus_dimming(&mut self, buffer_id: BufferId) {
+        let namespace = OverlayNamespace::from_string(FOCUS_NAMESPACE.to_string());
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state
+                .overlays
+                .clear_namespace(&namespace, &mut state.marker_list);
+        }
+    }
+}
+
+/// The lines around `position` up to the blank lines before and after, or
+/// just its line if that is blank
+fn paragraph_at(buffer: &mut crate::model::buffer::Buffer, position: usize) -> Range<usize> {
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    let mut iter = buffer.line_iterator(position, 80);
+    let line_start = iter.current_position();
+    let Some((_, line)) = iter.next() else {
+        // The empty line after a final newline
+        return line_start..line_start;
+    };
+    if is_blank(&line) {
+        return line_start..line_start + line.len();
+    }
+    let mut end = line_start + line.len();
+    while let Some((start, line)) = iter.next() {
+        if is_blank(&line) {
+            break;
+        }
+        end = start + line.len();
+    }
+
+    let mut iter = buffer.line_iterator(line_start, 80);
+    let mut start = line_start;
+    while let Some((previous, line)) = iter.prev() {
+        if is_blank(&line) {
+            break;
+        }
+        start = previous;
+    }
+    start..end
+}
+
+/// The sentence of `text` around byte `offset`: from after the last `.`,
+/// `!` or `?` followed by whitespace (or the start) to the next one (or the
+/// end). A sentence ending right before `offset` is still the current one.
+fn sentence_at(text: &str, offset: usize) -> Range<usize> {
+    let bytes = text.as_bytes();
+    let is_end = |i: usize| {
+        matches!(bytes[i], b'.' | b'!' | b'?')
+            && bytes.get(i + 1).is_none_or(|b| b.is_ascii_whitespace())
+    };
+    let offset = offset.min(bytes.len());
+
+    let mut start = (0..offset.saturating_sub(1))
+        .rev()
+        .find(|&i| is_end(i))
+        .map_or(0, |i| i + 1);
+    while start < bytes.len() && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    let end = (start.max(offset.saturating_sub(1))..bytes.len())
+        .find(|&i| is_end(i))
+        .map_or(bytes.len(), |i| i + 1);
+    start..end.max(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentence_at() {
+        let text = "One two. Three four! Pi is 3.14? Five";
+        assert_eq!(sentence_at(text, 2), 0..8);
+        // Right after its period, the sentence is still the current one
+        assert_eq!(sentence_at(text, 8), 0..8);
+        assert_eq!(sentence_at(text, 10), 9..20);
+        assert_eq!(sentence_at(text, 25), 21..32);
+        assert_eq!(sentence_at(text, text.len()), 33..text.len());
+    }
+}
@@ -0,0 +1,48 @@
+//! Git status badges in the file explorer
+//!
+//! While the file explorer is open the git status of the project's files
+//! (see `services::git_status`) is read in the background: when the explorer
+//! opens or is refreshed, and after saves and file operations. A read asked
+//! for while one runs starts again once it is done.
+
+use super::Editor;
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::git_status::{self, GitStatuses};
+
+impl Editor {
+    /// Read the git status of the project's files again, if the file
+    /// explorer shows them
+    pub(super) fn refresh_git_status(&mut self) {
+        if self.file_explorer.is_none() {
+            return;
+        }
+        if self
+            .git_status_task
+            .is_some_and(|task| self.is_task_running(task))
+        {
+            self.git_status_outdated = true;
+            return;
+        }
+        self.git_status_outdated = false;
+
+        let root = self.working_dir.clone();
+        self.git_status_task =
+            self.spawn_task("Reading git status".to_string(), move |_| async move {
+                let statuses =
+                    tokio::task::spawn_blocking(move || git_status::read_git_status(&root))
+                        .await
+                        .unwrap_or_default();
+                AsyncMessage::GitStatusRead { statuses }
+            });
+    }
+
+    /// Take the git status read in the background
+    pub(super) fn handle_git_status_read(&mut self, statuses: Option<GitStatuses>) {
+        self.git_status_task = None;
+        self.git_statuses = statuses;
+        if self.git_status_outdated {
+            // Files changed while it was read
+            self.refresh_git_status();
+        }
+    }
+}
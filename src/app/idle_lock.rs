@@ -0,0 +1,142 @@
+//! Locking the screen after a while without input
+//!
+//! With `editor.idle_lock_minutes` set, the screen is blanked with a lock
+//! message once no key, mouse or paste input has arrived for that long;
+//! "Lock Screen" locks it at once. Any key unlocks it, unless
+//! `editor.idle_lock_passphrase` is set: then the passphrase has to be
+//! typed, followed by Enter. Input while locked goes nowhere else.
+
+use super::Editor;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph};
+use ratatui::Frame;
+use std::time::{Duration, Instant};
+
+/// The screen while locked
+#[derive(Debug, Default)]
+pub(crate) struct IdleLock {
+    /// Passphrase typed so far
+    typed: String,
+    /// Whether the last passphrase entered was wrong
+    wrong: bool,
+}
+
+impl Editor {
+    /// Note input from the user. Returns true if the screen is locked, in
+    /// which case the input goes to the lock instead.
+    pub(super) fn note_user_input(&mut self) -> bool {
+        self.session_stats
+            .note_input(self.last_user_input.elapsed());
+        self.last_user_input = Instant::now();
+        self.idle_lock.is_some()
+    }
+
+    /// Lock the screen until a key (or the passphrase) is entered
+    pub fn lock_screen(&mut self) {
+        self.idle_lock = Some(IdleLock::default());
+    }
+
+    /// Lock the screen once there has been no input for
+    /// `editor.idle_lock_minutes`. Returns true if the screen needs to be
+    /// redrawn.
+    pub fn check_idle_lock(&mut self) -> bool {
+        let minutes = self.config.editor.idle_lock_minutes;
+        if minutes == 0 || self.idle_lock.is_some() {
+            return false;
+        }
+        if self.last_user_input.elapsed() < Duration::from_secs(minutes * 60) {
+            return false;
+        }
+        self.lock_screen();
+        true
+    }
+
+    /// Handle a key while the screen is locked
+    pub(super) fn handle_idle_lock_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(passphrase) = self
+            .config
+            .editor
+            .idle_lock_passphrase
+            .clone()
+            .filter(|p| !p.is_empty())
+        else {
+            self.unlock_screen();
+            return;
+        };
+        let Some(lock) = self.idle_lock.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Enter if lock.typed == passphrase => self.unlock_screen(),
+            KeyCode::Enter => {
+                lock.typed.clear();
+                lock.wrong = true;
+            }
+            KeyCode::Backspace => {
+                lock.typed.pop();
+            }
+            KeyCode::Esc => lock.typed.clear(),
+            KeyCode::Char(c)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                lock.typed.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn unlock_screen(&mut self) {
+        self.idle_lock = None;
+        self.last_user_input = Instant::now();
+    }
+
+    /// Draw the lock screen over everything, if locked. Returns true if it
+    /// was drawn.
+    pub(super) fn render_idle_lock(&self, frame: &mut Frame) -> bool {
+        let Some(lock) = &self.idle_lock else {
+            return false;
+        };
+        let area = frame.area();
+        let style = Style::default()
+            .fg(self.theme.editor_fg)
+            .bg(self.theme.editor_bg);
+
+        let mut lines = vec![
+            Line::styled("Locked", style.add_modifier(Modifier::BOLD)),
+            Line::raw(""),
+        ];
+        if self
+            .config
+            .editor
+            .idle_lock_passphrase
+            .as_ref()
+            .is_some_and(|p| !p.is_empty())
+        {
+            lines.push(Line::raw("Type the passphrase and press Enter"));
+            lines.push(Line::raw("•".repeat(lock.typed.chars().count())));
+            if lock.wrong {
+                lines.push(Line::styled(
+                    "Wrong passphrase",
+                    style.fg(self.theme.diagnostic_error_fg),
+                ));
+            }
+        } else {
+            lines.push(Line::raw("Press any key to unlock"));
+        }
+
+        let rows = lines.len() as u16;
+        let top = area.height.saturating_sub(rows) / 2;
+        frame.render_widget(Clear, area);
+        frame.render_widget(Block::default().style(style), area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .style(style)
+                .alignment(Alignment::Center),
+            Rect::new(area.x, area.y + top, area.width, area.height - top),
+        );
+        true
+    }
+}
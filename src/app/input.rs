@@ -1,22 +1,81 @@
 use super::normalize_path;
+use super::open_to_side::OpenSide;
 use super::*;
+use crate::input::actions::{surround_selection_events, track_auto_closed};
+use crate::input::path_completion::resolve_path;
+use crate::input::when_clause::WhenContext;
 use crate::services::plugins::hooks::HookArgs;
+use crate::view::export::ExportFormat;
+use crate::view::prompt::SuggestionRow;
+use crate::view::ui::context_keys;
 impl Editor {
-    /// Determine the current keybinding context based on UI state
-    pub(super) fn get_key_context(&self) -> crate::input::keybindings::KeyContext {
-        use crate::input::keybindings::KeyContext;
-
-        // Priority order: Menu > Prompt > Popup > Rename > Current context (FileExplorer or Normal)
+    /// Determine the current keybinding contexts based on UI state, from the
+    /// editor to the innermost UI (e.g., Normal > FileExplorer > Prompt)
+    pub(super) fn key_context_stack(&self) -> KeyContextStack {
+        // Nesting order: Normal < Vim mode or FileExplorer < Popup < Prompt < Menu
+        let mut stack = KeyContextStack::new(KeyContext::Normal);
+        if self.key_context == KeyContext::FileExplorer {
+            stack.push(KeyContext::FileExplorer);
+        } else if self.config.editor.vim_mode {
+            if let Some(vim_context) = self.vim.mode().key_context() {
+                stack.push(vim_context);
+            }
+        }
+        if self.active_state().popups.is_visible() {
+            stack.push(KeyContext::Popup);
+        }
+        if self.is_prompting() {
+            stack.push(KeyContext::Prompt);
+        }
         if self.menu_state.active_menu.is_some() {
-            KeyContext::Menu
-        } else if self.is_prompting() {
-            KeyContext::Prompt
-        } else if self.active_state().popups.is_visible() {
-            KeyContext::Popup
-        } else {
-            // Use the current context (can be FileExplorer or Normal)
-            self.key_context
+            stack.push(KeyContext::Menu);
+        }
+        stack.set_language(self.active_language());
+        stack
+    }
+
+    /// The detected language of the active buffer's file
+    pub(super) fn active_language(&self) -> Option<String> {
+        let path = self.buffer_metadata.get(&self.active_buffer)?.file_path()?;
+        detect_language(path)
+    }
+
+    /// Contexts commands from the palette run in: everything beneath the
+    /// palette's own prompt
+    pub(super) fn command_context_stack(&self) -> KeyContextStack {
+        let mut stack = self.key_context_stack();
+        if self.is_prompting() {
+            stack.pop();
         }
+        stack
+    }
+
+    /// Editor state that commands' when clauses are evaluated against
+    pub(super) fn when_context(&self) -> WhenContext {
+        let state = self.active_state();
+        let path = self
+            .buffer_metadata
+            .get(&self.active_buffer)
+            .and_then(|meta| meta.file_path());
+
+        // Keys shared with menu conditions have the same meaning
+        let mut context = WhenContext::new();
+        context.set_flag(context_keys::HAS_SELECTION, self.has_active_selection());
+        context.set_flag("buffer_modified", state.buffer.is_modified());
+        context.set_flag("read_only", self.is_active_buffer_read_only());
+        context.set_flag("has_file", path.is_some());
+        context.set_flag("multiple_cursors", state.cursors.count() > 1);
+        context.set_flag(
+            "multiple_splits",
+            self.split_manager.root().count_leaves() > 1,
+        );
+        context.set_flag(context_keys::FILE_EXPLORER, self.file_explorer.is_some());
+        context.set_flag("running_task", !self.running_tasks.is_empty());
+        context.set_flag("recording_macro", self.macro_recording.is_some());
+        if let Some(language) = self.active_language() {
+            context.set("language", language);
+        }
+        context
     }
 
     /// Handle a key event and return whether it was handled
@@ -25,6 +84,36 @@ impl Editor {
         &mut self,
         code: crossterm::event::KeyCode,
         modifiers: crossterm::event::KeyModifiers,
+    ) -> std::io::Result<()> {
+        if self.note_user_input() {
+            self.handle_idle_lock_key(code, modifiers);
+            return Ok(());
+        }
+
+        // Keys are recorded once handled, so the key that stops a recording
+        // isn't part of it
+        let recording = self.macro_recording.as_ref().map(|state| {
+            (
+                state.key,
+                (state.recorded.keys.len(), state.recorded.actions.len()),
+            )
+        });
+        let prompt_was_open = self.prompt.is_some();
+        self.trace_key_received(code, modifiers);
+
+        let result = self.dispatch_key(code, modifiers);
+
+        if let Some((register, marks)) = recording {
+            self.record_macro_key(code, modifiers, register, marks, prompt_was_open);
+        }
+        self.latency.key_handled();
+        result
+    }
+
+    fn dispatch_key(
+        &mut self,
+        code: crossterm::event::KeyCode,
+        modifiers: crossterm::event::KeyModifiers,
     ) -> std::io::Result<()> {
         use crate::input::keybindings::Action;
 
@@ -36,8 +125,47 @@ impl Editor {
             modifiers
         );
 
+        // A confirmation dialog takes all keys until it is answered
+        if self.confirm_dialog.is_some() {
+            self.handle_confirm_dialog_key(code, modifiers);
+            return Ok(());
+        }
+
+        // So does choosing the pane to open a file in
+        if self.pane_chooser.is_some() {
+            self.handle_pane_chooser_key(code);
+            return Ok(());
+        }
+
+        // Terminals send Shift+Tab as BackTab; keymaps bind it as Tab with Shift
+        let (code, modifiers) = match code {
+            crossterm::event::KeyCode::BackTab => (
+                crossterm::event::KeyCode::Tab,
+                modifiers | crossterm::event::KeyModifiers::SHIFT,
+            ),
+            _ => (code, modifiers),
+        };
+
+        // Any key but Ctrl+Tab ends cycling through recent buffers
+        if self.recent_buffer_cycle.is_some()
+            && self.handle_recent_buffer_cycle_key(code, modifiers)
+        {
+            return Ok(());
+        }
+
+        // "Bind Key" takes the keys themselves, not what they're bound to
+        if self
+            .prompt
+            .as_ref()
+            .is_some_and(|prompt| prompt.prompt_type == PromptType::BindKey)
+        {
+            self.capture_bind_key(code, modifiers);
+            return Ok(());
+        }
+
         // Determine the current context first
-        let mut context = self.get_key_context();
+        let mut contexts = self.key_context_stack();
+        let mut context = contexts.top();
 
         // Special case: Hover and Signature Help popups should be dismissed on any key press
         if matches!(context, crate::input::keybindings::KeyContext::Popup) {
@@ -47,90 +175,143 @@ impl Editor {
                 .popups
                 .top()
                 .and_then(|p| p.title.as_ref())
-                .is_some_and(|title| title == "Hover" || title == "Signature Help");
+                .is_some_and(|title| {
+                    title == "Hover" || title == "Signature Help" || title == "Diagnostics"
+                });
 
             if is_dismissable_popup {
                 // Dismiss the popup on any key press
                 self.hide_popup();
                 tracing::debug!("Dismissed hover/signature help popup on key press");
                 // Recalculate context now that popup is gone
-                context = self.get_key_context();
+                contexts = self.key_context_stack();
+                context = contexts.top();
             }
         }
 
-        // Only check buffer mode keybindings if we're not in a higher-priority context
-        // (Menu, Prompt, Popup should take precedence over mode bindings)
-        let should_check_mode_bindings = matches!(
+        // The selected suggestion's own keys come before the prompt's bindings
+        if context == crate::input::keybindings::KeyContext::Prompt
+            && self.chord_state.is_empty()
+            && self.handle_suggestion_action_key(code, modifiers)
+        {
+            return Ok(());
+        }
+
+        let in_buffer = matches!(
             context,
             crate::input::keybindings::KeyContext::Normal
-                | crate::input::keybindings::KeyContext::FileExplorer
+                | crate::input::keybindings::KeyContext::VimNormal
+                | crate::input::keybindings::KeyContext::VimVisual
         );
 
+        // A focused plugin panel gets keys before the buffer
+        if in_buffer {
+            if let Some(result) = self.handle_panel_key(code, modifiers) {
+                return result;
+            }
+        }
+
+        // The pager's single-key commands come before the keymap (`--pager`)
+        if in_buffer && self.chord_state.is_empty() && self.handle_pager_key(code, modifiers)? {
+            return Ok(());
+        }
+
+        // Only check buffer mode keybindings if we're not in a higher-priority context
+        // (Menu, Prompt, Popup should take precedence over mode bindings)
+        let should_check_mode_bindings =
+            in_buffer || matches!(context, crate::input::keybindings::KeyContext::FileExplorer);
+
+        // Bindings installed on the buffer itself come first, then its mode's
+        // (unless the key continues a pending chord)
+        let should_check_mode_bindings = should_check_mode_bindings && self.chord_state.is_empty();
+        if should_check_mode_bindings && in_buffer {
+            if let Some(command_name) = self.resolve_buffer_keybinding(code, modifiers) {
+                tracing::debug!("Buffer keybinding resolved to command: {}", command_name);
+                return self.run_mode_command(command_name);
+            }
+        }
+
         if should_check_mode_bindings {
             // Check buffer mode keybindings (for virtual buffers with custom modes)
             if let Some(command_name) = self.resolve_mode_keybinding(code, modifiers) {
                 tracing::debug!("Mode keybinding resolved to command: {}", command_name);
-                // Execute the command via the command registry
-                let commands = self.command_registry.read().unwrap().get_all();
-                if let Some(cmd) = commands.iter().find(|c| c.name == command_name) {
-                    let action = cmd.action.clone();
-                    drop(commands);
-                    return self.handle_action(action);
-                } else if command_name == "close-buffer" {
-                    // Handle built-in mode commands
-                    let buffer_id = self.active_buffer;
-                    return self.close_buffer(buffer_id);
-                } else if command_name == "revert-buffer" {
-                    // Refresh the buffer (for virtual buffers, this would re-query data)
-                    self.set_status_message("Refreshing buffer...".to_string());
-                    return Ok(());
-                } else {
-                    // Try as a plugin action
-                    let action = Action::PluginAction(command_name.clone());
-                    drop(commands);
-                    return self.handle_action(action);
-                }
+                return self.run_mode_command(command_name);
             }
         }
 
+        // In vim mode, mode switches, counts and operators come before bindings
+        if self.chord_state.is_empty() && self.handle_vim_key(code, modifiers, &contexts)? {
+            return Ok(());
+        }
+        let vim_bindings = matches!(
+            context,
+            crate::input::keybindings::KeyContext::VimNormal
+                | crate::input::keybindings::KeyContext::VimVisual
+        );
+
         // Check for chord sequence matches first
         let key_event = crossterm::event::KeyEvent::new(code, modifiers);
-        let chord_result = self
-            .keybindings
-            .resolve_chord(&self.chord_state, &key_event, context);
+        let chord_result =
+            self.keybindings
+                .resolve_chord_in_stack(&self.chord_state, &key_event, &contexts);
 
         match chord_result {
             crate::input::keybindings::ChordResolution::Complete(action) => {
                 // Complete chord match - execute action and clear chord state
                 tracing::debug!("Complete chord match -> Action: {:?}", action);
                 self.chord_state.clear();
+                self.chord_started = None;
+                if vim_bindings {
+                    return self.handle_vim_action(action);
+                }
                 return self.handle_action(action);
             }
             crate::input::keybindings::ChordResolution::Partial => {
                 // Partial match - add to chord state and wait for more keys
                 tracing::debug!("Partial chord match - waiting for next key");
+                if self.chord_state.is_empty() {
+                    self.chord_started = Some(std::time::Instant::now());
+                }
                 self.chord_state.push((code, modifiers));
                 return Ok(());
             }
             crate::input::keybindings::ChordResolution::NoMatch => {
-                // No chord match - clear state and try regular resolution
+                // A key that doesn't continue the pending chord ends it; the
+                // key is swallowed rather than run on its own (Escape just
+                // cancels the chord)
                 if !self.chord_state.is_empty() {
                     tracing::debug!("Chord sequence abandoned, clearing state");
+                    self.chord_state.push((code, modifiers));
+                    let sequence = self
+                        .chord_state
+                        .iter()
+                        .map(|(code, modifiers)| {
+                            crate::input::keybindings::format_keybinding(code, modifiers)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
                     self.chord_state.clear();
+                    self.chord_started = None;
+                    if code != KeyCode::Esc {
+                        self.set_status_message(format!("{} is not bound", sequence));
+                    }
+                    return Ok(());
                 }
             }
         }
 
         // Regular single-key resolution
-        let action = self.keybindings.resolve(&key_event, context);
+        let action = self.keybindings.resolve_in_stack(&key_event, &contexts);
 
-        tracing::debug!("Context: {:?} -> Action: {:?}", context, action);
+        tracing::debug!("Contexts: {} -> Action: {:?}", contexts, action);
 
         // Cancel pending LSP requests on user actions (except LSP actions themselves)
         // This ensures stale completions don't show up after the user has moved on
         match action {
             Action::LspCompletion
             | Action::LspGotoDefinition
+            | Action::LspGotoDefinitionRight
+            | Action::LspGotoDefinitionBelow
             | Action::LspReferences
             | Action::LspHover
             | Action::None => {
@@ -147,6 +328,11 @@ impl Editor {
             return Ok(());
         }
 
+        // Actions bound in vim contexts take the pending count and operator
+        if vim_bindings {
+            return self.handle_vim_action(action);
+        }
+
         // Handle the action
         match action {
             // Prompt mode actions - delegate to handle_action
@@ -229,151 +415,58 @@ impl Editor {
                 }
             }
             Action::PromptSelectPrev => {
-                if let Some(prompt) = self.prompt_mut() {
-                    if !prompt.suggestions.is_empty() {
-                        // Suggestions exist: navigate suggestions
-                        if let Some(selected) = prompt.selected_suggestion {
-                            // Don't wrap around - stay at 0 if already at the beginning
-                            let new_selected = if selected == 0 { 0 } else { selected - 1 };
-                            prompt.selected_suggestion = Some(new_selected);
-                            // Update input to match selected suggestion (but not for plugin prompts)
-                            if !matches!(prompt.prompt_type, PromptType::Plugin { .. }) {
-                                if let Some(suggestion) = prompt.suggestions.get(new_selected) {
-                                    prompt.input = suggestion.get_value().to_string();
-                                    prompt.cursor_pos = prompt.input.len();
-                                }
-                            }
-                            // Fire selection changed hook for plugin prompts
-                            if let PromptType::Plugin { ref custom_type } = prompt.prompt_type {
-                                let hook_args = HookArgs::PromptSelectionChanged {
-                                    prompt_type: custom_type.clone(),
-                                    selected_index: new_selected,
-                                };
-                                if let Some(ref ts_manager) = self.ts_plugin_manager {
-                                    ts_manager.run_hook("prompt_selection_changed", hook_args);
-                                }
-                            }
-                        }
-                    } else {
-                        // No suggestions: navigate history (Up arrow)
-                        let prompt_type = prompt.prompt_type.clone();
-                        let current_input = prompt.input.clone();
-
-                        // Get the appropriate history based on prompt type
-                        let history_item = match prompt_type {
-                            PromptType::Search
-                            | PromptType::ReplaceSearch
-                            | PromptType::QueryReplaceSearch => {
-                                self.search_history.navigate_prev(&current_input)
-                            }
-                            PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
-                                self.replace_history.navigate_prev(&current_input)
-                            }
-                            _ => None,
-                        };
-
-                        // Update prompt input if history item exists
-                        if let Some(history_text) = history_item {
-                            if let Some(prompt) = self.prompt_mut() {
-                                prompt.set_input(history_text.clone());
-
-                                // For search prompts, update highlights incrementally
-                                if matches!(
-                                    prompt_type,
-                                    PromptType::Search
-                                        | PromptType::ReplaceSearch
-                                        | PromptType::QueryReplaceSearch
-                                ) {
-                                    self.update_search_highlights(&history_text);
-                                }
-                            }
-                        }
-                    }
+                // Up on an empty input (or while browsing) recalls history
+                if self.navigate_prompt_history(true) {
+                    return Ok(());
+                }
+                if let Some(selected) = self.prompt.as_ref().and_then(|p| p.selected_suggestion) {
+                    // Don't wrap around - stay at 0 if already at the beginning
+                    self.select_prompt_suggestion(selected.saturating_sub(1));
                 }
             }
             Action::PromptSelectNext => {
-                if let Some(prompt) = self.prompt_mut() {
+                if self.navigate_prompt_history(false) {
+                    return Ok(());
+                }
+                if let Some(prompt) = self.prompt.as_ref() {
                     if !prompt.suggestions.is_empty() {
-                        // Suggestions exist: navigate suggestions
-                        if let Some(selected) = prompt.selected_suggestion {
-                            // Don't wrap around - stay at the end if already at the last item
-                            let new_selected = (selected + 1).min(prompt.suggestions.len() - 1);
-                            prompt.selected_suggestion = Some(new_selected);
-                            // Update input to match selected suggestion (but not for plugin prompts)
-                            if !matches!(prompt.prompt_type, PromptType::Plugin { .. }) {
-                                if let Some(suggestion) = prompt.suggestions.get(new_selected) {
-                                    prompt.input = suggestion.get_value().to_string();
-                                    prompt.cursor_pos = prompt.input.len();
-                                }
-                            }
-                            // Fire selection changed hook for plugin prompts
-                            if let PromptType::Plugin { ref custom_type } = prompt.prompt_type {
-                                let hook_args = HookArgs::PromptSelectionChanged {
-                                    prompt_type: custom_type.clone(),
-                                    selected_index: new_selected,
-                                };
-                                if let Some(ref ts_manager) = self.ts_plugin_manager {
-                                    ts_manager.run_hook("prompt_selection_changed", hook_args);
-                                }
-                            }
-                        }
-                    } else {
-                        // No suggestions: navigate history (Down arrow)
-                        let prompt_type = prompt.prompt_type.clone();
-
-                        // Get the appropriate history based on prompt type
-                        let history_item = match prompt_type {
-                            PromptType::Search
-                            | PromptType::ReplaceSearch
-                            | PromptType::QueryReplaceSearch => self.search_history.navigate_next(),
-                            PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
-                                self.replace_history.navigate_next()
-                            }
-                            _ => None,
-                        };
-
-                        // Update prompt input if history item exists
-                        if let Some(history_text) = history_item {
-                            if let Some(prompt) = self.prompt_mut() {
-                                prompt.set_input(history_text.clone());
-
-                                // For search prompts, update highlights incrementally
-                                if matches!(
-                                    prompt_type,
-                                    PromptType::Search
-                                        | PromptType::ReplaceSearch
-                                        | PromptType::QueryReplaceSearch
-                                ) {
-                                    self.update_search_highlights(&history_text);
-                                }
-                            }
-                        }
+                        // Don't wrap around - stay at the end if already at the last item.
+                        // With nothing selected (path prompts), start at the first item.
+                        let next = prompt
+                            .selected_suggestion
+                            .map_or(0, |selected| selected + 1);
+                        self.select_prompt_suggestion(next);
                     }
                 }
             }
             Action::PromptPageUp => {
-                if let Some(prompt) = self.prompt_mut() {
-                    if !prompt.suggestions.is_empty() {
-                        if let Some(selected) = prompt.selected_suggestion {
-                            // Move up by 10, but stop at 0 instead of wrapping
-                            prompt.selected_suggestion = Some(selected.saturating_sub(10));
-                        }
-                    }
+                if let Some(selected) = self.prompt.as_ref().and_then(|p| p.selected_suggestion) {
+                    // Move up by a page, but stop at 0 instead of wrapping
+                    let page = self.suggestions_page_size();
+                    self.select_prompt_suggestion(selected.saturating_sub(page));
                 }
             }
             Action::PromptPageDown => {
-                if let Some(prompt) = self.prompt_mut() {
-                    if !prompt.suggestions.is_empty() {
-                        if let Some(selected) = prompt.selected_suggestion {
-                            // Move down by 10, but stop at the end instead of wrapping
-                            let len = prompt.suggestions.len();
-                            let new_pos = selected + 10;
-                            prompt.selected_suggestion = Some(new_pos.min(len - 1));
-                        }
-                    }
+                if let Some(selected) = self.prompt.as_ref().and_then(|p| p.selected_suggestion) {
+                    // Move down by a page, but stop at the end instead of wrapping
+                    let page = self.suggestions_page_size();
+                    self.select_prompt_suggestion(selected + page);
                 }
             }
+            Action::PromptSelectFirst => {
+                self.select_prompt_suggestion(0);
+            }
+            Action::PromptSelectLast => {
+                self.select_prompt_suggestion(usize::MAX);
+            }
             Action::PromptAcceptSuggestion => {
+                let completing_path = self.prompt.as_ref().is_some_and(|p| {
+                    p.prompt_type == PromptType::SaveFileAs && p.selected_suggestion.is_none()
+                });
+                if completing_path {
+                    self.complete_path_prompt();
+                    return Ok(());
+                }
                 if let Some(prompt) = self.prompt_mut() {
                     if let Some(selected) = prompt.selected_suggestion {
                         if let Some(suggestion) = prompt.suggestions.get(selected) {
@@ -386,6 +479,7 @@ impl Editor {
                         }
                     }
                 }
+                self.begin_command_argument();
                 // Refresh suggestions after accepting (important for path completion)
                 self.update_prompt_suggestions();
             }
@@ -538,10 +632,78 @@ impl Editor {
         self.set_status_message(fallback.to_string());
     }
 
+    /// Run the command a mode or buffer keybinding is bound to
+    pub(super) fn run_mode_command(&mut self, command_name: String) -> std::io::Result<()> {
+        // Execute the command via the command registry
+        let commands = self.command_registry.read().unwrap().get_all();
+        if let Some(cmd) = commands.iter().find(|c| c.name == command_name) {
+            let action = cmd.action.clone();
+            drop(commands);
+            self.handle_action(action)
+        } else if command_name == "close-buffer" {
+            // Handle built-in mode commands
+            let buffer_id = self.active_buffer;
+            self.close_buffer(buffer_id)
+        } else if command_name == "revert-buffer" {
+            // Refresh the buffer (for virtual buffers, this would re-query data)
+            self.set_status_message("Refreshing buffer...".to_string());
+            Ok(())
+        } else {
+            // Try as a plugin action
+            drop(commands);
+            self.handle_action(Action::PluginAction(command_name))
+        }
+    }
+
+    /// Select the suggestion at `index` in the active prompt (clamped to the last one)
+    ///
+    /// The input follows the selection (except in plugin prompts, which manage
+    /// their own input), and plugin prompts are notified of the change.
+    fn select_prompt_suggestion(&mut self, index: usize) {
+        let Some(prompt) = self.prompt_mut() else {
+            return;
+        };
+        if prompt.suggestions.is_empty() {
+            return;
+        }
+
+        let index = index.min(prompt.suggestions.len() - 1);
+        prompt.selected_suggestion = Some(index);
+        // Update input to match selected suggestion (but not for plugin prompts)
+        if !matches!(prompt.prompt_type, PromptType::Plugin { .. }) {
+            if let Some(suggestion) = prompt.suggestions.get(index) {
+                prompt.input = suggestion.get_value().to_string();
+                prompt.cursor_pos = prompt.input.len();
+            }
+        }
+        // Fire selection changed hook for plugin prompts
+        if let PromptType::Plugin { ref custom_type } = prompt.prompt_type {
+            let hook_args = HookArgs::PromptSelectionChanged {
+                prompt_type: custom_type.clone(),
+                selected_index: index,
+            };
+            if let Some(ref ts_manager) = self.ts_plugin_manager {
+                ts_manager.run_hook("prompt_selection_changed", hook_args);
+            }
+        }
+    }
+
+    /// Number of suggestions PageUp/PageDown move by: the rows visible in the
+    /// suggestions popup
+    fn suggestions_page_size(&self) -> usize {
+        self.cached_layout
+            .suggestions_area
+            .map(|(_, _, visible_count, _)| visible_count)
+            .unwrap_or(self.config.editor.suggestions_max_height)
+            .max(1)
+    }
+
     /// Handle an action (for normal mode and command execution)
     pub(super) fn handle_action(&mut self, action: Action) -> std::io::Result<()> {
         use crate::input::keybindings::Action;
 
+        self.latency.key_dispatched();
+
         // Record action to macro if recording
         self.record_macro_action(&action);
 
@@ -557,10 +719,7 @@ impl Editor {
                     );
                 } else if self.check_save_conflict().is_some() {
                     // Check if file was modified externally since we opened/saved it
-                    self.start_prompt(
-                        "File changed on disk. Overwrite? (y/n): ".to_string(),
-                        PromptType::ConfirmSaveConflict,
-                    );
+                    self.confirm_save_conflict();
                 } else {
                     self.save()?;
                 }
@@ -594,32 +753,42 @@ impl Editor {
             Action::New => {
                 self.new_buffer();
             }
+            Action::NewScratchBuffer => self.start_new_scratch_buffer_prompt(),
             Action::Close => {
                 let buffer_id = self.active_buffer;
-                if self.active_state().buffer.is_modified() {
-                    // Buffer has unsaved changes - prompt for confirmation
-                    let name = self.get_buffer_display_name(buffer_id);
-                    self.start_prompt(
-                        format!("'{}' modified. (s)ave, (d)iscard, (C)ancel? ", name),
-                        PromptType::ConfirmCloseBuffer { buffer_id },
-                    );
+                if self.buffer_needs_saving(buffer_id) {
+                    // Buffer has unsaved changes - ask for confirmation
+                    self.confirm_close_buffer(buffer_id);
                 } else if let Err(e) = self.close_buffer(buffer_id) {
-                    self.set_status_message(format!("Cannot close buffer: {}", e));
+                    self.set_status_warning(format!("Cannot close buffer: {}", e));
                 } else {
                     self.set_status_message("Buffer closed".to_string());
                 }
             }
+            Action::CloseAllBuffers => {
+                let ids = self.tab_buffer_ids();
+                self.close_buffers(ids);
+            }
+            Action::CloseOtherBuffers => {
+                let ids = self.other_buffer_ids();
+                self.close_buffers(ids);
+            }
+            Action::CloseBuffersToRight => {
+                let ids = self.buffer_ids_to_right();
+                self.close_buffers(ids);
+            }
+            Action::CloseSavedBuffers => {
+                let ids = self.saved_buffer_ids();
+                self.close_buffers(ids);
+            }
             Action::Revert => {
                 // Check if buffer has unsaved changes - prompt for confirmation
                 if self.active_state().buffer.is_modified() {
-                    self.start_prompt(
-                        "Buffer has unsaved changes. Revert anyway? (y/n): ".to_string(),
-                        PromptType::ConfirmRevert,
-                    );
+                    self.confirm_revert();
                 } else {
                     // No local changes, just revert
                     if let Err(e) = self.revert_file() {
-                        self.set_status_message(format!("Failed to revert: {}", e));
+                        self.set_status_error(format!("Failed to revert: {}", e));
                     }
                 }
             }
@@ -634,6 +803,13 @@ impl Editor {
                 }
                 self.cut_selection()
             }
+            Action::KillLine => {
+                if self.is_editing_disabled() {
+                    self.set_status_message("Editing disabled in this buffer".to_string());
+                    return Ok(());
+                }
+                self.kill_line()
+            }
             Action::Paste => {
                 if self.is_editing_disabled() {
                     self.set_status_message("Editing disabled in this buffer".to_string());
@@ -659,10 +835,16 @@ impl Editor {
                     events.len()
                 );
                 // Apply all inverse events collected during undo
+                let edits = self.session_stats.edits;
                 for event in &events {
                     tracing::debug!("Undo applying event: {:?}", event);
                     self.apply_event_to_active_buffer(event);
                 }
+                // Undoing isn't editing
+                self.session_stats.edits = edits;
+                if !events.is_empty() {
+                    self.session_stats.undos += 1;
+                }
                 // Update modified status based on event log position
                 self.update_modified_from_event_log();
             }
@@ -673,18 +855,29 @@ impl Editor {
                 }
                 let events = self.active_event_log_mut().redo();
                 // Apply all events collected during redo
+                let edits = self.session_stats.edits;
                 for event in events {
                     self.apply_event_to_active_buffer(&event);
                 }
+                self.session_stats.edits = edits;
                 // Update modified status based on event log position
                 self.update_modified_from_event_log();
             }
             Action::ShowHelp => {
                 self.open_help_manual();
             }
+            Action::DismissStatusMessage => {
+                self.dismiss_status_message();
+            }
             Action::ShowKeyboardShortcuts => {
                 self.open_keyboard_shortcuts();
             }
+            Action::ShowKeybindings => {
+                self.start_keybindings_prompt();
+            }
+            Action::BindKey => {
+                self.start_bind_key_prompt();
+            }
             Action::CommandPalette => {
                 // Toggle command palette: close if already open, otherwise open it
                 if let Some(prompt) = &self.prompt {
@@ -694,13 +887,15 @@ impl Editor {
                     }
                 }
 
-                // Use the current context for filtering commands
-                let suggestions = self.command_registry.read().unwrap().filter(
+                // Use the contexts the palette opens over for filtering commands
+                let mut suggestions = self.command_registry.read().unwrap().filter(
                     "",
-                    self.key_context,
+                    &self.command_context_stack(),
                     &self.keybindings,
-                    self.has_active_selection(),
+                    &self.when_context(),
+                    self.config.editor.fuzzy_matcher.matcher(),
                 );
+                self.add_command_dry_runs(&mut suggestions);
                 self.start_prompt_with_suggestions(
                     "Command: ".to_string(),
                     PromptType::Command,
@@ -831,8 +1026,14 @@ impl Editor {
             Action::LspCompletion => {
                 self.request_completion()?;
             }
+            Action::LspGotoDefinitionRight => {
+                self.request_goto_definition(Some(OpenSide::Right))?;
+            }
+            Action::LspGotoDefinitionBelow => {
+                self.request_goto_definition(Some(OpenSide::Below))?;
+            }
             Action::LspGotoDefinition => {
-                self.request_goto_definition()?;
+                self.request_goto_definition(None)?;
             }
             Action::LspRename => {
                 self.start_rename()?;
@@ -855,73 +1056,18 @@ impl Editor {
                     if let Some(path) = metadata.file_path() {
                         if let Some(language) = crate::services::lsp::manager::detect_language(path)
                         {
-                            let restart_result = if let Some(lsp) = self.lsp.as_mut() {
-                                Some(lsp.manual_restart(&language))
-                            } else {
-                                None
-                            };
-
-                            if let Some((success, message)) = restart_result {
-                                self.status_message = Some(message);
-                                if success {
-                                    // Re-send didOpen for all buffers of this language
-                                    let buffers_for_language: Vec<_> = self
-                                        .buffer_metadata
-                                        .iter()
-                                        .filter_map(|(buf_id, meta)| {
-                                            if let Some(p) = meta.file_path() {
-                                                if crate::services::lsp::manager::detect_language(p)
-                                                    == Some(language.clone())
-                                                {
-                                                    Some((*buf_id, p.clone()))
-                                                } else {
-                                                    None
-                                                }
-                                            } else {
-                                                None
-                                            }
-                                        })
-                                        .collect();
-
-                                    for (buffer_id, buf_path) in buffers_for_language {
-                                        if let Some(state) = self.buffers.get(&buffer_id) {
-                                            let content = match state.buffer.to_string() {
-                                                Some(c) => c,
-                                                None => continue, // Skip buffers that aren't fully loaded
-                                            };
-                                            let uri: Option<lsp_types::Uri> =
-                                                url::Url::from_file_path(&buf_path).ok().and_then(
-                                                    |u| u.as_str().parse::<lsp_types::Uri>().ok(),
-                                                );
-                                            if let Some(uri) = uri {
-                                                if let Some(lang_id) =
-                                                    crate::services::lsp::manager::detect_language(
-                                                        &buf_path,
-                                                    )
-                                                {
-                                                    if let Some(lsp) = self.lsp.as_mut() {
-                                                        if let Some(handle) =
-                                                            lsp.get_or_spawn(&lang_id)
-                                                        {
-                                                            let _ = handle
-                                                                .did_open(uri, content, lang_id);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            } else {
-                                self.status_message = Some("No LSP manager available".to_string());
-                            }
+                            self.restart_lsp_server(&language);
                         } else {
-                            self.status_message =
-                                Some("No LSP server configured for this file type".to_string());
+                            self.push_status(
+                                StatusLevel::Info,
+                                "No LSP server configured for this file type".to_string(),
+                            );
                         }
                     } else {
-                        self.status_message =
-                            Some("Current buffer has no associated file".to_string());
+                        self.push_status(
+                            StatusLevel::Info,
+                            "Current buffer has no associated file".to_string(),
+                        );
                     }
                 }
             }
@@ -953,6 +1099,8 @@ impl Editor {
                                 disabled: false,
                                 keybinding: None,
                                 source: None,
+                                match_indices: Vec::new(),
+                                actions: Vec::new(),
                             }
                         })
                         .collect();
@@ -987,9 +1135,35 @@ impl Editor {
             Action::DumpConfig => {
                 self.dump_config();
             }
+            Action::RestoreSessionSnapshot => {
+                self.start_restore_snapshot_prompt();
+            }
+            Action::SaveSessionAs => {
+                let name = self.session_name().unwrap_or_default().to_string();
+                self.start_prompt_with_initial_text(
+                    "Save session as: ".to_string(),
+                    PromptType::SaveSessionAs,
+                    name,
+                );
+            }
+            Action::SwitchSession => {
+                self.start_switch_session_prompt();
+            }
             Action::SelectTheme => {
                 self.start_select_theme_prompt();
             }
+            Action::LintTheme => {
+                self.lint_theme();
+            }
+            Action::ExportHtml => self.start_export_prompt(ExportFormat::Html),
+            Action::ExportAnsi => self.start_export_prompt(ExportFormat::Ansi),
+            Action::EvaluateSelection => self.evaluate_selection(false),
+            Action::EvaluateSelectionInsert => self.evaluate_selection(true),
+            Action::InsertSnippet => self.start_insert_snippet_prompt(),
+            Action::RepeatLastPaletteQuery => self.repeat_last_palette_query(),
+            Action::ToggleSelectionMode => {
+                self.toggle_selection_mode();
+            }
             Action::Search => {
                 // If already in a search-related prompt, Ctrl+F acts like Enter (confirm search)
                 let is_search_prompt = self.prompt.as_ref().is_some_and(|p| {
@@ -1029,6 +1203,9 @@ impl Editor {
             Action::FindPrevious => {
                 self.find_previous();
             }
+            Action::SearchToQuickfix => {
+                self.search_matches_to_quickfix();
+            }
             Action::AddCursorNextMatch => self.add_cursor_at_next_match(),
             Action::AddCursorAbove => self.add_cursor_above(),
             Action::AddCursorBelow => self.add_cursor_below(),
@@ -1036,33 +1213,40 @@ impl Editor {
             Action::PrevBuffer => self.prev_buffer(),
             Action::SwitchToPreviousTab => self.switch_to_previous_tab(),
             Action::SwitchToTabByName => self.start_switch_to_tab_prompt(),
+            Action::NextRecentBuffer => self.cycle_recent_buffers(1),
+            Action::PrevRecentBuffer => self.cycle_recent_buffers(-1),
+            Action::ReopenClosedBuffer => self.reopen_closed_buffer(),
+            Action::ShowSaveHistory => self.show_save_history(),
+            Action::DiffBuffers => self.start_diff_buffers_prompt(),
+            Action::ToggleScrollLock => self.toggle_scroll_lock(),
+            Action::ZoomPane => self.toggle_zoom_pane(),
+            Action::ToggleFocusMode => self.toggle_focus_mode(),
+            Action::ReflowParagraph => {
+                if self.is_editing_disabled() {
+                    self.set_status_message("Editing disabled in this buffer".to_string());
+                } else {
+                    self.reflow_paragraph();
+                }
+            }
+            Action::ReflowToColumns => self.start_reflow_to_columns_prompt(),
+            Action::ToggleAutoHardWrap => self.toggle_auto_hard_wrap(),
+            Action::CreateBufferGroup => self.start_prompt(
+                "New buffer group: ".to_string(),
+                PromptType::CreateBufferGroup,
+            ),
+            Action::MoveToBufferGroup => self.start_move_to_buffer_group_prompt(),
+            Action::SwitchBufferGroup => self.start_switch_buffer_group_prompt(),
+            Action::MoveTabLeft => self.move_tab(-1),
+            Action::MoveTabRight => self.move_tab(1),
 
             // Tab scrolling
             Action::ScrollTabsLeft => {
-                let active_split_id = self.split_manager.active_split();
-                if let Some(view_state) = self.split_view_states.get_mut(&active_split_id) {
-                    view_state.tab_scroll_offset = view_state.tab_scroll_offset.saturating_sub(5);
-                    // After manual scroll, re-evaluate to clamp and show indicators
-                    self.ensure_active_tab_visible(
-                        active_split_id,
-                        self.active_buffer,
-                        self.terminal_width,
-                    );
-                    self.set_status_message("Scrolled tabs left".to_string());
-                }
+                self.scroll_tabs(-1);
+                self.set_status_message("Scrolled tabs left".to_string());
             }
             Action::ScrollTabsRight => {
-                let active_split_id = self.split_manager.active_split();
-                if let Some(view_state) = self.split_view_states.get_mut(&active_split_id) {
-                    view_state.tab_scroll_offset = view_state.tab_scroll_offset.saturating_add(5);
-                    // After manual scroll, re-evaluate to clamp and show indicators
-                    self.ensure_active_tab_visible(
-                        active_split_id,
-                        self.active_buffer,
-                        self.terminal_width,
-                    );
-                    self.set_status_message("Scrolled tabs right".to_string());
-                }
+                self.scroll_tabs(1);
+                self.set_status_message("Scrolled tabs right".to_string());
             }
             Action::NavigateBack => self.navigate_back(),
             Action::NavigateForward => self.navigate_forward(),
@@ -1075,9 +1259,21 @@ impl Editor {
             Action::DecreaseSplitSize => self.adjust_split_size(-0.05),
             Action::ToggleFileExplorer => self.toggle_file_explorer(),
             Action::ToggleLineNumbers => self.toggle_line_numbers(),
+            Action::ToggleRedactSecrets => self.toggle_redact_secrets(),
+            Action::LockScreen => self.lock_screen(),
+            Action::BufferStatistics => self.show_buffer_statistics(),
+            Action::ReloadEnvironment => self.reload_project_env(),
             Action::ToggleMouseCapture => self.toggle_mouse_capture(),
+            Action::TogglePerformanceOverlay => self.toggle_performance_overlay(),
             Action::FocusFileExplorer => self.focus_file_explorer(),
-            Action::FocusEditor => self.focus_editor(),
+            Action::FocusEditor => {
+                // In a filtered file explorer, Escape first shows the whole tree
+                if self.key_context != KeyContext::FileExplorer
+                    || !self.clear_file_explorer_filter()
+                {
+                    self.focus_editor();
+                }
+            }
             Action::FileExplorerUp => self.file_explorer_navigate_up(),
             Action::FileExplorerDown => self.file_explorer_navigate_down(),
             Action::FileExplorerPageUp => self.file_explorer_page_up(),
@@ -1085,6 +1281,8 @@ impl Editor {
             Action::FileExplorerExpand => self.file_explorer_toggle_expand(),
             Action::FileExplorerCollapse => self.file_explorer_collapse(),
             Action::FileExplorerOpen => self.file_explorer_open_file()?,
+            Action::FileExplorerOpenRight => self.file_explorer_open_to_side(OpenSide::Right),
+            Action::FileExplorerOpenBelow => self.file_explorer_open_to_side(OpenSide::Below),
             Action::FileExplorerRefresh => self.file_explorer_refresh(),
             Action::FileExplorerNewFile => self.file_explorer_new_file(),
             Action::FileExplorerNewDirectory => self.file_explorer_new_directory(),
@@ -1092,7 +1290,15 @@ impl Editor {
             Action::FileExplorerRename => self.file_explorer_rename(),
             Action::FileExplorerToggleHidden => self.file_explorer_toggle_hidden(),
             Action::FileExplorerToggleGitignored => self.file_explorer_toggle_gitignored(),
+            Action::FileExplorerFilter => self.file_explorer_filter(),
+            Action::OpenInExternalProgram => self.open_in_external_program(),
             Action::RemoveSecondaryCursors => {
+                // With nothing else to cancel, Escape dismisses the status message
+                let primary = self.active_state().cursors.primary();
+                if self.active_state().cursors.count() == 1 && primary.anchor.is_none() {
+                    self.dismiss_status_message();
+                }
+
                 // Convert action to events and apply them
                 if let Some(events) = self.action_to_events(Action::RemoveSecondaryCursors) {
                     // Wrap in batch for atomic undo
@@ -1168,7 +1374,6 @@ impl Editor {
                     .collect();
 
                 // Update context before checking if action is enabled
-                use crate::view::ui::context_keys;
                 self.menu_state
                     .context
                     .set(context_keys::HAS_SELECTION, self.has_active_selection())
@@ -1214,16 +1419,15 @@ impl Editor {
 
             Action::SwitchKeybindingMap(map_name) => {
                 // Check if the map exists (either built-in or user-defined)
-                let is_builtin = matches!(map_name.as_str(), "default" | "emacs" | "vscode");
-                let is_user_defined = self.config.keybinding_maps.contains_key(&map_name);
-
-                if is_builtin || is_user_defined {
+                if self.config.has_keymap(&map_name) {
                     // Update the active keybinding map in config
                     self.config.active_keybinding_map = map_name.clone();
 
                     // Reload the keybinding resolver with the new map
                     self.keybindings =
                         crate::input::keybindings::KeybindingResolver::new(&self.config);
+                    let _ = self.bind_project_tasks();
+                    self.keybindings.load_user_bindings(&self.user_keybindings);
 
                     self.set_status_message(format!("Switched to '{}' keybindings", map_name));
                 } else {
@@ -1231,6 +1435,9 @@ impl Editor {
                 }
             }
 
+            Action::ToggleVimMode => {
+                self.toggle_vim_mode();
+            }
             Action::SmartHome => {
                 self.smart_home();
             }
@@ -1252,6 +1459,9 @@ impl Editor {
             Action::JumpToPreviousError => {
                 self.jump_to_previous_error();
             }
+            Action::ShowDiagnostics => {
+                self.show_line_diagnostics();
+            }
             Action::SetBookmark(key) => {
                 self.set_bookmark(key);
             }
@@ -1348,6 +1558,32 @@ impl Editor {
                 };
                 self.set_status_message(format!("Confirm each replacement {}", state));
             }
+            Action::PromptToggleHiddenFiles => self.toggle_prompt_hidden_files(),
+            Action::ToggleSearchInSelection => {
+                if self.pending_search_range.is_none() {
+                    self.set_status_message("No selection to search in".to_string());
+                } else {
+                    self.search_in_selection = !self.search_in_selection;
+                    let state = if self.search_in_selection {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    };
+                    self.set_status_message(format!("Search in selection {}", state));
+                    // Update incremental highlights if in search prompt
+                    if let Some(prompt) = &self.prompt {
+                        if matches!(
+                            prompt.prompt_type,
+                            PromptType::Search
+                                | PromptType::ReplaceSearch
+                                | PromptType::QueryReplaceSearch
+                        ) {
+                            let query = prompt.input.clone();
+                            self.update_search_highlights(&query);
+                        }
+                    }
+                }
+            }
             Action::StartMacroRecording => {
                 // This is a no-op; use ToggleMacroRecording instead
                 self.set_status_message(
@@ -1358,7 +1594,7 @@ impl Editor {
                 self.stop_macro_recording();
             }
             Action::PlayMacro(key) => {
-                self.play_macro(key);
+                self.play_macro(key, 1);
             }
             Action::ToggleMacroRecording(key) => {
                 self.toggle_macro_recording(key);
@@ -1370,14 +1606,20 @@ impl Editor {
                 self.list_macros_in_buffer();
             }
             Action::PromptRecordMacro => {
-                self.start_prompt("Record macro (0-9): ".to_string(), PromptType::RecordMacro);
+                self.start_prompt(
+                    "Record macro (register a-z, 0-9): ".to_string(),
+                    PromptType::RecordMacro,
+                );
             }
             Action::PromptPlayMacro => {
-                self.start_prompt("Play macro (0-9): ".to_string(), PromptType::PlayMacro);
+                self.start_prompt(
+                    "Replay macro (register and optional count, e.g. a 3): ".to_string(),
+                    PromptType::PlayMacro,
+                );
             }
             Action::PlayLastMacro => {
                 if let Some(key) = self.last_macro_register {
-                    self.play_macro(key);
+                    self.play_macro(key, 1);
                 } else {
                     self.set_status_message("No macro has been recorded yet".to_string());
                 }
@@ -1416,6 +1658,20 @@ impl Editor {
                     }
                 }
             }
+            Action::RunProjectTask(name) => self.run_project_task(&name),
+            Action::ReloadProjectTasks => self.reload_project_tasks(),
+            Action::RunCommandSequence(names) => return self.run_command_sequence(&names),
+            Action::CancelRunningTask => self.cancel_running_task(),
+            Action::GoToWorkspaceSymbol => self.start_workspace_symbol_prompt(),
+            Action::RebuildSymbolIndex => {
+                self.rescan_project();
+                self.start_symbol_index();
+            }
+            Action::RebuildSearchIndex => {
+                self.rescan_project();
+                self.start_search_index();
+            }
+            Action::SelectTextObject(object, around) => self.select_text_object(&object, around),
             Action::PluginAction(action_name) => {
                 // Execute the plugin callback via TypeScript plugin thread
                 // Use non-blocking version to avoid deadlock with async plugin ops
@@ -1436,20 +1692,19 @@ impl Editor {
                 }
             }
             Action::PromptConfirm => {
+                if self.enter_path_prompt_directory() {
+                    return Ok(());
+                }
                 // Handle prompt confirmation (same logic as in handle_key)
                 if let Some((input, prompt_type, selected_index)) = self.confirm_prompt() {
                     use std::path::Path;
                     match prompt_type {
                         PromptType::OpenFile => {
-                            let input_path = Path::new(&input);
-                            let resolved_path = if input_path.is_absolute() {
-                                normalize_path(input_path)
-                            } else {
-                                normalize_path(&self.working_dir.join(input_path))
-                            };
+                            let resolved_path =
+                                normalize_path(&resolve_path(&input, &self.working_dir));
 
                             if let Err(e) = self.open_file(&resolved_path) {
-                                self.set_status_message(format!("Error opening file: {e}"));
+                                self.set_status_error(format!("Error opening file: {e}"));
                             } else {
                                 self.set_status_message(format!(
                                     "Opened {}",
@@ -1458,103 +1713,24 @@ impl Editor {
                             }
                         }
                         PromptType::SaveFileAs => {
-                            // Resolve path: if relative, make it relative to working_dir
-                            let input_path = Path::new(&input);
-                            let full_path = if input_path.is_absolute() {
-                                normalize_path(input_path)
+                            // Expand ~ and $VAR; relative paths are relative to working_dir
+                            let full_path =
+                                normalize_path(&resolve_path(&input, &self.working_dir));
+
+                            let is_current_file = self
+                                .active_state()
+                                .buffer
+                                .file_path()
+                                .is_some_and(|p| p == full_path);
+                            if full_path.exists() && !is_current_file {
+                                self.confirm_overwrite(full_path);
                             } else {
-                                normalize_path(&self.working_dir.join(input_path))
-                            };
-
-                            // Debug: log event log state before save
-                            let before_idx = self.active_event_log().current_index();
-                            let before_len = self.active_event_log().len();
-                            tracing::debug!(
-                                "SaveFileAs BEFORE: event_log index={}, len={}",
-                                before_idx,
-                                before_len
-                            );
-
-                            // Save the buffer to the new file
-                            match self.active_state_mut().buffer.save_to_file(&full_path) {
-                                Ok(()) => {
-                                    // Debug: log event log state after buffer save
-                                    let after_save_idx = self.active_event_log().current_index();
-                                    let after_save_len = self.active_event_log().len();
-                                    tracing::debug!(
-                                        "SaveFileAs AFTER buffer.save_to_file: event_log index={}, len={}",
-                                        after_save_idx, after_save_len
-                                    );
-
-                                    // Update metadata with the new path
-                                    let metadata = BufferMetadata::with_file(
-                                        full_path.clone(),
-                                        &self.working_dir,
-                                    );
-                                    self.buffer_metadata.insert(self.active_buffer, metadata);
-
-                                    // Mark the event log position as saved (for undo modified tracking)
-                                    self.active_event_log_mut().mark_saved();
-                                    tracing::debug!(
-                                        "SaveFileAs AFTER mark_saved: event_log index={}, len={}",
-                                        self.active_event_log().current_index(),
-                                        self.active_event_log().len()
-                                    );
-
-                                    // Record the file modification time so auto-revert won't trigger
-                                    // for our own save. This is critical for preserving undo history.
-                                    if let Ok(metadata) = std::fs::metadata(&full_path) {
-                                        if let Ok(mtime) = metadata.modified() {
-                                            self.file_mod_times.insert(full_path.clone(), mtime);
-                                        }
-                                    }
-
-                                    // Notify LSP of the new file if applicable
-                                    self.notify_lsp_save();
-
-                                    // Emit file saved event
-                                    self.emit_event(
-                                        crate::model::control_event::events::FILE_SAVED.name,
-                                        serde_json::json!({"path": full_path.display().to_string()}),
-                                    );
-
-                                    // Fire AfterFileSave hook for plugins
-                                    if let Some(ref ts_manager) = self.ts_plugin_manager {
-                                        let hook_args =
-                                            crate::services::plugins::hooks::HookArgs::AfterFileSave {
-                                                buffer_id: self.active_buffer,
-                                                path: full_path.clone(),
-                                            };
-                                        ts_manager.run_hook("after_file_save", hook_args);
-                                    }
-
-                                    // Check if we should close the buffer after saving
-                                    if let Some(buffer_to_close) = self.pending_close_buffer.take()
-                                    {
-                                        if let Err(e) = self.force_close_buffer(buffer_to_close) {
-                                            self.set_status_message(format!(
-                                                "Saved, but cannot close buffer: {}",
-                                                e
-                                            ));
-                                        } else {
-                                            self.set_status_message("Saved and closed".to_string());
-                                        }
-                                    } else {
-                                        self.set_status_message(format!(
-                                            "Saved as: {}",
-                                            full_path.display()
-                                        ));
-                                    }
-                                }
-                                Err(e) => {
-                                    // Clear pending close on error
-                                    self.pending_close_buffer = None;
-                                    self.set_status_message(format!("Error saving file: {}", e));
-                                }
+                                self.save_as(full_path);
                             }
                         }
                         PromptType::Search => {
                             self.perform_search(&input);
+                            self.clear_search_scope();
                         }
                         PromptType::ReplaceSearch => {
                             self.perform_search(&input);
@@ -1569,8 +1745,10 @@ impl Editor {
                             // Use interactive or batch replace based on confirm_each flag
                             if self.search_confirm_each {
                                 self.start_interactive_replace(&search, &input);
+                                self.clear_search_scope();
                             } else {
-                                self.perform_replace(&search, &input);
+                                // The scope is cleared once the replace is confirmed or cancelled
+                                self.confirm_replace_all(search, input);
                             }
                         }
                         PromptType::QueryReplaceSearch => {
@@ -1586,8 +1764,9 @@ impl Editor {
                             // Use interactive or batch replace based on confirm_each flag
                             if self.search_confirm_each {
                                 self.start_interactive_replace(&search, &input);
+                                self.clear_search_scope();
                             } else {
-                                self.perform_replace(&search, &input);
+                                self.confirm_replace_all(search, input);
                             }
                         }
                         PromptType::Command => {
@@ -1602,8 +1781,34 @@ impl Editor {
                                     .unwrap()
                                     .record_usage(&cmd_name);
                                 return self.handle_action(action);
-                            } else {
-                                self.set_status_message(format!("Unknown command: {input}"));
+                            }
+                            // "Go to Line 42": a command name followed by its argument
+                            let with_argument =
+                                self.command_registry.read().unwrap().split_argument(&input);
+                            match with_argument {
+                                Some((cmd, argument))
+                                    if cmd
+                                        .argument
+                                        .as_ref()
+                                        .is_some_and(|a| a.accepts(&argument)) =>
+                                {
+                                    self.set_status_message(format!("Executing: {}", cmd.name));
+                                    self.command_registry
+                                        .write()
+                                        .unwrap()
+                                        .record_usage(&cmd.name);
+                                    return self.run_command_with_argument(cmd.action, &argument);
+                                }
+                                Some((cmd, argument)) => {
+                                    let name = cmd.argument.map(|a| a.name).unwrap_or_default();
+                                    self.set_status_error(format!(
+                                        "Invalid {} for {}: '{}'",
+                                        name, cmd.name, argument
+                                    ));
+                                }
+                                None => {
+                                    self.set_status_message(format!("Unknown command: {input}"));
+                                }
                             }
                         }
                         PromptType::GotoLine => {
@@ -1725,6 +1930,20 @@ impl Editor {
                                 }
                             }
                         }
+                        PromptType::ReflowToColumns => match input.trim().parse::<usize>() {
+                            Ok(width) if width > 0 => {
+                                if self.is_editing_disabled() {
+                                    self.set_status_message(
+                                        "Editing disabled in this buffer".to_string(),
+                                    );
+                                } else {
+                                    self.reflow_paragraph_to(width);
+                                }
+                            }
+                            _ => {
+                                self.set_status_message(format!("Invalid column count: {}", input));
+                            }
+                        },
                         PromptType::SetComposeWidth => {
                             let buffer_id = self.active_buffer;
                             let active_split = self.split_manager.active_split();
@@ -1766,11 +1985,11 @@ impl Editor {
                         }
                         PromptType::RecordMacro => {
                             if let Some(c) = input.trim().chars().next() {
-                                if c.is_ascii_digit() {
+                                if super::macros::is_macro_register(c) {
                                     self.toggle_macro_recording(c);
                                 } else {
                                     self.set_status_message(
-                                        "Macro register must be 0-9".to_string(),
+                                        "Macro register must be a-z or 0-9".to_string(),
                                     );
                                 }
                             } else {
@@ -1778,16 +1997,17 @@ impl Editor {
                             }
                         }
                         PromptType::PlayMacro => {
-                            if let Some(c) = input.trim().chars().next() {
-                                if c.is_ascii_digit() {
-                                    self.play_macro(c);
-                                } else {
-                                    self.set_status_message(
-                                        "Macro register must be 0-9".to_string(),
-                                    );
-                                }
-                            } else {
+                            if input.trim().is_empty() {
                                 self.set_status_message("No register specified".to_string());
+                            } else if let Some((c, count)) =
+                                super::macros::parse_macro_replay(&input)
+                            {
+                                self.play_macro(c, count);
+                            } else {
+                                self.set_status_message(
+                                    "Expected a register (a-z, 0-9) and an optional count"
+                                        .to_string(),
+                                );
                             }
                         }
                         PromptType::SetBookmark => {
@@ -1827,99 +2047,20 @@ impl Editor {
                                 ts_manager.run_hook("prompt_confirmed", hook_args);
                             }
                         }
-                        PromptType::ConfirmRevert => {
-                            let input_lower = input.trim().to_lowercase();
-                            if input_lower == "y" || input_lower == "yes" {
-                                if let Err(e) = self.revert_file() {
-                                    self.set_status_message(format!("Failed to revert: {}", e));
-                                }
+                        PromptType::PluginRequest { request_id, choice } => {
+                            // `input` is the selected item's value; typed text that
+                            // matches no item doesn't answer a choice
+                            let value = if choice {
+                                selected_index.map(|_| input)
                             } else {
-                                self.set_status_message("Revert cancelled".to_string());
-                            }
-                        }
-                        PromptType::ConfirmSaveConflict => {
-                            let input_lower = input.trim().to_lowercase();
-                            if input_lower == "y" || input_lower == "yes" {
-                                // Force save despite conflict
-                                if let Err(e) = self.save() {
-                                    self.set_status_message(format!("Failed to save: {}", e));
-                                }
-                            } else {
-                                self.set_status_message("Save cancelled".to_string());
-                            }
-                        }
-                        PromptType::ConfirmCloseBuffer { buffer_id } => {
-                            let input_lower = input.trim().to_lowercase();
-                            match input_lower.chars().next() {
-                                Some('s') => {
-                                    // Save and close
-                                    // Check if buffer has a file path
-                                    let has_path = self
-                                        .buffers
-                                        .get(&buffer_id)
-                                        .map(|s| s.buffer.file_path().is_some())
-                                        .unwrap_or(false);
-
-                                    if has_path {
-                                        // Save the buffer
-                                        let old_active = self.active_buffer;
-                                        self.set_active_buffer(buffer_id);
-                                        if let Err(e) = self.save() {
-                                            self.set_status_message(format!(
-                                                "Failed to save: {}",
-                                                e
-                                            ));
-                                            self.set_active_buffer(old_active);
-                                            return Ok(());
-                                        }
-                                        self.set_active_buffer(old_active);
-                                        // Now close the buffer
-                                        if let Err(e) = self.force_close_buffer(buffer_id) {
-                                            self.set_status_message(format!(
-                                                "Cannot close buffer: {}",
-                                                e
-                                            ));
-                                        } else {
-                                            self.set_status_message("Saved and closed".to_string());
-                                        }
-                                    } else {
-                                        // No file path - need SaveAs first
-                                        // Store the buffer_id so we can close after save
-                                        self.pending_close_buffer = Some(buffer_id);
-                                        self.start_prompt_with_initial_text(
-                                            "Save as: ".to_string(),
-                                            PromptType::SaveFileAs,
-                                            String::new(),
-                                        );
-                                    }
-                                }
-                                Some('d') => {
-                                    // Discard and close
-                                    if let Err(e) = self.force_close_buffer(buffer_id) {
-                                        self.set_status_message(format!(
-                                            "Cannot close buffer: {}",
-                                            e
-                                        ));
-                                    } else {
-                                        self.set_status_message(
-                                            "Buffer closed (changes discarded)".to_string(),
-                                        );
-                                    }
-                                }
-                                _ => {
-                                    // Cancel (default)
-                                    self.set_status_message("Close cancelled".to_string());
-                                }
-                            }
-                        }
-                        PromptType::ConfirmQuitWithModified => {
-                            let input_lower = input.trim().to_lowercase();
-                            if input_lower == "y" || input_lower == "yes" {
-                                // Force quit without saving
-                                self.should_quit = true;
-                            } else {
-                                self.set_status_message("Quit cancelled".to_string());
-                            }
+                                Some(input)
+                            };
+                            self.send_plugin_response(
+                                crate::services::plugins::api::PluginResponse::PromptAnswered {
+                                    request_id,
+                                    value,
+                                },
+                            );
                         }
                         PromptType::LspRename {
                             original_text,
@@ -1942,6 +2083,20 @@ impl Editor {
                             // Perform file explorer rename with the new name from the prompt
                             self.perform_file_explorer_rename(original_path, original_name, input);
                         }
+                        PromptType::FileExplorerNewFile { parent } => {
+                            self.perform_file_explorer_create(&parent, &input, false);
+                        }
+                        PromptType::FileExplorerNewDirectory { parent } => {
+                            self.perform_file_explorer_create(&parent, &input, true);
+                        }
+                        PromptType::FileExplorerFilter => {
+                            // The tree was narrowed while typing; keep it to browse
+                            if !input.is_empty() {
+                                self.set_status_message(
+                                    "Filtered (Escape shows all files)".to_string(),
+                                );
+                            }
+                        }
                         PromptType::StopLspServer => {
                             // Stop the selected LSP server
                             let language = input.trim();
@@ -1970,6 +2125,61 @@ impl Editor {
                                 self.switch_to_tab(BufferId(id));
                             }
                         }
+                        PromptType::RestoreSessionSnapshot => {
+                            // input is the snapshot's path
+                            self.restore_session_snapshot(Path::new(input.trim()));
+                        }
+                        PromptType::SaveSessionAs => {
+                            self.handle_save_session_as(input.trim());
+                        }
+                        PromptType::InsertSnippet => {
+                            // input is the snippet's name
+                            self.insert_snippet(&input);
+                        }
+                        PromptType::Export { format } => {
+                            self.export_active_buffer(&input, format);
+                        }
+                        PromptType::SwitchSession => {
+                            // input is the session's name
+                            self.handle_switch_session(&input);
+                        }
+                        PromptType::CreateBufferGroup => {
+                            self.create_buffer_group(&input);
+                        }
+                        PromptType::MoveToBufferGroup => {
+                            // input is the group's name
+                            self.move_to_buffer_group(&input);
+                        }
+                        PromptType::SwitchBufferGroup => {
+                            // input is the group's name, empty for all buffers
+                            self.switch_buffer_group(&input);
+                        }
+                        PromptType::NewScratchBuffer => {
+                            // input is the language's extension, empty for plain text
+                            self.new_scratch_buffer(&input);
+                        }
+                        PromptType::DiffBuffers => {
+                            // input is the buffer id as a string
+                            if let Ok(id) = input.trim().parse::<usize>() {
+                                self.diff_buffers(BufferId(id));
+                            }
+                        }
+                        PromptType::WorkspaceSymbol => {
+                            // input is "line:column:path"
+                            self.go_to_workspace_symbol(&input);
+                        }
+                        PromptType::Keybindings => {
+                            // input is the bound command's name
+                            return self.run_keybinding_browser_choice(&input);
+                        }
+                        PromptType::BindKey => {
+                            // input is the captured chord
+                            self.accept_bind_key_chord(input);
+                        }
+                        PromptType::BindKeyCommand { chord } => {
+                            // input is the command's name
+                            self.bind_key_to_command(&chord, &input);
+                        }
                         PromptType::QueryReplaceConfirm => {
                             // This is handled by InsertChar, not PromptConfirm
                             // But if somehow Enter is pressed, treat it as skip (n)
@@ -2100,17 +2310,9 @@ impl Editor {
                     }
                     // Reset history navigation when user starts typing
                     // This allows them to press Up to get back to history items
-                    if let Some(ref prompt) = self.prompt {
-                        match &prompt.prompt_type {
-                            PromptType::Search
-                            | PromptType::ReplaceSearch
-                            | PromptType::QueryReplaceSearch => {
-                                self.search_history.reset_navigation();
-                            }
-                            PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
-                                self.replace_history.reset_navigation();
-                            }
-                            _ => {}
+                    if let Some(prompt_type) = self.prompt.as_ref().map(|p| p.prompt_type.clone()) {
+                        if let Some(history) = self.prompt_history_mut(&prompt_type) {
+                            history.reset_navigation();
                         }
                     }
 
@@ -2130,7 +2332,15 @@ impl Editor {
                     // Cancel any pending LSP requests since the text is changing
                     self.cancel_pending_lsp_requests();
 
-                    if let Some(events) = self.action_to_events(Action::InsertChar(c)) {
+                    // With a selection, a bracket or quote wraps it instead of replacing it
+                    let surround = if self.config.editor.auto_surround {
+                        surround_selection_events(self.active_state(), c)
+                    } else {
+                        None
+                    };
+                    let events = surround.or_else(|| self.action_to_events(Action::InsertChar(c)));
+
+                    if let Some(events) = events {
                         // Wrap multiple events (multi-cursor) in a Batch for atomic undo
                         if events.len() > 1 {
                             let batch = Event::Batch {
@@ -2142,12 +2352,16 @@ impl Editor {
                             // Note: LSP notifications now handled automatically by apply_event_to_active_buffer
                         } else {
                             // Single cursor - no need for batch
-                            for event in events {
+                            for event in &events {
                                 self.active_event_log_mut().append(event.clone());
-                                self.apply_event_to_active_buffer(&event);
+                                self.apply_event_to_active_buffer(event);
                                 // Note: LSP notifications now handled automatically by apply_event_to_active_buffer
                             }
                         }
+                        track_auto_closed(self.active_state_mut(), c, &events);
+                        if self.config.editor.auto_hard_wrap && !c.is_whitespace() {
+                            self.hard_wrap_at_cursor();
+                        }
                     }
 
                     // Auto-trigger signature help on '(' and ','
@@ -2248,6 +2462,18 @@ impl Editor {
     ) -> std::io::Result<bool> {
         use crossterm::event::{MouseButton, MouseEventKind};
 
+        if self.note_user_input() {
+            return Ok(false);
+        }
+
+        // A confirmation dialog takes all mouse input until it is answered
+        if self.confirm_dialog.is_some() {
+            return Ok(self.handle_confirm_dialog_mouse(mouse_event));
+        }
+        if self.pane_chooser.is_some() {
+            return Ok(self.handle_pane_chooser_mouse(mouse_event));
+        }
+
         // Cancel LSP rename prompt on any mouse interaction
         let mut needs_render = false;
         if let Some(ref prompt) = self.prompt {
@@ -2388,10 +2614,17 @@ impl Editor {
                 && row < inner_rect.y + inner_rect.height
             {
                 let relative_row = (row - inner_rect.y) as usize;
-                let item_idx = start_idx + relative_row;
-
-                if item_idx < *total_count {
-                    return Some(HoverTarget::SuggestionItem(item_idx));
+                let row_idx = start_idx + relative_row;
+
+                // Section headers can't be hovered
+                if row_idx < *total_count {
+                    if let Some(SuggestionRow::Suggestion(item_idx)) = self
+                        .prompt
+                        .as_ref()
+                        .and_then(|prompt| prompt.suggestion_rows().get(row_idx).copied())
+                    {
+                        return Some(HoverTarget::SuggestionItem(item_idx));
+                    }
                 }
             }
         }
@@ -2543,7 +2776,7 @@ impl Editor {
     /// Handle mouse click (down event)
     pub(super) fn handle_mouse_click(&mut self, col: u16, row: u16) -> std::io::Result<()> {
         // Check if click is on suggestions (command palette, autocomplete)
-        if let Some((inner_rect, start_idx, _visible_count, total_count)) =
+        if let Some((inner_rect, start_idx, _visible_count, _total_rows)) =
             &self.cached_layout.suggestions_area.clone()
         {
             if col >= inner_rect.x
@@ -2552,9 +2785,14 @@ impl Editor {
                 && row < inner_rect.y + inner_rect.height
             {
                 let relative_row = (row - inner_rect.y) as usize;
-                let item_idx = start_idx + relative_row;
+                let row_idx = start_idx + relative_row;
+                let clicked = self
+                    .prompt
+                    .as_ref()
+                    .and_then(|prompt| prompt.suggestion_rows().get(row_idx).copied());
 
-                if item_idx < *total_count {
+                // Clicks on section headers are ignored
+                if let Some(SuggestionRow::Suggestion(item_idx)) = clicked {
                     // Select and execute the clicked suggestion
                     if let Some(prompt) = &mut self.prompt {
                         prompt.selected_suggestion = Some(item_idx);
@@ -2696,6 +2934,13 @@ impl Editor {
             return Ok(());
         }
 
+        // Clicking a plugin panel focuses it; clicking anywhere else unfocuses it
+        if let Some(panel_id) = self.panel_at(col, row) {
+            self.focus_panel(&panel_id);
+            return Ok(());
+        }
+        self.focused_panel = None;
+
         // Check if click is on file explorer
         if let Some(explorer_area) = self.cached_layout.file_explorer_area {
             if col >= explorer_area.x
@@ -2801,7 +3046,7 @@ impl Editor {
 
         if let Some(split_id) = close_split_click {
             if let Err(e) = self.split_manager.close_split(split_id) {
-                self.set_status_message(format!("Cannot close split: {}", e));
+                self.set_status_warning(format!("Cannot close split: {}", e));
             } else {
                 // Update active buffer to match the new active split
                 let new_active_split = self.split_manager.active_split();
@@ -2831,18 +3076,12 @@ impl Editor {
 
             // Handle close button click
             if clicked_close {
-                if let Some(state) = self.buffers.get(&clicked_buffer) {
-                    if state.buffer.is_modified() {
-                        // Buffer has unsaved changes - prompt for confirmation
-                        let name = self.get_buffer_display_name(clicked_buffer);
-                        self.start_prompt(
-                            format!("'{}' modified. (s)ave, (d)iscard, (C)ancel? ", name),
-                            PromptType::ConfirmCloseBuffer {
-                                buffer_id: clicked_buffer,
-                            },
-                        );
+                if self.buffers.contains_key(&clicked_buffer) {
+                    if self.buffer_needs_saving(clicked_buffer) {
+                        // Buffer has unsaved changes - ask for confirmation
+                        self.confirm_close_buffer(clicked_buffer);
                     } else if let Err(e) = self.force_close_buffer(clicked_buffer) {
-                        self.set_status_message(format!("Cannot close buffer: {}", e));
+                        self.set_status_warning(format!("Cannot close buffer: {}", e));
                     } else {
                         self.set_status_message("Buffer closed".to_string());
                     }
@@ -3000,7 +3239,7 @@ impl Editor {
             {
                 // Scroll the file explorer
                 if let Some(explorer) = &mut self.file_explorer {
-                    let visible = explorer.tree().get_visible_nodes();
+                    let visible = explorer.visible_nodes();
                     if visible.is_empty() {
                         return Ok(());
                     }
@@ -3410,16 +3649,22 @@ impl Editor {
             let content_col = col.saturating_sub(content_rect.x);
             let content_row = row.saturating_sub(content_rect.y);
 
-            // Skip if click is in the gutter
-            if content_col < gutter_width {
-                return Ok(());
-            }
+            // Clicks in the gutter go to the start of the clicked line
+            let gutter_column = if content_col < gutter_width {
+                state.margins.left_column_at(content_col as usize)
+            } else {
+                None
+            };
 
             // Adjust for gutter
             let text_col = content_col.saturating_sub(gutter_width);
 
             // Account for horizontal scroll
-            let actual_col = (text_col as usize) + state.viewport.left_column;
+            let actual_col = if gutter_column.is_some() {
+                0
+            } else {
+                (text_col as usize) + state.viewport.left_column
+            };
 
             // Find the byte position for this line and column
             let mut line_iter = state.buffer.line_iterator(state.viewport.top_byte, 80);
@@ -3465,7 +3710,8 @@ impl Editor {
                     prop.get("onClick")
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string())
-                });
+                })
+                .filter(|_| gutter_column.is_none());
 
             if let Some(action_name) = onclick_action {
                 // Execute the action associated with this clickable element
@@ -3504,11 +3750,59 @@ impl Editor {
                 self.position_history
                     .record_movement(buffer_id, target_position, None);
             }
+
+            if let Some(column) = gutter_column {
+                return self.handle_gutter_click(buffer_id, column);
+            }
         }
 
         Ok(())
     }
 
+    /// Run the action for a click on a gutter column
+    ///
+    /// The cursor is already on the clicked line. A plugin's
+    /// `setGutterClickAction` takes precedence over `config.gutter`.
+    fn handle_gutter_click(
+        &mut self,
+        buffer_id: BufferId,
+        column: crate::view::margin::GutterColumn,
+    ) -> std::io::Result<()> {
+        use crate::view::margin::GutterColumn;
+
+        if let Some(ts_manager) = &self.ts_plugin_manager {
+            if ts_manager.has_hook_handlers("gutter_click") {
+                let state = self.active_state();
+                let line = state
+                    .buffer
+                    .get_line_number(state.cursors.primary().position);
+                ts_manager.run_hook(
+                    "gutter_click",
+                    HookArgs::GutterClick {
+                        buffer_id,
+                        line,
+                        column: column.name().to_string(),
+                    },
+                );
+            }
+        }
+
+        let action_name = match self.plugin_gutter_actions.get(&column) {
+            Some(action) => action.clone(),
+            None => match column {
+                GutterColumn::Sign => self.config.gutter.sign_click.clone(),
+                GutterColumn::LineNumber => self.config.gutter.line_number_click.clone(),
+                GutterColumn::Separator => String::new(),
+            },
+        };
+        if action_name.is_empty() {
+            return Ok(());
+        }
+        let action = Action::from_str(&action_name, &HashMap::new())
+            .unwrap_or(Action::PluginAction(action_name));
+        self.handle_action(action)
+    }
+
     /// Handle click in file explorer
     pub(super) fn handle_file_explorer_click(
         &mut self,
@@ -3564,6 +3858,62 @@ impl Editor {
         Ok(())
     }
 
+    /// Start the language server for `language` again, and open its
+    /// buffers in it
+    pub(super) fn restart_lsp_server(&mut self, language: &str) {
+        let restart_result = self.lsp.as_mut().map(|lsp| lsp.manual_restart(language));
+
+        if let Some((success, message)) = restart_result {
+            self.push_status(StatusLevel::Info, message);
+            if success {
+                // Re-send didOpen for all buffers of this language
+                let buffers_for_language: Vec<_> = self
+                    .buffer_metadata
+                    .iter()
+                    .filter_map(|(buf_id, meta)| {
+                        if let Some(p) = meta.file_path() {
+                            if crate::services::lsp::manager::detect_language(p).as_deref()
+                                == Some(language)
+                            {
+                                Some((*buf_id, p.clone()))
+                            } else {
+                                None
+                            }
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+
+                for (buffer_id, buf_path) in buffers_for_language {
+                    if let Some(state) = self.buffers.get(&buffer_id) {
+                        let content = match state.buffer.to_string() {
+                            Some(c) => c,
+                            None => continue, // Skip buffers that aren't fully loaded
+                        };
+                        let version = state.lsp_version();
+                        let uri: Option<lsp_types::Uri> = url::Url::from_file_path(&buf_path)
+                            .ok()
+                            .and_then(|u| u.as_str().parse::<lsp_types::Uri>().ok());
+                        if let Some(uri) = uri {
+                            if let Some(lang_id) =
+                                crate::services::lsp::manager::detect_language(&buf_path)
+                            {
+                                if let Some(lsp) = self.lsp.as_mut() {
+                                    if let Some(handle) = lsp.get_or_spawn(&lang_id) {
+                                        let _ = handle.did_open(uri, content, lang_id, version);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            self.push_status(StatusLevel::Info, "No LSP manager available".to_string());
+        }
+    }
+
     /// Start the theme selection prompt with available themes
     fn start_select_theme_prompt(&mut self) {
         let available_themes = crate::view::theme::Theme::available_themes();
@@ -3590,6 +3940,8 @@ impl Editor {
                     disabled: false,
                     keybinding: None,
                     source: None,
+                    match_indices: Vec::new(),
+                    actions: Vec::new(),
                 }
             })
             .collect();
@@ -3618,6 +3970,97 @@ impl Editor {
         }
     }
 
+    /// Turn selection mode on or off
+    ///
+    /// While it is on, plain movement keys extend the selection as if Shift
+    /// were held. Escape or the next edit also turns it off.
+    fn toggle_selection_mode(&mut self) {
+        let active = !self.active_state().cursors.primary().deselect_on_move;
+        let events: Vec<Event> = self
+            .active_state()
+            .cursors
+            .iter()
+            .map(|(cursor_id, cursor)| {
+                if active {
+                    Event::ClearAnchor { cursor_id }
+                } else {
+                    // Keep a selection made before the mode was turned on
+                    Event::SetAnchor {
+                        cursor_id,
+                        position: cursor.anchor.unwrap_or(cursor.position),
+                    }
+                }
+            })
+            .collect();
+        let batch = Event::Batch {
+            events,
+            description: "Toggle selection mode".to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+
+        if active {
+            self.set_status_message("Selection mode off".to_string());
+        } else {
+            self.set_status_message(
+                "Selection mode: movement extends the selection, Esc to cancel".to_string(),
+            );
+        }
+    }
+
+    /// Check the current theme's contrast ratios
+    ///
+    /// Failing color pairs are listed in a read-only "*Theme Lint*" buffer.
+    fn lint_theme(&mut self) {
+        let issues = self.theme.contrast_issues();
+        let total = self.theme.contrast_pair_count();
+        if issues.is_empty() {
+            self.set_status_message(format!(
+                "Theme '{}': all {} color pairs have enough contrast",
+                self.theme.name, total
+            ));
+            return;
+        }
+
+        let mut content = format!(
+            "Theme '{}': {} of {} color pairs have too little contrast\n\n",
+            self.theme.name,
+            issues.len(),
+            total
+        );
+        for issue in &issues {
+            content.push_str(&format!("{}\n", issue));
+        }
+
+        let buffer_name = "*Theme Lint*".to_string();
+        let existing = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == buffer_name)
+            .map(|(id, _)| *id);
+        let buffer_id = existing.unwrap_or_else(|| {
+            self.create_virtual_buffer(buffer_name, "special".to_string(), true)
+        });
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let len = state.buffer.len();
+            if len > 0 {
+                state.buffer.delete_bytes(0, len);
+            }
+            state.buffer.insert(0, &content);
+            state.buffer.clear_modified();
+            state.editing_disabled = true;
+            state.cursors.primary_mut().position = 0;
+            state.cursors.primary_mut().anchor = None;
+        }
+        self.set_active_buffer(buffer_id);
+        self.set_status_warning(format!(
+            "Theme '{}': {} of {} color pairs have too little contrast",
+            self.theme.name,
+            issues.len(),
+            total
+        ));
+    }
+
     /// Switch to the previously active tab in the current split
     fn switch_to_previous_tab(&mut self) {
         let active_split = self.split_manager.active_split();
@@ -3654,10 +4097,10 @@ impl Editor {
     }
 
     /// Start the switch-to-tab-by-name prompt with suggestions from open buffers
-    fn start_switch_to_tab_prompt(&mut self) {
+    pub(super) fn start_switch_to_tab_prompt(&mut self) {
         let active_split = self.split_manager.active_split();
         let open_buffers = if let Some(view_state) = self.split_view_states.get(&active_split) {
-            view_state.open_buffers.clone()
+            self.group_tabs(view_state.open_buffers.clone())
         } else {
             return;
         };
@@ -3702,6 +4145,8 @@ impl Editor {
                     disabled: false,
                     keybinding: None,
                     source: None,
+                    match_indices: Vec::new(),
+                    actions: Self::tab_suggestion_actions(),
                 }
             })
             .collect();
@@ -0,0 +1,172 @@
+//! "Show Keybindings": a searchable list of every key binding
+//!
+//! Bindings are listed per context with the action they run. Keys bound to
+//! more than one action in the same context of one binding list (a keymap,
+//! the config's `keybindings` or `keybindings.toml`) are flagged as
+//! conflicts, and commands with no key at all are listed as unbound.
+//! Typing filters by action, keys or note ("conflict", "unbound").
+
+use super::Editor;
+use crate::config::Keybinding;
+use crate::input::commands::Suggestion;
+use crate::input::keybindings::{Action, BindingConflict, KeyContext, KeybindingResolver};
+use crate::view::prompt::{Prompt, PromptType};
+
+impl Editor {
+    /// Open the key bindings browser
+    pub(super) fn start_keybindings_prompt(&mut self) {
+        let commands = self.command_registry.read().unwrap().get_all();
+        let command_for = |action: &Action| {
+            commands
+                .iter()
+                .find(|command| &command.action == action)
+                .map(|command| command.name.clone())
+        };
+
+        let conflicts = self.keybinding_conflicts();
+        let conflict_note = |context: KeyContext, language: Option<&str>, keys: &str| {
+            conflicts.iter().find_map(|(source, conflict)| {
+                (conflict.context == context
+                    && conflict.language.as_deref() == language
+                    && conflict.keys == keys)
+                    .then(|| {
+                        let shadowed: Vec<String> = conflict.actions[..conflict.actions.len() - 1]
+                            .iter()
+                            .map(KeybindingResolver::format_action)
+                            .collect();
+                        format!("conflict in {}: also {}", source, shadowed.join(", "))
+                    })
+            })
+        };
+
+        let bindings = self.keybindings.binding_infos();
+        let mut suggestions: Vec<Suggestion> = bindings
+            .iter()
+            .map(|binding| {
+                let mut description = binding.context.to_when_clause().to_string();
+                if let Some(language) = &binding.language {
+                    description = format!("{}, language {}", description, language);
+                }
+                if binding.custom {
+                    description.push_str(", custom");
+                }
+                let conflict =
+                    conflict_note(binding.context, binding.language.as_deref(), &binding.keys);
+                if let Some(note) = &conflict {
+                    description = format!("{}, {}", description, note);
+                }
+                Suggestion {
+                    text: KeybindingResolver::format_action(&binding.action),
+                    description: Some(description),
+                    // Runs the bound command, if it has one
+                    value: Some(command_for(&binding.action).unwrap_or_default()),
+                    disabled: false,
+                    keybinding: Some(binding.keys.clone()),
+                    source: None,
+                    match_indices: Vec::new(),
+                    actions: Vec::new(),
+                }
+            })
+            .collect();
+        // Conflicts first, so they're seen without searching
+        suggestions.sort_by_key(|s| {
+            !s.description
+                .as_deref()
+                .is_some_and(|d| d.contains("conflict in"))
+        });
+
+        let mut unbound: Vec<Suggestion> = commands
+            .iter()
+            .filter(|command| !bindings.iter().any(|b| b.action == command.action))
+            .map(|command| Suggestion {
+                text: command.name.clone(),
+                description: Some("unbound".to_string()),
+                value: Some(command.name.clone()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+                match_indices: Vec::new(),
+                actions: Vec::new(),
+            })
+            .collect();
+        unbound.sort_by(|a, b| a.text.cmp(&b.text));
+        suggestions.extend(unbound);
+
+        let conflict_count = conflicts.len();
+        self.prompt = Some(Prompt::with_suggestions(
+            "Key bindings: ".to_string(),
+            PromptType::Keybindings,
+            suggestions,
+        ));
+        if conflict_count > 0 {
+            self.set_status_warning(format!(
+                "{} key binding conflict{}",
+                conflict_count,
+                if conflict_count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    /// Run the command chosen in the key bindings browser
+    ///
+    /// `value` is the command's name, or empty for actions with no command.
+    pub(super) fn run_keybinding_browser_choice(&mut self, value: &str) -> std::io::Result<()> {
+        if value.is_empty() {
+            self.set_status_message("No command for this binding".to_string());
+            return Ok(());
+        }
+        self.run_mode_command(value.to_string())
+    }
+
+    /// Conflicts in each binding list that feeds the resolver, with a label
+    /// for the list they're in
+    fn keybinding_conflicts(&self) -> Vec<(String, BindingConflict)> {
+        let mut sources: Vec<(String, Vec<Keybinding>)> = Vec::new();
+
+        // Each map of the inheritance chain on its own, since a child map
+        // overriding its parent's keys is intended
+        let mut map_name = Some(
+            if self.config.has_keymap(&self.config.active_keybinding_map) {
+                self.config.active_keybinding_map.clone()
+            } else {
+                "default".to_string()
+            },
+        );
+        if self.config.editor.vim_mode {
+            sources.extend(
+                self.config
+                    .keymap("vim")
+                    .map(|map| ("keymap 'vim'".to_string(), map.bindings)),
+            );
+        }
+        let mut visited = Vec::new();
+        while let Some(name) = map_name.take() {
+            if visited.contains(&name) {
+                break;
+            }
+            if let Some(map) = self.config.keymap(&name) {
+                map_name = map.inherits;
+                sources.push((format!("keymap '{}'", name), map.bindings));
+            }
+            visited.push(name);
+        }
+
+        sources.push((
+            "config keybindings".to_string(),
+            self.config.keybindings.clone(),
+        ));
+        sources.push((
+            crate::input::keybindings_file::KEYBINDINGS_FILE.to_string(),
+            self.user_keybindings.clone(),
+        ));
+
+        sources
+            .into_iter()
+            .flat_map(|(label, bindings)| {
+                KeybindingResolver::find_conflicts(&bindings)
+                    .into_iter()
+                    .map(move |conflict| (label.clone(), conflict))
+            })
+            .collect()
+    }
+}
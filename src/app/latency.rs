@@ -0,0 +1,84 @@
+//! Typing latency: tracing keys to the screen and the performance overlay
+//!
+//! `handle_key` and `render` mark each key's way through the editor (see
+//! `services::latency`); the event loop reports when a frame has been
+//! flushed. "Toggle Performance Overlay" shows the percentiles of each
+//! stage, and how often syntax highlighting was put off to keep frames
+//! within budget; `--trace-latency FILE` writes every key out.
+
+use super::Editor;
+use crate::input::keybindings::format_keybinding;
+use crate::services::latency::{format_latency, LatencyStage};
+use crate::view::ui::PerformanceOverlayRow;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::path::Path;
+
+impl Editor {
+    /// Write the latency of every key to `path`
+    pub fn enable_latency_trace(&mut self, path: &Path) -> std::io::Result<()> {
+        self.latency.enable_dump(path)
+    }
+
+    /// Report that the last rendered frame has been written to the terminal
+    pub fn frame_flushed(&mut self) {
+        self.latency.frame_flushed();
+    }
+
+    pub(super) fn trace_key_received(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        self.latency
+            .key_received(format_keybinding(&code, &modifiers));
+    }
+
+    pub(super) fn toggle_performance_overlay(&mut self) {
+        self.show_performance_overlay = !self.show_performance_overlay;
+        self.set_status_message(
+            if self.show_performance_overlay {
+                "Performance overlay shown"
+            } else {
+                "Performance overlay hidden"
+            }
+            .to_string(),
+        );
+    }
+
+    /// How often frames were drawn without syntax highlighting, over all
+    /// open buffers, for the overlay
+    pub(super) fn highlight_fallback_note(&self) -> Option<String> {
+        let (frames, fallbacks) =
+            self.buffers
+                .values()
+                .fold((0, 0), |(frames, fallbacks), state| {
+                    (
+                        frames + state.highlight_stats.frames,
+                        fallbacks + state.highlight_stats.fallbacks,
+                    )
+                });
+        (frames > 0).then(|| {
+            format!(
+                "plain frames: {} of {} ({:.1}%)",
+                fallbacks,
+                frames,
+                fallbacks as f64 * 100.0 / frames as f64
+            )
+        })
+    }
+
+    /// The overlay's rows: each stage, then the total
+    pub(super) fn performance_overlay_rows(&self) -> Vec<PerformanceOverlayRow> {
+        let stages = LatencyStage::ALL
+            .iter()
+            .map(|stage| (stage.name(), Some(*stage)))
+            .chain(std::iter::once(("total", None)));
+        stages
+            .filter_map(|(name, stage)| {
+                let percentiles = self.latency.percentiles(stage)?;
+                Some(PerformanceOverlayRow {
+                    label: name.to_string(),
+                    p50: format_latency(percentiles.p50),
+                    p95: format_latency(percentiles.p95),
+                    p99: format_latency(percentiles.p99),
+                })
+            })
+            .collect()
+    }
+}
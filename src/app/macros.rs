@@ -0,0 +1,343 @@
+//! Keyboard macros
+//!
+//! Recording captures the keys pressed (not what they did), and replaying
+//! feeds them back through `handle_key`, so a macro behaves exactly like
+//! typing it again: prompts, chords and mode changes included. Macros live
+//! in registers named by a letter or digit, and can be replayed several
+//! times in a row.
+
+use super::*;
+use crate::input::action_format::ActionRecording;
+use crate::input::keybindings::format_keybinding;
+
+/// Whether `c` can name a macro register
+pub(super) fn is_macro_register(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+}
+
+/// Parse the "Replay Macro" prompt's input: a register, then an optional
+/// repeat count (e.g. "a", "a 3")
+pub(super) fn parse_macro_replay(input: &str) -> Option<(char, usize)> {
+    let input = input.trim();
+    let mut chars = input.chars();
+    let register = chars.next().filter(|c| is_macro_register(*c))?;
+    let count = chars.as_str().trim();
+    if count.is_empty() {
+        return Some((register, 1));
+    }
+    count.parse().ok().filter(|n| *n > 0).map(|n| (register, n))
+}
+
+impl Editor {
+    /// Toggle macro recording for the given register
+    pub(super) fn toggle_macro_recording(&mut self, key: char) {
+        if let Some(state) = &self.macro_recording {
+            if state.key == key {
+                // Stop recording
+                self.stop_macro_recording();
+            } else {
+                // Recording to a different key, stop current and start new
+                self.stop_macro_recording();
+                self.start_macro_recording(key);
+            }
+        } else {
+            // Start recording
+            self.start_macro_recording(key);
+        }
+    }
+
+    /// Start recording a macro
+    pub(super) fn start_macro_recording(&mut self, key: char) {
+        self.macro_recording = Some(MacroRecordingState {
+            key,
+            recorded: RecordedMacro::default(),
+            prompt_start: None,
+        });
+        self.set_status_message(format!(
+            "Recording macro '{}' (run \"Stop Recording\" or toggle '{}' again to stop)",
+            key, key
+        ));
+    }
+
+    /// Stop recording and save the macro
+    pub(super) fn stop_macro_recording(&mut self) {
+        if let Some(mut state) = self.macro_recording.take() {
+            // Keys typed into the prompt that stopped the recording aren't part of it
+            if let Some((keys, actions)) = state.prompt_start {
+                state.recorded.keys.truncate(keys);
+                state.recorded.actions.truncate(actions);
+            }
+            let key_count = state.recorded.keys.len();
+            let key = state.key;
+            self.macros.insert(key, state.recorded);
+            self.last_macro_register = Some(key);
+            self.set_status_message(format!("Macro '{}' saved ({} keys)", key, key_count));
+        } else {
+            self.set_status_message("Not recording a macro".to_string());
+        }
+    }
+
+    /// Replay a recorded macro `count` times
+    pub(super) fn play_macro(&mut self, key: char, count: usize) {
+        let Some(recorded) = self.macros.get(&key).cloned() else {
+            self.set_status_message(format!("No macro recorded for '{}'", key));
+            return;
+        };
+        if recorded.keys.is_empty() {
+            self.set_status_message(format!("Macro '{}' is empty", key));
+            return;
+        }
+        if self.macros_playing.contains(&key) {
+            self.set_status_warning(format!("Macro '{}' replays itself", key));
+            return;
+        }
+
+        // Temporarily disable recording to avoid recording the playback
+        let was_recording = self.macro_recording.take();
+        self.macros_playing.push(key);
+
+        let mut result = Ok(());
+        'replay: for _ in 0..count {
+            for &(code, modifiers) in &recorded.keys {
+                result = self.handle_key(code, modifiers);
+                if result.is_err() || self.should_quit {
+                    break 'replay;
+                }
+            }
+        }
+
+        self.macros_playing.pop();
+        // Restore recording state
+        self.macro_recording = was_recording;
+
+        match result {
+            Err(e) => self.set_status_error(format!("Macro '{}' failed: {}", key, e)),
+            // Nested replays report only the outermost one
+            Ok(()) if !self.macros_playing.is_empty() => {}
+            Ok(()) if count > 1 => self.set_status_message(format!(
+                "Played macro '{}' {} times ({} keys)",
+                key,
+                count,
+                recorded.keys.len()
+            )),
+            Ok(()) => self.set_status_message(format!(
+                "Played macro '{}' ({} keys)",
+                key,
+                recorded.keys.len()
+            )),
+        }
+    }
+
+    /// Record a key once it has been handled (if recording)
+    ///
+    /// `register` is the register that was recording before the key, and
+    /// `marks` the lengths of its keys and actions then; `prompt_was_open`
+    /// says whether a prompt was open.
+    pub(super) fn record_macro_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        register: char,
+        marks: (usize, usize),
+        prompt_was_open: bool,
+    ) {
+        let prompt_open = self.prompt.is_some();
+        let Some(state) = &mut self.macro_recording else {
+            // The key stopped the recording
+            return;
+        };
+        if state.key != register {
+            // The key stopped the recording and started another one
+            return;
+        }
+
+        state.recorded.keys.push((code, modifiers));
+        if !prompt_open {
+            state.prompt_start = None;
+        } else if !prompt_was_open {
+            state.prompt_start = Some(marks);
+        }
+    }
+
+    /// Record an action to the current macro (if recording)
+    pub(super) fn record_macro_action(&mut self, action: &Action) {
+        // The steps of a composite command are recorded as the command itself
+        if self.command_sequence_depth > 0 {
+            return;
+        }
+        if let Some(state) = &mut self.macro_recording {
+            // Don't record macro control actions themselves
+            match action {
+                Action::StartMacroRecording
+                | Action::StopMacroRecording
+                | Action::PlayMacro(_)
+                | Action::ToggleMacroRecording(_)
+                | Action::ShowMacro(_)
+                | Action::ListMacros
+                | Action::PromptRecordMacro
+                | Action::PromptPlayMacro
+                | Action::PlayLastMacro => {}
+                _ => {
+                    state.recorded.actions.push(action.clone());
+                }
+            }
+        }
+    }
+
+    /// Show a macro in a buffer: its keys, then the actions they ran as JSON
+    pub(super) fn show_macro_in_buffer(&mut self, key: char) {
+        if let Some(recorded) = self.macros.get(&key) {
+            let keys: Vec<String> = recorded
+                .keys
+                .iter()
+                .map(|(code, modifiers)| format_keybinding(code, modifiers))
+                .collect();
+            // Serialize the actions in the versioned action format
+            let json = ActionRecording::new(&recorded.actions).to_json();
+
+            // Create header with macro info
+            let content = format!(
+                "// Macro '{}' ({} keys)\n// Keys: {}\n// The actions below can be saved as a .json file for persistence\n\n{}",
+                key,
+                keys.len(),
+                keys.join(" "),
+                json
+            );
+            let key_count = keys.len();
+
+            // Create a new buffer for the macro
+            let buffer_id = BufferId(self.next_buffer_id);
+            self.next_buffer_id += 1;
+
+            let state = EditorState::new(
+                self.terminal_width,
+                self.terminal_height,
+                self.config.editor.large_file_threshold_bytes as usize,
+            );
+
+            self.buffers.insert(buffer_id, state);
+            self.event_logs.insert(buffer_id, EventLog::new());
+
+            // Set buffer content
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state.buffer = crate::model::buffer::Buffer::from_str(
+                    &content,
+                    self.config.editor.large_file_threshold_bytes as usize,
+                );
+            }
+
+            // Set metadata
+            let metadata = BufferMetadata {
+                kind: BufferKind::Virtual {
+                    mode: "macro-view".to_string(),
+                },
+                display_name: format!("*Macro {}*", key),
+                lsp_enabled: false,
+                lsp_disabled_reason: Some("Virtual macro buffer".to_string()),
+                read_only: false, // Allow editing for saving
+                binary: false,
+                scratch: false,
+            };
+            self.buffer_metadata.insert(buffer_id, metadata);
+
+            // Switch to the new buffer
+            self.active_buffer = buffer_id;
+            self.set_status_message(format!(
+                "Macro '{}' shown in buffer ({} keys) - save as .json for persistence",
+                key, key_count
+            ));
+        } else {
+            self.set_status_message(format!("No macro recorded for '{}'", key));
+        }
+    }
+
+    /// List all recorded macros in a buffer
+    pub(super) fn list_macros_in_buffer(&mut self) {
+        if self.macros.is_empty() {
+            self.set_status_message("No macros recorded".to_string());
+            return;
+        }
+
+        // Build a summary of all macros
+        let mut content =
+            String::from("// Recorded Macros\n// Use ShowMacro(key) to see details\n\n");
+
+        let mut keys: Vec<char> = self.macros.keys().copied().collect();
+        keys.sort();
+
+        for key in keys {
+            if let Some(recorded) = self.macros.get(&key) {
+                content.push_str(&format!("Macro '{}': {} keys\n", key, recorded.keys.len()));
+
+                // Show the first few keys as a preview
+                let preview: Vec<String> = recorded
+                    .keys
+                    .iter()
+                    .take(20)
+                    .map(|(code, modifiers)| format_keybinding(code, modifiers))
+                    .collect();
+                content.push_str(&format!("  {}", preview.join(" ")));
+                if recorded.keys.len() > 20 {
+                    content.push_str(&format!(" ... and {} more", recorded.keys.len() - 20));
+                }
+                content.push_str("\n\n");
+            }
+        }
+
+        // Create a new buffer for the macro list
+        let buffer_id = BufferId(self.next_buffer_id);
+        self.next_buffer_id += 1;
+
+        let state = EditorState::new(
+            self.terminal_width,
+            self.terminal_height,
+            self.config.editor.large_file_threshold_bytes as usize,
+        );
+
+        self.buffers.insert(buffer_id, state);
+        self.event_logs.insert(buffer_id, EventLog::new());
+
+        // Set buffer content
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.buffer = crate::model::buffer::Buffer::from_str(
+                &content,
+                self.config.editor.large_file_threshold_bytes as usize,
+            );
+        }
+
+        // Set metadata
+        let metadata = BufferMetadata {
+            kind: BufferKind::Virtual {
+                mode: "macro-list".to_string(),
+            },
+            display_name: "*Macros*".to_string(),
+            lsp_enabled: false,
+            lsp_disabled_reason: Some("Virtual macro list buffer".to_string()),
+            read_only: true,
+            binary: false,
+            scratch: false,
+        };
+        self.buffer_metadata.insert(buffer_id, metadata);
+
+        // Switch to the new buffer
+        self.active_buffer = buffer_id;
+        self.set_status_message(format!("Showing {} recorded macro(s)", self.macros.len()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_macro_replay() {
+        assert_eq!(parse_macro_replay("a"), Some(('a', 1)));
+        assert_eq!(parse_macro_replay(" 3 "), Some(('3', 1)));
+        assert_eq!(parse_macro_replay("a 12"), Some(('a', 12)));
+        assert_eq!(parse_macro_replay("q5"), Some(('q', 5)));
+        assert_eq!(parse_macro_replay(""), None);
+        assert_eq!(parse_macro_replay("!"), None);
+        assert_eq!(parse_macro_replay("a 0"), None);
+        assert_eq!(parse_macro_replay("a x"), None);
+    }
+}
@@ -1,14 +1,63 @@
+mod accessibility;
 mod async_messages;
+mod audit_log;
+mod buffer_diff;
+mod buffer_groups;
+mod bulk_close;
+mod closed_buffers;
+mod command_arguments;
+mod command_dry_run;
+mod commit_mode;
+mod composite_commands;
+mod confirm_dialog;
+mod edit_transactions;
+mod evaluation;
+mod export;
 mod file_explorer;
+mod file_explorer_filter;
+mod file_filter;
+mod file_locks;
 pub mod file_open;
 mod file_open_input;
+mod focus_mode;
+mod git_status;
 mod help;
+mod idle_lock;
 mod input;
+mod keybinding_browser;
+mod latency;
+mod macros;
+mod open_to_side;
+pub mod pager;
+mod paste;
+mod path_prompt;
 mod plugin_commands;
+mod plugin_jobs;
+mod plugin_panels;
+mod project_env;
+mod project_scan;
+mod project_tasks;
+mod quit_review;
+mod recent_buffers;
+mod reflow;
 mod render;
+mod running_tasks;
+mod scratch_buffers;
 pub mod script_control;
+mod scroll_lock;
+mod search_index;
 pub mod session;
+pub mod shell_integration;
+mod statistics;
+mod suggestion_actions;
+mod suggestion_preview;
+mod symbol_index;
+mod text_changes;
+mod text_objects;
 mod types;
+mod user_keybindings;
+mod variables;
+mod vim;
 
 use std::path::Component;
 
@@ -44,21 +93,37 @@ pub(crate) fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
     }
 }
 
+/// Device and inode of a file, to recognize hard links to the same file
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
 use self::types::{
     Bookmark, CachedLayout, EventLineInfo, InteractiveReplaceState, LspMessageEntry,
-    LspProgressInfo, MacroRecordingState, MouseState, SearchState, DEFAULT_BACKGROUND_FILE,
+    LspProgressInfo, MacroRecordingState, MouseState, RecordedMacro, SearchState,
+    DEFAULT_BACKGROUND_FILE,
 };
 use crate::config::Config;
 use crate::input::actions::action_to_events as convert_action_to_events;
-use crate::input::buffer_mode::ModeRegistry;
+use crate::input::buffer_mode::{BufferKeybindings, ModeRegistry};
 use crate::input::command_registry::CommandRegistry;
 use crate::input::commands::Suggestion;
-use crate::input::keybindings::{Action, KeyContext, KeybindingResolver};
+use crate::input::keybindings::{Action, KeyContext, KeyContextStack, KeybindingResolver};
 use crate::input::multi_cursor::{
     add_cursor_above, add_cursor_at_next_match, add_cursor_below, AddCursorResult,
 };
 use crate::input::position_history::PositionHistory;
+use crate::model::edit::ConflictPolicy;
 use crate::model::event::{CursorId, Event, EventLog, SplitDirection, SplitId};
+use crate::primitives::redaction::SecretRedactor;
 use crate::services::async_bridge::{AsyncBridge, AsyncMessage};
 use crate::services::fs::{FsBackend, FsManager, LocalFsBackend};
 use crate::services::lsp::client::LspServerConfig;
@@ -70,11 +135,12 @@ use crate::state::EditorState;
 use crate::view::file_tree::{FileTree, FileTreeView};
 use crate::view::prompt::{Prompt, PromptType};
 use crate::view::split::{SplitManager, SplitViewState};
+use crate::view::status_message::{StatusLevel, StatusMessageQueue};
 use crate::view::ui::{
-    FileExplorerRenderer, SplitRenderer, StatusBarRenderer, SuggestionsRenderer,
+    FileExplorerRenderer, PreviewRenderer, SplitRenderer, StatusBarRenderer, SuggestionsRenderer,
 };
 use crossterm::event::{KeyCode, KeyModifiers};
-use lsp_types::{Position, Range as LspRange, TextDocumentContentChangeEvent};
+use lsp_types::TextDocumentContentChangeEvent;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     Frame,
@@ -139,8 +205,8 @@ pub struct Editor {
     /// Should the editor quit?
     should_quit: bool,
 
-    /// Status message (shown in status bar)
-    status_message: Option<String>,
+    /// Status messages (shown in status bar)
+    status_messages: StatusMessageQueue,
 
     /// Plugin-provided status message (displayed alongside the core status)
     plugin_status_message: Option<String>,
@@ -161,6 +227,9 @@ pub struct Editor {
     /// Buffer mode registry (for buffer-local keybindings)
     mode_registry: ModeRegistry,
 
+    /// Keybindings plugins installed on individual buffers
+    buffer_keybindings: BufferKeybindings,
+
     /// Tokio runtime for async I/O tasks
     tokio_runtime: Option<tokio::runtime::Runtime>,
 
@@ -191,6 +260,12 @@ pub struct Editor {
     /// Whether mouse capture is enabled
     mouse_enabled: bool,
 
+    /// Typing latency, from key press to paint
+    latency: crate::services::latency::LatencyTracer,
+
+    /// Whether the performance overlay (latency percentiles) is shown
+    show_performance_overlay: bool,
+
     /// Current keybinding context
     key_context: KeyContext,
 
@@ -215,6 +290,9 @@ pub struct Editor {
     /// Pending LSP go-to-definition request ID (if any)
     pending_goto_definition_request: Option<u64>,
 
+    /// Pane side the pending go-to-definition opens on (None = in place)
+    pending_goto_definition_side: Option<open_to_side::OpenSide>,
+
     /// Pending LSP hover request ID (if any)
     pending_hover_request: Option<u64>,
 
@@ -224,6 +302,9 @@ pub struct Editor {
     /// Symbol name for pending references request
     pending_references_symbol: String,
 
+    /// Buffer and revision a pending LSP rename was requested against
+    pending_rename_revision: Option<(BufferId, u64)>,
+
     /// Pending LSP signature help request ID (if any)
     pending_signature_help_request: Option<u64>,
 
@@ -246,10 +327,15 @@ pub struct Editor {
     /// Search highlight namespace (for efficient bulk removal)
     search_namespace: crate::view::overlay::OverlayNamespace,
 
+    /// When the matches of the search prompt's query are to be counted
+    /// (counting waits until typing pauses)
+    search_count_due: Option<std::time::Instant>,
+
     /// LSP diagnostic namespace (for filtering and bulk removal)
     lsp_diagnostic_namespace: crate::view::overlay::OverlayNamespace,
 
-    /// Pending search range that should be reused when the next search is confirmed
+    /// Selection captured when the search prompt was opened
+    /// Searches and replacements are restricted to it while `search_in_selection` is on
     pending_search_range: Option<Range<usize>>,
 
     /// Interactive replace state (if interactive replace is active)
@@ -285,6 +371,18 @@ pub struct Editor {
     /// Replace history (for replace operations)
     replace_history: crate::input::input_history::InputHistory,
 
+    /// Command palette history (names of executed commands)
+    command_history: crate::input::input_history::InputHistory,
+
+    /// Open File history (paths of opened files)
+    file_history: crate::input::input_history::InputHistory,
+
+    /// Go to Line history
+    goto_line_history: crate::input::input_history::InputHistory,
+
+    /// Mode and query of the last confirmed palette prompt, for "Repeat Last Palette Query"
+    last_palette_query: Option<(PromptType, String)>,
+
     /// LSP progress tracking (token -> progress info)
     lsp_progress: std::collections::HashMap<String, LspProgressInfo>,
 
@@ -314,9 +412,96 @@ pub struct Editor {
     search_use_regex: bool,
     /// Whether to confirm each replacement (interactive/query-replace mode)
     search_confirm_each: bool,
+    /// Whether the current search/replace is restricted to `pending_search_range`
+    search_in_selection: bool,
+
+    /// Preview pane state for the theme, tab and file pickers
+    preview_state: suggestion_preview::PreviewState,
+
+    /// Screen reader announcements (inactive unless accessibility mode is on)
+    announcement_state: accessibility::AnnouncementState,
+
+    /// What to print on exit for `--pick` / `--pick-dir` (None outside pick mode)
+    pick_mode: Option<shell_integration::PickMode>,
+    /// File picked with Enter in the file explorer (in pick mode)
+    picked_path: Option<std::path::PathBuf>,
+
+    /// Open confirmation dialog and the action waiting on it
+    /// (takes all input until it is answered)
+    confirm_dialog: Option<confirm_dialog::PendingConfirm>,
+
+    /// Letters over the panes a file can be opened in
+    /// (takes all input until one is picked)
+    pane_chooser: Option<open_to_side::PaneChooser>,
+
+    /// Buffers from the most to the least recently used
+    recent_buffers: Vec<BufferId>,
+
+    /// Ctrl+Tab cycle in progress (see `recent_buffers`)
+    recent_buffer_cycle: Option<recent_buffers::RecentBufferCycle>,
+
+    /// Closed buffers "Reopen Closed Buffer" can bring back, the most
+    /// recently closed last
+    closed_buffers: Vec<closed_buffers::ClosedBuffer>,
+
+    /// Named groups of buffers (see `buffer_groups`)
+    buffer_groups: Vec<buffer_groups::BufferGroup>,
+
+    /// Group the tabs are limited to, if any
+    active_buffer_group: Option<String>,
+
+    /// Side-by-side diff being shown (see `buffer_diff`)
+    buffer_diff: Option<buffer_diff::BufferDiff>,
+
+    /// Panes scrolling together (see `scroll_lock`)
+    scroll_lock: Option<scroll_lock::ScrollLock>,
+
+    /// Dims text away from the cursor while on (see `focus_mode`)
+    focus_mode: Option<focus_mode::FocusMode>,
+
+    /// The git commit message being edited (see `commit_mode`)
+    commit_mode: Option<commit_mode::CommitMode>,
+
+    /// Buffer shown by `--pager`
+    pager: Option<pager::PagerState>,
+
+    /// Masks secrets on screen while "Redact Secrets" is on
+    redactor: Option<SecretRedactor>,
+
+    /// Set while the screen is locked (see `idle_lock`)
+    idle_lock: Option<idle_lock::IdleLock>,
+
+    /// When the last key, mouse or paste input arrived
+    last_user_input: std::time::Instant,
 
-    /// Macro storage (key -> list of recorded actions)
-    macros: HashMap<char, Vec<Action>>,
+    /// Edits, undos and time active this session (see `statistics`)
+    session_stats: statistics::SessionStats,
+
+    /// Files this editor holds an advisory lock on (`editor.lock_files`)
+    file_locks: HashSet<PathBuf>,
+
+    /// Jobs started by plugins that are still running
+    plugin_jobs: HashMap<u64, plugin_jobs::PluginJob>,
+
+    /// Background tasks started with `spawn_task`, oldest first
+    running_tasks: std::collections::BTreeMap<u64, running_tasks::RunningTask>,
+
+    /// Last ID given to a background task
+    next_task_id: u64,
+
+    /// Frame of the status bar spinner shown while tasks run
+    task_spinner_frame: usize,
+
+    /// Panels docked by plugins, in registration order
+    plugin_panels: Vec<plugin_panels::PluginPanel>,
+    /// Panel that has the keyboard focus (if any)
+    focused_panel: Option<String>,
+
+    /// Macro storage (register -> recorded keys)
+    macros: HashMap<char, RecordedMacro>,
+
+    /// Registers of the macros being replayed (innermost last)
+    macros_playing: Vec<char>,
 
     /// Macro recording state (Some(key) if recording, None otherwise)
     macro_recording: Option<MacroRecordingState>,
@@ -333,6 +518,9 @@ pub struct Editor {
     /// Flag set by plugin commands that need a render (e.g., RefreshLines)
     plugin_render_requested: bool,
 
+    /// Actions plugins set for gutter clicks (override `config.gutter`)
+    plugin_gutter_actions: HashMap<crate::view::margin::GutterColumn, String>,
+
     /// Pending chord sequence for multi-key bindings (e.g., C-x C-s in Emacs)
     /// Stores the keys pressed so far in a chord sequence
     chord_state: Vec<(crossterm::event::KeyCode, crossterm::event::KeyModifiers)>,
@@ -345,6 +533,9 @@ pub struct Editor {
     /// Used when closing a modified buffer that needs to be saved first
     pending_close_buffer: Option<BufferId>,
 
+    /// Quit review waiting on a SaveFileAs for an unnamed buffer
+    pending_quit_review: Option<quit_review::QuitReview>,
+
     /// Whether auto-revert mode is enabled (automatically reload files when changed on disk)
     auto_revert_enabled: bool,
 
@@ -374,6 +565,79 @@ pub struct Editor {
 
     /// Last auto-save time for rate limiting
     last_auto_save: std::time::Instant,
+
+    /// When the session was last snapshotted
+    last_session_snapshot: std::time::Instant,
+
+    /// Hash of the last snapshot's content (unchanged sessions aren't snapshotted again)
+    last_session_snapshot_hash: Option<u64>,
+
+    /// Name of the current session (None unless a named session is in use)
+    session_name: Option<String>,
+
+    /// Tasks from the project's `.fresh/tasks.toml`
+    project_tasks: Vec<crate::services::project_tasks::ProjectTask>,
+
+    /// Composite commands currently running (nested ones included)
+    command_sequence_depth: usize,
+
+    /// Finds text objects, including the ones registered by plugins
+    text_objects: crate::primitives::text_objects::TextObjectFinder,
+
+    /// Include/exclude globs from the config and `.fresh/project.toml`
+    file_filter: Arc<crate::services::file_filter::FileFilter>,
+
+    /// Definitions in the project's files (see `services::symbol_index`)
+    symbol_index: crate::services::symbol_index::SymbolIndex,
+
+    /// Background task bringing the symbol index up to date
+    symbol_index_task: Option<running_tasks::TaskHandle>,
+
+    /// Trigram index of the project's files (see `services::search_index`),
+    /// shared with plugins; None until it has been built
+    search_index: Option<Arc<RwLock<crate::services::search_index::SearchIndex>>>,
+
+    /// Background task bringing the search index up to date
+    search_index_task: Option<running_tasks::TaskHandle>,
+
+    /// The project's files (see `services::project_scan`), shared with
+    /// plugins; None until scanned, and while scanning again
+    project_scan: Option<Arc<crate::services::project_scan::ProjectScan>>,
+
+    /// Background task scanning the project
+    project_scan_task: Option<running_tasks::TaskHandle>,
+
+    /// Files were added or removed during the running scan
+    project_scan_outdated: bool,
+
+    /// Work to start once the running scan is done
+    project_scan_waiting: Vec<project_scan::ProjectScanUser>,
+
+    /// Git status of the project's files, shown in the file explorer
+    git_statuses: Option<crate::services::git_status::GitStatuses>,
+
+    /// Background task reading the git status
+    git_status_task: Option<running_tasks::TaskHandle>,
+
+    /// Files were saved while the git status was being read
+    git_status_outdated: bool,
+
+    /// Environment loaded from the project's `.envrc`
+    project_env: Option<Arc<crate::services::project_env::ProjectEnv>>,
+
+    /// Background task reading the `.envrc`
+    project_env_task: Option<running_tasks::TaskHandle>,
+
+    /// Bindings from the user's `keybindings.toml`, applied again whenever
+    /// the keybinding resolver is rebuilt
+    user_keybindings: Vec<crate::config::Keybinding>,
+
+    /// When the first key of the pending chord was pressed, for the chord timeout
+    chord_started: Option<std::time::Instant>,
+
+    /// Mode, count and pending operator of vim-style editing
+    /// (when `editor.vim_mode` is on)
+    vim: crate::input::vim::VimState,
 }
 
 impl Editor {
@@ -593,8 +857,9 @@ impl Editor {
         let file_explorer_width = config.file_explorer.width;
         let recovery_enabled = config.editor.recovery_enabled;
         let auto_save_interval_secs = config.editor.auto_save_interval_secs;
+        let announcement_state = accessibility::AnnouncementState::new(&config.accessibility);
 
-        Ok(Editor {
+        let mut editor = Editor {
             buffers,
             active_buffer: buffer_id,
             event_logs,
@@ -608,7 +873,7 @@ impl Editor {
             keybindings,
             clipboard: crate::services::clipboard::Clipboard::new(),
             should_quit: false,
-            status_message: None,
+            status_messages: StatusMessageQueue::new(),
             plugin_status_message: None,
             prompt: None,
             terminal_width: width,
@@ -616,6 +881,7 @@ impl Editor {
             lsp: Some(lsp),
             buffer_metadata,
             mode_registry: ModeRegistry::new(),
+            buffer_keybindings: BufferKeybindings::new(),
             tokio_runtime,
             async_bridge: Some(async_bridge),
             split_manager,
@@ -625,6 +891,8 @@ impl Editor {
             file_explorer_visible: false,
             file_explorer_width_percent: file_explorer_width,
             mouse_enabled: true,
+            latency: crate::services::latency::LatencyTracer::new(),
+            show_performance_overlay: false,
             key_context: KeyContext::Normal,
             menu_state: crate::view::ui::MenuState::new(),
             working_dir,
@@ -633,9 +901,11 @@ impl Editor {
             next_lsp_request_id: 0,
             pending_completion_request: None,
             pending_goto_definition_request: None,
+            pending_goto_definition_side: None,
             pending_hover_request: None,
             pending_references_request: None,
             pending_references_symbol: String::new(),
+            pending_rename_revision: None,
             pending_signature_help_request: None,
             pending_code_actions_request: None,
             pending_inlay_hints_request: None,
@@ -645,6 +915,7 @@ impl Editor {
             search_namespace: crate::view::overlay::OverlayNamespace::from_string(
                 "search".to_string(),
             ),
+            search_count_due: None,
             lsp_diagnostic_namespace: crate::view::overlay::OverlayNamespace::from_string(
                 "lsp-diagnostic".to_string(),
             ),
@@ -685,6 +956,10 @@ impl Editor {
                     }
                 }
             },
+            command_history: crate::input::input_history::InputHistory::new(),
+            file_history: crate::input::input_history::InputHistory::new(),
+            goto_line_history: crate::input::input_history::InputHistory::new(),
+            last_palette_query: None,
             lsp_progress: std::collections::HashMap::new(),
             lsp_server_statuses: std::collections::HashMap::new(),
             lsp_window_messages: Vec::new(),
@@ -696,14 +971,45 @@ impl Editor {
             search_whole_word: false,
             search_use_regex: false,
             search_confirm_each: false,
+            search_in_selection: false,
+            preview_state: Default::default(),
+            announcement_state,
+            pick_mode: None,
+            picked_path: None,
+            confirm_dialog: None,
+            pane_chooser: None,
+            recent_buffers: Vec::new(),
+            recent_buffer_cycle: None,
+            closed_buffers: Vec::new(),
+            buffer_groups: Vec::new(),
+            active_buffer_group: None,
+            buffer_diff: None,
+            scroll_lock: None,
+            focus_mode: None,
+            commit_mode: None,
+            pager: None,
+            redactor: None,
+            idle_lock: None,
+            last_user_input: std::time::Instant::now(),
+            session_stats: statistics::SessionStats::default(),
+            file_locks: HashSet::new(),
+            plugin_jobs: HashMap::new(),
+            running_tasks: std::collections::BTreeMap::new(),
+            next_task_id: 0,
+            task_spinner_frame: 0,
+            plugin_panels: Vec::new(),
+            focused_panel: None,
             macros: HashMap::new(),
+            macros_playing: Vec::new(),
             macro_recording: None,
             last_macro_register: None,
             pending_plugin_actions: Vec::new(),
             plugin_render_requested: false,
+            plugin_gutter_actions: HashMap::new(),
             chord_state: Vec::new(),
             pending_lsp_confirmation: None,
             pending_close_buffer: None,
+            pending_quit_review: None,
             auto_revert_enabled: true,
             file_watcher: None,
             watched_dirs: HashSet::new(),
@@ -723,7 +1029,46 @@ impl Editor {
                 })
             },
             last_auto_save: std::time::Instant::now(),
-        })
+            last_session_snapshot: std::time::Instant::now(),
+            last_session_snapshot_hash: None,
+            session_name: None,
+            project_tasks: Vec::new(),
+            command_sequence_depth: 0,
+            text_objects: crate::primitives::text_objects::TextObjectFinder::new(),
+            file_filter: Arc::new(crate::services::file_filter::FileFilter::default()),
+            symbol_index: crate::services::symbol_index::SymbolIndex::new(),
+            symbol_index_task: None,
+            search_index: None,
+            search_index_task: None,
+            project_scan: None,
+            project_scan_task: None,
+            project_scan_outdated: false,
+            git_statuses: None,
+            git_status_task: None,
+            git_status_outdated: false,
+            project_env: None,
+            project_env_task: None,
+            project_scan_waiting: Vec::new(),
+            user_keybindings: Vec::new(),
+            chord_started: None,
+            vim: crate::input::vim::VimState::new(),
+        };
+
+        editor.register_composite_commands();
+        if let Err(e) = editor.load_project_tasks() {
+            tracing::warn!("Failed to load project tasks: {}", e);
+            editor.set_status_warning(format!("Project tasks: {}", e));
+        }
+        if let Err(e) = editor.load_file_filter() {
+            tracing::warn!("Failed to load file filter: {}", e);
+            editor.set_status_warning(format!("Project files: {}", e));
+        }
+        if let Err(e) = editor.load_user_keybindings() {
+            tracing::warn!("Failed to load key bindings: {}", e);
+            editor.set_status_warning(format!("Key bindings: {}", e));
+        }
+
+        Ok(editor)
     }
 
     /// Get a reference to the event broadcaster
@@ -804,6 +1149,17 @@ impl Editor {
             .resolve_keybinding(mode_name, code, modifiers)
     }
 
+    /// Resolve a keybinding installed on the active buffer itself
+    pub fn resolve_buffer_keybinding(
+        &self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<String> {
+        self.buffer_keybindings
+            .resolve(self.active_buffer, code, modifiers)
+            .map(str::to_string)
+    }
+
     /// Check if LSP has any active progress tasks (e.g., indexing)
     pub fn has_active_lsp_progress(&self) -> bool {
         !self.lsp_progress.is_empty()
@@ -945,20 +1301,36 @@ impl Editor {
             .map(|(id, _)| *id);
 
         if let Some(id) = already_open {
-            // Commit pending movement before switching to existing buffer
-            if id != self.active_buffer {
-                self.position_history.commit_pending_movement();
-                self.set_active_buffer(id);
+            if self.config.editor.reuse_open_buffers {
+                // Commit pending movement before switching to existing buffer
+                if id != self.active_buffer {
+                    self.position_history.commit_pending_movement();
+                    self.set_active_buffer(id);
+                }
+                return Ok(id);
             }
-            return Ok(id);
         }
 
+        // A buffer for a hard link to this file edits the same content
+        // (symlinks were resolved by canonicalizing above)
+        let hard_link_buffer = match already_open {
+            None if file_exists => file_identity(path).and_then(|identity| {
+                self.buffers
+                    .iter()
+                    .filter_map(|(id, state)| Some((*id, state.buffer.file_path()?)))
+                    .find(|(_, other)| file_identity(other) == Some(identity))
+                    .map(|(id, _)| id)
+            }),
+            _ => None,
+        };
+
         // If the current buffer is empty and unmodified, replace it instead of creating a new one
         let replace_current = {
             let current_state = self.buffers.get(&self.active_buffer).unwrap();
             current_state.buffer.is_empty()
                 && !current_state.buffer.is_modified()
                 && current_state.buffer.file_path().is_none()
+                && !self.is_scratch_buffer(self.active_buffer)
         };
 
         let buffer_id = if replace_current {
@@ -999,6 +1371,8 @@ impl Editor {
             // Make binary buffers read-only
             state.editing_disabled = true;
             tracing::info!("Detected binary file: {}", path.display());
+        } else {
+            state.detect_file_type(path, &self.config.file_types, &self.grammar_registry);
         }
 
         self.buffers.insert(buffer_id, state);
@@ -1014,8 +1388,11 @@ impl Editor {
             metadata.disable_lsp("Binary file".to_string());
         }
 
-        // Notify LSP about the newly opened file (skip for binary files)
-        if !is_binary {
+        // Notify LSP about the newly opened file (skip for binary files, and
+        // for a second buffer of a file the server already knows)
+        if already_open.is_some() {
+            metadata.disable_lsp("File is open in another buffer".to_string());
+        } else if !is_binary {
             self.notify_lsp_file_opened(path, buffer_id, &mut metadata);
         }
 
@@ -1036,6 +1413,9 @@ impl Editor {
         }
 
         self.set_active_buffer(buffer_id);
+        if !file_exists {
+            self.apply_file_template();
+        }
         // Use display_name from metadata for relative path display
         let display_name = self
             .buffer_metadata
@@ -1045,9 +1425,26 @@ impl Editor {
 
         // Show appropriate status message for binary vs regular files
         if is_binary {
-            self.status_message = Some(format!("Opened {} [binary file, read-only]", display_name));
+            self.push_status(
+                StatusLevel::Info,
+                format!("Opened {} [binary file, read-only]", display_name),
+            );
         } else {
-            self.status_message = Some(format!("Opened {}", display_name));
+            self.push_status(StatusLevel::Info, format!("Opened {}", display_name));
+        }
+
+        // Saving one of two buffers of the same file overwrites the other's edits
+        if already_open.is_some() {
+            self.set_status_warning(format!(
+                "{} is also open in another buffer; edits may conflict",
+                display_name
+            ));
+        } else if let Some(other) = hard_link_buffer {
+            self.set_status_warning(format!(
+                "{} is the same file as {} (hard link); edits may conflict",
+                display_name,
+                self.get_buffer_display_name(other)
+            ));
         }
 
         // Emit control event
@@ -1062,6 +1459,13 @@ impl Editor {
         // Track file for auto-revert and conflict detection
         self.watch_file(path);
 
+        // The first buffer of an existing file takes (or respects) its lock
+        if file_exists && already_open.is_none() && !is_binary {
+            self.check_file_lock(buffer_id, path);
+        }
+
+        self.start_commit_mode(buffer_id, path);
+
         // Fire AfterFileOpen hook for plugins
         if let Some(ref ts_manager) = self.ts_plugin_manager {
             let hook_args = crate::services::plugins::hooks::HookArgs::AfterFileOpen {
@@ -1100,7 +1504,7 @@ impl Editor {
         self.event_logs.insert(buffer_id, EventLog::new());
 
         self.set_active_buffer(buffer_id);
-        self.status_message = Some("New buffer".to_string());
+        self.push_status(StatusLevel::Info, "New buffer".to_string());
 
         buffer_id
     }
@@ -1180,6 +1584,7 @@ impl Editor {
             state.buffer.delete_bytes(0, current_len);
         }
         state.buffer.insert(0, &text);
+        state.edit_history.reset();
 
         // Clear modified flag since this is virtual buffer content setting, not user edits
         state.buffer.clear_modified();
@@ -1314,10 +1719,8 @@ impl Editor {
     /// Close the given buffer
     pub fn close_buffer(&mut self, id: BufferId) -> io::Result<()> {
         // Check for unsaved changes
-        if let Some(state) = self.buffers.get(&id) {
-            if state.buffer.is_modified() {
-                return Err(io::Error::other("Buffer has unsaved changes"));
-            }
+        if self.buffer_needs_saving(id) {
+            return Err(io::Error::other("Buffer has unsaved changes"));
         }
         self.close_buffer_internal(id)
     }
@@ -1346,10 +1749,19 @@ impl Editor {
                 .set_split_buffer(split_id, replacement_buffer);
         }
 
-        self.buffers.remove(&id);
+        self.remember_closed_buffer(id);
+        self.remove_from_buffer_groups(id);
+        let closed_path = self
+            .buffers
+            .remove(&id)
+            .and_then(|state| state.buffer.file_path().map(Path::to_path_buf));
         self.event_logs.remove(&id);
         self.seen_byte_ranges.remove(&id);
         self.buffer_metadata.remove(&id);
+        if let Some(path) = closed_path {
+            self.release_file_lock(&path);
+        }
+        self.buffer_keybindings.clear(id);
 
         // Remove buffer from panel_ids mapping if it was a panel buffer
         // This prevents stale entries when the same panel_id is reused later
@@ -1391,7 +1803,7 @@ impl Editor {
         // Get the current split's open buffers
         let active_split = self.split_manager.active_split();
         let ids = if let Some(view_state) = self.split_view_states.get(&active_split) {
-            view_state.open_buffers.clone()
+            self.group_tabs(view_state.open_buffers.clone())
         } else {
             // Fallback to all buffers if no view state
             let mut all_ids: Vec<_> = self.buffers.keys().copied().collect();
@@ -1427,7 +1839,7 @@ impl Editor {
         // Get the current split's open buffers
         let active_split = self.split_manager.active_split();
         let ids = if let Some(view_state) = self.split_view_states.get(&active_split) {
-            view_state.open_buffers.clone()
+            self.group_tabs(view_state.open_buffers.clone())
         } else {
             // Fallback to all buffers if no view state
             let mut all_ids: Vec<_> = self.buffers.keys().copied().collect();
@@ -1458,6 +1870,51 @@ impl Editor {
         }
     }
 
+    /// Move the active buffer's tab `offset` places in the current split's
+    /// tab bar (left if negative)
+    pub fn move_tab(&mut self, offset: isize) {
+        let active_split = self.split_manager.active_split();
+        let active_buffer = self.active_buffer;
+        let moved = self
+            .split_view_states
+            .get_mut(&active_split)
+            .is_some_and(|view_state| view_state.move_buffer(active_buffer, offset));
+        if moved {
+            self.ensure_active_tab_visible(active_split, active_buffer, self.terminal_width);
+        }
+    }
+
+    /// Scroll the active split's tab bar by `tabs` tabs (left if negative),
+    /// as far as the active tab stays in view
+    pub fn scroll_tabs(&mut self, tabs: isize) {
+        let active_split = self.split_manager.active_split();
+        let width = self.split_tabs_width(active_split);
+        let tab_ids = self.split_tab_ids(active_split);
+        let tab_widths =
+            crate::view::ui::tabs::tab_widths(&tab_ids, &self.buffers, &self.buffer_metadata);
+        let Some(view_state) = self.split_view_states.get_mut(&active_split) else {
+            return;
+        };
+        view_state.tab_scroll_offset = crate::view::ui::tabs::scroll_tab_offset(
+            &tab_widths,
+            view_state.tab_scroll_offset,
+            tabs,
+        );
+        self.ensure_active_tab_visible(active_split, self.active_buffer, width);
+    }
+
+    /// Width of a split's tab bar as last drawn
+    fn split_tabs_width(&self, split_id: SplitId) -> u16 {
+        self.cached_layout
+            .split_areas
+            .iter()
+            .find(|(id, ..)| *id == split_id)
+            .map(|&(_, _, content_rect, scrollbar_rect, ..)| {
+                content_rect.width + scrollbar_rect.width
+            })
+            .unwrap_or_else(|| self.effective_tabs_width())
+    }
+
     /// Navigate back in position history
     pub fn navigate_back(&mut self) {
         // Set flag to prevent recording this navigation movement
@@ -1575,7 +2032,7 @@ impl Editor {
                 self.set_status_message("Split pane horizontally".to_string());
             }
             Err(e) => {
-                self.set_status_message(format!("Error splitting pane: {}", e));
+                self.set_status_error(format!("Error splitting pane: {}", e));
             }
         }
     }
@@ -1608,7 +2065,7 @@ impl Editor {
                 self.set_status_message("Split pane vertically".to_string());
             }
             Err(e) => {
-                self.set_status_message(format!("Error splitting pane: {}", e));
+                self.set_status_error(format!("Error splitting pane: {}", e));
             }
         }
     }
@@ -1620,10 +2077,12 @@ impl Editor {
             Ok(_) => {
                 // Clean up the view state for the closed split
                 self.split_view_states.remove(&active_split);
+                // Focus moves to another pane: show its buffer and cursors
+                self.restore_current_split_view_state();
                 self.set_status_message("Closed split".to_string());
             }
             Err(e) => {
-                self.set_status_message(format!("Cannot close split: {}", e));
+                self.set_status_warning(format!("Cannot close split: {}", e));
             }
         }
     }
@@ -1644,6 +2103,18 @@ impl Editor {
         self.set_status_message("Switched to previous split".to_string());
     }
 
+    /// Zoom the active split to fill the editor area, or show all splits
+    /// again
+    pub fn toggle_zoom_pane(&mut self) {
+        if self.split_manager.toggle_zoom() {
+            self.set_status_message("Pane zoomed (Zoom Pane again to restore)".to_string());
+        } else if self.split_manager.root().count_leaves() > 1 {
+            self.set_status_message("Pane layout restored".to_string());
+        } else {
+            self.set_status_message("No other pane to hide".to_string());
+        }
+    }
+
     /// Save the current split's cursor and viewport state
     fn save_current_split_view_state(&mut self) {
         let split_id = self.split_manager.active_split();
@@ -1700,7 +2171,8 @@ impl Editor {
         }
     }
 
-    /// Adjust cursors in other splits that share the same buffer after an edit
+    /// Adjust cursors and scroll positions in other splits that share the
+    /// same buffer after an edit
     fn adjust_other_split_cursors_for_event(&mut self, event: &Event) {
         // Find the edit parameters from the event
         let adjustments = match event {
@@ -1742,10 +2214,23 @@ impl Editor {
             }
 
             if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                let mut top_edited = false;
                 for (edit_pos, old_len, new_len) in &adjustments {
                     view_state
                         .cursors
                         .adjust_for_edit(*edit_pos, *old_len, *new_len);
+                    top_edited |= view_state
+                        .viewport
+                        .adjust_for_edit(*edit_pos, *old_len, *new_len);
+                }
+                // The split's top line was edited away: show from the start
+                // of the line the edit left there
+                if top_edited {
+                    if let Some(state) = self.buffers.get_mut(&current_buffer_id) {
+                        let top = view_state.viewport.top_byte;
+                        view_state.viewport.top_byte =
+                            state.buffer.line_iterator(top, 80).current_position();
+                    }
                 }
             }
         }
@@ -1755,7 +2240,7 @@ impl Editor {
     pub fn adjust_split_size(&mut self, delta: f32) {
         let active_split = self.split_manager.active_split();
         if let Err(e) = self.split_manager.adjust_ratio(active_split, delta) {
-            self.set_status_message(format!("Cannot adjust split size: {}", e));
+            self.set_status_warning(format!("Cannot adjust split size: {}", e));
         } else {
             self.set_status_message(format!("Adjusted split size by {:.0}%", delta * 100.0));
         }
@@ -1788,6 +2273,24 @@ impl Editor {
         }
     }
 
+    /// Toggle masking secrets on screen (see `primitives::redaction`)
+    pub fn toggle_redact_secrets(&mut self) {
+        if self.redactor.take().is_some() {
+            self.set_status_message("Secrets shown".to_string());
+            return;
+        }
+        let (redactor, errors) = SecretRedactor::new(&self.config.editor.redact_patterns);
+        self.redactor = Some(redactor);
+        if errors.is_empty() {
+            self.set_status_message("Secrets redacted".to_string());
+        } else {
+            self.set_status_message(format!(
+                "Secrets redacted; invalid redact_patterns left out: {}",
+                errors.join("; ")
+            ));
+        }
+    }
+
     /// Toggle mouse capture on/off
     pub fn toggle_mouse_capture(&mut self) {
         use std::io::stdout;
@@ -1888,14 +2391,14 @@ impl Editor {
         let config_dir = match dirs::config_dir() {
             Some(dir) => dir.join("fresh"),
             None => {
-                self.set_status_message("Error: Could not determine config directory".to_string());
+                self.set_status_error("Error: Could not determine config directory".to_string());
                 return;
             }
         };
 
         // Create the config directory if it doesn't exist
         if let Err(e) = std::fs::create_dir_all(&config_dir) {
-            self.set_status_message(format!("Error creating config directory: {}", e));
+            self.set_status_error(format!("Error creating config directory: {}", e));
             return;
         }
 
@@ -1918,7 +2421,7 @@ impl Editor {
                 }
             }
             Err(e) => {
-                self.set_status_message(format!("Error saving config: {}", e));
+                self.set_status_error(format!("Error saving config: {}", e));
             }
         }
     }
@@ -1961,6 +2464,13 @@ impl Editor {
 
         self.active_buffer = buffer_id;
 
+        // While cycling with Ctrl+Tab the order only changes once a buffer is picked
+        if self.recent_buffer_cycle.is_none() {
+            self.touch_recent_buffer(previous);
+            self.touch_recent_buffer(buffer_id);
+        }
+        self.join_active_buffer_group(buffer_id);
+
         // Update split manager to show this buffer
         self.split_manager.set_active_buffer_id(buffer_id);
 
@@ -2035,17 +2545,24 @@ impl Editor {
             _ => {}
         }
 
-        // IMPORTANT: Calculate LSP changes and line info BEFORE applying to buffer!
-        // The byte positions in the events are relative to the ORIGINAL buffer,
-        // so we must convert them to LSP positions before modifying the buffer.
-        let lsp_changes = self.collect_lsp_changes(event);
+        if event.modifies_buffer() {
+            self.session_stats.edits += 1;
+        }
 
-        // Calculate line info for plugin hooks (using same pre-modification buffer state)
+        // IMPORTANT: Calculate line info for plugin hooks BEFORE applying to buffer!
+        // The byte positions in the events are relative to the ORIGINAL buffer.
         let line_info = self.calculate_event_line_info(event);
 
         // 1. Apply the event to the buffer
         self.active_state_mut().apply(event);
 
+        // 1a. Any edit ends selection mode (and Emacs mark mode)
+        if event.modifies_buffer() {
+            self.active_state_mut()
+                .cursors
+                .map(|cursor| cursor.deselect_on_move = true);
+        }
+
         // 1b. Sync cursors and viewport from EditorState to SplitViewState
         // This keeps the authoritative View state in SplitViewState up to date
         self.sync_editor_state_to_split_view_state();
@@ -2096,8 +2613,8 @@ impl Editor {
         // 3. Trigger plugin hooks for this event (with pre-calculated line info)
         self.trigger_plugin_hooks_for_event(event, line_info);
 
-        // 4. Notify LSP of the change using pre-calculated positions
-        self.send_lsp_changes_for_buffer(self.active_buffer, lsp_changes);
+        // 4. Notify LSP and plugins of the changes recorded by the buffer
+        self.flush_buffer_changes(self.active_buffer);
     }
 
     /// Trigger plugin hooks for an event (if any)
@@ -2428,7 +2945,7 @@ impl Editor {
 
         if !text.is_empty() {
             self.clipboard.copy(text);
-            self.status_message = Some("Copied".to_string());
+            self.push_status(StatusLevel::Info, "Copied".to_string());
         }
     }
 
@@ -2469,8 +2986,45 @@ impl Editor {
         }
 
         if !deletions.is_empty() {
-            self.status_message = Some("Cut".to_string());
+            self.push_status(StatusLevel::Info, "Cut".to_string());
+        }
+    }
+
+    /// Cut from each cursor to the end of its line (Emacs C-k)
+    ///
+    /// At the end of a line the line break is killed, joining the next line.
+    pub fn kill_line(&mut self) {
+        let Some(events) = self.action_to_events(Action::DeleteToLineEnd) else {
+            return;
+        };
+
+        let mut killed: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Delete {
+                    range,
+                    deleted_text,
+                    ..
+                } => Some((range.start, deleted_text.as_str())),
+                _ => None,
+            })
+            .collect();
+        if killed.is_empty() {
+            return;
         }
+        killed.sort_by_key(|(start, _)| *start);
+        let text: Vec<&str> = killed.into_iter().map(|(_, text)| text).collect();
+        self.clipboard.copy(text.join("\n"));
+
+        let event = match <[Event; 1]>::try_from(events) {
+            Ok([event]) => event,
+            Err(events) => Event::Batch {
+                events,
+                description: "Kill line".to_string(),
+            },
+        };
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
     }
 
     /// Paste the clipboard content
@@ -2480,7 +3034,11 @@ impl Editor {
             Some(text) => text,
             None => return,
         };
+        self.paste_text(paste_text);
+    }
 
+    /// Insert pasted text at the primary cursor
+    fn paste_text(&mut self, paste_text: String) {
         let state = self.active_state();
         let cursor_id = state.cursors.primary_id();
         let position = state.cursors.primary().position;
@@ -2494,7 +3052,7 @@ impl Editor {
         self.active_event_log_mut().append(event.clone());
         self.apply_event_to_active_buffer(&event);
 
-        self.status_message = Some("Pasted".to_string());
+        self.push_status(StatusLevel::Info, "Pasted".to_string());
     }
 
     /// Add a cursor at the next occurrence of the selected text
@@ -2518,10 +3076,13 @@ impl Editor {
                 self.active_event_log_mut().append(event.clone());
                 self.apply_event_to_active_buffer(&event);
 
-                self.status_message = Some(format!("Added cursor at match ({})", total_cursors));
+                self.push_status(
+                    StatusLevel::Info,
+                    format!("Added cursor at match ({})", total_cursors),
+                );
             }
             AddCursorResult::Failed { message } => {
-                self.status_message = Some(message);
+                self.push_status(StatusLevel::Warning, message);
             }
         }
     }
@@ -2546,10 +3107,13 @@ impl Editor {
                 self.active_event_log_mut().append(event.clone());
                 self.apply_event_to_active_buffer(&event);
 
-                self.status_message = Some(format!("Added cursor above ({})", total_cursors));
+                self.push_status(
+                    StatusLevel::Info,
+                    format!("Added cursor above ({})", total_cursors),
+                );
             }
             AddCursorResult::Failed { message } => {
-                self.status_message = Some(message);
+                self.push_status(StatusLevel::Warning, message);
             }
         }
     }
@@ -2574,10 +3138,13 @@ impl Editor {
                 self.active_event_log_mut().append(event.clone());
                 self.apply_event_to_active_buffer(&event);
 
-                self.status_message = Some(format!("Added cursor below ({})", total_cursors));
+                self.push_status(
+                    StatusLevel::Info,
+                    format!("Added cursor below ({})", total_cursors),
+                );
             }
             AddCursorResult::Failed { message } => {
-                self.status_message = Some(message);
+                self.push_status(StatusLevel::Warning, message);
             }
         }
     }
@@ -2589,8 +3156,10 @@ impl Editor {
             .buffer
             .file_path()
             .map(|p| p.to_path_buf());
+        let audit = path.as_deref().and_then(|p| self.begin_save_audit(p));
         self.active_state_mut().buffer.save()?;
-        self.status_message = Some("Saved".to_string());
+        self.push_status(StatusLevel::Info, "Saved".to_string());
+        self.finish_save_audit(audit);
 
         // Mark the event log position as saved (for undo modified tracking)
         self.active_event_log_mut().mark_saved();
@@ -2607,6 +3176,14 @@ impl Editor {
         // Notify LSP of save
         self.notify_lsp_save();
 
+        // Re-read the file's definitions and trigrams
+        if let Some(ref p) = path {
+            self.project_path_changed(p);
+            self.refresh_git_status();
+            self.update_symbol_index_for(p);
+            self.update_search_index_for(p);
+        }
+
         // Delete recovery file (buffer is now saved)
         let _ = self.delete_buffer_recovery(self.active_buffer);
 
@@ -2635,19 +3212,190 @@ impl Editor {
         Ok(())
     }
 
+    /// Save the active buffer to a new path ("Save As")
+    ///
+    /// Closes the buffer afterwards if it was waiting on the save to close.
+    pub(crate) fn save_as(&mut self, full_path: PathBuf) {
+        // Debug: log event log state before save
+        let before_idx = self.active_event_log().current_index();
+        let before_len = self.active_event_log().len();
+        tracing::debug!(
+            "SaveFileAs BEFORE: event_log index={}, len={}",
+            before_idx,
+            before_len
+        );
+
+        // Create missing parent directories (e.g., "Save As" to "notes/2024/todo.txt")
+        if let Some(parent) = full_path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                self.pending_close_buffer = None;
+                self.pending_quit_review = None;
+                self.set_status_error(format!("Error saving file: {}", e));
+                return;
+            }
+        }
+
+        let old_path = self
+            .active_state()
+            .buffer
+            .file_path()
+            .map(Path::to_path_buf);
+
+        // Save the buffer to the new file
+        let audit = self.begin_save_audit(&full_path);
+        match self.active_state_mut().buffer.save_to_file(&full_path) {
+            Ok(()) => {
+                self.finish_save_audit(audit);
+
+                // Debug: log event log state after buffer save
+                let after_save_idx = self.active_event_log().current_index();
+                let after_save_len = self.active_event_log().len();
+                tracing::debug!(
+                    "SaveFileAs AFTER buffer.save_to_file: event_log index={}, len={}",
+                    after_save_idx,
+                    after_save_len
+                );
+
+                // Bind the buffer to its new file everywhere
+                if old_path.as_ref() != Some(&full_path) {
+                    self.rename_buffer(self.active_buffer, full_path.clone());
+                }
+
+                // Mark the event log position as saved (for undo modified tracking)
+                self.active_event_log_mut().mark_saved();
+                tracing::debug!(
+                    "SaveFileAs AFTER mark_saved: event_log index={}, len={}",
+                    self.active_event_log().current_index(),
+                    self.active_event_log().len()
+                );
+
+                // Record the file modification time so auto-revert won't trigger
+                // for our own save. This is critical for preserving undo history.
+                if let Ok(metadata) = std::fs::metadata(&full_path) {
+                    if let Ok(mtime) = metadata.modified() {
+                        self.file_mod_times.insert(full_path.clone(), mtime);
+                    }
+                }
+
+                // Notify LSP of the save (the rename above already sent didOpen)
+                self.notify_lsp_save();
+                self.project_path_changed(&full_path);
+                self.refresh_git_status();
+                self.update_symbol_index_for(&full_path);
+                self.update_search_index_for(&full_path);
+
+                // Emit file saved event
+                self.emit_event(
+                    crate::model::control_event::events::FILE_SAVED.name,
+                    serde_json::json!({"path": full_path.display().to_string()}),
+                );
+
+                // Fire AfterFileSave hook for plugins
+                if let Some(ref ts_manager) = self.ts_plugin_manager {
+                    let hook_args = crate::services::plugins::hooks::HookArgs::AfterFileSave {
+                        buffer_id: self.active_buffer,
+                        path: full_path.clone(),
+                    };
+                    ts_manager.run_hook("after_file_save", hook_args);
+                }
+
+                // Check if we should close the buffer after saving
+                if let Some(buffer_to_close) = self.pending_close_buffer.take() {
+                    if let Err(e) = self.force_close_buffer(buffer_to_close) {
+                        self.set_status_message(format!("Saved, but cannot close buffer: {}", e));
+                    } else {
+                        self.set_status_message("Saved and closed".to_string());
+                    }
+                } else {
+                    self.set_status_message(format!("Saved as: {}", full_path.display()));
+                }
+
+                // Carry on reviewing unsaved buffers if this save was part of quitting
+                if let Some(review) = self.pending_quit_review.take() {
+                    self.continue_quit_review(review.remaining, review.total);
+                }
+            }
+            Err(e) => {
+                // Clear pending close on error
+                self.pending_close_buffer = None;
+                self.pending_quit_review = None;
+                self.set_status_error(format!("Error saving file: {}", e));
+            }
+        }
+    }
+
+    /// Bind a buffer to a new file path
+    ///
+    /// Called after "Save As" and when a file is renamed in the file explorer.
+    /// Updates everything that knows the buffer by its path: the metadata (tab
+    /// title and status bar), syntax highlighting, the LSP server (didClose for
+    /// the old path, didOpen for the new one), the file watcher, the Open File
+    /// history and the file explorer. Plugins are told through the
+    /// `buffer_renamed` hook (the git gutter recomputes its indicators there).
+    pub fn rename_buffer(&mut self, buffer_id: BufferId, new_path: PathBuf) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        state.buffer.set_file_path(new_path.clone());
+        state.set_language_from_name(&new_path.to_string_lossy(), &self.grammar_registry);
+        state.detect_file_type(&new_path, &self.config.file_types, &self.grammar_registry);
+
+        let old_metadata = self.buffer_metadata.get(&buffer_id).cloned();
+        let old_path = old_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.file_path().cloned());
+        if let Some(old_metadata) = &old_metadata {
+            self.notify_lsp_file_closed(old_metadata);
+        }
+        if let Some(old_path) = &old_path {
+            self.file_mod_times.remove(old_path);
+            self.move_file_lock(old_path, &new_path);
+        }
+
+        let mut metadata = BufferMetadata::with_file(new_path.clone(), &self.working_dir);
+        if old_metadata.as_ref().is_some_and(|m| m.binary) {
+            metadata.binary = true;
+            metadata.read_only = true;
+            metadata.disable_lsp("Binary file".to_string());
+        } else {
+            self.notify_lsp_file_opened(&new_path, buffer_id, &mut metadata);
+        }
+        self.buffer_metadata.insert(buffer_id, metadata);
+
+        self.watch_file(&new_path);
+        self.file_history
+            .push(new_path.to_string_lossy().into_owned());
+        self.file_explorer_show_new_file(&new_path, buffer_id == self.active_buffer);
+
+        if let Some(ref ts_manager) = self.ts_plugin_manager {
+            let hook_args = crate::services::plugins::hooks::HookArgs::BufferRenamed {
+                buffer_id,
+                old_path,
+                new_path,
+            };
+            ts_manager.run_hook("buffer_renamed", hook_args);
+        }
+    }
+
     /// Revert the active buffer to the last saved version on disk
     /// Returns Ok(true) if reverted, Ok(false) if no file path, Err on failure
     pub fn revert_file(&mut self) -> io::Result<bool> {
         let path = match self.active_state().buffer.file_path() {
             Some(p) => p.to_path_buf(),
             None => {
-                self.status_message = Some("Buffer has no file to revert to".to_string());
+                self.push_status(
+                    StatusLevel::Warning,
+                    "Buffer has no file to revert to".to_string(),
+                );
                 return Ok(false);
             }
         };
 
         if !path.exists() {
-            self.status_message = Some(format!("File does not exist: {}", path.display()));
+            self.push_status(
+                StatusLevel::Warning,
+                format!("File does not exist: {}", path.display()),
+            );
             return Ok(false);
         }
 
@@ -2682,6 +3430,9 @@ impl Editor {
         // Replace the current buffer with the new state
         let buffer_id = self.active_buffer;
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            // Keep counting revisions, so edits against the old text are rejected
+            new_state.edit_history = std::mem::take(&mut state.edit_history);
+            new_state.edit_history.reset();
             *state = new_state;
             // Apply line wrap setting from config
             state.viewport.line_wrap_enabled = self.config.editor.line_wrap;
@@ -2705,7 +3456,7 @@ impl Editor {
         // Notify LSP that the file was changed
         self.notify_lsp_file_changed(&path);
 
-        self.status_message = Some("Reverted to saved file".to_string());
+        self.push_status(StatusLevel::Info, "Reverted to saved file".to_string());
         Ok(true)
     }
 
@@ -2716,12 +3467,12 @@ impl Editor {
         if self.auto_revert_enabled {
             // Start file watcher if not already running
             self.start_file_watcher();
-            self.status_message = Some("Auto-revert enabled".to_string());
+            self.push_status(StatusLevel::Info, "Auto-revert enabled".to_string());
         } else {
             // Stop file watcher
             self.file_watcher = None;
             self.watched_dirs.clear();
-            self.status_message = Some("Auto-revert disabled".to_string());
+            self.push_status(StatusLevel::Info, "Auto-revert disabled".to_string());
         }
     }
 
@@ -2793,7 +3544,10 @@ impl Editor {
             }
             Err(e) => {
                 tracing::error!("Failed to create file watcher: {}", e);
-                self.status_message = Some(format!("Failed to start file watcher: {}", e));
+                self.push_status(
+                    StatusLevel::Error,
+                    format!("Failed to start file watcher: {}", e),
+                );
             }
         }
     }
@@ -2834,10 +3588,10 @@ impl Editor {
         }
 
         // Get text before borrowing lsp
-        let text = match self
+        let (text, version) = match self
             .buffers
             .get(&buffer_id)
-            .and_then(|state| state.buffer.to_string())
+            .and_then(|state| Some((state.buffer.to_string()?, state.lsp_version())))
         {
             Some(t) => t,
             None => {
@@ -2879,7 +3633,7 @@ impl Editor {
                 if let Some(client) = lsp.get_or_spawn(&language) {
                     // Send didOpen
                     tracing::info!("Sending didOpen to LSP for: {}", uri.as_str());
-                    if let Err(e) = client.did_open(uri.clone(), text, language.clone()) {
+                    if let Err(e) = client.did_open(uri.clone(), text, language.clone(), version) {
                         tracing::warn!("Failed to send didOpen to LSP: {}", e);
                         return;
                     }
@@ -2939,6 +3693,29 @@ impl Editor {
         }
     }
 
+    /// Notify the LSP server that a buffer's file is no longer open
+    ///
+    /// Only sent to a server that is already running.
+    fn notify_lsp_file_closed(&mut self, metadata: &BufferMetadata) {
+        if !metadata.lsp_enabled {
+            return;
+        }
+        let (Some(path), Some(uri)) = (metadata.file_path(), metadata.file_uri()) else {
+            return;
+        };
+        let Some(language) = detect_language(path) else {
+            return;
+        };
+
+        self.diagnostic_result_ids.remove(uri.as_str());
+        if let Some(handle) = self.lsp.as_ref().and_then(|lsp| lsp.get_handle(&language)) {
+            tracing::info!("Sending didClose to LSP for: {}", uri.as_str());
+            if let Err(e) = handle.did_close(uri.clone()) {
+                tracing::warn!("Failed to send didClose to LSP: {}", e);
+            }
+        }
+    }
+
     /// Add a file to the file watcher (called when opening files)
     /// We watch the parent directory instead of the file itself to handle
     /// atomic saves (temp file + rename) which change the file's inode
@@ -2985,17 +3762,21 @@ impl Editor {
                             .buffers
                             .values()
                             .find(|s| s.buffer.file_path() == Some(path))
-                            .and_then(|state| state.buffer.to_string());
+                            .and_then(|state| {
+                                Some((state.buffer.to_string()?, state.lsp_version()))
+                            });
 
                         // Use full document sync - send the entire new content
-                        if let Some(content) = content {
+                        if let Some((content, version)) = content {
                             if let Some(client) = lsp.get_or_spawn(&language) {
                                 let content_change = TextDocumentContentChangeEvent {
                                     range: None, // None means full document replacement
                                     range_length: None,
                                     text: content,
                                 };
-                                if let Err(e) = client.did_change(lsp_uri, vec![content_change]) {
+                                if let Err(e) =
+                                    client.did_change(lsp_uri, vec![content_change], version)
+                                {
                                     tracing::warn!("Failed to notify LSP of file change: {}", e);
                                 }
                             }
@@ -3023,6 +3804,10 @@ impl Editor {
         }
 
         for buffer_id in buffer_ids {
+            // The pager takes in what its file gains as it arrives
+            if self.is_pager_buffer(buffer_id) {
+                continue;
+            }
             let state = match self.buffers.get(&buffer_id) {
                 Some(s) => s,
                 None => continue,
@@ -3048,10 +3833,13 @@ impl Editor {
 
             // If buffer has local modifications, show a warning (don't auto-revert)
             if state.buffer.is_modified() {
-                self.status_message = Some(format!(
-                    "File {} changed on disk (buffer has unsaved changes)",
-                    path.display()
-                ));
+                self.push_status(
+                    StatusLevel::Warning,
+                    format!(
+                        "File {} changed on disk (buffer has unsaved changes)",
+                        path.display()
+                    ),
+                );
                 continue;
             }
 
@@ -3126,18 +3914,8 @@ impl Editor {
     /// Request the editor to quit
     pub fn quit(&mut self) {
         // Check for unsaved buffers
-        let modified_count = self.count_modified_buffers();
-        if modified_count > 0 {
-            // Prompt user for confirmation
-            let msg = if modified_count == 1 {
-                "1 buffer has unsaved changes. Quit anyway? (y/n): ".to_string()
-            } else {
-                format!(
-                    "{} buffers have unsaved changes. Quit anyway? (y/n): ",
-                    modified_count
-                )
-            };
-            self.start_prompt(msg, PromptType::ConfirmQuitWithModified);
+        if self.count_modified_buffers() > 0 {
+            self.confirm_quit();
         } else {
             self.should_quit = true;
         }
@@ -3146,8 +3924,8 @@ impl Editor {
     /// Count the number of modified buffers
     fn count_modified_buffers(&self) -> usize {
         self.buffers
-            .values()
-            .filter(|state| state.buffer.is_modified())
+            .keys()
+            .filter(|&&id| self.buffer_needs_saving(id))
             .count()
     }
 
@@ -3299,7 +4077,9 @@ impl Editor {
             .buffers
             .iter()
             .filter_map(|(buffer_id, state)| {
-                let recovery_pending = state.buffer.is_recovery_pending();
+                // Scratch buffers are thrown away, not recovered
+                let recovery_pending =
+                    state.buffer.is_recovery_pending() && !self.is_scratch_buffer(*buffer_id);
                 if recovery_pending {
                     let path = state.buffer.file_path().map(|p| p.to_path_buf());
                     let recovery_id = self.recovery_service.get_buffer_id(path.as_deref());
@@ -3427,16 +4207,14 @@ impl Editor {
         Ok(())
     }
 
-    /// Resize all buffers to match new terminal size
+    /// Handle a terminal resize
     pub fn resize(&mut self, width: u16, height: u16) {
         // Update terminal dimensions for future buffer creation
         self.terminal_width = width;
         self.terminal_height = height;
 
-        // Resize all existing buffer EditorStates
-        for state in self.buffers.values_mut() {
-            state.resize(width, height);
-        }
+        // Buffer viewports are resized (and reflowed) when rendered, once the
+        // size of each split's content area is known
 
         // Resize all SplitViewState viewports
         for view_state in self.split_view_states.values_mut() {
@@ -3453,17 +4231,15 @@ impl Editor {
 
     /// Start a search prompt with an optional selection scope
     ///
-    /// When `use_selection_range` is true and a single-line selection is present,
-    /// the search will be restricted to that range once confirmed.
+    /// Any selection present is remembered so the search can be restricted to it
+    /// (toggleable from the prompt). When `use_selection_range` is true the
+    /// restriction starts enabled.
     fn start_search_prompt(
         &mut self,
         message: String,
         prompt_type: PromptType,
         use_selection_range: bool,
     ) {
-        // Reset any previously stored selection range
-        self.pending_search_range = None;
-
         let selection_range = {
             let state = self.active_state();
             state.cursors.primary().selection_range()
@@ -3481,9 +4257,8 @@ impl Editor {
             None
         };
 
-        if use_selection_range {
-            self.pending_search_range = selection_range;
-        }
+        self.pending_search_range = selection_range.filter(|range| !range.is_empty());
+        self.search_in_selection = use_selection_range && self.pending_search_range.is_some();
 
         // Determine the default text: selection > last history > empty
         let from_history = selected_text.is_none();
@@ -3628,7 +4403,9 @@ impl Editor {
             .unwrap_or_else(|| self.working_dir.clone());
 
         // Create the file open state
-        self.file_open_state = Some(file_open::FileOpenState::new(initial_dir.clone()));
+        let mut state = file_open::FileOpenState::new(initial_dir.clone());
+        state.fuzzy_matcher = self.config.editor.fuzzy_matcher;
+        self.file_open_state = Some(state);
 
         // Start async directory loading
         self.load_file_open_directory(initial_dir);
@@ -3672,6 +4449,11 @@ impl Editor {
             Ok(entries) => {
                 if let Some(state) = &mut self.file_open_state {
                     state.set_entries(entries);
+                    // Keep the filter typed before the listing arrived (e.g., after Tab on "~/Doc")
+                    if !state.filter.is_empty() {
+                        let filter = state.filter.clone();
+                        state.apply_filter(&filter);
+                    }
                 }
             }
             Err(e) => {
@@ -3682,18 +4464,168 @@ impl Editor {
         }
     }
 
-    /// Cancel the current prompt and return to normal mode
-    pub fn cancel_prompt(&mut self) {
+    /// Input history used by a prompt type (navigated with Up/Down)
+    fn prompt_history_mut(
+        &mut self,
+        prompt_type: &PromptType,
+    ) -> Option<&mut crate::input::input_history::InputHistory> {
+        match prompt_type {
+            PromptType::Search | PromptType::ReplaceSearch | PromptType::QueryReplaceSearch => {
+                Some(&mut self.search_history)
+            }
+            PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
+                Some(&mut self.replace_history)
+            }
+            PromptType::Command => Some(&mut self.command_history),
+            PromptType::OpenFile => Some(&mut self.file_history),
+            PromptType::GotoLine => Some(&mut self.goto_line_history),
+            _ => None,
+        }
+    }
+
+    /// Move through the prompt's input history with Up (`prev`) or Down
+    ///
+    /// Up recalls history when the input is empty, when there is no list to
+    /// move through, or while history is already being browsed; Down only
+    /// while browsing. Returns false if the key should move through the
+    /// suggestions (or file list) instead.
+    pub(crate) fn navigate_prompt_history(&mut self, prev: bool) -> bool {
+        let Some((prompt_type, input, has_list)) = self.prompt.as_ref().map(|p| {
+            (
+                p.prompt_type.clone(),
+                p.input.clone(),
+                !p.suggestions.is_empty() || p.prompt_type == PromptType::OpenFile,
+            )
+        }) else {
+            return false;
+        };
+        let Some(history) = self.prompt_history_mut(&prompt_type) else {
+            return false;
+        };
+
+        let browsing = history.is_navigating();
+        let item = if prev {
+            if !browsing && has_list && !input.is_empty() {
+                return false;
+            }
+            history.navigate_prev(&input)
+        } else {
+            if !browsing {
+                return false;
+            }
+            history.navigate_next()
+        };
+
+        let Some(text) = item else {
+            // At either end of the history: stay there while browsing
+            return browsing;
+        };
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.set_input(text.clone());
+        }
+        self.refresh_prompt_for_input(&prompt_type, &text);
+        true
+    }
+
+    /// Add confirmed prompt input to its history
+    ///
+    /// For the palette modes (commands, files, go to line, search) it also
+    /// becomes the query reopened by "Repeat Last Palette Query".
+    pub(crate) fn record_prompt_input(&mut self, prompt_type: &PromptType, input: &str) {
+        if *prompt_type == PromptType::Command {
+            // Repeating the last query must not replace it
+            let is_repeat = self
+                .command_registry
+                .read()
+                .unwrap()
+                .get_all()
+                .iter()
+                .any(|c| c.name == input && c.action == Action::RepeatLastPaletteQuery);
+            if is_repeat {
+                return;
+            }
+        }
+
+        let Some(history) = self.prompt_history_mut(prompt_type) else {
+            return;
+        };
+        history.push(input.to_string());
+        history.reset_navigation();
+
+        let is_palette = matches!(
+            prompt_type,
+            PromptType::Command | PromptType::OpenFile | PromptType::GotoLine | PromptType::Search
+        );
+        if is_palette && !input.is_empty() {
+            self.last_palette_query = Some((prompt_type.clone(), input.to_string()));
+        }
+    }
+
+    /// Reopen the last confirmed palette prompt with its query prefilled
+    pub(crate) fn repeat_last_palette_query(&mut self) {
+        let Some((prompt_type, query)) = self.last_palette_query.clone() else {
+            self.set_status_message("No palette query to repeat".to_string());
+            return;
+        };
+
+        match prompt_type {
+            PromptType::Command => {
+                self.start_prompt_with_suggestions(
+                    "Command: ".to_string(),
+                    PromptType::Command,
+                    Vec::new(),
+                );
+            }
+            PromptType::OpenFile => {
+                self.start_prompt("Open file: ".to_string(), PromptType::OpenFile);
+                self.init_file_open_state();
+            }
+            PromptType::GotoLine => {
+                self.start_prompt("Go to line: ".to_string(), PromptType::GotoLine);
+            }
+            _ => {
+                self.start_search_prompt("Search: ".to_string(), PromptType::Search, false);
+            }
+        }
+
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.set_input(query.clone());
+        }
+        if matches!(prompt_type, PromptType::GotoLine | PromptType::Search) {
+            if let Some(history) = self.prompt_history_mut(&prompt_type) {
+                // Up continues with the query before the repeated one
+                history.init_at_last();
+            }
+        }
+        self.refresh_prompt_for_input(&prompt_type, &query);
+    }
+
+    /// Update suggestions, file list or search highlights after the prompt
+    /// input was replaced (e.g., by a history item)
+    pub(crate) fn refresh_prompt_for_input(&mut self, prompt_type: &PromptType, input: &str) {
+        match prompt_type {
+            PromptType::Search | PromptType::ReplaceSearch | PromptType::QueryReplaceSearch => {
+                self.update_search_highlights(input);
+            }
+            PromptType::OpenFile => self.update_file_open_filter(),
+            PromptType::Command => self.update_prompt_suggestions(),
+            _ => {}
+        }
+    }
+
+    /// Cancel the current prompt and return to normal mode
+    pub fn cancel_prompt(&mut self) {
         // Determine prompt type and reset appropriate history navigation
+        if let Some(prompt_type) = self.prompt.as_ref().map(|p| p.prompt_type.clone()) {
+            if let Some(history) = self.prompt_history_mut(&prompt_type) {
+                history.reset_navigation();
+            }
+        }
         if let Some(ref prompt) = self.prompt {
             match &prompt.prompt_type {
                 PromptType::Search | PromptType::ReplaceSearch | PromptType::QueryReplaceSearch => {
-                    self.search_history.reset_navigation();
                     self.clear_search_highlights();
                 }
-                PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
-                    self.replace_history.reset_navigation();
-                }
                 PromptType::Plugin { custom_type } => {
                     // Fire plugin hook for prompt cancellation
                     use crate::services::plugins::hooks::HookArgs;
@@ -3718,13 +4650,28 @@ impl Editor {
                     self.file_open_state = None;
                     self.file_browser_layout = None;
                 }
+                PromptType::SaveFileAs => {
+                    // Cancelling a save that was part of quitting cancels the quit
+                    self.pending_quit_review = None;
+                }
+                PromptType::FileExplorerFilter => {
+                    self.clear_file_explorer_filter();
+                }
+                PromptType::PluginRequest { request_id, .. } => {
+                    self.send_plugin_response(
+                        crate::services::plugins::api::PluginResponse::PromptAnswered {
+                            request_id: *request_id,
+                            value: None,
+                        },
+                    );
+                }
                 _ => {}
             }
         }
 
         self.prompt = None;
-        self.pending_search_range = None;
-        self.status_message = Some("Canceled".to_string());
+        self.clear_search_scope();
+        self.push_status(StatusLevel::Info, "Canceled".to_string());
     }
 
     /// Get the confirmed input and prompt type, consuming the prompt
@@ -3743,6 +4690,17 @@ impl Editor {
                     | PromptType::StopLspServer
                     | PromptType::SelectTheme
                     | PromptType::SwitchToTab
+                    | PromptType::RestoreSessionSnapshot
+                    | PromptType::SwitchSession
+                    | PromptType::MoveToBufferGroup
+                    | PromptType::SwitchBufferGroup
+                    | PromptType::NewScratchBuffer
+                    | PromptType::DiffBuffers
+                    | PromptType::InsertSnippet
+                    | PromptType::WorkspaceSymbol
+                    | PromptType::Keybindings
+                    | PromptType::BindKeyCommand { .. }
+                    | PromptType::PluginRequest { .. }
             ) {
                 // Use the selected suggestion if any
                 if let Some(selected_idx) = prompt.selected_suggestion {
@@ -3785,19 +4743,7 @@ impl Editor {
             }
 
             // Add to appropriate history based on prompt type
-            match prompt.prompt_type {
-                PromptType::Search | PromptType::ReplaceSearch | PromptType::QueryReplaceSearch => {
-                    self.search_history.push(final_input.clone());
-                    // Reset navigation state
-                    self.search_history.reset_navigation();
-                }
-                PromptType::Replace { .. } | PromptType::QueryReplace { .. } => {
-                    self.replace_history.push(final_input.clone());
-                    // Reset navigation state
-                    self.replace_history.reset_navigation();
-                }
-                _ => {}
-            }
+            self.record_prompt_input(&prompt.prompt_type, &final_input);
 
             Some((final_input, prompt.prompt_type, selected_index))
         } else {
@@ -3837,14 +4783,70 @@ impl Editor {
     /// Set a status message to display in the status bar
     pub fn set_status_message(&mut self, message: String) {
         self.plugin_status_message = None;
-        self.status_message = Some(message);
+        self.push_status(StatusLevel::Info, message);
+    }
+
+    /// Show a warning in the status bar
+    pub fn set_status_warning(&mut self, message: String) {
+        self.plugin_status_message = None;
+        self.push_status(StatusLevel::Warning, message);
+    }
+
+    /// Show an error in the status bar
+    ///
+    /// By default errors stay up until dismissed (see `dismiss_status_message`).
+    pub fn set_status_error(&mut self, message: String) {
+        self.plugin_status_message = None;
+        self.push_status(StatusLevel::Error, message);
+    }
+
+    /// Queue a status message with the display duration configured for its level
+    fn push_status(&mut self, level: StatusLevel, message: String) {
+        let duration_ms = match level {
+            StatusLevel::Info => self.config.editor.status_message_duration_ms,
+            StatusLevel::Warning => self.config.editor.status_warning_duration_ms,
+            StatusLevel::Error => self.config.editor.status_error_duration_ms,
+        };
+        let duration = (duration_ms > 0).then(|| std::time::Duration::from_millis(duration_ms));
+        self.status_messages.push(message, level, duration);
+    }
+
+    /// Dismiss the most important status message (errors first)
+    ///
+    /// Returns false if there was no message to dismiss.
+    pub fn dismiss_status_message(&mut self) -> bool {
+        self.status_messages.dismiss()
+    }
+
+    /// Remove status messages whose display time is up
+    ///
+    /// Called from the event loop. Returns true if the status bar needs redrawing.
+    pub fn expire_status_messages(&mut self) -> bool {
+        self.status_messages.expire(std::time::Instant::now())
+    }
+
+    /// Drop a pending key chord once `chord_timeout_ms` has passed
+    ///
+    /// Returns true if a chord was dropped (the status bar needs redrawing).
+    pub fn expire_chord(&mut self) -> bool {
+        let timeout = self.config.editor.chord_timeout_ms;
+        let expired = timeout > 0
+            && self
+                .chord_started
+                .is_some_and(|started| started.elapsed().as_millis() >= u128::from(timeout));
+        if expired {
+            tracing::debug!("Chord timed out: {:?}", self.chord_state);
+            self.chord_state.clear();
+            self.chord_started = None;
+        }
+        expired
     }
 
     /// Get the current status message
     pub fn get_status_message(&self) -> Option<&String> {
         self.plugin_status_message
             .as_ref()
-            .or_else(|| self.status_message.as_ref())
+            .or_else(|| self.status_messages.latest().map(|m| &m.text))
     }
 
     /// Update prompt suggestions based on current input
@@ -3858,15 +4860,34 @@ impl Editor {
 
         match prompt_type {
             PromptType::Command => {
-                let selection_active = self.has_active_selection();
-                if let Some(prompt) = &mut self.prompt {
-                    // Use the underlying context (not Prompt context) for filtering
-                    prompt.suggestions = self.command_registry.read().unwrap().filter(
+                let when = self.when_context();
+                let contexts = self.command_context_stack();
+                let argument_suggestions = self.command_argument_suggestions(&input);
+                let mut sections = Vec::new();
+                let mut suggestions = argument_suggestions.unwrap_or_else(|| {
+                    let registry = self.command_registry.read().unwrap();
+                    // Use the underlying contexts (not Prompt context) for filtering
+                    let mut suggestions = registry.filter(
                         &input,
-                        self.key_context,
+                        &contexts,
                         &self.keybindings,
-                        selection_active,
+                        &when,
+                        self.config.editor.fuzzy_matcher.matcher(),
                     );
+                    // Before anything is typed, the last used commands come first
+                    if input.is_empty() {
+                        let recent = registry.move_recent_to_front(&mut suggestions);
+                        if recent > 0 {
+                            sections.push((0, "Recently Used".to_string()));
+                            sections.push((recent, "Other Commands".to_string()));
+                        }
+                    }
+                    suggestions
+                });
+                self.add_command_dry_runs(&mut suggestions);
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.suggestions = suggestions;
+                    prompt.sections = sections;
                     prompt.selected_suggestion = if prompt.suggestions.is_empty() {
                         None
                     } else {
@@ -3883,19 +4904,10 @@ impl Editor {
                 self.update_file_open_filter();
             }
             PromptType::SaveFileAs => {
-                // Fire plugin hook for file path completion.
-                // The hook is processed asynchronously by the plugin thread.
-                // Commands (SetPromptSuggestions) will be picked up by the main loop's
-                // process_async_messages() -> process_plugin_commands() on the next frame.
-                use crate::services::plugins::hooks::HookArgs;
-                let hook_args = HookArgs::PromptChanged {
-                    prompt_type: "save-file-as".to_string(),
-                    input,
-                };
-
-                if let Some(ref ts_manager) = self.ts_plugin_manager {
-                    ts_manager.run_hook("prompt_changed", hook_args);
-                }
+                self.update_path_prompt_suggestions();
+            }
+            PromptType::FileExplorerFilter => {
+                self.update_file_explorer_filter();
             }
             PromptType::Plugin { custom_type } => {
                 // Fire plugin hook for prompt input change
@@ -3909,9 +4921,21 @@ impl Editor {
                     ts_manager.run_hook("prompt_changed", hook_args);
                 }
             }
-            PromptType::SwitchToTab | PromptType::SelectTheme | PromptType::StopLspServer => {
+            PromptType::SwitchToTab
+            | PromptType::SelectTheme
+            | PromptType::StopLspServer
+            | PromptType::RestoreSessionSnapshot
+            | PromptType::SwitchSession
+            | PromptType::MoveToBufferGroup
+            | PromptType::SwitchBufferGroup
+            | PromptType::NewScratchBuffer
+            | PromptType::DiffBuffers
+            | PromptType::InsertSnippet
+            | PromptType::WorkspaceSymbol
+            | PromptType::BindKeyCommand { .. }
+            | PromptType::PluginRequest { .. } => {
                 // Filter suggestions using fuzzy matching
-                use crate::input::fuzzy::fuzzy_match;
+                let matcher = self.config.editor.fuzzy_matcher.matcher();
 
                 if let Some(prompt) = &mut self.prompt {
                     if let Some(original) = &prompt.original_suggestions {
@@ -3919,9 +4943,11 @@ impl Editor {
                         let mut filtered: Vec<(crate::input::commands::Suggestion, i32)> = original
                             .iter()
                             .filter_map(|s| {
-                                let result = fuzzy_match(&input, &s.text);
+                                let result = matcher.fuzzy_match(&input, &s.text);
                                 if result.matched {
-                                    Some((s.clone(), result.score))
+                                    let mut s = s.clone();
+                                    s.match_indices = result.match_positions;
+                                    Some((s, result.score))
                                 } else {
                                     None
                                 }
@@ -3940,6 +4966,43 @@ impl Editor {
                     }
                 }
             }
+            PromptType::Keybindings => {
+                // Like the fuzzy filter above, but keys and notes match too
+                // (only the action's text is highlighted)
+                let matcher = self.config.editor.fuzzy_matcher.matcher();
+
+                if let Some(prompt) = &mut self.prompt {
+                    if let Some(original) = &prompt.original_suggestions {
+                        let mut filtered: Vec<(crate::input::commands::Suggestion, i32)> = original
+                            .iter()
+                            .filter_map(|s| {
+                                let result = matcher.fuzzy_match(&input, &s.text);
+                                if result.matched {
+                                    let mut s = s.clone();
+                                    s.match_indices = result.match_positions;
+                                    return Some((s, result.score));
+                                }
+                                [s.keybinding.as_deref(), s.description.as_deref()]
+                                    .into_iter()
+                                    .flatten()
+                                    .map(|other| matcher.fuzzy_match(&input, other))
+                                    .filter(|result| result.matched)
+                                    .map(|result| result.score)
+                                    .max()
+                                    .map(|score| (s.clone(), score))
+                            })
+                            .collect();
+                        filtered.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+                        prompt.suggestions = filtered.into_iter().map(|(s, _)| s).collect();
+                        prompt.selected_suggestion = if prompt.suggestions.is_empty() {
+                            None
+                        } else {
+                            Some(0)
+                        };
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -3961,16 +5024,23 @@ impl Editor {
 
         for message in messages {
             match message {
-                AsyncMessage::LspDiagnostics { uri, diagnostics } => {
-                    self.handle_lsp_diagnostics(uri, diagnostics);
+                AsyncMessage::LspDiagnostics {
+                    uri,
+                    version,
+                    diagnostics,
+                } => {
+                    self.handle_lsp_diagnostics(uri, version, diagnostics);
                 }
                 AsyncMessage::LspInitialized { language } => {
                     tracing::info!("LSP server initialized for language: {}", language);
-                    self.status_message = Some(format!("LSP ({}) ready", language));
+                    self.push_status(StatusLevel::Info, format!("LSP ({}) ready", language));
                 }
                 AsyncMessage::LspError { language, error } => {
                     tracing::error!("LSP error for {}: {}", language, error);
-                    self.status_message = Some(format!("LSP error ({}): {}", language, error));
+                    self.push_status(
+                        StatusLevel::Error,
+                        format!("LSP error ({}): {}", language, error),
+                    );
                 }
                 AsyncMessage::LspCompletion { request_id, items } => {
                     if let Err(e) = self.handle_completion_response(request_id, items) {
@@ -4064,6 +5134,26 @@ impl Editor {
                 } => {
                     self.handle_plugin_process_output(process_id, stdout, stderr, exit_code);
                 }
+                AsyncMessage::PluginJobOutput {
+                    job_id,
+                    stream,
+                    line,
+                } => {
+                    self.handle_plugin_job_output(job_id, stream, line);
+                }
+                AsyncMessage::PluginJobExited {
+                    job_id,
+                    exit_code,
+                    killed,
+                } => {
+                    self.handle_plugin_job_exited(job_id, exit_code, killed);
+                }
+                AsyncMessage::TaskProgress { task_id, message } => {
+                    self.handle_task_progress(task_id, message);
+                }
+                AsyncMessage::TaskFinished { task_id } => {
+                    self.handle_task_finished(task_id);
+                }
                 AsyncMessage::CustomNotification {
                     language,
                     method,
@@ -4105,6 +5195,34 @@ impl Editor {
                 AsyncMessage::FileOpenDirectoryLoaded(result) => {
                     self.handle_file_open_directory_loaded(result);
                 }
+                AsyncMessage::ProjectTaskOutput { name, result } => {
+                    self.handle_project_task_output(name, result);
+                }
+                AsyncMessage::EvaluationOutput {
+                    buffer_id,
+                    insert_at,
+                    result,
+                } => {
+                    self.handle_evaluation_output(buffer_id, insert_at, result);
+                }
+                AsyncMessage::SymbolIndexUpdated { index, changed } => {
+                    self.handle_symbol_index_updated(*index, changed);
+                }
+                AsyncMessage::ProjectScanned { scan } => {
+                    self.handle_project_scanned(*scan);
+                }
+                AsyncMessage::SearchIndexUpdated { index, changed } => {
+                    self.handle_search_index_updated(*index, changed);
+                }
+                AsyncMessage::ProjectLinesCounted { stats } => {
+                    self.handle_project_lines_counted(stats);
+                }
+                AsyncMessage::GitStatusRead { statuses } => {
+                    self.handle_git_status_read(statuses);
+                }
+                AsyncMessage::ProjectEnvLoaded { result } => {
+                    self.handle_project_env_loaded(result);
+                }
             }
         }
 
@@ -4118,6 +5236,9 @@ impl Editor {
         // Process pending plugin action completions
         self.process_pending_plugin_actions();
 
+        // Edits made by plugin commands and async results
+        self.flush_text_changes();
+
         // Process pending LSP server restarts (with exponential backoff)
         self.process_pending_lsp_restarts();
 
@@ -4214,6 +5335,7 @@ impl Editor {
                     path: state.buffer.file_path().map(|p| p.to_path_buf()),
                     modified: state.buffer.is_modified(),
                     length: state.buffer.len(),
+                    revision: state.edit_history.revision(),
                 };
                 snapshot.buffers.insert(*buffer_id, buffer_info);
 
@@ -4298,6 +5420,11 @@ impl Editor {
 
             // Update working directory (for spawning processes in correct directory)
             snapshot.working_dir = self.working_dir.clone();
+            snapshot.file_filter = Arc::clone(&self.file_filter);
+            snapshot.search_index = self.search_index.clone();
+            snapshot.project_files = self.project_scan.clone();
+            snapshot.fuzzy_matcher = self.config.editor.fuzzy_matcher;
+            snapshot.project_env = self.project_env.clone();
         }
     }
 
@@ -4315,12 +5442,30 @@ impl Editor {
             PluginCommand::DeleteRange { buffer_id, range } => {
                 self.handle_delete_range(buffer_id, range);
             }
+            PluginCommand::ApplyEdits {
+                buffer_id,
+                transaction,
+                policy,
+                request_id,
+            } => {
+                self.handle_apply_edits(buffer_id, transaction, policy, request_id);
+            }
             PluginCommand::InsertAtCursor { text } => {
                 self.handle_insert_at_cursor(text);
             }
             PluginCommand::DeleteSelection => {
                 self.handle_delete_selection();
             }
+            PluginCommand::RegisterTextObject {
+                name,
+                pattern,
+                queries,
+            } => {
+                self.handle_register_text_object(name, pattern, queries);
+            }
+            PluginCommand::SelectTextObject { name, around } => {
+                self.select_text_object(&name, around);
+            }
 
             // ==================== Overlay Commands ====================
             PluginCommand::AddOverlay {
@@ -4331,9 +5476,10 @@ impl Editor {
                 underline,
                 bold,
                 italic,
+                revision,
             } => {
                 self.handle_add_overlay(
-                    buffer_id, namespace, range, color, underline, bold, italic,
+                    buffer_id, namespace, range, color, underline, bold, italic, revision,
                 );
             }
             PluginCommand::RemoveOverlay { buffer_id, handle } => {
@@ -4406,6 +5552,21 @@ impl Editor {
             } => {
                 self.handle_clear_virtual_text_namespace(buffer_id, namespace);
             }
+            PluginCommand::ClearNamespaceInRange {
+                buffer_id,
+                namespace,
+                range,
+            } => {
+                self.handle_clear_namespace_in_range(buffer_id, namespace, range);
+            }
+            PluginCommand::SetDecorations {
+                buffer_id,
+                namespace,
+                range,
+                decorations,
+            } => {
+                self.handle_set_decorations(buffer_id, namespace, range, decorations);
+            }
 
             // ==================== Menu Commands ====================
             PluginCommand::AddMenuItem {
@@ -4503,12 +5664,57 @@ impl Editor {
             PluginCommand::SetStatus { message } => {
                 self.handle_set_status(message);
             }
-            PluginCommand::StartPrompt { label, prompt_type } => {
-                self.handle_start_prompt(label, prompt_type);
+            PluginCommand::StartPrompt {
+                label,
+                prompt_type,
+                initial_input,
+            } => {
+                self.handle_start_prompt(label, prompt_type, initial_input);
             }
             PluginCommand::SetPromptSuggestions { suggestions } => {
                 self.handle_set_prompt_suggestions(suggestions);
             }
+            PluginCommand::RequestPrompt {
+                request,
+                request_id,
+            } => {
+                self.handle_request_prompt(request, request_id);
+            }
+
+            // ==================== Job Commands ====================
+            PluginCommand::SpawnJob {
+                job_id,
+                spec,
+                title,
+            } => {
+                self.handle_spawn_job(job_id, spec, title);
+            }
+            PluginCommand::KillJob { job_id } => {
+                self.handle_kill_job(job_id);
+            }
+
+            // ==================== Panel Commands ====================
+            PluginCommand::RegisterPanel {
+                id,
+                title,
+                position,
+                size,
+                mode,
+            } => {
+                self.handle_register_panel(id, title, position, size, mode);
+            }
+            PluginCommand::UnregisterPanel { id } => {
+                self.handle_unregister_panel(&id);
+            }
+            PluginCommand::SetPanelContent { id, content } => {
+                self.handle_set_panel_content(&id, content);
+            }
+            PluginCommand::RefreshPanel { id } => {
+                self.handle_refresh_panel(&id);
+            }
+            PluginCommand::FocusPanel { id } => {
+                self.focus_panel(&id);
+            }
 
             // ==================== Command/Mode Registration ====================
             PluginCommand::RegisterCommand { command } => {
@@ -4517,6 +5723,9 @@ impl Editor {
             PluginCommand::UnregisterCommand { name } => {
                 self.handle_unregister_command(name);
             }
+            PluginCommand::SetGutterClickAction { column, action } => {
+                self.handle_set_gutter_click_action(column, action);
+            }
             PluginCommand::DefineMode {
                 name,
                 parent,
@@ -4525,6 +5734,15 @@ impl Editor {
             } => {
                 self.handle_define_mode(name, parent, bindings, read_only);
             }
+            PluginCommand::SetBufferKeybindings {
+                buffer_id,
+                bindings,
+            } => {
+                self.handle_set_buffer_keybindings(buffer_id, bindings);
+            }
+            PluginCommand::RemoveBufferKeybindings { buffer_id, keys } => {
+                self.handle_remove_buffer_keybindings(buffer_id, keys);
+            }
 
             // ==================== File/Navigation Commands ====================
             PluginCommand::OpenFileInBackground { path } => {
@@ -4541,6 +5759,17 @@ impl Editor {
             } => {
                 return self.handle_open_file_in_split(split_id, path, line, column);
             }
+            PluginCommand::OpenFileInNewSplit { path, vertical } => {
+                return self.handle_open_file_in_new_split(path, vertical);
+            }
+            PluginCommand::OpenFileToSide {
+                path,
+                line,
+                column,
+                below,
+            } => {
+                self.handle_open_file_to_side(path, line, column, below);
+            }
             PluginCommand::ShowBuffer { buffer_id } => {
                 self.handle_show_buffer(buffer_id);
             }
@@ -5040,9 +6269,12 @@ impl Editor {
         }
 
         self.pending_goto_definition_request = None;
+        let side = self.pending_goto_definition_side.take();
 
         if locations.is_empty() {
-            self.status_message = Some("No definition found".to_string());
+            if !self.goto_definition_from_index(side) {
+                self.push_status(StatusLevel::Info, "No definition found".to_string());
+            }
             return Ok(());
         }
 
@@ -5051,6 +6283,16 @@ impl Editor {
 
         // Convert URI to file path
         if let Ok(path) = uri_to_path(&location.uri) {
+            if let Some(side) = side {
+                let open = open_to_side::SideOpen {
+                    path,
+                    line: Some(location.range.start.line as usize + 1),
+                    column: Some(location.range.start.character as usize + 1),
+                };
+                self.open_to_side(open, side);
+                return Ok(());
+            }
+
             // Open the file
             let buffer_id = self.open_file(&path)?;
 
@@ -5082,13 +6324,15 @@ impl Editor {
                 }
             }
 
-            self.status_message = Some(format!(
-                "Jumped to definition at {}:{}",
-                path.display(),
-                line + 1
-            ));
+            self.push_status(
+                StatusLevel::Info,
+                format!("Jumped to definition at {}:{}", path.display(), line + 1),
+            );
         } else {
-            self.status_message = Some("Could not open definition location".to_string());
+            self.push_status(
+                StatusLevel::Warning,
+                "Could not open definition location".to_string(),
+            );
         }
 
         Ok(())
@@ -5189,8 +6433,9 @@ impl Editor {
         Ok(())
     }
 
-    /// Request LSP go-to-definition at current cursor position
-    fn request_goto_definition(&mut self) -> io::Result<()> {
+    /// Request LSP go-to-definition at current cursor position, opening it
+    /// in another pane if `side` is given
+    fn request_goto_definition(&mut self, side: Option<open_to_side::OpenSide>) -> io::Result<()> {
         // Get the current buffer and cursor position
         let state = self.active_state();
         let cursor_pos = state.cursors.primary().position;
@@ -5215,6 +6460,7 @@ impl Editor {
                         let request_id = self.next_lsp_request_id;
                         self.next_lsp_request_id += 1;
                         self.pending_goto_definition_request = Some(request_id);
+                        self.pending_goto_definition_side = side;
 
                         let _ = handle.goto_definition(
                             request_id,
@@ -5228,11 +6474,16 @@ impl Editor {
                             line,
                             character
                         );
+                        return Ok(());
                     }
                 }
             }
         }
 
+        // No language server for this buffer: use the symbol index
+        if !self.goto_definition_from_index(side) {
+            self.push_status(StatusLevel::Info, "No definition found".to_string());
+        }
         Ok(())
     }
 
@@ -5919,36 +7170,25 @@ impl Editor {
     }
 
     /// Apply LSP text edits to a buffer and return the number of changes made.
-    /// Edits are sorted in reverse order and applied as a batch.
+    /// The edits are committed as one transaction, so they undo as one step.
     fn apply_lsp_text_edits(
         &mut self,
         buffer_id: BufferId,
-        mut edits: Vec<lsp_types::TextEdit>,
+        edits: Vec<lsp_types::TextEdit>,
     ) -> io::Result<usize> {
         if edits.is_empty() {
             return Ok(0);
         }
 
-        // Sort edits by position (reverse order to avoid offset issues)
-        edits.sort_by(|a, b| {
-            b.range
-                .start
-                .line
-                .cmp(&a.range.start.line)
-                .then(b.range.start.character.cmp(&a.range.start.character))
-        });
-
-        // Collect all events for this buffer into a batch
-        let mut batch_events = Vec::new();
-        let mut changes = 0;
+        let mut transaction = self
+            .begin_edit_transaction(buffer_id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Buffer not found"))?;
+        let state = self
+            .buffers
+            .get(&buffer_id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Buffer not found"))?;
 
-        // Create events for all edits
         for edit in edits {
-            let state = self
-                .buffers
-                .get_mut(&buffer_id)
-                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Buffer not found"))?;
-
             // Convert LSP range to byte positions
             let start_line = edit.range.start.line as usize;
             let start_char = edit.range.start.character as usize;
@@ -5956,64 +7196,31 @@ impl Editor {
             let end_char = edit.range.end.character as usize;
 
             let start_pos = state.buffer.lsp_position_to_byte(start_line, start_char);
-            let end_pos = state.buffer.lsp_position_to_byte(end_line, end_char);
-            let buffer_len = state.buffer.len();
-
-            // Log the conversion for debugging
-            let old_text = if start_pos < end_pos && end_pos <= buffer_len {
-                state.get_text_range(start_pos, end_pos)
-            } else {
-                format!(
-                    "<invalid range: start={}, end={}, buffer_len={}>",
-                    start_pos, end_pos, buffer_len
-                )
-            };
+            let end_pos = state
+                .buffer
+                .lsp_position_to_byte(end_line, end_char)
+                .max(start_pos);
             tracing::debug!(
-                "  Converting LSP range line {}:{}-{}:{} to bytes {}..{} (replacing {:?} with {:?})",
-                start_line, start_char, end_line, end_char,
-                start_pos, end_pos, old_text, edit.new_text
+                "  Converting LSP range line {}:{}-{}:{} to bytes {}..{} (replacing with {:?})",
+                start_line,
+                start_char,
+                end_line,
+                end_char,
+                start_pos,
+                end_pos,
+                edit.new_text
             );
 
-            // Delete old text
-            if start_pos < end_pos {
-                let deleted_text = state.get_text_range(start_pos, end_pos);
-                let cursor_id = state.cursors.primary_id();
-                let delete_event = Event::Delete {
-                    range: start_pos..end_pos,
-                    deleted_text,
-                    cursor_id,
-                };
-                batch_events.push(delete_event);
-            }
-
-            // Insert new text
-            if !edit.new_text.is_empty() {
-                let state = self
-                    .buffers
-                    .get(&buffer_id)
-                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Buffer not found"))?;
-                let cursor_id = state.cursors.primary_id();
-                let insert_event = Event::Insert {
-                    position: start_pos,
-                    text: edit.new_text.clone(),
-                    cursor_id,
-                };
-                batch_events.push(insert_event);
-            }
-
-            changes += 1;
-        }
-
-        // Create a batch event for all rename changes
-        if !batch_events.is_empty() {
-            let batch = Event::Batch {
-                events: batch_events,
-                description: "LSP Rename".to_string(),
-            };
-            self.apply_rename_batch_to_buffer(buffer_id, batch)?;
+            transaction.replace(start_pos..end_pos, edit.new_text);
         }
 
-        Ok(changes)
+        self.commit_edit_transaction(
+            buffer_id,
+            &transaction,
+            ConflictPolicy::Reject,
+            "LSP Rename",
+        )
+        .map_err(|e| io::Error::other(e.to_string()))
     }
 
     /// Handle rename response from LSP
@@ -6024,6 +7231,16 @@ impl Editor {
     ) -> io::Result<()> {
         self.lsp_status.clear();
 
+        // The edits' positions refer to the text the rename was requested on
+        let pending_revision = self.pending_rename_revision.take();
+        let modified = pending_revision
+            .is_some_and(|(buffer_id, revision)| self.buffer_revision(buffer_id) != Some(revision));
+        let result = if modified {
+            Err("content modified".to_string())
+        } else {
+            result
+        };
+
         match result {
             Ok(workspace_edit) => {
                 // Log the full workspace edit for debugging
@@ -6110,8 +7327,10 @@ impl Editor {
                     }
                 }
 
-                self.status_message =
-                    Some(format!("Renamed successfully ({} changes)", total_changes));
+                self.push_status(
+                    StatusLevel::Info,
+                    format!("Renamed successfully ({} changes)", total_changes),
+                );
             }
             Err(error) => {
                 // Per LSP spec: ContentModified errors (-32801) should NOT be shown to user
@@ -6122,11 +7341,13 @@ impl Editor {
                         "LSP rename: ContentModified error (expected, ignoring): {}",
                         error
                     );
-                    self.status_message =
-                        Some("Rename cancelled (document was modified)".to_string());
+                    self.push_status(
+                        StatusLevel::Info,
+                        "Rename cancelled (document was modified)".to_string(),
+                    );
                 } else {
                     // Show other errors to user
-                    self.status_message = Some(format!("Rename failed: {}", error));
+                    self.push_status(StatusLevel::Error, format!("Rename failed: {}", error));
                 }
             }
         }
@@ -6145,15 +7366,6 @@ impl Editor {
             event_log.append(batch.clone());
         }
 
-        // IMPORTANT: Calculate LSP changes BEFORE applying to buffer!
-        // The byte positions in the events are relative to the ORIGINAL buffer,
-        // so we must convert them to LSP positions before modifying the buffer.
-        // Otherwise, the LSP server will receive incorrect position information.
-        let original_active = self.active_buffer;
-        self.active_buffer = buffer_id;
-        let lsp_changes = self.collect_lsp_changes(&batch);
-        self.active_buffer = original_active;
-
         // Save cursor position before applying batch
         // The batch will move the cursor to each edit location, but we want to
         // preserve the cursor position (adjusted for edits before it)
@@ -6216,8 +7428,8 @@ impl Editor {
             state.cursors.primary_mut().anchor = Some(new_anchor);
         }
 
-        // Notify LSP about the changes using pre-calculated positions
-        self.send_lsp_changes_for_buffer(buffer_id, lsp_changes);
+        // Notify LSP and plugins of the changes recorded by the buffer
+        self.flush_buffer_changes(buffer_id);
 
         Ok(())
     }
@@ -6286,9 +7498,14 @@ impl Editor {
             uri.as_str()
         );
 
+        let version = self
+            .buffers
+            .get(&buffer_id)
+            .map_or(0, |state| state.lsp_version());
+
         if let Some(lsp) = &mut self.lsp {
             if let Some(client) = lsp.get_or_spawn(&language) {
-                if let Err(e) = client.did_change(uri, changes) {
+                if let Err(e) = client.did_change(uri, changes, version) {
                     tracing::warn!("Failed to send didChange to LSP: {}", e);
                 } else {
                     tracing::info!("Successfully sent batched didChange to LSP");
@@ -6319,7 +7536,7 @@ impl Editor {
 
             // Check if we're on a word
             if word_start >= word_end {
-                self.status_message = Some("No symbol at cursor".to_string());
+                self.push_status(StatusLevel::Info, "No symbol at cursor".to_string());
                 return Ok(());
             }
 
@@ -6376,7 +7593,7 @@ impl Editor {
 
         // Check if the name actually changed
         if new_name == original_text {
-            self.status_message = Some("Name unchanged".to_string());
+            self.push_status(StatusLevel::Info, "Name unchanged".to_string());
             return;
         }
 
@@ -6388,6 +7605,9 @@ impl Editor {
         // LSP uses UTF-16 code units for character offsets, not byte offsets
         let state = self.active_state();
         let (line, character) = state.buffer.position_to_lsp_position(rename_pos);
+        let rename_revision = self
+            .buffer_revision(self.active_buffer)
+            .map(|revision| (self.active_buffer, revision));
 
         // Get the current file URI and path
         let metadata = self.buffer_metadata.get(&self.active_buffer);
@@ -6406,6 +7626,7 @@ impl Editor {
                         let request_id = self.next_lsp_request_id;
                         self.next_lsp_request_id += 1;
                         self.lsp_status = "LSP: rename...".to_string();
+                        self.pending_rename_revision = rename_revision;
 
                         let _ = handle.rename(
                             request_id,
@@ -6425,7 +7646,10 @@ impl Editor {
                 }
             }
         } else {
-            self.status_message = Some("Cannot rename in unsaved buffer".to_string());
+            self.push_status(
+                StatusLevel::Warning,
+                "Cannot rename in unsaved buffer".to_string(),
+            );
         }
     }
 }
@@ -6498,6 +7722,7 @@ fn parse_key_string(key_str: &str) -> Option<(KeyCode, KeyModifiers)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lsp_types::{Position, Range as LspRange};
 
     #[test]
     fn test_editor_new() {
@@ -6964,7 +8189,7 @@ mod tests {
         assert_eq!(line, 0, "Insertion at start should be line 0");
         assert_eq!(character, 0, "Insertion at start should be char 0");
 
-        // Create the range as we do in notify_lsp_change
+        // Create the range as we do for didChange events
         let lsp_pos = Position::new(line as u32, character as u32);
         let lsp_range = LspRange::new(lsp_pos, lsp_pos);
 
@@ -7292,6 +8517,168 @@ mod tests {
         assert_eq!(search_state.matches[1], 27, "Second match at position 27");
     }
 
+    #[test]
+    fn test_search_in_selection() {
+        let config = Config::default();
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+
+        // Insert text
+        let state = editor.active_state_mut();
+        state.apply(&Event::Insert {
+            position: 0,
+            text: "foo bar foo bar foo bar".to_string(),
+            cursor_id: state.cursors.primary_id(),
+        });
+
+        // Restrict search to "bar foo bar" (bytes 4..15)
+        editor.pending_search_range = Some(4..14);
+        editor.search_in_selection = true;
+        editor.perform_search("foo");
+
+        let search_state = editor.search_state.as_ref().unwrap();
+        assert_eq!(
+            search_state.matches,
+            vec![8],
+            "Should only find the match inside the selection"
+        );
+
+        // Replace-all is restricted to the selection as well
+        editor.perform_replace("bar", "baz");
+        assert_eq!(
+            editor.active_state().buffer.to_string().unwrap(),
+            "foo baz foo baz foo bar"
+        );
+
+        // Turning the toggle off searches the whole buffer again
+        editor.search_in_selection = false;
+        editor.perform_search("foo");
+        let search_state = editor.search_state.as_ref().unwrap();
+        assert_eq!(search_state.matches.len(), 3);
+    }
+
+    #[test]
+    fn test_search_prompt_reports_regex_error_and_keeps_highlights() {
+        let config = Config::default();
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+
+        let state = editor.active_state_mut();
+        state.apply(&Event::Insert {
+            position: 0,
+            text: "foo bar foo".to_string(),
+            cursor_id: state.cursors.primary_id(),
+        });
+
+        editor.search_use_regex = true;
+        editor.start_prompt("Search: ".to_string(), PromptType::Search);
+
+        // A valid pattern highlights matches and has no error
+        editor.update_search_highlights("fo+");
+        assert!(editor.prompt.as_ref().unwrap().error.is_none());
+        let ns = editor.search_namespace.clone();
+        let count_highlights = |editor: &Editor| {
+            editor
+                .active_state()
+                .overlays
+                .all()
+                .iter()
+                .filter(|o| o.namespace.as_ref() == Some(&ns))
+                .count()
+        };
+        assert_eq!(count_highlights(&editor), 2);
+
+        // An unclosed group reports the position of the '(' and keeps old highlights
+        editor.update_search_highlights("fo(o");
+        let error = editor.prompt.as_ref().unwrap().error.clone().unwrap();
+        assert_eq!(error.position, Some(2));
+        assert!(error.message.contains("unclosed group"));
+        assert_eq!(count_highlights(&editor), 2);
+
+        // Fixing the pattern clears the error
+        editor.update_search_highlights("fo(o)");
+        assert!(editor.prompt.as_ref().unwrap().error.is_none());
+    }
+
+    #[test]
+    fn test_search_prompt_match_count() {
+        use crate::view::prompt::MatchCount;
+
+        let config = Config::default();
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+
+        let state = editor.active_state_mut();
+        state.apply(&Event::Insert {
+            position: 0,
+            text: "foo bar foo bar foo".to_string(),
+            cursor_id: state.cursors.primary_id(),
+        });
+        state.cursors.primary_mut().position = 5;
+
+        editor.start_prompt("Search: ".to_string(), PromptType::Search);
+
+        // The counter points at the first match after the cursor
+        editor.update_search_highlights("foo");
+        assert_eq!(
+            editor.prompt.as_ref().unwrap().match_count,
+            Some(MatchCount {
+                current: Some(2),
+                total: 3,
+                truncated: false,
+            })
+        );
+
+        editor.update_search_highlights("xyz");
+        assert_eq!(
+            editor.prompt.as_ref().unwrap().match_count,
+            Some(MatchCount {
+                current: None,
+                total: 0,
+                truncated: false,
+            })
+        );
+
+        // An empty query has no counter
+        editor.update_search_highlights("");
+        assert_eq!(editor.prompt.as_ref().unwrap().match_count, None);
+    }
+
+    #[test]
+    fn test_search_match_limit() {
+        use crate::view::prompt::MatchCount;
+
+        let mut config = Config::default();
+        config.limits.max_search_matches = 2;
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+
+        let state = editor.active_state_mut();
+        state.apply(&Event::Insert {
+            position: 0,
+            text: "foo foo foo foo".to_string(),
+            cursor_id: state.cursors.primary_id(),
+        });
+        state.cursors.primary_mut().position = 0;
+
+        editor.start_prompt("Search: ".to_string(), PromptType::Search);
+        editor.update_search_highlights("foo");
+        assert_eq!(
+            editor.prompt.as_ref().unwrap().match_count,
+            Some(MatchCount {
+                current: Some(1),
+                total: 2,
+                truncated: true,
+            })
+        );
+
+        editor.prompt = None;
+        editor.perform_search("foo");
+        let search_state = editor.search_state.as_ref().unwrap();
+        assert_eq!(search_state.matches, vec![0, 4]);
+        assert!(search_state.truncated);
+        assert_eq!(
+            editor.get_status_message().map(String::as_str),
+            Some("Found 2+ matches for 'foo'")
+        );
+    }
+
     #[test]
     fn test_bookmarks() {
         let config = Config::default();
@@ -7466,154 +8853,83 @@ mod tests {
         assert_eq!(action, Action::JumpToBookmark('5'));
     }
 
-    /// This test demonstrates the bug where LSP didChange notifications contain
-    /// incorrect positions because they're calculated from the already-modified buffer.
-    ///
-    /// When applying LSP rename edits:
-    /// 1. apply_rename_batch_to_buffer() applies the batch to the buffer
-    /// 2. Then calls notify_lsp_change() which calls collect_lsp_changes()
-    /// 3. collect_lsp_changes() converts byte positions to LSP positions using
-    ///    the CURRENT buffer state
-    ///
-    /// But the byte positions in the events are relative to the ORIGINAL buffer,
-    /// not the modified one! This causes LSP to receive wrong positions.
+    /// Apply an event to the active buffer and return the LSP changes it recorded
+    fn apply_and_take_lsp_changes(
+        editor: &mut Editor,
+        event: &Event,
+    ) -> Vec<TextDocumentContentChangeEvent> {
+        let state = editor.active_state_mut();
+        state.apply(event);
+        state
+            .take_changes()
+            .iter()
+            .map(text_changes::lsp_change_event)
+            .collect()
+    }
+
+    /// LSP positions of each change must come from the text before that change,
+    /// not from the buffer once the whole batch is applied.
     #[test]
-    fn test_lsp_rename_didchange_positions_bug() {
+    fn test_lsp_changes_use_positions_before_each_edit() {
         use crate::model::buffer::Buffer;
 
         let config = Config::default();
         let mut editor = Editor::new(config, 80, 24).unwrap();
 
-        // Set buffer content: "fn foo(val: i32) {\n    val + 1\n}\n"
-        // Line 0: positions 0-19 (includes newline)
-        // Line 1: positions 19-31 (includes newline)
         let initial = "fn foo(val: i32) {\n    val + 1\n}\n";
         editor.active_state_mut().buffer = Buffer::from_str(initial, 1024 * 1024);
-
-        // Simulate LSP rename batch: rename "val" to "value" in two places
-        // This is applied in reverse order to preserve positions:
-        // 1. Delete "val" at position 23 (line 1, char 4), insert "value"
-        // 2. Delete "val" at position 7 (line 0, char 7), insert "value"
         let cursor_id = editor.active_state().cursors.primary_id();
 
+        // Earlier edits shift the positions of later ones: after inserting a
+        // line at the start, the "val" on line 0 is on line 1
         let batch = Event::Batch {
             events: vec![
-                // Second occurrence first (reverse order for position preservation)
-                Event::Delete {
-                    range: 23..26, // "val" on line 1
-                    deleted_text: "val".to_string(),
-                    cursor_id,
-                },
                 Event::Insert {
-                    position: 23,
-                    text: "value".to_string(),
+                    position: 0,
+                    text: "// x\n".to_string(),
                     cursor_id,
                 },
-                // First occurrence second
                 Event::Delete {
-                    range: 7..10, // "val" on line 0
+                    range: 12..15,
                     deleted_text: "val".to_string(),
                     cursor_id,
                 },
                 Event::Insert {
-                    position: 7,
+                    position: 12,
                     text: "value".to_string(),
                     cursor_id,
                 },
             ],
-            description: "LSP Rename".to_string(),
+            description: "Edit".to_string(),
         };
+        let changes = apply_and_take_lsp_changes(&mut editor, &batch);
 
-        // CORRECT: Calculate LSP positions BEFORE applying batch
-        let lsp_changes_before = editor.collect_lsp_changes(&batch);
-
-        // Now apply the batch (this is what apply_rename_batch_to_buffer does)
-        editor.active_state_mut().apply(&batch);
-
-        // BUG DEMONSTRATION: Calculate LSP positions AFTER applying batch
-        // This is what happens when notify_lsp_change is called after state.apply()
-        let lsp_changes_after = editor.collect_lsp_changes(&batch);
-
-        // Verify buffer was correctly modified
-        let final_content = editor.active_state().buffer.to_string().unwrap();
         assert_eq!(
-            final_content, "fn foo(value: i32) {\n    value + 1\n}\n",
-            "Buffer should have 'value' in both places"
+            editor.active_state().buffer.to_string().unwrap(),
+            "// x\nfn foo(value: i32) {\n    val + 1\n}\n"
         );
-
-        // The CORRECT positions (before applying batch):
-        // - Delete at 23..26 should be line 1, char 4-7 (in original buffer)
-        // - Insert at 23 should be line 1, char 4 (in original buffer)
-        // - Delete at 7..10 should be line 0, char 7-10 (in original buffer)
-        // - Insert at 7 should be line 0, char 7 (in original buffer)
-        assert_eq!(lsp_changes_before.len(), 4, "Should have 4 changes");
-
-        let first_delete = &lsp_changes_before[0];
-        let first_del_range = first_delete.range.unwrap();
+        assert_eq!(changes.len(), 3);
+        let range = changes[0].range.unwrap();
         assert_eq!(
-            first_del_range.start.line, 1,
-            "First delete should be on line 1 (BEFORE)"
+            (range.start, range.end),
+            (Position::new(0, 0), Position::new(0, 0))
         );
+        assert_eq!(changes[0].text, "// x\n");
+        let range = changes[1].range.unwrap();
         assert_eq!(
-            first_del_range.start.character, 4,
-            "First delete start should be at char 4 (BEFORE)"
+            (range.start, range.end),
+            (Position::new(1, 7), Position::new(1, 10))
         );
-
-        // The INCORRECT positions (after applying batch):
-        // Since the buffer has changed, position 23 now points to different text!
-        // Original buffer position 23 was start of "val" on line 1
-        // But after rename, the buffer is "fn foo(value: i32) {\n    value + 1\n}\n"
-        // Position 23 in new buffer is 'l' in "value" (line 1, offset into "value")
-        assert_eq!(lsp_changes_after.len(), 4, "Should have 4 changes");
-
-        let first_delete_after = &lsp_changes_after[0];
-        let first_del_range_after = first_delete_after.range.unwrap();
-
-        // THIS IS THE BUG: The positions are WRONG when calculated from modified buffer
-        // The first delete's range.end position will be wrong because the buffer changed
-        eprintln!("BEFORE modification:");
-        eprintln!(
-            "  Delete at line {}, char {}-{}",
-            first_del_range.start.line,
-            first_del_range.start.character,
-            first_del_range.end.character
-        );
-        eprintln!("AFTER modification:");
-        eprintln!(
-            "  Delete at line {}, char {}-{}",
-            first_del_range_after.start.line,
-            first_del_range_after.start.character,
-            first_del_range_after.end.character
-        );
-
-        // The bug causes the position calculation to be wrong.
-        // After applying the batch, position 23..26 in the modified buffer
-        // is different from what it was in the original buffer.
-        //
-        // Modified buffer: "fn foo(value: i32) {\n    value + 1\n}\n"
-        // Position 23 = 'l' in second "value"
-        // Position 26 = 'e' in second "value"
-        // This maps to line 1, char 2-5 (wrong!)
-        //
-        // Original buffer: "fn foo(val: i32) {\n    val + 1\n}\n"
-        // Position 23 = 'v' in "val"
-        // Position 26 = ' ' after "val"
-        // This maps to line 1, char 4-7 (correct!)
-
-        // The positions are different! This demonstrates the bug.
-        // Note: Due to how the batch is applied (all operations at once),
-        // the exact positions may vary, but they will definitely be wrong.
-        assert_ne!(
-            first_del_range_after.end.character, first_del_range.end.character,
-            "BUG CONFIRMED: LSP positions are different when calculated after buffer modification!"
+        assert_eq!(changes[1].text, "");
+        let range = changes[2].range.unwrap();
+        assert_eq!(
+            (range.start, range.end),
+            (Position::new(1, 7), Position::new(1, 7))
         );
+        assert_eq!(changes[2].text, "value");
 
-        eprintln!("\n=== BUG DEMONSTRATED ===");
-        eprintln!("When collect_lsp_changes() is called AFTER buffer modification,");
-        eprintln!("the positions are WRONG because they're calculated from the");
-        eprintln!("modified buffer, not the original buffer.");
-        eprintln!("This causes the second rename to fail with 'content modified' error.");
-        eprintln!("========================\n");
+        // Taking the changes empties the queue
+        assert!(!editor.active_state().has_changes());
     }
 
     #[test]
@@ -7722,7 +9038,6 @@ mod tests {
         editor.active_state_mut().buffer = Buffer::from_str(initial, 1024 * 1024);
 
         let cursor_id = editor.active_state().cursors.primary_id();
-        let buffer_id = editor.active_buffer;
 
         // === FIRST RENAME: "val" -> "value" ===
         // Create batch for first rename (applied in reverse order)
@@ -7754,8 +9069,8 @@ mod tests {
             description: "LSP Rename 1".to_string(),
         };
 
-        // Collect LSP changes BEFORE applying (this is the fix)
-        let lsp_changes1 = editor.collect_lsp_changes(&batch1);
+        // Positions are recorded from the text before each edit
+        let lsp_changes1 = apply_and_take_lsp_changes(&mut editor, &batch1);
 
         // Verify first rename LSP positions are correct
         assert_eq!(
@@ -7774,11 +9089,6 @@ mod tests {
         );
         assert_eq!(first_del_range.end.character, 7, "First delete end char");
 
-        // Apply first rename
-        editor
-            .apply_rename_batch_to_buffer(buffer_id, batch1)
-            .unwrap();
-
         // Verify buffer after first rename
         let after_first = editor.active_state().buffer.to_string().unwrap();
         assert_eq!(
@@ -7823,8 +9133,7 @@ mod tests {
             description: "LSP Rename 2".to_string(),
         };
 
-        // Collect LSP changes BEFORE applying (this is the fix)
-        let lsp_changes2 = editor.collect_lsp_changes(&batch2);
+        let lsp_changes2 = apply_and_take_lsp_changes(&mut editor, &batch2);
 
         // Verify second rename LSP positions are correct
         // THIS IS WHERE THE BUG WOULD MANIFEST - if positions are wrong,
@@ -7867,11 +9176,6 @@ mod tests {
             "Second rename third delete end should be at char 12 (7 + 5 for 'value')"
         );
 
-        // Apply second rename
-        editor
-            .apply_rename_batch_to_buffer(buffer_id, batch2)
-            .unwrap();
-
         // Verify buffer after second rename
         let after_second = editor.active_state().buffer.to_string().unwrap();
         assert_eq!(
@@ -7955,4 +9259,294 @@ mod tests {
             .sum();
         assert!(view_state.tab_scroll_offset <= total_width);
     }
+
+    #[test]
+    fn test_rename_buffer_rebinds_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old_path = dir.path().join("notes.txt");
+        let new_path = dir.path().join("notes.rs");
+        std::fs::write(&old_path, "fn main() {}\n").unwrap();
+        std::fs::write(&new_path, "fn main() {}\n").unwrap();
+
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        editor.open_file(&old_path).unwrap();
+        let buffer_id = editor.active_buffer;
+        editor.rename_buffer(buffer_id, new_path.clone());
+
+        assert_eq!(
+            editor.active_state().buffer.file_path(),
+            Some(new_path.as_path())
+        );
+        let metadata = editor.buffer_metadata.get(&buffer_id).unwrap();
+        assert_eq!(metadata.file_path(), Some(&new_path));
+        assert!(metadata.display_name.ends_with("notes.rs"));
+        assert_eq!(
+            editor.file_history.last(),
+            Some(new_path.to_string_lossy().as_ref())
+        );
+        assert!(!editor.file_mod_times.contains_key(&old_path));
+    }
+
+    #[test]
+    fn test_open_same_file_twice() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "notes\n").unwrap();
+
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        let first = editor.open_file(&path).unwrap();
+        editor.new_buffer();
+        assert_eq!(editor.open_file(&path).unwrap(), first);
+        assert_eq!(editor.active_buffer, first);
+
+        editor.config.editor.reuse_open_buffers = false;
+        let second = editor.open_file(&path).unwrap();
+        assert_ne!(second, first);
+        assert!(editor
+            .get_status_message()
+            .is_some_and(|m| m.contains("edits may conflict")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_hard_link_warns() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        let link = dir.path().join("link.txt");
+        std::fs::write(&path, "notes\n").unwrap();
+        std::fs::hard_link(&path, &link).unwrap();
+
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        editor.open_file(&path).unwrap();
+        editor.open_file(&link).unwrap();
+        let message = editor.get_status_message().cloned().unwrap_or_default();
+        assert!(message.contains("hard link"), "status: {}", message);
+    }
+
+    #[test]
+    fn test_locked_file_opens_read_only() {
+        use crate::services::file_lock::{lock_path, LockInfo};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "notes\n").unwrap();
+        let lock = lock_path(&path).unwrap();
+        let other = LockInfo {
+            pid: 1,
+            host: "elsewhere".to_string(),
+        };
+        std::fs::write(&lock, serde_json::to_string(&other).unwrap()).unwrap();
+
+        let mut config = Config::default();
+        config.editor.lock_files = true;
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+        let buffer_id = editor.open_file(&path).unwrap();
+        assert!(editor.is_editing_disabled());
+        assert!(editor.confirm_dialog().is_some());
+
+        // Stealing the lock makes the buffer editable
+        editor.handle_confirm_dialog_key(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert!(!editor.is_editing_disabled());
+        let info: LockInfo =
+            serde_json::from_str(&std::fs::read_to_string(&lock).unwrap()).unwrap();
+        assert!(info.is_current());
+
+        // Closing the buffer removes the lock
+        editor.close_buffer(buffer_id).unwrap();
+        assert!(!lock.exists());
+    }
+
+    #[test]
+    fn test_plugin_panel_layout_and_focus() {
+        use crate::services::plugins::api::{PanelPosition, PanelText};
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        editor
+            .handle_plugin_command(PluginCommand::RegisterPanel {
+                id: "log".to_string(),
+                title: "HTTP Log".to_string(),
+                position: PanelPosition::Bottom,
+                size: 5,
+                mode: None,
+            })
+            .unwrap();
+        editor
+            .handle_plugin_command(PluginCommand::SetPanelContent {
+                id: "log".to_string(),
+                content: vec![PanelText {
+                    x: 0,
+                    y: 0,
+                    text: "GET /health 200".to_string(),
+                    fg: None,
+                    bg: None,
+                    bold: false,
+                }],
+            })
+            .unwrap();
+        terminal.draw(|frame| editor.render(frame)).unwrap();
+
+        // The panel is docked below the buffers
+        let (id, area) = editor.cached_layout.panel_areas[0].clone();
+        assert_eq!(id, "log");
+        assert_eq!(area.height, 5);
+        let content_area = editor.cached_layout.editor_content_area.unwrap();
+        assert_eq!(content_area.y + content_area.height, area.y);
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..80)
+            .map(|x| buffer.cell((x, area.y + 1)).unwrap().symbol().to_string())
+            .collect();
+        assert!(row.contains("GET /health 200"));
+
+        // A focused panel keeps typed text out of the buffer
+        editor.handle_mouse_click(area.x + 2, area.y + 2).unwrap();
+        editor
+            .handle_key(KeyCode::Char('x'), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(editor.active_state().buffer.to_string().unwrap(), "");
+
+        // Escape gives the focus back to the buffer
+        editor.handle_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+        editor
+            .handle_key(KeyCode::Char('x'), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(editor.active_state().buffer.to_string().unwrap(), "x");
+    }
+
+    #[test]
+    fn test_composite_command_stops_at_failed_step() {
+        let mut config = Config::default();
+        config
+            .composite_commands
+            .push(crate::config::CompositeCommand {
+                name: "Toggle Line Numbers Twice".to_string(),
+                description: None,
+                commands: vec![
+                    "Toggle Line Numbers".to_string(),
+                    "Toggle Line Numbers".to_string(),
+                ],
+            });
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+        let shown = editor.active_state().margins.show_line_numbers;
+
+        // Registered in the palette, running both steps
+        let command = editor
+            .command_registry
+            .read()
+            .unwrap()
+            .find_by_name("Toggle Line Numbers Twice")
+            .unwrap();
+        editor.handle_action(command.action).unwrap();
+        assert_eq!(editor.active_state().margins.show_line_numbers, shown);
+
+        // An unknown step stops the chain and is reported
+        editor
+            .handle_action(Action::RunCommandSequence(vec![
+                "Toggle Line Numbers".to_string(),
+                "No Such Command".to_string(),
+                "Toggle Line Numbers".to_string(),
+            ]))
+            .unwrap();
+        assert_eq!(editor.active_state().margins.show_line_numbers, !shown);
+        assert_eq!(
+            editor.get_status_message().unwrap(),
+            "Step 2/3 failed: unknown command 'No Such Command'"
+        );
+    }
+
+    #[test]
+    fn test_select_text_object() {
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        let state = editor.active_state_mut();
+        state.apply(&Event::Insert {
+            position: 0,
+            text: "call(a, \"b c\")".to_string(),
+            cursor_id: state.cursors.primary_id(),
+        });
+        state.cursors.primary_mut().position = 10;
+
+        editor
+            .handle_action(Action::SelectTextObject("quotes".to_string(), false))
+            .unwrap();
+        assert_eq!(
+            editor.active_state().cursors.primary().selection_range(),
+            Some(9..12)
+        );
+
+        editor
+            .handle_action(Action::SelectTextObject("brackets".to_string(), true))
+            .unwrap();
+        assert_eq!(
+            editor.active_state().cursors.primary().selection_range(),
+            Some(4..14)
+        );
+
+        // Nothing to select: the selection is kept
+        editor
+            .handle_action(Action::SelectTextObject("function".to_string(), true))
+            .unwrap();
+        assert_eq!(
+            editor.active_state().cursors.primary().selection_range(),
+            Some(4..14)
+        );
+    }
+
+    #[test]
+    fn test_cancel_running_task() {
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        let handle = editor
+            .spawn_task("Sleep".to_string(), |progress| async move {
+                progress.report("waiting");
+                tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                AsyncMessage::TaskFinished { task_id: 0 }
+            })
+            .unwrap();
+        assert!(editor.is_task_running(handle));
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !editor
+            .running_tasks_status()
+            .unwrap()
+            .contains("Sleep: waiting")
+        {
+            assert!(std::time::Instant::now() < deadline, "no progress reported");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            editor.process_async_messages();
+        }
+
+        editor.handle_action(Action::CancelRunningTask).unwrap();
+        assert!(!editor.is_task_running(handle));
+        assert!(editor.running_tasks_status().is_none());
+        assert_eq!(editor.get_status_message().unwrap(), "Cancelled 'Sleep'");
+    }
+
+    #[test]
+    fn test_goto_definition_from_symbol_index() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "// helpers\nfn helper() {}\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "call helper here\n").unwrap();
+        let mut editor =
+            Editor::with_plugins_disabled(Config::default(), 80, 24, Some(dir.path().into()))
+                .unwrap();
+        let root = editor.working_dir.clone();
+        let scan = crate::services::project_scan::scan_project(
+            &root,
+            &crate::services::file_filter::FileFilter::default(),
+            &|_| {},
+        );
+        editor.symbol_index.update(&root, &scan);
+
+        // No language server for text files, so the index is used
+        editor.open_file(&dir.path().join("notes.txt")).unwrap();
+        editor.active_state_mut().cursors.primary_mut().position = 7;
+        editor.handle_action(Action::LspGotoDefinition).unwrap();
+
+        let path = editor.active_state().buffer.file_path().unwrap();
+        assert!(path.ends_with("lib.rs"));
+        assert_eq!(
+            editor.active_state().cursors.primary().position,
+            "// helpers\nfn ".len()
+        );
+    }
 }
@@ -0,0 +1,213 @@
+//! Opening files next to the current pane
+//!
+//! "Open to the Right" and "Open Below" (from the file explorer, go to
+//! definition and plugin pickers such as Git Find File) open a file in
+//! another pane. With a single pane it is split that way first; with two the
+//! other pane is used; with more every other pane is labelled with a letter
+//! and the user picks one with that key or a click (see `PaneChooser`).
+//!
+//! Files dropped onto the terminal open in the pane under the mouse pointer,
+//! or in a new split when they are dropped near its right or bottom edge.
+
+use super::Editor;
+use crate::input::keybindings::KeyContext;
+use crate::model::event::SplitId;
+use crate::view::ui::PaneLabelsRenderer;
+use crossterm::event::{KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+use std::path::PathBuf;
+
+/// Which way "Open to the Right/Below" splits a single pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OpenSide {
+    Right,
+    Below,
+}
+
+/// A file to open once its pane is known
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SideOpen {
+    pub path: PathBuf,
+    /// 1-indexed line (None = where the buffer's cursor is)
+    pub line: Option<usize>,
+    /// 1-indexed byte column
+    pub column: Option<usize>,
+}
+
+impl SideOpen {
+    pub fn file(path: PathBuf) -> Self {
+        Self {
+            path,
+            line: None,
+            column: None,
+        }
+    }
+}
+
+/// Letters shown over the panes a file can be opened in
+#[derive(Debug)]
+pub(crate) struct PaneChooser {
+    labels: Vec<(char, SplitId)>,
+    open: SideOpen,
+}
+
+/// Part of a pane, from its right or bottom edge, that a drop splits
+const DROP_EDGE_FRACTION: u16 = 4;
+
+impl Editor {
+    /// Open a file in another pane, asking which one if there are several
+    pub(crate) fn open_to_side(&mut self, open: SideOpen, side: OpenSide) {
+        let active = self.split_manager.active_split();
+        let others: Vec<SplitId> = self
+            .split_manager
+            .root()
+            .leaf_split_ids()
+            .into_iter()
+            .filter(|&id| id != active)
+            .collect();
+
+        match others.as_slice() {
+            [] => self.open_in_new_split(&open, side),
+            [other] => self.open_in_pane(*other, &open),
+            _ => {
+                let labels = ('a'..='z').zip(others).collect();
+                self.pane_chooser = Some(PaneChooser { labels, open });
+                self.set_status_message("Open in which pane? (letter, Esc to cancel)".to_string());
+            }
+        }
+    }
+
+    /// Split the active pane towards `side` and open the file in the new pane
+    fn open_in_new_split(&mut self, open: &SideOpen, side: OpenSide) {
+        match side {
+            OpenSide::Right => self.split_pane_vertical(),
+            OpenSide::Below => self.split_pane_horizontal(),
+        }
+        self.open_in_active_pane(open);
+    }
+
+    /// Focus `split_id` and open the file there
+    fn open_in_pane(&mut self, split_id: SplitId, open: &SideOpen) {
+        self.save_current_split_view_state();
+        if !self.split_manager.set_active_split(split_id) {
+            return;
+        }
+        self.restore_current_split_view_state();
+        self.open_in_active_pane(open);
+    }
+
+    fn open_in_active_pane(&mut self, open: &SideOpen) {
+        if let Err(e) = self.open_file(&open.path) {
+            self.set_status_error(format!("Cannot open {}: {}", open.path.display(), e));
+            return;
+        }
+        if open.line.is_some() || open.column.is_some() {
+            self.jump_to_line_column(open.line, open.column);
+        }
+        self.key_context = KeyContext::Normal;
+        self.set_status_message(format!("Opened {}", open.path.display()));
+    }
+
+    /// Handle a key while the pane chooser is shown; every key is consumed
+    pub(super) fn handle_pane_chooser_key(&mut self, code: KeyCode) {
+        let Some(chooser) = self.pane_chooser.as_ref() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                self.pane_chooser = None;
+                self.set_status_message("Cancelled".to_string());
+            }
+            KeyCode::Char(c) => {
+                let c = c.to_ascii_lowercase();
+                if let Some(&(_, split_id)) = chooser.labels.iter().find(|(label, _)| *label == c) {
+                    if let Some(chooser) = self.pane_chooser.take() {
+                        self.open_in_pane(split_id, &chooser.open);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a mouse event while the pane chooser is shown: clicking a
+    /// labelled pane picks it. Returns true if the screen needs to be redrawn.
+    pub(super) fn handle_pane_chooser_mouse(&mut self, mouse_event: MouseEvent) -> bool {
+        if mouse_event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return false;
+        }
+        let Some(split_id) = self.pane_at(mouse_event.column, mouse_event.row) else {
+            return false;
+        };
+        let labelled = self
+            .pane_chooser
+            .as_ref()
+            .is_some_and(|chooser| chooser.labels.iter().any(|&(_, id)| id == split_id));
+        if !labelled {
+            return false;
+        }
+        if let Some(chooser) = self.pane_chooser.take() {
+            self.open_in_pane(split_id, &chooser.open);
+        }
+        true
+    }
+
+    /// Draw the chooser's letters over their panes
+    pub(super) fn render_pane_chooser(&self, frame: &mut Frame) {
+        let Some(chooser) = &self.pane_chooser else {
+            return;
+        };
+        let labels: Vec<(char, Rect)> = chooser
+            .labels
+            .iter()
+            .filter_map(|&(label, split_id)| Some((label, self.pane_area(split_id)?)))
+            .collect();
+        PaneLabelsRenderer::render(frame, &labels, &self.theme);
+    }
+
+    /// Where a pane's content was last drawn
+    fn pane_area(&self, split_id: SplitId) -> Option<Rect> {
+        self.cached_layout
+            .split_areas
+            .iter()
+            .find(|(id, ..)| *id == split_id)
+            .map(|&(_, _, rect, ..)| rect)
+    }
+
+    /// The pane whose content is at a screen position
+    fn pane_at(&self, col: u16, row: u16) -> Option<SplitId> {
+        self.cached_layout
+            .split_areas
+            .iter()
+            .find(|(_, _, rect, ..)| {
+                col >= rect.x && col < rect.right() && row >= rect.y && row < rect.bottom()
+            })
+            .map(|&(split_id, ..)| split_id)
+    }
+
+    /// Get the pane under the mouse pointer ready for dropped files:
+    /// focus it, and split it if the pointer is near its right or bottom edge
+    pub(super) fn focus_drop_target(&mut self) {
+        let Some((col, row)) = self.mouse_state.last_position else {
+            return;
+        };
+        let Some(split_id) = self.pane_at(col, row) else {
+            return;
+        };
+        let Some(area) = self.pane_area(split_id) else {
+            return;
+        };
+
+        if split_id != self.split_manager.active_split() {
+            self.save_current_split_view_state();
+            self.split_manager.set_active_split(split_id);
+            self.restore_current_split_view_state();
+        }
+        if col >= area.right() - area.width / DROP_EDGE_FRACTION {
+            self.split_pane_vertical();
+        } else if row >= area.bottom() - area.height / DROP_EDGE_FRACTION {
+            self.split_pane_horizontal();
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Viewing a file or piped output read-only, like `less`
+//!
+//! `fresh --pager FILE` and `command | fresh --pager` open the text
+//! read-only with single keys for viewing: Space/`f` and `b` page, `j`/`k`
+//! scroll, `g`/`G` go to the top and bottom, `/`, `n` and `N` search, `l`
+//! toggles line numbers, `F` follows the end as text arrives and `q` quits.
+//! Other keys work as in the editor.
+//!
+//! Piped input is copied to a temporary file as it is read, and text the
+//! file gains is taken in as it arrives (see `TextBuffer::append_from_file`).
+//! Past `editor.large_file_threshold_bytes` the text stays on disk and is
+//! loaded as it is viewed, so multi-GB logs page in constant memory.
+
+use super::Editor;
+use crate::input::keybindings::Action;
+use crate::model::event::BufferId;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Stdin being copied to a temporary file for the pager
+#[derive(Debug)]
+pub struct StdinSpool {
+    /// Removed when dropped
+    path: tempfile::TempPath,
+    done: Arc<AtomicBool>,
+}
+
+impl StdinSpool {
+    /// Start copying stdin to a temporary file in the background
+    ///
+    /// The file gets a new random name and is only readable by the user, as
+    /// piped input can hold secrets.
+    pub fn start() -> io::Result<Self> {
+        let (mut file, path) = tempfile::Builder::new()
+            .prefix("fresh-stdin-")
+            .tempfile()?
+            .into_parts();
+        let done = Arc::new(AtomicBool::new(false));
+        let finished = Arc::clone(&done);
+        std::thread::spawn(move || {
+            let mut stdin = io::stdin().lock();
+            let mut chunk = vec![0u8; 64 * 1024];
+            loop {
+                match stdin.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if let Err(e) = file.write_all(&chunk[..n]) {
+                            tracing::warn!("Failed to copy stdin for the pager: {}", e);
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        tracing::warn!("Failed to read stdin for the pager: {}", e);
+                        break;
+                    }
+                }
+            }
+            finished.store(true, Ordering::Release);
+        });
+        Ok(Self { path, done })
+    }
+
+    /// The file stdin is copied to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn is_done(&self) -> bool {
+        self.done.load(Ordering::Acquire)
+    }
+}
+
+/// The buffer shown by `--pager`
+#[derive(Debug)]
+pub(crate) struct PagerState {
+    buffer_id: BufferId,
+    /// Keep the view at the end as text arrives
+    follow: bool,
+    /// Piped input (its file is removed when the pager goes)
+    stdin: Option<StdinSpool>,
+    /// Whether all of the piped input has been read and shown
+    input_ended: bool,
+}
+
+impl Editor {
+    /// Show `path` read-only with the pager's keys, or piped input when
+    /// `stdin` is given (`path` is then where it is copied to)
+    pub fn start_pager(&mut self, path: &Path, stdin: Option<StdinSpool>) -> io::Result<()> {
+        let buffer_id = self.open_file(path)?;
+        if let Some(metadata) = self.buffer_metadata.get_mut(&buffer_id) {
+            metadata.read_only = true;
+            if stdin.is_some() {
+                metadata.display_name = "(stdin)".to_string();
+            }
+        }
+        self.pager = Some(PagerState {
+            buffer_id,
+            follow: false,
+            stdin,
+            input_ended: false,
+        });
+        self.set_status_message("q quits, / searches, F follows the end".to_string());
+        Ok(())
+    }
+
+    /// Whether `buffer_id` is the pager's, which takes in what its file
+    /// gains itself rather than being reverted
+    pub(super) fn is_pager_buffer(&self, buffer_id: BufferId) -> bool {
+        self.pager
+            .as_ref()
+            .is_some_and(|pager| pager.buffer_id == buffer_id)
+    }
+
+    /// Take in text the pager's file has gained, following it to the end
+    /// if asked to. Returns true if the screen needs to be redrawn.
+    pub fn update_pager(&mut self) -> bool {
+        let Some(pager) = &self.pager else {
+            return false;
+        };
+        let buffer_id = pager.buffer_id;
+        let follow = pager.follow;
+        let stdin_done =
+            !pager.input_ended && pager.stdin.as_ref().is_some_and(StdinSpool::is_done);
+
+        let threshold = self.config.editor.large_file_threshold_bytes as usize;
+        let added = match self.buffers.get_mut(&buffer_id) {
+            Some(state) => state.append_from_file(threshold).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read what the pager's file gained: {}", e);
+                0
+            }),
+            None => return false,
+        };
+        if added > 0 && follow && self.active_buffer == buffer_id {
+            let _ = self.handle_action(Action::MoveDocumentEnd);
+        }
+
+        // Once stdin is read to the end its file no longer grows
+        if stdin_done && added == 0 {
+            if let Some(pager) = self.pager.as_mut() {
+                pager.input_ended = true;
+            }
+            self.set_status_message("End of input".to_string());
+            return true;
+        }
+        added > 0
+    }
+
+    /// Run the pager's action for a key in its buffer, if it has one.
+    /// Returns true if the key was handled.
+    pub(super) fn handle_pager_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> io::Result<bool> {
+        if !self.is_pager_buffer(self.active_buffer)
+            || !(modifiers - KeyModifiers::SHIFT).is_empty()
+        {
+            return Ok(false);
+        }
+        let KeyCode::Char(c) = code else {
+            return Ok(false);
+        };
+        let action = match c {
+            'q' => Action::Quit,
+            ' ' | 'f' => Action::MovePageDown,
+            'b' => Action::MovePageUp,
+            'j' => Action::ScrollDown,
+            'k' => Action::ScrollUp,
+            'g' => Action::MoveDocumentStart,
+            'G' => Action::MoveDocumentEnd,
+            '/' => Action::Search,
+            'n' => Action::FindNext,
+            'N' => Action::FindPrevious,
+            'l' => Action::ToggleLineNumbers,
+            'F' => {
+                self.toggle_pager_follow()?;
+                return Ok(true);
+            }
+            _ => return Ok(false),
+        };
+
+        // Moving away from the end stops following it
+        if action != Action::MoveDocumentEnd {
+            self.stop_pager_follow();
+        }
+        self.handle_action(action)?;
+        Ok(true)
+    }
+
+    fn toggle_pager_follow(&mut self) -> io::Result<()> {
+        let Some(pager) = self.pager.as_mut() else {
+            return Ok(());
+        };
+        pager.follow = !pager.follow;
+        if pager.follow {
+            self.handle_action(Action::MoveDocumentEnd)?;
+            self.set_status_message("Following the end (F to stop)".to_string());
+        } else {
+            self.set_status_message("Stopped following".to_string());
+        }
+        Ok(())
+    }
+
+    fn stop_pager_follow(&mut self) {
+        let following = self.pager.as_ref().is_some_and(|pager| pager.follow);
+        if following {
+            if let Some(pager) = self.pager.as_mut() {
+                pager.follow = false;
+            }
+            self.set_status_message("Stopped following".to_string());
+        }
+    }
+}
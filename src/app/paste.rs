@@ -0,0 +1,87 @@
+//! Text pasted by the terminal (bracketed paste)
+//!
+//! A paste goes into the prompt if one is open, and otherwise into the buffer
+//! at the cursor. Terminals paste the paths of files dropped onto them, so a
+//! paste of nothing but existing file paths (see `input::dropped_paths`)
+//! opens the files instead, or asks first, per `editor.dropped_files`. They
+//! open in the pane under the mouse pointer (see `open_to_side`).
+
+use super::confirm_dialog::ConfirmAction;
+use super::Editor;
+use crate::config::DroppedFiles;
+use crate::input::dropped_paths::parse_dropped_paths;
+use crate::view::confirm_dialog::{ConfirmChoice, ConfirmDialog, DialogButton};
+use std::path::PathBuf;
+
+impl Editor {
+    /// Handle text pasted by the terminal
+    pub fn handle_paste(&mut self, text: String) {
+        // Line endings arrive as "\r" from some terminals
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        if self.note_user_input() || self.confirm_dialog.is_some() {
+            return;
+        }
+        if let Some(prompt) = self.prompt_mut() {
+            prompt.insert_str(&text);
+            self.update_prompt_suggestions();
+            return;
+        }
+
+        let dropped = match self.config.editor.dropped_files {
+            DroppedFiles::Insert => None,
+            DroppedFiles::Ask | DroppedFiles::Open => parse_dropped_paths(&text),
+        };
+        match dropped {
+            Some(paths) if self.config.editor.dropped_files == DroppedFiles::Open => {
+                self.open_dropped_files(&paths);
+            }
+            Some(paths) => self.confirm_open_dropped_files(paths, text),
+            None => self.insert_pasted_text(text),
+        }
+    }
+
+    fn confirm_open_dropped_files(&mut self, paths: Vec<PathBuf>, text: String) {
+        let message = match paths.as_slice() {
+            [path] => format!("Open the dropped file '{}'?", path.display()),
+            _ => format!("Open the {} dropped files?", paths.len()),
+        };
+        let dialog = ConfirmDialog::new(
+            "Dropped Files",
+            message,
+            vec![
+                DialogButton::new("Open", 'o', ConfirmChoice::Yes),
+                DialogButton::new("Insert Path", 'i', ConfirmChoice::No),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        );
+        self.show_confirm_dialog(dialog, ConfirmAction::OpenDroppedFiles { paths, text });
+    }
+
+    /// Open each dropped file, leaving the last one active
+    pub(super) fn open_dropped_files(&mut self, paths: &[PathBuf]) {
+        self.focus_drop_target();
+        let mut opened = 0;
+        for path in paths {
+            match self.open_file(path) {
+                Ok(_) => opened += 1,
+                Err(e) => {
+                    self.set_status_error(format!("Failed to open {}: {}", path.display(), e));
+                    return;
+                }
+            }
+        }
+        if opened > 1 {
+            self.set_status_message(format!("Opened {} dropped files", opened));
+        }
+    }
+
+    /// Insert a paste into the buffer, like pasting from the clipboard
+    pub(super) fn insert_pasted_text(&mut self, text: String) {
+        if self.is_editing_disabled() {
+            self.set_status_message("Editing disabled in this buffer".to_string());
+            return;
+        }
+        self.paste_text(text);
+    }
+}
@@ -0,0 +1,131 @@
+//! Path completion in file prompts
+//!
+//! The "Save As" prompt lists the entries of the directory being typed as
+//! suggestions. Tab fills in what the matching entries have in common (or the
+//! selected entry), Enter on a directory lists its contents, and `~`, `$VAR`
+//! and `${VAR}` are expanded. Hidden files can be shown or hidden here and in
+//! the Open File browser.
+//!
+//! Plugins still receive `prompt_changed` for "save-file-as", and the
+//! suggestions they set are listed after the built-in ones.
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::input::path_completion::{common_prefix, path_completions, resolve_path};
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Refresh the completions of a path prompt
+    ///
+    /// Nothing is selected, so Enter keeps the typed path until the user picks
+    /// a completion.
+    pub(super) fn update_path_prompt_suggestions(&mut self) {
+        let Some(prompt) = &mut self.prompt else {
+            return;
+        };
+        prompt.suggestions =
+            path_completions(&prompt.input, &self.working_dir, prompt.show_hidden_files);
+        prompt.selected_suggestion = None;
+
+        if prompt.prompt_type == PromptType::SaveFileAs {
+            use crate::services::plugins::hooks::HookArgs;
+            let hook_args = HookArgs::PromptChanged {
+                prompt_type: "save-file-as".to_string(),
+                input: prompt.input.clone(),
+            };
+            if let Some(ref ts_manager) = self.ts_plugin_manager {
+                ts_manager.run_hook("prompt_changed", hook_args);
+            }
+        }
+    }
+
+    /// List suggestions set by a plugin after the built-in completions
+    ///
+    /// Suggestions for a path that is already listed are skipped.
+    pub(super) fn add_plugin_path_suggestions(&mut self, suggestions: Vec<Suggestion>) {
+        if let Some(prompt) = &mut self.prompt {
+            let mut merged =
+                path_completions(&prompt.input, &self.working_dir, prompt.show_hidden_files);
+            for suggestion in suggestions {
+                if !merged
+                    .iter()
+                    .any(|s| s.get_value() == suggestion.get_value())
+                {
+                    merged.push(suggestion);
+                }
+            }
+            prompt.suggestions = merged;
+            prompt.selected_suggestion = None;
+        }
+    }
+
+    /// Complete the typed path as far as the matching entries agree
+    pub(super) fn complete_path_prompt(&mut self) {
+        self.update_path_prompt_suggestions();
+        if let Some(prompt) = &mut self.prompt {
+            if let Some(prefix) = common_prefix(&prompt.suggestions) {
+                if prefix.len() > prompt.input.len() {
+                    prompt.input = prefix;
+                    prompt.cursor_pos = prompt.input.len();
+                    prompt.clear_selection();
+                }
+            }
+        }
+        self.update_path_prompt_suggestions();
+    }
+
+    /// List the contents of the directory entered in the Save As prompt
+    ///
+    /// Returns false (so the path is saved to) unless the typed path, or the
+    /// selected completion, is an existing directory.
+    pub(super) fn enter_path_prompt_directory(&mut self) -> bool {
+        let value = match &self.prompt {
+            Some(prompt) if prompt.prompt_type == PromptType::SaveFileAs => prompt
+                .selected_suggestion
+                .and_then(|index| prompt.suggestions.get(index))
+                .map(|s| s.get_value().to_string())
+                .unwrap_or_else(|| prompt.input.clone()),
+            _ => return false,
+        };
+        if value.is_empty() || !resolve_path(&value, &self.working_dir).is_dir() {
+            return false;
+        }
+
+        if let Some(prompt) = &mut self.prompt {
+            prompt.input = if value.ends_with('/') {
+                value
+            } else {
+                format!("{}/", value)
+            };
+            prompt.cursor_pos = prompt.input.len();
+            prompt.clear_selection();
+        }
+        self.update_path_prompt_suggestions();
+        true
+    }
+
+    /// Show or hide hidden files in the active file prompt
+    pub(super) fn toggle_prompt_hidden_files(&mut self) {
+        let shown = if self.is_file_open_active() {
+            self.file_open_toggle_hidden();
+            self.file_open_state.as_ref().is_some_and(|s| s.show_hidden)
+        } else {
+            match &mut self.prompt {
+                Some(prompt) if prompt.prompt_type == PromptType::SaveFileAs => {
+                    prompt.show_hidden_files = !prompt.show_hidden_files;
+                    let shown = prompt.show_hidden_files;
+                    self.update_path_prompt_suggestions();
+                    shown
+                }
+                _ => return,
+            }
+        };
+
+        let message = if shown {
+            "Showing hidden files"
+        } else {
+            "Hiding hidden files"
+        };
+        self.set_status_message(message.to_string());
+    }
+}
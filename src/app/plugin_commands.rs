@@ -3,13 +3,15 @@
 //! This module groups plugin commands by domain for better maintainability.
 
 use crate::model::event::{BufferId, CursorId, Event, SplitId};
+use crate::primitives::text_objects::CustomTextObject;
 use crate::services::plugins::api::{
-    LayoutHints, MenuPosition, PluginResponse, ViewTransformPayload,
+    Decoration, LayoutHints, MenuPosition, PluginResponse, ViewTransformPayload,
 };
 use crate::view::overlay::{OverlayHandle, OverlayNamespace};
 use crate::view::split::SplitViewState;
 use std::io;
 
+use super::open_to_side::{OpenSide, SideOpen};
 use super::Editor;
 
 impl Editor {
@@ -31,6 +33,7 @@ impl Editor {
     // ==================== Overlay Commands ====================
 
     /// Handle AddOverlay command
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn handle_add_overlay(
         &mut self,
         buffer_id: BufferId,
@@ -40,8 +43,19 @@ impl Editor {
         underline: bool,
         bold: bool,
         italic: bool,
+        revision: Option<u64>,
     ) {
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let range = match revision {
+                Some(revision) => match state.edit_history.rebase(range, revision) {
+                    Ok(range) => range,
+                    Err(e) => {
+                        tracing::trace!("Dropping overlay for {:?}: {}", buffer_id, e);
+                        return;
+                    }
+                },
+                None => range,
+            };
             let face = crate::model::event::OverlayFace::Style {
                 color,
                 bold,
@@ -240,6 +254,119 @@ impl Editor {
         }
     }
 
+    // ==================== Decoration Commands ====================
+
+    /// Handle ClearNamespaceInRange command
+    pub(super) fn handle_clear_namespace_in_range(
+        &mut self,
+        buffer_id: BufferId,
+        namespace: String,
+        range: std::ops::Range<usize>,
+    ) {
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            use crate::view::virtual_text::VirtualTextNamespace;
+            state.overlays.clear_namespace_in_range(
+                &OverlayNamespace::from_string(namespace.clone()),
+                &range,
+                &mut state.marker_list,
+            );
+            state.virtual_texts.clear_namespace_in_range(
+                &mut state.marker_list,
+                &VirtualTextNamespace::from_string(namespace),
+                range.start,
+                range.end,
+            );
+        }
+    }
+
+    /// Handle SetDecorations command
+    pub(super) fn handle_set_decorations(
+        &mut self,
+        buffer_id: BufferId,
+        namespace: String,
+        range: Option<std::ops::Range<usize>>,
+        decorations: Vec<Decoration>,
+    ) {
+        match range {
+            Some(range) => {
+                self.handle_clear_namespace_in_range(buffer_id, namespace.clone(), range)
+            }
+            None => {
+                self.handle_clear_namespace(
+                    buffer_id,
+                    OverlayNamespace::from_string(namespace.clone()),
+                );
+                self.handle_clear_virtual_text_namespace(buffer_id, namespace.clone());
+            }
+        }
+
+        for decoration in decorations {
+            match decoration {
+                Decoration::Overlay {
+                    range,
+                    color,
+                    underline,
+                    bold,
+                    italic,
+                } => self.handle_add_overlay(
+                    buffer_id,
+                    Some(OverlayNamespace::from_string(namespace.clone())),
+                    range,
+                    color,
+                    underline,
+                    bold,
+                    italic,
+                    None,
+                ),
+                Decoration::VirtualText {
+                    position,
+                    text,
+                    color,
+                    before,
+                } => {
+                    if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                        use crate::view::virtual_text::{
+                            VirtualTextNamespace, VirtualTextPosition,
+                        };
+                        use ratatui::style::{Color, Style};
+
+                        let vtext_position = if before {
+                            VirtualTextPosition::BeforeChar
+                        } else {
+                            VirtualTextPosition::AfterChar
+                        };
+                        state.virtual_texts.add_in_namespace(
+                            &mut state.marker_list,
+                            position,
+                            text,
+                            Style::default().fg(Color::Rgb(color.0, color.1, color.2)),
+                            vtext_position,
+                            VirtualTextNamespace::from_string(namespace.clone()),
+                            0, // priority
+                        );
+                    }
+                }
+                Decoration::VirtualLine {
+                    position,
+                    text,
+                    fg_color,
+                    bg_color,
+                    above,
+                    priority,
+                } => self.handle_add_virtual_line(
+                    buffer_id,
+                    position,
+                    text,
+                    fg_color,
+                    bg_color,
+                    above,
+                    namespace.clone(),
+                    priority,
+                ),
+            }
+        }
+    }
+
     // ==================== Menu Commands ====================
 
     /// Handle AddMenuItem command
@@ -621,6 +748,28 @@ impl Editor {
         }
     }
 
+    // ==================== Text Object Commands ====================
+
+    /// Handle RegisterTextObject command
+    pub(super) fn handle_register_text_object(
+        &mut self,
+        name: String,
+        pattern: Option<String>,
+        queries: std::collections::HashMap<String, String>,
+    ) {
+        let pattern = match pattern.map(|p| regex::Regex::new(&p)).transpose() {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                self.set_status_error(format!("Text object '{}': invalid pattern: {}", name, e));
+                return;
+            }
+        };
+        let object = CustomTextObject { queries, pattern };
+        if let Err(e) = self.text_objects.register(name.clone(), object) {
+            self.set_status_error(format!("Text object '{}': {}", name, e));
+        }
+    }
+
     // ==================== File/Navigation Commands ====================
 
     /// Helper to jump to a line/column position in the active buffer
@@ -712,6 +861,39 @@ impl Editor {
         Ok(())
     }
 
+    /// Handle OpenFileInNewSplit command
+    pub(super) fn handle_open_file_in_new_split(
+        &mut self,
+        path: std::path::PathBuf,
+        vertical: bool,
+    ) -> io::Result<()> {
+        if vertical {
+            self.split_pane_vertical();
+        } else {
+            self.split_pane_horizontal();
+        }
+        if let Err(e) = self.open_file(&path) {
+            tracing::error!("Failed to open file from plugin: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Handle OpenFileToSide command
+    pub(super) fn handle_open_file_to_side(
+        &mut self,
+        path: std::path::PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        below: bool,
+    ) {
+        let side = if below {
+            OpenSide::Below
+        } else {
+            OpenSide::Right
+        };
+        self.open_to_side(SideOpen { path, line, column }, side);
+    }
+
     /// Handle OpenFileInBackground command
     pub(super) fn handle_open_file_in_background(&mut self, path: std::path::PathBuf) {
         // Open file in a new tab without switching to it
@@ -781,6 +963,23 @@ impl Editor {
         split_id: Option<SplitId>,
         payload: ViewTransformPayload,
     ) {
+        // Tokens from an older revision point at text that has moved; keep
+        // showing the current transform until the plugin answers the next request
+        if let Some(revision) = payload.revision {
+            let current = self
+                .buffers
+                .get(&buffer_id)
+                .map(|state| state.edit_history.revision());
+            if current.is_some_and(|current| current != revision) {
+                tracing::trace!(
+                    "Dropping view transform for {:?} at stale revision {}",
+                    buffer_id,
+                    revision
+                );
+                return;
+            }
+        }
+
         let target_split = split_id.unwrap_or(self.split_manager.active_split());
         let view_state = self
             .split_view_states
@@ -855,22 +1054,29 @@ impl Editor {
     }
 
     /// Handle StartPrompt command
-    pub(super) fn handle_start_prompt(&mut self, label: String, prompt_type: String) {
+    pub(super) fn handle_start_prompt(
+        &mut self,
+        label: String,
+        prompt_type: String,
+        initial_input: Option<String>,
+    ) {
         // Create a plugin-controlled prompt
         use crate::view::prompt::{Prompt, PromptType};
-        self.prompt = Some(Prompt::new(
+        let input = initial_input.unwrap_or_default();
+        self.prompt = Some(Prompt::with_initial_text(
             label,
             PromptType::Plugin {
                 custom_type: prompt_type.clone(),
             },
+            input.clone(),
         ));
 
-        // Fire the prompt_changed hook immediately with empty input
+        // Fire the prompt_changed hook immediately with the initial input
         // This allows plugins to initialize the prompt state
         use crate::services::plugins::hooks::HookArgs;
         let hook_args = HookArgs::PromptChanged {
             prompt_type: prompt_type.clone(),
-            input: String::new(),
+            input,
         };
 
         if let Some(ref ts_manager) = self.ts_plugin_manager {
@@ -878,11 +1084,70 @@ impl Editor {
         }
     }
 
+    /// Handle RequestPrompt command
+    ///
+    /// A prompt the user is still typing in is cancelled first (answering it
+    /// with null if it was another plugin request).
+    pub(super) fn handle_request_prompt(
+        &mut self,
+        request: crate::services::plugins::api::PluginPromptRequest,
+        request_id: u64,
+    ) {
+        use crate::input::commands::Suggestion;
+        use crate::services::plugins::api::PluginPromptRequest;
+        use crate::view::prompt::{Prompt, PromptType};
+
+        if self.prompt.is_some() {
+            self.cancel_prompt();
+        }
+
+        // "Branch name" is shown as "Branch name: "
+        let label = |title: String| {
+            let title = title.trim_end();
+            if title.ends_with([':', '?']) {
+                format!("{} ", title)
+            } else {
+                format!("{}: ", title)
+            }
+        };
+        let prompt_type = |choice| PromptType::PluginRequest { request_id, choice };
+        let prompt = match request {
+            PluginPromptRequest::Input { title, default } => {
+                Prompt::with_initial_text(label(title), prompt_type(false), default)
+            }
+            PluginPromptRequest::Select { title, items } => {
+                Prompt::with_suggestions(label(title), prompt_type(true), items)
+            }
+            PluginPromptRequest::Confirm { message } => {
+                let choice = |text: &str, value: &str| Suggestion {
+                    value: Some(value.to_string()),
+                    ..Suggestion::new(text.to_string())
+                };
+                Prompt::with_suggestions(
+                    label(message),
+                    prompt_type(true),
+                    vec![choice("Yes", "yes"), choice("No", "no")],
+                )
+            }
+        };
+        self.prompt = Some(prompt);
+    }
+
     /// Handle SetPromptSuggestions command
     pub(super) fn handle_set_prompt_suggestions(
         &mut self,
         suggestions: Vec<crate::input::commands::Suggestion>,
     ) {
+        use crate::view::prompt::PromptType;
+        if self
+            .prompt
+            .as_ref()
+            .is_some_and(|p| p.prompt_type == PromptType::SaveFileAs)
+        {
+            self.add_plugin_path_suggestions(suggestions);
+            return;
+        }
+
         // Update the current prompt's suggestions
         if let Some(prompt) = &mut self.prompt {
             prompt.suggestions = suggestions;
@@ -906,6 +1171,26 @@ impl Editor {
         self.command_registry.read().unwrap().unregister(&name);
     }
 
+    /// Handle SetGutterClickAction command
+    pub(super) fn handle_set_gutter_click_action(
+        &mut self,
+        column: String,
+        action: Option<String>,
+    ) {
+        let Some(column) = crate::view::margin::GutterColumn::from_name(&column) else {
+            tracing::warn!("setGutterClickAction: unknown gutter column '{}'", column);
+            return;
+        };
+        match action {
+            Some(action) => {
+                self.plugin_gutter_actions.insert(column, action);
+            }
+            None => {
+                self.plugin_gutter_actions.remove(&column);
+            }
+        }
+    }
+
     /// Handle DefineMode command
     pub(super) fn handle_define_mode(
         &mut self,
@@ -936,6 +1221,51 @@ impl Editor {
         tracing::info!("Registered buffer mode '{}'", name);
     }
 
+    /// Handle SetBufferKeybindings command
+    pub(super) fn handle_set_buffer_keybindings(
+        &mut self,
+        buffer_id: BufferId,
+        bindings: Vec<(String, String)>,
+    ) {
+        use super::parse_key_string;
+
+        if !self.buffers.contains_key(&buffer_id) {
+            tracing::warn!("Cannot bind keys in unknown buffer {:?}", buffer_id);
+            return;
+        }
+        for (key_str, command) in bindings {
+            if let Some((code, modifiers)) = parse_key_string(&key_str) {
+                self.buffer_keybindings
+                    .bind(buffer_id, code, modifiers, command);
+            } else {
+                tracing::warn!("Failed to parse key binding: {}", key_str);
+            }
+        }
+    }
+
+    /// Handle RemoveBufferKeybindings command
+    ///
+    /// Without keys, all of the buffer's own bindings are removed.
+    pub(super) fn handle_remove_buffer_keybindings(
+        &mut self,
+        buffer_id: BufferId,
+        keys: Option<Vec<String>>,
+    ) {
+        use super::parse_key_string;
+
+        let Some(keys) = keys else {
+            self.buffer_keybindings.clear(buffer_id);
+            return;
+        };
+        for key_str in keys {
+            if let Some((code, modifiers)) = parse_key_string(&key_str) {
+                self.buffer_keybindings.unbind(buffer_id, code, modifiers);
+            } else {
+                tracing::warn!("Failed to parse key binding: {}", key_str);
+            }
+        }
+    }
+
     // ==================== LSP Commands ====================
 
     /// Handle SendLspRequest command
@@ -980,3 +1310,106 @@ impl Editor {
         self.clipboard.copy(text);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn editor_with_file(dir: &tempfile::TempDir, content: &str) -> Editor {
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, content).unwrap();
+        let mut editor = Editor::new(Config::default(), 80, 24).unwrap();
+        editor.open_file(&path).unwrap();
+        editor
+    }
+
+    fn edit(editor: &mut Editor, event: impl FnOnce(CursorId) -> Event) {
+        let cursor_id = editor.active_state().cursors.primary_id();
+        editor.apply_event_to_active_buffer(&event(cursor_id));
+    }
+
+    fn overlay_ranges(editor: &Editor) -> Vec<std::ops::Range<usize>> {
+        let state = editor.active_state();
+        state
+            .overlays
+            .all()
+            .iter()
+            .map(|overlay| overlay.range(&state.marker_list))
+            .collect()
+    }
+
+    #[test]
+    fn test_overlay_from_old_revision_is_rebased_or_dropped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut editor = editor_with_file(&dir, "hello world\n");
+        let buffer_id = editor.active_buffer;
+        let revision = editor.active_state().edit_history.revision();
+
+        // Text inserted before the range moves it
+        edit(&mut editor, |cursor_id| Event::Insert {
+            position: 0,
+            text: ">> ".to_string(),
+            cursor_id,
+        });
+        editor.handle_add_overlay(
+            buffer_id,
+            None,
+            6..11,
+            (255, 0, 0),
+            false,
+            false,
+            false,
+            Some(revision),
+        );
+        assert_eq!(overlay_ranges(&editor), vec![9..14]);
+
+        // Text edited inside the range drops it
+        edit(&mut editor, |cursor_id| Event::Delete {
+            range: 9..10,
+            deleted_text: "w".to_string(),
+            cursor_id,
+        });
+        editor.handle_add_overlay(
+            buffer_id,
+            None,
+            6..11,
+            (255, 0, 0),
+            false,
+            false,
+            false,
+            Some(revision),
+        );
+        assert_eq!(overlay_ranges(&editor).len(), 1);
+    }
+
+    #[test]
+    fn test_view_transform_from_old_revision_is_dropped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut editor = editor_with_file(&dir, "hello world\n");
+        let buffer_id = editor.active_buffer;
+        let split_id = editor.split_manager.active_split();
+        let payload = |revision| ViewTransformPayload {
+            range: 0..12,
+            tokens: Vec::new(),
+            layout_hints: None,
+            revision: Some(revision),
+        };
+        let old_revision = editor.active_state().edit_history.revision();
+        edit(&mut editor, |cursor_id| Event::Insert {
+            position: 0,
+            text: "# ".to_string(),
+            cursor_id,
+        });
+
+        editor.handle_submit_view_transform(buffer_id, Some(split_id), payload(old_revision));
+        assert!(editor
+            .split_view_states
+            .get(&split_id)
+            .is_none_or(|view_state| view_state.view_transform.is_none()));
+
+        let revision = editor.active_state().edit_history.revision();
+        editor.handle_submit_view_transform(buffer_id, Some(split_id), payload(revision));
+        assert!(editor.split_view_states[&split_id].view_transform.is_some());
+    }
+}
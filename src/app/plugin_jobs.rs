@@ -0,0 +1,101 @@
+//! Jobs started by plugins (`editor.spawnJob`)
+//!
+//! Jobs run on the editor's async runtime like project tasks. Their output is
+//! passed to plugins line by line through the `job_output` hook, followed by
+//! a `job_exit` hook, and running jobs are listed in the status bar.
+
+use super::Editor;
+use crate::services::plugins::hooks::HookArgs;
+use crate::services::plugins::process::{self, JobSpec, JobStream};
+use tokio::sync::oneshot;
+
+/// A running plugin job
+pub(super) struct PluginJob {
+    /// Shown in the status bar while the job runs
+    pub title: String,
+    /// Kills the job when sent on (None once killJob was called)
+    pub cancel: Option<oneshot::Sender<()>>,
+}
+
+impl Editor {
+    /// Handle SpawnJob command
+    pub(super) fn handle_spawn_job(&mut self, job_id: u64, spec: JobSpec, title: String) {
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            self.set_status_error("Async runtime not available".to_string());
+            self.handle_plugin_job_exited(job_id, -1, false);
+            return;
+        };
+
+        let (cancel, cancel_rx) = oneshot::channel();
+        runtime.spawn(process::run_plugin_job(
+            job_id,
+            spec,
+            self.project_env.clone(),
+            bridge.sender(),
+            cancel_rx,
+        ));
+        self.plugin_jobs.insert(
+            job_id,
+            PluginJob {
+                title,
+                cancel: Some(cancel),
+            },
+        );
+    }
+
+    /// Handle KillJob command
+    pub(super) fn handle_kill_job(&mut self, job_id: u64) {
+        // The entry is removed when the job reports its exit
+        if let Some(cancel) = self
+            .plugin_jobs
+            .get_mut(&job_id)
+            .and_then(|job| job.cancel.take())
+        {
+            let _ = cancel.send(());
+        }
+    }
+
+    /// Pass a line of job output to plugins
+    pub(super) fn handle_plugin_job_output(
+        &mut self,
+        job_id: u64,
+        stream: JobStream,
+        line: String,
+    ) {
+        if let Some(ref ts_manager) = self.ts_plugin_manager {
+            ts_manager.run_hook(
+                "job_output",
+                HookArgs::JobOutput {
+                    job_id,
+                    stream: stream.as_str().to_string(),
+                    line,
+                },
+            );
+        }
+    }
+
+    /// Forget a finished job and tell plugins how it ended
+    pub(super) fn handle_plugin_job_exited(&mut self, job_id: u64, exit_code: i32, killed: bool) {
+        self.plugin_jobs.remove(&job_id);
+        if let Some(ref ts_manager) = self.ts_plugin_manager {
+            ts_manager.run_hook(
+                "job_exit",
+                HookArgs::JobExit {
+                    job_id,
+                    exit_code,
+                    killed,
+                },
+            );
+        }
+    }
+
+    /// Status bar text for running jobs ("title" or "title (+2 jobs)")
+    pub(super) fn plugin_jobs_status(&self) -> Option<String> {
+        let oldest = self.plugin_jobs.keys().min()?;
+        let title = &self.plugin_jobs[oldest].title;
+        Some(match self.plugin_jobs.len() {
+            1 => format!("{}...", title),
+            n => format!("{}... (+{} jobs)", title, n - 1),
+        })
+    }
+}
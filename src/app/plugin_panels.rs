@@ -0,0 +1,219 @@
+//! Panels drawn by plugins
+//!
+//! A plugin docks a panel on the left, right or bottom of the editor area
+//! (see `editor.registerPanel`). The editor lays the panels out and draws
+//! their borders; whenever a panel's size or focus changes it fires the
+//! `panel_render` hook, and the plugin answers with the text to show, which is
+//! kept until the next answer. A focused panel gets keys first through its
+//! mode's keybindings; Escape gives the focus back to the buffer.
+
+use super::Editor;
+use crate::input::keybindings::KeyContext;
+use crate::services::plugins::api::{PanelPosition, PanelText};
+use crate::services::plugins::hooks::HookArgs;
+use crate::view::ui::PluginPanelRenderer;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+/// Columns (or rows) left to the buffers when docking panels; panels that
+/// don't fit are hidden
+const MIN_EDITOR_SIZE: u16 = 10;
+
+/// A panel registered by a plugin
+pub(super) struct PluginPanel {
+    pub id: String,
+    pub title: String,
+    pub position: PanelPosition,
+    /// Width (left/right) or height (bottom), borders included
+    pub size: u16,
+    /// Mode whose keybindings apply while the panel is focused
+    pub mode: Option<String>,
+    /// Text drawn by the plugin
+    pub content: Vec<PanelText>,
+    /// Content size and focus the plugin last drew for (None: draw again)
+    pub rendered: Option<(u16, u16, bool)>,
+}
+
+impl Editor {
+    /// Handle RegisterPanel command (re-registering updates the panel)
+    pub(super) fn handle_register_panel(
+        &mut self,
+        id: String,
+        title: String,
+        position: PanelPosition,
+        size: u16,
+        mode: Option<String>,
+    ) {
+        if let Some(panel) = self.plugin_panels.iter_mut().find(|p| p.id == id) {
+            panel.title = title;
+            panel.position = position;
+            panel.size = size;
+            panel.mode = mode;
+            panel.rendered = None;
+            return;
+        }
+        self.plugin_panels.push(PluginPanel {
+            id,
+            title,
+            position,
+            size,
+            mode,
+            content: Vec::new(),
+            rendered: None,
+        });
+    }
+
+    /// Handle UnregisterPanel command
+    pub(super) fn handle_unregister_panel(&mut self, id: &str) {
+        self.plugin_panels.retain(|p| p.id != id);
+        if self.focused_panel.as_deref() == Some(id) {
+            self.focused_panel = None;
+        }
+    }
+
+    /// Handle SetPanelContent command
+    pub(super) fn handle_set_panel_content(&mut self, id: &str, content: Vec<PanelText>) {
+        if let Some(panel) = self.plugin_panels.iter_mut().find(|p| p.id == id) {
+            panel.content = content;
+        }
+    }
+
+    /// Handle RefreshPanel command
+    pub(super) fn handle_refresh_panel(&mut self, id: &str) {
+        if let Some(panel) = self.plugin_panels.iter_mut().find(|p| p.id == id) {
+            panel.rendered = None;
+        }
+    }
+
+    /// Give the keyboard focus to a panel
+    pub(super) fn focus_panel(&mut self, id: &str) {
+        if !self.plugin_panels.iter().any(|p| p.id == id) {
+            return;
+        }
+        self.focused_panel = Some(id.to_string());
+        if self.key_context == KeyContext::FileExplorer {
+            self.key_context = KeyContext::Normal;
+        }
+    }
+
+    /// Check if the panel `id` has the keyboard focus
+    fn is_panel_focused(&self, id: &str) -> bool {
+        self.focused_panel.as_deref() == Some(id) && self.key_context == KeyContext::Normal
+    }
+
+    /// Dock the plugin panels in `area`, draw them and return what is left
+    /// for the buffers
+    pub(super) fn render_plugin_panels(&mut self, frame: &mut Frame, area: Rect) -> Rect {
+        self.cached_layout.panel_areas.clear();
+        let mut remaining = area;
+
+        for index in 0..self.plugin_panels.len() {
+            let panel = &self.plugin_panels[index];
+            let panel_area = match panel.position {
+                PanelPosition::Left | PanelPosition::Right => {
+                    if remaining.width < panel.size + MIN_EDITOR_SIZE {
+                        continue;
+                    }
+                    remaining.width -= panel.size;
+                    let x = if panel.position == PanelPosition::Left {
+                        remaining.x += panel.size;
+                        remaining.x - panel.size
+                    } else {
+                        remaining.x + remaining.width
+                    };
+                    Rect::new(x, remaining.y, panel.size, remaining.height)
+                }
+                PanelPosition::Bottom => {
+                    if remaining.height < panel.size + MIN_EDITOR_SIZE {
+                        continue;
+                    }
+                    remaining.height -= panel.size;
+                    Rect::new(
+                        remaining.x,
+                        remaining.y + remaining.height,
+                        remaining.width,
+                        panel.size,
+                    )
+                }
+            };
+
+            let focused = self.is_panel_focused(&panel.id);
+            let inner = PluginPanelRenderer::render(
+                frame,
+                panel_area,
+                &panel.title,
+                &panel.content,
+                focused,
+                &self.theme,
+            );
+            self.cached_layout
+                .panel_areas
+                .push((panel.id.clone(), panel_area));
+
+            let drawn_for = Some((inner.width, inner.height, focused));
+            if panel.rendered != drawn_for {
+                if let Some(ref ts_manager) = self.ts_plugin_manager {
+                    ts_manager.run_hook(
+                        "panel_render",
+                        HookArgs::PanelRender {
+                            panel_id: panel.id.clone(),
+                            width: inner.width,
+                            height: inner.height,
+                            focused,
+                        },
+                    );
+                }
+                self.plugin_panels[index].rendered = drawn_for;
+            }
+        }
+
+        remaining
+    }
+
+    /// ID of the panel at a screen position
+    pub(super) fn panel_at(&self, col: u16, row: u16) -> Option<String> {
+        self.cached_layout
+            .panel_areas
+            .iter()
+            .find(|(_, rect)| {
+                col >= rect.x
+                    && col < rect.x + rect.width
+                    && row >= rect.y
+                    && row < rect.y + rect.height
+            })
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Handle a key while a panel has the focus
+    ///
+    /// Returns None for keys the panel leaves to the global keymap (unbound
+    /// keys with Ctrl or Alt); other unbound keys are ignored.
+    pub(super) fn handle_panel_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<std::io::Result<()>> {
+        let id = self.focused_panel.clone()?;
+        if code == KeyCode::Esc && modifiers.is_empty() {
+            self.focused_panel = None;
+            return Some(Ok(()));
+        }
+
+        let command_name = self
+            .plugin_panels
+            .iter()
+            .find(|p| p.id == id)
+            .and_then(|p| p.mode.as_deref())
+            .and_then(|mode| self.mode_registry.resolve_keybinding(mode, code, modifiers));
+        if let Some(command_name) = command_name {
+            return Some(self.run_mode_command(command_name));
+        }
+
+        if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+            None
+        } else {
+            Some(Ok(()))
+        }
+    }
+}
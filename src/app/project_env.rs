@@ -0,0 +1,91 @@
+//! Loading the project's `.envrc`
+//!
+//! With `editor.load_envrc` on, the variables the project's `.envrc` exports
+//! (see `services::project_env`) are read in the background at startup, and
+//! again by "Reload Environment". They are kept here rather than set in the
+//! editor's own environment, and given to the tasks, evaluators, plugin
+//! processes and language servers it starts; language servers already
+//! running are restarted when they change. The status bar shows when an
+//! environment is loaded.
+
+use super::Editor;
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::project_env::{self, ProjectEnv};
+use std::sync::Arc;
+
+impl Editor {
+    /// Load the project's `.envrc` if `editor.load_envrc` is on
+    pub fn init_project_env(&mut self) {
+        if self.config.editor.load_envrc {
+            self.reload_project_env();
+        }
+    }
+
+    /// Read the project's `.envrc` again, replacing what was loaded from it
+    pub fn reload_project_env(&mut self) {
+        if self
+            .project_env_task
+            .is_some_and(|task| self.is_task_running(task))
+        {
+            self.set_status_message("Environment is being loaded".to_string());
+            return;
+        }
+
+        let root = self.working_dir.clone();
+        self.project_env_task =
+            self.spawn_task("Loading environment".to_string(), move |_| async move {
+                let result =
+                    tokio::task::spawn_blocking(move || project_env::load_project_env(&root))
+                        .await
+                        .unwrap_or_else(|e| Err(e.to_string()));
+                AsyncMessage::ProjectEnvLoaded { result }
+            });
+    }
+
+    /// Keep the variables read from the `.envrc` for the processes started
+    /// from now on
+    pub(super) fn handle_project_env_loaded(&mut self, result: Result<Option<ProjectEnv>, String>) {
+        self.project_env_task = None;
+        let env = match result {
+            Ok(env) => env.map(Arc::new),
+            Err(e) => {
+                self.set_status_error(format!("Failed to load environment: {}", e));
+                return;
+            }
+        };
+        let changed =
+            self.project_env.as_ref().map(|env| &env.vars) != env.as_ref().map(|env| &env.vars);
+        self.project_env = env.clone();
+
+        if changed {
+            let running = match self.lsp.as_mut() {
+                Some(lsp) => {
+                    lsp.set_project_env(env.clone());
+                    lsp.running_servers()
+                }
+                None => Vec::new(),
+            };
+            for language in running {
+                self.restart_lsp_server(&language);
+            }
+        }
+        self.set_status_message(match env {
+            Some(env) => format!(
+                "Loaded {} {} from {}",
+                env.vars.len(),
+                if env.vars.len() == 1 {
+                    "variable"
+                } else {
+                    "variables"
+                },
+                project_env::ENVRC
+            ),
+            None => format!("No {} in the project", project_env::ENVRC),
+        });
+    }
+
+    /// Status bar text shown while an environment is loaded
+    pub(super) fn project_env_status(&self) -> Option<String> {
+        self.project_env.as_ref().map(|_| "env: direnv".to_string())
+    }
+}
@@ -0,0 +1,184 @@
+//! The project's file list in the editor
+//!
+//! The files (see `services::project_scan`) are scanned in the background at
+//! startup, and again when the file watcher or a save shows that a file was
+//! added or removed, or when the file filter changes. The search and symbol
+//! indexes wait for a scan to index from, and plugins read it with
+//! `listProjectFiles`. While auto-revert is on, the scanned directories are
+//! watched.
+
+use super::Editor;
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::project_scan::{self, ProjectScan};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Most directories of a scan that are watched
+const MAX_WATCHED_PROJECT_DIRS: usize = 4096;
+
+/// Work waiting for the project's files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ProjectScanUser {
+    SymbolIndex,
+    SearchIndex,
+    BufferStatistics,
+    FileExplorerFilter,
+}
+
+impl Editor {
+    /// Scan the project's files in the background
+    pub fn init_project_scan(&mut self) {
+        self.start_project_scan();
+    }
+
+    /// The project's files for `user`, or None if they are being scanned;
+    /// `user` is then started again once the scan is done
+    pub(super) fn project_files_for(&mut self, user: ProjectScanUser) -> Option<Arc<ProjectScan>> {
+        if let Some(scan) = &self.project_scan {
+            return Some(Arc::clone(scan));
+        }
+        if !self.project_scan_waiting.contains(&user) {
+            self.project_scan_waiting.push(user);
+        }
+        self.start_project_scan();
+        None
+    }
+
+    /// Whether `user` is waiting for a scan
+    pub(super) fn is_waiting_for_project_scan(&self, user: ProjectScanUser) -> bool {
+        self.project_scan_waiting.contains(&user)
+    }
+
+    /// Start scanning the project, unless a scan is running
+    fn start_project_scan(&mut self) {
+        if self
+            .project_scan_task
+            .is_some_and(|task| self.is_task_running(task))
+        {
+            return;
+        }
+        self.project_scan_outdated = false;
+
+        let root = self.working_dir.clone();
+        let filter = (*self.file_filter).clone();
+        let task = self.spawn_task("Scanning files".to_string(), move |progress| async move {
+            let scan = tokio::task::spawn_blocking(move || {
+                project_scan::scan_project(&root, &filter, &|files| {
+                    progress.report(format!("{} files", files))
+                })
+            })
+            .await
+            .unwrap_or_default();
+            AsyncMessage::ProjectScanned {
+                scan: Box::new(scan),
+            }
+        });
+        if task.is_none() {
+            // Nothing will come back to start them
+            self.project_scan_waiting.clear();
+        }
+        self.project_scan_task = task;
+    }
+
+    /// Take a finished scan and start what was waiting for it
+    pub(super) fn handle_project_scanned(&mut self, scan: ProjectScan) {
+        self.project_scan_task = None;
+        if self.project_scan_outdated {
+            // Files were added or removed while it ran
+            self.start_project_scan();
+            return;
+        }
+
+        for cycle in &scan.cycles {
+            tracing::warn!(
+                "Symbolic link cycle not followed: {}",
+                self.working_dir.join(cycle).display()
+            );
+        }
+        tracing::info!(
+            "Project scanned: {} files in {} directories",
+            scan.files.len(),
+            scan.dirs.len()
+        );
+        self.watch_project_dirs(&scan);
+        self.project_scan = Some(Arc::new(scan));
+
+        for user in std::mem::take(&mut self.project_scan_waiting) {
+            match user {
+                ProjectScanUser::SymbolIndex => self.start_symbol_index(),
+                ProjectScanUser::SearchIndex => self.start_search_index(),
+                ProjectScanUser::BufferStatistics => self.show_buffer_statistics(),
+                ProjectScanUser::FileExplorerFilter => self.update_file_explorer_filter(),
+            }
+        }
+    }
+
+    /// Scan again if `path` was added to or removed from the project
+    pub(super) fn project_path_changed(&mut self, path: &Path) {
+        let Ok(relative) = path.strip_prefix(&self.working_dir) else {
+            return;
+        };
+        // Hidden files aren't scanned
+        if relative
+            .components()
+            .any(|part| part.as_os_str().to_string_lossy().starts_with('.'))
+        {
+            return;
+        }
+        let stale = match &self.project_scan {
+            Some(scan) => match std::fs::metadata(path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    !scan.contains_dir(relative) && !self.file_filter.is_excluded(path, true)
+                }
+                Ok(_) => {
+                    !scan.contains_file(relative) && !self.file_filter.is_excluded(path, false)
+                }
+                Err(_) => scan.contains_file(relative) || scan.contains_dir(relative),
+            },
+            // The running scan may have missed it
+            None => self.project_scan_task.is_some(),
+        };
+        if stale {
+            self.rescan_project();
+        }
+    }
+
+    /// Drop the scanned files and scan again
+    pub(super) fn rescan_project(&mut self) {
+        self.project_scan = None;
+        if self
+            .project_scan_task
+            .is_some_and(|task| self.is_task_running(task))
+        {
+            self.project_scan_outdated = true;
+        } else {
+            self.start_project_scan();
+        }
+    }
+
+    /// Watch the scanned directories for files being added or removed
+    fn watch_project_dirs(&mut self, scan: &ProjectScan) {
+        use notify::{RecursiveMode, Watcher};
+
+        if !self.auto_revert_enabled {
+            return;
+        }
+        if self.file_watcher.is_none() {
+            self.start_file_watcher();
+        }
+        let Some(watcher) = &mut self.file_watcher else {
+            return;
+        };
+        for dir in scan.dirs.iter().take(MAX_WATCHED_PROJECT_DIRS) {
+            let dir = self.working_dir.join(dir);
+            if self.watched_dirs.contains(&dir) {
+                continue;
+            }
+            if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                tracing::warn!("Failed to watch directory {:?}: {}", dir, e);
+                break;
+            }
+            self.watched_dirs.insert(dir);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Project tasks in the command palette
+//!
+//! Tasks from the project's `.fresh/tasks.toml` are registered as "Project: …"
+//! commands (and key bindings, if they have one). Running one executes its
+//! shell command in the background and handles the output as the task asks.
+
+use super::Editor;
+use crate::input::commands::{Command, CommandSource};
+use crate::input::keybindings::{Action, KeyContext, KeybindingResolver};
+use crate::model::event::{BufferId, Event};
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::project_tasks::{self, TaskOutput, TaskResult, COMMAND_PREFIX};
+
+impl Editor {
+    /// Load the project's tasks into the command palette
+    ///
+    /// Replaces the commands and key bindings of previously loaded tasks, so
+    /// tasks from another workspace never linger. Returns the number of tasks.
+    pub fn load_project_tasks(&mut self) -> Result<usize, String> {
+        self.command_registry
+            .read()
+            .unwrap()
+            .unregister_by_prefix(COMMAND_PREFIX);
+        self.keybindings
+            .remove_bindings_where(|action| matches!(action, Action::RunProjectTask(_)));
+        self.project_tasks.clear();
+
+        self.project_tasks = project_tasks::load_tasks(&self.working_dir)?;
+        {
+            let registry = self.command_registry.read().unwrap();
+            for task in &self.project_tasks {
+                registry.register(Command {
+                    name: task.command_name(),
+                    description: task
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| task.command.clone()),
+                    action: Action::RunProjectTask(task.name.clone()),
+                    contexts: vec![],
+                    source: CommandSource::Project,
+                    when: None,
+                    argument: None,
+                });
+            }
+        }
+        self.bind_project_tasks()?;
+
+        Ok(self.project_tasks.len())
+    }
+
+    /// Add key bindings for the loaded tasks that define one
+    ///
+    /// Needed again whenever the keybinding resolver is rebuilt.
+    pub(crate) fn bind_project_tasks(&mut self) -> Result<(), String> {
+        let mut invalid = Vec::new();
+        for task in &self.project_tasks {
+            let Some(key) = &task.key else {
+                continue;
+            };
+            match KeybindingResolver::parse_key_string(key) {
+                Some((code, modifiers)) => self.keybindings.add_binding(
+                    KeyContext::Normal,
+                    code,
+                    modifiers,
+                    Action::RunProjectTask(task.name.clone()),
+                ),
+                None => invalid.push(format!("'{}' for '{}'", key, task.name)),
+            }
+        }
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("invalid key {}", invalid.join(", ")))
+        }
+    }
+
+    /// Reload the tasks after `.fresh/tasks.toml` was edited
+    pub(crate) fn reload_project_tasks(&mut self) {
+        match self.load_project_tasks() {
+            Ok(1) => self.set_status_message("Loaded 1 project task".to_string()),
+            Ok(count) => self.set_status_message(format!("Loaded {} project tasks", count)),
+            Err(e) => self.set_status_warning(format!("Project tasks: {}", e)),
+        }
+    }
+
+    /// Run a project task in the background (see `spawn_task`)
+    pub(crate) fn run_project_task(&mut self, name: &str) {
+        let Some(task) = self.project_tasks.iter().find(|t| t.name == name).cloned() else {
+            self.set_status_error(format!("Unknown project task '{}'", name));
+            return;
+        };
+        // ${file}, ${line}, ... refer to the buffer the task is run from
+        let command = self.expand_command_variables(&task.command);
+        let working_dir = self.working_dir.clone();
+        let project_env = self.project_env.clone();
+        // The last line printed is shown as the task's progress
+        self.spawn_task(task.name.clone(), move |progress| async move {
+            let result =
+                project_tasks::run_task(&command, &working_dir, project_env.as_deref(), |line| {
+                    if !line.trim().is_empty() {
+                        progress.report(line);
+                    }
+                })
+                .await;
+            AsyncMessage::ProjectTaskOutput {
+                name: task.name,
+                result,
+            }
+        });
+    }
+
+    /// Handle a finished project task according to its output setting
+    pub(super) fn handle_project_task_output(
+        &mut self,
+        name: String,
+        result: std::io::Result<TaskResult>,
+    ) {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.set_status_error(format!("Failed to run '{}': {}", name, e));
+                return;
+            }
+        };
+        let output = self
+            .project_tasks
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.output)
+            .unwrap_or_default();
+
+        if output == TaskOutput::Buffer {
+            self.show_project_task_output(&name, &result);
+        }
+
+        if result.exit_code != 0 {
+            let reason = last_line(&result.stderr)
+                .or_else(|| last_line(&result.stdout))
+                .map(|line| format!(": {}", line))
+                .unwrap_or_default();
+            self.set_status_error(format!(
+                "'{}' failed (exit code {}){}",
+                name, result.exit_code, reason
+            ));
+            return;
+        }
+
+        match output {
+            TaskOutput::Buffer => self.set_status_message(format!("'{}' finished", name)),
+            TaskOutput::Status => {
+                let message = last_line(&result.stdout)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("'{}' finished", name));
+                self.set_status_message(message);
+            }
+            TaskOutput::Insert => self.insert_project_task_output(&result.stdout),
+            TaskOutput::None => {}
+        }
+    }
+
+    /// Show a task's output in a read-only "*Project: name*" buffer
+    ///
+    /// Running the task again reuses its buffer.
+    fn show_project_task_output(&mut self, name: &str, result: &TaskResult) {
+        let buffer_name = format!("*{}{}*", COMMAND_PREFIX, name);
+        let existing = self
+            .buffer_metadata
+            .iter()
+            .find(|(_, m)| m.display_name == buffer_name)
+            .map(|(id, _)| *id);
+        let buffer_id: BufferId = existing.unwrap_or_else(|| {
+            self.create_virtual_buffer(buffer_name, "special".to_string(), true)
+        });
+
+        let mut content = result.stdout.clone();
+        content.push_str(&result.stderr);
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("\n[exit code {}]\n", result.exit_code));
+
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let len = state.buffer.len();
+            if len > 0 {
+                state.buffer.delete_bytes(0, len);
+            }
+            state.buffer.insert(0, &content);
+            state.buffer.clear_modified();
+            state.editing_disabled = true;
+            state.cursors.primary_mut().position = 0;
+            state.cursors.primary_mut().anchor = None;
+        }
+        self.set_active_buffer(buffer_id);
+    }
+
+    /// Insert a task's stdout at the cursor (without its final newline)
+    fn insert_project_task_output(&mut self, stdout: &str) {
+        if self.is_editing_disabled() {
+            self.set_status_message("Editing disabled in this buffer".to_string());
+            return;
+        }
+        let text = stdout.strip_suffix('\n').unwrap_or(stdout);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        if text.is_empty() {
+            return;
+        }
+
+        let state = self.active_state();
+        let event = Event::Insert {
+            position: state.cursors.primary().position,
+            text: text.to_string(),
+            cursor_id: state.cursors.primary_id(),
+        };
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
+    }
+}
+
+/// Last non-empty line of some output
+fn last_line(output: &str) -> Option<&str> {
+    output.lines().map(str::trim).rfind(|line| !line.is_empty())
+}
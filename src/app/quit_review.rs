@@ -0,0 +1,228 @@
+//! Reviewing unsaved buffers before quitting
+//!
+//! Quitting with modified buffers lists them and offers to save them all,
+//! discard them all, or go through them one at a time. Each step of the review
+//! shows the buffer's pending changes as a diff against the file on disk.
+
+use super::confirm_dialog::ConfirmAction;
+use super::Editor;
+use crate::model::event::BufferId;
+use crate::model::line_diff::{unified_diff, DiffLineKind};
+use crate::view::confirm_dialog::{
+    ConfirmChoice, ConfirmDialog, DetailLine, DetailStyle, DialogButton,
+};
+
+/// Unchanged lines shown around each change in the preview
+const PREVIEW_CONTEXT_LINES: usize = 2;
+
+/// Buffers still to be reviewed, while a "Save As" for an unnamed one is open
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct QuitReview {
+    pub(crate) remaining: Vec<BufferId>,
+    pub(crate) total: usize,
+}
+
+impl Editor {
+    /// Ask what to do with the modified buffers before quitting
+    pub(crate) fn confirm_quit(&mut self) {
+        let modified = self.modified_buffer_ids();
+        let message = if modified.len() == 1 {
+            "1 buffer has unsaved changes:".to_string()
+        } else {
+            format!("{} buffers have unsaved changes:", modified.len())
+        };
+        let details = modified
+            .iter()
+            .map(|&id| {
+                let name = self.get_buffer_display_name(id);
+                DetailLine::new(format!("• {}", name), DetailStyle::Normal)
+            })
+            .collect();
+
+        let dialog = ConfirmDialog::new(
+            "Quit",
+            message,
+            vec![
+                DialogButton::new("Save All", 's', ConfirmChoice::Yes),
+                DialogButton::new("Discard All", 'd', ConfirmChoice::No),
+                DialogButton::new("Review", 'r', ConfirmChoice::Review),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_details(details)
+        .with_focus(ConfirmChoice::Cancel);
+        self.show_confirm_dialog(dialog, ConfirmAction::Quit);
+    }
+
+    /// Modified buffers, in the order they were opened
+    fn modified_buffer_ids(&self) -> Vec<BufferId> {
+        let mut ids: Vec<BufferId> = self
+            .buffers
+            .keys()
+            .copied()
+            .filter(|&id| self.buffer_needs_saving(id))
+            .collect();
+        ids.sort_by_key(|id| id.0);
+        ids
+    }
+
+    /// Step through every modified buffer, then quit
+    pub(crate) fn start_quit_review(&mut self) {
+        let remaining = self.modified_buffer_ids();
+        let total = remaining.len();
+        self.continue_quit_review(remaining, total);
+    }
+
+    /// Save every modified buffer, then quit
+    ///
+    /// Buffers without a file are reviewed afterwards so each gets a name.
+    pub(crate) fn save_all_and_quit(&mut self) {
+        let mut unnamed = Vec::new();
+        for buffer_id in self.modified_buffer_ids() {
+            let has_path = self
+                .buffers
+                .get(&buffer_id)
+                .is_some_and(|s| s.buffer.file_path().is_some());
+            if !has_path {
+                unnamed.push(buffer_id);
+                continue;
+            }
+
+            let old_active = self.active_buffer;
+            self.set_active_buffer(buffer_id);
+            let saved = self.save();
+            self.set_active_buffer(old_active);
+            if let Err(e) = saved {
+                let name = self.get_buffer_display_name(buffer_id);
+                self.set_status_error(format!("Failed to save '{}': {}", name, e));
+                return;
+            }
+        }
+
+        let total = unnamed.len();
+        self.continue_quit_review(unnamed, total);
+    }
+
+    /// Ask about the next buffer still to be reviewed (quits when none are left)
+    pub(crate) fn continue_quit_review(&mut self, mut remaining: Vec<BufferId>, total: usize) {
+        // Skip buffers that were closed or saved in the meantime
+        remaining.retain(|&id| self.buffer_needs_saving(id));
+        if remaining.is_empty() {
+            self.should_quit = true;
+            return;
+        }
+
+        let buffer_id = remaining.remove(0);
+        let index = total.saturating_sub(remaining.len()).max(1);
+        self.set_active_buffer(buffer_id);
+
+        let name = self.get_buffer_display_name(buffer_id);
+        let dialog = ConfirmDialog::new(
+            format!("Unsaved Changes ({} of {})", index, total.max(index)),
+            format!("Save changes to '{}'?", name),
+            vec![
+                DialogButton::new("Save", 's', ConfirmChoice::Yes),
+                DialogButton::new("Discard", 'd', ConfirmChoice::No),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_details(self.unsaved_changes_preview(buffer_id));
+        self.show_confirm_dialog(
+            dialog,
+            ConfirmAction::ReviewQuit {
+                buffer_id,
+                remaining,
+                total,
+            },
+        );
+    }
+
+    /// Save a buffer during the quit review, then move on to the next one
+    ///
+    /// Buffers without a file go through "Save As" first; the review picks up
+    /// again once that completes.
+    pub(crate) fn save_and_continue_quit_review(
+        &mut self,
+        buffer_id: BufferId,
+        remaining: Vec<BufferId>,
+        total: usize,
+    ) {
+        let has_path = self
+            .buffers
+            .get(&buffer_id)
+            .is_some_and(|s| s.buffer.file_path().is_some());
+
+        if !has_path {
+            self.set_active_buffer(buffer_id);
+            self.pending_quit_review = Some(QuitReview { remaining, total });
+            self.start_prompt_with_initial_text(
+                "Save as: ".to_string(),
+                crate::view::prompt::PromptType::SaveFileAs,
+                String::new(),
+            );
+            return;
+        }
+
+        let old_active = self.active_buffer;
+        self.set_active_buffer(buffer_id);
+        let saved = self.save();
+        self.set_active_buffer(old_active);
+        if let Err(e) = saved {
+            self.set_status_error(format!("Failed to save: {}", e));
+            return;
+        }
+
+        self.continue_quit_review(remaining, total);
+    }
+
+    /// Diff of a buffer's content against its file on disk
+    ///
+    /// Buffers without a file show all of their content as added.
+    fn unsaved_changes_preview(&self, buffer_id: BufferId) -> Vec<DetailLine> {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return Vec::new();
+        };
+        let Some(current) = state.buffer.to_string() else {
+            return vec![DetailLine::new(
+                "(file too large to preview)",
+                DetailStyle::Muted,
+            )];
+        };
+        let saved = state
+            .buffer
+            .file_path()
+            .and_then(|path| std::fs::read(path).ok())
+            .unwrap_or_default();
+
+        let Some(diff) = unified_diff(&saved, current.as_bytes(), PREVIEW_CONTEXT_LINES) else {
+            return vec![DetailLine::new(
+                "(too many changes to preview)",
+                DetailStyle::Muted,
+            )];
+        };
+        if diff.is_empty() {
+            return vec![DetailLine::new(
+                "(same as the file on disk)",
+                DetailStyle::Muted,
+            )];
+        }
+
+        diff.into_iter()
+            .map(|line| {
+                let text = line.text.replace('\t', "    ");
+                match line.kind {
+                    DiffLineKind::Context => {
+                        DetailLine::new(format!("  {}", text), DetailStyle::Normal)
+                    }
+                    DiffLineKind::Added => {
+                        DetailLine::new(format!("+ {}", text), DetailStyle::Added)
+                    }
+                    DiffLineKind::Removed => {
+                        DetailLine::new(format!("- {}", text), DetailStyle::Removed)
+                    }
+                    DiffLineKind::Gap => DetailLine::new("  …", DetailStyle::Muted),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,172 @@
+//! Cycling through buffers in most recently used order
+//!
+//! The editor keeps its buffers ordered by when they were last switched to.
+//! Ctrl+Tab goes to the next buffer in that order and Ctrl+Shift+Tab to the
+//! previous one, showing the list in a popup. Each press moves further down
+//! the list without reordering it; the buffer reached is moved to the front
+//! once any other key is pressed (which is then handled as usual), with
+//! Enter, or after a moment without keys, since terminals don't report
+//! releasing Ctrl. Esc goes back to the buffer the cycle started from.
+
+use super::Editor;
+use crate::input::keybindings::{Action, KeyContext};
+use crate::model::event::BufferId;
+use crate::view::ui::RecentBuffersRenderer;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+use std::time::{Duration, Instant};
+
+/// How long the popup stays up after the last Ctrl+Tab
+const CYCLE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// A Ctrl+Tab cycle in progress
+#[derive(Debug)]
+pub(crate) struct RecentBufferCycle {
+    /// Buffers in the order they were last used when the cycle started
+    order: Vec<BufferId>,
+    /// Position of the active buffer in `order`
+    index: usize,
+    last_key: Instant,
+}
+
+impl Editor {
+    /// Move `buffer_id` to the front of the recently used buffers
+    pub(super) fn touch_recent_buffer(&mut self, buffer_id: BufferId) {
+        self.recent_buffers.retain(|&id| id != buffer_id);
+        self.recent_buffers.insert(0, buffer_id);
+    }
+
+    /// Open tabs from the most to the least recently used, starting with the
+    /// active buffer; tabs never switched to come last, in tab order
+    fn recent_buffer_order(&mut self) -> Vec<BufferId> {
+        let tabs = self.tab_buffer_ids();
+        self.recent_buffers.retain(|id| tabs.contains(id));
+        let tabs = self.group_tabs(tabs);
+
+        let mut order = vec![self.active_buffer];
+        for &id in self.recent_buffers.iter().chain(&tabs) {
+            if tabs.contains(&id) && !order.contains(&id) {
+                order.push(id);
+            }
+        }
+        order
+    }
+
+    /// Switch `step` buffers further along the recently used order,
+    /// starting a cycle if none is in progress
+    pub(super) fn cycle_recent_buffers(&mut self, step: isize) {
+        let cycle = match self.recent_buffer_cycle.take() {
+            Some(cycle) => cycle,
+            None => {
+                let order = self.recent_buffer_order();
+                if order.len() < 2 {
+                    self.set_status_message("No other buffers".to_string());
+                    return;
+                }
+                RecentBufferCycle {
+                    order,
+                    index: 0,
+                    last_key: Instant::now(),
+                }
+            }
+        };
+        let len = cycle.order.len() as isize;
+        let index = (cycle.index as isize + step).rem_euclid(len) as usize;
+        let buffer_id = cycle.order[index];
+        self.recent_buffer_cycle = Some(RecentBufferCycle {
+            index,
+            last_key: Instant::now(),
+            ..cycle
+        });
+        if self.buffers.contains_key(&buffer_id) {
+            self.set_active_buffer(buffer_id);
+        }
+    }
+
+    /// Handle a key while cycling. Returns true if it was consumed; other
+    /// keys end the cycle and are then handled as usual.
+    pub(super) fn handle_recent_buffer_cycle_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> bool {
+        let action = self
+            .keybindings
+            .resolve(&KeyEvent::new(code, modifiers), KeyContext::Normal);
+        match (action, code) {
+            (Action::NextRecentBuffer | Action::PrevRecentBuffer, _) => false,
+            (_, KeyCode::Esc) => {
+                self.cancel_recent_buffer_cycle();
+                true
+            }
+            (_, KeyCode::Enter) => {
+                self.finish_recent_buffer_cycle();
+                true
+            }
+            _ => {
+                self.finish_recent_buffer_cycle();
+                false
+            }
+        }
+    }
+
+    /// End the cycle, moving the buffer it reached to the front
+    fn finish_recent_buffer_cycle(&mut self) {
+        let Some(cycle) = self.recent_buffer_cycle.take() else {
+            return;
+        };
+        let original = cycle.order[0];
+        self.touch_recent_buffer(original);
+        self.touch_recent_buffer(self.active_buffer);
+
+        // "Switch to Previous Tab" goes back to where the cycle started
+        let active_split = self.split_manager.active_split();
+        if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
+            if original != self.active_buffer {
+                view_state.previous_buffer = Some(original);
+            }
+        }
+    }
+
+    /// End the cycle, going back to the buffer it started from
+    fn cancel_recent_buffer_cycle(&mut self) {
+        let Some(original) = self
+            .recent_buffer_cycle
+            .as_ref()
+            .map(|cycle| cycle.order[0])
+        else {
+            return;
+        };
+        if self.buffers.contains_key(&original) {
+            self.set_active_buffer(original);
+        }
+        self.recent_buffer_cycle = None;
+    }
+
+    /// End the cycle once no key has been pressed for a while.
+    /// Returns true if the screen needs to be redrawn.
+    pub fn expire_recent_buffer_cycle(&mut self) -> bool {
+        let expired = self
+            .recent_buffer_cycle
+            .as_ref()
+            .is_some_and(|cycle| cycle.last_key.elapsed() >= CYCLE_TIMEOUT);
+        if expired {
+            self.finish_recent_buffer_cycle();
+        }
+        expired
+    }
+
+    /// Draw the recently used buffers while cycling
+    pub(super) fn render_recent_buffers(&self, frame: &mut Frame, area: Rect) {
+        let Some(cycle) = &self.recent_buffer_cycle else {
+            return;
+        };
+        let names: Vec<String> = cycle
+            .order
+            .iter()
+            .map(|&id| self.get_buffer_display_name(id))
+            .collect();
+        RecentBuffersRenderer::render(frame, area, &names, cycle.index, &self.theme);
+    }
+}
@@ -0,0 +1,205 @@
+//! Hard wrapping prose and comments
+//!
+//! "Reflow Paragraph" rewraps the selected lines, or the paragraph at the
+//! cursor, to `editor.hard_wrap_column`, and "Reflow to Columns" to a width
+//! it asks for. With `editor.auto_hard_wrap` (or "Toggle Auto Hard Wrap") a
+//! line is broken as soon as typing runs it past that column. In files of a
+//! known programming language only comments are wrapped; other files are
+//! prose (see `primitives::reflow`).
+
+use super::Editor;
+use crate::model::buffer::Buffer;
+use crate::model::event::Event;
+use crate::primitives::reflow::{self, WrapStyle};
+use crate::services::text_stats;
+use crate::view::prompt::PromptType;
+use std::ops::Range;
+
+impl Editor {
+    /// Rewrap the selected lines, or the paragraph or comment block at the
+    /// cursor, to `editor.hard_wrap_column`
+    pub fn reflow_paragraph(&mut self) {
+        self.reflow_paragraph_to(self.config.editor.hard_wrap_column);
+    }
+
+    /// Ask for a width, then reflow like "Reflow Paragraph" to it
+    pub(super) fn start_reflow_to_columns_prompt(&mut self) {
+        self.start_prompt_with_initial_text(
+            "Reflow to columns: ".to_string(),
+            PromptType::ReflowToColumns,
+            self.config.editor.hard_wrap_column.to_string(),
+        );
+    }
+
+    /// Rewrap the selected lines, or the paragraph or comment block at the
+    /// cursor, to `width` columns
+    pub(super) fn reflow_paragraph_to(&mut self, width: usize) {
+        let style = self.wrap_style();
+        let state = self.active_state_mut();
+        let cursor = *state.cursors.primary();
+        let cursor_id = state.cursors.primary_id();
+        let range = match cursor.selection_range() {
+            Some(selection) => Some(whole_lines(&mut state.buffer, selection)),
+            None => paragraph_at(&mut state.buffer, cursor.position, &style),
+        };
+        let Some(range) = range else {
+            self.set_status_message("No text to reflow here".to_string());
+            return;
+        };
+
+        let old_text = state.get_text_range(range.start, range.end);
+        let new_text = reflow::reflow(&old_text, width, &style);
+        if new_text == old_text {
+            self.set_status_message(format!("Already wrapped at {} columns", width));
+            return;
+        }
+        let batch = Event::Batch {
+            events: vec![
+                Event::Delete {
+                    range: range.clone(),
+                    deleted_text: old_text,
+                    cursor_id,
+                },
+                Event::Insert {
+                    position: range.start,
+                    text: new_text,
+                    cursor_id,
+                },
+            ],
+            description: "Reflow paragraph".to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+        self.set_status_message(format!("Reflowed to {} columns", width));
+    }
+
+    /// Turn hard wrapping while typing on or off
+    pub fn toggle_auto_hard_wrap(&mut self) {
+        self.config.editor.auto_hard_wrap = !self.config.editor.auto_hard_wrap;
+        let state = if self.config.editor.auto_hard_wrap {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        self.set_status_message(format!(
+            "Auto hard wrap {} at {} columns",
+            state, self.config.editor.hard_wrap_column
+        ));
+    }
+
+    /// After typing, break the cursor's line at its last space before the
+    /// wrap column if it runs past it. Only with a single cursor.
+    pub(super) fn hard_wrap_at_cursor(&mut self) {
+        let width = self.config.editor.hard_wrap_column;
+        let style = self.wrap_style();
+        let state = self.active_state_mut();
+        if state.cursors.count() > 1 {
+            return;
+        }
+        let cursor = *state.cursors.primary();
+        let cursor_id = state.cursors.primary_id();
+        let line_start = state
+            .buffer
+            .line_iterator(cursor.position, 80)
+            .current_position();
+        let line = state.get_text_range(line_start, cursor.position);
+        let Some((spaces, text)) = reflow::wrap_break(&line, width, &style) else {
+            return;
+        };
+
+        let range = line_start + spaces.start..line_start + spaces.end;
+        let deleted_text = state.get_text_range(range.start, range.end);
+        let new_position = cursor.position - range.len() + text.len();
+        let batch = Event::Batch {
+            events: vec![
+                Event::Delete {
+                    range: range.clone(),
+                    deleted_text,
+                    cursor_id,
+                },
+                Event::Insert {
+                    position: range.start,
+                    text: text.clone(),
+                    cursor_id,
+                },
+                Event::MoveCursor {
+                    cursor_id,
+                    old_position: range.start + text.len(),
+                    new_position,
+                    old_anchor: None,
+                    new_anchor: None,
+                    old_sticky_column: 0,
+                    new_sticky_column: 0,
+                },
+            ],
+            description: "Hard wrap".to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+    }
+
+    /// Which lines of the active buffer are text to wrap: comments in a
+    /// programming language, everything in other files
+    fn wrap_style(&self) -> WrapStyle {
+        let language = self
+            .buffer_metadata
+            .get(&self.active_buffer)
+            .and_then(|metadata| metadata.file_path())
+            .and_then(|path| text_stats::language_for_path(path));
+        match language {
+            None | Some(("Markdown", _)) => WrapStyle::PROSE,
+            Some((_, syntax)) => WrapStyle {
+                markers: syntax.line,
+                block_comments: syntax.block.is_some_and(|(open, _)| open == "/*"),
+                prose: false,
+            },
+        }
+    }
+}
+
+/// `range` extended to whole lines, without the last line's newline
+fn whole_lines(buffer: &mut Buffer, range: Range<usize>) -> Range<usize> {
+    let mut iter = buffer.line_iterator(range.start, 80);
+    let start = iter.current_position();
+    let mut end = start;
+    while let Some((line_start, line)) = iter.next() {
+        if line_start > start && line_start >= range.end {
+            break;
+        }
+        end = line_start + line.trim_end_matches(['\n', '\r']).len();
+    }
+    start..end
+}
+
+/// The lines of text around `position` continuing each other's paragraph
+/// (see `reflow::continues_paragraph`), without the last line's newline. None
+/// if the line at `position` is blank or code.
+fn paragraph_at(buffer: &mut Buffer, position: usize, style: &WrapStyle) -> Option<Range<usize>> {
+    let mut iter = buffer.line_iterator(position, 80);
+    let line_start = iter.current_position();
+    let (_, line) = iter.next()?;
+    if !reflow::is_text(&line, style) {
+        return None;
+    }
+    let mut end = line_start + line.trim_end_matches(['\n', '\r']).len();
+    let mut previous = line.clone();
+    while let Some((start, next)) = iter.next() {
+        if !reflow::continues_paragraph(&previous, &next, style) {
+            break;
+        }
+        end = start + next.trim_end_matches(['\n', '\r']).len();
+        previous = next;
+    }
+
+    let mut iter = buffer.line_iterator(line_start, 80);
+    let mut start = line_start;
+    let mut next = line;
+    while let Some((previous_start, previous)) = iter.prev() {
+        if !reflow::continues_paragraph(&previous, &next, style) {
+            break;
+        }
+        start = previous_start;
+        next = previous;
+    }
+    Some(start..end)
+}
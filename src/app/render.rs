@@ -1,10 +1,60 @@
 use super::*;
+use crate::view::prompt::{MatchCount, PromptError};
+
+/// Smallest terminal the editor is laid out in (menu bar, tab bar, two lines
+/// of text, status bar and prompt line)
+const MIN_TERMINAL_WIDTH: u16 = 20;
+const MIN_TERMINAL_HEIGHT: u16 = 6;
+
+/// Pause in typing a search query after which its matches are counted
+const SEARCH_COUNT_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
 
 impl Editor {
     /// Render the editor to the terminal
     pub fn render(&mut self, frame: &mut Frame) {
         let _span = tracing::trace_span!("render").entered();
         let size = frame.area();
+        self.latency.render_started();
+
+        // Nothing of the buffers shows while the screen is locked
+        if self.render_idle_lock(frame) {
+            self.latency.render_finished();
+            return;
+        }
+
+        // Line up the panes of a side-by-side diff
+        self.sync_buffer_diff_scroll();
+        self.sync_scroll_lock();
+
+        // Dim what the cursor moved away from
+        self.update_focus_mode();
+        // Lint the commit message after edits
+        self.update_commit_mode();
+
+        if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
+            self.render_terminal_too_small(frame);
+            self.latency.render_finished();
+            return;
+        }
+
+        // Scrolling options from the config, for every view of every buffer
+        let (typewriter, scroll_past_end) = (
+            self.config.editor.typewriter_scrolling,
+            self.config.editor.scroll_past_end,
+        );
+        let viewports = self
+            .buffers
+            .values_mut()
+            .map(|state| &mut state.viewport)
+            .chain(
+                self.split_view_states
+                    .values_mut()
+                    .map(|view_state| &mut view_state.viewport),
+            );
+        for viewport in viewports {
+            viewport.typewriter = typewriter;
+            viewport.scroll_past_end = scroll_past_end;
+        }
 
         // NOTE: Viewport sync with cursor is handled by split_rendering.rs which knows the
         // correct content area dimensions. Don't sync here with incorrect EditorState viewport size.
@@ -36,6 +86,9 @@ impl Editor {
             )
         });
 
+        // Show an inline error line when the prompt input is invalid (e.g., bad regex)
+        let show_prompt_error = self.prompt.as_ref().is_some_and(|p| p.error.is_some());
+
         // Hide status bar when suggestions popup or file browser popup is shown
         let has_suggestions = self
             .prompt
@@ -47,9 +100,11 @@ impl Editor {
             .map_or(false, |p| p.prompt_type == PromptType::OpenFile)
             && self.file_open_state.is_some();
 
-        // Build main vertical layout: [menu_bar, main_content, status_bar, search_options, prompt_line]
+        // Build main vertical layout:
+        // [menu_bar, main_content, status_bar, search_options, prompt_error, prompt_line]
         // Status bar is hidden when suggestions popup is shown
         // Search options bar is shown when in search prompt
+        // Prompt error line is shown when the prompt input fails validation
         let constraints = vec![
             Constraint::Length(1), // Menu bar
             Constraint::Min(0),    // Main content area
@@ -59,6 +114,7 @@ impl Editor {
                 1
             }), // Status bar (hidden with popups)
             Constraint::Length(if show_search_options { 1 } else { 0 }), // Search options bar
+            Constraint::Length(if show_prompt_error { 1 } else { 0 }), // Prompt error line
             Constraint::Length(1), // Prompt line (always reserved)
         ];
 
@@ -71,7 +127,8 @@ impl Editor {
         let main_content_area = main_chunks[1];
         let status_bar_idx = 2;
         let search_options_idx = 3;
-        let prompt_line_idx = 4;
+        let prompt_error_idx = 4;
+        let prompt_line_idx = 5;
 
         // Split main content area based on file explorer visibility
         let editor_content_area;
@@ -118,6 +175,7 @@ impl Editor {
                     horizontal_chunks[0],
                     is_focused,
                     &files_with_unsaved_changes,
+                    self.git_statuses.as_ref(),
                     &self.keybindings,
                     self.key_context,
                     &self.theme,
@@ -130,6 +188,9 @@ impl Editor {
             editor_content_area = main_content_area;
         }
 
+        // Dock plugin panels around the buffers
+        let editor_content_area = self.render_plugin_panels(frame, editor_content_area);
+
         // Note: Tabs are now rendered within each split by SplitRenderer
 
         // Trigger lines_changed hooks for newly visible lines in all visible buffers
@@ -141,6 +202,7 @@ impl Editor {
             // Get visible buffers and their areas
             let visible_buffers = self.split_manager.get_visible_buffers(editor_content_area);
 
+            let long_line_bytes = self.config.limits.long_line_bytes;
             let mut total_new_lines = 0usize;
             for (split_id, buffer_id, split_area) in visible_buffers {
                 if let Some(state) = self.buffers.get_mut(&buffer_id) {
@@ -170,6 +232,7 @@ impl Editor {
                         crate::services::plugins::hooks::HookArgs::ViewTransformRequest {
                             buffer_id,
                             split_id,
+                            revision: state.edit_history.revision(),
                             viewport_start,
                             viewport_end,
                             tokens: base_tokens,
@@ -198,8 +261,11 @@ impl Editor {
                             let byte_end = line_start + line_content.len();
                             let byte_range = (line_start, byte_end);
 
-                            // Only add if this byte range hasn't been seen before
-                            if !seen_byte_ranges.contains(&byte_range) {
+                            // Only add if this byte range hasn't been seen before. Huge
+                            // lines are left out: per-line plugin work on them would stall
+                            if !seen_byte_ranges.contains(&byte_range)
+                                && line_content.len() <= long_line_bytes
+                            {
                                 new_lines.push(crate::services::plugins::hooks::LineInfo {
                                     line_number,
                                     byte_start: line_start,
@@ -219,6 +285,7 @@ impl Editor {
                         total_new_lines += new_lines.len();
                         let hook_args = crate::services::plugins::hooks::HookArgs::LinesChanged {
                             buffer_id,
+                            revision: state.edit_history.revision(),
                             lines: new_lines,
                         };
                         ts_manager.run_hook("lines_changed", hook_args);
@@ -246,11 +313,12 @@ impl Editor {
         let lsp_waiting = self.pending_completion_request.is_some()
             || self.pending_goto_definition_request.is_some();
 
-        // Hide the hardware cursor when menu is open or file explorer is focused
+        // Hide the hardware cursor when menu is open or file explorer or a plugin panel is focused
         // (the file explorer will set its own cursor position when focused)
         // This also causes visual cursor indicators in the editor to be dimmed
-        let hide_cursor =
-            self.menu_state.active_menu.is_some() || self.key_context == KeyContext::FileExplorer;
+        let hide_cursor = self.menu_state.active_menu.is_some()
+            || self.key_context == KeyContext::FileExplorer
+            || self.focused_panel.is_some();
 
         // Convert HoverTarget to tab hover info for rendering
         let hovered_tab = match &self.mouse_state.hover_target {
@@ -267,6 +335,7 @@ impl Editor {
             _ => None,
         };
 
+        let group_buffers = self.active_group_buffers().map(<[BufferId]>::to_vec);
         let (split_areas, tab_areas, close_split_areas) = SplitRenderer::render_content(
             frame,
             editor_content_area,
@@ -285,6 +354,8 @@ impl Editor {
             hide_cursor,
             hovered_tab,
             hovered_close_split,
+            self.redactor.as_ref(),
+            group_buffers.as_deref(),
         );
         self.cached_layout.split_areas = split_areas;
         self.cached_layout.tab_areas = tab_areas;
@@ -306,13 +377,22 @@ impl Editor {
                 if let Some(file_open_state) = &self.file_open_state {
                     // Calculate popup area: position above prompt line, covering status bar
                     let max_height = main_chunks[prompt_line_idx].y.saturating_sub(1).min(20);
-                    let popup_area = ratatui::layout::Rect {
+                    let mut popup_area = ratatui::layout::Rect {
                         x: 0,
                         y: main_chunks[prompt_line_idx].y.saturating_sub(max_height),
                         width: size.width,
                         height: max_height,
                     };
 
+                    // Share the popup with the preview of the highlighted file
+                    if let Some(preview) = self.suggestion_preview() {
+                        let (list_area, preview_area) = PreviewRenderer::split(popup_area);
+                        if let Some(preview_area) = preview_area {
+                            PreviewRenderer::render(frame, preview_area, preview, &self.theme);
+                        }
+                        popup_area = list_area;
+                    }
+
                     self.file_browser_layout = crate::view::ui::FileBrowserRenderer::render(
                         frame,
                         popup_area,
@@ -322,19 +402,15 @@ impl Editor {
                     );
                 }
             } else if !prompt.suggestions.is_empty() {
-                // For other prompts, render suggestions as before
-                // Calculate overlay area: position above prompt line (which is below status bar)
-                let suggestion_count = prompt.suggestions.len().min(10);
-                let height = suggestion_count as u16 + 2; // +2 for borders
-
-                // Position suggestions above the prompt line
-                // The prompt line is at main_chunks[3], so suggestions go above it
-                let suggestions_area = ratatui::layout::Rect {
-                    x: 0,
-                    y: main_chunks[prompt_line_idx].y.saturating_sub(height),
-                    width: size.width,
-                    height,
-                };
+                // For other prompts, size the suggestions popup to its content and
+                // anchor it to the prompt line on whichever side has room
+                let suggestions_area = SuggestionsRenderer::popup_area(
+                    prompt,
+                    main_chunks[prompt_line_idx],
+                    size,
+                    self.config.editor.suggestions_max_height,
+                    self.config.editor.suggestions_max_width,
+                );
 
                 // Clear the area behind the suggestions to obscure underlying text
                 frame.render_widget(ratatui::widgets::Clear, suggestions_area);
@@ -346,6 +422,17 @@ impl Editor {
                     &self.theme,
                     self.mouse_state.hover_target.as_ref(),
                 );
+
+                // Preview of the highlighted theme or tab beside the popup
+                if let Some(preview) = self.suggestion_preview() {
+                    if let Some(preview_area) = PreviewRenderer::area_beside(
+                        suggestions_area,
+                        main_chunks[prompt_line_idx],
+                        size,
+                    ) {
+                        PreviewRenderer::render(frame, preview_area, preview, &self.theme);
+                    }
+                }
             }
         }
 
@@ -355,13 +442,32 @@ impl Editor {
             .get(&self.active_buffer)
             .map(|m| m.display_name.clone())
             .unwrap_or_else(|| "[No Name]".to_string());
-        let status_message = self.status_message.clone();
+        self.status_messages.expire(std::time::Instant::now());
+        let status_messages: Vec<_> = self.status_messages.iter().cloned().collect();
         let plugin_status_message = self.plugin_status_message.clone();
         let prompt = self.prompt.clone();
-        let lsp_status = self.lsp_status.clone();
+        // Running background tasks, plugin jobs and the loaded environment are
+        // listed next to the LSP status
+        let lsp_status = [
+            Some(self.lsp_status.clone()).filter(|status| !status.is_empty()),
+            self.running_tasks_status(),
+            self.plugin_jobs_status(),
+            self.project_env_status(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" | ");
         let theme = self.theme.clone();
         let keybindings_cloned = self.keybindings.clone(); // Clone the keybindings
         let chord_state_cloned = self.chord_state.clone(); // Clone the chord state
+        let vim_status = self.vim_status();
+        let custom_status = self
+            .config
+            .editor
+            .status_format
+            .clone()
+            .map(|format| self.expand_variables(&format));
 
         // Render status bar (hidden when suggestions or file browser popup is shown)
         if !has_suggestions && !has_file_browser {
@@ -369,13 +475,15 @@ impl Editor {
                 frame,
                 main_chunks[status_bar_idx],
                 self.active_state_mut(), // Use the mutable reference
-                &status_message,
+                &status_messages,
                 &plugin_status_message,
                 &lsp_status,
                 &theme,
                 &display_name,
                 &keybindings_cloned, // Pass the cloned keybindings
                 &chord_state_cloned, // Pass the cloned chord state
+                vim_status.as_deref(),
+                custom_status.as_deref(),
             );
         }
 
@@ -396,18 +504,37 @@ impl Editor {
                 }
             });
 
+            // Show "In Selection" option only when the search started with a selection
+            let in_selection = self
+                .pending_search_range
+                .as_ref()
+                .map(|_| self.search_in_selection);
+
             StatusBarRenderer::render_search_options(
                 frame,
                 main_chunks[search_options_idx],
                 self.search_case_sensitive,
                 self.search_whole_word,
                 self.search_use_regex,
+                in_selection,
                 confirm_each,
                 &theme,
                 &keybindings_cloned,
             );
         }
 
+        // Render inline prompt error (e.g., regex syntax error) just above the prompt line
+        if show_prompt_error {
+            if let Some(prompt) = &prompt {
+                StatusBarRenderer::render_prompt_error(
+                    frame,
+                    main_chunks[prompt_error_idx],
+                    prompt,
+                    &theme,
+                );
+            }
+        }
+
         // Render prompt line if active
         if let Some(prompt) = &prompt {
             // Use specialized renderer for file open prompt to show colorized path
@@ -573,6 +700,91 @@ impl Editor {
             &self.theme,
             self.mouse_state.hover_target.as_ref(),
         );
+
+        // While a chord is pending, list the keys that can come next
+        if self.config.editor.which_key && !self.chord_state.is_empty() {
+            let entries: Vec<_> = self
+                .keybindings
+                .chord_continuations_in_stack(&self.chord_state, &self.key_context_stack())
+                .iter()
+                .map(crate::view::ui::WhichKeyEntry::from_continuation)
+                .collect();
+            crate::view::ui::WhichKeyRenderer::render(
+                frame,
+                main_content_area,
+                &self.chord_state,
+                &entries,
+                &self.theme,
+            );
+        }
+
+        if self.show_performance_overlay {
+            crate::view::ui::PerformanceOverlayRenderer::render(
+                frame,
+                main_content_area,
+                self.latency.sample_count(),
+                &self.performance_overlay_rows(),
+                self.highlight_fallback_note().as_deref(),
+                &self.theme,
+            );
+        }
+
+        self.render_recent_buffers(frame, main_content_area);
+        self.render_pane_chooser(frame);
+        // Confirmation dialogs are modal, so they go over the menus too
+        self.render_confirm_dialog(frame);
+        self.latency.render_finished();
+    }
+
+    /// Highlight the regions the last frame had to draw without highlighting
+    ///
+    /// Called from the event loop when no input is waiting. Returns true if
+    /// anything was highlighted and the screen needs to be redrawn.
+    pub fn highlight_deferred_regions(&mut self) -> bool {
+        let mut highlighted = false;
+        for state in self.buffers.values_mut() {
+            if let Some(range) = state.deferred_highlight.take() {
+                // Fills the highlighter's cache, so the next frame is a hit
+                state.highlighter.highlight_viewport(
+                    &state.buffer,
+                    range.start,
+                    range.end,
+                    &self.theme,
+                );
+                highlighted = true;
+            }
+        }
+        highlighted
+    }
+
+    /// Shown instead of the editor while the terminal is too small for it
+    fn render_terminal_too_small(&mut self, frame: &mut Frame) {
+        use ratatui::layout::{Alignment, Rect};
+        use ratatui::style::Style;
+        use ratatui::widgets::{Block, Paragraph, Wrap};
+
+        // Nothing from the last full layout is on screen to be clicked
+        self.cached_layout = CachedLayout::default();
+
+        let area = frame.area();
+        let message = format!(
+            "Terminal too small ({}x{}, need {}x{})",
+            area.width, area.height, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+        );
+        let rows = (message.len() as u16).div_ceil(area.width.max(1));
+        let top = area.height.saturating_sub(rows) / 2;
+        let style = Style::default()
+            .fg(self.theme.editor_fg)
+            .bg(self.theme.editor_bg);
+        let paragraph = Paragraph::new(message)
+            .style(style)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(Block::default().style(style), area);
+        frame.render_widget(
+            paragraph,
+            Rect::new(area.x, area.y + top, area.width, area.height - top),
+        );
     }
 
     /// Render hover highlights for interactive elements (separators, scrollbars)
@@ -966,9 +1178,9 @@ impl Editor {
         }
 
         // Get the buffer text
-        let text = if let Some(state) = self.buffers.get(&self.active_buffer) {
+        let (text, version) = if let Some(state) = self.buffers.get(&self.active_buffer) {
             match state.buffer.to_string() {
-                Some(t) => t,
+                Some(t) => (t, state.lsp_version()),
                 None => {
                     tracing::debug!("notify_lsp_current_file_opened: buffer not fully loaded");
                     return;
@@ -983,7 +1195,7 @@ impl Editor {
         if let Some(lsp) = &mut self.lsp {
             if let Some(client) = lsp.get_or_spawn(language) {
                 tracing::info!("Sending didOpen to newly started LSP for: {}", uri.as_str());
-                if let Err(e) = client.did_open(uri.clone(), text, file_language) {
+                if let Err(e) = client.did_open(uri.clone(), text, file_language, version) {
                     tracing::warn!("Failed to send didOpen to LSP: {}", e);
                 } else {
                     tracing::info!("Successfully sent didOpen to LSP after confirmation");
@@ -1092,166 +1304,6 @@ impl Editor {
     // NOTE: Diagnostics are now applied automatically via process_async_messages()
     // when received from the LSP server asynchronously. No manual polling needed!
 
-    /// Notify LSP of a text change event
-    pub(super) fn notify_lsp_change(&mut self, event: &Event) {
-        // Collect all changes from the event (handles batches efficiently)
-        let changes = self.collect_lsp_changes(event);
-        if changes.is_empty() {
-            return;
-        }
-
-        // Check if LSP is enabled for this buffer
-        let metadata = match self.buffer_metadata.get(&self.active_buffer) {
-            Some(m) => m,
-            None => {
-                tracing::debug!(
-                    "notify_lsp_change: no metadata for buffer {:?}",
-                    self.active_buffer
-                );
-                return;
-            }
-        };
-
-        if !metadata.lsp_enabled {
-            // LSP is disabled for this buffer, don't try to spawn or notify
-            tracing::debug!("notify_lsp_change: LSP disabled for this buffer");
-            return;
-        }
-
-        // Get the URI (computed once in with_file)
-        let uri = match metadata.file_uri() {
-            Some(u) => u.clone(),
-            None => {
-                tracing::debug!(
-                    "notify_lsp_change: no URI for buffer (not a file or URI creation failed)"
-                );
-                return;
-            }
-        };
-
-        // Get the file path for language detection
-        let path = match metadata.file_path() {
-            Some(p) => p,
-            None => {
-                tracing::debug!("notify_lsp_change: no file path for buffer");
-                return;
-            }
-        };
-
-        let language = match detect_language(path) {
-            Some(l) => l,
-            None => {
-                tracing::debug!("notify_lsp_change: no language detected for {:?}", path);
-                return;
-            }
-        };
-
-        tracing::debug!(
-            "notify_lsp_change: sending {} changes to {} in single didChange notification",
-            changes.len(),
-            uri.as_str()
-        );
-
-        if let Some(lsp) = &mut self.lsp {
-            if let Some(client) = lsp.get_or_spawn(&language) {
-                // Send all changes in a single didChange notification
-                // This is much more efficient for batch operations like LSP rename
-                if let Err(e) = client.did_change(uri.clone(), changes) {
-                    tracing::warn!("Failed to send didChange to LSP: {}", e);
-                } else {
-                    tracing::info!("Successfully sent batched didChange to LSP");
-
-                    // Request pull diagnostics after the change
-                    // TODO: Consider debouncing this to avoid excessive requests during rapid typing
-                    let previous_result_id = self.diagnostic_result_ids.get(uri.as_str()).cloned();
-                    let request_id = self.next_lsp_request_id;
-                    self.next_lsp_request_id += 1;
-
-                    if let Err(e) =
-                        client.document_diagnostic(request_id, uri.clone(), previous_result_id)
-                    {
-                        tracing::debug!(
-                            "Failed to request pull diagnostics after change (server may not support): {}",
-                            e
-                        );
-                    } else {
-                        tracing::debug!(
-                            "Requested pull diagnostics after change for {} (request_id={})",
-                            uri.as_str(),
-                            request_id
-                        );
-                    }
-                }
-            } else {
-                tracing::warn!(
-                    "notify_lsp_change: failed to get or spawn LSP client for {}",
-                    language
-                );
-            }
-        } else {
-            tracing::debug!("notify_lsp_change: no LSP manager available");
-        }
-    }
-
-    /// Collect all LSP text document changes from an event (recursively for batches)
-    pub(super) fn collect_lsp_changes(&self, event: &Event) -> Vec<TextDocumentContentChangeEvent> {
-        match event {
-            Event::Insert { position, text, .. } => {
-                tracing::debug!(
-                    "collect_lsp_changes: processing Insert at position {}",
-                    position
-                );
-                // For insert: create a zero-width range at the insertion point
-                let (line, character) = self
-                    .active_state()
-                    .buffer
-                    .position_to_lsp_position(*position);
-                let lsp_pos = Position::new(line as u32, character as u32);
-                let lsp_range = LspRange::new(lsp_pos, lsp_pos);
-                vec![TextDocumentContentChangeEvent {
-                    range: Some(lsp_range),
-                    range_length: None,
-                    text: text.clone(),
-                }]
-            }
-            Event::Delete { range, .. } => {
-                tracing::debug!("collect_lsp_changes: processing Delete range {:?}", range);
-                // For delete: create a range from start to end, send empty string
-                let (start_line, start_char) = self
-                    .active_state()
-                    .buffer
-                    .position_to_lsp_position(range.start);
-                let (end_line, end_char) = self
-                    .active_state()
-                    .buffer
-                    .position_to_lsp_position(range.end);
-                let lsp_range = LspRange::new(
-                    Position::new(start_line as u32, start_char as u32),
-                    Position::new(end_line as u32, end_char as u32),
-                );
-                vec![TextDocumentContentChangeEvent {
-                    range: Some(lsp_range),
-                    range_length: None,
-                    text: String::new(),
-                }]
-            }
-            Event::Batch { events, .. } => {
-                // Collect all changes from sub-events into a single vector
-                // This allows sending all changes in one didChange notification
-                tracing::debug!(
-                    "collect_lsp_changes: processing Batch with {} events",
-                    events.len()
-                );
-                let mut all_changes = Vec::new();
-                for sub_event in events {
-                    all_changes.extend(self.collect_lsp_changes(sub_event));
-                }
-                all_changes
-            }
-            _ => Vec::new(), // Ignore cursor movements and other events
-        }
-    }
-
     /// Calculate line information for an event (before buffer modification)
     /// This provides accurate line numbers for plugin hooks to track changes.
     ///
@@ -1269,7 +1321,7 @@ impl Editor {
     /// - O(1) per edit, but requires careful bookkeeping
     ///
     /// We use Approach 2 because:
-    /// - Matches existing LSP infrastructure (`collect_lsp_changes`)
+    /// - Matches the positions sent to LSP (computed before each edit)
     /// - More efficient for typical editing patterns
     /// - Plugins can choose to re-diff if they need more accuracy
     ///
@@ -1446,25 +1498,17 @@ impl Editor {
         self.search_state = None;
     }
 
-    /// Update search highlights in visible viewport only (for incremental search)
-    /// This is called as the user types in the search prompt for real-time feedback
-    pub(super) fn update_search_highlights(&mut self, query: &str) {
-        // If query is empty, clear highlights and return
-        if query.is_empty() {
-            self.clear_search_highlights();
-            return;
-        }
-
-        // Get theme colors and search settings before borrowing state
-        let search_bg = self.theme.search_match_bg;
-        let search_fg = self.theme.search_match_fg;
-        let case_sensitive = self.search_case_sensitive;
+    /// Build the search regex for a query using the current search options
+    ///
+    /// In regex mode the query is validated first so that syntax errors carry the
+    /// position of the offending character, which the search prompt highlights.
+    pub(super) fn build_search_regex(&self, query: &str) -> Result<regex::Regex, PromptError> {
         let whole_word = self.search_whole_word;
-        let use_regex = self.search_use_regex;
-        let ns = self.search_namespace.clone();
 
-        // Build regex pattern if regex mode is enabled, or escape for literal search
-        let regex_pattern = if use_regex {
+        let regex_pattern = if self.search_use_regex {
+            if let Err(e) = regex_syntax::Parser::new().parse(query) {
+                return Err(regex_syntax_error(&e));
+            }
             if whole_word {
                 format!(r"\b{}\b", query)
             } else {
@@ -1479,20 +1523,160 @@ impl Editor {
             }
         };
 
-        // Build regex with case sensitivity
-        let regex = regex::RegexBuilder::new(&regex_pattern)
-            .case_insensitive(!case_sensitive)
-            .build();
+        regex::RegexBuilder::new(&regex_pattern)
+            .case_insensitive(!self.search_case_sensitive)
+            .build()
+            .map_err(|e| PromptError {
+                position: None,
+                message: e.to_string(),
+            })
+    }
+
+    /// The byte range searches and replacements are restricted to, if any
+    ///
+    /// This is the selection captured when the search prompt opened, and only
+    /// applies while the "in selection" search option is enabled.
+    pub(super) fn search_scope(&self) -> Option<Range<usize>> {
+        if self.search_in_selection {
+            self.pending_search_range.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Forget the selection captured for the current search/replace
+    ///
+    /// Called once the search or replace has run, since edits make the
+    /// captured byte range stale.
+    pub(super) fn clear_search_scope(&mut self) {
+        self.pending_search_range = None;
+        self.search_in_selection = false;
+    }
+
+    /// The active prompt, if it is a search prompt
+    fn search_prompt_mut(&mut self) -> Option<&mut Prompt> {
+        self.prompt.as_mut().filter(|prompt| {
+            matches!(
+                prompt.prompt_type,
+                PromptType::Search | PromptType::ReplaceSearch | PromptType::QueryReplaceSearch
+            )
+        })
+    }
+
+    /// Set (or clear) the inline error shown under an active search prompt
+    fn set_search_prompt_error(&mut self, error: Option<PromptError>) {
+        if let Some(prompt) = self.search_prompt_mut() {
+            prompt.error = error;
+        }
+    }
+
+    /// Find the start offsets of all matches of `regex` in the active buffer,
+    /// restricted to `scope` if given
+    ///
+    /// Stops after `limits.max_search_matches`; the flag tells whether it did.
+    /// Returns None if the buffer isn't fully loaded.
+    fn find_search_matches(
+        &self,
+        regex: &regex::Regex,
+        scope: Option<&Range<usize>>,
+    ) -> Option<(Vec<usize>, bool)> {
+        let content = self.active_state().buffer.to_string()?;
+        let (start, end) = match scope {
+            Some(range) => (range.start, range.end.min(content.len())),
+            None => (0, content.len()),
+        };
+        let limit = self.config.limits.max_search_matches;
+        let mut matches: Vec<usize> = regex
+            .find_iter(&content[start..end])
+            .map(|m| start + m.start())
+            .take(limit.saturating_add(1))
+            .collect();
+        let truncated = matches.len() > limit;
+        matches.truncate(limit);
+        Some((matches, truncated))
+    }
+
+    /// Count the matches of the search prompt's query once typing has paused
+    ///
+    /// Counting scans the whole buffer, so it waits `SEARCH_COUNT_DELAY` after
+    /// the last change to the query. Called from the event loop. Returns true
+    /// if the counter changed and the screen needs to be redrawn.
+    pub fn update_search_match_count(&mut self) -> bool {
+        match self.search_count_due {
+            Some(due) if std::time::Instant::now() >= due => self.search_count_due = None,
+            _ => return false,
+        }
+        let Some(query) = self.search_prompt_mut().map(|p| p.input.clone()) else {
+            return false;
+        };
+        let Ok(regex) = self.build_search_regex(&query) else {
+            return false;
+        };
 
-        let regex = match regex {
-            Ok(r) => r,
-            Err(_) => {
-                // Invalid regex, clear highlights and return
-                self.clear_search_highlights();
+        let scope = self.search_scope();
+        let cursor_pos = self.active_state().cursors.primary().position;
+        let match_count =
+            self.find_search_matches(&regex, scope.as_ref())
+                .map(|(matches, truncated)| MatchCount {
+                    current: (!matches.is_empty()).then(|| {
+                        matches
+                            .iter()
+                            .position(|&pos| pos >= cursor_pos)
+                            .unwrap_or(0)
+                            + 1
+                    }),
+                    total: matches.len(),
+                    truncated,
+                });
+        if let Some(prompt) = self.search_prompt_mut() {
+            prompt.match_count = match_count;
+        }
+        true
+    }
+
+    /// Highlight the matches of a search query in the visible viewport as the
+    /// user types it, and schedule counting all matches in the buffer
+    pub(super) fn update_search_highlights(&mut self, query: &str) {
+        // If query is empty, clear highlights and return
+        if query.is_empty() {
+            self.set_search_prompt_error(None);
+            if let Some(prompt) = self.search_prompt_mut() {
+                prompt.match_count = None;
+            }
+            self.search_count_due = None;
+            self.clear_search_highlights();
+            return;
+        }
+
+        // Get theme colors and search scope before borrowing state
+        let search_bg = self.theme.search_match_bg;
+        let search_fg = self.theme.search_match_fg;
+        let ns = self.search_namespace.clone();
+        let scope = self.search_scope();
+
+        let regex = match self.build_search_regex(query) {
+            Ok(r) => {
+                self.set_search_prompt_error(None);
+                r
+            }
+            Err(error) => {
+                // Invalid regex: keep the highlights of the last valid pattern and
+                // report the problem inline under the prompt instead
+                self.set_search_prompt_error(Some(error));
                 return;
             }
         };
 
+        // While typing in the search prompt, all matches in the buffer (not just the
+        // visible ones) are counted once typing pauses, so the prompt can show which
+        // match Enter will jump to. No count is shown until then.
+        if let Some(prompt) = self.search_prompt_mut() {
+            prompt.match_count = None;
+            self.search_count_due = Some(std::time::Instant::now() + SEARCH_COUNT_DELAY);
+        }
+
+        let max_highlights = self.config.limits.max_highlights_per_frame;
+        let long_line_bytes = self.config.limits.long_line_bytes;
         let state = self.active_state_mut();
 
         // Clear any existing search highlights
@@ -1518,17 +1702,29 @@ impl Editor {
             }
         }
 
-        // Ensure we don't go past buffer end
-        visible_end = visible_end.min(state.buffer.len());
+        // Ensure we don't go past buffer end, and don't scan all of a huge line
+        visible_end =
+            visible_end
+                .min(state.buffer.len())
+                .min(visible_start.saturating_add(
+                    long_line_bytes.saturating_mul(visible_height.max(1) as usize),
+                ));
 
         // Get the visible text
         let visible_text = state.get_text_range(visible_start, visible_end);
 
         // Find all matches using regex
-        for mat in regex.find_iter(&visible_text) {
+        for mat in regex.find_iter(&visible_text).take(max_highlights) {
             let absolute_pos = visible_start + mat.start();
             let match_len = mat.end() - mat.start();
 
+            // Skip matches outside the selection when searching in selection
+            if let Some(ref scope) = scope {
+                if absolute_pos < scope.start || absolute_pos + match_len > scope.end {
+                    continue;
+                }
+            }
+
             // Add overlay for this match
             let search_style = ratatui::style::Style::default().fg(search_fg).bg(search_bg);
             let overlay = crate::view::overlay::Overlay::with_namespace(
@@ -1559,66 +1755,25 @@ impl Editor {
             return;
         }
 
-        let search_range = self.pending_search_range.take();
+        let search_range = self.search_scope();
 
-        let buffer_content = {
-            let state = self.active_state();
-            match state.buffer.to_string() {
-                Some(t) => t,
-                None => {
-                    self.set_status_message("Buffer not fully loaded".to_string());
-                    return;
-                }
-            }
-        };
-
-        // Get search settings
-        let case_sensitive = self.search_case_sensitive;
-        let whole_word = self.search_whole_word;
-        let use_regex = self.search_use_regex;
-
-        // Determine search boundaries
-        let (search_start, search_end) = if let Some(ref range) = search_range {
-            (range.start, range.end)
-        } else {
-            (0, buffer_content.len())
-        };
-
-        // Build regex pattern
-        let regex_pattern = if use_regex {
-            if whole_word {
-                format!(r"\b{}\b", query)
-            } else {
-                query.to_string()
-            }
-        } else {
-            let escaped = regex::escape(query);
-            if whole_word {
-                format!(r"\b{}\b", escaped)
-            } else {
-                escaped
-            }
-        };
-
-        // Build regex with case sensitivity
-        let regex = match regex::RegexBuilder::new(&regex_pattern)
-            .case_insensitive(!case_sensitive)
-            .build()
-        {
+        let regex = match self.build_search_regex(query) {
             Ok(r) => r,
             Err(e) => {
                 self.search_state = None;
-                self.set_status_message(format!("Invalid regex: {}", e));
+                self.set_status_message(format!("Invalid regex: {}", e.message));
                 return;
             }
         };
 
         // Find all matches within the search range
-        let search_slice = &buffer_content[search_start..search_end];
-        let matches: Vec<usize> = regex
-            .find_iter(search_slice)
-            .map(|m| search_start + m.start())
-            .collect();
+        let (matches, truncated) = match self.find_search_matches(&regex, search_range.as_ref()) {
+            Some(found) => found,
+            None => {
+                self.set_status_message("Buffer not fully loaded".to_string());
+                return;
+            }
+        };
 
         if matches.is_empty() {
             self.search_state = None;
@@ -1658,6 +1813,7 @@ impl Editor {
         self.search_state = Some(SearchState {
             query: query.to_string(),
             matches,
+            truncated,
             current_match_index: Some(current_match_index),
             wrap_search: search_range.is_none(), // Only wrap if not searching in selection
             search_range,
@@ -1665,17 +1821,19 @@ impl Editor {
             whole_word: self.search_whole_word,
         });
 
+        // Past the match limit the count is a lower bound ("100000+")
+        let count = format!("{}{}", num_matches, if truncated { "+" } else { "" });
         let msg = if self.search_state.as_ref().unwrap().search_range.is_some() {
             format!(
                 "Found {} match{} for '{}' in selection",
-                num_matches,
+                count,
                 if num_matches == 1 { "" } else { "es" },
                 query
             )
         } else {
             format!(
                 "Found {} match{} for '{}'",
-                num_matches,
+                count,
                 if num_matches == 1 { "" } else { "es" },
                 query
             )
@@ -1703,6 +1861,7 @@ impl Editor {
             search_state.current_match_index = Some(next_index);
             let match_pos = search_state.matches[next_index];
             let matches_len = search_state.matches.len();
+            let more = if search_state.truncated { "+" } else { "" };
 
             {
                 let state = self.active_state_mut();
@@ -1713,7 +1872,12 @@ impl Editor {
                     .ensure_visible(&mut state.buffer, state.cursors.primary());
             }
 
-            self.set_status_message(format!("Match {} of {}", next_index + 1, matches_len));
+            self.set_status_message(format!(
+                "Match {} of {}{}",
+                next_index + 1,
+                matches_len,
+                more
+            ));
         } else {
             self.set_status_message("No active search. Press Ctrl+F to search.".to_string());
         }
@@ -1739,6 +1903,7 @@ impl Editor {
             search_state.current_match_index = Some(prev_index);
             let match_pos = search_state.matches[prev_index];
             let matches_len = search_state.matches.len();
+            let more = if search_state.truncated { "+" } else { "" };
 
             {
                 let state = self.active_state_mut();
@@ -1749,12 +1914,119 @@ impl Editor {
                     .ensure_visible(&mut state.buffer, state.cursors.primary());
             }
 
-            self.set_status_message(format!("Match {} of {}", prev_index + 1, matches_len));
+            self.set_status_message(format!(
+                "Match {} of {}{}",
+                prev_index + 1,
+                matches_len,
+                more
+            ));
         } else {
             self.set_status_message("No active search. Press Ctrl+F to search.".to_string());
         }
     }
 
+    /// Send all matches of the current search to the quickfix list
+    ///
+    /// Fires the `search_matches` hook with the location and line text of each
+    /// match; the quickfix plugin shows them in a panel for later review.
+    pub(super) fn search_matches_to_quickfix(&mut self) {
+        let (query, positions) = match &self.search_state {
+            Some(search_state) if !search_state.matches.is_empty() => {
+                (search_state.query.clone(), search_state.matches.clone())
+            }
+            _ => {
+                self.set_status_message("No active search. Press Ctrl+F to search.".to_string());
+                return;
+            }
+        };
+
+        let state = self.active_state();
+        let file = match state.buffer.file_path() {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => {
+                self.set_status_message("Save the buffer to list its matches".to_string());
+                return;
+            }
+        };
+        let content = match state.buffer.to_string() {
+            Some(content) => content,
+            None => {
+                self.set_status_message("Buffer not fully loaded".to_string());
+                return;
+            }
+        };
+
+        let long_line_bytes = self.config.limits.long_line_bytes;
+
+        // Matches are sorted, so line numbers can be counted incrementally
+        let mut matches = Vec::with_capacity(positions.len());
+        let mut line = 1;
+        let mut counted_up_to = 0;
+        for pos in positions {
+            line += content[counted_up_to..pos].matches('\n').count();
+            counted_up_to = pos;
+
+            let line_start = content[..pos].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = content[pos..].find('\n').map_or(content.len(), |i| pos + i);
+            matches.push(crate::services::plugins::hooks::SearchMatchLocation {
+                file: file.clone(),
+                line,
+                column: pos - line_start + 1,
+                line_text: quote_line(
+                    &content[line_start..line_end],
+                    pos - line_start,
+                    long_line_bytes,
+                )
+                .trim_end_matches('\r')
+                .to_string(),
+            });
+        }
+
+        let count = matches.len();
+        if let Some(ref ts_manager) = self.ts_plugin_manager {
+            ts_manager.run_hook(
+                "search_matches",
+                crate::services::plugins::hooks::HookArgs::SearchMatches {
+                    query: query.clone(),
+                    matches,
+                },
+            );
+        }
+
+        self.set_status_message(format!(
+            "Sent {} match{} for '{}' to quickfix list",
+            count,
+            if count == 1 { "" } else { "es" },
+            query
+        ));
+    }
+
+    /// Start offsets of every occurrence of `search` in the search scope
+    /// (the selection, if search-in-selection is on, else the whole buffer)
+    pub(super) fn find_replace_matches(&self, search: &str) -> Vec<usize> {
+        let state = self.active_state();
+        let (scope_start, scope_end) = match self.search_scope() {
+            Some(range) => (range.start, range.end.min(state.buffer.len())),
+            None => (0, state.buffer.len()),
+        };
+        let mut matches = Vec::new();
+        let mut current_pos = scope_start;
+
+        while current_pos < scope_end {
+            if let Some(offset) =
+                state
+                    .buffer
+                    .find_next_in_range(search, current_pos, Some(current_pos..scope_end))
+            {
+                matches.push(offset);
+                current_pos = offset + search.len();
+            } else {
+                break;
+            }
+        }
+        matches
+    }
+
     /// Perform a replace-all operation
     /// Replaces all occurrences of the search query with the replacement text
     pub(super) fn perform_replace(&mut self, search: &str, replacement: &str) {
@@ -1763,32 +2035,19 @@ impl Editor {
             return;
         }
 
-        // Find all matches first (before making any modifications)
-        let matches = {
-            let state = self.active_state();
-            let buffer_len = state.buffer.len();
-            let mut matches = Vec::new();
-            let mut current_pos = 0;
-
-            while current_pos < buffer_len {
-                if let Some(offset) = state.buffer.find_next_in_range(
-                    search,
-                    current_pos,
-                    Some(current_pos..buffer_len),
-                ) {
-                    matches.push(offset);
-                    current_pos = offset + search.len();
-                } else {
-                    break;
-                }
-            }
-            matches
-        };
+        let scope = self.search_scope();
 
+        // Find all matches first (before making any modifications)
+        let matches = self.find_replace_matches(search);
         let count = matches.len();
 
         if count == 0 {
-            self.set_status_message(format!("No occurrences of '{}' found.", search));
+            let msg = if scope.is_some() {
+                format!("No occurrences of '{}' found in selection.", search)
+            } else {
+                format!("No occurrences of '{}' found.", search)
+            };
+            self.set_status_message(msg);
             return;
         }
 
@@ -1857,11 +2116,12 @@ impl Editor {
 
         // Set status message
         self.set_status_message(format!(
-            "Replaced {} occurrence{} of '{}' with '{}'",
+            "Replaced {} occurrence{} of '{}' with '{}'{}",
             count,
             if count == 1 { "" } else { "s" },
             search,
-            replacement
+            replacement,
+            if scope.is_some() { " in selection" } else { "" }
         ));
     }
 
@@ -1872,10 +2132,24 @@ impl Editor {
             return;
         }
 
+        let scope = self.search_scope();
+
         // Find the first match lazily (don't find all matches upfront)
         let state = self.active_state();
         let start_pos = state.cursors.primary().position;
-        let first_match = state.buffer.find_next(search, start_pos);
+        let first_match = match scope {
+            // Within a selection: search from the cursor to the end of the selection,
+            // then wrap around to the start of the selection
+            Some(ref range) => state
+                .buffer
+                .find_next_in_range(search, start_pos, Some(range.clone()))
+                .or_else(|| {
+                    state
+                        .buffer
+                        .find_next_in_range(search, range.start, Some(range.clone()))
+                }),
+            None => state.buffer.find_next(search, start_pos),
+        };
 
         let Some(first_match_pos) = first_match else {
             self.set_status_message(format!("No occurrences of '{}' found.", search));
@@ -1890,6 +2164,7 @@ impl Editor {
             start_pos: first_match_pos,
             has_wrapped: false,
             replacements_made: 0,
+            scope,
         });
 
         // Move cursor to first match
@@ -1919,6 +2194,7 @@ impl Editor {
                 // Replace current match
                 self.replace_current_match(&ir_state)?;
                 ir_state.replacements_made += 1;
+                ir_state.adjust_scope_after_replacement();
 
                 // Find next match lazily (after the replacement)
                 let search_pos = ir_state.current_match_pos + ir_state.replacement.len();
@@ -1959,6 +2235,7 @@ impl Editor {
                 // First replace the current match
                 self.replace_current_match(&ir_state)?;
                 ir_state.replacements_made += 1;
+                ir_state.adjust_scope_after_replacement();
 
                 // Find all remaining matches using streaming search
                 // Collecting positions (Vec<usize>) is low memory cost even for huge files
@@ -2066,6 +2343,12 @@ impl Editor {
     ) -> Option<(usize, bool)> {
         let state = self.active_state();
 
+        // Bounds of the search: the selection when searching in selection, else the whole buffer
+        let (scope_start, scope_end) = match ir_state.scope {
+            Some(ref range) => (range.start, range.end.min(state.buffer.len())),
+            None => (0, state.buffer.len()),
+        };
+
         if ir_state.has_wrapped {
             // We've already wrapped - only search from start_pos up to (but not including) the original start position
             // Use find_next_in_range to avoid wrapping again
@@ -2080,9 +2363,8 @@ impl Editor {
             None // No more matches before original start position
         } else {
             // Haven't wrapped yet - search normally from start_pos
-            // First try from start_pos to end of buffer
-            let buffer_len = state.buffer.len();
-            let search_range = Some(start_pos..buffer_len);
+            // First try from start_pos to end of buffer (or selection)
+            let search_range = Some(start_pos..scope_end);
             if let Some(match_pos) =
                 state
                     .buffer
@@ -2092,12 +2374,12 @@ impl Editor {
             }
 
             // No match from start_pos to end - wrap to beginning
-            // Search from 0 to start_pos (original position)
-            let wrap_range = Some(0..ir_state.start_pos);
+            // Search from the start of the buffer (or selection) to start_pos (original position)
+            let wrap_range = Some(scope_start..ir_state.start_pos);
             if let Some(match_pos) =
                 state
                     .buffer
-                    .find_next_in_range(&ir_state.search, 0, wrap_range)
+                    .find_next_in_range(&ir_state.search, scope_start, wrap_range)
             {
                 return Some((match_pos, true)); // Found match after wrapping
             }
@@ -2241,7 +2523,12 @@ impl Editor {
                 old_position: cursor.position,
                 new_position: new_pos,
                 old_anchor: cursor.anchor,
-                new_anchor: None,
+                // Preserve anchor if deselect_on_move is false (selection/mark mode)
+                new_anchor: if cursor.deselect_on_move {
+                    None
+                } else {
+                    cursor.anchor
+                },
                 old_sticky_column: cursor.sticky_column,
                 new_sticky_column: 0,
             };
@@ -2798,226 +3085,58 @@ impl Editor {
         }
     }
 
-    /// Toggle macro recording for the given register
-    pub(super) fn toggle_macro_recording(&mut self, key: char) {
-        if let Some(state) = &self.macro_recording {
-            if state.key == key {
-                // Stop recording
-                self.stop_macro_recording();
-            } else {
-                // Recording to a different key, stop current and start new
-                self.stop_macro_recording();
-                self.start_macro_recording(key);
-            }
-        } else {
-            // Start recording
-            self.start_macro_recording(key);
-        }
-    }
-
-    /// Start recording a macro
-    pub(super) fn start_macro_recording(&mut self, key: char) {
-        self.macro_recording = Some(MacroRecordingState {
-            key,
-            actions: Vec::new(),
-        });
-        self.set_status_message(format!(
-            "Recording macro '{}' (press Ctrl+Shift+R {} to stop)",
-            key, key
-        ));
-    }
-
-    /// Stop recording and save the macro
-    pub(super) fn stop_macro_recording(&mut self) {
-        if let Some(state) = self.macro_recording.take() {
-            let action_count = state.actions.len();
-            let key = state.key;
-            self.macros.insert(key, state.actions);
-            self.last_macro_register = Some(key);
-            self.set_status_message(format!("Macro '{}' saved ({} actions)", key, action_count));
-        } else {
-            self.set_status_message("Not recording a macro".to_string());
-        }
-    }
-
-    /// Play back a recorded macro
-    pub(super) fn play_macro(&mut self, key: char) {
-        if let Some(actions) = self.macros.get(&key).cloned() {
-            if actions.is_empty() {
-                self.set_status_message(format!("Macro '{}' is empty", key));
-                return;
-            }
-
-            // Temporarily disable recording to avoid recording the playback
-            let was_recording = self.macro_recording.take();
-
-            let action_count = actions.len();
-            for action in actions {
-                let _ = self.handle_action(action);
-            }
-
-            // Restore recording state
-            self.macro_recording = was_recording;
-
-            self.set_status_message(format!("Played macro '{}' ({} actions)", key, action_count));
-        } else {
-            self.set_status_message(format!("No macro recorded for '{}'", key));
-        }
-    }
-
-    /// Record an action to the current macro (if recording)
-    pub(super) fn record_macro_action(&mut self, action: &Action) {
-        if let Some(state) = &mut self.macro_recording {
-            // Don't record macro control actions themselves
-            match action {
-                Action::StartMacroRecording
-                | Action::StopMacroRecording
-                | Action::PlayMacro(_)
-                | Action::ToggleMacroRecording(_)
-                | Action::ShowMacro(_)
-                | Action::ListMacros
-                | Action::PromptRecordMacro
-                | Action::PromptPlayMacro
-                | Action::PlayLastMacro => {}
-                _ => {
-                    state.actions.push(action.clone());
-                }
-            }
-        }
-    }
-
-    /// Show a macro in a buffer as JSON
-    pub(super) fn show_macro_in_buffer(&mut self, key: char) {
-        if let Some(actions) = self.macros.get(&key) {
-            // Serialize the macro to JSON
-            let json = match serde_json::to_string_pretty(actions) {
-                Ok(json) => json,
-                Err(e) => {
-                    self.set_status_message(format!("Failed to serialize macro: {}", e));
-                    return;
-                }
-            };
-
-            // Create header with macro info
-            let content = format!(
-                "// Macro '{}' ({} actions)\n// This buffer can be saved as a .json file for persistence\n\n{}",
-                key,
-                actions.len(),
-                json
-            );
-
-            // Create a new buffer for the macro
-            let buffer_id = BufferId(self.next_buffer_id);
-            self.next_buffer_id += 1;
-
-            let state = EditorState::new(
-                self.terminal_width.into(),
-                self.terminal_height.into(),
-                self.config.editor.large_file_threshold_bytes as usize,
-            );
-
-            self.buffers.insert(buffer_id, state);
-            self.event_logs.insert(buffer_id, EventLog::new());
-
-            // Set buffer content
-            if let Some(state) = self.buffers.get_mut(&buffer_id) {
-                state.buffer = crate::model::buffer::Buffer::from_str(
-                    &content,
-                    self.config.editor.large_file_threshold_bytes as usize,
-                );
-            }
-
-            // Set metadata
-            let metadata = BufferMetadata {
-                kind: BufferKind::Virtual {
-                    mode: "macro-view".to_string(),
-                },
-                display_name: format!("*Macro {}*", key),
-                lsp_enabled: false,
-                lsp_disabled_reason: Some("Virtual macro buffer".to_string()),
-                read_only: false, // Allow editing for saving
-                binary: false,
-            };
-            self.buffer_metadata.insert(buffer_id, metadata);
+    /// Show the diagnostics on the cursor's line in a popup
+    pub(super) fn show_line_diagnostics(&mut self) {
+        use crate::view::popup::{Popup, PopupPosition};
+        use ratatui::style::Style;
 
-            // Switch to the new buffer
-            self.active_buffer = buffer_id;
-            self.set_status_message(format!(
-                "Macro '{}' shown in buffer ({} actions) - save as .json for persistence",
-                key,
-                actions.len()
-            ));
-        } else {
-            self.set_status_message(format!("No macro recorded for '{}'", key));
-        }
-    }
+        let diagnostic_ns = self.lsp_diagnostic_namespace.clone();
+        let state = self.active_state();
+        let line = state
+            .buffer
+            .get_line_number(state.cursors.primary().position);
 
-    /// List all recorded macros in a buffer
-    pub(super) fn list_macros_in_buffer(&mut self) {
-        if self.macros.is_empty() {
-            self.set_status_message("No macros recorded".to_string());
+        // Most severe first (diagnostic overlays are prioritized by severity)
+        let mut diagnostics: Vec<(i32, String)> = state
+            .overlays
+            .all()
+            .iter()
+            .filter(|overlay| overlay.namespace.as_ref() == Some(&diagnostic_ns))
+            .filter_map(|overlay| {
+                let range = overlay.range(&state.marker_list);
+                let first = state.buffer.get_line_number(range.start);
+                let last = state
+                    .buffer
+                    .get_line_number(range.end.saturating_sub(1).max(range.start));
+                let message = overlay.message.clone()?;
+                (first..=last)
+                    .contains(&line)
+                    .then_some((overlay.priority, message))
+            })
+            .collect();
+        if diagnostics.is_empty() {
+            self.set_status_message("No diagnostics on this line".to_string());
             return;
         }
+        diagnostics.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
 
-        // Build a summary of all macros
-        let mut content =
-            String::from("// Recorded Macros\n// Use ShowMacro(key) to see details\n\n");
-
-        let mut keys: Vec<char> = self.macros.keys().copied().collect();
-        keys.sort();
-
-        for key in keys {
-            if let Some(actions) = self.macros.get(&key) {
-                content.push_str(&format!("Macro '{}': {} actions\n", key, actions.len()));
-
-                // Show first few actions as preview
-                for (i, action) in actions.iter().take(5).enumerate() {
-                    content.push_str(&format!("  {}. {:?}\n", i + 1, action));
-                }
-                if actions.len() > 5 {
-                    content.push_str(&format!("  ... and {} more actions\n", actions.len() - 5));
-                }
-                content.push('\n');
-            }
-        }
-
-        // Create a new buffer for the macro list
-        let buffer_id = BufferId(self.next_buffer_id);
-        self.next_buffer_id += 1;
-
-        let state = EditorState::new(
-            self.terminal_width.into(),
-            self.terminal_height.into(),
-            self.config.editor.large_file_threshold_bytes as usize,
-        );
-
-        self.buffers.insert(buffer_id, state);
-        self.event_logs.insert(buffer_id, EventLog::new());
-
-        // Set buffer content
-        if let Some(state) = self.buffers.get_mut(&buffer_id) {
-            state.buffer = crate::model::buffer::Buffer::from_str(
-                &content,
-                self.config.editor.large_file_threshold_bytes as usize,
-            );
-        }
-
-        // Set metadata
-        let metadata = BufferMetadata {
-            kind: BufferKind::Virtual {
-                mode: "macro-list".to_string(),
-            },
-            display_name: "*Macros*".to_string(),
-            lsp_enabled: false,
-            lsp_disabled_reason: Some("Virtual macro list buffer".to_string()),
-            read_only: true,
-            binary: false,
-        };
-        self.buffer_metadata.insert(buffer_id, metadata);
-
-        // Switch to the new buffer
-        self.active_buffer = buffer_id;
-        self.set_status_message(format!("Showing {} recorded macro(s)", self.macros.len()));
+        let lines: Vec<String> = diagnostics
+            .into_iter()
+            .flat_map(|(_, message)| {
+                message
+                    .lines()
+                    .map(|line| line.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let mut popup = Popup::text(lines, &self.theme);
+        popup.title = Some("Diagnostics".to_string());
+        popup.position = PopupPosition::BelowCursor;
+        popup.width = 80;
+        popup.max_height = 15;
+        popup.border_style = Style::default().fg(self.theme.popup_border_fg);
+        popup.background_style = Style::default().bg(self.theme.popup_bg);
+        self.active_state_mut().popups.show(popup);
     }
 
     /// Set a bookmark at the current position
@@ -3135,73 +3254,106 @@ impl Editor {
         }
     }
 
+    /// Load command palette usage (for frecency ranking) from disk
+    pub fn load_command_frecency(&self) {
+        let Ok(path) = crate::input::frecency::get_command_frecency_path() else {
+            return;
+        };
+        match crate::input::frecency::FrecencyStore::load_from_file(&path) {
+            Ok(frecency) => self
+                .command_registry
+                .write()
+                .unwrap()
+                .set_frecency(frecency),
+            Err(e) => tracing::warn!("Failed to load command frecency: {}", e),
+        }
+    }
+
+    /// Save command palette usage (for frecency ranking) to disk
+    pub fn save_command_frecency(&self) {
+        let Ok(path) = crate::input::frecency::get_command_frecency_path() else {
+            return;
+        };
+        let registry = self.command_registry.read().unwrap();
+        if let Err(e) = registry.frecency().save_to_file(&path) {
+            tracing::warn!("Failed to save command frecency: {}", e);
+        } else {
+            tracing::debug!("Saved command frecency to {:?}", path);
+        }
+    }
+
+    /// Buffers shown as tabs in a split: its open buffers in the active group
+    pub(super) fn split_tab_ids(&self, split_id: SplitId) -> Vec<BufferId> {
+        let Some(view_state) = self.split_view_states.get(&split_id) else {
+            return Vec::new();
+        };
+        self.group_tabs(view_state.open_buffers.clone())
+            .into_iter()
+            .filter(|id| self.buffers.contains_key(id))
+            .collect()
+    }
+
     /// Ensure the active tab in a split is visible by adjusting its scroll offset.
-    /// This function recalculates the required scroll_offset based on the active tab's position
-    /// and the available width, and updates the SplitViewState.
+    /// This moves the split's tab window (see `compute_tab_window`) as little as
+    /// possible for the active tab to fit in `available_width` columns, and
+    /// updates the SplitViewState.
     pub(super) fn ensure_active_tab_visible(
         &mut self,
         split_id: SplitId,
         active_buffer: BufferId,
         available_width: u16,
     ) {
+        let tab_ids = self.split_tab_ids(split_id);
+        let tab_widths =
+            crate::view::ui::tabs::tab_widths(&tab_ids, &self.buffers, &self.buffer_metadata);
+        let active_tab_index = tab_ids.iter().position(|id| *id == active_buffer);
         let Some(view_state) = self.split_view_states.get_mut(&split_id) else {
             return;
         };
 
-        let split_buffers = &view_state.open_buffers;
-        let buffers = &self.buffers;
-        let buffer_metadata = &self.buffer_metadata;
-        // The theme is not strictly necessary here, but passed to TabsRenderer
-        // so we'll just use a dummy default style for width calculation
-
-        // Calculate widths of tabs (and separators)
-        let mut tab_layout_info: Vec<(usize, bool)> = Vec::new();
-        for (idx, id) in split_buffers.iter().enumerate() {
-            let Some(state) = buffers.get(id) else {
-                continue;
-            };
-
-            let name = if let Some(metadata) = buffer_metadata.get(id) {
-                metadata.display_name.as_str()
-            } else {
-                state
-                    .buffer
-                    .file_path()
-                    .and_then(|p| p.file_name())
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("[No Name]")
-            };
-
-            let modified_indicator_width = if state.buffer.is_modified() { 1 } else { 0 };
-            let tab_width = 2 + name.chars().count() + modified_indicator_width; // " {name}{modified} "
-            let is_active = *id == active_buffer;
-
-            tab_layout_info.push((tab_width, is_active));
-            if idx < split_buffers.len() - 1 {
-                tab_layout_info.push((1, false)); // separator
-            }
-        }
-
-        let total_tabs_width: usize = tab_layout_info.iter().map(|(w, _)| w).sum();
-        let max_visible_width = available_width as usize;
-
-        let tab_widths: Vec<usize> = tab_layout_info.iter().map(|(w, _)| *w).collect();
-        let active_tab_index = tab_layout_info.iter().position(|(_, is_active)| *is_active);
+        view_state.tab_scroll_offset = crate::view::ui::tabs::compute_tab_window(
+            &tab_widths,
+            active_tab_index,
+            available_width as usize,
+            view_state.tab_scroll_offset,
+        )
+        .offset;
+    }
+}
 
-        let new_scroll_offset = if let Some(idx) = active_tab_index {
-            crate::view::ui::tabs::compute_tab_scroll_offset(
-                &tab_widths,
-                idx,
-                max_visible_width,
-                view_state.tab_scroll_offset,
-                1, // separator width
-            )
-        } else {
-            view_state
-                .tab_scroll_offset
-                .min(total_tabs_width.saturating_sub(max_visible_width))
-        };
+/// The part of `line` quoted for a match at byte `offset` in it
+///
+/// That's the whole line, unless it's longer than `max_bytes`: then it's a
+/// `max_bytes` window that starts a little before the match.
+fn quote_line(line: &str, offset: usize, max_bytes: usize) -> &str {
+    if line.len() <= max_bytes {
+        return line;
+    }
+    let mut start = offset.saturating_sub(max_bytes / 4).min(line.len());
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + max_bytes).min(line.len());
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    &line[start..end]
+}
 
-        view_state.tab_scroll_offset = new_scroll_offset;
+/// Convert a regex parse error into a prompt error pointing at the offending character
+fn regex_syntax_error(error: &regex_syntax::Error) -> PromptError {
+    match error {
+        regex_syntax::Error::Parse(e) => PromptError {
+            position: Some(e.span().start.offset),
+            message: e.kind().to_string(),
+        },
+        regex_syntax::Error::Translate(e) => PromptError {
+            position: Some(e.span().start.offset),
+            message: e.kind().to_string(),
+        },
+        e => PromptError {
+            position: None,
+            message: e.to_string(),
+        },
     }
 }
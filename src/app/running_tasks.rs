@@ -0,0 +1,175 @@
+//! Long-running commands in the background
+//!
+//! Commands that would block the UI (project tasks, evaluation, ...) run on
+//! the async runtime through `spawn_task`, which returns a handle to the
+//! task. Running tasks show a spinner with their latest progress in the
+//! status bar, and "Cancel Running Task" stops the most recent one.
+
+use super::Editor;
+use crate::services::async_bridge::AsyncMessage;
+use std::future::Future;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Frames of the status bar spinner
+const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// How long each spinner frame is shown
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// Handle to a background task started with `spawn_task`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskHandle(pub u64);
+
+/// A background task that hasn't finished yet
+pub(super) struct RunningTask {
+    /// Shown in the status bar while the task runs
+    pub title: String,
+    /// Latest progress reported by the task
+    pub progress: Option<String>,
+    pub started: Instant,
+    /// Stops the task (dropping its future, which kills its processes)
+    pub abort: tokio::task::AbortHandle,
+}
+
+/// Lets a task report its progress to the status bar
+#[derive(Clone)]
+pub struct TaskProgress {
+    task_id: u64,
+    sender: mpsc::Sender<AsyncMessage>,
+}
+
+impl TaskProgress {
+    /// Show `message` next to the task's title
+    pub fn report(&self, message: impl Into<String>) {
+        let _ = self.sender.send(AsyncMessage::TaskProgress {
+            task_id: self.task_id,
+            message: message.into(),
+        });
+    }
+}
+
+impl Editor {
+    /// Run `task` in the background, showing `title` in the status bar
+    ///
+    /// The message the task returns is handled like any other async message
+    /// once it finishes. Returns None (after reporting it) if the async
+    /// runtime isn't available.
+    pub(super) fn spawn_task<F, Fut>(&mut self, title: String, task: F) -> Option<TaskHandle>
+    where
+        F: FnOnce(TaskProgress) -> Fut,
+        Fut: Future<Output = AsyncMessage> + Send + 'static,
+    {
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            self.set_status_error("Async runtime not available".to_string());
+            return None;
+        };
+
+        self.next_task_id += 1;
+        let task_id = self.next_task_id;
+        let sender = bridge.sender();
+        let future = task(TaskProgress {
+            task_id,
+            sender: sender.clone(),
+        });
+        let join = runtime.spawn(async move {
+            let message = future.await;
+            let _ = sender.send(message);
+            let _ = sender.send(AsyncMessage::TaskFinished { task_id });
+        });
+
+        self.running_tasks.insert(
+            task_id,
+            RunningTask {
+                title,
+                progress: None,
+                started: Instant::now(),
+                abort: join.abort_handle(),
+            },
+        );
+        Some(TaskHandle(task_id))
+    }
+
+    /// Stop a background task; its result is never delivered
+    ///
+    /// Returns false if the task already finished.
+    pub(super) fn cancel_task(&mut self, handle: TaskHandle) -> bool {
+        match self.running_tasks.remove(&handle.0) {
+            Some(task) => {
+                task.abort.abort();
+                self.set_status_message(format!("Cancelled '{}'", task.title));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop the most recently started background task
+    pub(super) fn cancel_running_task(&mut self) {
+        match self.running_tasks.keys().next_back().copied() {
+            Some(task_id) => {
+                self.cancel_task(TaskHandle(task_id));
+            }
+            None => self.set_status_message("No running task".to_string()),
+        }
+    }
+
+    /// Record progress reported by a task
+    pub(super) fn handle_task_progress(&mut self, task_id: u64, message: String) {
+        if let Some(task) = self.running_tasks.get_mut(&task_id) {
+            task.progress = Some(message);
+        }
+    }
+
+    /// Forget a finished task
+    pub(super) fn handle_task_finished(&mut self, task_id: u64) {
+        self.running_tasks.remove(&task_id);
+    }
+
+    /// Whether the task behind `handle` is still running
+    pub(super) fn is_task_running(&self, handle: TaskHandle) -> bool {
+        self.running_tasks.contains_key(&handle.0)
+    }
+
+    /// Advance the status bar spinner
+    ///
+    /// Returns true if its frame changed, meaning the screen needs a redraw.
+    pub fn update_task_spinner(&mut self) -> bool {
+        let Some(oldest) = self.running_tasks.values().next() else {
+            return false;
+        };
+        let frame = (oldest.started.elapsed().as_millis() / SPINNER_FRAME.as_millis()) as usize;
+        let changed = frame != self.task_spinner_frame;
+        self.task_spinner_frame = frame;
+        changed
+    }
+
+    /// Status bar text for running tasks ("⠹ Build: Compiling foo (12s) (+1 task)")
+    pub(super) fn running_tasks_status(&self) -> Option<String> {
+        let oldest = self.running_tasks.values().next()?;
+        let spinner = SPINNER[self.task_spinner_frame % SPINNER.len()];
+        let progress = oldest
+            .progress
+            .as_ref()
+            .map(|p| format!(": {}", p))
+            .unwrap_or_default();
+        let elapsed = oldest.started.elapsed().as_secs();
+        let elapsed = if elapsed > 0 {
+            format!(" ({}s)", elapsed)
+        } else {
+            String::new()
+        };
+        Some(match self.running_tasks.len() {
+            1 => format!("{} {}{}{}", spinner, oldest.title, progress, elapsed),
+            n => format!(
+                "{} {}{}{} (+{} {})",
+                spinner,
+                oldest.title,
+                progress,
+                elapsed,
+                n - 1,
+                if n == 2 { "task" } else { "tasks" }
+            ),
+        })
+    }
+}
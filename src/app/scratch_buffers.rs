@@ -0,0 +1,118 @@
+//! Scratch buffers for throwaway text
+//!
+//! "New Scratch Buffer" opens an unnamed buffer, "*scratch*", highlighted as
+//! the language picked for it. Scratch buffers are never saved unless "Save
+//! As" is used on them: closing them or quitting discards their text without
+//! asking, and they aren't kept for recovery. Saved as a file, a scratch
+//! buffer becomes an ordinary buffer.
+
+use super::types::BufferMetadata;
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::BufferId;
+use crate::view::prompt::{Prompt, PromptType};
+use std::collections::HashSet;
+
+impl Editor {
+    /// Whether `buffer_id` is a scratch buffer
+    pub(super) fn is_scratch_buffer(&self, buffer_id: BufferId) -> bool {
+        self.buffer_metadata
+            .get(&buffer_id)
+            .is_some_and(|metadata| metadata.scratch)
+    }
+
+    /// Whether closing `buffer_id` or quitting would lose changes worth
+    /// asking about: it is modified and not a scratch buffer
+    pub(super) fn buffer_needs_saving(&self, buffer_id: BufferId) -> bool {
+        !self.is_scratch_buffer(buffer_id)
+            && self
+                .buffers
+                .get(&buffer_id)
+                .is_some_and(|state| state.buffer.is_modified())
+    }
+
+    /// Ask which language a new scratch buffer is highlighted as
+    pub(super) fn start_new_scratch_buffer_prompt(&mut self) {
+        let mut suggestions = vec![language_suggestion("Plain Text", None)];
+        let mut seen = HashSet::new();
+        let mut syntaxes: Vec<(&str, &str)> = self
+            .grammar_registry
+            .syntax_set()
+            .syntaxes()
+            .iter()
+            .filter(|syntax| !syntax.hidden && syntax.name != "Plain Text")
+            .filter_map(|syntax| {
+                let extension = syntax.file_extensions.first()?;
+                Some((syntax.name.as_str(), extension.as_str()))
+            })
+            .filter(|(name, _)| seen.insert(name.to_lowercase()))
+            .collect();
+        syntaxes.sort_by_key(|(name, _)| name.to_lowercase());
+        suggestions.extend(
+            syntaxes
+                .into_iter()
+                .map(|(name, extension)| language_suggestion(name, Some(extension))),
+        );
+
+        self.prompt = Some(Prompt::with_suggestions(
+            "Scratch buffer language: ".to_string(),
+            PromptType::NewScratchBuffer,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Open a scratch buffer highlighted as files with `extension` (plain
+    /// text if empty)
+    pub fn new_scratch_buffer(&mut self, extension: &str) -> BufferId {
+        let buffer_id = self.new_buffer();
+
+        let taken: HashSet<String> = self
+            .buffer_metadata
+            .values()
+            .filter(|metadata| metadata.scratch)
+            .map(|metadata| metadata.display_name.clone())
+            .collect();
+        let name = (1..)
+            .map(|n| match n {
+                1 => "*scratch*".to_string(),
+                n => format!("*scratch {}*", n),
+            })
+            .find(|name| !taken.contains(name))
+            .unwrap_or_default();
+
+        let metadata = self
+            .buffer_metadata
+            .entry(buffer_id)
+            .or_insert_with(BufferMetadata::new);
+        metadata.display_name = name.clone();
+        metadata.scratch = true;
+        if !extension.is_empty() {
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                state.set_language_from_name(
+                    &format!("scratch.{}", extension),
+                    &self.grammar_registry,
+                );
+            }
+        }
+        self.set_status_message(format!("New scratch buffer {}", name));
+        buffer_id
+    }
+}
+
+/// A language for the "New Scratch Buffer" prompt; its value is the extension
+/// highlighted as
+fn language_suggestion(name: &str, extension: Option<&str>) -> Suggestion {
+    Suggestion {
+        text: name.to_string(),
+        description: extension.map(|ext| format!(".{}", ext)),
+        value: Some(extension.unwrap_or_default().to_string()),
+        disabled: false,
+        keybinding: None,
+        source: None,
+        match_indices: Vec::new(),
+        actions: Vec::new(),
+    }
+}
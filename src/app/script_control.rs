@@ -5,12 +5,14 @@
 //! with the editor programmatically and allows converting interactions to scriptable tests.
 
 use super::Editor;
+use crate::input::action_format::SerializedAction;
 use crate::{config::Config, model::control_event::EventBroadcaster};
 use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{backend::TestBackend, Terminal};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{
+    collections::HashMap,
     io::{self, BufRead, Write},
     path::PathBuf,
 };
@@ -96,6 +98,15 @@ pub enum ScriptCommand {
         text: String,
     },
 
+    /// Run an editor action by its keymap name (e.g., "move_word_right")
+    Action {
+        /// Action name
+        action: String,
+        /// Action arguments (e.g., {"char": "a"} for "insert_char")
+        #[serde(default)]
+        args: HashMap<String, Value>,
+    },
+
     /// Quit the editor
     Quit,
 
@@ -404,6 +415,7 @@ impl ScriptControlMode {
             ScriptCommand::GetBuffer => self.handle_get_buffer(),
             ScriptCommand::OpenFile { path } => self.handle_open_file(&path),
             ScriptCommand::TypeText { text } => self.handle_type_text(&text),
+            ScriptCommand::Action { action, args } => self.handle_run_action(action, args),
             ScriptCommand::Quit => self.handle_quit(),
             ScriptCommand::ExportTest { test_name } => self.handle_export_test(&test_name),
             ScriptCommand::WaitFor {
@@ -417,9 +429,13 @@ impl ScriptControlMode {
 
     /// Render to the virtual terminal
     fn render_to_terminal(&mut self) -> io::Result<()> {
+        // Like the event loop: pick up work that waits for input to pause
+        self.editor.update_search_match_count();
+        self.editor.update_suggestion_preview();
         self.terminal.draw(|frame| {
             self.editor.render(frame);
         })?;
+        self.editor.frame_flushed();
         Ok(())
     }
 
@@ -749,6 +765,23 @@ impl ScriptControlMode {
         Ok(ScriptResponse::Ok { message: None })
     }
 
+    /// Handle action command
+    fn handle_run_action(
+        &mut self,
+        action: String,
+        args: HashMap<String, Value>,
+    ) -> io::Result<ScriptResponse> {
+        let action = match (SerializedAction { action, args }).to_action() {
+            Ok(action) => action,
+            Err(message) => return Ok(ScriptResponse::Error { message }),
+        };
+        self.editor.handle_action(action)?;
+        let _ = self.editor.process_async_messages();
+        self.render_to_terminal()?;
+
+        Ok(ScriptResponse::Ok { message: None })
+    }
+
     /// Handle quit command
     fn handle_quit(&mut self) -> io::Result<ScriptResponse> {
         // Quit the editor
@@ -1078,6 +1111,15 @@ pub fn get_command_schema() -> String {
                 },
                 "example": {"type": "type_text", "text": "Hello, World!"}
             },
+            {
+                "type": "action",
+                "description": "Run an editor action by its keymap name",
+                "parameters": {
+                    "action": "Action name (e.g., \"move_word_right\", \"save\")",
+                    "args": "Optional action arguments (e.g., {\"char\": \"a\"} for insert_char)"
+                },
+                "example": {"type": "action", "action": "insert_char", "args": {"char": "a"}}
+            },
             {
                 "type": "quit",
                 "description": "Quit the editor",
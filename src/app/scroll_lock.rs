@@ -0,0 +1,109 @@
+//! Scrolling two panes together
+//!
+//! "Toggle Scroll Lock" locks the active pane to the next one: scrolling
+//! either scrolls the other by as many lines, keeping the distance between
+//! their top lines as it was when the lock was turned on. The panes may show
+//! different buffers, or the same buffer in two places. The lock ends when
+//! one of the panes is closed. While "Diff Buffers" lines up its panes, that
+//! takes the place of the lock.
+
+use super::Editor;
+use crate::model::event::SplitId;
+
+/// Two panes scrolling together
+#[derive(Debug, Clone)]
+pub(crate) struct ScrollLock {
+    first: SplitId,
+    second: SplitId,
+    /// Top line of `second` minus that of `first`
+    offset: isize,
+    /// Top lines of the two panes when they were last lined up
+    synced_top: (usize, usize),
+}
+
+impl Editor {
+    /// Lock the active pane's scrolling to the next pane's, or unlock it
+    pub fn toggle_scroll_lock(&mut self) {
+        if self.scroll_lock.take().is_some() {
+            self.set_status_message("Scroll lock off".to_string());
+            return;
+        }
+        if self.buffer_diff.is_some() {
+            self.set_status_message("Diff Buffers already scrolls its panes together".to_string());
+            return;
+        }
+        let first = self.split_manager.active_split();
+        let leaves = self.split_manager.root().leaf_split_ids();
+        let Some(position) = leaves.iter().position(|&id| id == first) else {
+            return;
+        };
+        let second = leaves[(position + 1) % leaves.len()];
+        if second == first {
+            self.set_status_message("Scroll lock needs two panes".to_string());
+            return;
+        }
+        let (Some(first_top), Some(second_top)) = (self.split_top(first), self.split_top(second))
+        else {
+            return;
+        };
+        self.scroll_lock = Some(ScrollLock {
+            first,
+            second,
+            offset: second_top as isize - first_top as isize,
+            synced_top: (first_top, second_top),
+        });
+        self.set_status_message("Scroll lock on: panes scroll together".to_string());
+    }
+
+    /// Scroll the other locked pane along with the active one. Ends the lock
+    /// once one of its panes is gone.
+    pub(super) fn sync_scroll_lock(&mut self) {
+        let Some(lock) = &self.scroll_lock else {
+            return;
+        };
+        if self.buffer_diff.is_some() {
+            return;
+        }
+        let (Some(first_top), Some(second_top)) =
+            (self.split_top(lock.first), self.split_top(lock.second))
+        else {
+            self.scroll_lock = None;
+            return;
+        };
+
+        let active_split = self.split_manager.active_split();
+        let (other, other_top) = if active_split == lock.first {
+            if first_top == lock.synced_top.0 {
+                return;
+            }
+            (lock.second, first_top.saturating_add_signed(lock.offset))
+        } else if active_split == lock.second {
+            if second_top == lock.synced_top.1 {
+                return;
+            }
+            (lock.first, second_top.saturating_add_signed(-lock.offset))
+        } else {
+            return;
+        };
+        let Some(other_buffer) = self.split_manager.buffer_for_split(other) else {
+            return;
+        };
+        self.scroll_inactive_split_to_line(other, other_buffer, other_top);
+        // Past the end of its buffer, the pane stops at the last line
+        let other_top = self.split_top_line(other, other_buffer);
+
+        if let Some(lock) = self.scroll_lock.as_mut() {
+            lock.synced_top = if active_split == lock.first {
+                (first_top, other_top)
+            } else {
+                (other_top, second_top)
+            };
+        }
+    }
+
+    /// Top line of `split_id`, or None if it was closed
+    fn split_top(&self, split_id: SplitId) -> Option<usize> {
+        let buffer_id = self.split_manager.buffer_for_split(split_id)?;
+        Some(self.split_top_line(split_id, buffer_id))
+    }
+}
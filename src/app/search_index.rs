@@ -0,0 +1,125 @@
+//! The project's search index in the editor
+//!
+//! The trigram index (see `services::search_index`) is built in the background
+//! from the project's scanned files at startup when `editor.search_index` is
+//! on, or with "Rebuild Search Index". It is updated when a file is saved, written back to disk on exit,
+//! and shared with plugins through the state snapshot, where live grep
+//! searches it with `searchProject`.
+
+use super::project_scan::ProjectScanUser;
+use super::Editor;
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::search_index::{self, SearchIndex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+impl Editor {
+    /// Index the project at startup if `editor.search_index` is on
+    pub fn init_search_index(&mut self) {
+        if self.config.editor.search_index {
+            self.start_search_index();
+        }
+    }
+
+    /// Load the project's search index and bring it up to date in the background
+    ///
+    /// Until it is done, searches use the index as it was (if any).
+    pub(super) fn start_search_index(&mut self) {
+        if self
+            .search_index_task
+            .is_some_and(|task| self.is_task_running(task))
+            || self.is_waiting_for_project_scan(ProjectScanUser::SearchIndex)
+        {
+            self.set_status_message("Search index is already being updated".to_string());
+            return;
+        }
+        let Some(scan) = self.project_files_for(ProjectScanUser::SearchIndex) else {
+            return;
+        };
+
+        let root = self.working_dir.clone();
+        let task = self.spawn_task("Indexing files".to_string(), move |_| async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let path = search_index::get_search_index_path(&root).ok();
+                let mut index = path
+                    .as_deref()
+                    .and_then(|path| match SearchIndex::load_from_file(path) {
+                        Ok(index) => Some(index),
+                        Err(e) => {
+                            tracing::warn!("Failed to load search index: {}", e);
+                            None
+                        }
+                    })
+                    .unwrap_or_default();
+                let changed = index.update(&root, &scan);
+                if let Some(path) = path.filter(|_| index.is_dirty()) {
+                    if let Err(e) = index.save_to_file(&path) {
+                        tracing::warn!("Failed to save search index: {}", e);
+                    }
+                }
+                (index, changed)
+            })
+            .await;
+            let (index, changed) = result.unwrap_or_default();
+            AsyncMessage::SearchIndexUpdated {
+                index: Box::new(index),
+                changed,
+            }
+        });
+        self.search_index_task = task;
+    }
+
+    /// Take the index built in the background
+    pub(super) fn handle_search_index_updated(&mut self, index: SearchIndex, changed: usize) {
+        let file_count = index.file_count();
+        self.search_index = Some(Arc::new(RwLock::new(index)));
+        self.search_index_task = None;
+
+        // Files saved while the index was being built
+        let open_files: Vec<PathBuf> = self
+            .buffer_metadata
+            .values()
+            .filter_map(|meta| meta.file_path().cloned())
+            .collect();
+        for path in open_files {
+            self.update_search_index_for(&path);
+        }
+
+        tracing::info!(
+            "Search index updated: {} files, {} changed",
+            file_count,
+            changed
+        );
+    }
+
+    /// Re-read a saved (or deleted) file into the search index
+    pub(super) fn update_search_index_for(&mut self, path: &Path) {
+        let Some(index) = &self.search_index else {
+            return;
+        };
+        if path.starts_with(&self.working_dir) && !self.file_filter.is_excluded(path, false) {
+            if let Ok(mut index) = index.write() {
+                index.update_file(&self.working_dir, path);
+            }
+        }
+    }
+
+    /// Write the search index back to disk if it changed
+    pub fn save_search_index(&mut self) {
+        let Some(index) = &self.search_index else {
+            return;
+        };
+        let Ok(mut index) = index.write() else {
+            return;
+        };
+        if !index.is_dirty() {
+            return;
+        }
+        let Ok(path) = search_index::get_search_index_path(&self.working_dir) else {
+            return;
+        };
+        if let Err(e) = index.save_to_file(&path) {
+            tracing::warn!("Failed to save search index: {}", e);
+        }
+    }
+}
@@ -2,20 +2,25 @@
 //!
 //! This module provides conversion between live Editor state and serialized Session data.
 
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
+use crate::input::commands::Suggestion;
 use crate::model::event::{BufferId, SplitDirection, SplitId};
 use crate::session::{
-    FileExplorerState, SearchOptions, SerializedBookmark, SerializedCursor, SerializedFileState,
-    SerializedScroll, SerializedSplitDirection, SerializedSplitNode, SerializedSplitViewState,
-    SerializedViewMode, Session, SessionConfigOverrides, SessionError, SessionHistories,
-    SESSION_VERSION,
+    get_named_session_path, validate_session_name, FileExplorerState, SearchOptions,
+    SerializedBookmark, SerializedCursor, SerializedFileState, SerializedScroll,
+    SerializedSplitDirection, SerializedSplitNode, SerializedSplitViewState, SerializedViewMode,
+    Session, SessionConfigOverrides, SessionError, SessionHistories, SESSION_VERSION,
 };
 use crate::state::ViewMode;
+use crate::view::prompt::{Prompt, PromptType};
 use crate::view::split::{SplitNode, SplitViewState};
 
+use super::file_open::format_modified;
 use super::types::Bookmark;
 use super::Editor;
 
@@ -148,14 +153,12 @@ impl Editor {
         };
 
         // Capture histories using the items() accessor
-        // Note: Only search and replace histories exist in Editor currently.
-        // Other history fields are placeholders for future features.
         let histories = SessionHistories {
             search: self.search_history.items().to_vec(),
             replace: self.replace_history.items().to_vec(),
-            command_palette: Vec::new(), // Future: when command palette has history
-            goto_line: Vec::new(),       // Future: when goto line prompt has history
-            open_file: Vec::new(),       // Future: when file open prompt has history
+            command_palette: self.command_history.items().to_vec(),
+            goto_line: self.goto_line_history.items().to_vec(),
+            open_file: self.file_history.items().to_vec(),
         };
         tracing::trace!(
             "Captured histories: {} search, {} replace",
@@ -186,6 +189,7 @@ impl Editor {
             histories,
             search_options,
             bookmarks,
+            name: self.session_name.clone(),
             saved_at: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -194,11 +198,162 @@ impl Editor {
     }
 
     /// Save the current session to disk
+    ///
+    /// Named sessions are saved under their name as well as being the
+    /// project's session (so the next start picks up where this one left off).
     pub fn save_session(&self) -> Result<(), SessionError> {
         let session = self.capture_session();
+        if let Some(name) = &self.session_name {
+            session.save_named(name)?;
+        }
         session.save()
     }
 
+    /// Name of the current session, if it is a named session
+    pub fn session_name(&self) -> Option<&str> {
+        self.session_name.as_deref()
+    }
+
+    /// Save the current session under `name` and make it the current session
+    pub fn save_session_as(&mut self, name: &str) -> Result<(), SessionError> {
+        self.capture_session().save_named(name)?;
+        self.session_name = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Handle the name entered in the "Save Session As" prompt
+    pub(super) fn handle_save_session_as(&mut self, name: &str) {
+        if let Err(e) = validate_session_name(name) {
+            self.set_status_error(e);
+            return;
+        }
+
+        let exists = get_named_session_path(&self.working_dir, name).is_ok_and(|p| p.exists());
+        if exists && self.session_name.as_deref() != Some(name) {
+            self.confirm_overwrite_session(name.to_string());
+            return;
+        }
+
+        match self.save_session_as(name) {
+            Ok(()) => self.set_status_message(format!("Session saved as '{}'", name)),
+            Err(e) => self.set_status_error(format!("Failed to save session: {}", e)),
+        }
+    }
+
+    /// Ask which named session to switch to (most recently used first)
+    pub(super) fn start_switch_session_prompt(&mut self) {
+        let sessions = match Session::list_named(&self.working_dir) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                self.set_status_error(format!("Failed to list sessions: {}", e));
+                return;
+            }
+        };
+        if sessions.is_empty() {
+            self.set_status_message(
+                "No named sessions for this project (use \"Save Session As\")".to_string(),
+            );
+            return;
+        }
+
+        let suggestions: Vec<Suggestion> = sessions
+            .iter()
+            .map(|session| {
+                let last_used = format_modified(UNIX_EPOCH + Duration::from_secs(session.saved_at));
+                let is_current = self.session_name.as_deref() == Some(session.name.as_str());
+                Suggestion {
+                    text: session.name.clone(),
+                    description: Some(if is_current {
+                        format!("(current) last used {}", last_used)
+                    } else {
+                        format!("last used {}", last_used)
+                    }),
+                    value: Some(session.name.clone()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                    match_indices: Vec::new(),
+                    actions: Vec::new(),
+                }
+            })
+            .collect();
+
+        self.prompt = Some(Prompt::with_suggestions(
+            "Switch session: ".to_string(),
+            PromptType::SwitchSession,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Handle the session picked in the "Switch Session" prompt
+    pub(super) fn handle_switch_session(&mut self, name: &str) {
+        if self.session_name.as_deref() == Some(name) {
+            self.set_status_message(format!("Already in session '{}'", name));
+            return;
+        }
+        self.confirm_switch_session(name.to_string());
+    }
+
+    /// Replace the open files and layout with the named session
+    ///
+    /// With `stash`, the current session is saved first (under its name, or as
+    /// the project's session if it has none) so it can be switched back to.
+    /// Buffers with unsaved changes must be saved or discarded first.
+    pub fn switch_session(&mut self, name: &str, stash: bool) -> Result<(), SessionError> {
+        let session = Session::load_named(&self.working_dir, name)?.ok_or_else(|| {
+            SessionError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no session named '{}'", name),
+            ))
+        })?;
+
+        if stash {
+            self.save_session()?;
+        }
+
+        self.close_session_buffers();
+        self.apply_session(&session)?;
+        self.session_name = Some(name.to_string());
+
+        // Record when the session was last used
+        let mut session = session;
+        session.touch();
+        session.save_named(name)?;
+        Ok(())
+    }
+
+    /// Close every file buffer and split, leaving a single empty buffer
+    ///
+    /// Plugin panels and other virtual buffers stay open.
+    fn close_session_buffers(&mut self) {
+        self.close_other_splits();
+        let to_close: Vec<BufferId> = self
+            .buffers
+            .keys()
+            .copied()
+            .filter(|id| self.buffer_metadata.get(id).is_none_or(|m| !m.is_virtual()))
+            .collect();
+        for buffer_id in to_close {
+            if let Err(e) = self.force_close_buffer(buffer_id) {
+                tracing::warn!("Failed to close buffer {:?}: {}", buffer_id, e);
+            }
+        }
+        self.bookmarks.clear();
+    }
+
+    /// Close every split except the active one
+    fn close_other_splits(&mut self) {
+        let active_split = self.split_manager.active_split();
+        for split_id in self.split_manager.root().leaf_split_ids() {
+            if split_id != active_split && self.split_manager.close_split(split_id).is_ok() {
+                self.split_view_states.remove(&split_id);
+            }
+        }
+    }
+
     /// Try to load and apply a session for the current working directory
     ///
     /// Returns true if a session was successfully loaded and applied.
@@ -217,6 +372,130 @@ impl Editor {
         }
     }
 
+    /// Snapshot the session if the snapshot interval has passed
+    ///
+    /// Called every frame from the main loop. Sessions that haven't changed
+    /// since the last snapshot aren't written again. Returns true if a
+    /// snapshot was written.
+    pub fn snapshot_session_if_due(&mut self) -> Result<bool, SessionError> {
+        let minutes = self.config.editor.session_snapshot_interval_mins;
+        if minutes == 0 || self.last_session_snapshot.elapsed() < Duration::from_secs(minutes * 60)
+        {
+            return Ok(false);
+        }
+        self.last_session_snapshot = Instant::now();
+        self.snapshot_session()
+    }
+
+    /// Snapshot the session now, unless it is unchanged since the last snapshot
+    ///
+    /// Returns true if a snapshot was written.
+    pub fn snapshot_session(&mut self) -> Result<bool, SessionError> {
+        let mut session = self.capture_session();
+
+        // Compare everything except the timestamp
+        let saved_at = std::mem::take(&mut session.saved_at);
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_value(&session)?
+            .to_string()
+            .hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.last_session_snapshot_hash == Some(hash) {
+            return Ok(false);
+        }
+        session.saved_at = saved_at;
+
+        session.save_snapshot(self.config.editor.session_snapshot_count)?;
+        self.last_session_snapshot_hash = Some(hash);
+        Ok(true)
+    }
+
+    /// Ask which snapshot to restore (newest first)
+    pub(super) fn start_restore_snapshot_prompt(&mut self) {
+        let snapshots = match Session::list_snapshots(&self.working_dir) {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                self.set_status_error(format!("Failed to list session snapshots: {}", e));
+                return;
+            }
+        };
+
+        let suggestions: Vec<Suggestion> = snapshots
+            .iter()
+            .filter_map(|snapshot| {
+                let session = Session::load_snapshot(&snapshot.path, &self.working_dir).ok()?;
+                let time = UNIX_EPOCH + Duration::from_secs(snapshot.saved_at);
+                let datetime: chrono::DateTime<chrono::Local> = time.into();
+                let files = session
+                    .split_states
+                    .values()
+                    .flat_map(|state| &state.open_files)
+                    .collect::<HashSet<_>>()
+                    .len();
+                Some(Suggestion {
+                    text: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    description: Some(format!(
+                        "{}, {} file{}",
+                        format_modified(time),
+                        files,
+                        if files == 1 { "" } else { "s" }
+                    )),
+                    value: Some(snapshot.path.to_string_lossy().into_owned()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                    match_indices: Vec::new(),
+                    actions: Vec::new(),
+                })
+            })
+            .collect();
+
+        if suggestions.is_empty() {
+            self.set_status_message("No session snapshots for this project".to_string());
+            return;
+        }
+
+        self.prompt = Some(Prompt::with_suggestions(
+            "Restore snapshot: ".to_string(),
+            PromptType::RestoreSessionSnapshot,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Restore the files and layout saved in a snapshot
+    ///
+    /// Open buffers stay open (including ones with unsaved changes); only the
+    /// split layout is replaced.
+    pub(super) fn restore_session_snapshot(&mut self, path: &Path) {
+        let session = match Session::load_snapshot(path, &self.working_dir) {
+            Ok(session) => session,
+            Err(e) => {
+                self.set_status_error(format!("Failed to load session snapshot: {}", e));
+                return;
+            }
+        };
+
+        // The snapshot's layout is rebuilt starting from a single split
+        self.close_other_splits();
+
+        match self.apply_session(&session) {
+            Ok(()) => {
+                let time = UNIX_EPOCH + Duration::from_secs(session.saved_at);
+                let datetime: chrono::DateTime<chrono::Local> = time.into();
+                self.set_status_message(format!(
+                    "Restored session from {}",
+                    datetime.format("%Y-%m-%d %H:%M:%S")
+                ));
+            }
+            Err(e) => {
+                self.set_status_error(format!("Failed to restore session snapshot: {}", e));
+            }
+        }
+    }
+
     /// Apply a loaded session to the editor
     pub fn apply_session(&mut self, session: &Session) -> Result<(), SessionError> {
         tracing::debug!(
@@ -244,6 +523,8 @@ impl Editor {
             self.mouse_enabled = mouse_enabled;
         }
 
+        self.session_name = session.name.clone();
+
         // 2. Restore search options
         self.search_case_sensitive = session.search_options.case_sensitive;
         self.search_whole_word = session.search_options.whole_word;
@@ -262,6 +543,15 @@ impl Editor {
         for item in &session.histories.replace {
             self.replace_history.push(item.clone());
         }
+        for item in &session.histories.command_palette {
+            self.command_history.push(item.clone());
+        }
+        for item in &session.histories.goto_line {
+            self.goto_line_history.push(item.clone());
+        }
+        for item in &session.histories.open_file {
+            self.file_history.push(item.clone());
+        }
 
         // 4. Restore file explorer state
         self.file_explorer_visible = session.file_explorer.visible;
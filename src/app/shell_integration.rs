@@ -0,0 +1,196 @@
+//! Handing files and directories over to the shell and to other programs
+//!
+//! - `fresh --pick` / `fresh --pick-dir` print the path chosen in the file
+//!   explorer when the editor exits, for shell aliases such as
+//!   `cd "$(fresh --pick-dir)"`.
+//! - "Open in External Program" opens a file with the system opener, or with
+//!   the program configured for its extension.
+
+use super::Editor;
+use crate::input::keybindings::KeyContext;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// What `--pick` and `--pick-dir` print on exit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickMode {
+    /// The picked file or directory
+    Path,
+    /// The picked directory, or the directory containing the picked file
+    Directory,
+}
+
+/// System program that opens a file with its default application
+fn system_opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Command line that opens `path`: the program configured for its extension
+/// (matched case-insensitively), or the system opener
+pub(crate) fn external_command(path: &Path, programs: &HashMap<String, String>) -> Vec<String> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    let configured = extension.and_then(|ext| {
+        programs
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('.').to_lowercase() == ext)
+            .map(|(_, program)| program.clone())
+    });
+
+    let mut command: Vec<String> = configured
+        .unwrap_or_else(|| system_opener().to_string())
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+    if command.is_empty() {
+        command.push(system_opener().to_string());
+    }
+    command.push(path.to_string_lossy().into_owned());
+    command
+}
+
+impl Editor {
+    /// Print a picked path on exit (see [`PickMode`])
+    ///
+    /// Pressing Enter on a file in the file explorer picks it and quits.
+    pub fn set_pick_mode(&mut self, mode: PickMode) {
+        self.pick_mode = Some(mode);
+    }
+
+    /// Path to print on exit in pick mode
+    ///
+    /// The file picked with Enter, else the file explorer's selection, else the
+    /// active buffer's file. With [`PickMode::Directory`] a file resolves to its
+    /// parent directory.
+    pub fn picked_path(&self) -> Option<PathBuf> {
+        let mode = self.pick_mode?;
+        let path = self
+            .picked_path
+            .clone()
+            .or_else(|| {
+                self.file_explorer
+                    .as_ref()
+                    .and_then(|explorer| explorer.get_selected_entry())
+                    .map(|entry| entry.path.clone())
+            })
+            .or_else(|| {
+                self.active_state()
+                    .buffer
+                    .file_path()
+                    .map(Path::to_path_buf)
+            })?;
+
+        match mode {
+            PickMode::Path => Some(path),
+            PickMode::Directory if path.is_dir() => Some(path),
+            PickMode::Directory => path.parent().map(Path::to_path_buf),
+        }
+    }
+
+    /// Pick a file from the file explorer in pick mode
+    ///
+    /// Returns false (leaving the file to be opened as usual) outside pick mode.
+    pub(super) fn pick_file(&mut self, path: &Path) -> bool {
+        if self.pick_mode.is_none() {
+            return false;
+        }
+        self.picked_path = Some(path.to_path_buf());
+        self.quit();
+        true
+    }
+
+    /// Open the selected file explorer entry, or the active buffer's file, in
+    /// an external program
+    pub(super) fn open_in_external_program(&mut self) {
+        let path = if self.key_context == KeyContext::FileExplorer {
+            self.file_explorer
+                .as_ref()
+                .and_then(|explorer| explorer.get_selected_entry())
+                .map(|entry| entry.path.clone())
+        } else {
+            self.active_state()
+                .buffer
+                .file_path()
+                .map(Path::to_path_buf)
+        };
+        let Some(path) = path else {
+            self.set_status_message("No file to open".to_string());
+            return;
+        };
+
+        let command = external_command(&path, &self.config.editor.external_programs);
+        let spawned = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(mut child) => {
+                // Reap the process when it exits, without waiting for it here
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+                self.set_status_message(format!("Opened {} in {}", name, command[0]));
+            }
+            Err(e) => {
+                self.set_status_error(format!("Failed to run {}: {}", command[0], e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_command() {
+        let mut programs = HashMap::new();
+        programs.insert("pdf".to_string(), "zathura --fork".to_string());
+        programs.insert(".SVG".to_string(), "inkscape".to_string());
+
+        assert_eq!(
+            external_command(Path::new("/docs/a.PDF"), &programs),
+            vec!["zathura", "--fork", "/docs/a.PDF"]
+        );
+        assert_eq!(
+            external_command(Path::new("logo.svg"), &programs),
+            vec!["inkscape", "logo.svg"]
+        );
+        assert_eq!(
+            external_command(Path::new("notes.txt"), &programs),
+            vec![system_opener(), "notes.txt"]
+        );
+    }
+
+    #[test]
+    fn test_picked_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let mut editor = Editor::new(crate::config::Config::default(), 80, 24).unwrap();
+        assert_eq!(editor.picked_path(), None);
+
+        editor.set_pick_mode(PickMode::Path);
+        assert!(editor.pick_file(&file));
+        assert!(editor.should_quit());
+        assert_eq!(editor.picked_path(), Some(file.clone()));
+
+        editor.set_pick_mode(PickMode::Directory);
+        assert_eq!(editor.picked_path(), Some(dir.path().to_path_buf()));
+    }
+}
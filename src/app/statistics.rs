@@ -0,0 +1,161 @@
+//! "Buffer Statistics": counts for the active buffer, the project and the
+//! session
+//!
+//! The popup shows the lines, words and characters of the active buffer and
+//! its selection, the project's code, comment and blank lines per language
+//! (see `services::text_stats`, counted in the background from the scanned
+//! files), and what was done this session: edits, undos and time active.
+//! The selection's words and characters are also shown live in the status
+//! bar.
+
+use super::project_scan::ProjectScanUser;
+use super::Editor;
+use crate::model::event::{PopupContentData, PopupData, PopupPositionData};
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::text_stats::{self, LanguageStats, LineCounts, TextCounts};
+use std::time::Duration;
+
+/// Pauses between inputs longer than this don't count as time active
+const IDLE_GAP: Duration = Duration::from_secs(60);
+
+/// Most languages listed in the popup
+const MAX_LANGUAGES: usize = 10;
+
+/// What was done since the editor started
+#[derive(Debug, Default)]
+pub(crate) struct SessionStats {
+    /// Edits applied to buffers, not counting undo and redo
+    pub(super) edits: usize,
+    pub(super) undos: usize,
+    /// Time with input arriving less than `IDLE_GAP` apart
+    active: Duration,
+}
+
+impl SessionStats {
+    /// Count the time since the previous input as active, unless it was a
+    /// pause
+    pub(super) fn note_input(&mut self, since_last: Duration) {
+        if since_last < IDLE_GAP {
+            self.active += since_last;
+        }
+    }
+}
+
+impl Editor {
+    /// Count the project's lines in the background, then show the popup
+    pub fn show_buffer_statistics(&mut self) {
+        let Some(scan) = self.project_files_for(ProjectScanUser::BufferStatistics) else {
+            self.set_status_message("Scanning the project's files...".to_string());
+            return;
+        };
+        let root = self.working_dir.clone();
+        self.spawn_task("Counting lines".to_string(), move |_| async move {
+            let stats = tokio::task::spawn_blocking(move || {
+                text_stats::project_code_stats(&root, &scan.files)
+            })
+            .await
+            .unwrap_or_default();
+            AsyncMessage::ProjectLinesCounted { stats }
+        });
+    }
+
+    /// Show the "Buffer Statistics" popup with the project's counted lines
+    pub(super) fn handle_project_lines_counted(&mut self, stats: Vec<LanguageStats>) {
+        let name = self.get_buffer_display_name(self.active_buffer);
+        let state = self.active_state_mut();
+        let len = state.buffer.len();
+        let buffer = text_stats::text_counts(&state.get_text_range(0, len));
+        let selection = state.selection_counts(usize::MAX);
+
+        let mut lines = vec![format!("{}: {}", name, format_text_counts(buffer))];
+        if let Some(selection) = selection {
+            lines.push(format!("Selection: {}", format_text_counts(selection)));
+        }
+
+        lines.push(String::new());
+        if stats.is_empty() {
+            lines.push("Project: no source files".to_string());
+        } else {
+            let row = |name: &str, files: usize, counts: LineCounts| {
+                format!(
+                    "{:<12} {:>6} {:>8} {:>8} {:>7}",
+                    name, files, counts.code, counts.comment, counts.blank
+                )
+            };
+            lines.push(format!(
+                "{:<12} {:>6} {:>8} {:>8} {:>7}",
+                "Project", "Files", "Code", "Comment", "Blank"
+            ));
+            for language in stats.iter().take(MAX_LANGUAGES) {
+                lines.push(row(language.language, language.files, language.lines));
+            }
+            if stats.len() > MAX_LANGUAGES {
+                lines.push(format!("... {} more", stats.len() - MAX_LANGUAGES));
+            }
+            let total = stats
+                .iter()
+                .fold(LineCounts::default(), |mut total, language| {
+                    total.code += language.lines.code;
+                    total.comment += language.lines.comment;
+                    total.blank += language.lines.blank;
+                    total
+                });
+            let files = stats.iter().map(|language| language.files).sum();
+            lines.push(row("Total", files, total));
+        }
+
+        lines.push(String::new());
+        lines.push(format!(
+            "Session: {}, {}, active {}",
+            plural(self.session_stats.edits, "edit"),
+            plural(self.session_stats.undos, "undo"),
+            format_duration(self.session_stats.active)
+        ));
+
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(20, 80) as u16
+            + 2;
+        let height = lines.len() as u16 + 2;
+        self.show_popup(PopupData {
+            title: Some("Buffer Statistics".to_string()),
+            content: PopupContentData::Text(lines),
+            position: PopupPositionData::Centered,
+            width,
+            max_height: height,
+            bordered: true,
+        });
+    }
+}
+
+/// e.g. "3 lines, 12 words, 64 chars"
+fn format_text_counts(counts: TextCounts) -> String {
+    format!(
+        "{}, {}, {}",
+        plural(counts.lines, "line"),
+        plural(counts.words, "word"),
+        plural(counts.chars, "char")
+    )
+}
+
+/// e.g. "1 edit" or "2 edits"
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// e.g. "1h 05m" or "12m 30s"
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
@@ -0,0 +1,128 @@
+//! Secondary actions of prompt suggestions
+//!
+//! Besides being confirmed with Enter, a suggestion can offer actions run by
+//! their own keys while it is selected (see `SuggestionAction`); the
+//! suggestions popup lists them in a row at the bottom. The buffer picker
+//! closes buffers and opens them in a split this way, and plugin prompts get
+//! the "prompt_action" hook.
+
+use super::Editor;
+use crate::input::commands::SuggestionAction;
+use crate::model::event::BufferId;
+use crate::services::plugins::hooks::HookArgs;
+use crate::view::prompt::PromptType;
+
+impl Editor {
+    /// Run the selected suggestion's action for a key press, if it has one
+    pub(super) fn handle_suggestion_action_key(
+        &mut self,
+        code: crossterm::event::KeyCode,
+        modifiers: crossterm::event::KeyModifiers,
+    ) -> bool {
+        let Some(action) = self
+            .prompt
+            .as_ref()
+            .and_then(|prompt| prompt.selected_action(code, modifiers))
+            .cloned()
+        else {
+            return false;
+        };
+        self.run_suggestion_action(action);
+        true
+    }
+
+    /// Run an action of the selected suggestion
+    fn run_suggestion_action(&mut self, action: SuggestionAction) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        let Some(selected_index) = prompt.selected_suggestion else {
+            return;
+        };
+        let Some(suggestion) = prompt.suggestions.get(selected_index) else {
+            return;
+        };
+        let value = suggestion.get_value().to_string();
+        let input = prompt.input.clone();
+
+        match prompt.prompt_type.clone() {
+            PromptType::SwitchToTab => {
+                if let Ok(id) = value.parse::<usize>() {
+                    self.run_tab_suggestion_action(&action.id, BufferId(id), &input);
+                }
+            }
+            PromptType::Plugin { custom_type } => {
+                if !action.keep_open {
+                    self.prompt = None;
+                }
+                let hook_args = HookArgs::PromptAction {
+                    prompt_type: custom_type,
+                    input,
+                    selected_index,
+                    action: action.id,
+                };
+                if let Some(ref ts_manager) = self.ts_plugin_manager {
+                    ts_manager.run_hook("prompt_action", hook_args);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The buffer picker's actions for each tab
+    pub(super) fn tab_suggestion_actions() -> Vec<SuggestionAction> {
+        vec![
+            SuggestionAction {
+                keep_open: true,
+                ..SuggestionAction::new("ctrl+w", "close", "close")
+            },
+            SuggestionAction::new("ctrl+enter", "open in split", "split"),
+        ]
+    }
+
+    /// Close a buffer from the buffer picker, or open it in a new split
+    fn run_tab_suggestion_action(&mut self, action: &str, buffer_id: BufferId, input: &str) {
+        match action {
+            "close" => {
+                if self.buffer_needs_saving(buffer_id) {
+                    // Asks whether to discard the changes
+                    self.prompt = None;
+                    self.confirm_close_buffer(buffer_id);
+                    return;
+                }
+                let selected = self.prompt.as_ref().and_then(|p| p.selected_suggestion);
+                if let Err(e) = self.close_buffer(buffer_id) {
+                    self.set_status_warning(format!("Cannot close buffer: {}", e));
+                    return;
+                }
+                self.set_status_message("Buffer closed".to_string());
+                self.reopen_switch_to_tab_prompt(input, selected);
+            }
+            "split" => {
+                self.prompt = None;
+                self.split_pane_vertical();
+                self.set_active_buffer(buffer_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Open the buffer picker again after its tabs changed, keeping the
+    /// filter and the selected row
+    fn reopen_switch_to_tab_prompt(&mut self, input: &str, selected: Option<usize>) {
+        self.start_switch_to_tab_prompt();
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+        if prompt.prompt_type != PromptType::SwitchToTab {
+            return;
+        }
+        prompt.input = input.to_string();
+        prompt.cursor_pos = input.len();
+        self.update_prompt_suggestions();
+        if let Some(prompt) = self.prompt.as_mut() {
+            let last = prompt.suggestions.len().checked_sub(1);
+            prompt.selected_suggestion = selected.zip(last).map(|(s, last)| s.min(last));
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Preview of the highlighted item in the theme, tab, file and symbol pickers
+//!
+//! The preview is rebuilt only after the selection has rested on an item for
+//! `suggestion_preview_delay_ms`, so scrolling quickly through a long list
+//! doesn't load every file along the way. Files are never loaded whole: only
+//! the start of the file, or the lines around a symbol, are read (see
+//! `services::file_preview`), and only those lines are highlighted.
+
+use super::Editor;
+use crate::model::buffer::Buffer;
+use crate::model::event::BufferId;
+use crate::primitives::highlight_engine::HighlightEngine;
+use crate::services::file_preview::{self, PreviewRange};
+use crate::view::prompt::PromptType;
+use crate::view::ui::{PreviewContent, PreviewLine, SuggestionPreview};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Maximum number of lines loaded into a preview
+const MAX_PREVIEW_LINES: usize = 100;
+/// Maximum number of bytes read from a file for its preview
+const MAX_PREVIEW_BYTES: u64 = 64 * 1024;
+/// Lines shown before and after a symbol's line
+const PREVIEW_CONTEXT_LINES: usize = 5;
+
+/// Picker item that can be previewed
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PreviewTarget {
+    Theme(String),
+    Buffer(BufferId),
+    File(PathBuf),
+    /// A 0-indexed line of a file
+    FileLine(PathBuf, usize),
+}
+
+/// Preview waiting out the delay, and the preview currently shown
+#[derive(Debug, Default)]
+pub(crate) struct PreviewState {
+    pending: Option<(PreviewTarget, Instant)>,
+    shown: Option<(PreviewTarget, SuggestionPreview)>,
+}
+
+impl Editor {
+    /// Preview to draw next to the active picker, if any
+    pub(crate) fn suggestion_preview(&self) -> Option<&SuggestionPreview> {
+        self.preview_state
+            .shown
+            .as_ref()
+            .map(|(_, preview)| preview)
+    }
+
+    /// Update the preview for the highlighted picker item
+    ///
+    /// Called from the event loop. Returns true if the preview changed and the
+    /// screen needs to be redrawn.
+    pub fn update_suggestion_preview(&mut self) -> bool {
+        let Some(target) = self.preview_target() else {
+            let changed = self.preview_state.shown.is_some();
+            self.preview_state = PreviewState::default();
+            return changed;
+        };
+
+        if matches!(&self.preview_state.shown, Some((shown, _)) if *shown == target) {
+            self.preview_state.pending = None;
+            return false;
+        }
+
+        // Restart the delay whenever the highlighted item changes
+        let since = match &self.preview_state.pending {
+            Some((pending, since)) if *pending == target => *since,
+            _ => {
+                let now = Instant::now();
+                self.preview_state.pending = Some((target.clone(), now));
+                now
+            }
+        };
+        let delay = Duration::from_millis(self.config.editor.suggestion_preview_delay_ms);
+        if since.elapsed() < delay {
+            return false;
+        }
+
+        let preview = self.build_suggestion_preview(&target);
+        self.preview_state.pending = None;
+        self.preview_state.shown = Some((target, preview));
+        true
+    }
+
+    /// Item highlighted in the active picker, if it can be previewed
+    fn preview_target(&self) -> Option<PreviewTarget> {
+        if !self.config.editor.suggestion_preview {
+            return None;
+        }
+
+        let prompt = self.prompt.as_ref()?;
+        match prompt.prompt_type {
+            PromptType::OpenFile => {
+                let entry = self.file_open_state.as_ref()?.selected_entry()?;
+                if entry.fs_entry.is_dir() {
+                    None
+                } else {
+                    Some(PreviewTarget::File(entry.fs_entry.path.clone()))
+                }
+            }
+            PromptType::SelectTheme => {
+                let suggestion = prompt.suggestions.get(prompt.selected_suggestion?)?;
+                Some(PreviewTarget::Theme(suggestion.get_value().to_string()))
+            }
+            PromptType::SwitchToTab => {
+                let suggestion = prompt.suggestions.get(prompt.selected_suggestion?)?;
+                let id = suggestion.get_value().parse().ok()?;
+                Some(PreviewTarget::Buffer(BufferId(id)))
+            }
+            PromptType::WorkspaceSymbol => {
+                // Value is "line:column:path", with the path relative to the project
+                let suggestion = prompt.suggestions.get(prompt.selected_suggestion?)?;
+                let mut parts = suggestion.get_value().splitn(3, ':');
+                let line = parts.next()?.parse().ok()?;
+                let path = parts.nth(1)?;
+                Some(PreviewTarget::FileLine(self.working_dir.join(path), line))
+            }
+            _ => None,
+        }
+    }
+
+    fn build_suggestion_preview(&self, target: &PreviewTarget) -> SuggestionPreview {
+        match target {
+            PreviewTarget::Theme(name) => SuggestionPreview {
+                title: name.clone(),
+                content: PreviewContent::Theme(Box::new(crate::view::theme::Theme::from_name(
+                    name,
+                ))),
+            },
+            PreviewTarget::Buffer(id) => {
+                let title = self
+                    .buffer_metadata
+                    .get(id)
+                    .map(|m| m.display_name.clone())
+                    .unwrap_or_else(|| format!("Buffer {:?}", id));
+                let content = match self.buffers.get(id) {
+                    Some(state) => {
+                        let lines = (0..MAX_PREVIEW_LINES)
+                            .map_while(|line| state.buffer.get_line(line))
+                            .map(|bytes| {
+                                String::from_utf8_lossy(&bytes)
+                                    .trim_end_matches(['\n', '\r'])
+                                    .to_string()
+                            })
+                            .collect();
+                        let path = self.buffer_metadata.get(id).and_then(|m| m.file_path());
+                        PreviewContent::Text(self.highlight_preview_lines(
+                            path.map(PathBuf::as_path),
+                            lines,
+                            None,
+                        ))
+                    }
+                    None => PreviewContent::Message("Buffer is no longer open".to_string()),
+                };
+                SuggestionPreview { title, content }
+            }
+            PreviewTarget::File(path) => SuggestionPreview {
+                title: Self::preview_file_name(path),
+                content: self.file_preview_content(
+                    path,
+                    PreviewRange::Start {
+                        max_bytes: MAX_PREVIEW_BYTES,
+                    },
+                    None,
+                ),
+            },
+            PreviewTarget::FileLine(path, line) => SuggestionPreview {
+                title: format!("{}:{}", Self::preview_file_name(path), line + 1),
+                content: self.file_preview_content(
+                    path,
+                    PreviewRange::AroundLine {
+                        line: *line,
+                        context: PREVIEW_CONTEXT_LINES,
+                    },
+                    Some(*line),
+                ),
+            },
+        }
+    }
+
+    fn preview_file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string())
+    }
+
+    /// Read part of a file for its preview
+    fn file_preview_content(
+        &self,
+        path: &Path,
+        range: PreviewRange,
+        target_line: Option<usize>,
+    ) -> PreviewContent {
+        match file_preview::load_file_slice(path, range, MAX_PREVIEW_LINES) {
+            Ok(Some(slice)) => {
+                let target = target_line.map(|line| line.saturating_sub(slice.first_line));
+                PreviewContent::Text(self.highlight_preview_lines(Some(path), slice.lines, target))
+            }
+            Ok(None) => PreviewContent::Message("Binary file".to_string()),
+            Err(e) => PreviewContent::Message(format!("Cannot read file: {}", e)),
+        }
+    }
+
+    /// Highlight the lines of a preview for the language of `path`
+    ///
+    /// Only the given lines are parsed, so constructs that start before them
+    /// (e.g., a block comment) may be highlighted wrongly.
+    fn highlight_preview_lines(
+        &self,
+        path: Option<&Path>,
+        lines: Vec<String>,
+        target: Option<usize>,
+    ) -> Vec<PreviewLine> {
+        let spans = match path {
+            Some(path) => {
+                let text = lines.join("\n");
+                let buffer = Buffer::from_str(&text, 0);
+                HighlightEngine::for_file(path, &self.grammar_registry).highlight_viewport(
+                    &buffer,
+                    0,
+                    text.len(),
+                    &self.theme,
+                )
+            }
+            None => Vec::new(),
+        };
+
+        let mut line_start = 0;
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, text)| {
+                let line_end = line_start + text.len();
+                let highlights = spans
+                    .iter()
+                    .filter(|span| span.range.start < line_end && span.range.end > line_start)
+                    .map(|span| {
+                        let start = span.range.start.max(line_start) - line_start;
+                        let end = span.range.end.min(line_end) - line_start;
+                        (start..end, span.color)
+                    })
+                    .collect();
+                line_start = line_end + 1;
+                PreviewLine {
+                    text,
+                    highlights,
+                    is_target: target == Some(idx),
+                }
+            })
+            .collect()
+    }
+}
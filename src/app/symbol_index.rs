@@ -0,0 +1,232 @@
+//! The project's symbol index in the editor
+//!
+//! The index (see `services::symbol_index`) is loaded and brought up to date
+//! from the project's scanned files in the background at startup or with
+//! "Rebuild Symbol Index", updated when
+//! a file is saved, and written back to disk on exit. "Go To Symbol in
+//! Workspace" lists its symbols, and go-to-definition falls back to it when
+//! no language server answers.
+
+use super::open_to_side::{OpenSide, SideOpen};
+use super::project_scan::ProjectScanUser;
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::primitives::word_navigation::{find_word_end, find_word_start};
+use crate::services::async_bridge::AsyncMessage;
+use crate::services::symbol_index::{self, SymbolIndex};
+use crate::view::prompt::{Prompt, PromptType};
+use std::path::{Path, PathBuf};
+
+impl Editor {
+    /// Index the project at startup, unless `editor.symbol_index` is off
+    pub fn init_symbol_index(&mut self) {
+        if self.config.editor.symbol_index {
+            self.start_symbol_index();
+        }
+    }
+
+    /// Load the project's symbol index and bring it up to date in the background
+    pub(super) fn start_symbol_index(&mut self) {
+        if self.is_symbol_index_building() {
+            self.set_status_message("Symbol index is already being updated".to_string());
+            return;
+        }
+        let Some(scan) = self.project_files_for(ProjectScanUser::SymbolIndex) else {
+            return;
+        };
+
+        let root = self.working_dir.clone();
+        let task = self.spawn_task("Indexing symbols".to_string(), move |_| async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let path = symbol_index::get_symbol_index_path(&root).ok();
+                let mut index = path
+                    .as_deref()
+                    .and_then(|path| match SymbolIndex::load_from_file(path) {
+                        Ok(index) => Some(index),
+                        Err(e) => {
+                            tracing::warn!("Failed to load symbol index: {}", e);
+                            None
+                        }
+                    })
+                    .unwrap_or_default();
+                let changed = index.update(&root, &scan);
+                if let Some(path) = path.filter(|_| index.is_dirty()) {
+                    if let Err(e) = index.save_to_file(&path) {
+                        tracing::warn!("Failed to save symbol index: {}", e);
+                    }
+                }
+                (index, changed)
+            })
+            .await;
+            let (index, changed) = result.unwrap_or_default();
+            AsyncMessage::SymbolIndexUpdated {
+                index: Box::new(index),
+                changed,
+            }
+        });
+        self.symbol_index_task = task;
+    }
+
+    /// Whether the symbol index is being brought up to date
+    fn is_symbol_index_building(&self) -> bool {
+        self.symbol_index_task
+            .is_some_and(|task| self.is_task_running(task))
+            || self.is_waiting_for_project_scan(ProjectScanUser::SymbolIndex)
+    }
+
+    /// Take the index built in the background
+    pub(super) fn handle_symbol_index_updated(&mut self, index: SymbolIndex, changed: usize) {
+        self.symbol_index = index;
+        self.symbol_index_task = None;
+
+        // Files saved while the index was being built
+        let open_files: Vec<PathBuf> = self
+            .buffer_metadata
+            .values()
+            .filter_map(|meta| meta.file_path().cloned())
+            .collect();
+        for path in open_files {
+            self.update_symbol_index_for(&path);
+        }
+
+        tracing::info!(
+            "Symbol index updated: {} files, {} changed",
+            self.symbol_index.file_count(),
+            changed
+        );
+    }
+
+    /// Re-read a saved (or deleted) file into the symbol index
+    pub(super) fn update_symbol_index_for(&mut self, path: &Path) {
+        if path.starts_with(&self.working_dir) && !self.file_filter.is_excluded(path, false) {
+            self.symbol_index.update_file(&self.working_dir, path);
+        }
+    }
+
+    /// Write the symbol index back to disk if it changed
+    pub fn save_symbol_index(&mut self) {
+        if !self.symbol_index.is_dirty() {
+            return;
+        }
+        let Ok(path) = symbol_index::get_symbol_index_path(&self.working_dir) else {
+            return;
+        };
+        if let Err(e) = self.symbol_index.save_to_file(&path) {
+            tracing::warn!("Failed to save symbol index: {}", e);
+        }
+    }
+
+    /// Pick one of the project's symbols and jump to it
+    pub(super) fn start_workspace_symbol_prompt(&mut self) {
+        let mut symbols: Vec<_> = self.symbol_index.symbols().collect();
+        if symbols.is_empty() {
+            let message = if self.is_symbol_index_building() {
+                "Symbol index is still being built"
+            } else {
+                "No symbols indexed (run \"Rebuild Symbol Index\")"
+            };
+            self.set_status_message(message.to_string());
+            return;
+        }
+        symbols.sort_by(|a, b| {
+            (a.1.name.as_str(), a.0, a.1.line).cmp(&(b.1.name.as_str(), b.0, b.1.line))
+        });
+
+        let suggestions = symbols
+            .into_iter()
+            .map(|(path, symbol)| Suggestion {
+                text: symbol.name.clone(),
+                description: Some(format!(
+                    "{} {}:{}",
+                    symbol.kind.label(),
+                    path.display(),
+                    symbol.line + 1
+                )),
+                value: Some(format!(
+                    "{}:{}:{}",
+                    symbol.line,
+                    symbol.column,
+                    path.display()
+                )),
+                disabled: false,
+                keybinding: None,
+                source: None,
+                match_indices: Vec::new(),
+                actions: Vec::new(),
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            "Go to symbol: ".to_string(),
+            PromptType::WorkspaceSymbol,
+            suggestions,
+        ));
+    }
+
+    /// Jump to the symbol chosen in the workspace symbol prompt
+    ///
+    /// `value` is "line:column:path", with the path relative to the project.
+    pub(super) fn go_to_workspace_symbol(&mut self, value: &str) {
+        let mut parts = value.splitn(3, ':');
+        let (Some(Ok(line)), Some(Ok(column)), Some(path)) = (
+            parts.next().map(str::parse::<usize>),
+            parts.next().map(str::parse::<usize>),
+            parts.next(),
+        ) else {
+            return;
+        };
+        self.jump_to_indexed_symbol(Path::new(path), line, column);
+    }
+
+    /// Go to the definition of the word at the cursor using the symbol index,
+    /// in another pane if `side` is given
+    ///
+    /// Returns false (without a status message) if the index has none.
+    pub(super) fn goto_definition_from_index(&mut self, side: Option<OpenSide>) -> bool {
+        let state = self.active_state();
+        let cursor = state.cursors.primary().position;
+        let start = find_word_start(&state.buffer, cursor);
+        let end = find_word_end(&state.buffer, cursor);
+        if start >= end {
+            return false;
+        }
+        let word = String::from_utf8_lossy(&state.buffer.slice_bytes(start..end)).to_string();
+
+        // Definitions in files deleted outside the editor are dropped
+        let root = self.working_dir.clone();
+        let found: Vec<(PathBuf, usize, usize)> = self
+            .symbol_index
+            .find(&word)
+            .into_iter()
+            .map(|(path, symbol)| (path.to_path_buf(), symbol.line, symbol.column))
+            .collect();
+        for (path, line, column) in found {
+            if root.join(&path).is_file() {
+                match side {
+                    Some(side) => {
+                        let open = SideOpen {
+                            path: root.join(&path),
+                            line: Some(line + 1),
+                            column: Some(column + 1),
+                        };
+                        self.open_to_side(open, side);
+                    }
+                    None => self.jump_to_indexed_symbol(&path, line, column),
+                }
+                return true;
+            }
+            self.symbol_index.remove_file(&root, &path);
+        }
+        false
+    }
+
+    /// Open a file of the project at a 0-indexed line and byte column
+    fn jump_to_indexed_symbol(&mut self, path: &Path, line: usize, column: usize) {
+        let full_path = self.working_dir.join(path);
+        if let Err(e) = self.open_file(&full_path) {
+            self.set_status_error(format!("Cannot open {}: {}", path.display(), e));
+            return;
+        }
+        self.jump_to_line_column(Some(line + 1), Some(column + 1));
+        self.set_status_message(format!("{}:{}", path.display(), line + 1));
+    }
+}
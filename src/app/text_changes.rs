@@ -0,0 +1,59 @@
+//! Change notifications in the editor
+//!
+//! Buffers record every insert and delete as a `TextChange` (see
+//! `model::text_change`). After edits are applied the changes are taken and
+//! handed to the LSP server (as incremental `didChange` events) and to plugins
+//! (the `buffer_changed` hook), so both see the same stream no matter which
+//! code path made the edit.
+
+use super::Editor;
+use crate::model::event::BufferId;
+use crate::model::text_change::{LspPosition, TextChange};
+use crate::services::plugins::hooks::HookArgs;
+use lsp_types::{Position, Range as LspRange, TextDocumentContentChangeEvent};
+
+impl Editor {
+    /// Send the changes made to any buffer since the last notification
+    pub(super) fn flush_text_changes(&mut self) {
+        let buffer_ids: Vec<BufferId> = self
+            .buffers
+            .iter()
+            .filter(|(_, state)| state.has_changes())
+            .map(|(id, _)| *id)
+            .collect();
+        for buffer_id in buffer_ids {
+            self.flush_buffer_changes(buffer_id);
+        }
+    }
+
+    /// Send the changes made to a buffer since the last notification
+    pub(super) fn flush_buffer_changes(&mut self, buffer_id: BufferId) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let changes = state.take_changes();
+        if changes.is_empty() {
+            return;
+        }
+
+        let lsp_changes = changes.iter().map(lsp_change_event).collect();
+        self.send_lsp_changes_for_buffer(buffer_id, lsp_changes);
+
+        if let Some(ref ts_manager) = self.ts_plugin_manager {
+            ts_manager.run_hook(
+                "buffer_changed",
+                HookArgs::BufferChanged { buffer_id, changes },
+            );
+        }
+    }
+}
+
+/// Incremental `didChange` event for a change
+pub(super) fn lsp_change_event(change: &TextChange) -> TextDocumentContentChangeEvent {
+    let position = |pos: LspPosition| Position::new(pos.line as u32, pos.character as u32);
+    TextDocumentContentChangeEvent {
+        range: Some(LspRange::new(position(change.start), position(change.end))),
+        range_length: None,
+        text: change.new_text.clone(),
+    }
+}
@@ -0,0 +1,64 @@
+//! Selecting text objects ("inside quotes", "around function", ...)
+//!
+//! The objects themselves are found by `primitives::text_objects`; this sets
+//! each cursor's selection to the object around it.
+
+use super::Editor;
+use crate::model::event::Event;
+
+impl Editor {
+    /// Select the text object `name` around each cursor
+    ///
+    /// Cursors without such an object around them are left as they are.
+    pub(super) fn select_text_object(&mut self, name: &str, around: bool) {
+        if !self.text_objects.exists(name) {
+            self.set_status_error(format!("Unknown text object '{}'", name));
+            return;
+        }
+
+        let state = self.active_state();
+        let Some(text) = state.buffer.to_string() else {
+            self.set_status_error("Text objects need the whole file loaded".to_string());
+            return;
+        };
+        let language = state.highlighter.language().copied();
+        let cursors: Vec<_> = state
+            .cursors
+            .iter()
+            .map(|(cursor_id, cursor)| (cursor_id, *cursor))
+            .collect();
+
+        let mut events = Vec::new();
+        for (cursor_id, cursor) in cursors {
+            let Some(range) =
+                self.text_objects
+                    .find(&text, cursor.position, name, around, language.as_ref())
+            else {
+                continue;
+            };
+            events.push(Event::MoveCursor {
+                cursor_id,
+                old_position: cursor.position,
+                new_position: range.end,
+                old_anchor: cursor.anchor,
+                new_anchor: Some(range.start),
+                old_sticky_column: cursor.sticky_column,
+                new_sticky_column: 0,
+            });
+        }
+
+        let event = match events.len() {
+            0 => {
+                self.set_status_message(format!("No {} around the cursor", name));
+                return;
+            }
+            1 => events.pop().unwrap(),
+            _ => Event::Batch {
+                events,
+                description: format!("Select {}", name),
+            },
+        };
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
+    }
+}
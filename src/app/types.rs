@@ -27,6 +27,8 @@ pub(super) struct SearchState {
     pub query: String,
     /// All match positions in the buffer (byte offsets)
     pub matches: Vec<usize>,
+    /// Whether `matches` stopped at the search match limit
+    pub truncated: bool,
     /// Index of the currently selected match
     pub current_match_index: Option<usize>,
     /// Whether search wraps around at document boundaries
@@ -63,6 +65,18 @@ pub(super) struct InteractiveReplaceState {
     pub has_wrapped: bool,
     /// Number of replacements made so far
     pub replacements_made: usize,
+    /// Byte range replacements are restricted to (when replacing in selection)
+    pub scope: Option<Range<usize>>,
+}
+
+impl InteractiveReplaceState {
+    /// Shift the end of the scope to account for the replacement just made,
+    /// so the selection keeps covering the same text
+    pub fn adjust_scope_after_replacement(&mut self) {
+        if let Some(scope) = self.scope.as_mut() {
+            scope.end = (scope.end + self.replacement.len()).saturating_sub(self.search.len());
+        }
+    }
 }
 
 /// The kind of buffer (file-backed or virtual)
@@ -104,6 +118,10 @@ pub struct BufferMetadata {
     /// Whether the buffer contains binary content
     /// Binary buffers are automatically read-only and render unprintable chars as code points
     pub binary: bool,
+
+    /// Whether this is a scratch buffer, whose changes are discarded without
+    /// asking on close and quit
+    pub scratch: bool,
 }
 
 impl BufferMetadata {
@@ -150,6 +168,7 @@ impl BufferMetadata {
             lsp_disabled_reason: None,
             read_only: false,
             binary: false,
+            scratch: false,
         }
     }
 
@@ -178,6 +197,7 @@ impl BufferMetadata {
             lsp_disabled_reason: None,
             read_only: false,
             binary: false,
+            scratch: false,
         }
     }
 
@@ -225,6 +245,7 @@ impl BufferMetadata {
             lsp_disabled_reason: Some("Virtual buffer".to_string()),
             read_only,
             binary: false,
+            scratch: false,
         }
     }
 
@@ -235,13 +256,26 @@ impl BufferMetadata {
     }
 }
 
+/// A recorded keyboard macro
+#[derive(Debug, Clone, Default)]
+pub(super) struct RecordedMacro {
+    /// The keys pressed, replayed through the normal key dispatch
+    pub keys: Vec<(crossterm::event::KeyCode, crossterm::event::KeyModifiers)>,
+    /// The actions the keys ran while recording (shown by "Show Macro")
+    pub actions: Vec<Action>,
+}
+
 /// State for macro recording
 #[derive(Debug, Clone)]
 pub(super) struct MacroRecordingState {
     /// The register key for this macro
     pub key: char,
-    /// Actions recorded so far
-    pub actions: Vec<Action>,
+    /// Keys and actions recorded so far
+    pub recorded: RecordedMacro,
+    /// Lengths of `recorded.keys` and `recorded.actions` before the key that
+    /// opened the prompt that is still open (dropped if that prompt stops
+    /// the recording, e.g. "Stop Recording" in the command palette)
+    pub prompt_start: Option<(usize, usize)>,
 }
 
 /// LSP progress information
@@ -332,8 +366,10 @@ pub(super) struct MouseState {
 pub(super) struct CachedLayout {
     /// File explorer area (if visible)
     pub file_explorer_area: Option<Rect>,
-    /// Editor content area (excluding file explorer)
+    /// Editor content area (excluding file explorer and plugin panels)
     pub editor_content_area: Option<Rect>,
+    /// Plugin panel areas, borders included (panel_id, rect)
+    pub panel_areas: Vec<(String, Rect)>,
     /// Individual split areas with their scrollbar areas and thumb positions
     /// (split_id, buffer_id, content_rect, scrollbar_rect, thumb_start, thumb_end)
     pub split_areas: Vec<(SplitId, BufferId, Rect, Rect, usize, usize)>,
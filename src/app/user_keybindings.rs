@@ -0,0 +1,227 @@
+//! The user's key bindings file in the editor
+//!
+//! Bindings from `~/.config/fresh/keybindings.toml` (see
+//! `input::keybindings_file`) are loaded at startup on top of the active
+//! keymap and kept, so switching keymaps doesn't drop them.
+//!
+//! "Bind Key" adds one: the keys pressed in its prompt are captured as they
+//! are (plain Enter accepts, plain Backspace takes the last key back and plain
+//! Esc cancels), then a command is picked. The binding applies right away,
+//! and can be saved to the file.
+
+use super::confirm_dialog::ConfirmAction;
+use super::Editor;
+use crate::config::Keybinding;
+use crate::input::commands::Suggestion;
+use crate::input::keybindings::{KeyContext, KeybindingResolver};
+use crate::input::keybindings_file::{self, KEYBINDINGS_FILE};
+use crate::view::confirm_dialog::{ConfirmChoice, ConfirmDialog, DialogButton};
+use crate::view::prompt::{Prompt, PromptType};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+
+/// Context "Bind Key" binds in
+const BIND_KEY_CONTEXT: KeyContext = KeyContext::Normal;
+
+impl Editor {
+    /// Load the user's key bindings file
+    ///
+    /// Returns the number of bindings loaded. Invalid entries are skipped and
+    /// returned together as the error, after the valid ones are applied.
+    pub fn load_user_keybindings(&mut self) -> Result<usize, String> {
+        let Some(path) = keybindings_file::keybindings_path() else {
+            return Ok(0);
+        };
+        let loaded = keybindings_file::load_keybindings(&path)?;
+
+        self.user_keybindings = loaded.bindings;
+        self.keybindings.load_user_bindings(&self.user_keybindings);
+
+        if loaded.errors.is_empty() {
+            Ok(self.user_keybindings.len())
+        } else {
+            Err(format!(
+                "{}: {}",
+                KEYBINDINGS_FILE,
+                loaded.errors.join("; ")
+            ))
+        }
+    }
+
+    /// Open the "Bind Key" prompt, which captures the chord to bind
+    pub(super) fn start_bind_key_prompt(&mut self) {
+        self.prompt = Some(Prompt::new(
+            "Press the keys to bind (Enter to accept): ".to_string(),
+            PromptType::BindKey,
+        ));
+    }
+
+    /// Handle a key pressed in the "Bind Key" prompt
+    pub(super) fn capture_bind_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+        match (code, modifiers) {
+            (KeyCode::Enter, KeyModifiers::NONE) => {
+                let chord = prompt.input.clone();
+                self.prompt = None;
+                self.accept_bind_key_chord(chord);
+            }
+            (KeyCode::Esc, KeyModifiers::NONE) => self.cancel_prompt(),
+            (KeyCode::Backspace, KeyModifiers::NONE) => {
+                let len = prompt.input.rfind(' ').unwrap_or(0);
+                prompt.input.truncate(len);
+                prompt.cursor_pos = prompt.input.len();
+            }
+            _ => match KeybindingResolver::key_string(code, modifiers) {
+                Some(key) => {
+                    if !prompt.input.is_empty() {
+                        prompt.input.push(' ');
+                    }
+                    prompt.input.push_str(&key);
+                    prompt.cursor_pos = prompt.input.len();
+                }
+                None => self.set_status_warning("That key can't be bound".to_string()),
+            },
+        }
+    }
+
+    /// Ask for the command to bind the captured `chord` to
+    pub(super) fn accept_bind_key_chord(&mut self, chord: String) {
+        if chord.is_empty() {
+            self.set_status_message("No keys pressed".to_string());
+            return;
+        }
+
+        let mut commands = self.command_registry.read().unwrap().get_all();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        let suggestions = commands
+            .into_iter()
+            .map(|command| Suggestion {
+                text: command.name.clone(),
+                description: Some(command.description),
+                value: Some(command.name),
+                disabled: false,
+                keybinding: None,
+                source: None,
+                match_indices: Vec::new(),
+                actions: Vec::new(),
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            format!("Bind {} to: ", chord),
+            PromptType::BindKeyCommand { chord },
+            suggestions,
+        ));
+    }
+
+    /// Bind `chord` to the command named `name` for this session, then offer
+    /// to save it to the key bindings file
+    pub(super) fn bind_key_to_command(&mut self, chord: &str, name: &str) {
+        let action = self
+            .command_registry
+            .read()
+            .unwrap()
+            .get_all()
+            .into_iter()
+            .find(|command| command.name == name)
+            .map(|command| command.action);
+        let Some(action) = action else {
+            self.set_status_error(format!("Unknown command '{}'", name));
+            return;
+        };
+        let Some((action_name, args)) = action.to_name_and_args() else {
+            self.set_status_error(format!("'{}' can't be bound to a key", name));
+            return;
+        };
+
+        let binding = match keybindings_file::parse_binding(
+            chord,
+            action_name.to_string(),
+            args.clone(),
+            BIND_KEY_CONTEXT,
+        ) {
+            Ok(binding) => binding,
+            Err(e) => {
+                self.set_status_error(format!("Can't bind {}: {}", chord, e));
+                return;
+            }
+        };
+        // The chord's earlier binding would otherwise show as a conflict
+        let keys = binding_keys(&binding);
+        self.user_keybindings
+            .retain(|b| b.when != binding.when || binding_keys(b) != keys);
+        self.user_keybindings.push(binding);
+        self.keybindings.load_user_bindings(&self.user_keybindings);
+
+        let dialog = ConfirmDialog::new(
+            "Bind Key",
+            format!(
+                "{} now runs '{}'. Save it to {}?",
+                chord, name, KEYBINDINGS_FILE
+            ),
+            vec![
+                DialogButton::new("Save", 's', ConfirmChoice::Yes),
+                DialogButton::new("This Session Only", 't', ConfirmChoice::No),
+            ],
+        );
+        self.show_confirm_dialog(
+            dialog,
+            ConfirmAction::SaveKeybinding {
+                chord: chord.to_string(),
+                action: action_name.to_string(),
+                args,
+            },
+        );
+    }
+
+    /// Add a binding made with "Bind Key" to the key bindings file
+    pub(super) fn save_keybinding(
+        &mut self,
+        chord: &str,
+        action: &str,
+        args: &HashMap<String, serde_json::Value>,
+    ) {
+        let Some(path) = keybindings_file::keybindings_path() else {
+            self.set_status_error("No config directory to save key bindings in".to_string());
+            return;
+        };
+        let result = match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(e.to_string()),
+        }
+        .and_then(|content| {
+            keybindings_file::set_binding(&content, BIND_KEY_CONTEXT, chord, action, args)
+        })
+        .and_then(|content| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(&path, content).map_err(|e| e.to_string())
+        });
+
+        match result {
+            Ok(()) => self.set_status_message(format!("Saved {} to {}", chord, path.display())),
+            Err(e) => self.set_status_error(format!("Failed to save {}: {}", KEYBINDINGS_FILE, e)),
+        }
+    }
+}
+
+/// The keys of a binding, for comparing bindings
+fn binding_keys(binding: &Keybinding) -> Vec<Option<(KeyCode, KeyModifiers)>> {
+    let press = |key: &str, modifiers: &[String]| {
+        let mut parts = modifiers.to_vec();
+        parts.push(key.to_string());
+        KeybindingResolver::parse_key_string(&parts.join("+"))
+    };
+    if binding.keys.is_empty() {
+        vec![press(&binding.key, &binding.modifiers)]
+    } else {
+        binding
+            .keys
+            .iter()
+            .map(|key| press(&key.key, &key.modifiers))
+            .collect()
+    }
+}
@@ -0,0 +1,289 @@
+//! `${name}` variables of the active buffer, and the features that expand them
+//!
+//! Variables are expanded when they are used: in snippets ("Insert Snippet"),
+//! file templates (when a new file is opened), project task commands and the
+//! `editor.status_format` status bar layout.
+//!
+//! | Variable | Value |
+//! |---|---|
+//! | `file`, `relative_file` | Path of the buffer's file (absolute, or relative to the workspace) |
+//! | `filename`, `file_stem`, `extension`, `dir` | Parts of that path |
+//! | `workspace` | The working directory |
+//! | `line`, `column` | Cursor position (1-based) |
+//! | `selection`, `clipboard` | Selected text, clipboard content |
+//! | `modified` | ` [+]` when the buffer has unsaved changes |
+//! | `date`, `date:FORMAT`, `time`, `env:NAME` | See [`builtin_variable`] |
+//!
+//! Variables from the `variables` configuration are available too.
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::Event;
+use crate::primitives::variables::{builtin_variable, expand_command_variables, expand_variables};
+use crate::view::prompt::{Prompt, PromptType};
+
+impl Editor {
+    /// Expand the `${...}` variables in `template` for the active buffer
+    pub fn expand_variables(&mut self, template: &str) -> String {
+        expand_variables(template, |name| self.variable_value(name))
+    }
+
+    /// Expand the `${...}` variables in the shell command `template`, each
+    /// value quoted as a single word
+    pub fn expand_command_variables(&mut self, template: &str) -> String {
+        expand_command_variables(template, |name| self.variable_value(name))
+    }
+
+    /// Value of a built-in or user-defined variable
+    fn variable_value(&mut self, name: &str) -> Option<String> {
+        if let Some(value) = self.editor_variable(name) {
+            return Some(value);
+        }
+        let user_value = self.config.variables.get(name)?.clone();
+        // User variables can use the built-in ones (but not each other)
+        Some(expand_variables(&user_value, |inner| {
+            self.editor_variable(inner)
+        }))
+    }
+
+    /// Value of a built-in variable
+    fn editor_variable(&mut self, name: &str) -> Option<String> {
+        let path = self
+            .active_state()
+            .buffer
+            .file_path()
+            .map(std::path::Path::to_path_buf);
+        let path_part = |part: Option<&std::ffi::OsStr>| {
+            part.map(|part| part.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        let value = match name {
+            "file" => path.map(|p| p.display().to_string()).unwrap_or_default(),
+            "relative_file" => path
+                .map(|p| {
+                    p.strip_prefix(&self.working_dir)
+                        .unwrap_or(p.as_path())
+                        .display()
+                        .to_string()
+                })
+                .unwrap_or_default(),
+            "filename" => path_part(path.as_deref().and_then(|p| p.file_name())),
+            "file_stem" => path_part(path.as_deref().and_then(|p| p.file_stem())),
+            "extension" => path_part(path.as_deref().and_then(|p| p.extension())),
+            "dir" => path
+                .as_deref()
+                .and_then(|p| p.parent())
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            "workspace" => self.working_dir.display().to_string(),
+            "line" | "column" => {
+                let estimated_line_length = self.config.editor.estimated_line_length;
+                let state = self.active_state_mut();
+                let position = state.cursors.primary().position;
+                if name == "line" {
+                    (state.buffer.get_line_number(position) + 1).to_string()
+                } else {
+                    let line_start = state
+                        .buffer
+                        .line_iterator(position, estimated_line_length)
+                        .current_position();
+                    (position - line_start + 1).to_string()
+                }
+            }
+            "selection" => {
+                let state = self.active_state_mut();
+                match state.cursors.primary().selection_range() {
+                    Some(range) => state.get_text_range(range.start, range.end),
+                    None => String::new(),
+                }
+            }
+            "clipboard" => self.clipboard.paste().unwrap_or_default(),
+            "modified" => {
+                if self.active_state().buffer.is_modified() {
+                    " [+]".to_string()
+                } else {
+                    String::new()
+                }
+            }
+            _ => return builtin_variable(name),
+        };
+        Some(value)
+    }
+
+    /// Prompt for a snippet from the `snippets` configuration
+    pub(super) fn start_insert_snippet_prompt(&mut self) {
+        if self.config.snippets.is_empty() {
+            self.set_status_message("No snippets configured".to_string());
+            return;
+        }
+
+        let mut names: Vec<&String> = self.config.snippets.keys().collect();
+        names.sort();
+        let suggestions: Vec<Suggestion> = names
+            .into_iter()
+            .map(|name| Suggestion {
+                text: name.clone(),
+                description: self.config.snippets[name]
+                    .lines()
+                    .next()
+                    .map(|line| line.trim().to_string()),
+                value: Some(name.clone()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+                match_indices: Vec::new(),
+                actions: Vec::new(),
+            })
+            .collect();
+
+        self.prompt = Some(Prompt::with_suggestions(
+            "Insert snippet: ".to_string(),
+            PromptType::InsertSnippet,
+            suggestions,
+        ));
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.selected_suggestion = Some(0);
+        }
+    }
+
+    /// Insert a snippet at the cursor, replacing the selection
+    pub(super) fn insert_snippet(&mut self, name: &str) {
+        let Some(body) = self.config.snippets.get(name).cloned() else {
+            self.set_status_error(format!("Unknown snippet '{}'", name));
+            return;
+        };
+        if self.is_editing_disabled() {
+            self.set_status_message("Editing disabled in this buffer".to_string());
+            return;
+        }
+
+        // Expanded before the selection is replaced, so ${selection} can wrap it
+        let text = self.expand_variables(&body);
+        let state = self.active_state_mut();
+        let cursor_id = state.cursors.primary_id();
+        let mut events = Vec::new();
+        let position = match state.cursors.primary().selection_range() {
+            Some(range) => {
+                let deleted_text = state.get_text_range(range.start, range.end);
+                events.push(Event::Delete {
+                    range: range.clone(),
+                    deleted_text,
+                    cursor_id,
+                });
+                range.start
+            }
+            None => state.cursors.primary().position,
+        };
+        events.push(Event::Insert {
+            position,
+            text,
+            cursor_id,
+        });
+
+        // One undo step, with or without a replaced selection
+        let batch = Event::Batch {
+            events,
+            description: format!("Insert snippet '{}'", name),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+        self.set_status_message(format!("Inserted snippet '{}'", name));
+    }
+
+    /// Fill a newly created buffer with the template for its file extension
+    pub(super) fn apply_file_template(&mut self) {
+        let template = self
+            .active_state()
+            .buffer
+            .file_path()
+            .and_then(|path| path.extension())
+            .and_then(|ext| {
+                self.config
+                    .file_templates
+                    .get(ext.to_string_lossy().as_ref())
+            })
+            .cloned();
+        let Some(template) = template else {
+            return;
+        };
+
+        let content = self.expand_variables(&template);
+        let state = self.active_state_mut();
+        if !state.buffer.is_empty() {
+            return;
+        }
+        state.buffer.insert(0, &content);
+        state.cursors.primary_mut().position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expand_buffer_variables() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "first\nsecond line\n").unwrap();
+
+        let mut config = Config::default();
+        config
+            .variables
+            .insert("title".to_string(), "${file_stem} by me".to_string());
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+        editor.open_file(&path).unwrap();
+        editor.active_state_mut().cursors.primary_mut().position = 9;
+
+        assert_eq!(
+            editor.expand_variables("${filename} ${extension} ${line}:${column}"),
+            "notes.md md 2:4"
+        );
+        assert_eq!(editor.expand_variables("# ${title}"), "# notes by me");
+        assert_eq!(editor.expand_variables("${nope}"), "${nope}");
+    }
+
+    #[test]
+    fn test_snippet_replacing_selection_is_one_undo() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("page.html");
+        std::fs::write(&path, "hello").unwrap();
+
+        let mut config = Config::default();
+        config
+            .snippets
+            .insert("bold".to_string(), "<b>${selection}</b>".to_string());
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+        editor.open_file(&path).unwrap();
+        let cursor = editor.active_state_mut().cursors.primary_mut();
+        cursor.anchor = Some(0);
+        cursor.position = 5;
+
+        editor.insert_snippet("bold");
+        let content = editor.active_state().buffer.to_string().unwrap();
+        assert_eq!(content, "<b>hello</b>");
+
+        editor
+            .handle_action(crate::input::keybindings::Action::Undo)
+            .unwrap();
+        let content = editor.active_state().buffer.to_string().unwrap();
+        assert_eq!(content, "hello");
+    }
+
+    #[test]
+    fn test_new_file_uses_template() {
+        let dir = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config
+            .file_templates
+            .insert("sh".to_string(), "#!/bin/sh\n# ${filename}\n".to_string());
+        let mut editor = Editor::new(config, 80, 24).unwrap();
+        editor.open_file(&dir.path().join("build.sh")).unwrap();
+
+        let content = editor.active_state().buffer.to_string().unwrap();
+        assert_eq!(content, "#!/bin/sh\n# build.sh\n");
+    }
+}
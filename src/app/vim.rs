@@ -0,0 +1,122 @@
+//! Vim-style modal editing in the editor
+//!
+//! The modes, counts and operators live in `input::vim`; this feeds it keys
+//! and runs the actions it produces.
+
+use super::Editor;
+use crate::input::keybindings::{Action, KeyContext, KeyContextStack};
+use crate::input::vim::{VimCommand, VimMode};
+use crate::model::event::Event;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+impl Editor {
+    /// Whether keys in the buffer go through the vim layer
+    fn vim_active(&self, contexts: &KeyContextStack) -> bool {
+        self.config.editor.vim_mode
+            && matches!(
+                contexts.top(),
+                KeyContext::Normal | KeyContext::VimNormal | KeyContext::VimVisual
+            )
+    }
+
+    /// Give a key to the vim layer (mode switches, counts and operators).
+    /// Returns whether it was handled; other keys resolve through the key
+    /// bindings and are passed to [`Editor::handle_vim_action`].
+    pub(super) fn handle_vim_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        contexts: &KeyContextStack,
+    ) -> std::io::Result<bool> {
+        if !self.vim_active(contexts) {
+            return Ok(false);
+        }
+        match self.vim.key(code, modifiers) {
+            Some(command) => {
+                self.run_vim_command(command)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Run an action bound in a vim context, applying the pending count and
+    /// operator
+    pub(super) fn handle_vim_action(&mut self, action: Action) -> std::io::Result<()> {
+        let command = self.vim.command(action);
+        self.run_vim_command(command)
+    }
+
+    fn run_vim_command(&mut self, command: VimCommand) -> std::io::Result<()> {
+        if command.append {
+            let state = self.active_state();
+            let position = state.cursors.primary().position;
+            let at_line_end = position >= state.buffer.len()
+                || matches!(
+                    state.buffer.slice_bytes(position..position + 1).first(),
+                    Some(b'\n' | b'\r')
+                );
+            if !at_line_end {
+                self.handle_action(Action::MoveRight)?;
+            }
+        }
+
+        for action in command.actions {
+            self.handle_action(action)?;
+        }
+
+        if command.collapse_to_start {
+            let events: Vec<Event> = self
+                .active_state()
+                .cursors
+                .iter()
+                .flat_map(|(cursor_id, cursor)| {
+                    let start = cursor
+                        .selection_range()
+                        .map_or(cursor.position, |range| range.start);
+                    [
+                        Event::ClearAnchor { cursor_id },
+                        Event::MoveCursor {
+                            cursor_id,
+                            old_position: cursor.position,
+                            new_position: start,
+                            old_anchor: None,
+                            new_anchor: None,
+                            old_sticky_column: cursor.sticky_column,
+                            new_sticky_column: 0,
+                        },
+                    ]
+                })
+                .collect();
+            let batch = Event::Batch {
+                events,
+                description: "Collapse selection".to_string(),
+            };
+            self.active_event_log_mut().append(batch.clone());
+            self.apply_event_to_active_buffer(&batch);
+        }
+        Ok(())
+    }
+
+    /// Turn modal editing on or off
+    pub(super) fn toggle_vim_mode(&mut self) {
+        self.config.editor.vim_mode = !self.config.editor.vim_mode;
+        self.vim.set_mode(VimMode::Normal);
+
+        // The vim contexts' bindings are only loaded while vim mode is on
+        self.keybindings = crate::input::keybindings::KeybindingResolver::new(&self.config);
+        let _ = self.bind_project_tasks();
+        self.keybindings.load_user_bindings(&self.user_keybindings);
+
+        self.set_status_message(if self.config.editor.vim_mode {
+            "Vim mode enabled".to_string()
+        } else {
+            "Vim mode disabled".to_string()
+        });
+    }
+
+    /// Mode shown in the status bar while vim mode is on
+    pub(super) fn vim_status(&self) -> Option<String> {
+        self.config.editor.vim_mode.then(|| self.vim.status())
+    }
+}
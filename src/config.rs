@@ -1,3 +1,5 @@
+use crate::input::fuzzy::{FuzzyMatcher, SmithWatermanMatcher, SubsequenceMatcher};
+use crate::input::keybindings::KeymapPreset;
 use crate::services::lsp::client::LspServerConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -36,6 +38,45 @@ pub struct Config {
 
     #[serde(default)]
     pub menu: MenuConfig,
+
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+
+    /// User-defined `${name}` variables for snippets, file templates, tasks
+    /// and the status bar format. Values can use the built-in variables.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+
+    /// Snippets for "Insert Snippet", by name (`${...}` variables are expanded)
+    #[serde(default)]
+    pub snippets: HashMap<String, String>,
+
+    /// Initial content of new files, by file extension (`${...}` variables
+    /// are expanded)
+    #[serde(default)]
+    pub file_templates: HashMap<String, String>,
+
+    /// Palette commands that run a sequence of other commands
+    #[serde(default)]
+    pub composite_commands: Vec<CompositeCommand>,
+
+    /// Which project files the file finder, project search and the file
+    /// explorer consider (added to by the project's `.fresh/project.toml`)
+    #[serde(default)]
+    pub files: FilesConfig,
+
+    /// Rules giving files a language, tried in order before the built-in
+    /// detection by file name, `#!` line and content
+    #[serde(default)]
+    pub file_types: Vec<FileTypeRule>,
+
+    /// Caps that keep the editor responsive on pathological input
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    /// What clicking the gutter beside the text does
+    #[serde(default)]
+    pub gutter: GutterConfig,
 }
 
 fn default_keybinding_map_name() -> String {
@@ -70,6 +111,41 @@ pub struct EditorConfig {
     #[serde(default = "default_true")]
     pub line_wrap: bool,
 
+    /// Keep the cursor's line vertically centered while typing and moving
+    #[serde(default = "default_false")]
+    pub typewriter_scrolling: bool,
+
+    /// Let the last line scroll up to the top of the view
+    #[serde(default = "default_false")]
+    pub scroll_past_end: bool,
+
+    /// What focus mode keeps undimmed around the cursor
+    #[serde(default)]
+    pub focus_mode_unit: FocusUnit,
+
+    /// Width "Reflow Paragraph" and hard wrapping while typing wrap text to
+    #[serde(default = "default_hard_wrap_column")]
+    pub hard_wrap_column: usize,
+
+    /// Break lines of prose and comments at `hard_wrap_column` while typing
+    #[serde(default = "default_false")]
+    pub auto_hard_wrap: bool,
+
+    /// Rulers, lint and the staged diff when editing a git commit message
+    /// (`COMMIT_EDITMSG`)
+    #[serde(default = "default_true")]
+    pub commit_mode: bool,
+
+    /// Underline words of a commit message missing from the system word
+    /// list
+    #[serde(default = "default_true")]
+    pub commit_spell_check: bool,
+
+    /// Load the variables the project's `.envrc` exports (through direnv,
+    /// once allowed) at startup, for the commands the editor runs
+    #[serde(default = "default_false")]
+    pub load_envrc: bool,
+
     #[serde(default = "default_highlight_timeout")]
     pub highlight_timeout_ms: u64,
 
@@ -106,6 +182,152 @@ pub struct EditorConfig {
     /// Set to 0 to disable periodic auto-save (manual recovery only).
     #[serde(default = "default_auto_save_interval")]
     pub auto_save_interval_secs: u32,
+
+    /// Maximum number of rows in the prompt suggestions popup
+    /// The popup shrinks to fit when there are fewer suggestions.
+    #[serde(default = "default_suggestions_max_height")]
+    pub suggestions_max_height: usize,
+
+    /// Maximum width of the prompt suggestions popup in columns
+    /// The popup shrinks to fit its content. 0 means the full terminal width.
+    #[serde(default)]
+    pub suggestions_max_width: usize,
+
+    /// Show a preview pane next to the theme, tab and file pickers
+    /// The preview shows the effect or content of the highlighted item.
+    #[serde(default = "default_true")]
+    pub suggestion_preview: bool,
+
+    /// Delay in milliseconds before the suggestion preview updates while navigating
+    /// Avoids loading every item when scrolling quickly through a list.
+    #[serde(default = "default_suggestion_preview_delay")]
+    pub suggestion_preview_delay_ms: u64,
+
+    /// How long info messages stay in the status bar, in milliseconds
+    /// 0 keeps them until they are replaced or dismissed.
+    #[serde(default = "default_status_message_duration")]
+    pub status_message_duration_ms: u64,
+
+    /// How long warnings stay in the status bar, in milliseconds
+    /// 0 keeps them until they are dismissed.
+    #[serde(default = "default_status_warning_duration")]
+    pub status_warning_duration_ms: u64,
+
+    /// How long errors stay in the status bar, in milliseconds
+    /// 0 (the default) keeps them until they are dismissed with Escape.
+    #[serde(default)]
+    pub status_error_duration_ms: u64,
+
+    /// Minutes between session snapshots (open files, layout, cursors)
+    /// Snapshots can be restored after a crash with "Restore Session from Snapshot".
+    /// 0 disables snapshots (the session is still saved on exit).
+    #[serde(default = "default_session_snapshot_interval")]
+    pub session_snapshot_interval_mins: u64,
+
+    /// Number of session snapshots kept per project (older ones are deleted)
+    #[serde(default = "default_session_snapshot_count")]
+    pub session_snapshot_count: usize,
+
+    /// Seconds "Evaluate Selection" waits for the evaluator before stopping it
+    #[serde(default = "default_evaluation_timeout")]
+    pub evaluation_timeout_secs: u64,
+
+    /// Left part of the status bar, with `${...}` variables
+    /// (e.g. "${relative_file}${modified} | Ln ${line}, Col ${column}").
+    /// Unset uses the built-in layout.
+    #[serde(default)]
+    pub status_format: Option<String>,
+
+    /// Programs used by "Open in External Program", by file extension
+    /// (e.g. `{"pdf": "zathura", "svg": "inkscape --with-gui"}`). The file's
+    /// path is appended to the command. Other files use the system opener
+    /// (`xdg-open`, or `open` on macOS).
+    #[serde(default)]
+    pub external_programs: HashMap<String, String>,
+
+    /// Opening a file that is already open switches to its buffer.
+    /// When false a second buffer is opened (with a warning that edits may conflict).
+    #[serde(default = "default_true")]
+    pub reuse_open_buffers: bool,
+
+    /// Take an advisory lock on opened files (a `.name.fresh-lock` file next to
+    /// them) and check for locks of other Fresh instances and Vim swap files.
+    /// A file locked elsewhere opens read-only, with the option to steal the lock.
+    #[serde(default)]
+    pub lock_files: bool,
+
+    /// Index the definitions in the project's files at startup, for
+    /// "Go To Symbol in Workspace" and go-to-definition without LSP.
+    /// The index is kept in the data directory and updated incrementally.
+    #[serde(default = "default_true")]
+    pub symbol_index: bool,
+
+    /// Keep a trigram index of the project's files so live grep answers
+    /// plain-text queries without reading every file. Built in the
+    /// background at startup, kept in the data directory and updated
+    /// incrementally.
+    #[serde(default)]
+    pub search_index: bool,
+
+    /// How long a key chord (e.g. Ctrl+K Ctrl+W) waits for its next key, in
+    /// milliseconds. The pending keys are then dropped. 0 waits forever.
+    #[serde(default = "default_chord_timeout")]
+    pub chord_timeout_ms: u64,
+
+    /// While a key chord is pending, show a panel above the status bar that
+    /// lists the keys that can come next and what they run
+    #[serde(default = "default_true")]
+    pub which_key: bool,
+
+    /// Modal (vim-style) editing: keys in the buffer start in normal mode,
+    /// where they run motions and operators, and `i` enters insert mode.
+    /// Motion keys are bound in the `vim_normal` and `vim_visual` contexts.
+    #[serde(default)]
+    pub vim_mode: bool,
+
+    /// What pasting nothing but paths to existing files does (terminals paste
+    /// the paths of files dropped onto them): "ask", "open" or "insert"
+    #[serde(default)]
+    pub dropped_files: DroppedFiles,
+
+    /// Typing a bracket or quote while text is selected wraps the selection
+    /// in the pair instead of replacing it
+    #[serde(default = "default_true")]
+    pub auto_surround: bool,
+
+    /// How the command palette, file finder, buffer picker and symbol search
+    /// match what is typed: "subsequence" or "smith_waterman" (fzf-style)
+    #[serde(default)]
+    pub fuzzy_matcher: FuzzyMatcherKind,
+
+    /// Draw the editor in this many rows below the shell's output instead of
+    /// on the alternate screen, keeping the scrollback in view (0 = full
+    /// screen)
+    #[serde(default)]
+    pub inline_rows: u16,
+
+    /// Regexes for the secrets "Redact Secrets" masks on screen; with a
+    /// capture group only the group is masked
+    #[serde(default = "default_redact_patterns")]
+    pub redact_patterns: Vec<String>,
+
+    /// Lock the screen after this many minutes without input (0 = never)
+    #[serde(default)]
+    pub idle_lock_minutes: u64,
+
+    /// Passphrase to type to unlock the screen; without one any key unlocks
+    #[serde(default)]
+    pub idle_lock_passphrase: Option<String>,
+
+    /// Append a record of every save (path, time, user, size and SHA-256
+    /// before and after) to an audit file
+    #[serde(default)]
+    pub audit_log: bool,
+
+    /// Audit file to append to (`save_audit.jsonl` in the data directory by
+    /// default)
+    #[serde(default)]
+    pub audit_log_path: Option<std::path::PathBuf>,
 }
 
 fn default_tab_size() -> usize {
@@ -133,6 +355,10 @@ fn default_scroll_offset() -> usize {
     3
 }
 
+fn default_hard_wrap_column() -> usize {
+    80
+}
+
 fn default_highlight_timeout() -> u64 {
     5
 }
@@ -149,6 +375,54 @@ fn default_auto_save_interval() -> u32 {
     2 // Auto-save every 2 seconds for fast recovery
 }
 
+fn default_suggestions_max_height() -> usize {
+    10
+}
+
+fn default_suggestion_preview_delay() -> u64 {
+    150
+}
+
+fn default_status_message_duration() -> u64 {
+    5000
+}
+
+fn default_status_warning_duration() -> u64 {
+    10000
+}
+
+fn default_session_snapshot_interval() -> u64 {
+    5
+}
+
+fn default_session_snapshot_count() -> usize {
+    10
+}
+
+fn default_evaluation_timeout() -> u64 {
+    10
+}
+
+fn default_chord_timeout() -> u64 {
+    2000
+}
+
+fn default_redact_patterns() -> Vec<String> {
+    [
+        // Values of password=, api_key: and the like
+        r#"(?i)(?:password|passwd|pwd|secret|token|api[_-]?key|access[_-]?key|private[_-]?key)\w*["']?\s*[:=]\s*["']?([^\s"',;]+)"#,
+        // AWS access key IDs
+        r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+        // GitHub, Slack and OpenAI-style tokens
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+        r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+        r"\bsk-[A-Za-z0-9_-]{20,}",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
 impl Default for EditorConfig {
     fn default() -> Self {
         Self {
@@ -159,6 +433,14 @@ impl Default for EditorConfig {
             scroll_offset: default_scroll_offset(),
             syntax_highlighting: true,
             line_wrap: true,
+            typewriter_scrolling: false,
+            scroll_past_end: false,
+            focus_mode_unit: FocusUnit::default(),
+            hard_wrap_column: default_hard_wrap_column(),
+            auto_hard_wrap: false,
+            commit_mode: true,
+            commit_spell_check: true,
+            load_envrc: false,
             highlight_timeout_ms: default_highlight_timeout(),
             snapshot_interval: default_snapshot_interval(),
             large_file_threshold_bytes: default_large_file_threshold(),
@@ -166,6 +448,69 @@ impl Default for EditorConfig {
             enable_inlay_hints: true,
             recovery_enabled: true,
             auto_save_interval_secs: default_auto_save_interval(),
+            suggestions_max_height: default_suggestions_max_height(),
+            suggestions_max_width: 0,
+            suggestion_preview: true,
+            suggestion_preview_delay_ms: default_suggestion_preview_delay(),
+            status_message_duration_ms: default_status_message_duration(),
+            status_warning_duration_ms: default_status_warning_duration(),
+            status_error_duration_ms: 0,
+            session_snapshot_interval_mins: default_session_snapshot_interval(),
+            session_snapshot_count: default_session_snapshot_count(),
+            evaluation_timeout_secs: default_evaluation_timeout(),
+            status_format: None,
+            external_programs: HashMap::new(),
+            reuse_open_buffers: true,
+            lock_files: false,
+            symbol_index: true,
+            search_index: false,
+            chord_timeout_ms: default_chord_timeout(),
+            which_key: true,
+            vim_mode: false,
+            dropped_files: DroppedFiles::default(),
+            auto_surround: true,
+            fuzzy_matcher: FuzzyMatcherKind::default(),
+            inline_rows: 0,
+            redact_patterns: default_redact_patterns(),
+            idle_lock_minutes: 0,
+            idle_lock_passphrase: None,
+            audit_log: false,
+            audit_log_path: None,
+        }
+    }
+}
+
+/// How a paste of dropped file paths is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DroppedFiles {
+    /// Ask whether to open the files or insert the paths
+    #[default]
+    Ask,
+    /// Open the files
+    Open,
+    /// Insert the paths as text, like any other paste
+    Insert,
+}
+
+/// Which fuzzy matcher filters pickers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FuzzyMatcherKind {
+    /// Fast subsequence matching
+    #[default]
+    Subsequence,
+    /// Smith-Waterman alignment scored like fzf, which ranks better
+    #[serde(alias = "fzf")]
+    SmithWaterman,
+}
+
+impl FuzzyMatcherKind {
+    /// The matcher of this kind
+    pub fn matcher(self) -> &'static dyn FuzzyMatcher {
+        match self {
+            Self::Subsequence => &SubsequenceMatcher,
+            Self::SmithWaterman => &SmithWatermanMatcher,
         }
     }
 }
@@ -210,6 +555,37 @@ impl Default for FileExplorerConfig {
     }
 }
 
+/// Screen reader support
+///
+/// When enabled, the editor announces the line under the cursor, mode changes
+/// and the selected completion as short text messages (see
+/// `services::accessibility`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    /// Whether to send announcements
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Where announcements are sent
+    #[serde(default)]
+    pub backend: AnnouncementBackend,
+
+    /// File or named pipe announcements are appended to (pipe backend)
+    #[serde(default)]
+    pub pipe_path: Option<std::path::PathBuf>,
+}
+
+/// How announcements reach the screen reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementBackend {
+    /// One line per announcement, appended to `pipe_path`
+    Pipe,
+    /// OSC 9 escape sequences written to the terminal
+    #[default]
+    Osc,
+}
+
 /// A single key in a sequence
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyPress {
@@ -220,6 +596,141 @@ pub struct KeyPress {
     pub modifiers: Vec<String>,
 }
 
+/// A command that runs other commands in order (a simple macro)
+///
+/// The chain stops at the first command that is unknown or fails.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompositeCommand {
+    /// Name shown in the command palette
+    pub name: String,
+
+    /// Description shown next to the name
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Names of the commands to run, as shown in the palette
+    pub commands: Vec<String>,
+}
+
+/// A rule giving matching files a language (see `primitives::file_type`)
+///
+/// A file matches if its name matches `name` or its first line matches
+/// `first_line`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileTypeRule {
+    /// Glob on the file name, with `*` and `?` (e.g. `"*.conf"`)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Regex on the first line (e.g. `"^#!.*\\bcrystal\\b"`)
+    #[serde(default)]
+    pub first_line: Option<String>,
+
+    /// File extension of the language to use (e.g. `"sh"`)
+    pub language: String,
+}
+
+/// Glob sets selecting project files, in .gitignore syntax
+///
+/// Patterns are relative to the project root: `target/` matches a directory
+/// anywhere, `/build` only at the root and `*.min.js` any such file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FilesConfig {
+    /// Only files matching one of these are considered (all files if empty)
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Files and directories matching these are left out
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Soft limits for pathological input (huge match counts, floods of
+/// diagnostics, multi-megabyte lines)
+///
+/// Past a limit the rest is left out and the editor says how much, instead of
+/// freezing while it processes everything.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LimitsConfig {
+    /// Matches a search collects; the count is shown as "N+" past this
+    #[serde(default = "default_max_search_matches")]
+    pub max_search_matches: usize,
+
+    /// Search match highlights drawn for one screen
+    #[serde(default = "default_max_highlights_per_frame")]
+    pub max_highlights_per_frame: usize,
+
+    /// Diagnostics shown per buffer (the most severe are kept)
+    #[serde(default = "default_max_diagnostics")]
+    pub max_diagnostics: usize,
+
+    /// Lines longer than this many bytes are treated as pathological: they
+    /// aren't passed to plugins' `lines_changed` hook, and only this much of
+    /// them is scanned for search highlights or quoted in match lists
+    #[serde(default = "default_long_line_bytes")]
+    pub long_line_bytes: usize,
+}
+
+fn default_max_search_matches() -> usize {
+    100_000
+}
+
+fn default_max_highlights_per_frame() -> usize {
+    2_000
+}
+
+fn default_max_diagnostics() -> usize {
+    1_000
+}
+
+fn default_long_line_bytes() -> usize {
+    10_000
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_search_matches: default_max_search_matches(),
+            max_highlights_per_frame: default_max_highlights_per_frame(),
+            max_diagnostics: default_max_diagnostics(),
+            long_line_bytes: default_long_line_bytes(),
+        }
+    }
+}
+
+/// Actions run by clicking a gutter column
+///
+/// Each is an action name (as in keybindings) or a plugin command; empty does
+/// nothing. The cursor moves to the clicked line first. Plugins can take over
+/// a column with `setGutterClickAction`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GutterConfig {
+    /// Clicking a line number
+    #[serde(default = "default_line_number_click")]
+    pub line_number_click: String,
+
+    /// Clicking the sign column (diagnostic, git and breakpoint markers)
+    #[serde(default = "default_sign_click")]
+    pub sign_click: String,
+}
+
+fn default_line_number_click() -> String {
+    "select_line".to_string()
+}
+
+fn default_sign_click() -> String {
+    "show_diagnostics".to_string()
+}
+
+impl Default for GutterConfig {
+    fn default() -> Self {
+        Self {
+            line_number_click: default_line_number_click(),
+            sign_click: default_sign_click(),
+        }
+    }
+}
+
 /// Keybinding definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Keybinding {
@@ -285,6 +796,11 @@ pub struct LanguageConfig {
     /// If specified, this grammar will be used when highlighter is "textmate"
     #[serde(default)]
     pub textmate_grammar: Option<std::path::PathBuf>,
+
+    /// Shell command used by "Evaluate Selection" (e.g. "python3 -")
+    /// The selected code is passed on stdin and stdout is the result.
+    #[serde(default)]
+    pub evaluator: Option<String>,
 }
 
 /// Preference for which syntax highlighting backend to use
@@ -302,6 +818,17 @@ pub enum HighlighterPreference {
     TextMate,
 }
 
+/// Text focus mode keeps undimmed around the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusUnit {
+    /// The lines between the blank lines around the cursor
+    #[default]
+    Paragraph,
+    /// The sentence around the cursor, within its paragraph
+    Sentence,
+}
+
 /// Menu bar configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MenuConfig {
@@ -350,6 +877,15 @@ impl Default for Config {
             languages: Self::default_languages(),
             lsp: Self::default_lsp_config(),
             menu: MenuConfig::default(),
+            accessibility: AccessibilityConfig::default(),
+            variables: HashMap::new(),
+            snippets: HashMap::new(),
+            file_templates: HashMap::new(),
+            composite_commands: Vec::new(),
+            files: FilesConfig::default(),
+            file_types: Vec::new(),
+            limits: LimitsConfig::default(),
+            gutter: GutterConfig::default(),
         }
     }
 }
@@ -387,15 +923,27 @@ impl Config {
     /// Load a built-in keymap from embedded JSON
     fn load_builtin_keymap(name: &str) -> Option<KeymapConfig> {
         let json_content = match name {
-            "default" => include_str!("../keymaps/default.json"),
-            "emacs" => include_str!("../keymaps/emacs.json"),
-            "vscode" => include_str!("../keymaps/vscode.json"),
-            _ => return None,
+            // Not a preset: layered on top of the active map while vim mode is on
+            "vim" => include_str!("../keymaps/vim.json"),
+            _ => KeymapPreset::find(name)?.source(),
         };
 
         serde_json::from_str(json_content).ok()
     }
 
+    /// Whether `name` is a built-in preset or a map defined in `keybinding_maps`
+    pub fn has_keymap(&self, name: &str) -> bool {
+        KeymapPreset::find(name).is_some() || self.keybinding_maps.contains_key(name)
+    }
+
+    /// A keymap's own definition (user-defined or built-in), without inheritance
+    pub fn keymap(&self, name: &str) -> Option<KeymapConfig> {
+        self.keybinding_maps
+            .get(name)
+            .cloned()
+            .or_else(|| Self::load_builtin_keymap(name))
+    }
+
     /// Resolve a keymap with inheritance
     /// Returns all bindings from the keymap and its parent chain
     pub fn resolve_keymap(&self, map_name: &str) -> Vec<Keybinding> {
@@ -419,14 +967,7 @@ impl Config {
         }
         visited.insert(map_name.to_string());
 
-        // Try to load the keymap (user-defined or built-in)
-        let keymap = self
-            .keybinding_maps
-            .get(map_name)
-            .cloned()
-            .or_else(|| Self::load_builtin_keymap(map_name));
-
-        let Some(keymap) = keymap else {
+        let Some(keymap) = self.keymap(map_name) else {
             return Vec::new();
         };
 
@@ -455,6 +996,7 @@ impl Config {
                 auto_indent: true,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
+                evaluator: None,
             },
         );
 
@@ -467,6 +1009,7 @@ impl Config {
                 auto_indent: true,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
+                evaluator: Some("node -".to_string()),
             },
         );
 
@@ -479,6 +1022,7 @@ impl Config {
                 auto_indent: true,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
+                evaluator: None,
             },
         );
 
@@ -491,6 +1035,7 @@ impl Config {
                 auto_indent: true,
                 highlighter: HighlighterPreference::Auto,
                 textmate_grammar: None,
+                evaluator: Some("python3 -".to_string()),
             },
         );
 
@@ -0,0 +1,110 @@
+//! Serialized form of actions
+//!
+//! Macros are shown, and scripts send actions, by the names keymaps use
+//! (`{"action": "insert_char", "args": {"char": "a"}}`) rather than by the
+//! `Action` enum's variant names, so they keep working when the enum changes.
+//! Lists of actions carry a format version.
+
+use crate::input::keybindings::Action;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Version of the format written by this build
+pub const ACTION_FORMAT_VERSION: u32 = 1;
+
+/// An action with its arguments, as written to disk or sent by scripts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedAction {
+    /// Action name, as used in keymaps (e.g., "move_left")
+    pub action: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub args: HashMap<String, Value>,
+}
+
+impl SerializedAction {
+    /// Serialized form of an action (None for `Action::None`)
+    pub fn from_action(action: &Action) -> Option<Self> {
+        let (name, args) = action.to_name_and_args()?;
+        Some(Self {
+            action: name.to_string(),
+            args,
+        })
+    }
+
+    pub fn to_action(&self) -> Result<Action, String> {
+        Action::from_str(&self.action, &self.args).ok_or_else(|| {
+            if self.args.is_empty() {
+                format!("unknown action '{}'", self.action)
+            } else {
+                format!("unknown action or bad arguments for '{}'", self.action)
+            }
+        })
+    }
+}
+
+/// A list of actions, such as the ones a macro ran
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionRecording {
+    pub version: u32,
+    pub actions: Vec<SerializedAction>,
+}
+
+impl ActionRecording {
+    pub fn new(actions: &[Action]) -> Self {
+        Self {
+            version: ACTION_FORMAT_VERSION,
+            actions: actions
+                .iter()
+                .filter_map(SerializedAction::from_action)
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("actions always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let actions = vec![
+            Action::InsertChar('é'),
+            Action::MoveWordRight,
+            Action::MenuOpen("File".to_string()),
+            Action::SwitchKeybindingMap("emacs".to_string()),
+            Action::PluginAction("git_grep".to_string()),
+            Action::None,
+            Action::ScrollTabsLeft,
+        ];
+
+        let json = ActionRecording::new(&actions).to_json();
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"insert_char\""));
+        assert!(!json.contains("InsertChar"));
+
+        let recording: ActionRecording = serde_json::from_str(&json).unwrap();
+        let parsed: Vec<Action> = recording
+            .actions
+            .iter()
+            .map(|a| a.to_action().unwrap())
+            .collect();
+        let mut expected = actions.clone();
+        expected.retain(|a| *a != Action::None);
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_to_action() {
+        let action: SerializedAction =
+            serde_json::from_str(r#"{"action": "set_bookmark", "args": {"char": "a"}}"#).unwrap();
+        assert_eq!(action.to_action().unwrap(), Action::SetBookmark('a'));
+
+        let unknown: SerializedAction = serde_json::from_str(r#"{"action": "fly"}"#).unwrap();
+        assert_eq!(unknown.to_action().unwrap_err(), "unknown action 'fly'");
+    }
+}
@@ -181,6 +181,113 @@ fn max_cursor_position(buffer: &Buffer) -> usize {
     buffer.len()
 }
 
+/// The closing character auto-close and auto-surround pair with `ch`
+pub fn closing_pair(ch: char) -> Option<char> {
+    match ch {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '`' => Some('`'),
+        _ => None,
+    }
+}
+
+/// Remember the closers that the events of an `InsertChar(ch)` auto-inserted,
+/// so that typing them steps over them instead of adding another
+pub fn track_auto_closed(state: &mut EditorState, ch: char, events: &[Event]) {
+    // Forget closers that have since been replaced
+    state.auto_closed.retain(|&pos| {
+        state
+            .buffer
+            .slice_bytes(pos..pos + 1)
+            .first()
+            .is_some_and(|b| b")]}\"'`".contains(b))
+    });
+
+    let Some(close) = closing_pair(ch) else {
+        return;
+    };
+    let pair = format!("{}{}", ch, close);
+    if !events
+        .iter()
+        .any(|event| matches!(event, Event::Insert { text, .. } if *text == pair))
+    {
+        return;
+    }
+
+    // A cursor that auto-closed sits between the pair it inserted
+    let len = state.buffer.len();
+    let closers: Vec<usize> = state
+        .cursors
+        .iter()
+        .map(|(_, cursor)| cursor.position)
+        .filter(|&pos| {
+            pos > 0 && pos < len && state.buffer.slice_bytes(pos - 1..pos + 1) == pair.as_bytes()
+        })
+        .collect();
+    for pos in closers {
+        if !state.auto_closed.contains(&pos) {
+            state.auto_closed.push(pos);
+        }
+    }
+}
+
+/// Events that wrap every cursor's selection in `open` and its closing
+/// character, keeping the selected text selected
+///
+/// Returns None (so the character is typed as usual) unless `open` starts a
+/// pair and every cursor has a non-empty, non-block selection.
+pub fn surround_selection_events(state: &EditorState, open: char) -> Option<Vec<Event>> {
+    let close = closing_pair(open)?;
+    let mut selections = state
+        .cursors
+        .iter()
+        .map(|(cursor_id, cursor)| {
+            let range = cursor.selection_range().filter(|r| !r.is_empty())?;
+            (cursor.selection_mode == SelectionMode::Normal).then_some((
+                cursor_id,
+                range,
+                cursor.position,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    // Later selections first, so earlier insertions don't shift them
+    selections.sort_by_key(|(_, range, _)| std::cmp::Reverse(range.start));
+
+    let mut events = Vec::new();
+    for (cursor_id, range, position) in selections {
+        events.push(Event::Insert {
+            position: range.end,
+            text: close.to_string(),
+            cursor_id,
+        });
+        events.push(Event::Insert {
+            position: range.start,
+            text: open.to_string(),
+            cursor_id,
+        });
+        // Select the text inside the pair, with the cursor on the same side
+        let (inner_start, inner_end) = (range.start + 1, range.end + 1);
+        let (new_position, new_anchor) = if position == range.end {
+            (inner_end, inner_start)
+        } else {
+            (inner_start, inner_end)
+        };
+        events.push(Event::MoveCursor {
+            cursor_id,
+            old_position: inner_start,
+            new_position,
+            old_anchor: None,
+            new_anchor: Some(new_anchor),
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+    }
+    Some(events)
+}
+
 /// Convert an action into a sequence of events that can be applied to the editor state
 ///
 /// # Parameters
@@ -218,19 +325,7 @@ pub fn action_to_events(
             let is_closing_delimiter = matches!(ch, '}' | ')' | ']');
 
             // Check if this is an opening bracket that should auto-close
-            let auto_close_char = if auto_indent {
-                match ch {
-                    '(' => Some(')'),
-                    '[' => Some(']'),
-                    '{' => Some('}'),
-                    '"' => Some('"'),
-                    '\'' => Some('\''),
-                    '`' => Some('`'),
-                    _ => None,
-                }
-            } else {
-                None
-            };
+            let auto_close_char = if auto_indent { closing_pair(ch) } else { None };
 
             // First, collect just the cursor IDs and positions (without borrowing state)
             let cursor_info: Vec<_> = cursor_vec
@@ -309,6 +404,7 @@ pub fn action_to_events(
             ) in cursor_data
             {
                 // First, delete the selection if there is one
+                let had_selection = selection.is_some();
                 if let (Some(range), Some(text)) = (selection, deleted_text) {
                     events.push(Event::Delete {
                         range,
@@ -319,12 +415,17 @@ pub fn action_to_events(
 
                 // Then handle insertion
                 // Skip-over logic for closing brackets/quotes
-                // When the user types a closing bracket and the cursor is right before that bracket,
-                // just move the cursor forward instead of inserting a duplicate
+                // When the user types a closing bracket and the cursor is right before one that
+                // auto-close inserted, just move the cursor forward instead of inserting a duplicate
                 // BUT: if line has only spaces before cursor, perform dedent first (for auto-paired braces)
-                if auto_indent && matches!(ch, ')' | ']' | '}' | '"' | '\'' | '`') {
+                if auto_indent
+                    && !had_selection
+                    && matches!(ch, ')' | ']' | '}' | '"' | '\'' | '`')
+                    && state.auto_closed.contains(&insert_position)
+                {
                     if let Some(next_byte) = char_after {
                         if next_byte == ch as u8 {
+                            state.auto_closed.retain(|&pos| pos != insert_position);
                             // Check if we need to dedent before skipping over
                             // This handles the case where auto-pair inserted the closing delimiter
                             // and we pressed Enter to get indent, then typed the closing delimiter
@@ -829,6 +930,27 @@ pub fn action_to_events(
             }
         }
 
+        Action::MoveWordEnd => {
+            for (cursor_id, cursor) in state.cursors.iter() {
+                let new_pos = find_word_end(&state.buffer, cursor.position);
+                // Preserve anchor if deselect_on_move is false (Emacs mark mode)
+                let new_anchor = if cursor.deselect_on_move {
+                    None
+                } else {
+                    cursor.anchor
+                };
+                events.push(Event::MoveCursor {
+                    cursor_id,
+                    old_position: cursor.position,
+                    new_position: new_pos,
+                    old_anchor: cursor.anchor,
+                    new_anchor,
+                    old_sticky_column: cursor.sticky_column,
+                    new_sticky_column: 0, // Reset sticky column
+                });
+            }
+        }
+
         Action::MoveDocumentStart => {
             for (cursor_id, cursor) in state.cursors.iter() {
                 // Preserve anchor if deselect_on_move is false (Emacs mark mode)
@@ -1151,6 +1273,22 @@ pub fn action_to_events(
             }
         }
 
+        Action::SelectWordEnd => {
+            for (cursor_id, cursor) in state.cursors.iter() {
+                let new_pos = find_word_end(&state.buffer, cursor.position);
+                let anchor = cursor.anchor.unwrap_or(cursor.position);
+                events.push(Event::MoveCursor {
+                    cursor_id,
+                    old_position: cursor.position,
+                    new_position: new_pos,
+                    old_anchor: cursor.anchor,
+                    new_anchor: Some(anchor),
+                    old_sticky_column: cursor.sticky_column,
+                    new_sticky_column: 0, // Reset sticky column
+                });
+            }
+        }
+
         Action::SelectDocumentStart => {
             for (cursor_id, cursor) in state.cursors.iter() {
                 let anchor = cursor.anchor.unwrap_or(cursor.position);
@@ -1586,12 +1724,33 @@ pub fn action_to_events(
         | Action::SaveAs
         | Action::Open
         | Action::New
+        | Action::NewScratchBuffer
         | Action::Close
+        | Action::CloseAllBuffers
+        | Action::CloseOtherBuffers
+        | Action::CloseBuffersToRight
+        | Action::CloseSavedBuffers
         | Action::GotoLine
         | Action::NextBuffer
         | Action::PrevBuffer
         | Action::SwitchToPreviousTab
         | Action::SwitchToTabByName
+        | Action::NextRecentBuffer
+        | Action::PrevRecentBuffer
+        | Action::ReopenClosedBuffer
+        | Action::ShowSaveHistory
+        | Action::DiffBuffers
+        | Action::ToggleScrollLock
+        | Action::ZoomPane
+        | Action::ToggleFocusMode
+        | Action::ReflowParagraph
+        | Action::ReflowToColumns
+        | Action::ToggleAutoHardWrap
+        | Action::CreateBufferGroup
+        | Action::MoveToBufferGroup
+        | Action::SwitchBufferGroup
+        | Action::MoveTabLeft
+        | Action::MoveTabRight
         | Action::NavigateBack
         | Action::NavigateForward
         | Action::SplitHorizontal
@@ -1601,13 +1760,20 @@ pub fn action_to_events(
         | Action::PrevSplit
         | Action::Copy
         | Action::Cut
+        | Action::KillLine
         | Action::Paste
         | Action::AddCursorNextMatch
         | Action::AddCursorAbove
         | Action::AddCursorBelow
         | Action::CommandPalette
         | Action::ShowHelp
+        | Action::DismissStatusMessage
         | Action::ToggleLineWrap
+        | Action::ToggleRedactSecrets
+        | Action::LockScreen
+        | Action::BufferStatistics
+        | Action::ReloadEnvironment
+        | Action::ToggleVimMode
         | Action::ToggleComposeMode
         | Action::SetComposeWidth
         | Action::IncreaseSplitSize
@@ -1617,7 +1783,10 @@ pub fn action_to_events(
         | Action::GoToMatchingBracket
         | Action::JumpToNextError
         | Action::JumpToPreviousError
+        | Action::ShowDiagnostics
         | Action::ShowKeyboardShortcuts
+        | Action::ShowKeybindings
+        | Action::BindKey
         | Action::SmartHome
         | Action::IndentSelection
         | Action::DedentSelection
@@ -1630,6 +1799,8 @@ pub fn action_to_events(
         | Action::ToggleSearchWholeWord
         | Action::ToggleSearchRegex
         | Action::ToggleSearchConfirmEach
+        | Action::ToggleSearchInSelection
+        | Action::PromptToggleHiddenFiles
         | Action::StartMacroRecording
         | Action::StopMacroRecording
         | Action::PlayMacro(_)
@@ -1653,6 +1824,8 @@ pub fn action_to_events(
         | Action::PromptSelectNext
         | Action::PromptPageUp
         | Action::PromptPageDown
+        | Action::PromptSelectFirst
+        | Action::PromptSelectLast
         | Action::PromptAcceptSuggestion
         | Action::PromptMoveWordLeft
         | Action::PromptMoveWordRight
@@ -1687,6 +1860,8 @@ pub fn action_to_events(
         | Action::FileExplorerExpand
         | Action::FileExplorerCollapse
         | Action::FileExplorerOpen
+        | Action::FileExplorerOpenRight
+        | Action::FileExplorerOpenBelow
         | Action::FileExplorerRefresh
         | Action::FileExplorerNewFile
         | Action::FileExplorerNewDirectory
@@ -1694,8 +1869,12 @@ pub fn action_to_events(
         | Action::FileExplorerRename
         | Action::FileExplorerToggleHidden
         | Action::FileExplorerToggleGitignored
+        | Action::FileExplorerFilter
+        | Action::OpenInExternalProgram
         | Action::LspCompletion
         | Action::LspGotoDefinition
+        | Action::LspGotoDefinitionRight
+        | Action::LspGotoDefinitionBelow
         | Action::LspReferences
         | Action::LspRename
         | Action::LspHover
@@ -1706,11 +1885,16 @@ pub fn action_to_events(
         | Action::ToggleInlayHints
         | Action::ToggleLineNumbers
         | Action::ToggleMouseCapture
+        | Action::TogglePerformanceOverlay
         | Action::DumpConfig
+        | Action::RestoreSessionSnapshot
+        | Action::SaveSessionAs
+        | Action::SwitchSession
         | Action::Search
         | Action::FindInSelection
         | Action::FindNext
         | Action::FindPrevious
+        | Action::SearchToQuickfix
         | Action::Replace
         | Action::QueryReplace
         | Action::MenuActivate
@@ -1722,11 +1906,27 @@ pub fn action_to_events(
         | Action::MenuExecute
         | Action::MenuOpen(_)
         | Action::SwitchKeybindingMap(_)
+        | Action::RunProjectTask(_)
+        | Action::ReloadProjectTasks
+        | Action::RunCommandSequence(_)
+        | Action::CancelRunningTask
+        | Action::GoToWorkspaceSymbol
+        | Action::RebuildSymbolIndex
+        | Action::RebuildSearchIndex
+        | Action::SelectTextObject(_, _)
         | Action::PluginAction(_)
         | Action::None
         | Action::ScrollTabsLeft
         | Action::ScrollTabsRight
         | Action::SelectTheme
+        | Action::ToggleSelectionMode
+        | Action::LintTheme
+        | Action::ExportHtml
+        | Action::ExportAnsi
+        | Action::EvaluateSelection
+        | Action::EvaluateSelectionInsert
+        | Action::InsertSnippet
+        | Action::RepeatLastPaletteQuery
         | Action::Revert
         | Action::ToggleAutoRevert => return None,
 
@@ -3018,4 +3218,98 @@ mod tests {
 
         assert_eq!(state.buffer.to_string().unwrap(), "(bc)");
     }
+
+    /// Type a character the way the editor does, tracking auto-closed pairs
+    fn type_char(state: &mut EditorState, ch: char) {
+        let events = action_to_events(state, Action::InsertChar(ch), 4, true, 80).unwrap();
+        for event in &events {
+            state.apply(event);
+        }
+        track_auto_closed(state, ch, &events);
+    }
+
+    #[test]
+    fn test_skip_over_only_auto_closed() {
+        let mut state =
+            EditorState::new(80, 24, crate::config::LARGE_FILE_THRESHOLD_BYTES as usize);
+
+        // "(" auto-closes; typing "x)" steps over the inserted ")"
+        type_char(&mut state, '(');
+        type_char(&mut state, 'x');
+        type_char(&mut state, ')');
+        assert_eq!(state.buffer.to_string().unwrap(), "(x)");
+        assert_eq!(state.cursors.primary().position, 3);
+        assert!(state.auto_closed.is_empty());
+
+        // A ")" that was already there is typed as usual
+        state.apply(&Event::MoveCursor {
+            cursor_id: CursorId(0),
+            old_position: 3,
+            new_position: 2,
+            old_anchor: None,
+            new_anchor: None,
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+        type_char(&mut state, ')');
+        assert_eq!(state.buffer.to_string().unwrap(), "(x))");
+    }
+
+    #[test]
+    fn test_auto_closed_follows_edits() {
+        let mut state =
+            EditorState::new(80, 24, crate::config::LARGE_FILE_THRESHOLD_BYTES as usize);
+
+        // Nested pairs: both closers are tracked as text goes in before them
+        type_char(&mut state, '[');
+        type_char(&mut state, '(');
+        type_char(&mut state, 'a');
+        assert_eq!(state.buffer.to_string().unwrap(), "[(a)]");
+        type_char(&mut state, ')');
+        type_char(&mut state, ']');
+        assert_eq!(state.buffer.to_string().unwrap(), "[(a)]");
+        assert_eq!(state.cursors.primary().position, 5);
+    }
+
+    #[test]
+    fn test_surround_selection() {
+        let mut state =
+            EditorState::new(80, 24, crate::config::LARGE_FILE_THRESHOLD_BYTES as usize);
+        state.apply(&Event::Insert {
+            position: 0,
+            text: "say hi".to_string(),
+            cursor_id: CursorId(0),
+        });
+        // Select "hi", cursor at the end
+        state.apply(&Event::MoveCursor {
+            cursor_id: CursorId(0),
+            old_position: 6,
+            new_position: 6,
+            old_anchor: None,
+            new_anchor: Some(4),
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+
+        let events = surround_selection_events(&state, '"').unwrap();
+        for event in &events {
+            state.apply(event);
+        }
+        assert_eq!(state.buffer.to_string().unwrap(), "say \"hi\"");
+        let cursor = state.cursors.primary();
+        assert_eq!((cursor.anchor, cursor.position), (Some(5), 7));
+
+        // Only openers surround, and only selections
+        assert!(surround_selection_events(&state, 'x').is_none());
+        state.apply(&Event::MoveCursor {
+            cursor_id: CursorId(0),
+            old_position: 7,
+            new_position: 7,
+            old_anchor: Some(5),
+            new_anchor: None,
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+        assert!(surround_selection_events(&state, '(').is_none());
+    }
 }
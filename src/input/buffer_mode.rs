@@ -2,8 +2,10 @@
 //!
 //! This module implements an Emacs-style major mode system where each buffer
 //! can have its own mode that defines keybindings. Modes support inheritance,
-//! allowing derived modes to extend parent modes.
+//! allowing derived modes to extend parent modes. Plugins can also install
+//! bindings on a single buffer, which take precedence over its mode.
 
+use crate::model::event::BufferId;
 use crossterm::event::{KeyCode, KeyModifiers};
 use std::collections::HashMap;
 
@@ -197,6 +199,72 @@ impl Default for ModeRegistry {
     }
 }
 
+/// Keybindings installed on individual buffers
+///
+/// Resolved before the buffer's mode and the global keymap. Bindings are
+/// dropped when their buffer is closed.
+#[derive(Debug, Clone, Default)]
+pub struct BufferKeybindings {
+    buffers: HashMap<BufferId, HashMap<(KeyCode, KeyModifiers), String>>,
+}
+
+impl BufferKeybindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind a key in a buffer, replacing any existing binding for that key
+    pub fn bind(
+        &mut self,
+        buffer_id: BufferId,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        command: impl Into<String>,
+    ) {
+        self.buffers
+            .entry(buffer_id)
+            .or_default()
+            .insert((code, modifiers), command.into());
+    }
+
+    /// Remove a key's binding from a buffer
+    ///
+    /// Returns true if the key was bound.
+    pub fn unbind(&mut self, buffer_id: BufferId, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        let Some(bindings) = self.buffers.get_mut(&buffer_id) else {
+            return false;
+        };
+        let removed = bindings.remove(&(code, modifiers)).is_some();
+        if bindings.is_empty() {
+            self.buffers.remove(&buffer_id);
+        }
+        removed
+    }
+
+    /// Remove all bindings of a buffer
+    pub fn clear(&mut self, buffer_id: BufferId) {
+        self.buffers.remove(&buffer_id);
+    }
+
+    /// Command bound to a key in a buffer
+    pub fn resolve(
+        &self,
+        buffer_id: BufferId,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<&str> {
+        self.buffers
+            .get(&buffer_id)?
+            .get(&(code, modifiers))
+            .map(String::as_str)
+    }
+
+    /// Whether a buffer has any bindings of its own
+    pub fn has_bindings(&self, buffer_id: BufferId) -> bool {
+        self.buffers.contains_key(&buffer_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,4 +373,45 @@ mod tests {
             Some(&"child:action".to_string())
         );
     }
+
+    #[test]
+    fn test_buffer_keybindings() {
+        let mut bindings = BufferKeybindings::new();
+        let preview = BufferId(1);
+        let other = BufferId(2);
+
+        bindings.bind(
+            preview,
+            KeyCode::Char('q'),
+            KeyModifiers::NONE,
+            "close-buffer",
+        );
+        bindings.bind(preview, KeyCode::Enter, KeyModifiers::NONE, "results_goto");
+        assert_eq!(
+            bindings.resolve(preview, KeyCode::Char('q'), KeyModifiers::NONE),
+            Some("close-buffer")
+        );
+        assert_eq!(
+            bindings.resolve(other, KeyCode::Char('q'), KeyModifiers::NONE),
+            None
+        );
+
+        // Rebinding replaces the command
+        bindings.bind(preview, KeyCode::Enter, KeyModifiers::NONE, "results_open");
+        assert_eq!(
+            bindings.resolve(preview, KeyCode::Enter, KeyModifiers::NONE),
+            Some("results_open")
+        );
+
+        assert!(bindings.unbind(preview, KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!bindings.unbind(preview, KeyCode::Enter, KeyModifiers::NONE));
+        assert!(bindings.has_bindings(preview));
+
+        bindings.clear(preview);
+        assert!(!bindings.has_bindings(preview));
+        assert_eq!(
+            bindings.resolve(preview, KeyCode::Char('q'), KeyModifiers::NONE),
+            None
+        );
+    }
 }
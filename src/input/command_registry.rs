@@ -4,9 +4,10 @@
 //! while maintaining the built-in command set.
 
 use crate::input::commands::{get_all_commands, Command, Suggestion};
-use crate::input::fuzzy::fuzzy_match;
-use crate::input::keybindings::Action;
-use crate::input::keybindings::KeyContext;
+use crate::input::frecency::{now_secs, FrecencyStore};
+use crate::input::fuzzy::FuzzyMatcher;
+use crate::input::keybindings::KeyContextStack;
+use crate::input::when_clause::WhenContext;
 use std::sync::{Arc, RwLock};
 
 /// Registry for managing editor commands
@@ -21,20 +22,32 @@ pub struct CommandRegistry {
     plugin_commands: Arc<RwLock<Vec<Command>>>,
 
     /// Command usage history (most recent first)
-    /// Used to sort command palette suggestions by recency
+    /// Used to break ties between equally ranked suggestions
     command_history: Vec<String>,
+
+    /// How often and how recently each command was used
+    /// Used to rank command palette suggestions (persisted across sessions)
+    frecency: FrecencyStore,
 }
 
 impl CommandRegistry {
     /// Maximum number of commands to keep in history
     const MAX_HISTORY_SIZE: usize = 50;
 
+    /// Number of recently used commands listed first when the palette opens
+    const RECENT_COMMANDS: usize = 5;
+
+    /// Largest boost frecency gives a fuzzy match score
+    /// (as much as a match at the start of the name)
+    const MAX_FRECENCY_BONUS: u32 = 48;
+
     /// Create a new command registry with built-in commands
     pub fn new() -> Self {
         Self {
             builtin_commands: get_all_commands(),
             plugin_commands: Arc::new(RwLock::new(Vec::new())),
             command_history: Vec::new(),
+            frecency: FrecencyStore::new(),
         }
     }
 
@@ -53,6 +66,40 @@ impl CommandRegistry {
         if self.command_history.len() > Self::MAX_HISTORY_SIZE {
             self.command_history.truncate(Self::MAX_HISTORY_SIZE);
         }
+
+        self.frecency.record(command_name, now_secs());
+    }
+
+    /// Usage counts and times of commands (for saving to disk)
+    pub fn frecency(&self) -> &FrecencyStore {
+        &self.frecency
+    }
+
+    /// Replace the usage counts and times (after loading them from disk)
+    pub fn set_frecency(&mut self, frecency: FrecencyStore) {
+        self.frecency = frecency;
+    }
+
+    /// Move the most recently used commands to the front of `suggestions`
+    /// (most recent first), for the "Recently Used" section of the palette
+    ///
+    /// Disabled commands stay where they are. Returns how many were moved.
+    pub fn move_recent_to_front(&self, suggestions: &mut Vec<Suggestion>) -> usize {
+        let mut recent = Vec::new();
+        for name in &self.command_history {
+            if recent.len() == Self::RECENT_COMMANDS {
+                break;
+            }
+            if let Some(pos) = suggestions
+                .iter()
+                .position(|s| &s.text == name && !s.disabled)
+            {
+                recent.push(suggestions.remove(pos));
+            }
+        }
+        let count = recent.len();
+        suggestions.splice(0..0, recent);
+        count
     }
 
     /// Get the position of a command in history (0 = most recent)
@@ -99,60 +146,95 @@ impl CommandRegistry {
         all_commands
     }
 
+    /// Split palette input into a command that takes an argument and the
+    /// argument's text, e.g. "Go to Line 42" into "Go to Line" and "42"
+    ///
+    /// The name matches case-insensitively and must be followed by a space.
+    /// The argument may be empty ("Go to Line "), so completions can be shown
+    /// as soon as the name is accepted.
+    pub fn split_argument(&self, input: &str) -> Option<(Command, String)> {
+        self.get_all()
+            .into_iter()
+            .filter(|cmd| cmd.argument.is_some())
+            .filter(|cmd| {
+                input
+                    .get(..cmd.name.len())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&cmd.name))
+                    && input[cmd.name.len()..].starts_with(' ')
+            })
+            .max_by_key(|cmd| cmd.name.len())
+            .map(|cmd| {
+                let argument = input[cmd.name.len()..].trim().to_string();
+                (cmd, argument)
+            })
+    }
+
     /// Filter commands by fuzzy matching query with context awareness
     ///
-    /// When query is empty, commands are sorted by recency (most recently used first).
-    /// When query is not empty, commands are sorted by match quality (fzf-style scoring)
-    /// with recency as tiebreaker for equal scores.
-    /// Disabled commands always appear after enabled ones.
+    /// When query is empty, commands are sorted by frecency (most often and
+    /// recently used first). When query is not empty, commands are sorted by
+    /// match quality (fzf-style scoring) boosted by frecency, so a command used
+    /// all the time wins over a slightly better match. Recency breaks ties.
+    /// Disabled commands always appear after enabled ones. Commands are
+    /// available if they declare any of the contexts in the stack and their
+    /// when clause (if any) holds in `when`. `matcher` scores the names.
     pub fn filter(
         &self,
         query: &str,
-        current_context: KeyContext,
+        contexts: &KeyContextStack,
         keybinding_resolver: &crate::input::keybindings::KeybindingResolver,
-        selection_active: bool,
+        when: &WhenContext,
+        matcher: &dyn FuzzyMatcher,
     ) -> Vec<Suggestion> {
         let commands = self.get_all();
 
-        // Helper function to check if command is available in current context
+        // Helper function to check if command is available in the current contexts
         let is_available = |cmd: &Command| -> bool {
             // Empty contexts means available in all contexts
-            cmd.contexts.is_empty() || cmd.contexts.contains(&current_context)
+            (cmd.contexts.is_empty() || cmd.contexts.iter().any(|c| contexts.contains(*c)))
+                && cmd.when.as_ref().is_none_or(|clause| clause.evaluate(when))
         };
 
-        // Filter and convert to suggestions with history position and fuzzy score
+        let has_query = !query.is_empty();
+        let now = now_secs();
+
+        // Filter and convert to suggestions with history position and rank
+        // (fuzzy score plus frecency bonus, or frecency alone without a query)
         let mut suggestions: Vec<(Suggestion, Option<usize>, i32)> = commands
             .into_iter()
             .filter_map(|cmd| {
                 // Use fuzzy matching
-                let fuzzy_result = fuzzy_match(query, &cmd.name);
+                let fuzzy_result = matcher.fuzzy_match(query, &cmd.name);
                 if !fuzzy_result.matched {
                     return None;
                 }
 
-                let mut available = is_available(&cmd);
-                if cmd.action == Action::FindInSelection && !selection_active {
-                    available = false;
-                }
+                let available = is_available(&cmd);
                 let keybinding =
-                    keybinding_resolver.get_keybinding_for_action(&cmd.action, current_context);
+                    keybinding_resolver.get_keybinding_for_action_in_stack(&cmd.action, contexts);
                 let history_pos = self.history_position(&cmd.name);
-                let suggestion = Suggestion::with_source(
+                let mut suggestion = Suggestion::with_source(
                     cmd.name.clone(),
                     Some(cmd.description),
                     !available,
                     keybinding,
                     Some(cmd.source),
                 );
-                Some((suggestion, history_pos, fuzzy_result.score))
+                suggestion.match_indices = fuzzy_result.match_positions;
+                let frecency = self.frecency.score(&cmd.name, now);
+                let rank = if has_query {
+                    fuzzy_result.score + frecency.div_ceil(10).min(Self::MAX_FRECENCY_BONUS) as i32
+                } else {
+                    frecency.min(i32::MAX as u32) as i32
+                };
+                Some((suggestion, history_pos, rank))
             })
             .collect();
 
         // Sort by:
         // 1. Disabled status (enabled first)
-        // 2. Fuzzy match score (higher is better) - only when query is not empty
+        // 2. Rank (higher is better)
         // 3. History position (recent first, then never-used alphabetically)
-        let has_query = !query.is_empty();
         suggestions.sort_by(|(a, a_hist, a_score), (b, b_hist, b_score)| {
             // First sort by disabled status
             match a.disabled.cmp(&b.disabled) {
@@ -160,12 +242,10 @@ impl CommandRegistry {
                 other => return other,
             }
 
-            // When there's a query, sort by fuzzy score (higher is better)
-            if has_query {
-                match b_score.cmp(a_score) {
-                    std::cmp::Ordering::Equal => {}
-                    other => return other,
-                }
+            // Then by rank (higher is better)
+            match b_score.cmp(a_score) {
+                std::cmp::Ordering::Equal => {}
+                other => return other,
             }
 
             // Then sort by history position (lower = more recent = better)
@@ -219,7 +299,8 @@ impl Default for CommandRegistry {
 mod tests {
     use super::*;
     use crate::input::commands::CommandSource;
-    use crate::input::keybindings::Action;
+    use crate::input::fuzzy::SubsequenceMatcher;
+    use crate::input::keybindings::{Action, KeyContext};
 
     #[test]
     fn test_command_registry_creation() {
@@ -238,6 +319,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         };
 
         registry.register(custom_command.clone());
@@ -258,6 +341,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         };
 
         registry.register(custom_command);
@@ -277,6 +362,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         };
 
         let command2 = Command {
@@ -285,6 +372,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         };
 
         registry.register(command1);
@@ -307,6 +396,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         registry.register(Command {
@@ -315,6 +406,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         registry.register(Command {
@@ -323,6 +416,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         assert_eq!(registry.plugin_command_count(), 3);
@@ -349,9 +444,17 @@ mod tests {
             action: Action::None,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
-        let results = registry.filter("save", KeyContext::Normal, &keybindings, false);
+        let results = registry.filter(
+            "save",
+            &KeyContextStack::new(KeyContext::Normal),
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
         assert!(results.len() >= 2); // At least "Save File" + "Test Save"
 
         // Check that both built-in and custom commands appear
@@ -359,6 +462,27 @@ mod tests {
         assert!(names.iter().any(|n| n.contains("Save")));
     }
 
+    #[test]
+    fn test_filter_ranks_word_boundary_matches_first() {
+        use crate::config::Config;
+        use crate::input::keybindings::KeybindingResolver;
+
+        let registry = CommandRegistry::new();
+        let config = Config::default();
+        let keybindings = KeybindingResolver::new(&config);
+
+        let results = registry.filter(
+            "sf",
+            &KeyContextStack::new(KeyContext::Normal),
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
+        assert_eq!(results[0].text, "Save File");
+        // The matched chars are passed on for highlighting
+        assert_eq!(results[0].match_indices, vec![0, 5]);
+    }
+
     #[test]
     fn test_context_filtering() {
         use crate::config::Config;
@@ -374,6 +498,8 @@ mod tests {
             action: Action::None,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         registry.register(Command {
@@ -382,19 +508,87 @@ mod tests {
             action: Action::None,
             contexts: vec![KeyContext::Popup],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         // In normal context, "Popup Only" should be disabled
-        let results = registry.filter("", KeyContext::Normal, &keybindings, false);
+        let results = registry.filter(
+            "",
+            &KeyContextStack::new(KeyContext::Normal),
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
         let popup_only = results.iter().find(|s| s.text == "Popup Only");
         assert!(popup_only.is_some());
         assert!(popup_only.unwrap().disabled);
 
         // In popup context, "Normal Only" should be disabled
-        let results = registry.filter("", KeyContext::Popup, &keybindings, false);
+        let results = registry.filter(
+            "",
+            &KeyContextStack::new(KeyContext::Popup),
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
         let normal_only = results.iter().find(|s| s.text == "Normal Only");
         assert!(normal_only.is_some());
         assert!(normal_only.unwrap().disabled);
+
+        // With the popup open over the editor, both are available
+        let mut stack = KeyContextStack::new(KeyContext::Normal);
+        stack.push(KeyContext::Popup);
+        let results = registry.filter(
+            "",
+            &stack,
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
+        for name in ["Normal Only", "Popup Only"] {
+            let suggestion = results.iter().find(|s| s.text == name).unwrap();
+            assert!(!suggestion.disabled, "{} should be available", name);
+        }
+    }
+
+    #[test]
+    fn test_when_clause_availability() {
+        use crate::input::when_clause::WhenClause;
+
+        let registry = CommandRegistry::new();
+        let keybindings =
+            crate::input::keybindings::KeybindingResolver::new(&crate::config::Config::default());
+        registry.register(Command {
+            name: "Format Rust Selection".to_string(),
+            description: "".to_string(),
+            action: Action::None,
+            contexts: vec![],
+            source: CommandSource::Plugin("test".to_string()),
+            when: WhenClause::parse("has_selection && language == 'rust'").ok(),
+            argument: None,
+        });
+        let stack = KeyContextStack::new(KeyContext::Normal);
+        let is_disabled = |when: &WhenContext| {
+            let results = registry.filter("", &stack, &keybindings, when, &SubsequenceMatcher);
+            ["Format Rust Selection", "Find in Selection"].map(|name| {
+                results
+                    .iter()
+                    .find(|s| s.text == name)
+                    .map(|s| s.disabled)
+                    .unwrap()
+            })
+        };
+
+        let mut when = WhenContext::new();
+        when.set("language", "rust");
+        assert_eq!(is_disabled(&when), [true, true]);
+
+        when.set_flag("has_selection", true);
+        assert_eq!(is_disabled(&when), [false, false]);
+
+        when.set("language", "python");
+        assert_eq!(is_disabled(&when), [true, false]);
     }
 
     #[test]
@@ -408,6 +602,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         registry.register(Command {
@@ -416,6 +612,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         let all = registry.get_all();
@@ -438,6 +636,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         // Should now find the custom version
@@ -478,7 +678,13 @@ mod tests {
         registry.record_usage("Open File");
 
         // Filter with empty query should return history-sorted results
-        let results = registry.filter("", KeyContext::Normal, &keybindings, false);
+        let results = registry.filter(
+            "",
+            &KeyContextStack::new(KeyContext::Normal),
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
 
         // Find positions of our test commands in results
         let open_pos = results.iter().position(|s| s.text == "Open File").unwrap();
@@ -531,6 +737,8 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         registry.register(Command {
@@ -539,12 +747,20 @@ mod tests {
             action: Action::None,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         });
 
         // Use one built-in command
         registry.record_usage("Save File");
 
-        let results = registry.filter("", KeyContext::Normal, &keybindings, false);
+        let results = registry.filter(
+            "",
+            &KeyContextStack::new(KeyContext::Normal),
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
 
         let save_pos = results.iter().position(|s| s.text == "Save File").unwrap();
         let alpha_pos = results
@@ -612,4 +828,75 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_frecency_ranking() {
+        use crate::config::Config;
+        use crate::input::keybindings::KeybindingResolver;
+
+        let mut registry = CommandRegistry::new();
+        let config = Config::default();
+        let keybindings = KeybindingResolver::new(&config);
+        let contexts = KeyContextStack::new(KeyContext::Normal);
+
+        // "Save File" is used all the time, "Quit" was used last
+        for _ in 0..5 {
+            registry.record_usage("Save File");
+        }
+        registry.record_usage("Quit");
+
+        let results = registry.filter(
+            "",
+            &contexts,
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
+        assert_eq!(results[0].text, "Save File");
+        assert_eq!(results[1].text, "Quit");
+
+        // A frequently used command outranks a slightly better match
+        let results = registry.filter(
+            "s",
+            &contexts,
+            &keybindings,
+            &WhenContext::new(),
+            &SubsequenceMatcher,
+        );
+        assert_eq!(results[0].text, "Save File");
+    }
+
+    #[test]
+    fn test_split_argument() {
+        let registry = CommandRegistry::new();
+
+        let (cmd, argument) = registry.split_argument("go to line 42").unwrap();
+        assert_eq!(cmd.name, "Go to Line");
+        assert_eq!(argument, "42");
+
+        let (cmd, argument) = registry.split_argument("Select Theme ").unwrap();
+        assert_eq!(cmd.name, "Select Theme");
+        assert_eq!(argument, "");
+
+        // No space after the name, or a command without an argument
+        assert!(registry.split_argument("Go to Line").is_none());
+        assert!(registry.split_argument("Go to Line42").is_none());
+        assert!(registry.split_argument("Save File now").is_none());
+    }
+
+    #[test]
+    fn test_move_recent_to_front() {
+        let mut registry = CommandRegistry::new();
+        for name in ["Quit", "Save File", "Open File", "Quit"] {
+            registry.record_usage(name);
+        }
+
+        let mut suggestions: Vec<Suggestion> = ["Close Buffer", "Open File", "Quit", "Save File"]
+            .iter()
+            .map(|name| Suggestion::new(name.to_string()))
+            .collect();
+        assert_eq!(registry.move_recent_to_front(&mut suggestions), 3);
+        let names: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(names, ["Quit", "Open File", "Save File", "Close Buffer"]);
+    }
 }
@@ -1,14 +1,74 @@
 //! Command palette system for executing editor actions by name
 
-use crate::input::keybindings::{Action, KeyContext};
+use crate::input::fuzzy::fuzzy_match;
+use crate::input::keybindings::{
+    format_keybinding, Action, KeyContext, KeybindingResolver, KEYMAP_PRESETS,
+};
+use crate::input::when_clause::WhenClause;
+use crossterm::event::{KeyCode, KeyModifiers};
 
-/// Source of a command (builtin or from a plugin)
+/// Source of a command (builtin, from a plugin, or from the project)
 #[derive(Debug, Clone, PartialEq)]
 pub enum CommandSource {
     /// Built-in editor command
     Builtin,
     /// Command registered by a plugin (contains plugin filename without extension)
     Plugin(String),
+    /// Task defined by the project in `.fresh/tasks.toml`
+    Project,
+}
+
+/// Kind of value a command argument takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentType {
+    /// A positive whole number (e.g. a line number)
+    Number,
+    /// Any text
+    Text,
+}
+
+/// Where the command palette gets completions for an argument from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentCompleter {
+    /// No completions: any value of the argument's type
+    None,
+    /// Names of the available themes
+    Themes,
+    /// Names of the snippets in the configuration
+    Snippets,
+}
+
+/// An argument that can be typed after a command's name in the palette
+/// (e.g. "Go to Line 42")
+///
+/// Commands with an argument still work without one: they open their own
+/// prompt, which the argument would have answered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandArgument {
+    /// Name shown in the palette (e.g. "line")
+    pub name: String,
+    /// Kind of value the argument takes
+    pub arg_type: ArgumentType,
+    /// Source of completions for the argument
+    pub completer: ArgumentCompleter,
+}
+
+impl CommandArgument {
+    pub fn new(name: &str, arg_type: ArgumentType, completer: ArgumentCompleter) -> Self {
+        Self {
+            name: name.to_string(),
+            arg_type,
+            completer,
+        }
+    }
+
+    /// Check if `value` is a valid value of the argument's type
+    pub fn accepts(&self, value: &str) -> bool {
+        match self.arg_type {
+            ArgumentType::Number => value.parse::<usize>().is_ok_and(|n| n > 0),
+            ArgumentType::Text => !value.is_empty(),
+        }
+    }
 }
 
 /// A command that can be executed from the command palette
@@ -24,6 +84,51 @@ pub struct Command {
     pub contexts: Vec<KeyContext>,
     /// Source of the command (builtin or plugin)
     pub source: CommandSource,
+    /// Condition on editor state for the command to be available
+    /// (None = whenever its contexts match)
+    pub when: Option<WhenClause>,
+    /// Argument that can be typed after the name (None = takes no argument)
+    pub argument: Option<CommandArgument>,
+}
+
+/// Something a suggestion can do besides being confirmed, run with its own
+/// key while the suggestion is selected (e.g. Ctrl+W closes a buffer in the
+/// buffer picker)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestionAction {
+    /// Key that runs it, e.g. "ctrl+enter" (see
+    /// `KeybindingResolver::parse_key_string`)
+    pub key: String,
+    /// What it does, shown in the hint row below the suggestions
+    pub label: String,
+    /// Name passed to whatever handles it
+    pub id: String,
+    /// Leave the prompt open after running it (closed like a confirm otherwise)
+    pub keep_open: bool,
+}
+
+impl SuggestionAction {
+    pub fn new(key: &str, label: &str, id: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            label: label.to_string(),
+            id: id.to_string(),
+            keep_open: false,
+        }
+    }
+
+    /// Whether a key press runs this action
+    pub fn matches(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        KeybindingResolver::parse_key_string(&self.key) == Some((code, modifiers))
+    }
+
+    /// The key as shown to the user (e.g. "Ctrl+Enter")
+    pub fn key_label(&self) -> String {
+        match KeybindingResolver::parse_key_string(&self.key) {
+            Some((code, modifiers)) => format_keybinding(&code, &modifiers),
+            None => self.key.clone(),
+        }
+    }
 }
 
 /// A single suggestion item for autocomplete
@@ -41,6 +146,10 @@ pub struct Suggestion {
     pub keybinding: Option<String>,
     /// Source of the command (for command palette)
     pub source: Option<CommandSource>,
+    /// Char indices of `text` that matched the filter query (drawn in bold)
+    pub match_indices: Vec<usize>,
+    /// Secondary actions, listed below the suggestions while it is selected
+    pub actions: Vec<SuggestionAction>,
 }
 
 impl Suggestion {
@@ -52,6 +161,8 @@ impl Suggestion {
             disabled: false,
             keybinding: None,
             source: None,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
         }
     }
 
@@ -63,6 +174,8 @@ impl Suggestion {
             disabled: false,
             keybinding: None,
             source: None,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
         }
     }
 
@@ -78,6 +191,8 @@ impl Suggestion {
             disabled,
             keybinding: None,
             source: None,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
         }
     }
 
@@ -94,6 +209,8 @@ impl Suggestion {
             disabled,
             keybinding,
             source: None,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
         }
     }
 
@@ -111,6 +228,8 @@ impl Suggestion {
             disabled,
             keybinding,
             source,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
         }
     }
 
@@ -121,7 +240,7 @@ impl Suggestion {
 
 /// Get all available commands for the command palette
 pub fn get_all_commands() -> Vec<Command> {
-    vec![
+    let mut commands = vec![
         // File operations
         Command {
             name: "Open File".to_string(),
@@ -129,6 +248,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Open,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Save File".to_string(),
@@ -136,6 +257,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Save,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Save File As".to_string(),
@@ -143,6 +266,59 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SaveAs,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Export Buffer as HTML".to_string(),
+            description: "Write the buffer (or the selection) as HTML with syntax colors"
+                .to_string(),
+            action: Action::ExportHtml,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Export Buffer as ANSI Text".to_string(),
+            description: "Write the buffer (or the selection) as text with ANSI color codes"
+                .to_string(),
+            action: Action::ExportAnsi,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Evaluate Selection".to_string(),
+            description: "Run the selection (or line) through the language's evaluator".to_string(),
+            action: Action::EvaluateSelection,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Evaluate Selection and Insert Result".to_string(),
+            description: "Insert the evaluator's output below the selection (or line)".to_string(),
+            action: Action::EvaluateSelectionInsert,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Insert Snippet".to_string(),
+            description: "Insert a snippet from the configuration at the cursor".to_string(),
+            action: Action::InsertSnippet,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: Some(CommandArgument::new(
+                "snippet",
+                ArgumentType::Text,
+                ArgumentCompleter::Snippets,
+            )),
         },
         Command {
             name: "New File".to_string(),
@@ -150,6 +326,17 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::New,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "New Scratch Buffer".to_string(),
+            description: "Create a buffer for throwaway text that is never saved".to_string(),
+            action: Action::NewScratchBuffer,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Close Buffer".to_string(),
@@ -157,6 +344,44 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Close,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Close All Buffers".to_string(),
+            description: "Close every open buffer".to_string(),
+            action: Action::CloseAllBuffers,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Close Others".to_string(),
+            description: "Close every buffer except the current one".to_string(),
+            action: Action::CloseOtherBuffers,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Close to the Right".to_string(),
+            description: "Close the tabs to the right of the current one".to_string(),
+            action: Action::CloseBuffersToRight,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Close Saved".to_string(),
+            description: "Close every buffer without unsaved changes".to_string(),
+            action: Action::CloseSavedBuffers,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Revert File".to_string(),
@@ -164,6 +389,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Revert,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("has_file"),
+            argument: None,
         },
         Command {
             name: "Toggle Auto-Revert".to_string(),
@@ -171,6 +398,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ToggleAutoRevert,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Quit".to_string(),
@@ -178,6 +407,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Quit,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Edit operations
         Command {
@@ -186,6 +417,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Undo,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Redo".to_string(),
@@ -193,6 +426,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Redo,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Copy".to_string(),
@@ -200,6 +435,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Copy,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Cut".to_string(),
@@ -207,6 +444,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Cut,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Paste".to_string(),
@@ -214,6 +453,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Paste,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Delete Line".to_string(),
@@ -221,6 +462,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DeleteLine,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Delete Word Backward".to_string(),
@@ -228,6 +471,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DeleteWordBackward,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Delete Word Forward".to_string(),
@@ -235,6 +480,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DeleteWordForward,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Delete to End of Line".to_string(),
@@ -242,6 +489,17 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DeleteToLineEnd,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Kill to End of Line".to_string(),
+            description: "Cut from cursor to the end of the line into the clipboard".to_string(),
+            action: Action::KillLine,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Transpose Characters".to_string(),
@@ -249,6 +507,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::TransposeChars,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Open Line".to_string(),
@@ -256,6 +516,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::OpenLine,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Recenter".to_string(),
@@ -263,6 +525,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Recenter,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Set Mark".to_string(),
@@ -270,6 +534,18 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SetMark,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Toggle Selection Mode".to_string(),
+            description: "Extend the selection with plain movement keys (no Shift needed)"
+                .to_string(),
+            action: Action::ToggleSelectionMode,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Selection
         Command {
@@ -278,6 +554,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SelectAll,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Select Word".to_string(),
@@ -285,6 +563,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SelectWord,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Select Line".to_string(),
@@ -292,6 +572,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SelectLine,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Expand Selection".to_string(),
@@ -299,6 +581,81 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ExpandSelection,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Inside Quotes".to_string(),
+            description: "Select the text inside the quotes at the cursor".to_string(),
+            action: Action::SelectTextObject("quotes".to_string(), false),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Around Quotes".to_string(),
+            description: "Select the quoted string at the cursor, quotes included".to_string(),
+            action: Action::SelectTextObject("quotes".to_string(), true),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Inside Brackets".to_string(),
+            description: "Select the text inside the brackets around the cursor".to_string(),
+            action: Action::SelectTextObject("brackets".to_string(), false),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Around Brackets".to_string(),
+            description: "Select the brackets around the cursor and their contents".to_string(),
+            action: Action::SelectTextObject("brackets".to_string(), true),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Inside Function".to_string(),
+            description: "Select the body of the function around the cursor".to_string(),
+            action: Action::SelectTextObject("function".to_string(), false),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Around Function".to_string(),
+            description: "Select the function around the cursor".to_string(),
+            action: Action::SelectTextObject("function".to_string(), true),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Inside Paragraph".to_string(),
+            description: "Select the paragraph at the cursor".to_string(),
+            action: Action::SelectTextObject("paragraph".to_string(), false),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Select Around Paragraph".to_string(),
+            description: "Select the paragraph at the cursor and the blank lines after it"
+                .to_string(),
+            action: Action::SelectTextObject("paragraph".to_string(), true),
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Multi-cursor
         Command {
@@ -307,6 +664,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::AddCursorAbove,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Add Cursor Below".to_string(),
@@ -314,6 +673,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::AddCursorBelow,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Add Cursor at Next Match".to_string(),
@@ -321,6 +682,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::AddCursorNextMatch,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Remove Secondary Cursors".to_string(),
@@ -328,6 +691,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::RemoveSecondaryCursors,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("multiple_cursors"),
+            argument: None,
         },
         // Buffer navigation
         Command {
@@ -336,6 +701,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::NextBuffer,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Previous Buffer".to_string(),
@@ -343,6 +710,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::PrevBuffer,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Switch to Previous Tab".to_string(),
@@ -350,6 +719,139 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SwitchToPreviousTab,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Next Recent Buffer".to_string(),
+            description: "Cycle through buffers in the order they were last used".to_string(),
+            action: Action::NextRecentBuffer,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Previous Recent Buffer".to_string(),
+            description: "Cycle back through buffers in the order they were last used".to_string(),
+            action: Action::PrevRecentBuffer,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Reopen Closed Buffer".to_string(),
+            description: "Open the most recently closed buffer again where it was left".to_string(),
+            action: Action::ReopenClosedBuffer,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Show Save History".to_string(),
+            description: "List the audited saves of the current file".to_string(),
+            action: Action::ShowSaveHistory,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Diff Buffers".to_string(),
+            description: "Compare the current buffer with another one side by side".to_string(),
+            action: Action::DiffBuffers,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Toggle Scroll Lock".to_string(),
+            description: "Scroll the current pane and the next one together".to_string(),
+            action: Action::ToggleScrollLock,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Zoom Pane".to_string(),
+            description: "Show only the current pane, or all panes again".to_string(),
+            action: Action::ZoomPane,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Toggle Focus Mode".to_string(),
+            description: "Dim everything but the paragraph or sentence at the cursor".to_string(),
+            action: Action::ToggleFocusMode,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Reflow Paragraph".to_string(),
+            description: "Rewrap the paragraph, comment or selected lines at the wrap column"
+                .to_string(),
+            action: Action::ReflowParagraph,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Reflow to Columns".to_string(),
+            description: "Rewrap the paragraph, comment or selected lines to a width".to_string(),
+            action: Action::ReflowToColumns,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: Some(CommandArgument::new(
+                "columns",
+                ArgumentType::Number,
+                ArgumentCompleter::None,
+            )),
+        },
+        Command {
+            name: "Toggle Auto Hard Wrap".to_string(),
+            description: "Break lines at the wrap column while typing".to_string(),
+            action: Action::ToggleAutoHardWrap,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Create Buffer Group".to_string(),
+            description: "Start a named group of buffers with the current one".to_string(),
+            action: Action::CreateBufferGroup,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Move Buffer to Group".to_string(),
+            description: "Move the current buffer into a buffer group".to_string(),
+            action: Action::MoveToBufferGroup,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Switch Buffer Group".to_string(),
+            description: "Show only the tabs of a buffer group, or all buffers".to_string(),
+            action: Action::SwitchBufferGroup,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Switch to Tab by Name".to_string(),
@@ -357,6 +859,26 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SwitchToTabByName,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Move Tab Left".to_string(),
+            description: "Move the current tab one place to the left".to_string(),
+            action: Action::MoveTabLeft,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Move Tab Right".to_string(),
+            description: "Move the current tab one place to the right".to_string(),
+            action: Action::MoveTabRight,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Split operations
         Command {
@@ -365,6 +887,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SplitHorizontal,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Split Vertical".to_string(),
@@ -372,6 +896,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SplitVertical,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Close Split".to_string(),
@@ -379,6 +905,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::CloseSplit,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("multiple_splits"),
+            argument: None,
         },
         Command {
             name: "Next Split".to_string(),
@@ -386,6 +914,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::NextSplit,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("multiple_splits"),
+            argument: None,
         },
         Command {
             name: "Previous Split".to_string(),
@@ -393,6 +923,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::PrevSplit,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("multiple_splits"),
+            argument: None,
         },
         Command {
             name: "Increase Split Size".to_string(),
@@ -400,6 +932,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::IncreaseSplitSize,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("multiple_splits"),
+            argument: None,
         },
         Command {
             name: "Decrease Split Size".to_string(),
@@ -407,6 +941,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DecreaseSplitSize,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("multiple_splits"),
+            argument: None,
         },
         // View toggles
         Command {
@@ -415,6 +951,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ToggleLineNumbers,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Scroll Up".to_string(),
@@ -422,6 +960,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ScrollUp,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Scroll Down".to_string(),
@@ -429,6 +969,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ScrollDown,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Scroll Tabs Left".to_string(),
@@ -436,6 +978,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ScrollTabsLeft,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Scroll Tabs Right".to_string(),
@@ -443,6 +987,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ScrollTabsRight,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Toggle Mouse Support".to_string(),
@@ -450,6 +996,17 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ToggleMouseCapture,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Toggle Performance Overlay".to_string(),
+            description: "Show typing latency percentiles, from key press to paint".to_string(),
+            action: Action::TogglePerformanceOverlay,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // File explorer
         Command {
@@ -458,6 +1015,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ToggleFileExplorer,
             contexts: vec![KeyContext::Normal, KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Focus File Explorer".to_string(),
@@ -465,6 +1024,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FocusFileExplorer,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Focus Editor".to_string(),
@@ -472,6 +1033,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FocusEditor,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "File Explorer: Refresh".to_string(),
@@ -479,6 +1042,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerRefresh,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "File Explorer: New File".to_string(),
@@ -486,6 +1051,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerNewFile,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "File Explorer: New Directory".to_string(),
@@ -493,6 +1060,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerNewDirectory,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "File Explorer: Delete".to_string(),
@@ -500,6 +1069,26 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerDelete,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "File Explorer: Open to the Right".to_string(),
+            description: "Open the selected file in the pane to the right".to_string(),
+            action: Action::FileExplorerOpenRight,
+            contexts: vec![KeyContext::FileExplorer],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "File Explorer: Open Below".to_string(),
+            description: "Open the selected file in the pane below".to_string(),
+            action: Action::FileExplorerOpenBelow,
+            contexts: vec![KeyContext::FileExplorer],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "File Explorer: Rename".to_string(),
@@ -507,6 +1096,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerRename,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Toggle Hidden Files".to_string(),
@@ -514,6 +1105,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerToggleHidden,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Toggle Gitignored Files".to_string(),
@@ -521,6 +1114,26 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FileExplorerToggleGitignored,
             contexts: vec![KeyContext::FileExplorer],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "File Explorer: Filter".to_string(),
+            description: "Show only the files matching a query as it is typed".to_string(),
+            action: Action::FileExplorerFilter,
+            contexts: vec![KeyContext::FileExplorer],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Open in External Program".to_string(),
+            description: "Open the file with its default application".to_string(),
+            action: Action::OpenInExternalProgram,
+            contexts: vec![KeyContext::Normal, KeyContext::FileExplorer],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // View
         Command {
@@ -529,6 +1142,54 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ToggleLineWrap,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Redact Secrets".to_string(),
+            description: "Mask API keys, passwords and other secrets on screen".to_string(),
+            action: Action::ToggleRedactSecrets,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Lock Screen".to_string(),
+            description: "Hide the screen until a key or the passphrase is entered".to_string(),
+            action: Action::LockScreen,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Buffer Statistics".to_string(),
+            description: "Count words, the project's code lines and this session's edits"
+                .to_string(),
+            action: Action::BufferStatistics,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Reload Environment".to_string(),
+            description: "Load the variables the project's .envrc exports again".to_string(),
+            action: Action::ReloadEnvironment,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Toggle Vim Mode".to_string(),
+            description: "Switch between modal (vim-style) and regular editing".to_string(),
+            action: Action::ToggleVimMode,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Note: Compose mode commands removed - markdown_compose plugin provides these
         Command {
@@ -537,6 +1198,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SetBackground,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Set Background Blend".to_string(),
@@ -544,6 +1207,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SetBackgroundBlend,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Note: Command Palette is intentionally not in the command list
         // to avoid confusion when it's already open (use Ctrl+P or Ctrl+/ to toggle)
@@ -554,6 +1219,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Search,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Find in Selection".to_string(),
@@ -561,6 +1228,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FindInSelection,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("has_selection"),
+            argument: None,
         },
         Command {
             name: "Find Next".to_string(),
@@ -568,6 +1237,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FindNext,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Find Previous".to_string(),
@@ -575,6 +1246,17 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::FindPrevious,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Search Matches to Quickfix".to_string(),
+            description: "List all matches of the current search in the quickfix panel".to_string(),
+            action: Action::SearchToQuickfix,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Replace".to_string(),
@@ -582,6 +1264,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::Replace,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Query Replace".to_string(),
@@ -589,6 +1273,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::QueryReplace,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Navigation
         Command {
@@ -597,6 +1283,21 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::GotoLine,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: Some(CommandArgument::new(
+                "line",
+                ArgumentType::Number,
+                ArgumentCompleter::None,
+            )),
+        },
+        Command {
+            name: "Repeat Last Palette Query".to_string(),
+            description: "Reopen the last palette prompt with its query filled in".to_string(),
+            action: Action::RepeatLastPaletteQuery,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Smart Home".to_string(),
@@ -605,6 +1306,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::SmartHome,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Show Completions".to_string(),
@@ -612,6 +1315,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspCompletion,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Go to Definition".to_string(),
@@ -619,6 +1324,28 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspGotoDefinition,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Go to Definition to the Right".to_string(),
+            description: "Open the definition of the symbol under cursor in the pane to the right"
+                .to_string(),
+            action: Action::LspGotoDefinitionRight,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Go to Definition Below".to_string(),
+            description: "Open the definition of the symbol under cursor in the pane below"
+                .to_string(),
+            action: Action::LspGotoDefinitionBelow,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Show Hover Info".to_string(),
@@ -626,6 +1353,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspHover,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Find References".to_string(),
@@ -633,6 +1362,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspReferences,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Show Signature Help".to_string(),
@@ -640,6 +1371,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspSignatureHelp,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Code Actions".to_string(),
@@ -647,6 +1380,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspCodeActions,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Start/Restart LSP Server".to_string(),
@@ -654,6 +1389,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspRestart,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Stop LSP Server".to_string(),
@@ -661,6 +1398,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspStop,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Navigate Back".to_string(),
@@ -668,6 +1407,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::NavigateBack,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Navigate Forward".to_string(),
@@ -675,6 +1416,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::NavigateForward,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Smart editing
         Command {
@@ -683,6 +1426,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ToggleComment,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Indent Selection".to_string(),
@@ -690,6 +1435,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::IndentSelection,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Dedent Selection".to_string(),
@@ -697,6 +1444,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DedentSelection,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Go to Matching Bracket".to_string(),
@@ -704,6 +1453,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::GoToMatchingBracket,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Error navigation
         Command {
@@ -712,6 +1463,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::JumpToNextError,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Jump to Previous Error".to_string(),
@@ -719,6 +1472,17 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::JumpToPreviousError,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Show Line Diagnostics".to_string(),
+            description: "Show the diagnostics on the cursor's line in a popup".to_string(),
+            action: Action::ShowDiagnostics,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // LSP
         Command {
@@ -727,6 +1491,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::LspRename,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Bookmarks and Macros
         Command {
@@ -735,6 +1501,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ListBookmarks,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "List Macros".to_string(),
@@ -742,27 +1510,36 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ListMacros,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
-            name: "Record Macro".to_string(),
-            description: "Toggle macro recording for a register (0-9)".to_string(),
+            name: "Start Recording Macro".to_string(),
+            description: "Record the keys you press into a register (a-z, 0-9)".to_string(),
             action: Action::PromptRecordMacro,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
-            name: "Stop Recording Macro".to_string(),
+            name: "Stop Recording".to_string(),
             description: "Stop the current macro recording".to_string(),
             action: Action::StopMacroRecording,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: when("recording_macro"),
+            argument: None,
         },
         Command {
-            name: "Play Macro".to_string(),
-            description: "Play macro from a register (0-9)".to_string(),
+            name: "Replay Macro".to_string(),
+            description: "Replay the keys recorded in a register, optionally several times"
+                .to_string(),
             action: Action::PromptPlayMacro,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Play Last Macro".to_string(),
@@ -770,6 +1547,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::PlayLastMacro,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Set Bookmark".to_string(),
@@ -777,6 +1556,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::PromptSetBookmark,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Jump to Bookmark".to_string(),
@@ -784,6 +1565,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::PromptJumpToBookmark,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Help
         Command {
@@ -792,6 +1575,8 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ShowHelp,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         Command {
             name: "Show Keyboard Shortcuts".to_string(),
@@ -799,6 +1584,36 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::ShowKeyboardShortcuts,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Show Keybindings".to_string(),
+            description: "Search key bindings by context, with conflicts and unbound commands"
+                .to_string(),
+            action: Action::ShowKeybindings,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Bind Key".to_string(),
+            description: "Press a key or chord and pick a command to run with it".to_string(),
+            action: Action::BindKey,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Dismiss Status Message".to_string(),
+            description: "Remove the most important message from the status bar".to_string(),
+            action: Action::DismissStatusMessage,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
         // Config
         Command {
@@ -807,45 +1622,135 @@ pub fn get_all_commands() -> Vec<Command> {
             action: Action::DumpConfig,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
+        // Session
         Command {
-            name: "Toggle Inlay Hints".to_string(),
-            description: "Show or hide LSP inlay hints (type hints, parameter hints)".to_string(),
-            action: Action::ToggleInlayHints,
+            name: "Restore Session from Snapshot".to_string(),
+            description: "Reopen the files and layout saved in a periodic session snapshot"
+                .to_string(),
+            action: Action::RestoreSessionSnapshot,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Save Session As".to_string(),
+            description: "Save the open files and layout as a named session".to_string(),
+            action: Action::SaveSessionAs,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Switch Session".to_string(),
+            description: "Replace the open files and layout with a named session".to_string(),
+            action: Action::SwitchSession,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Reload Project Tasks".to_string(),
+            description: "Reload the project's commands from .fresh/tasks.toml".to_string(),
+            action: Action::ReloadProjectTasks,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Cancel Running Task".to_string(),
+            description: "Stop the most recently started background task".to_string(),
+            action: Action::CancelRunningTask,
+            contexts: vec![],
+            source: CommandSource::Builtin,
+            when: when("running_task"),
+            argument: None,
+        },
+        Command {
+            name: "Go To Symbol in Workspace".to_string(),
+            description: "Jump to a function, type, ... defined anywhere in the project"
+                .to_string(),
+            action: Action::GoToWorkspaceSymbol,
             contexts: vec![KeyContext::Normal],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
-        // Theme selection
         Command {
-            name: "Select Theme".to_string(),
-            description: "Choose a color theme for the editor".to_string(),
-            action: Action::SelectTheme,
+            name: "Rebuild Symbol Index".to_string(),
+            description: "Re-read the project's changed files into the symbol index".to_string(),
+            action: Action::RebuildSymbolIndex,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
-        // Keybinding map switching
         Command {
-            name: "Switch to Default Keybindings".to_string(),
-            description: "Switch to the default keybinding map".to_string(),
-            action: Action::SwitchKeybindingMap("default".to_string()),
+            name: "Rebuild Search Index".to_string(),
+            description: "Index the project's files for fast project-wide search".to_string(),
+            action: Action::RebuildSearchIndex,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
+        },
+        Command {
+            name: "Toggle Inlay Hints".to_string(),
+            description: "Show or hide LSP inlay hints (type hints, parameter hints)".to_string(),
+            action: Action::ToggleInlayHints,
+            contexts: vec![KeyContext::Normal],
+            source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
+        // Theme selection
         Command {
-            name: "Switch to Emacs Keybindings".to_string(),
-            description: "Switch to Emacs-style keybindings".to_string(),
-            action: Action::SwitchKeybindingMap("emacs".to_string()),
+            name: "Select Theme".to_string(),
+            description: "Choose a color theme for the editor".to_string(),
+            action: Action::SelectTheme,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: Some(CommandArgument::new(
+                "theme",
+                ArgumentType::Text,
+                ArgumentCompleter::Themes,
+            )),
         },
         Command {
-            name: "Switch to VSCode Keybindings".to_string(),
-            description: "Switch to VSCode-style keybindings".to_string(),
-            action: Action::SwitchKeybindingMap("vscode".to_string()),
+            name: "Lint Theme".to_string(),
+            description: "Check text and background colors of the current theme for contrast"
+                .to_string(),
+            action: Action::LintTheme,
             contexts: vec![],
             source: CommandSource::Builtin,
+            when: None,
+            argument: None,
         },
-    ]
+    ];
+
+    // Keybinding map switching
+    commands.extend(KEYMAP_PRESETS.iter().map(|preset| Command {
+        name: format!("Switch to {} Keybindings", preset.label),
+        description: preset.description.to_string(),
+        action: Action::SwitchKeybindingMap(preset.name.to_string()),
+        contexts: vec![],
+        source: CommandSource::Builtin,
+        when: None,
+        argument: None,
+    }));
+    commands
+}
+
+/// Parse the when clause of a builtin command
+fn when(clause: &str) -> Option<WhenClause> {
+    Some(WhenClause::parse(clause).expect("invalid builtin when clause"))
 }
 
 /// Filter commands by fuzzy matching the query, with context awareness
@@ -854,7 +1759,6 @@ pub fn filter_commands(
     current_context: KeyContext,
     keybinding_resolver: &crate::input::keybindings::KeybindingResolver,
 ) -> Vec<Suggestion> {
-    let query_lower = query.to_lowercase();
     let commands = get_all_commands();
 
     // Helper function to check if command is available in current context
@@ -863,48 +1767,32 @@ pub fn filter_commands(
         cmd.contexts.is_empty() || cmd.contexts.contains(&current_context)
     };
 
-    // Helper function for fuzzy matching
-    let matches_query = |cmd: &Command| -> bool {
-        if query.is_empty() {
-            return true;
-        }
-
-        let name_lower = cmd.name.to_lowercase();
-        let mut query_chars = query_lower.chars();
-        let mut current_char = query_chars.next();
-
-        for name_char in name_lower.chars() {
-            if let Some(qc) = current_char {
-                if qc == name_char {
-                    current_char = query_chars.next();
-                }
-            } else {
-                break;
-            }
-        }
-
-        current_char.is_none() // All query characters matched
-    };
-
-    // Filter and convert to suggestions
-    let mut suggestions: Vec<Suggestion> = commands
+    // Filter and convert to suggestions, keeping the fuzzy score for ranking
+    let mut suggestions: Vec<(Suggestion, i32)> = commands
         .into_iter()
-        .filter(|cmd| matches_query(cmd))
-        .map(|cmd| {
+        .filter_map(|cmd| {
+            let result = fuzzy_match(query, &cmd.name);
+            if !result.matched {
+                return None;
+            }
             let available = is_available(&cmd);
             let keybinding =
                 keybinding_resolver.get_keybinding_for_action(&cmd.action, current_context);
-            Suggestion::with_all(
+            let mut suggestion = Suggestion::with_all(
                 cmd.name.clone(),
                 Some(cmd.description),
                 !available,
                 keybinding,
-            )
+            );
+            suggestion.match_indices = result.match_positions;
+            Some((suggestion, result.score))
         })
         .collect();
 
-    // Sort: available commands first, then disabled ones
-    suggestions.sort_by_key(|s| s.disabled);
+    // Sort: available commands first, then best matches
+    suggestions.sort_by(|(a, a_score), (b, b_score)| {
+        a.disabled.cmp(&b.disabled).then(b_score.cmp(a_score))
+    });
 
-    suggestions
+    suggestions.into_iter().map(|(s, _)| s).collect()
 }
@@ -0,0 +1,165 @@
+//! Recognizing file paths dropped onto the terminal
+//!
+//! Terminals paste the paths of files dragged onto them as text: quoted
+//! (`'/tmp/my file.txt'`), with backslash escapes (`/tmp/my\ file.txt`) or
+//! as `file://` URIs, several separated by spaces or newlines. A paste counts
+//! as a drop only if every word is an absolute path to an existing file, so
+//! ordinary text is never mistaken for one.
+
+use std::path::PathBuf;
+
+/// The files named by a paste, if it is nothing but dropped file paths
+pub fn parse_dropped_paths(text: &str) -> Option<Vec<PathBuf>> {
+    parse_dropped_paths_with(text, |path| path.is_file())
+}
+
+fn parse_dropped_paths_with(
+    text: &str,
+    is_file: impl Fn(&std::path::Path) -> bool,
+) -> Option<Vec<PathBuf>> {
+    let words = split_words(text)?;
+    if words.is_empty() {
+        return None;
+    }
+
+    words
+        .into_iter()
+        .map(|word| {
+            let path = match word.strip_prefix("file://") {
+                // "file:///tmp/a" or "file://localhost/tmp/a"
+                Some(rest) => {
+                    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+                    PathBuf::from(percent_decode(rest)?)
+                }
+                None => PathBuf::from(word),
+            };
+            (path.is_absolute() && is_file(&path)).then_some(path)
+        })
+        .collect()
+}
+
+/// Split shell-style words: quotes group, and outside single quotes a
+/// backslash escapes the next character (except on Windows, where it
+/// separates path components)
+///
+/// Returns None for an unterminated quote.
+fn split_words(text: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' if !cfg!(windows) => word.push(chars.next()?),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' if !cfg!(windows) => {
+                in_word = true;
+                word.push(chars.next()?);
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Some(words)
+}
+
+/// Decode `%XX` escapes in a URI path; None if they don't form UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn parse(text: &str) -> Option<Vec<PathBuf>> {
+        let files = [
+            "/tmp/notes.txt",
+            "/tmp/my file.txt",
+            "/tmp/it's.md",
+            "/tmp/a b/c%.rs",
+        ];
+        parse_dropped_paths_with(text, |path| files.iter().any(|f| Path::new(f) == path))
+    }
+
+    fn paths(paths: &[&str]) -> Option<Vec<PathBuf>> {
+        Some(paths.iter().map(PathBuf::from).collect())
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_quoted_and_escaped_paths() {
+        assert_eq!(parse("/tmp/notes.txt"), paths(&["/tmp/notes.txt"]));
+        assert_eq!(parse("'/tmp/my file.txt' "), paths(&["/tmp/my file.txt"]));
+        assert_eq!(parse("/tmp/my\\ file.txt"), paths(&["/tmp/my file.txt"]));
+        assert_eq!(parse("\"/tmp/it's.md\""), paths(&["/tmp/it's.md"]));
+        assert_eq!(
+            parse("/tmp/notes.txt '/tmp/my file.txt'\n"),
+            paths(&["/tmp/notes.txt", "/tmp/my file.txt"])
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_file_uris() {
+        assert_eq!(
+            parse("file:///tmp/a%20b/c%25.rs\r\nfile://localhost/tmp/notes.txt"),
+            paths(&["/tmp/a b/c%.rs", "/tmp/notes.txt"])
+        );
+        assert_eq!(parse("file:///tmp/my%2"), None);
+    }
+
+    #[test]
+    fn test_other_text_is_not_a_drop() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("  \n"), None);
+        assert_eq!(parse("notes.txt"), None);
+        assert_eq!(parse("/tmp/missing.txt"), None);
+        // One word that isn't a file spoils the whole paste
+        assert_eq!(parse("/tmp/notes.txt and more"), None);
+        assert_eq!(parse("'/tmp/notes.txt"), None);
+    }
+}
@@ -0,0 +1,168 @@
+//! Frecency: ranking items by how often and how recently they were used
+//!
+//! Each use adds to an item's count, and the score is that count weighted by
+//! the age of the last use. A command used a lot months ago ranks below one
+//! used a few times today. The command palette uses it to order suggestions.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Usage of a single item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FrecencyEntry {
+    /// Number of uses
+    count: u32,
+    /// Time of the last use (seconds since the Unix epoch)
+    last_used: u64,
+}
+
+/// Usage counts and last-use times of named items
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrecencyStore {
+    entries: HashMap<String, FrecencyEntry>,
+}
+
+impl FrecencyStore {
+    /// Maximum number of items to remember (the lowest scores are dropped)
+    const MAX_ENTRIES: usize = 200;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a use of `name` at `now` (seconds since the Unix epoch)
+    pub fn record(&mut self, name: &str, now: u64) {
+        let entry = self
+            .entries
+            .entry(name.to_string())
+            .or_insert(FrecencyEntry {
+                count: 0,
+                last_used: now,
+            });
+        entry.count = entry.count.saturating_add(1);
+        entry.last_used = now;
+
+        if self.entries.len() > Self::MAX_ENTRIES {
+            let lowest = self
+                .entries
+                .keys()
+                .filter(|key| key.as_str() != name)
+                .min_by_key(|key| self.score(key, now))
+                .cloned();
+            if let Some(lowest) = lowest {
+                self.entries.remove(&lowest);
+            }
+        }
+    }
+
+    /// Score of `name` at `now` (0 if never used, higher is better)
+    pub fn score(&self, name: &str, now: u64) -> u32 {
+        self.entries.get(name).map_or(0, |entry| {
+            entry
+                .count
+                .saturating_mul(recency_weight(now.saturating_sub(entry.last_used)))
+        })
+    }
+
+    /// Check if nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Save the store to a file
+    pub fn save_to_file(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)
+    }
+
+    /// Load the store from a file (empty if the file doesn't exist)
+    pub fn load_from_file(path: &std::path::Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(std::io::Error::other)
+    }
+}
+
+/// Weight of a use by its age: recent uses count much more than old ones
+fn recency_weight(age_secs: u64) -> u32 {
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+    match age_secs {
+        age if age < 4 * HOUR => 100,
+        age if age < DAY => 70,
+        age if age < 7 * DAY => 50,
+        age if age < 30 * DAY => 30,
+        _ => 10,
+    }
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Get the path for the command palette frecency file
+pub fn get_command_frecency_path() -> std::io::Result<std::path::PathBuf> {
+    Ok(crate::input::input_history::get_data_dir()?.join("command_frecency.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_frequent_and_recent_rank_higher() {
+        let mut store = FrecencyStore::new();
+        let now = 100 * DAY;
+
+        // Used often, but a long time ago
+        for _ in 0..3 {
+            store.record("Old Favorite", now - 60 * DAY);
+        }
+        // Used twice today
+        store.record("Save File", now - 60);
+        store.record("Save File", now);
+        store.record("Quit", now);
+
+        assert!(store.score("Save File", now) > store.score("Quit", now));
+        assert!(store.score("Save File", now) > store.score("Old Favorite", now));
+        assert_eq!(store.score("Never Used", now), 0);
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let mut store = FrecencyStore::new();
+        for i in 0..FrecencyStore::MAX_ENTRIES + 10 {
+            store.record(&format!("Command {}", i), i as u64);
+        }
+        assert_eq!(store.entries.len(), FrecencyStore::MAX_ENTRIES);
+        // The item just recorded is never the one dropped
+        let last = format!("Command {}", FrecencyStore::MAX_ENTRIES + 9);
+        assert!(store.score(&last, 1000) > 0);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("frecency.json");
+
+        let mut store = FrecencyStore::new();
+        store.record("Save File", 42);
+        store.save_to_file(&path).unwrap();
+
+        assert_eq!(FrecencyStore::load_from_file(&path).unwrap(), store);
+        assert!(FrecencyStore::load_from_file(&dir.path().join("none.json"))
+            .unwrap()
+            .is_empty());
+    }
+}
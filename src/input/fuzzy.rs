@@ -1,8 +1,13 @@
-//! Fuzzy matching algorithm inspired by fzf
+//! Fuzzy matching algorithms inspired by fzf
 //!
 //! Provides substring-style fuzzy matching where query characters must appear
 //! in order in the target string, but not necessarily consecutively.
 //! Matching is case-insensitive.
+//!
+//! Matchers implement [`FuzzyMatcher`]; `editor.fuzzy_matcher` picks the one
+//! the command palette, file finder, buffer picker and symbol search use:
+//! [`SubsequenceMatcher`] (the default) or [`SmithWatermanMatcher`], which
+//! scores like fzf's v2 algorithm.
 
 /// Score bonus constants for match quality ranking
 mod score {
@@ -60,7 +65,54 @@ impl PartialOrd for FuzzyMatch {
     }
 }
 
-/// Perform fzf-style fuzzy matching of a query against a target string.
+/// A fuzzy matching algorithm
+pub trait FuzzyMatcher: Send + Sync {
+    /// Match `query` against `target`, ignoring case
+    ///
+    /// An empty query matches everything with a score of 0.
+    fn fuzzy_match(&self, query: &str, target: &str) -> FuzzyMatch;
+}
+
+/// Greedy-with-lookahead subsequence matcher (the default)
+///
+/// Keeps the best score for each matched query prefix while walking the
+/// target once, so it is fast but can miss the best alignment when a
+/// character appears several times.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SubsequenceMatcher;
+
+impl FuzzyMatcher for SubsequenceMatcher {
+    fn fuzzy_match(&self, query: &str, target: &str) -> FuzzyMatch {
+        if query.is_empty() {
+            return FuzzyMatch {
+                matched: true,
+                score: 0,
+                match_positions: Vec::new(),
+            };
+        }
+
+        let query_lower: Vec<char> = query.to_lowercase().chars().collect();
+        let target_chars: Vec<char> = target.chars().collect();
+        let target_lower: Vec<char> = target.to_lowercase().chars().collect();
+
+        // Try to find the best matching positions using a greedy approach
+        // that considers bonuses at each step
+        let result = find_best_match(&query_lower, &target_chars, &target_lower);
+
+        if let Some((positions, score)) = result {
+            FuzzyMatch {
+                matched: true,
+                score,
+                match_positions: positions,
+            }
+        } else {
+            FuzzyMatch::no_match()
+        }
+    }
+}
+
+/// Perform fzf-style fuzzy matching of a query against a target string,
+/// with the default [`SubsequenceMatcher`].
 ///
 /// Returns a `FuzzyMatch` containing:
 /// - `matched`: true if all query characters appear in order in the target
@@ -89,31 +141,7 @@ impl PartialOrd for FuzzyMatch {
 /// assert!(!result.matched);
 /// ```
 pub fn fuzzy_match(query: &str, target: &str) -> FuzzyMatch {
-    if query.is_empty() {
-        return FuzzyMatch {
-            matched: true,
-            score: 0,
-            match_positions: Vec::new(),
-        };
-    }
-
-    let query_lower: Vec<char> = query.to_lowercase().chars().collect();
-    let target_chars: Vec<char> = target.chars().collect();
-    let target_lower: Vec<char> = target.to_lowercase().chars().collect();
-
-    // Try to find the best matching positions using a greedy approach
-    // that considers bonuses at each step
-    let result = find_best_match(&query_lower, &target_chars, &target_lower);
-
-    if let Some((positions, score)) = result {
-        FuzzyMatch {
-            matched: true,
-            score,
-            match_positions: positions,
-        }
-    } else {
-        FuzzyMatch::no_match()
-    }
+    SubsequenceMatcher.fuzzy_match(query, target)
 }
 
 /// Find the best matching positions for query in target
@@ -244,18 +272,182 @@ fn find_best_match(
         .map(|s| (s.positions.clone(), s.score))
 }
 
+/// Scores for [`SmithWatermanMatcher`], fzf's
+mod sw_score {
+    /// Each matched character
+    pub const MATCH: i32 = 16;
+    /// The first unmatched character of a gap
+    pub const GAP_START: i32 = -3;
+    /// Each further unmatched character of a gap
+    pub const GAP_EXTENSION: i32 = -1;
+    /// Match right after whitespace (or at the start)
+    pub const BOUNDARY_WHITE: i32 = MATCH / 2 + 2;
+    /// Match right after a path or list delimiter
+    pub const BOUNDARY_DELIMITER: i32 = MATCH / 2 + 1;
+    /// Match right after any other non-word character
+    pub const BOUNDARY: i32 = MATCH / 2;
+    /// Match at a camelCase or letter-to-digit transition
+    pub const CAMEL_CASE: i32 = BOUNDARY + GAP_EXTENSION;
+    /// Least bonus of a match continuing a run of matches, so that a run
+    /// beats an equally long gapped match
+    pub const CONSECUTIVE: i32 = -(GAP_START + GAP_EXTENSION);
+    /// The first query character's bonus counts this many times
+    pub const FIRST_CHAR_MULTIPLIER: i32 = 2;
+}
+
+/// Smith-Waterman local alignment scorer, like fzf's v2 algorithm
+///
+/// Every query character must match, in order; unmatched target characters
+/// before and after the match are free and gaps between matches cost an
+/// affine penalty. Matches after word boundaries and runs of consecutive
+/// matches earn bonuses, and the alignment with the best total is found by
+/// dynamic programming over all of them, so it ranks better than
+/// [`SubsequenceMatcher`] at the cost of time proportional to query length
+/// times target length.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmithWatermanMatcher;
+
+impl SmithWatermanMatcher {
+    /// The bonus for matching `current` after `previous` (None at the start)
+    fn bonus(previous: Option<char>, current: char) -> i32 {
+        let Some(previous) = previous else {
+            return sw_score::BOUNDARY_WHITE;
+        };
+        if previous.is_whitespace() {
+            sw_score::BOUNDARY_WHITE
+        } else if matches!(previous, '/' | '\\' | ',' | ':' | ';' | '|') {
+            sw_score::BOUNDARY_DELIMITER
+        } else if !previous.is_alphanumeric() {
+            sw_score::BOUNDARY
+        } else if (previous.is_lowercase() && current.is_uppercase())
+            || (!previous.is_numeric() && current.is_numeric())
+        {
+            sw_score::CAMEL_CASE
+        } else {
+            0
+        }
+    }
+}
+
+impl FuzzyMatcher for SmithWatermanMatcher {
+    fn fuzzy_match(&self, query: &str, target: &str) -> FuzzyMatch {
+        if query.is_empty() {
+            return FuzzyMatch {
+                matched: true,
+                score: 0,
+                match_positions: Vec::new(),
+            };
+        }
+
+        let query: Vec<char> = query.to_lowercase().chars().collect();
+        let target_chars: Vec<char> = target.chars().collect();
+        // Lowercased one by one so indices stay those of `target`
+        let target_lower: Vec<char> = target_chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+        let (m, n) = (query.len(), target_chars.len());
+        if n < m {
+            return FuzzyMatch::no_match();
+        }
+
+        let bonuses: Vec<i32> = (0..n)
+            .map(|j| Self::bonus(j.checked_sub(1).map(|p| target_chars[p]), target_chars[j]))
+            .collect();
+
+        // score[i][j]: best score of matching query[..=i] with query[i] at
+        // target[j]; from[i][j]: where query[i - 1] matched on that path;
+        // run[i][j]: the bonus of the run of consecutive matches ending there
+        const NONE: i32 = i32::MIN;
+        let mut score = vec![vec![NONE; n]; m];
+        let mut from = vec![vec![0usize; n]; m];
+        let mut run = vec![vec![0i32; n]; m];
+
+        for (j, &c) in target_lower.iter().enumerate() {
+            if c == query[0] {
+                score[0][j] = sw_score::MATCH + bonuses[j] * sw_score::FIRST_CHAR_MULTIPLIER;
+                run[0][j] = bonuses[j];
+            }
+        }
+        for (i, &q) in query.iter().enumerate().skip(1) {
+            // Best previous match followed by a gap of one or more, and
+            // where that match was
+            let mut gap = NONE;
+            let mut gap_from = 0;
+            for (j, &c) in target_lower.iter().enumerate() {
+                if j >= 2 {
+                    if gap != NONE {
+                        gap += sw_score::GAP_EXTENSION;
+                    }
+                    let start = score[i - 1][j - 2];
+                    if start != NONE && start + sw_score::GAP_START > gap {
+                        gap = start + sw_score::GAP_START;
+                        gap_from = j - 2;
+                    }
+                }
+                if c != q {
+                    continue;
+                }
+
+                if gap != NONE {
+                    score[i][j] = gap + sw_score::MATCH + bonuses[j];
+                    from[i][j] = gap_from;
+                    run[i][j] = bonuses[j];
+                }
+                if j >= 1 && score[i - 1][j - 1] != NONE {
+                    // A run keeps the bonus of where it started
+                    let bonus = bonuses[j].max(run[i - 1][j - 1]).max(sw_score::CONSECUTIVE);
+                    let consecutive = score[i - 1][j - 1] + sw_score::MATCH + bonus;
+                    if consecutive >= score[i][j] {
+                        score[i][j] = consecutive;
+                        from[i][j] = j - 1;
+                        run[i][j] = bonus;
+                    }
+                }
+            }
+        }
+
+        // The best end; the first one on ties
+        let Some((end, &best)) = score[m - 1]
+            .iter()
+            .enumerate()
+            .filter(|(_, &s)| s != NONE)
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+        else {
+            return FuzzyMatch::no_match();
+        };
+
+        let mut match_positions = vec![0; m];
+        let mut j = end;
+        for (i, position) in match_positions.iter_mut().enumerate().rev() {
+            *position = j;
+            j = from[i][j];
+        }
+        FuzzyMatch {
+            matched: true,
+            score: best,
+            match_positions,
+        }
+    }
+}
+
 /// Filter a list of items using fuzzy matching, returning sorted results
 ///
 /// Items are sorted by match quality (best matches first).
 /// Non-matching items are excluded.
-pub fn fuzzy_filter<T, F>(query: &str, items: &[T], get_text: F) -> Vec<(usize, FuzzyMatch)>
+pub fn fuzzy_filter<T, F>(
+    matcher: &dyn FuzzyMatcher,
+    query: &str,
+    items: &[T],
+    get_text: F,
+) -> Vec<(usize, FuzzyMatch)>
 where
     F: Fn(&T) -> &str,
 {
     let mut results: Vec<(usize, FuzzyMatch)> = items
         .iter()
         .enumerate()
-        .map(|(idx, item)| (idx, fuzzy_match(query, get_text(item))))
+        .map(|(idx, item)| (idx, matcher.fuzzy_match(query, get_text(item))))
         .filter(|(_, m)| m.matched)
         .collect();
 
@@ -371,7 +563,7 @@ mod tests {
     #[test]
     fn test_fuzzy_filter() {
         let items = vec!["Save File", "Open File", "Save As", "Quit"];
-        let results = fuzzy_filter("sf", &items, |s| s);
+        let results = fuzzy_filter(&SubsequenceMatcher, "sf", &items, |s| s);
 
         assert!(!results.is_empty());
         // "Save File" should match
@@ -431,4 +623,49 @@ mod tests {
         assert!(fuzzy_match("mod", "src/input/mod.rs").matched);
         assert!(fuzzy_match("cmdreg", "command_registry.rs").matched);
     }
+
+    #[test]
+    fn test_smith_waterman_matches_like_subsequence() {
+        let sw = SmithWatermanMatcher;
+        assert!(sw.fuzzy_match("", "anything").matched);
+        assert!(sw.fuzzy_match("SAVE", "save file").matched);
+        assert!(sw.fuzzy_match("gtd", "Go to Definition").matched);
+        assert!(sw.fuzzy_match("cmdreg", "command_registry.rs").matched);
+        assert!(!sw.fuzzy_match("fs", "Save File").matched);
+        assert!(!sw.fuzzy_match("very long query", "short").matched);
+
+        let result = sw.fuzzy_match("sf", "Save File");
+        assert_eq!(result.match_positions, vec![0, 5]);
+    }
+
+    #[test]
+    fn test_smith_waterman_finds_best_alignment() {
+        let sw = SmithWatermanMatcher;
+        // The first 'm' is a dead end; the run at the word boundary wins
+        let result = sw.fuzzy_match("main", "src/my_main.rs");
+        assert!(result.matched);
+        assert_eq!(result.match_positions, vec![7, 8, 9, 10]);
+
+        // Runs and boundaries beat scattered matches
+        let run = sw.fuzzy_match("ab", "xabc");
+        let sparse = sw.fuzzy_match("ab", "xaxb");
+        assert!(run.score > sparse.score);
+        let boundary = sw.fuzzy_match("fb", "foo_bar");
+        let middle = sw.fuzzy_match("ob", "foo_bar");
+        assert!(boundary.score > middle.score);
+        let camel = sw.fuzzy_match("sf", "saveFile");
+        assert_eq!(camel.match_positions, vec![0, 4]);
+    }
+
+    #[test]
+    fn test_matchers_are_interchangeable() {
+        let matchers: [&dyn FuzzyMatcher; 2] = [&SubsequenceMatcher, &SmithWatermanMatcher];
+        let items = vec!["Save File", "Open File", "Save As", "Quit"];
+        for matcher in matchers {
+            let results = fuzzy_filter(matcher, "sa", &items, |s| s);
+            let texts: Vec<&str> = results.iter().map(|(idx, _)| items[*idx]).collect();
+            assert_eq!(texts.len(), 2);
+            assert!(texts.contains(&"Save As") && texts.contains(&"Save File"));
+        }
+    }
 }
@@ -304,6 +304,11 @@ impl InputHistory {
         }
     }
 
+    /// Check if the user is currently browsing history items
+    pub fn is_navigating(&self) -> bool {
+        self.position.is_some()
+    }
+
     /// Check if history is empty
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
@@ -638,4 +643,18 @@ mod tests {
         assert_eq!(history.len(), 1);
         assert_eq!(history.last(), Some("third"));
     }
+
+    #[test]
+    fn test_is_navigating() {
+        let mut history = InputHistory::new();
+        history.push("first".to_string());
+        assert!(!history.is_navigating());
+
+        history.navigate_prev("");
+        assert!(history.is_navigating());
+
+        // Moving past the newest item returns to the original input
+        assert_eq!(history.navigate_next(), Some(String::new()));
+        assert!(!history.is_navigating());
+    }
 }
@@ -2,6 +2,55 @@ use crate::config::Config;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::collections::HashMap;
 
+/// A built-in keymap that can be chosen as the `active_keybinding_map`
+///
+/// The config's `keybindings` and the user's `keybindings.toml` are layered on
+/// top of whichever preset (or user-defined map) is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeymapPreset {
+    /// Name used in the config and by `switch_keybinding_map`
+    pub name: &'static str,
+    /// Name shown in the command palette
+    pub label: &'static str,
+    pub description: &'static str,
+    /// Keymap JSON (a `KeymapConfig`)
+    source: &'static str,
+}
+
+/// Keymaps shipped with the editor
+pub const KEYMAP_PRESETS: &[KeymapPreset] = &[
+    KeymapPreset {
+        name: "default",
+        label: "Default",
+        description: "Switch to the default keybinding map",
+        source: include_str!("../../keymaps/default.json"),
+    },
+    KeymapPreset {
+        name: "emacs",
+        label: "Emacs",
+        description: "Switch to Emacs-style keybindings",
+        source: include_str!("../../keymaps/emacs.json"),
+    },
+    KeymapPreset {
+        name: "vscode",
+        label: "VSCode",
+        description: "Switch to VSCode-style keybindings",
+        source: include_str!("../../keymaps/vscode.json"),
+    },
+];
+
+impl KeymapPreset {
+    /// Look up a built-in preset by name
+    pub fn find(name: &str) -> Option<&'static KeymapPreset> {
+        KEYMAP_PRESETS.iter().find(|preset| preset.name == name)
+    }
+
+    /// The preset's keymap JSON
+    pub fn source(&self) -> &'static str {
+        self.source
+    }
+}
+
 /// Format a keybinding as a user-friendly string
 /// On macOS, this will show ⌘ instead of Ctrl for better UX
 pub fn format_keybinding(keycode: &KeyCode, modifiers: &KeyModifiers) -> String {
@@ -134,9 +183,25 @@ pub enum KeyContext {
     FileExplorer,
     /// Menu bar is active
     Menu,
+    /// Vim normal mode (when `editor.vim_mode` is enabled)
+    VimNormal,
+    /// Vim visual mode (when `editor.vim_mode` is enabled)
+    VimVisual,
 }
 
 impl KeyContext {
+    /// Every context, in the order bindings are listed
+    pub const ALL: [KeyContext; 8] = [
+        KeyContext::Global,
+        KeyContext::Normal,
+        KeyContext::Prompt,
+        KeyContext::Popup,
+        KeyContext::FileExplorer,
+        KeyContext::Menu,
+        KeyContext::VimNormal,
+        KeyContext::VimVisual,
+    ];
+
     /// Check if a context should allow input
     pub fn allows_text_input(&self) -> bool {
         matches!(self, KeyContext::Normal | KeyContext::Prompt)
@@ -151,10 +216,36 @@ impl KeyContext {
             "fileExplorer" | "file_explorer" => Some(KeyContext::FileExplorer),
             "normal" => Some(KeyContext::Normal),
             "menu" => Some(KeyContext::Menu),
+            "vimNormal" | "vim_normal" => Some(KeyContext::VimNormal),
+            "vimVisual" | "vim_visual" => Some(KeyContext::VimVisual),
             _ => None,
         }
     }
 
+    /// Parse a binding's "when" clause: a context, optionally narrowed to
+    /// buffers of one language (`"normal && language == 'rust'"`)
+    ///
+    /// A clause naming only a language applies in Normal, as does one with
+    /// no recognized context.
+    pub fn from_binding_when(when: Option<&str>) -> (Self, Option<String>) {
+        let mut context = None;
+        let mut language = None;
+        for part in when.unwrap_or_default().split("&&") {
+            let part = part.trim();
+            let value = part
+                .strip_prefix("language")
+                .and_then(|rest| rest.trim_start().strip_prefix("=="));
+            match value {
+                Some(value) => {
+                    let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+                    language = (!value.is_empty()).then(|| value.to_string());
+                }
+                None => context = context.or_else(|| Self::from_when_clause(part)),
+            }
+        }
+        (context.unwrap_or(KeyContext::Normal), language)
+    }
+
     /// Convert context to "when" clause string
     pub fn to_when_clause(self) -> &'static str {
         match self {
@@ -164,8 +255,96 @@ impl KeyContext {
             KeyContext::Popup => "popup",
             KeyContext::FileExplorer => "fileExplorer",
             KeyContext::Menu => "menu",
+            KeyContext::VimNormal => "vimNormal",
+            KeyContext::VimVisual => "vimVisual",
+        }
+    }
+}
+
+/// The contexts active at once, from the root (Normal) to the innermost
+/// (e.g., Normal > FileExplorer > Prompt)
+///
+/// The innermost context gets all of its bindings. Contexts beneath it only
+/// contribute application-wide actions, so keys don't leak through to the
+/// editor. Commands are available if they declare any context in the stack.
+///
+/// The stack also carries the active buffer's language, which selects the
+/// bindings scoped to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyContextStack {
+    contexts: Vec<KeyContext>,
+    language: Option<String>,
+}
+
+impl KeyContextStack {
+    /// A stack holding only `root`
+    pub fn new(root: KeyContext) -> Self {
+        Self {
+            contexts: vec![root],
+            language: None,
+        }
+    }
+
+    /// Set the active buffer's language (e.g. "rust")
+    pub fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
+
+    /// The active buffer's language, if known
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// Enter a nested context
+    pub fn push(&mut self, context: KeyContext) {
+        self.contexts.push(context);
+    }
+
+    /// Leave the innermost context (the root is never removed)
+    pub fn pop(&mut self) -> Option<KeyContext> {
+        if self.contexts.len() > 1 {
+            self.contexts.pop()
+        } else {
+            None
         }
     }
+
+    /// The innermost context, which receives keys first
+    pub fn top(&self) -> KeyContext {
+        *self.contexts.last().expect("context stack is never empty")
+    }
+
+    /// Whether a context is anywhere in the stack
+    pub fn contains(&self, context: KeyContext) -> bool {
+        self.contexts.contains(&context)
+    }
+
+    /// Contexts beneath the innermost one, nearest first
+    pub fn ancestors(&self) -> impl Iterator<Item = KeyContext> + '_ {
+        self.contexts.iter().rev().skip(1).copied()
+    }
+}
+
+impl From<KeyContext> for KeyContextStack {
+    /// A single context on top of Normal, the context everything else opens from
+    fn from(context: KeyContext) -> Self {
+        let mut stack = Self::new(KeyContext::Normal);
+        if context != KeyContext::Normal {
+            stack.push(context);
+        }
+        stack
+    }
+}
+
+impl std::fmt::Display for KeyContextStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.contexts.iter().map(|c| c.to_when_clause()).collect();
+        write!(f, "{}", names.join(" > "))?;
+        if let Some(language) = &self.language {
+            write!(f, " ({})", language)?;
+        }
+        Ok(())
+    }
 }
 
 /// High-level actions that can be performed in the editor
@@ -183,6 +362,7 @@ pub enum Action {
     MoveDown,
     MoveWordLeft,
     MoveWordRight,
+    MoveWordEnd,
     MoveLineStart,
     MoveLineEnd,
     MovePageUp,
@@ -197,6 +377,7 @@ pub enum Action {
     SelectDown,
     SelectWordLeft,
     SelectWordRight,
+    SelectWordEnd,
     SelectLineStart,
     SelectLineEnd,
     SelectDocumentStart,
@@ -207,6 +388,7 @@ pub enum Action {
     SelectWord,
     SelectLine,
     ExpandSelection,
+    SelectTextObject(String, bool), // Text object name, and whether to include its delimiters
 
     // Block/rectangular selection (column-wise)
     BlockSelectLeft,
@@ -221,6 +403,7 @@ pub enum Action {
     DeleteWordForward,
     DeleteLine,
     DeleteToLineEnd,
+    KillLine, // Cut to the end of the line (Emacs C-k)
     TransposeChars,
     OpenLine,
 
@@ -229,6 +412,7 @@ pub enum Action {
 
     // Selection
     SetMark,
+    ToggleSelectionMode,
 
     // Clipboard
     Copy,
@@ -246,7 +430,12 @@ pub enum Action {
     SaveAs,
     Open,
     New,
+    NewScratchBuffer,
     Close,
+    CloseAllBuffers,
+    CloseOtherBuffers,
+    CloseBuffersToRight,
+    CloseSavedBuffers,
     Quit,
     Revert,
     ToggleAutoRevert,
@@ -256,6 +445,7 @@ pub enum Action {
     GoToMatchingBracket,
     JumpToNextError,
     JumpToPreviousError,
+    ShowDiagnostics,
 
     // Smart editing
     SmartHome,
@@ -274,6 +464,10 @@ pub enum Action {
     ToggleSearchWholeWord,
     ToggleSearchRegex,
     ToggleSearchConfirmEach,
+    ToggleSearchInSelection,
+
+    // File prompts
+    PromptToggleHiddenFiles,
 
     // Macros
     StartMacroRecording,
@@ -299,17 +493,48 @@ pub enum Action {
     ScrollDown,
     ShowHelp,
     ShowKeyboardShortcuts,
+    ShowKeybindings,
+    BindKey,
     CommandPalette,
     ToggleLineWrap,
+    ToggleRedactSecrets,
+    LockScreen,
+    BufferStatistics,
+    ReloadEnvironment,
+    ToggleVimMode,
     ToggleComposeMode,
     SetComposeWidth,
     SelectTheme,
+    LintTheme,
+    ExportHtml,
+    ExportAnsi,
+    EvaluateSelection,
+    EvaluateSelectionInsert,
+    InsertSnippet,
+    RepeatLastPaletteQuery,
+    DismissStatusMessage,
 
     // Buffer/tab navigation
     NextBuffer,
     PrevBuffer,
     SwitchToPreviousTab,
     SwitchToTabByName,
+    NextRecentBuffer,
+    PrevRecentBuffer,
+    ReopenClosedBuffer,
+    ShowSaveHistory,
+    DiffBuffers,
+    ToggleScrollLock,
+    ZoomPane,
+    ToggleFocusMode,
+    ReflowParagraph,
+    ReflowToColumns,
+    ToggleAutoHardWrap,
+    CreateBufferGroup,
+    MoveToBufferGroup,
+    SwitchBufferGroup,
+    MoveTabLeft,
+    MoveTabRight,
 
     // Tab scrolling
     ScrollTabsLeft,
@@ -341,6 +566,8 @@ pub enum Action {
     PromptSelectNext,
     PromptPageUp,
     PromptPageDown,
+    PromptSelectFirst,
+    PromptSelectLast,
     PromptAcceptSuggestion,
     PromptMoveWordLeft,
     PromptMoveWordRight,
@@ -379,6 +606,8 @@ pub enum Action {
     FileExplorerExpand,
     FileExplorerCollapse,
     FileExplorerOpen,
+    FileExplorerOpenRight,
+    FileExplorerOpenBelow,
     FileExplorerRefresh,
     FileExplorerNewFile,
     FileExplorerNewDirectory,
@@ -386,10 +615,14 @@ pub enum Action {
     FileExplorerRename,
     FileExplorerToggleHidden,
     FileExplorerToggleGitignored,
+    FileExplorerFilter,
+    OpenInExternalProgram,
 
     // LSP operations
     LspCompletion,
     LspGotoDefinition,
+    LspGotoDefinitionRight,
+    LspGotoDefinitionBelow,
     LspReferences,
     LspRename,
     LspHover,
@@ -402,17 +635,24 @@ pub enum Action {
     // View toggles
     ToggleLineNumbers,
     ToggleMouseCapture,
+    TogglePerformanceOverlay,
     SetBackground,
     SetBackgroundBlend,
 
     // Config operations
     DumpConfig,
 
+    // Session
+    RestoreSessionSnapshot,
+    SaveSessionAs,
+    SwitchSession,
+
     // Search and replace
     Search,
     FindInSelection,
     FindNext,
     FindPrevious,
+    SearchToQuickfix, // List all matches of the current search in the quickfix panel
     Replace,
     QueryReplace, // Interactive replace (y/n/!/q for each match)
 
@@ -429,6 +669,23 @@ pub enum Action {
     // Keybinding map switching
     SwitchKeybindingMap(String), // Switch to a named keybinding map (e.g., "default", "emacs", "vscode")
 
+    // Project tasks (.fresh/tasks.toml)
+    RunProjectTask(String), // Run a project task by name
+    ReloadProjectTasks,
+
+    // Composite commands: run palette commands by name, in order
+    RunCommandSequence(Vec<String>),
+
+    // Background tasks
+    CancelRunningTask,
+
+    // Symbol index
+    GoToWorkspaceSymbol,
+    RebuildSymbolIndex,
+
+    // Search index
+    RebuildSearchIndex,
+
     // Plugin custom actions
     PluginAction(String),
 
@@ -456,6 +713,7 @@ impl Action {
             "move_down" => Some(Action::MoveDown),
             "move_word_left" => Some(Action::MoveWordLeft),
             "move_word_right" => Some(Action::MoveWordRight),
+            "move_word_end" => Some(Action::MoveWordEnd),
             "move_line_start" => Some(Action::MoveLineStart),
             "move_line_end" => Some(Action::MoveLineEnd),
             "move_page_up" => Some(Action::MovePageUp),
@@ -469,6 +727,7 @@ impl Action {
             "select_down" => Some(Action::SelectDown),
             "select_word_left" => Some(Action::SelectWordLeft),
             "select_word_right" => Some(Action::SelectWordRight),
+            "select_word_end" => Some(Action::SelectWordEnd),
             "select_line_start" => Some(Action::SelectLineStart),
             "select_line_end" => Some(Action::SelectLineEnd),
             "select_document_start" => Some(Action::SelectDocumentStart),
@@ -479,6 +738,14 @@ impl Action {
             "select_word" => Some(Action::SelectWord),
             "select_line" => Some(Action::SelectLine),
             "expand_selection" => Some(Action::ExpandSelection),
+            "select_text_object" => {
+                let object = args.get("object")?.as_str()?;
+                let around = args
+                    .get("around")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                Some(Action::SelectTextObject(object.to_string(), around))
+            }
 
             // Block/rectangular selection
             "block_select_left" => Some(Action::BlockSelectLeft),
@@ -492,10 +759,12 @@ impl Action {
             "delete_word_forward" => Some(Action::DeleteWordForward),
             "delete_line" => Some(Action::DeleteLine),
             "delete_to_line_end" => Some(Action::DeleteToLineEnd),
+            "kill_line" => Some(Action::KillLine),
             "transpose_chars" => Some(Action::TransposeChars),
             "open_line" => Some(Action::OpenLine),
             "recenter" => Some(Action::Recenter),
             "set_mark" => Some(Action::SetMark),
+            "toggle_selection_mode" => Some(Action::ToggleSelectionMode),
 
             "copy" => Some(Action::Copy),
             "cut" => Some(Action::Cut),
@@ -510,7 +779,12 @@ impl Action {
             "save_as" => Some(Action::SaveAs),
             "open" => Some(Action::Open),
             "new" => Some(Action::New),
+            "new_scratch_buffer" => Some(Action::NewScratchBuffer),
             "close" => Some(Action::Close),
+            "close_all_buffers" => Some(Action::CloseAllBuffers),
+            "close_other_buffers" => Some(Action::CloseOtherBuffers),
+            "close_buffers_to_right" => Some(Action::CloseBuffersToRight),
+            "close_saved_buffers" => Some(Action::CloseSavedBuffers),
             "quit" => Some(Action::Quit),
             "revert" => Some(Action::Revert),
             "toggle_auto_revert" => Some(Action::ToggleAutoRevert),
@@ -518,6 +792,7 @@ impl Action {
             "goto_matching_bracket" => Some(Action::GoToMatchingBracket),
             "jump_to_next_error" => Some(Action::JumpToNextError),
             "jump_to_previous_error" => Some(Action::JumpToPreviousError),
+            "show_diagnostics" => Some(Action::ShowDiagnostics),
 
             "smart_home" => Some(Action::SmartHome),
             "indent_selection" => Some(Action::IndentSelection),
@@ -551,6 +826,8 @@ impl Action {
             "toggle_search_whole_word" => Some(Action::ToggleSearchWholeWord),
             "toggle_search_regex" => Some(Action::ToggleSearchRegex),
             "toggle_search_confirm_each" => Some(Action::ToggleSearchConfirmEach),
+            "toggle_search_in_selection" => Some(Action::ToggleSearchInSelection),
+            "prompt_toggle_hidden_files" => Some(Action::PromptToggleHiddenFiles),
 
             "start_macro_recording" => Some(Action::StartMacroRecording),
             "stop_macro_recording" => Some(Action::StopMacroRecording),
@@ -589,13 +866,41 @@ impl Action {
             "scroll_down" => Some(Action::ScrollDown),
             "show_help" => Some(Action::ShowHelp),
             "keyboard_shortcuts" => Some(Action::ShowKeyboardShortcuts),
+            "show_keybindings" => Some(Action::ShowKeybindings),
+            "bind_key" => Some(Action::BindKey),
             "command_palette" => Some(Action::CommandPalette),
             "toggle_line_wrap" => Some(Action::ToggleLineWrap),
+            "toggle_redact_secrets" => Some(Action::ToggleRedactSecrets),
+            "lock_screen" => Some(Action::LockScreen),
+            "buffer_statistics" => Some(Action::BufferStatistics),
+            "reload_environment" => Some(Action::ReloadEnvironment),
+            "toggle_vim_mode" => Some(Action::ToggleVimMode),
             "toggle_compose_mode" => Some(Action::ToggleComposeMode),
             "set_compose_width" => Some(Action::SetComposeWidth),
+            "dismiss_status_message" => Some(Action::DismissStatusMessage),
 
             "next_buffer" => Some(Action::NextBuffer),
             "prev_buffer" => Some(Action::PrevBuffer),
+            "switch_to_previous_tab" => Some(Action::SwitchToPreviousTab),
+            "switch_to_tab_by_name" => Some(Action::SwitchToTabByName),
+            "move_tab_left" => Some(Action::MoveTabLeft),
+            "move_tab_right" => Some(Action::MoveTabRight),
+            "scroll_tabs_left" => Some(Action::ScrollTabsLeft),
+            "scroll_tabs_right" => Some(Action::ScrollTabsRight),
+            "next_recent_buffer" => Some(Action::NextRecentBuffer),
+            "prev_recent_buffer" => Some(Action::PrevRecentBuffer),
+            "reopen_closed_buffer" => Some(Action::ReopenClosedBuffer),
+            "show_save_history" => Some(Action::ShowSaveHistory),
+            "diff_buffers" => Some(Action::DiffBuffers),
+            "toggle_scroll_lock" => Some(Action::ToggleScrollLock),
+            "zoom_pane" => Some(Action::ZoomPane),
+            "toggle_focus_mode" => Some(Action::ToggleFocusMode),
+            "reflow_paragraph" => Some(Action::ReflowParagraph),
+            "reflow_to_columns" => Some(Action::ReflowToColumns),
+            "toggle_auto_hard_wrap" => Some(Action::ToggleAutoHardWrap),
+            "create_buffer_group" => Some(Action::CreateBufferGroup),
+            "move_to_buffer_group" => Some(Action::MoveToBufferGroup),
+            "switch_buffer_group" => Some(Action::SwitchBufferGroup),
 
             "navigate_back" => Some(Action::NavigateBack),
             "navigate_forward" => Some(Action::NavigateForward),
@@ -619,6 +924,8 @@ impl Action {
             "prompt_select_next" => Some(Action::PromptSelectNext),
             "prompt_page_up" => Some(Action::PromptPageUp),
             "prompt_page_down" => Some(Action::PromptPageDown),
+            "prompt_select_first" => Some(Action::PromptSelectFirst),
+            "prompt_select_last" => Some(Action::PromptSelectLast),
             "prompt_accept_suggestion" => Some(Action::PromptAcceptSuggestion),
             "prompt_delete_word_forward" => Some(Action::PromptDeleteWordForward),
             "prompt_delete_word_backward" => Some(Action::PromptDeleteWordBackward),
@@ -654,6 +961,8 @@ impl Action {
             "file_explorer_expand" => Some(Action::FileExplorerExpand),
             "file_explorer_collapse" => Some(Action::FileExplorerCollapse),
             "file_explorer_open" => Some(Action::FileExplorerOpen),
+            "file_explorer_open_right" => Some(Action::FileExplorerOpenRight),
+            "file_explorer_open_below" => Some(Action::FileExplorerOpenBelow),
             "file_explorer_refresh" => Some(Action::FileExplorerRefresh),
             "file_explorer_new_file" => Some(Action::FileExplorerNewFile),
             "file_explorer_new_directory" => Some(Action::FileExplorerNewDirectory),
@@ -661,9 +970,13 @@ impl Action {
             "file_explorer_rename" => Some(Action::FileExplorerRename),
             "file_explorer_toggle_hidden" => Some(Action::FileExplorerToggleHidden),
             "file_explorer_toggle_gitignored" => Some(Action::FileExplorerToggleGitignored),
+            "file_explorer_filter" => Some(Action::FileExplorerFilter),
+            "open_in_external_program" => Some(Action::OpenInExternalProgram),
 
             "lsp_completion" => Some(Action::LspCompletion),
             "lsp_goto_definition" => Some(Action::LspGotoDefinition),
+            "lsp_goto_definition_right" => Some(Action::LspGotoDefinitionRight),
+            "lsp_goto_definition_below" => Some(Action::LspGotoDefinitionBelow),
             "lsp_references" => Some(Action::LspReferences),
             "lsp_rename" => Some(Action::LspRename),
             "lsp_hover" => Some(Action::LspHover),
@@ -675,16 +988,28 @@ impl Action {
 
             "toggle_line_numbers" => Some(Action::ToggleLineNumbers),
             "toggle_mouse_capture" => Some(Action::ToggleMouseCapture),
+            "toggle_performance_overlay" => Some(Action::TogglePerformanceOverlay),
             "set_background" => Some(Action::SetBackground),
             "set_background_blend" => Some(Action::SetBackgroundBlend),
             "select_theme" => Some(Action::SelectTheme),
+            "lint_theme" => Some(Action::LintTheme),
+            "export_html" => Some(Action::ExportHtml),
+            "export_ansi" => Some(Action::ExportAnsi),
+            "evaluate_selection" => Some(Action::EvaluateSelection),
+            "evaluate_selection_insert" => Some(Action::EvaluateSelectionInsert),
+            "insert_snippet" => Some(Action::InsertSnippet),
+            "repeat_last_palette_query" => Some(Action::RepeatLastPaletteQuery),
 
             "dump_config" => Some(Action::DumpConfig),
+            "restore_session_snapshot" => Some(Action::RestoreSessionSnapshot),
+            "save_session_as" => Some(Action::SaveSessionAs),
+            "switch_session" => Some(Action::SwitchSession),
 
             "search" => Some(Action::Search),
             "find_in_selection" => Some(Action::FindInSelection),
             "find_next" => Some(Action::FindNext),
             "find_previous" => Some(Action::FindPrevious),
+            "search_to_quickfix" => Some(Action::SearchToQuickfix),
             "replace" => Some(Action::Replace),
             "query_replace" => Some(Action::QueryReplace),
 
@@ -705,9 +1030,342 @@ impl Action {
                 Some(Action::SwitchKeybindingMap(map_name.to_string()))
             }
 
+            "run_project_task" => {
+                let name = args.get("name")?.as_str()?;
+                Some(Action::RunProjectTask(name.to_string()))
+            }
+            "reload_project_tasks" => Some(Action::ReloadProjectTasks),
+
+            "run_commands" => {
+                let names = args.get("commands")?.as_array()?;
+                names
+                    .iter()
+                    .map(|name| name.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Action::RunCommandSequence)
+            }
+
+            "cancel_running_task" => Some(Action::CancelRunningTask),
+
+            "go_to_workspace_symbol" => Some(Action::GoToWorkspaceSymbol),
+            "rebuild_symbol_index" => Some(Action::RebuildSymbolIndex),
+            "rebuild_search_index" => Some(Action::RebuildSearchIndex),
+
+            "plugin_action" => {
+                let name = args.get("name")?.as_str()?;
+                Some(Action::PluginAction(name.to_string()))
+            }
+
             _ => None,
         }
     }
+
+    /// Name and arguments of this action, in the form `from_str` parses
+    ///
+    /// This is the stable form actions are written in wherever they outlive
+    /// the editor process (keymaps, recorded macros, scripts). Returns None
+    /// for `Action::None`.
+    pub fn to_name_and_args(&self) -> Option<(&'static str, HashMap<String, serde_json::Value>)> {
+        let arg = |key: &str, value: String| {
+            HashMap::from([(key.to_string(), serde_json::Value::String(value))])
+        };
+
+        let name = match self {
+            Action::InsertChar(c) => return Some(("insert_char", arg("char", c.to_string()))),
+            Action::InsertNewline => "insert_newline",
+            Action::InsertTab => "insert_tab",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveWordLeft => "move_word_left",
+            Action::MoveWordRight => "move_word_right",
+            Action::MoveWordEnd => "move_word_end",
+            Action::MoveLineStart => "move_line_start",
+            Action::MoveLineEnd => "move_line_end",
+            Action::MovePageUp => "move_page_up",
+            Action::MovePageDown => "move_page_down",
+            Action::MoveDocumentStart => "move_document_start",
+            Action::MoveDocumentEnd => "move_document_end",
+            Action::SelectLeft => "select_left",
+            Action::SelectRight => "select_right",
+            Action::SelectUp => "select_up",
+            Action::SelectDown => "select_down",
+            Action::SelectWordLeft => "select_word_left",
+            Action::SelectWordRight => "select_word_right",
+            Action::SelectWordEnd => "select_word_end",
+            Action::SelectLineStart => "select_line_start",
+            Action::SelectLineEnd => "select_line_end",
+            Action::SelectDocumentStart => "select_document_start",
+            Action::SelectDocumentEnd => "select_document_end",
+            Action::SelectPageUp => "select_page_up",
+            Action::SelectPageDown => "select_page_down",
+            Action::SelectAll => "select_all",
+            Action::SelectWord => "select_word",
+            Action::SelectLine => "select_line",
+            Action::ExpandSelection => "expand_selection",
+            Action::SelectTextObject(object, around) => {
+                return Some((
+                    "select_text_object",
+                    HashMap::from([
+                        (
+                            "object".to_string(),
+                            serde_json::Value::String(object.clone()),
+                        ),
+                        ("around".to_string(), serde_json::Value::Bool(*around)),
+                    ]),
+                ));
+            }
+            Action::BlockSelectLeft => "block_select_left",
+            Action::BlockSelectRight => "block_select_right",
+            Action::BlockSelectUp => "block_select_up",
+            Action::BlockSelectDown => "block_select_down",
+            Action::DeleteBackward => "delete_backward",
+            Action::DeleteForward => "delete_forward",
+            Action::DeleteWordBackward => "delete_word_backward",
+            Action::DeleteWordForward => "delete_word_forward",
+            Action::DeleteLine => "delete_line",
+            Action::DeleteToLineEnd => "delete_to_line_end",
+            Action::KillLine => "kill_line",
+            Action::TransposeChars => "transpose_chars",
+            Action::OpenLine => "open_line",
+            Action::Recenter => "recenter",
+            Action::SetMark => "set_mark",
+            Action::ToggleSelectionMode => "toggle_selection_mode",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::Paste => "paste",
+            Action::AddCursorAbove => "add_cursor_above",
+            Action::AddCursorBelow => "add_cursor_below",
+            Action::AddCursorNextMatch => "add_cursor_next_match",
+            Action::RemoveSecondaryCursors => "remove_secondary_cursors",
+            Action::Save => "save",
+            Action::SaveAs => "save_as",
+            Action::Open => "open",
+            Action::New => "new",
+            Action::NewScratchBuffer => "new_scratch_buffer",
+            Action::Close => "close",
+            Action::CloseAllBuffers => "close_all_buffers",
+            Action::CloseOtherBuffers => "close_other_buffers",
+            Action::CloseBuffersToRight => "close_buffers_to_right",
+            Action::CloseSavedBuffers => "close_saved_buffers",
+            Action::Quit => "quit",
+            Action::Revert => "revert",
+            Action::ToggleAutoRevert => "toggle_auto_revert",
+            Action::GotoLine => "goto_line",
+            Action::GoToMatchingBracket => "goto_matching_bracket",
+            Action::JumpToNextError => "jump_to_next_error",
+            Action::JumpToPreviousError => "jump_to_previous_error",
+            Action::ShowDiagnostics => "show_diagnostics",
+            Action::SmartHome => "smart_home",
+            Action::IndentSelection => "indent_selection",
+            Action::DedentSelection => "dedent_selection",
+            Action::ToggleComment => "toggle_comment",
+            Action::SetBookmark(c) => return Some(("set_bookmark", arg("char", c.to_string()))),
+            Action::JumpToBookmark(c) => {
+                return Some(("jump_to_bookmark", arg("char", c.to_string())))
+            }
+            Action::ClearBookmark(c) => {
+                return Some(("clear_bookmark", arg("char", c.to_string())))
+            }
+            Action::ListBookmarks => "list_bookmarks",
+            Action::ToggleSearchCaseSensitive => "toggle_search_case_sensitive",
+            Action::ToggleSearchWholeWord => "toggle_search_whole_word",
+            Action::ToggleSearchRegex => "toggle_search_regex",
+            Action::ToggleSearchConfirmEach => "toggle_search_confirm_each",
+            Action::ToggleSearchInSelection => "toggle_search_in_selection",
+            Action::PromptToggleHiddenFiles => "prompt_toggle_hidden_files",
+            Action::StartMacroRecording => "start_macro_recording",
+            Action::StopMacroRecording => "stop_macro_recording",
+            Action::PlayMacro(c) => return Some(("play_macro", arg("char", c.to_string()))),
+            Action::ToggleMacroRecording(c) => {
+                return Some(("toggle_macro_recording", arg("char", c.to_string())))
+            }
+            Action::ShowMacro(c) => return Some(("show_macro", arg("char", c.to_string()))),
+            Action::ListMacros => "list_macros",
+            Action::PromptRecordMacro => "prompt_record_macro",
+            Action::PromptPlayMacro => "prompt_play_macro",
+            Action::PlayLastMacro => "play_last_macro",
+            Action::PromptSetBookmark => "prompt_set_bookmark",
+            Action::PromptJumpToBookmark => "prompt_jump_to_bookmark",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::ShowHelp => "show_help",
+            Action::ShowKeyboardShortcuts => "keyboard_shortcuts",
+            Action::ShowKeybindings => "show_keybindings",
+            Action::BindKey => "bind_key",
+            Action::CommandPalette => "command_palette",
+            Action::ToggleLineWrap => "toggle_line_wrap",
+            Action::ToggleRedactSecrets => "toggle_redact_secrets",
+            Action::LockScreen => "lock_screen",
+            Action::BufferStatistics => "buffer_statistics",
+            Action::ReloadEnvironment => "reload_environment",
+            Action::ToggleVimMode => "toggle_vim_mode",
+            Action::ToggleComposeMode => "toggle_compose_mode",
+            Action::SetComposeWidth => "set_compose_width",
+            Action::SelectTheme => "select_theme",
+            Action::LintTheme => "lint_theme",
+            Action::ExportHtml => "export_html",
+            Action::ExportAnsi => "export_ansi",
+            Action::EvaluateSelection => "evaluate_selection",
+            Action::EvaluateSelectionInsert => "evaluate_selection_insert",
+            Action::InsertSnippet => "insert_snippet",
+            Action::RepeatLastPaletteQuery => "repeat_last_palette_query",
+            Action::DismissStatusMessage => "dismiss_status_message",
+            Action::NextBuffer => "next_buffer",
+            Action::PrevBuffer => "prev_buffer",
+            Action::SwitchToPreviousTab => "switch_to_previous_tab",
+            Action::SwitchToTabByName => "switch_to_tab_by_name",
+            Action::MoveTabLeft => "move_tab_left",
+            Action::MoveTabRight => "move_tab_right",
+            Action::ScrollTabsLeft => "scroll_tabs_left",
+            Action::ScrollTabsRight => "scroll_tabs_right",
+            Action::NextRecentBuffer => "next_recent_buffer",
+            Action::PrevRecentBuffer => "prev_recent_buffer",
+            Action::ReopenClosedBuffer => "reopen_closed_buffer",
+            Action::ShowSaveHistory => "show_save_history",
+            Action::DiffBuffers => "diff_buffers",
+            Action::ToggleScrollLock => "toggle_scroll_lock",
+            Action::ZoomPane => "zoom_pane",
+            Action::ToggleFocusMode => "toggle_focus_mode",
+            Action::ReflowParagraph => "reflow_paragraph",
+            Action::ReflowToColumns => "reflow_to_columns",
+            Action::ToggleAutoHardWrap => "toggle_auto_hard_wrap",
+            Action::CreateBufferGroup => "create_buffer_group",
+            Action::MoveToBufferGroup => "move_to_buffer_group",
+            Action::SwitchBufferGroup => "switch_buffer_group",
+            Action::NavigateBack => "navigate_back",
+            Action::NavigateForward => "navigate_forward",
+            Action::SplitHorizontal => "split_horizontal",
+            Action::SplitVertical => "split_vertical",
+            Action::CloseSplit => "close_split",
+            Action::NextSplit => "next_split",
+            Action::PrevSplit => "prev_split",
+            Action::IncreaseSplitSize => "increase_split_size",
+            Action::DecreaseSplitSize => "decrease_split_size",
+            Action::PromptConfirm => "prompt_confirm",
+            Action::PromptCancel => "prompt_cancel",
+            Action::PromptBackspace => "prompt_backspace",
+            Action::PromptDelete => "prompt_delete",
+            Action::PromptMoveLeft => "prompt_move_left",
+            Action::PromptMoveRight => "prompt_move_right",
+            Action::PromptMoveStart => "prompt_move_start",
+            Action::PromptMoveEnd => "prompt_move_end",
+            Action::PromptSelectPrev => "prompt_select_prev",
+            Action::PromptSelectNext => "prompt_select_next",
+            Action::PromptPageUp => "prompt_page_up",
+            Action::PromptPageDown => "prompt_page_down",
+            Action::PromptSelectFirst => "prompt_select_first",
+            Action::PromptSelectLast => "prompt_select_last",
+            Action::PromptAcceptSuggestion => "prompt_accept_suggestion",
+            Action::PromptMoveWordLeft => "prompt_move_word_left",
+            Action::PromptMoveWordRight => "prompt_move_word_right",
+            Action::PromptDeleteWordForward => "prompt_delete_word_forward",
+            Action::PromptDeleteWordBackward => "prompt_delete_word_backward",
+            Action::PromptDeleteToLineEnd => "prompt_delete_to_line_end",
+            Action::PromptCopy => "prompt_copy",
+            Action::PromptCut => "prompt_cut",
+            Action::PromptPaste => "prompt_paste",
+            Action::PromptMoveLeftSelecting => "prompt_move_left_selecting",
+            Action::PromptMoveRightSelecting => "prompt_move_right_selecting",
+            Action::PromptMoveHomeSelecting => "prompt_move_home_selecting",
+            Action::PromptMoveEndSelecting => "prompt_move_end_selecting",
+            Action::PromptSelectWordLeft => "prompt_select_word_left",
+            Action::PromptSelectWordRight => "prompt_select_word_right",
+            Action::PromptSelectAll => "prompt_select_all",
+            Action::PopupSelectNext => "popup_select_next",
+            Action::PopupSelectPrev => "popup_select_prev",
+            Action::PopupPageUp => "popup_page_up",
+            Action::PopupPageDown => "popup_page_down",
+            Action::PopupConfirm => "popup_confirm",
+            Action::PopupCancel => "popup_cancel",
+            Action::ToggleFileExplorer => "toggle_file_explorer",
+            Action::FocusFileExplorer => "focus_file_explorer",
+            Action::FocusEditor => "focus_editor",
+            Action::FileExplorerUp => "file_explorer_up",
+            Action::FileExplorerDown => "file_explorer_down",
+            Action::FileExplorerPageUp => "file_explorer_page_up",
+            Action::FileExplorerPageDown => "file_explorer_page_down",
+            Action::FileExplorerExpand => "file_explorer_expand",
+            Action::FileExplorerCollapse => "file_explorer_collapse",
+            Action::FileExplorerOpen => "file_explorer_open",
+            Action::FileExplorerOpenRight => "file_explorer_open_right",
+            Action::FileExplorerOpenBelow => "file_explorer_open_below",
+            Action::FileExplorerRefresh => "file_explorer_refresh",
+            Action::FileExplorerNewFile => "file_explorer_new_file",
+            Action::FileExplorerNewDirectory => "file_explorer_new_directory",
+            Action::FileExplorerDelete => "file_explorer_delete",
+            Action::FileExplorerRename => "file_explorer_rename",
+            Action::FileExplorerToggleHidden => "file_explorer_toggle_hidden",
+            Action::FileExplorerToggleGitignored => "file_explorer_toggle_gitignored",
+            Action::FileExplorerFilter => "file_explorer_filter",
+            Action::OpenInExternalProgram => "open_in_external_program",
+            Action::LspCompletion => "lsp_completion",
+            Action::LspGotoDefinition => "lsp_goto_definition",
+            Action::LspGotoDefinitionRight => "lsp_goto_definition_right",
+            Action::LspGotoDefinitionBelow => "lsp_goto_definition_below",
+            Action::LspReferences => "lsp_references",
+            Action::LspRename => "lsp_rename",
+            Action::LspHover => "lsp_hover",
+            Action::LspSignatureHelp => "lsp_signature_help",
+            Action::LspCodeActions => "lsp_code_actions",
+            Action::LspRestart => "lsp_restart",
+            Action::LspStop => "lsp_stop",
+            Action::ToggleInlayHints => "toggle_inlay_hints",
+            Action::ToggleLineNumbers => "toggle_line_numbers",
+            Action::ToggleMouseCapture => "toggle_mouse_capture",
+            Action::TogglePerformanceOverlay => "toggle_performance_overlay",
+            Action::SetBackground => "set_background",
+            Action::SetBackgroundBlend => "set_background_blend",
+            Action::DumpConfig => "dump_config",
+            Action::RestoreSessionSnapshot => "restore_session_snapshot",
+            Action::SaveSessionAs => "save_session_as",
+            Action::SwitchSession => "switch_session",
+            Action::Search => "search",
+            Action::FindInSelection => "find_in_selection",
+            Action::FindNext => "find_next",
+            Action::FindPrevious => "find_previous",
+            Action::SearchToQuickfix => "search_to_quickfix",
+            Action::Replace => "replace",
+            Action::QueryReplace => "query_replace",
+            Action::MenuActivate => "menu_activate",
+            Action::MenuClose => "menu_close",
+            Action::MenuLeft => "menu_left",
+            Action::MenuRight => "menu_right",
+            Action::MenuUp => "menu_up",
+            Action::MenuDown => "menu_down",
+            Action::MenuExecute => "menu_execute",
+            Action::MenuOpen(value) => return Some(("menu_open", arg("name", value.clone()))),
+            Action::SwitchKeybindingMap(value) => {
+                return Some(("switch_keybinding_map", arg("map", value.clone())))
+            }
+            Action::RunProjectTask(value) => {
+                return Some(("run_project_task", arg("name", value.clone())))
+            }
+            Action::ReloadProjectTasks => "reload_project_tasks",
+            Action::RunCommandSequence(names) => {
+                let names = names.iter().cloned().map(serde_json::Value::String);
+                return Some((
+                    "run_commands",
+                    HashMap::from([(
+                        "commands".to_string(),
+                        serde_json::Value::Array(names.collect()),
+                    )]),
+                ));
+            }
+            Action::CancelRunningTask => "cancel_running_task",
+            Action::GoToWorkspaceSymbol => "go_to_workspace_symbol",
+            Action::RebuildSymbolIndex => "rebuild_symbol_index",
+            Action::RebuildSearchIndex => "rebuild_search_index",
+            Action::PluginAction(value) => {
+                return Some(("plugin_action", arg("name", value.clone())))
+            }
+            Action::None => return None,
+        };
+        Some((name, HashMap::new()))
+    }
 }
 
 /// Result of chord resolution
@@ -721,6 +1379,44 @@ pub enum ChordResolution {
     NoMatch,
 }
 
+/// A key that can follow a pending chord
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChordContinuation {
+    /// The next key
+    pub key: (KeyCode, KeyModifiers),
+    /// The action it runs, or None if it only leads to longer chords
+    pub action: Option<Action>,
+}
+
+/// A key or chord as it is bound once custom bindings override the keymap's
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingInfo {
+    pub context: KeyContext,
+    /// The language the binding applies to, if only one
+    pub language: Option<String>,
+    /// The key, or the chord's keys separated by spaces (e.g., "Ctrl+K Ctrl+S")
+    pub keys: String,
+    pub action: Action,
+    /// Whether the binding comes from the config or keybindings file rather
+    /// than the keymap
+    pub custom: bool,
+}
+
+/// Keys bound to more than one action in one context of one binding list
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingConflict {
+    pub context: KeyContext,
+    /// The language the keys are bound for, if only one
+    pub language: Option<String>,
+    /// Formatted like `BindingInfo::keys`
+    pub keys: String,
+    /// The actions in the order they were bound; the last one wins
+    pub actions: Vec<Action>,
+}
+
+/// A language's key bindings: key sequence -> (action, whether it is custom)
+type LanguageKeymap = HashMap<Vec<(KeyCode, KeyModifiers)>, (Action, bool)>;
+
 /// Resolves key events to actions based on configuration
 #[derive(Clone)]
 pub struct KeybindingResolver {
@@ -737,6 +1433,11 @@ pub struct KeybindingResolver {
 
     /// Default chord bindings for each context
     default_chord_bindings: HashMap<KeyContext, HashMap<Vec<(KeyCode, KeyModifiers)>, Action>>,
+
+    /// Bindings scoped to a buffer language, by context and language; single
+    /// keys are one-key sequences. Each action says whether it is custom
+    /// (custom bindings replace the keymap's)
+    language_bindings: HashMap<(KeyContext, String), LanguageKeymap>,
 }
 
 impl KeybindingResolver {
@@ -747,12 +1448,28 @@ impl KeybindingResolver {
             default_bindings: HashMap::new(),
             chord_bindings: HashMap::new(),
             default_chord_bindings: HashMap::new(),
+            language_bindings: HashMap::new(),
         };
 
         // Load bindings from the active keymap (with inheritance resolution) into default_bindings
-        let map_bindings = config.resolve_keymap(&config.active_keybinding_map);
+        let map_name = if config.has_keymap(&config.active_keybinding_map) {
+            config.active_keybinding_map.as_str()
+        } else {
+            tracing::warn!(
+                "Unknown keybinding map '{}', using the default map",
+                config.active_keybinding_map
+            );
+            "default"
+        };
+        let map_bindings = config.resolve_keymap(map_name);
         resolver.load_default_bindings_from_vec(&map_bindings);
 
+        // The vim layer adds its own contexts on top of whichever map is active
+        if config.editor.vim_mode {
+            let vim_bindings = config.resolve_keymap("vim");
+            resolver.load_default_bindings_from_vec(&vim_bindings);
+        }
+
         // Then, load custom keybindings (these override the default map bindings)
         resolver.load_bindings_from_vec(&config.keybindings);
 
@@ -763,11 +1480,11 @@ impl KeybindingResolver {
     fn load_default_bindings_from_vec(&mut self, bindings: &[crate::config::Keybinding]) {
         for binding in bindings {
             // Determine context from "when" clause
-            let context = if let Some(ref when) = binding.when {
-                KeyContext::from_when_clause(when).unwrap_or(KeyContext::Normal)
-            } else {
-                KeyContext::Normal
-            };
+            let (context, language) = KeyContext::from_binding_when(binding.when.as_deref());
+            if let Some(language) = language {
+                self.insert_language_binding(context, language, binding, false);
+                continue;
+            }
 
             if let Some(action) = Action::from_str(&binding.action, &binding.args) {
                 // Check if this is a chord binding (has keys field)
@@ -858,11 +1575,11 @@ impl KeybindingResolver {
     fn load_bindings_from_vec(&mut self, bindings: &[crate::config::Keybinding]) {
         for binding in bindings {
             // Determine context from "when" clause
-            let context = if let Some(ref when) = binding.when {
-                KeyContext::from_when_clause(when).unwrap_or(KeyContext::Normal)
-            } else {
-                KeyContext::Normal
-            };
+            let (context, language) = KeyContext::from_binding_when(binding.when.as_deref());
+            if let Some(language) = language {
+                self.insert_language_binding(context, language, binding, true);
+                continue;
+            }
 
             if let Some(action) = Action::from_str(&binding.action, &binding.args) {
                 // Check if this is a chord binding (has keys field)
@@ -898,6 +1615,76 @@ impl KeybindingResolver {
         }
     }
 
+    /// Add a binding scoped to `language`
+    fn insert_language_binding(
+        &mut self,
+        context: KeyContext,
+        language: String,
+        binding: &crate::config::Keybinding,
+        custom: bool,
+    ) {
+        let Some(action) = Action::from_str(&binding.action, &binding.args) else {
+            return;
+        };
+        let Some(sequence) = Self::binding_sequence(binding) else {
+            return;
+        };
+        self.language_bindings
+            .entry((context, language))
+            .or_default()
+            .insert(sequence, (action, custom));
+    }
+
+    /// The keys of a binding: its chord, or its single key; None if a key
+    /// can't be parsed
+    fn binding_sequence(
+        binding: &crate::config::Keybinding,
+    ) -> Option<Vec<(KeyCode, KeyModifiers)>> {
+        if binding.keys.is_empty() {
+            Self::parse_key(&binding.key)
+                .map(|key| vec![(key, Self::parse_modifiers(&binding.modifiers))])
+        } else {
+            binding
+                .keys
+                .iter()
+                .map(|press| {
+                    Self::parse_key(&press.key)
+                        .map(|key| (key, Self::parse_modifiers(&press.modifiers)))
+                })
+                .collect()
+        }
+    }
+
+    /// Bindings for the stack's language in its innermost context
+    fn language_bindings_for(&self, stack: &KeyContextStack) -> Option<&LanguageKeymap> {
+        let language = stack.language()?;
+        self.language_bindings
+            .get(&(stack.top(), language.to_string()))
+    }
+
+    /// Chords (of two keys or more) for the stack's language; its single
+    /// keys are resolved with the other single keys
+    fn language_chords(
+        &self,
+        stack: &KeyContextStack,
+    ) -> Option<HashMap<Vec<(KeyCode, KeyModifiers)>, Action>> {
+        self.language_bindings_for(stack).map(|bindings| {
+            bindings
+                .iter()
+                .filter(|(sequence, _)| sequence.len() > 1)
+                .map(|(sequence, (action, _))| (sequence.clone(), action.clone()))
+                .collect()
+        })
+    }
+
+    /// Load the user's bindings from the key bindings file
+    ///
+    /// Like custom bindings from the config, these take priority over the
+    /// keymap's defaults; loaded last, they also win over the config's.
+    pub fn load_user_bindings(&mut self, bindings: &[crate::config::Keybinding]) {
+        self.load_bindings_from_vec(bindings);
+    }
+
     /// Check if an action is application-wide (should be accessible in all contexts)
     fn is_application_wide_action(action: &Action) -> bool {
         matches!(
@@ -923,32 +1710,53 @@ impl KeybindingResolver {
         event: &KeyEvent,
         context: KeyContext,
     ) -> ChordResolution {
+        self.resolve_chord_in_stack(chord_state, event, &KeyContextStack::from(context))
+    }
+
+    /// Resolve a key event with chord state in the stack's innermost context,
+    /// including the bindings for the stack's language
+    pub fn resolve_chord_in_stack(
+        &self,
+        chord_state: &[(KeyCode, KeyModifiers)],
+        event: &KeyEvent,
+        stack: &KeyContextStack,
+    ) -> ChordResolution {
+        let context = stack.top();
+
         // Build the full sequence: existing chord state + new key
         let mut full_sequence = chord_state.to_vec();
         full_sequence.push((event.code, event.modifiers));
 
         tracing::debug!(
-            "KeybindingResolver.resolve_chord: sequence={:?}, context={:?}",
+            "KeybindingResolver.resolve_chord: sequence={:?}, contexts={}",
             full_sequence,
-            context
+            stack
         );
 
         // Check all chord binding sources in priority order
+        let language_chords = self.language_chords(stack);
         let search_order = vec![
-            (&self.chord_bindings, &KeyContext::Global, "custom global"),
             (
-                &self.default_chord_bindings,
-                &KeyContext::Global,
+                self.chord_bindings.get(&KeyContext::Global),
+                "custom global",
+            ),
+            (
+                self.default_chord_bindings.get(&KeyContext::Global),
                 "default global",
             ),
-            (&self.chord_bindings, &context, "custom context"),
-            (&self.default_chord_bindings, &context, "default context"),
+            (language_chords.as_ref(), "language"),
+            (self.chord_bindings.get(&context), "custom context"),
+            (self.default_chord_bindings.get(&context), "default context"),
         ];
 
+        let is_prefix = |chord_seq: &[(KeyCode, KeyModifiers)]| {
+            chord_seq.len() > full_sequence.len()
+                && chord_seq[..full_sequence.len()] == full_sequence[..]
+        };
         let mut has_partial_match = false;
 
-        for (binding_map, bind_context, label) in search_order {
-            if let Some(context_chords) = binding_map.get(bind_context) {
+        for (context_chords, label) in search_order {
+            if let Some(context_chords) = context_chords {
                 // Check for exact match
                 if let Some(action) = context_chords.get(&full_sequence) {
                     tracing::debug!("  -> Complete chord match in {}: {:?}", label, action);
@@ -956,14 +1764,9 @@ impl KeybindingResolver {
                 }
 
                 // Check for partial match (our sequence is a prefix of any binding)
-                for (chord_seq, _) in context_chords.iter() {
-                    if chord_seq.len() > full_sequence.len()
-                        && chord_seq[..full_sequence.len()] == full_sequence[..]
-                    {
-                        tracing::debug!("  -> Partial chord match in {}", label);
-                        has_partial_match = true;
-                        break;
-                    }
+                if context_chords.keys().any(|chord_seq| is_prefix(chord_seq)) {
+                    tracing::debug!("  -> Partial chord match in {}", label);
+                    has_partial_match = true;
                 }
             }
         }
@@ -976,13 +1779,77 @@ impl KeybindingResolver {
         }
     }
 
+    /// List the keys that can follow a pending chord `prefix`
+    ///
+    /// A key that completes a chord carries its action; a key that only leads
+    /// to longer chords has none. When several sources bind the same key, the
+    /// one `resolve_chord` would use wins. Sorted by key for display.
+    pub fn chord_continuations(
+        &self,
+        prefix: &[(KeyCode, KeyModifiers)],
+        context: KeyContext,
+    ) -> Vec<ChordContinuation> {
+        self.chord_continuations_in_stack(prefix, &KeyContextStack::from(context))
+    }
+
+    /// List the keys that can follow a pending chord in the stack's innermost
+    /// context, including chords bound for the stack's language
+    pub fn chord_continuations_in_stack(
+        &self,
+        prefix: &[(KeyCode, KeyModifiers)],
+        stack: &KeyContextStack,
+    ) -> Vec<ChordContinuation> {
+        let context = stack.top();
+        let language_chords = self.language_chords(stack);
+        let search_order = [
+            self.chord_bindings.get(&KeyContext::Global),
+            self.default_chord_bindings.get(&KeyContext::Global),
+            language_chords.as_ref(),
+            self.chord_bindings.get(&context),
+            self.default_chord_bindings.get(&context),
+        ];
+
+        let mut continuations: Vec<ChordContinuation> = Vec::new();
+        for context_chords in search_order {
+            let Some(context_chords) = context_chords else {
+                continue;
+            };
+            for (chord_seq, action) in context_chords {
+                if chord_seq.len() <= prefix.len() || chord_seq[..prefix.len()] != prefix[..] {
+                    continue;
+                }
+                let key = chord_seq[prefix.len()];
+                let action = (chord_seq.len() == prefix.len() + 1).then(|| action.clone());
+                match continuations.iter_mut().find(|c| c.key == key) {
+                    // A complete chord beats a longer one from a lower-priority
+                    // source, matching resolve_chord's exact-match-first order
+                    Some(existing) => {
+                        if existing.action.is_none() && action.is_some() {
+                            existing.action = action;
+                        }
+                    }
+                    None => continuations.push(ChordContinuation { key, action }),
+                }
+            }
+        }
+
+        continuations.sort_by_cached_key(|c| format_keybinding(&c.key.0, &c.key.1));
+        continuations
+    }
+
     /// Resolve a key event to an action in the given context
     pub fn resolve(&self, event: &KeyEvent, context: KeyContext) -> Action {
+        self.resolve_in_stack(event, &KeyContextStack::from(context))
+    }
+
+    /// Resolve a key event to an action, walking the context stack
+    pub fn resolve_in_stack(&self, event: &KeyEvent, stack: &KeyContextStack) -> Action {
+        let context = stack.top();
         tracing::debug!(
-            "KeybindingResolver.resolve: code={:?}, modifiers={:?}, context={:?}",
+            "KeybindingResolver.resolve: code={:?}, modifiers={:?}, contexts={}",
             event.code,
             event.modifiers,
-            context
+            stack
         );
 
         // Check Global bindings first (highest priority - work in all contexts)
@@ -1000,6 +1867,15 @@ impl KeybindingResolver {
             }
         }
 
+        // Then bindings for the active buffer's language
+        if let Some((action, _)) = self
+            .language_bindings_for(stack)
+            .and_then(|bindings| bindings.get(&vec![(event.code, event.modifiers)]))
+        {
+            tracing::debug!("  -> Found in language bindings: {:?}", action);
+            return action.clone();
+        }
+
         // Try context-specific custom bindings
         if let Some(context_bindings) = self.bindings.get(&context) {
             if let Some(action) = context_bindings.get(&(event.code, event.modifiers)) {
@@ -1024,26 +1900,22 @@ impl KeybindingResolver {
             }
         }
 
-        // Fall back to normal context ONLY for application-wide actions
+        // Fall back to the enclosing contexts ONLY for application-wide actions
         // This prevents keys from leaking through to the editor when in special contexts
-        if context != KeyContext::Normal {
-            if let Some(normal_bindings) = self.bindings.get(&KeyContext::Normal) {
-                if let Some(action) = normal_bindings.get(&(event.code, event.modifiers)) {
-                    if Self::is_application_wide_action(action) {
-                        tracing::debug!(
-                            "  -> Found application-wide action in custom normal bindings: {:?}",
-                            action
-                        );
-                        return action.clone();
-                    }
-                }
-            }
-
-            if let Some(normal_bindings) = self.default_bindings.get(&KeyContext::Normal) {
-                if let Some(action) = normal_bindings.get(&(event.code, event.modifiers)) {
+        for ancestor in stack.ancestors() {
+            for (bindings, label) in [
+                (&self.bindings, "custom"),
+                (&self.default_bindings, "default"),
+            ] {
+                if let Some(action) = bindings
+                    .get(&ancestor)
+                    .and_then(|b| b.get(&(event.code, event.modifiers)))
+                {
                     if Self::is_application_wide_action(action) {
                         tracing::debug!(
-                            "  -> Found application-wide action in default normal bindings: {:?}",
+                            "  -> Found application-wide action in {} {} bindings: {:?}",
+                            label,
+                            ancestor.to_when_clause(),
                             action
                         );
                         return action.clone();
@@ -1268,6 +2140,8 @@ impl KeybindingResolver {
             KeyContext::Popup,
             KeyContext::FileExplorer,
             KeyContext::Menu,
+            KeyContext::VimNormal,
+            KeyContext::VimVisual,
         ] {
             let mut all_keys: HashMap<(KeyCode, KeyModifiers), Action> = HashMap::new();
 
@@ -1305,13 +2179,120 @@ impl KeybindingResolver {
         bindings
     }
 
+    /// List every binding in effect, by context and then keys
+    ///
+    /// A custom binding replaces the keymap's binding for the same keys.
+    pub fn binding_infos(&self) -> Vec<BindingInfo> {
+        let mut infos = Vec::new();
+        for context in KeyContext::ALL {
+            let mut keys: LanguageKeymap = HashMap::new();
+            for (bindings, custom) in [(&self.default_bindings, false), (&self.bindings, true)] {
+                for (key, action) in bindings.get(&context).into_iter().flatten() {
+                    keys.insert(vec![*key], (action.clone(), custom));
+                }
+            }
+            for (chords, custom) in [
+                (&self.default_chord_bindings, false),
+                (&self.chord_bindings, true),
+            ] {
+                for (sequence, action) in chords.get(&context).into_iter().flatten() {
+                    keys.insert(sequence.clone(), (action.clone(), custom));
+                }
+            }
+
+            let mut context_infos: Vec<BindingInfo> = keys
+                .into_iter()
+                .map(|(sequence, (action, custom))| BindingInfo {
+                    context,
+                    language: None,
+                    keys: Self::format_sequence(&sequence),
+                    action,
+                    custom,
+                })
+                .collect();
+            context_infos.sort_by(|a, b| a.keys.cmp(&b.keys));
+            infos.extend(context_infos);
+        }
+
+        let mut language_infos: Vec<BindingInfo> = self
+            .language_bindings
+            .iter()
+            .flat_map(|((context, language), bindings)| {
+                bindings
+                    .iter()
+                    .map(|(sequence, (action, custom))| BindingInfo {
+                        context: *context,
+                        language: Some(language.clone()),
+                        keys: Self::format_sequence(sequence),
+                        action: action.clone(),
+                        custom: *custom,
+                    })
+            })
+            .collect();
+        language_infos.sort_by(|a, b| (&a.language, &a.keys).cmp(&(&b.language, &b.keys)));
+        infos.extend(language_infos);
+        infos
+    }
+
+    /// Find keys bound to different actions in the same context of `bindings`
+    ///
+    /// Only one of them can run, so all but the last are dead. Bindings with
+    /// an unknown key or action are ignored.
+    pub fn find_conflicts(bindings: &[crate::config::Keybinding]) -> Vec<BindingConflict> {
+        let mut conflicts: Vec<BindingConflict> = Vec::new();
+        let mut seen: HashMap<_, Vec<Action>> = HashMap::new();
+        let mut order = Vec::new();
+
+        for binding in bindings {
+            let Some(action) = Action::from_str(&binding.action, &binding.args) else {
+                continue;
+            };
+            let (context, language) = KeyContext::from_binding_when(binding.when.as_deref());
+            let Some(sequence) = Self::binding_sequence(binding) else {
+                continue;
+            };
+
+            let key = (context, language, sequence);
+            let actions = seen.entry(key.clone()).or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            });
+            if actions.last() != Some(&action) {
+                actions.retain(|a| a != &action);
+                actions.push(action);
+            }
+        }
+
+        for key in order {
+            let actions = &seen[&key];
+            if actions.len() > 1 {
+                conflicts.push(BindingConflict {
+                    context: key.0,
+                    language: key.1.clone(),
+                    keys: Self::format_sequence(&key.2),
+                    actions: actions.clone(),
+                });
+            }
+        }
+        conflicts
+    }
+
+    /// Format a key sequence as readable keys separated by spaces
+    fn format_sequence(sequence: &[(KeyCode, KeyModifiers)]) -> String {
+        sequence
+            .iter()
+            .map(|(key_code, modifiers)| Self::format_key(*key_code, *modifiers))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     /// Format a key combination as a readable string
     fn format_key(key_code: KeyCode, modifiers: KeyModifiers) -> String {
         format_keybinding(&key_code, &modifiers)
     }
 
     /// Format an action as a readable description
-    fn format_action(action: &Action) -> String {
+    pub fn format_action(action: &Action) -> String {
         match action {
             Action::InsertChar(c) => format!("Insert character '{c}'"),
             Action::InsertNewline => "Insert newline".to_string(),
@@ -1322,6 +2303,7 @@ impl KeybindingResolver {
             Action::MoveDown => "Move cursor down".to_string(),
             Action::MoveWordLeft => "Move word left".to_string(),
             Action::MoveWordRight => "Move word right".to_string(),
+            Action::MoveWordEnd => "Move to word end".to_string(),
             Action::MoveLineStart => "Move to line start".to_string(),
             Action::MoveLineEnd => "Move to line end".to_string(),
             Action::MovePageUp => "Move page up".to_string(),
@@ -1334,6 +2316,7 @@ impl KeybindingResolver {
             Action::SelectDown => "Select down".to_string(),
             Action::SelectWordLeft => "Select word left".to_string(),
             Action::SelectWordRight => "Select word right".to_string(),
+            Action::SelectWordEnd => "Select to word end".to_string(),
             Action::SelectLineStart => "Select to line start".to_string(),
             Action::SelectLineEnd => "Select to line end".to_string(),
             Action::SelectDocumentStart => "Select to document start".to_string(),
@@ -1344,6 +2327,11 @@ impl KeybindingResolver {
             Action::SelectWord => "Select word under cursor".to_string(),
             Action::SelectLine => "Select current line".to_string(),
             Action::ExpandSelection => "Expand selection".to_string(),
+            Action::SelectTextObject(object, around) => format!(
+                "Select {} {}",
+                if *around { "around" } else { "inside" },
+                object
+            ),
             Action::BlockSelectLeft => "Block select left".to_string(),
             Action::BlockSelectRight => "Block select right".to_string(),
             Action::BlockSelectUp => "Block select up".to_string(),
@@ -1354,10 +2342,12 @@ impl KeybindingResolver {
             Action::DeleteWordForward => "Delete word forward".to_string(),
             Action::DeleteLine => "Delete line".to_string(),
             Action::DeleteToLineEnd => "Delete to end of line".to_string(),
+            Action::KillLine => "Kill to end of line".to_string(),
             Action::TransposeChars => "Transpose characters".to_string(),
             Action::OpenLine => "Open line below".to_string(),
             Action::Recenter => "Recenter view on cursor".to_string(),
             Action::SetMark => "Set mark (start selection)".to_string(),
+            Action::ToggleSelectionMode => "Toggle selection mode".to_string(),
             Action::Copy => "Copy".to_string(),
             Action::Cut => "Cut".to_string(),
             Action::Paste => "Paste".to_string(),
@@ -1369,7 +2359,12 @@ impl KeybindingResolver {
             Action::SaveAs => "Save file as...".to_string(),
             Action::Open => "Open file".to_string(),
             Action::New => "New file".to_string(),
+            Action::NewScratchBuffer => "New scratch buffer".to_string(),
             Action::Close => "Close file".to_string(),
+            Action::CloseAllBuffers => "Close all buffers".to_string(),
+            Action::CloseOtherBuffers => "Close other buffers".to_string(),
+            Action::CloseBuffersToRight => "Close buffers to the right".to_string(),
+            Action::CloseSavedBuffers => "Close saved buffers".to_string(),
             Action::Quit => "Quit editor".to_string(),
             Action::Revert => "Revert to saved file".to_string(),
             Action::ToggleAutoRevert => "Toggle auto-revert mode".to_string(),
@@ -1377,6 +2372,7 @@ impl KeybindingResolver {
             Action::GoToMatchingBracket => "Go to matching bracket".to_string(),
             Action::JumpToNextError => "Jump to next error/diagnostic".to_string(),
             Action::JumpToPreviousError => "Jump to previous error/diagnostic".to_string(),
+            Action::ShowDiagnostics => "Show diagnostics on the current line".to_string(),
             Action::SmartHome => {
                 "Smart home (toggle line start / first non-whitespace)".to_string()
             }
@@ -1391,6 +2387,8 @@ impl KeybindingResolver {
             Action::ToggleSearchWholeWord => "Toggle search whole word matching".to_string(),
             Action::ToggleSearchRegex => "Toggle search regex mode".to_string(),
             Action::ToggleSearchConfirmEach => "Toggle confirm each replacement".to_string(),
+            Action::ToggleSearchInSelection => "Toggle search within selection".to_string(),
+            Action::PromptToggleHiddenFiles => "Show or hide hidden files".to_string(),
             Action::StartMacroRecording => "Start macro recording".to_string(),
             Action::StopMacroRecording => "Stop macro recording".to_string(),
             Action::PlayMacro(c) => format!("Play macro '{}'", c),
@@ -1398,7 +2396,7 @@ impl KeybindingResolver {
             Action::ShowMacro(c) => format!("Show macro '{}' in buffer", c),
             Action::ListMacros => "List all recorded macros".to_string(),
             Action::PromptRecordMacro => "Record macro (prompts for register)".to_string(),
-            Action::PromptPlayMacro => "Play macro (prompts for register)".to_string(),
+            Action::PromptPlayMacro => "Replay macro (prompts for register and count)".to_string(),
             Action::PlayLastMacro => "Play last recorded macro".to_string(),
             Action::PromptSetBookmark => "Set bookmark (prompts for register)".to_string(),
             Action::PromptJumpToBookmark => "Jump to bookmark (prompts for register)".to_string(),
@@ -1408,10 +2406,18 @@ impl KeybindingResolver {
             Action::ScrollDown => "Scroll down".to_string(),
             Action::ShowHelp => "Show manual".to_string(),
             Action::ShowKeyboardShortcuts => "Show keyboard shortcuts".to_string(),
+            Action::ShowKeybindings => "Browse key bindings".to_string(),
+            Action::BindKey => "Bind a key to a command".to_string(),
             Action::CommandPalette => "Command palette".to_string(),
             Action::ToggleLineWrap => "Toggle line wrap".to_string(),
+            Action::ToggleRedactSecrets => "Toggle redacting secrets".to_string(),
+            Action::LockScreen => "Lock screen".to_string(),
+            Action::BufferStatistics => "Buffer statistics".to_string(),
+            Action::ReloadEnvironment => "Reload the project's .envrc".to_string(),
+            Action::ToggleVimMode => "Toggle vim mode".to_string(),
             Action::ToggleComposeMode => "Toggle compose mode".to_string(),
             Action::SetComposeWidth => "Set compose width".to_string(),
+            Action::DismissStatusMessage => "Dismiss status message".to_string(),
             Action::NextBuffer => "Next buffer".to_string(),
             Action::PrevBuffer => "Previous buffer".to_string(),
             Action::NavigateBack => "Navigate back in history".to_string(),
@@ -1435,6 +2441,8 @@ impl KeybindingResolver {
             Action::PromptSelectNext => "Prompt select next".to_string(),
             Action::PromptPageUp => "Prompt page up".to_string(),
             Action::PromptPageDown => "Prompt page down".to_string(),
+            Action::PromptSelectFirst => "Prompt select first suggestion".to_string(),
+            Action::PromptSelectLast => "Prompt select last suggestion".to_string(),
             Action::PromptAcceptSuggestion => "Prompt accept suggestion".to_string(),
             Action::PromptMoveWordLeft => "Prompt move word left".to_string(),
             Action::PromptMoveWordRight => "Prompt move word right".to_string(),
@@ -1467,6 +2475,8 @@ impl KeybindingResolver {
             Action::FileExplorerExpand => "File explorer: expand directory".to_string(),
             Action::FileExplorerCollapse => "File explorer: collapse directory".to_string(),
             Action::FileExplorerOpen => "File explorer: open file".to_string(),
+            Action::FileExplorerOpenRight => "File explorer: open to the right".to_string(),
+            Action::FileExplorerOpenBelow => "File explorer: open below".to_string(),
             Action::FileExplorerRefresh => "File explorer: refresh".to_string(),
             Action::FileExplorerNewFile => "File explorer: new file".to_string(),
             Action::FileExplorerNewDirectory => "File explorer: new directory".to_string(),
@@ -1476,8 +2486,12 @@ impl KeybindingResolver {
             Action::FileExplorerToggleGitignored => {
                 "File explorer: toggle gitignored files".to_string()
             }
+            Action::FileExplorerFilter => "File explorer: filter files".to_string(),
+            Action::OpenInExternalProgram => "Open file in external program".to_string(),
             Action::LspCompletion => "LSP: Show completion suggestions".to_string(),
             Action::LspGotoDefinition => "LSP: Go to definition".to_string(),
+            Action::LspGotoDefinitionRight => "LSP: Go to definition to the right".to_string(),
+            Action::LspGotoDefinitionBelow => "LSP: Go to definition below".to_string(),
             Action::LspReferences => "LSP: Find references".to_string(),
             Action::LspRename => "LSP: Rename symbol".to_string(),
             Action::LspHover => "LSP: Show hover documentation".to_string(),
@@ -1488,13 +2502,18 @@ impl KeybindingResolver {
             Action::ToggleInlayHints => "Toggle inlay hints".to_string(),
             Action::ToggleLineNumbers => "Toggle line numbers".to_string(),
             Action::ToggleMouseCapture => "Toggle mouse support".to_string(),
+            Action::TogglePerformanceOverlay => "Toggle performance overlay".to_string(),
             Action::SetBackground => "Set ANSI background file".to_string(),
             Action::SetBackgroundBlend => "Set background blend ratio".to_string(),
             Action::DumpConfig => "Dump config to file".to_string(),
+            Action::RestoreSessionSnapshot => "Restore session from snapshot".to_string(),
+            Action::SaveSessionAs => "Save session under a name".to_string(),
+            Action::SwitchSession => "Switch to a named session".to_string(),
             Action::Search => "Search for text in buffer".to_string(),
             Action::FindInSelection => "Search within selection".to_string(),
             Action::FindNext => "Find next search match".to_string(),
             Action::FindPrevious => "Find previous search match".to_string(),
+            Action::SearchToQuickfix => "Send search matches to quickfix list".to_string(),
             Action::Replace => "Replace text in buffer".to_string(),
             Action::QueryReplace => "Interactive replace (y/n/!/q for each match)".to_string(),
             Action::MenuActivate => "Activate menu bar".to_string(),
@@ -1506,11 +2525,43 @@ impl KeybindingResolver {
             Action::MenuExecute => "Execute selected menu item".to_string(),
             Action::MenuOpen(name) => format!("Open {} menu", name),
             Action::SwitchKeybindingMap(map) => format!("Switch to '{}' keybindings", map),
+            Action::RunProjectTask(name) => format!("Project task: {}", name),
+            Action::ReloadProjectTasks => "Reload project tasks".to_string(),
+            Action::RunCommandSequence(names) => format!("Run commands: {}", names.join(", ")),
+            Action::CancelRunningTask => "Cancel the most recent background task".to_string(),
+            Action::GoToWorkspaceSymbol => "Go to a symbol in the workspace".to_string(),
+            Action::RebuildSymbolIndex => "Rebuild the symbol index".to_string(),
+            Action::RebuildSearchIndex => "Rebuild the search index".to_string(),
             Action::PluginAction(name) => format!("Plugin action: {}", name),
             Action::ScrollTabsLeft => "Scroll tabs left".to_string(),
             Action::ScrollTabsRight => "Scroll tabs right".to_string(),
+            Action::NextRecentBuffer => "Next recently used buffer".to_string(),
+            Action::PrevRecentBuffer => "Previous recently used buffer".to_string(),
+            Action::ReopenClosedBuffer => "Reopen closed buffer".to_string(),
+            Action::ShowSaveHistory => "Show save history".to_string(),
+            Action::DiffBuffers => "Diff buffers".to_string(),
+            Action::ToggleScrollLock => "Toggle scroll lock".to_string(),
+            Action::ZoomPane => "Zoom pane".to_string(),
+            Action::ToggleFocusMode => "Toggle focus mode".to_string(),
+            Action::ReflowParagraph => "Reflow paragraph".to_string(),
+            Action::ReflowToColumns => "Reflow to columns".to_string(),
+            Action::ToggleAutoHardWrap => "Toggle auto hard wrap".to_string(),
+            Action::CreateBufferGroup => "Create buffer group".to_string(),
+            Action::MoveToBufferGroup => "Move buffer to group".to_string(),
+            Action::SwitchBufferGroup => "Switch buffer group".to_string(),
             Action::SelectTheme => "Select theme".to_string(),
+            Action::LintTheme => "Check theme contrast".to_string(),
+            Action::ExportHtml => "Export buffer or selection as HTML".to_string(),
+            Action::ExportAnsi => "Export buffer or selection as ANSI text".to_string(),
+            Action::EvaluateSelection => "Evaluate selection and show the result".to_string(),
+            Action::EvaluateSelectionInsert => {
+                "Evaluate selection and insert the result below".to_string()
+            }
+            Action::InsertSnippet => "Insert a snippet".to_string(),
+            Action::RepeatLastPaletteQuery => "Repeat last palette query".to_string(),
             Action::SwitchToPreviousTab => "Switch to previous tab".to_string(),
+            Action::MoveTabLeft => "Move tab left".to_string(),
+            Action::MoveTabRight => "Move tab right".to_string(),
             Action::SwitchToTabByName => "Switch to tab by name".to_string(),
             Action::None => "No action".to_string(),
         }
@@ -1524,43 +2575,49 @@ impl KeybindingResolver {
         action: &Action,
         context: KeyContext,
     ) -> Option<String> {
-        // Check custom bindings first (higher priority)
-        if let Some(context_bindings) = self.bindings.get(&context) {
-            for ((keycode, modifiers), bound_action) in context_bindings {
-                if bound_action == action {
-                    return Some(format_keybinding(keycode, modifiers));
-                }
-            }
-        }
+        self.get_keybinding_for_action_in_stack(action, &KeyContextStack::from(context))
+    }
 
-        // Check default bindings for this context
-        if let Some(context_bindings) = self.default_bindings.get(&context) {
-            for ((keycode, modifiers), bound_action) in context_bindings {
-                if bound_action == action {
-                    return Some(format_keybinding(keycode, modifiers));
-                }
-            }
+    /// Key for an action where the context stack would resolve it
+    ///
+    /// The innermost context is searched first, then application-wide actions
+    /// in the contexts beneath it.
+    pub fn get_keybinding_for_action_in_stack(
+        &self,
+        action: &Action,
+        stack: &KeyContextStack,
+    ) -> Option<String> {
+        let find_in = |context: &KeyContext| {
+            [&self.bindings, &self.default_bindings]
+                .into_iter()
+                .filter_map(|bindings| bindings.get(context))
+                .flat_map(|bindings| bindings.iter())
+                .find(|(_, bound_action)| *bound_action == action)
+                .map(|((keycode, modifiers), _)| format_keybinding(keycode, modifiers))
+        };
+
+        // A binding for the buffer's language shadows the context's
+        let language_key = self.language_bindings_for(stack).and_then(|bindings| {
+            bindings
+                .iter()
+                .filter(|(sequence, (bound_action, _))| {
+                    sequence.len() == 1 && bound_action == action
+                })
+                .map(|(sequence, _)| format_keybinding(&sequence[0].0, &sequence[0].1))
+                .min()
+        });
+        if language_key.is_some() {
+            return language_key;
         }
 
-        // For certain contexts, also check Normal context for application-wide actions
-        if context != KeyContext::Normal && Self::is_application_wide_action(action) {
-            // Check custom normal bindings
-            if let Some(normal_bindings) = self.bindings.get(&KeyContext::Normal) {
-                for ((keycode, modifiers), bound_action) in normal_bindings {
-                    if bound_action == action {
-                        return Some(format_keybinding(keycode, modifiers));
-                    }
-                }
-            }
+        // Check custom bindings first (higher priority), then defaults
+        if let Some(keybinding) = find_in(&stack.top()) {
+            return Some(keybinding);
+        }
 
-            // Check default normal bindings
-            if let Some(normal_bindings) = self.default_bindings.get(&KeyContext::Normal) {
-                for ((keycode, modifiers), bound_action) in normal_bindings {
-                    if bound_action == action {
-                        return Some(format_keybinding(keycode, modifiers));
-                    }
-                }
-            }
+        // Enclosing contexts only resolve application-wide actions
+        if Self::is_application_wide_action(action) {
+            return stack.ancestors().find_map(|ancestor| find_in(&ancestor));
         }
 
         None
@@ -1569,17 +2626,19 @@ impl KeybindingResolver {
     /// Reload bindings from config (for hot reload)
     pub fn reload(&mut self, config: &Config) {
         self.bindings.clear();
+        for bindings in self.language_bindings.values_mut() {
+            bindings.retain(|_, (_, custom)| !*custom);
+        }
         for binding in &config.keybindings {
+            // Determine context from "when" clause
+            let (context, language) = KeyContext::from_binding_when(binding.when.as_deref());
+            if let Some(language) = language {
+                self.insert_language_binding(context, language, binding, true);
+                continue;
+            }
             if let Some(key_code) = Self::parse_key(&binding.key) {
                 let modifiers = Self::parse_modifiers(&binding.modifiers);
                 if let Some(action) = Action::from_str(&binding.action, &binding.args) {
-                    // Determine context from "when" clause
-                    let context = if let Some(ref when) = binding.when {
-                        KeyContext::from_when_clause(when).unwrap_or(KeyContext::Normal)
-                    } else {
-                        KeyContext::Normal
-                    };
-
                     self.bindings
                         .entry(context)
                         .or_insert_with(HashMap::new)
@@ -1588,6 +2647,87 @@ impl KeybindingResolver {
             }
         }
     }
+
+    /// Parse a key description like "ctrl+alt+b" or "f5"
+    pub fn parse_key_string(key: &str) -> Option<(KeyCode, KeyModifiers)> {
+        let mut parts: Vec<String> = key.split('+').map(|p| p.trim().to_string()).collect();
+        let key_code = Self::parse_key(&parts.pop()?)?;
+        let known = |m: &String| {
+            matches!(
+                m.to_lowercase().as_str(),
+                "ctrl" | "control" | "shift" | "alt"
+            )
+        };
+        if !parts.iter().all(known) {
+            return None;
+        }
+        Some((key_code, Self::parse_modifiers(&parts)))
+    }
+
+    /// Write a key the way `parse_key_string` reads it (e.g. "ctrl+shift+f5")
+    ///
+    /// Returns None for keys that can't be written that way.
+    pub fn key_string(key_code: KeyCode, modifiers: KeyModifiers) -> Option<String> {
+        let key = match key_code {
+            KeyCode::Enter => "enter".to_string(),
+            KeyCode::Backspace => "backspace".to_string(),
+            KeyCode::Delete => "delete".to_string(),
+            KeyCode::Tab => "tab".to_string(),
+            KeyCode::Esc => "esc".to_string(),
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Left => "left".to_string(),
+            KeyCode::Right => "right".to_string(),
+            KeyCode::Up => "up".to_string(),
+            KeyCode::Down => "down".to_string(),
+            KeyCode::Home => "home".to_string(),
+            KeyCode::End => "end".to_string(),
+            KeyCode::PageUp => "pageup".to_string(),
+            KeyCode::PageDown => "pagedown".to_string(),
+            KeyCode::F(n) => format!("f{}", n),
+            // "+" separates modifiers from the key
+            KeyCode::Char(c) if c.is_ascii_graphic() && c != '+' => {
+                c.to_ascii_lowercase().to_string()
+            }
+            _ => return None,
+        };
+
+        let mut parts = Vec::new();
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            parts.push("ctrl".to_string());
+        }
+        if modifiers.contains(KeyModifiers::ALT) {
+            parts.push("alt".to_string());
+        }
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            parts.push("shift".to_string());
+        }
+        parts.push(key);
+        Some(parts.join("+"))
+    }
+
+    /// Bind a key at runtime (e.g., for project tasks)
+    ///
+    /// Like custom bindings from the config, these take priority over the
+    /// keymap's defaults.
+    pub fn add_binding(
+        &mut self,
+        context: KeyContext,
+        key_code: KeyCode,
+        modifiers: KeyModifiers,
+        action: Action,
+    ) {
+        self.bindings
+            .entry(context)
+            .or_default()
+            .insert((key_code, modifiers), action);
+    }
+
+    /// Remove the custom bindings whose action matches `predicate`
+    pub fn remove_bindings_where(&mut self, predicate: impl Fn(&Action) -> bool) {
+        for context_bindings in self.bindings.values_mut() {
+            context_bindings.retain(|_, action| !predicate(action));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1604,6 +2744,35 @@ mod tests {
         assert_eq!(KeybindingResolver::parse_key("a"), Some(KeyCode::Char('a')));
     }
 
+    #[test]
+    fn test_key_string_round_trip() {
+        for (code, modifiers, expected) in [
+            (KeyCode::Char('k'), KeyModifiers::CONTROL, "ctrl+k"),
+            (
+                KeyCode::F(5),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+                "ctrl+shift+f5",
+            ),
+            (KeyCode::Char(' '), KeyModifiers::ALT, "alt+space"),
+            (KeyCode::PageDown, KeyModifiers::NONE, "pagedown"),
+        ] {
+            let written = KeybindingResolver::key_string(code, modifiers).unwrap();
+            assert_eq!(written, expected);
+            assert_eq!(
+                KeybindingResolver::parse_key_string(&written),
+                Some((code, modifiers))
+            );
+        }
+        assert_eq!(
+            KeybindingResolver::key_string(KeyCode::Char('+'), KeyModifiers::CONTROL),
+            None
+        );
+        assert_eq!(
+            KeybindingResolver::key_string(KeyCode::BackTab, KeyModifiers::SHIFT),
+            None
+        );
+    }
+
     #[test]
     fn test_parse_modifiers() {
         let mods = vec!["ctrl".to_string()];
@@ -1732,6 +2901,48 @@ mod tests {
         // Note: Prompt context might handle this differently in practice
     }
 
+    #[test]
+    fn test_context_stack_resolution() {
+        use crate::config::Keybinding;
+
+        let mut config = Config::default();
+        for (key, action) in [("f7", "quit"), ("f8", "undo")] {
+            config.keybindings.push(Keybinding {
+                key: key.to_string(),
+                modifiers: vec![],
+                keys: vec![],
+                action: action.to_string(),
+                args: HashMap::new(),
+                when: Some("fileExplorer".to_string()),
+            });
+        }
+        let resolver = KeybindingResolver::new(&config);
+        let f7 = KeyEvent::new(KeyCode::F(7), KeyModifiers::empty());
+        let f8 = KeyEvent::new(KeyCode::F(8), KeyModifiers::empty());
+
+        let mut stack = KeyContextStack::new(KeyContext::Normal);
+        stack.push(KeyContext::FileExplorer);
+        assert_eq!(resolver.resolve_in_stack(&f8, &stack), Action::Undo);
+
+        // A prompt over the explorer gets its application-wide actions only
+        stack.push(KeyContext::Prompt);
+        assert_eq!(stack.to_string(), "normal > fileExplorer > prompt");
+        assert_eq!(resolver.resolve_in_stack(&f7, &stack), Action::Quit);
+        assert_eq!(resolver.resolve_in_stack(&f8, &stack), Action::None);
+        assert_eq!(
+            resolver.get_keybinding_for_action_in_stack(&Action::Quit, &stack),
+            resolver.get_keybinding_for_action(&Action::Quit, KeyContext::FileExplorer)
+        );
+
+        // Without the explorer underneath, F7 means nothing in a prompt
+        assert_eq!(resolver.resolve(&f7, KeyContext::Prompt), Action::None);
+
+        assert_eq!(stack.pop(), Some(KeyContext::Prompt));
+        assert_eq!(stack.pop(), Some(KeyContext::FileExplorer));
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.top(), KeyContext::Normal);
+    }
+
     #[test]
     fn test_context_priority_resolution() {
         use crate::config::Keybinding;
@@ -1824,6 +3035,375 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_chord_resolution() {
+        let config = Config {
+            active_keybinding_map: "vscode".to_string(),
+            ..Config::default()
+        };
+        let resolver = KeybindingResolver::new(&config);
+        let ctrl = |c| (KeyCode::Char(c), KeyModifiers::CONTROL);
+        let event = |(code, modifiers)| KeyEvent::new(code, modifiers);
+
+        assert_eq!(
+            resolver.resolve_chord(&[], &event(ctrl('k')), KeyContext::Normal),
+            ChordResolution::Partial
+        );
+        assert_eq!(
+            resolver.resolve_chord(&[ctrl('k')], &event(ctrl('w')), KeyContext::Normal),
+            ChordResolution::Complete(Action::Close)
+        );
+        assert_eq!(
+            resolver.resolve_chord(&[ctrl('k')], &event(ctrl('q')), KeyContext::Normal),
+            ChordResolution::NoMatch
+        );
+        // Chords are per context
+        assert_eq!(
+            resolver.resolve_chord(&[], &event(ctrl('k')), KeyContext::Prompt),
+            ChordResolution::NoMatch
+        );
+    }
+
+    #[test]
+    fn test_emacs_preset() {
+        use crate::config::Keybinding;
+
+        let mut config = Config {
+            active_keybinding_map: "emacs".to_string(),
+            ..Config::default()
+        };
+        let resolver = KeybindingResolver::new(&config);
+        let ctrl = |c| (KeyCode::Char(c), KeyModifiers::CONTROL);
+        let alt = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT);
+        let event = |(code, modifiers)| KeyEvent::new(code, modifiers);
+
+        assert_eq!(
+            resolver.resolve(&event(ctrl('a')), KeyContext::Normal),
+            Action::MoveLineStart
+        );
+        assert_eq!(
+            resolver.resolve(&event(ctrl('k')), KeyContext::Normal),
+            Action::KillLine
+        );
+        assert_eq!(
+            resolver.resolve(&alt('f'), KeyContext::Normal),
+            Action::MoveWordRight
+        );
+        assert_eq!(
+            resolver.resolve_chord(&[ctrl('x')], &event(ctrl('s')), KeyContext::Normal),
+            ChordResolution::Complete(Action::Save)
+        );
+
+        // User bindings are layered on top of the preset
+        config.keybindings.push(Keybinding {
+            key: "k".to_string(),
+            modifiers: vec!["ctrl".to_string()],
+            keys: vec![],
+            action: "delete_line".to_string(),
+            args: HashMap::new(),
+            when: None,
+        });
+        let resolver = KeybindingResolver::new(&config);
+        assert_eq!(
+            resolver.resolve(&event(ctrl('k')), KeyContext::Normal),
+            Action::DeleteLine
+        );
+        assert_eq!(
+            resolver.resolve(&event(ctrl('e')), KeyContext::Normal),
+            Action::MoveLineEnd
+        );
+    }
+
+    #[test]
+    fn test_unknown_keymap_falls_back_to_default() {
+        let config = Config {
+            active_keybinding_map: "no-such-map".to_string(),
+            ..Config::default()
+        };
+        let resolver = KeybindingResolver::new(&config);
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(resolver.resolve(&ctrl_s, KeyContext::Normal), Action::Save);
+    }
+
+    #[test]
+    fn test_chord_continuations() {
+        use crate::config::{KeyPress, Keybinding};
+
+        let mut config = Config {
+            active_keybinding_map: "emacs".to_string(),
+            ..Config::default()
+        };
+        let press = |key: &str, modifiers: &[&str]| KeyPress {
+            key: key.to_string(),
+            modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+        };
+        config.keybindings.push(Keybinding {
+            key: String::new(),
+            modifiers: vec![],
+            keys: vec![press("x", &["ctrl"]), press("r", &[]), press("t", &[])],
+            action: "undo".to_string(),
+            args: HashMap::new(),
+            when: Some("normal".to_string()),
+        });
+        let resolver = KeybindingResolver::new(&config);
+        let ctrl_x = (KeyCode::Char('x'), KeyModifiers::CONTROL);
+
+        let continuations = resolver.chord_continuations(&[ctrl_x], KeyContext::Normal);
+        let find = |code, modifiers| {
+            continuations
+                .iter()
+                .find(|c| c.key == (code, modifiers))
+                .map(|c| c.action.clone())
+        };
+        assert_eq!(
+            find(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            Some(Some(Action::Save))
+        );
+        assert_eq!(
+            find(KeyCode::Char('k'), KeyModifiers::NONE),
+            Some(Some(Action::Close))
+        );
+        // "r" only leads to a longer chord
+        assert_eq!(find(KeyCode::Char('r'), KeyModifiers::NONE), Some(None));
+        assert_eq!(find(KeyCode::Char('z'), KeyModifiers::NONE), None);
+
+        let after_r = resolver.chord_continuations(
+            &[ctrl_x, (KeyCode::Char('r'), KeyModifiers::NONE)],
+            KeyContext::Normal,
+        );
+        assert_eq!(
+            after_r,
+            vec![ChordContinuation {
+                key: (KeyCode::Char('t'), KeyModifiers::NONE),
+                action: Some(Action::Undo),
+            }]
+        );
+        // Nothing follows a key that isn't a prefix
+        assert!(resolver
+            .chord_continuations(
+                &[(KeyCode::Char('q'), KeyModifiers::NONE)],
+                KeyContext::Normal
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn test_binding_infos_and_conflicts() {
+        use crate::config::{KeyPress, Keybinding};
+
+        let binding = |key: &str, action: &str, when: Option<&str>| Keybinding {
+            key: key.to_string(),
+            modifiers: vec!["ctrl".to_string()],
+            keys: vec![],
+            action: action.to_string(),
+            args: HashMap::new(),
+            when: when.map(|w| w.to_string()),
+        };
+        let mut chord = binding("", "redo", None);
+        chord.keys = vec![
+            KeyPress {
+                key: "k".to_string(),
+                modifiers: vec!["ctrl".to_string()],
+            },
+            KeyPress {
+                key: "u".to_string(),
+                modifiers: vec![],
+            },
+        ];
+        let bindings = vec![
+            binding("f7", "save", None),
+            binding("f7", "open", None),
+            // Same action twice, or the same keys in another context, is fine
+            binding("f8", "undo", None),
+            binding("f8", "undo", None),
+            binding("f7", "quit", Some("prompt")),
+            chord,
+        ];
+
+        let ctrl = |code| format_keybinding(&code, &KeyModifiers::CONTROL);
+        let ctrl_f7 = ctrl(KeyCode::F(7));
+
+        let conflicts = KeybindingResolver::find_conflicts(&bindings);
+        assert_eq!(
+            conflicts,
+            vec![BindingConflict {
+                context: KeyContext::Normal,
+                language: None,
+                keys: ctrl_f7.clone(),
+                actions: vec![Action::Save, Action::Open],
+            }]
+        );
+
+        let mut config = Config::default();
+        config.keybindings = bindings;
+        let infos = KeybindingResolver::new(&config).binding_infos();
+        let find = |context: KeyContext, keys: &str| {
+            infos
+                .iter()
+                .find(|info| info.context == context && info.keys == keys)
+        };
+        let f7 = find(KeyContext::Normal, &ctrl_f7).unwrap();
+        assert_eq!(f7.action, Action::Open);
+        assert!(f7.custom);
+        assert_eq!(
+            find(KeyContext::Prompt, &ctrl_f7).map(|info| &info.action),
+            Some(&Action::Quit)
+        );
+        assert_eq!(
+            find(
+                KeyContext::Normal,
+                &format!("{} U", ctrl(KeyCode::Char('k')))
+            )
+            .map(|info| &info.action),
+            Some(&Action::Redo)
+        );
+        // Keymap bindings are listed too
+        let save = find(KeyContext::Normal, &ctrl(KeyCode::Char('s'))).unwrap();
+        assert_eq!(save.action, Action::Save);
+        assert!(!save.custom);
+    }
+
+    #[test]
+    fn test_key_context_from_binding_when() {
+        assert_eq!(
+            KeyContext::from_binding_when(None),
+            (KeyContext::Normal, None)
+        );
+        assert_eq!(
+            KeyContext::from_binding_when(Some("prompt")),
+            (KeyContext::Prompt, None)
+        );
+        assert_eq!(
+            KeyContext::from_binding_when(Some("normal && language == 'rust'")),
+            (KeyContext::Normal, Some("rust".to_string()))
+        );
+        assert_eq!(
+            KeyContext::from_binding_when(Some("language==\"python\" && fileExplorer")),
+            (KeyContext::FileExplorer, Some("python".to_string()))
+        );
+        assert_eq!(
+            KeyContext::from_binding_when(Some("language == ''")),
+            (KeyContext::Normal, None)
+        );
+    }
+
+    #[test]
+    fn test_language_bindings() {
+        use crate::config::{KeyPress, Keybinding};
+
+        let mut config = Config::default();
+        config.keybindings.push(Keybinding {
+            key: "f9".to_string(),
+            modifiers: vec![],
+            keys: vec![],
+            action: "undo".to_string(),
+            args: HashMap::new(),
+            when: Some("normal && language == 'rust'".to_string()),
+        });
+        config.keybindings.push(Keybinding {
+            key: String::new(),
+            modifiers: vec![],
+            keys: vec![
+                KeyPress {
+                    key: "f9".to_string(),
+                    modifiers: vec!["alt".to_string()],
+                },
+                KeyPress {
+                    key: "r".to_string(),
+                    modifiers: vec![],
+                },
+            ],
+            action: "redo".to_string(),
+            args: HashMap::new(),
+            when: Some("language == 'rust'".to_string()),
+        });
+        let mut resolver = KeybindingResolver::new(&config);
+        let f9 = KeyEvent::new(KeyCode::F(9), KeyModifiers::NONE);
+        let alt_f9 = KeyEvent::new(KeyCode::F(9), KeyModifiers::ALT);
+        let r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
+
+        let mut stack = KeyContextStack::new(KeyContext::Normal);
+        assert_eq!(resolver.resolve_in_stack(&f9, &stack), Action::None);
+        stack.set_language(Some("python".to_string()));
+        assert_eq!(resolver.resolve_in_stack(&f9, &stack), Action::None);
+        assert_eq!(
+            resolver.resolve_chord_in_stack(&[], &alt_f9, &stack),
+            ChordResolution::NoMatch
+        );
+
+        stack.set_language(Some("rust".to_string()));
+        assert_eq!(stack.to_string(), "normal (rust)");
+        assert_eq!(resolver.resolve_in_stack(&f9, &stack), Action::Undo);
+        assert_eq!(
+            resolver.resolve_chord_in_stack(&[], &alt_f9, &stack),
+            ChordResolution::Partial
+        );
+        assert_eq!(
+            resolver.resolve_chord_in_stack(&[(KeyCode::F(9), KeyModifiers::ALT)], &r, &stack),
+            ChordResolution::Complete(Action::Redo)
+        );
+        assert_eq!(
+            resolver
+                .chord_continuations_in_stack(&[(KeyCode::F(9), KeyModifiers::ALT)], &stack)
+                .len(),
+            1
+        );
+        assert_eq!(
+            resolver.get_keybinding_for_action_in_stack(&Action::Undo, &stack),
+            Some(format_keybinding(&KeyCode::F(9), &KeyModifiers::NONE))
+        );
+        // Only in the binding's context
+        stack.push(KeyContext::Prompt);
+        assert_eq!(resolver.resolve_in_stack(&f9, &stack), Action::None);
+
+        let infos = resolver.binding_infos();
+        let f9_info = infos
+            .iter()
+            .find(|info| info.action == Action::Undo && info.language.is_some())
+            .unwrap();
+        assert_eq!(f9_info.language.as_deref(), Some("rust"));
+        assert!(f9_info.custom);
+
+        // Reloading drops custom language bindings that are gone
+        resolver.reload(&Config::default());
+        stack.pop();
+        assert_eq!(resolver.resolve_in_stack(&f9, &stack), Action::None);
+    }
+
+    #[test]
+    fn test_vim_bindings() {
+        let mut config = Config::default();
+        let w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE);
+        let g = (KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(
+            KeybindingResolver::new(&config).resolve(&w, KeyContext::VimNormal),
+            Action::None
+        );
+
+        config.editor.vim_mode = true;
+        let resolver = KeybindingResolver::new(&config);
+        assert_eq!(
+            resolver.resolve(&w, KeyContext::VimNormal),
+            Action::MoveWordRight
+        );
+        assert_eq!(
+            resolver.resolve(&w, KeyContext::VimVisual),
+            Action::MoveWordRight
+        );
+        // Normal mode doesn't type text
+        let z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE);
+        assert_eq!(resolver.resolve(&z, KeyContext::VimNormal), Action::None);
+        // The regular editing bindings are unchanged
+        assert_eq!(
+            resolver.resolve(&w, KeyContext::Normal),
+            Action::InsertChar('w')
+        );
+        assert_eq!(
+            resolver.resolve_chord(&[g], &KeyEvent::new(g.0, g.1), KeyContext::VimNormal),
+            ChordResolution::Complete(Action::MoveDocumentStart)
+        );
+    }
+
     #[test]
     fn test_all_context_default_bindings_exist() {
         let config = Config::default();
@@ -1936,6 +3516,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_runtime_bindings() {
+        let config = Config::default();
+        let mut resolver = KeybindingResolver::new(&config);
+
+        let (code, modifiers) = KeybindingResolver::parse_key_string("ctrl+alt+b").unwrap();
+        assert_eq!(code, KeyCode::Char('b'));
+        assert_eq!(modifiers, KeyModifiers::CONTROL | KeyModifiers::ALT);
+        assert_eq!(
+            KeybindingResolver::parse_key_string("F5"),
+            Some((KeyCode::F(5), KeyModifiers::empty()))
+        );
+        assert_eq!(KeybindingResolver::parse_key_string("hyper+b"), None);
+        assert_eq!(KeybindingResolver::parse_key_string("ctrl+"), None);
+
+        let task = Action::RunProjectTask("Build".to_string());
+        resolver.add_binding(KeyContext::Normal, code, modifiers, task.clone());
+        let event = KeyEvent::new(code, modifiers);
+        assert_eq!(resolver.resolve(&event, KeyContext::Normal), task);
+
+        resolver.remove_bindings_where(|a| matches!(a, Action::RunProjectTask(_)));
+        assert_ne!(resolver.resolve(&event, KeyContext::Normal), task);
+    }
+
     #[test]
     fn test_terminal_key_equivalents() {
         // Test that terminal_key_equivalents returns correct mappings
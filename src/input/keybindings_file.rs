@@ -0,0 +1,313 @@
+//! User key bindings from `~/.config/fresh/keybindings.toml`
+//!
+//! Each table is a key context, mapping key chords to action names. A chord is
+//! one or more keys separated by spaces; actions that take arguments use an
+//! inline table:
+//!
+//! ```toml
+//! [normal]
+//! "ctrl+k ctrl+c" = "toggle_comment"
+//! "f5" = { action = "plugin_action", args = { name = "start_live_grep" } }
+//!
+//! [global]
+//! "alt+p" = "command_palette"
+//! ```
+//!
+//! These bindings override the keymap's defaults and the `keybindings` of the
+//! config file. Invalid entries are skipped and reported; the rest still apply.
+//! "Bind Key" adds entries with `set_binding`.
+
+use crate::config::{KeyPress, Keybinding};
+use crate::input::keybindings::{Action, KeyContext, KeybindingResolver};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Name of the key bindings file in the config directory
+pub const KEYBINDINGS_FILE: &str = "keybindings.toml";
+
+/// Action bound to a chord
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BoundAction {
+    Name(String),
+    WithArgs {
+        action: String,
+        #[serde(default)]
+        args: HashMap<String, serde_json::Value>,
+    },
+}
+
+/// Bindings read from the key bindings file
+#[derive(Debug, Default)]
+pub struct UserKeybindings {
+    pub bindings: Vec<Keybinding>,
+    /// Entries that were skipped, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Path of the user's key bindings file
+pub fn keybindings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("fresh").join(KEYBINDINGS_FILE))
+}
+
+/// Load the key bindings file
+///
+/// A missing file has no bindings. Fails only if the file can't be read or
+/// isn't valid TOML.
+pub fn load_keybindings(path: &Path) -> Result<UserKeybindings, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(UserKeybindings::default()),
+        Err(e) => return Err(format!("{}: {}", KEYBINDINGS_FILE, e)),
+    };
+    parse_keybindings(&content).map_err(|e| format!("{}: {}", KEYBINDINGS_FILE, e))
+}
+
+/// Parse the contents of a key bindings file
+pub fn parse_keybindings(content: &str) -> Result<UserKeybindings, String> {
+    let tables: BTreeMap<String, BTreeMap<String, BoundAction>> =
+        toml::from_str(content).map_err(|e| e.message().to_string())?;
+
+    let mut result = UserKeybindings::default();
+    for (context_name, chords) in tables {
+        let Some(context) = KeyContext::from_when_clause(&context_name) else {
+            result.errors.push(format!(
+                "[{}]: unknown context (expected global, normal, prompt, popup, \
+                 file_explorer, menu, vim_normal or vim_visual)",
+                context_name
+            ));
+            continue;
+        };
+
+        for (chord, bound) in chords {
+            let (action, args) = match bound {
+                BoundAction::Name(action) => (action, HashMap::new()),
+                BoundAction::WithArgs { action, args } => (action, args),
+            };
+            match parse_binding(&chord, action, args, context) {
+                Ok(binding) => result.bindings.push(binding),
+                Err(e) => result
+                    .errors
+                    .push(format!("[{}] \"{}\": {}", context_name, chord, e)),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Check one entry and turn it into a binding
+pub fn parse_binding(
+    chord: &str,
+    action: String,
+    args: HashMap<String, serde_json::Value>,
+    context: KeyContext,
+) -> Result<Keybinding, String> {
+    let mut keys = Vec::new();
+    for key in chord.split_whitespace() {
+        if KeybindingResolver::parse_key_string(key).is_none() {
+            return Err(format!("cannot parse key '{}'", key));
+        }
+        let mut parts: Vec<String> = key.split('+').map(|p| p.trim().to_string()).collect();
+        let key = parts.pop().unwrap_or_default();
+        keys.push(KeyPress {
+            key,
+            modifiers: parts,
+        });
+    }
+    if keys.is_empty() {
+        return Err("no key given".to_string());
+    }
+
+    if Action::from_str(&action, &args).is_none() {
+        return Err(if args.is_empty() {
+            format!("unknown command '{}'", action)
+        } else {
+            format!("unknown command '{}' or invalid args", action)
+        });
+    }
+
+    // A single key uses key + modifiers, a sequence uses keys
+    let (key, modifiers, keys) = if keys.len() == 1 {
+        let press = keys.remove(0);
+        (press.key, press.modifiers, Vec::new())
+    } else {
+        (String::new(), Vec::new(), keys)
+    };
+    Ok(Keybinding {
+        key,
+        modifiers,
+        keys,
+        action,
+        args,
+        when: Some(context.to_when_clause().to_string()),
+    })
+}
+
+/// Bind `chord` to `action` in the `context` table of a key bindings file's
+/// contents, replacing the chord's entry there if it has one
+///
+/// The rest of the file, comments included, is kept as it is.
+pub fn set_binding(
+    content: &str,
+    context: KeyContext,
+    chord: &str,
+    action: &str,
+    args: &HashMap<String, serde_json::Value>,
+) -> Result<String, String> {
+    let bound = if args.is_empty() {
+        toml::Value::String(action.to_string())
+    } else {
+        let mut table = toml::Table::new();
+        table.insert(
+            "action".to_string(),
+            toml::Value::String(action.to_string()),
+        );
+        table.insert(
+            "args".to_string(),
+            toml::Value::try_from(args).map_err(|e| e.to_string())?,
+        );
+        toml::Value::Table(table)
+    };
+    let entry = format!("{} = {}", toml::Value::String(chord.to_string()), bound);
+
+    let section = context.to_when_clause();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut current_section = None;
+    let mut header = None;
+    let mut existing = None;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix('[') {
+            let name = rest.split(']').next().unwrap_or_default().trim();
+            if name == section {
+                header = Some(i);
+            }
+            current_section = Some(name.to_string());
+        } else if current_section.as_deref() == Some(section)
+            && toml::from_str::<toml::Table>(trimmed)
+                .is_ok_and(|entry| entry.len() == 1 && entry.contains_key(chord))
+        {
+            existing = Some(i);
+        }
+    }
+
+    match (existing, header) {
+        (Some(i), _) => lines[i] = entry,
+        (None, Some(i)) => lines.insert(i + 1, entry),
+        (None, None) => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.push(entry);
+        }
+    }
+    let mut result = lines.join("\n");
+    result.push('\n');
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keybindings() {
+        let parsed = parse_keybindings(
+            r#"
+            [normal]
+            "ctrl+alt+u" = "undo"
+            "ctrl+k ctrl+c" = "toggle_comment"
+            "f5" = { action = "plugin_action", args = { name = "start_live_grep" } }
+
+            [global]
+            "alt+p" = "command_palette"
+            "#,
+        )
+        .unwrap();
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.bindings.len(), 4);
+
+        // Sorted by context
+        let first = &parsed.bindings[0];
+        assert_eq!(first.action, "command_palette");
+        assert_eq!(first.when.as_deref(), Some("global"));
+        let chord = parsed
+            .bindings
+            .iter()
+            .find(|b| b.action == "toggle_comment")
+            .unwrap();
+        assert!(chord.key.is_empty());
+        assert_eq!(chord.keys.len(), 2);
+        assert_eq!(chord.keys[1].key, "c");
+        assert_eq!(chord.keys[1].modifiers, vec!["ctrl"]);
+        let undo = parsed.bindings.iter().find(|b| b.action == "undo").unwrap();
+        assert_eq!(undo.key, "u");
+        assert_eq!(undo.modifiers, vec!["ctrl", "alt"]);
+        assert_eq!(undo.when.as_deref(), Some("normal"));
+    }
+
+    #[test]
+    fn test_invalid_entries_are_reported() {
+        let parsed = parse_keybindings(
+            r#"
+            [normal]
+            "ctrl+s" = "save"
+            "ctrl+shift" = "undo"
+            "hyper+x" = "undo"
+            "ctrl+q" = "no_such_command"
+
+            [editor]
+            "ctrl+z" = "undo"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.bindings.len(), 1);
+        assert_eq!(parsed.errors.len(), 4);
+        assert!(parsed.errors[0].starts_with("[editor]: unknown context"));
+        assert!(parsed
+            .errors
+            .iter()
+            .any(|e| e == "[normal] \"ctrl+q\": unknown command 'no_such_command'"));
+        assert!(parsed
+            .errors
+            .iter()
+            .any(|e| e == "[normal] \"hyper+x\": cannot parse key 'hyper+x'"));
+
+        assert!(parse_keybindings("[normal\n").is_err());
+    }
+
+    #[test]
+    fn test_set_binding() {
+        let none = HashMap::new();
+        let content = "# My bindings\n[global]\n\"alt+p\" = \"command_palette\"\n";
+
+        // A new table goes at the end
+        let content = set_binding(content, KeyContext::Normal, "ctrl+k x", "save", &none).unwrap();
+        assert_eq!(
+            content,
+            "# My bindings\n[global]\n\"alt+p\" = \"command_palette\"\n\n[normal]\n\"ctrl+k x\" = \"save\"\n"
+        );
+
+        // An existing table gets the entry, and a bound chord is replaced
+        let args = HashMap::from([(
+            "name".to_string(),
+            serde_json::Value::String("start_live_grep".to_string()),
+        )]);
+        let content =
+            set_binding(&content, KeyContext::Normal, "f5", "plugin_action", &args).unwrap();
+        let content = set_binding(&content, KeyContext::Normal, "ctrl+k x", "undo", &none).unwrap();
+        assert!(content.starts_with("# My bindings\n"));
+
+        let parsed = parse_keybindings(&content).unwrap();
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.bindings.len(), 3);
+        let chord = parsed.bindings.iter().find(|b| b.keys.len() == 2).unwrap();
+        assert_eq!(chord.action, "undo");
+        let f5 = parsed.bindings.iter().find(|b| b.key == "f5").unwrap();
+        assert_eq!(f5.action, "plugin_action");
+        assert_eq!(f5.args, args);
+    }
+}
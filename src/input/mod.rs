@@ -2,12 +2,19 @@
 //!
 //! This module handles the input-to-action-to-event translation.
 
+pub mod action_format;
 pub mod actions;
 pub mod buffer_mode;
 pub mod command_registry;
 pub mod commands;
+pub mod dropped_paths;
+pub mod frecency;
 pub mod fuzzy;
 pub mod input_history;
 pub mod keybindings;
+pub mod keybindings_file;
 pub mod multi_cursor;
+pub mod path_completion;
 pub mod position_history;
+pub mod vim;
+pub mod when_clause;
@@ -0,0 +1,183 @@
+//! Path expansion and completion for file prompts
+//!
+//! Paths typed into "Open File" and "Save As" may start with `~` and contain
+//! `$VAR` or `${VAR}`. Completion lists the entries of the directory the last
+//! path component is in, keeping the directory part as the user typed it.
+
+use crate::input::commands::Suggestion;
+use crate::primitives::variables::expand_env_variables;
+use std::path::{Path, PathBuf};
+
+/// Most completions listed at once
+const MAX_COMPLETIONS: usize = 100;
+
+/// Whether the input names a path on its own rather than something relative
+/// to the directory being browsed (e.g., "/etc", "~/notes", "$HOME/src")
+pub fn is_rooted(input: &str) -> bool {
+    input.starts_with('/') || input.starts_with('~') || input.starts_with('$')
+}
+
+/// Expand a leading `~` and `$VAR` / `${VAR}` references
+///
+/// Variables that are not set are left as they are.
+pub fn expand_path(input: &str) -> String {
+    expand_path_with(input, dirs::home_dir(), |name| std::env::var(name).ok())
+}
+
+fn expand_path_with(
+    input: &str,
+    home: Option<PathBuf>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    // Only "~" and "~/..." refer to the home directory ("~user" is not supported)
+    if let (Some(after), Some(home)) = (input.strip_prefix('~'), home) {
+        if after.is_empty() || after.starts_with('/') {
+            result.push_str(&home.to_string_lossy());
+            rest = after;
+        }
+    }
+    result.push_str(&expand_env_variables(rest, lookup));
+
+    result
+}
+
+/// Resolve a path typed into a file prompt
+///
+/// Relative paths are relative to `working_dir`.
+pub fn resolve_path(input: &str, working_dir: &Path) -> PathBuf {
+    let expanded = PathBuf::from(expand_path(input));
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        working_dir.join(expanded)
+    }
+}
+
+/// Split a typed path into its directory part (with the trailing `/`) and the
+/// component being completed
+pub fn split_last_component(input: &str) -> (&str, &str) {
+    match input.rfind('/') {
+        Some(slash) => input.split_at(slash + 1),
+        None => ("", input),
+    }
+}
+
+/// Completions for the last component of a typed path
+///
+/// Directories come first and end with `/`. Hidden entries are listed when
+/// `show_hidden` is set or the component being completed starts with a dot.
+pub fn path_completions(input: &str, working_dir: &Path, show_hidden: bool) -> Vec<Suggestion> {
+    let (dir_part, pattern) = split_last_component(input);
+    let dir = resolve_path(dir_part, working_dir);
+    let Ok(read_dir) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let show_hidden = show_hidden || pattern.starts_with('.');
+    let pattern = pattern.to_lowercase();
+    let mut entries: Vec<(String, bool)> = read_dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            (name, entry.path().is_dir())
+        })
+        .filter(|(name, _)| show_hidden || !name.starts_with('.'))
+        .filter(|(name, _)| name.to_lowercase().starts_with(&pattern))
+        .collect();
+    entries.sort_by(|(a, a_dir), (b, b_dir)| b_dir.cmp(a_dir).then_with(|| a.cmp(b)));
+    entries.truncate(MAX_COMPLETIONS);
+
+    entries
+        .into_iter()
+        .map(|(name, is_dir)| {
+            let suffix = if is_dir { "/" } else { "" };
+            Suggestion {
+                text: format!("{}{}", name, suffix),
+                description: is_dir.then(|| "directory".to_string()),
+                value: Some(format!("{}{}{}", dir_part, name, suffix)),
+                disabled: false,
+                keybinding: None,
+                source: None,
+                match_indices: Vec::new(),
+                actions: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Longest prefix shared by all completions (what Tab fills in)
+pub fn common_prefix(suggestions: &[Suggestion]) -> Option<String> {
+    let mut values = suggestions.iter().map(|s| s.get_value());
+    let first = values.next()?;
+    let mut len = first.len();
+    for value in values {
+        len = first
+            .char_indices()
+            .zip(value.chars())
+            .take_while(|((i, a), b)| *i < len && a == b)
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0);
+    }
+    Some(first[..len].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(input: &str) -> String {
+        expand_path_with(input, Some(PathBuf::from("/home/me")), |name| {
+            (name == "PROJECT").then(|| "/src/fresh".to_string())
+        })
+    }
+
+    #[test]
+    fn test_expand_path() {
+        assert_eq!(expand("~"), "/home/me");
+        assert_eq!(expand("~/notes.txt"), "/home/me/notes.txt");
+        assert_eq!(expand("~other/x"), "~other/x");
+        assert_eq!(expand("$PROJECT/src"), "/src/fresh/src");
+        assert_eq!(expand("${PROJECT}_old/a"), "/src/fresh_old/a");
+        assert_eq!(expand("$UNSET/a"), "$UNSET/a");
+        assert_eq!(expand("${UNSET}/a"), "${UNSET}/a");
+        assert_eq!(expand("a$/b${"), "a$/b${");
+        assert_eq!(expand("price$"), "price$");
+    }
+
+    #[test]
+    fn test_path_completions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("src/mod.rs"), "").unwrap();
+        std::fs::write(dir.path().join("setup.sh"), "").unwrap();
+        std::fs::write(dir.path().join(".secret"), "").unwrap();
+
+        let texts = |suggestions: Vec<Suggestion>| -> Vec<String> {
+            suggestions.into_iter().map(|s| s.text).collect()
+        };
+
+        assert_eq!(
+            texts(path_completions("s", dir.path(), false)),
+            vec!["src/", "setup.sh"]
+        );
+        assert_eq!(texts(path_completions("", dir.path(), false)).len(), 2);
+        assert_eq!(texts(path_completions("", dir.path(), true)).len(), 3);
+        assert_eq!(
+            texts(path_completions(".s", dir.path(), false)),
+            vec![".secret"]
+        );
+
+        let nested = path_completions("src/m", dir.path(), false);
+        assert_eq!(nested[0].get_value(), "src/main.rs");
+        assert_eq!(common_prefix(&nested).as_deref(), Some("src/m"));
+        assert_eq!(
+            common_prefix(&path_completions("src/ma", dir.path(), false)).as_deref(),
+            Some("src/main.rs")
+        );
+    }
+}
@@ -0,0 +1,529 @@
+//! Vim-style modal editing
+//!
+//! With `editor.vim_mode` on, keys in a buffer go through [`VimState`] first.
+//! It keeps the mode (normal, insert or visual), the pending count and the
+//! pending operator, and turns them into plain [`Action`]s: `3j` is three
+//! `MoveDown`s, `dw` is `SetMark`, `MoveWordRight`, `Cut`. Motions themselves
+//! are ordinary bindings in the `vim_normal` and `vim_visual` contexts
+//! (`keymaps/vim.json`), so they can be rebound like any other key.
+
+use crate::input::keybindings::{Action, KeyContext};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Largest count accepted (further digits are ignored)
+const MAX_COUNT: usize = 9999;
+
+/// The current vim mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VimMode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+}
+
+impl VimMode {
+    /// Name shown in the status bar
+    pub fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+        }
+    }
+
+    /// Key context the mode's bindings live in (insert mode uses the regular
+    /// editing bindings)
+    pub fn key_context(self) -> Option<KeyContext> {
+        match self {
+            VimMode::Normal => Some(KeyContext::VimNormal),
+            VimMode::Insert => None,
+            VimMode::Visual => Some(KeyContext::VimVisual),
+        }
+    }
+}
+
+/// An operator waiting for its motion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> char {
+        match self {
+            Operator::Delete => 'd',
+            Operator::Change => 'c',
+            Operator::Yank => 'y',
+        }
+    }
+}
+
+/// Actions to run for a key
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VimCommand {
+    pub actions: Vec<Action>,
+    /// Step over the character under the cursor first, unless it ends the
+    /// line (`a`)
+    pub append: bool,
+    /// Afterwards, drop the selection and put the cursor at its start
+    /// (yanking leaves the cursor where the yanked text begins)
+    pub collapse_to_start: bool,
+}
+
+impl VimCommand {
+    fn run(actions: Vec<Action>) -> Self {
+        Self {
+            actions,
+            ..Self::default()
+        }
+    }
+}
+
+/// Mode, count and pending operator of the vim layer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VimState {
+    mode: VimMode,
+    /// Count typed so far (e.g. the 3 of `3dw`)
+    count: Option<usize>,
+    /// Operator typed so far, with the count given before it
+    operator: Option<(Operator, Option<usize>)>,
+}
+
+impl VimState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Switch mode, dropping any pending count or operator
+    pub fn set_mode(&mut self, mode: VimMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    /// Mode and pending keys for the status bar (e.g. "NORMAL 2d")
+    pub fn status(&self) -> String {
+        let mut pending = String::new();
+        if let Some((operator, count)) = self.operator {
+            if let Some(count) = count {
+                pending.push_str(&count.to_string());
+            }
+            pending.push(operator.key());
+        }
+        if let Some(count) = self.count {
+            pending.push_str(&count.to_string());
+        }
+        if pending.is_empty() {
+            self.mode.label().to_string()
+        } else {
+            format!("{} {}", self.mode.label(), pending)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+    }
+
+    /// Handle a key the vim layer owns (mode switches, counts, operators).
+    /// Returns `None` for keys that resolve through the key bindings instead;
+    /// their action is then passed to [`VimState::command`].
+    pub fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<VimCommand> {
+        if code == KeyCode::Esc {
+            return Some(match self.mode {
+                VimMode::Insert => {
+                    self.set_mode(VimMode::Normal);
+                    VimCommand::default()
+                }
+                VimMode::Normal | VimMode::Visual => {
+                    self.set_mode(VimMode::Normal);
+                    VimCommand::run(vec![Action::RemoveSecondaryCursors])
+                }
+            });
+        }
+        if self.mode == VimMode::Insert {
+            return None;
+        }
+        let KeyCode::Char(c) = code else {
+            return None;
+        };
+        if !(modifiers - KeyModifiers::SHIFT).is_empty() {
+            return None;
+        }
+
+        // Counts (a leading 0 is the line start motion)
+        if let Some(digit) = c.to_digit(10) {
+            if digit > 0 || self.count.is_some() {
+                let count = self.count.unwrap_or(0) * 10 + digit as usize;
+                if count <= MAX_COUNT {
+                    self.count = Some(count);
+                }
+                return Some(VimCommand::default());
+            }
+            return None;
+        }
+
+        // Key names in keymaps are case-insensitive, so motions on capital
+        // letters are built in
+        match c {
+            'G' => return Some(self.command(Action::MoveDocumentEnd)),
+            'N' => return Some(self.command(Action::FindPrevious)),
+            _ => {}
+        }
+
+        if self.mode == VimMode::Visual {
+            return self.visual_key(c);
+        }
+
+        // Operators, or a doubled operator for whole lines (`dd`)
+        if let Some(operator) = Operator::from_char(c) {
+            return Some(match self.operator {
+                Some((pending, before)) if pending == operator => {
+                    let lines = multiply(before, self.count).unwrap_or(1);
+                    self.reset();
+                    self.apply(operator, linewise(operator, lines))
+                }
+                Some(_) => {
+                    self.reset();
+                    VimCommand::default()
+                }
+                None => {
+                    self.operator = Some((operator, self.count.take()));
+                    VimCommand::default()
+                }
+            });
+        }
+        if self.operator.is_some() {
+            return None;
+        }
+
+        let (actions, mode) = match c {
+            'i' => (vec![], VimMode::Insert),
+            'a' => {
+                self.set_mode(VimMode::Insert);
+                return Some(VimCommand {
+                    append: true,
+                    ..VimCommand::default()
+                });
+            }
+            'A' => (vec![Action::MoveLineEnd], VimMode::Insert),
+            'I' => (vec![Action::SmartHome], VimMode::Insert),
+            'o' => (
+                vec![Action::MoveLineEnd, Action::InsertNewline],
+                VimMode::Insert,
+            ),
+            'O' => (
+                vec![Action::MoveLineStart, Action::InsertNewline, Action::MoveUp],
+                VimMode::Insert,
+            ),
+            'v' => (vec![Action::SetMark], VimMode::Visual),
+            'D' => (
+                vec![Action::SetMark, Action::MoveLineEnd, Action::Cut],
+                VimMode::Normal,
+            ),
+            'C' => (
+                vec![Action::SetMark, Action::MoveLineEnd, Action::Cut],
+                VimMode::Insert,
+            ),
+            _ => return None,
+        };
+        self.set_mode(mode);
+        Some(VimCommand::run(actions))
+    }
+
+    /// Operators in visual mode act on the selection
+    fn visual_key(&mut self, c: char) -> Option<VimCommand> {
+        let (command, mode) = match c {
+            'd' | 'x' => (VimCommand::run(vec![Action::Cut]), VimMode::Normal),
+            'c' | 's' => (VimCommand::run(vec![Action::Cut]), VimMode::Insert),
+            'y' => (
+                VimCommand {
+                    actions: vec![Action::Copy],
+                    collapse_to_start: true,
+                    ..VimCommand::default()
+                },
+                VimMode::Normal,
+            ),
+            'v' => (
+                VimCommand::run(vec![Action::RemoveSecondaryCursors]),
+                VimMode::Normal,
+            ),
+            _ => return None,
+        };
+        self.set_mode(mode);
+        Some(command)
+    }
+
+    /// Apply the pending count and operator to an action bound in a vim
+    /// context
+    pub fn command(&mut self, action: Action) -> VimCommand {
+        let operator = self.operator.take();
+        let count = multiply(operator.and_then(|(_, before)| before), self.count.take());
+
+        let Some((operator, _)) = operator else {
+            if action == Action::None {
+                return VimCommand::default();
+            }
+            return VimCommand::run(repeat(action, count));
+        };
+
+        // An operator takes the text a motion moves over
+        let selection = match action {
+            Action::MoveDown => linewise(operator, count.unwrap_or(1) + 1),
+            Action::MoveUp => {
+                let lines = count.unwrap_or(1);
+                let mut actions = vec![Action::MoveUp; lines];
+                actions.extend(linewise(operator, lines + 1));
+                actions
+            }
+            // `cw` changes to the end of the word, leaving the space after it
+            Action::MoveWordRight if operator == Operator::Change => {
+                charwise(Action::MoveWordEnd, count)
+            }
+            action if is_motion(&action) => charwise(action, count),
+            _ => return VimCommand::default(),
+        };
+        self.apply(operator, selection)
+    }
+
+    /// Run `operator` on what `selection` selects
+    fn apply(&mut self, operator: Operator, mut actions: Vec<Action>) -> VimCommand {
+        match operator {
+            Operator::Delete => actions.push(Action::Cut),
+            Operator::Change => {
+                actions.push(Action::Cut);
+                self.set_mode(VimMode::Insert);
+            }
+            Operator::Yank => {
+                actions.push(Action::Copy);
+                return VimCommand {
+                    actions,
+                    collapse_to_start: true,
+                    ..VimCommand::default()
+                };
+            }
+        }
+        VimCommand::run(actions)
+    }
+}
+
+/// Counts given before and after an operator multiply (`2d3w` deletes six
+/// words)
+fn multiply(before: Option<usize>, after: Option<usize>) -> Option<usize> {
+    match (before, after) {
+        (Some(a), Some(b)) => Some((a * b).min(MAX_COUNT)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Actions that move the cursor, and so can follow an operator
+fn is_motion(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::MoveLeft
+            | Action::MoveRight
+            | Action::MoveUp
+            | Action::MoveDown
+            | Action::MoveWordLeft
+            | Action::MoveWordRight
+            | Action::MoveWordEnd
+            | Action::MoveLineStart
+            | Action::MoveLineEnd
+            | Action::SmartHome
+            | Action::MovePageUp
+            | Action::MovePageDown
+            | Action::MoveDocumentStart
+            | Action::MoveDocumentEnd
+    )
+}
+
+/// Run an action `count` times, for actions where repeating makes sense
+fn repeat(action: Action, count: Option<usize>) -> Vec<Action> {
+    let Some(count) = count else {
+        return vec![action];
+    };
+    match action {
+        // A count on `gg` or `G` goes to that line
+        Action::MoveDocumentStart | Action::MoveDocumentEnd => {
+            let mut actions = vec![Action::MoveDocumentStart];
+            actions.extend(vec![Action::MoveDown; count - 1]);
+            actions
+        }
+        action
+            if is_motion(&action)
+                || matches!(
+                    action,
+                    Action::DeleteForward
+                        | Action::DeleteBackward
+                        | Action::Paste
+                        | Action::Undo
+                        | Action::Redo
+                        | Action::FindNext
+                        | Action::FindPrevious
+                ) =>
+        {
+            vec![action; count]
+        }
+        action => vec![action],
+    }
+}
+
+/// Select from the cursor over `count` repetitions of a motion
+fn charwise(motion: Action, count: Option<usize>) -> Vec<Action> {
+    let mut actions = vec![Action::SetMark];
+    actions.extend(repeat(motion, count));
+    actions
+}
+
+/// Select `lines` whole lines from the cursor's line down. A change keeps the
+/// final newline, leaving an empty line to type into.
+fn linewise(operator: Operator, lines: usize) -> Vec<Action> {
+    let mut actions = vec![Action::MoveLineStart, Action::SetMark];
+    actions.extend(vec![Action::MoveDown; lines.saturating_sub(1)]);
+    actions.push(Action::MoveLineEnd);
+    if operator != Operator::Change {
+        actions.push(Action::MoveRight);
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(vim: &mut VimState, keys: &str) -> Option<VimCommand> {
+        let mut last = None;
+        for c in keys.chars() {
+            last = vim.key(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        last
+    }
+
+    #[test]
+    fn test_counts_repeat_motions() {
+        let mut vim = VimState::new();
+        assert_eq!(keys(&mut vim, "12"), Some(VimCommand::default()));
+        assert_eq!(vim.status(), "NORMAL 12");
+        assert_eq!(vim.command(Action::MoveDown).actions.len(), 12);
+        assert_eq!(vim.status(), "NORMAL");
+
+        // 0 is a motion unless a count is being typed
+        assert_eq!(keys(&mut vim, "0"), None);
+
+        // A count on G goes to that line
+        assert_eq!(
+            keys(&mut vim, "3G").unwrap().actions,
+            vec![
+                Action::MoveDocumentStart,
+                Action::MoveDown,
+                Action::MoveDown
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut vim = VimState::new();
+        assert_eq!(keys(&mut vim, "2d"), Some(VimCommand::default()));
+        assert_eq!(keys(&mut vim, "3"), Some(VimCommand::default()));
+        assert_eq!(vim.status(), "NORMAL 2d3");
+        let mut expected = vec![Action::SetMark];
+        expected.extend(vec![Action::MoveWordRight; 6]);
+        expected.push(Action::Cut);
+        assert_eq!(vim.command(Action::MoveWordRight).actions, expected);
+
+        // cw changes to the end of the word
+        keys(&mut vim, "c");
+        assert_eq!(
+            vim.command(Action::MoveWordRight).actions,
+            vec![Action::SetMark, Action::MoveWordEnd, Action::Cut]
+        );
+        assert_eq!(vim.mode(), VimMode::Insert);
+        vim.key(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        // Yanking leaves the cursor at the start of the text
+        keys(&mut vim, "y");
+        assert!(vim.command(Action::MoveWordLeft).collapse_to_start);
+
+        // An operator followed by something other than a motion is dropped
+        keys(&mut vim, "d");
+        assert_eq!(vim.command(Action::Undo), VimCommand::default());
+        assert_eq!(vim.status(), "NORMAL");
+    }
+
+    #[test]
+    fn test_linewise_operators() {
+        let mut vim = VimState::new();
+        assert_eq!(
+            keys(&mut vim, "2dd").unwrap().actions,
+            vec![
+                Action::MoveLineStart,
+                Action::SetMark,
+                Action::MoveDown,
+                Action::MoveLineEnd,
+                Action::MoveRight,
+                Action::Cut
+            ]
+        );
+
+        keys(&mut vim, "d");
+        assert_eq!(
+            vim.command(Action::MoveUp).actions,
+            vec![
+                Action::MoveUp,
+                Action::MoveLineStart,
+                Action::SetMark,
+                Action::MoveDown,
+                Action::MoveLineEnd,
+                Action::MoveRight,
+                Action::Cut
+            ]
+        );
+
+        // cc keeps the line break
+        let cc = keys(&mut vim, "cc").unwrap();
+        assert_eq!(cc.actions.last(), Some(&Action::Cut));
+        assert!(!cc.actions.contains(&Action::MoveRight));
+        assert_eq!(vim.mode(), VimMode::Insert);
+    }
+
+    #[test]
+    fn test_modes() {
+        let mut vim = VimState::new();
+        assert_eq!(vim.mode().key_context(), Some(KeyContext::VimNormal));
+
+        assert!(keys(&mut vim, "a").unwrap().append);
+        assert_eq!(vim.mode(), VimMode::Insert);
+        assert_eq!(vim.mode().key_context(), None);
+        // Insert mode types text
+        assert_eq!(keys(&mut vim, "d"), None);
+        vim.key(KeyCode::Esc, KeyModifiers::NONE);
+
+        assert_eq!(keys(&mut vim, "v").unwrap().actions, vec![Action::SetMark]);
+        assert_eq!(vim.status(), "VISUAL");
+        assert_eq!(keys(&mut vim, "w"), None);
+        let yank = keys(&mut vim, "y").unwrap();
+        assert_eq!(yank.actions, vec![Action::Copy]);
+        assert!(yank.collapse_to_start);
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        // Keys with modifiers resolve through the bindings
+        assert_eq!(vim.key(KeyCode::Char('r'), KeyModifiers::CONTROL), None);
+    }
+}
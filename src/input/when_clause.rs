@@ -0,0 +1,295 @@
+//! "When" clauses: conditions on editor state that enable commands
+//!
+//! A command with a when clause is only available (not greyed out in the
+//! palette) while its clause holds. Clauses combine context keys:
+//!
+//! ```text
+//! has_selection && !read_only
+//! buffer_modified || language == 'rust'
+//! (multiple_cursors || has_selection) && language != 'markdown'
+//! ```
+//!
+//! A bare key is true if the editor set it (see `WhenContext`); `==` and
+//! `!=` compare a key's value with a quoted string.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Values of the context keys when a clause is evaluated
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WhenContext {
+    values: HashMap<String, String>,
+}
+
+impl WhenContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a key that is either true or false
+    pub fn set_flag(&mut self, key: &str, value: bool) {
+        if value {
+            self.values.insert(key.to_string(), "true".to_string());
+        } else {
+            self.values.remove(key);
+        }
+    }
+
+    /// Set a key to a value (compared with `==` and `!=`)
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        self.values.insert(key.to_string(), value.into());
+    }
+
+    /// Whether `key` is set to a non-empty value
+    fn is_true(&self, key: &str) -> bool {
+        self.values.get(key).is_some_and(|value| !value.is_empty())
+    }
+
+    fn get(&self, key: &str) -> &str {
+        self.values.get(key).map_or("", String::as_str)
+    }
+}
+
+/// A parsed when clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhenClause {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Key(String),
+    Equals(String, String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl WhenClause {
+    /// Parse a clause such as `has_selection && !read_only`
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(format!("unexpected {}", token));
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// Whether the clause holds in `context`
+    pub fn evaluate(&self, context: &WhenContext) -> bool {
+        self.expr.evaluate(context)
+    }
+}
+
+impl fmt::Display for WhenClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Expr {
+    fn evaluate(&self, context: &WhenContext) -> bool {
+        match self {
+            Expr::Key(key) => context.is_true(key),
+            Expr::Equals(key, value) => context.get(key) == value,
+            Expr::Not(expr) => !expr.evaluate(context),
+            Expr::And(a, b) => a.evaluate(context) && b.evaluate(context),
+            Expr::Or(a, b) => a.evaluate(context) || b.evaluate(context),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Key(String),
+    Str(String),
+    Not,
+    And,
+    Or,
+    Eq,
+    NotEq,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Key(key) => write!(f, "'{}'", key),
+            Token::Str(value) => write!(f, "string '{}'", value),
+            Token::Not => f.write_str("'!'"),
+            Token::And => f.write_str("'&&'"),
+            Token::Or => f.write_str("'||'"),
+            Token::Eq => f.write_str("'=='"),
+            Token::NotEq => f.write_str("'!='"),
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::NotEq,
+            '!' => Token::Not,
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Eq,
+            '&' if chars.next_if_eq(&'&').is_some() => Token::And,
+            '|' if chars.next_if_eq(&'|').is_some() => Token::Or,
+            '\'' | '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(ch) => value.push(ch),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                Token::Str(value)
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut key = c.to_string();
+                while let Some(ch) =
+                    chars.next_if(|ch| ch.is_alphanumeric() || *ch == '_' || *ch == '.')
+                {
+                    key.push(ch);
+                }
+                Token::Key(key)
+            }
+            c => return Err(format!("unexpected '{}'", c)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser: `||` binds looser than `&&`, which binds
+/// looser than `!` and comparisons
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.pos) == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let expr = self.parse_or()?;
+                if !self.eat(&Token::Close) {
+                    return Err("missing ')'".to_string());
+                }
+                Ok(expr)
+            }
+            Some(Token::Key(key)) => {
+                let negate = if self.eat(&Token::Eq) {
+                    false
+                } else if self.eat(&Token::NotEq) {
+                    true
+                } else {
+                    return Ok(Expr::Key(key));
+                };
+                let value = match self.next() {
+                    Some(Token::Str(value) | Token::Key(value)) => value,
+                    Some(token) => return Err(format!("expected a value, found {}", token)),
+                    None => return Err("expected a value".to_string()),
+                };
+                let expr = Expr::Equals(key, value);
+                Ok(if negate {
+                    Expr::Not(Box::new(expr))
+                } else {
+                    expr
+                })
+            }
+            Some(token) => Err(format!("unexpected {}", token)),
+            None => Err("unexpected end of clause".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> WhenContext {
+        let mut context = WhenContext::new();
+        context.set_flag("has_selection", true);
+        context.set_flag("read_only", false);
+        context.set("language", "rust");
+        context
+    }
+
+    fn holds(clause: &str) -> bool {
+        WhenClause::parse(clause).unwrap().evaluate(&context())
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert!(holds("has_selection"));
+        assert!(!holds("read_only"));
+        assert!(holds("!read_only"));
+        assert!(!holds("unknown_key"));
+        assert!(holds("has_selection && !read_only"));
+        assert!(holds("read_only || language == 'rust'"));
+        assert!(holds("language != \"python\""));
+        assert!(!holds("!(has_selection || read_only)"));
+        // && binds tighter than ||
+        assert!(holds("has_selection || read_only && read_only"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(WhenClause::parse("").is_err());
+        assert!(WhenClause::parse("has_selection &&").is_err());
+        assert!(WhenClause::parse("(has_selection").is_err());
+        assert!(WhenClause::parse("language == ").is_err());
+        assert!(WhenClause::parse("a b").is_err());
+        assert!(WhenClause::parse("language == 'rust").is_err());
+        assert_eq!(
+            WhenClause::parse(" has_selection ").unwrap().to_string(),
+            "has_selection"
+        );
+    }
+}
@@ -2,21 +2,25 @@ use clap::Parser;
 use crossterm::{
     cursor::SetCursorStyle,
     event::{
-        poll as event_poll, read as event_read, Event as CrosstermEvent, KeyEvent, KeyEventKind,
-        KeyboardEnhancementFlags, MouseEvent, PopKeyboardEnhancementFlags,
-        PushKeyboardEnhancementFlags,
+        poll as event_poll, read as event_read, DisableBracketedPaste, EnableBracketedPaste,
+        Event as CrosstermEvent, KeyEvent, KeyEventKind, KeyboardEnhancementFlags, MouseEvent,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use fresh::{
-    app::script_control::ScriptControlMode, app::Editor, config, services::signal_handler,
+    app::pager::StdinSpool, app::script_control::ScriptControlMode,
+    app::shell_integration::PickMode, app::Editor, config, services::signal_handler,
+    view::diff_backend::DiffBackend,
 };
-use ratatui::Terminal;
+use ratatui::backend::Backend;
+use ratatui::layout::{Position, Rect};
+use ratatui::{Terminal, TerminalOptions, Viewport};
 use std::{
-    io::{self, stdout},
+    io::{self, stdout, Write},
     path::PathBuf,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
@@ -46,6 +50,10 @@ struct Args {
     #[arg(long, value_name = "LOG_FILE")]
     event_log: Option<PathBuf>,
 
+    /// Write how long each key took from press to paint, stage by stage, to FILE
+    #[arg(long, value_name = "FILE")]
+    trace_latency: Option<PathBuf>,
+
     /// Enable script control mode (accepts JSON commands via stdin, outputs to stdout)
     #[arg(long)]
     script_mode: bool,
@@ -65,11 +73,167 @@ struct Args {
     /// Don't restore previous session (start fresh)
     #[arg(long)]
     no_session: bool,
+
+    /// Start up, draw the first frame, exit and print how long each startup phase took
+    #[arg(long)]
+    bench_startup: bool,
+
+    /// Pick a file or directory in the file explorer and print its path on exit
+    #[arg(long, conflicts_with = "pick_dir")]
+    pick: bool,
+
+    /// Pick a directory and print it on exit (a picked file prints its directory),
+    /// e.g. cd "$(fresh --pick-dir)"
+    #[arg(long)]
+    pick_dir: bool,
+
+    /// Draw the editor in ROWS rows below the shell's output instead of on the
+    /// alternate screen (default: editor.inline_rows, or 20), e.g. --inline=15
+    #[arg(
+        long,
+        value_name = "ROWS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "0"
+    )]
+    inline: Option<u16>,
+
+    /// View FILE, or stdin if it is piped or FILE is "-", read-only like less
+    /// (q quits, / searches, F follows the end)
+    #[arg(long, conflicts_with_all = ["pick", "pick_dir"])]
+    pager: bool,
+}
+
+/// Rows `--inline` draws the editor in when editor.inline_rows isn't set
+const DEFAULT_INLINE_ROWS: u16 = 20;
+
+/// Where the editor is drawn in inline mode
+#[derive(Debug, Clone, Copy)]
+struct InlineArea {
+    /// Terminal row of the editor's first row
+    top: u16,
+    rows: u16,
+}
+
+impl InlineArea {
+    /// Make room for `rows` rows from the cursor down, scrolling the shell's
+    /// output up if there are fewer left
+    fn reserve(rows: u16) -> io::Result<Self> {
+        let (_, height) = crossterm::terminal::size()?;
+        let rows = rows.clamp(1, height.max(1));
+        let (column, _) = crossterm::cursor::position()?;
+        let mut out = stdout();
+        if column > 0 {
+            out.write_all(b"\r\n")?;
+        }
+        for _ in 1..rows {
+            out.write_all(b"\n")?;
+        }
+        out.flush()?;
+        let (_, bottom) = crossterm::cursor::position()?;
+        Ok(Self {
+            top: (bottom + 1).saturating_sub(rows),
+            rows,
+        })
+    }
+}
+
+/// Whether --pager shows stdin: with no file, or a file named "-"
+fn pager_reads_stdin(file: Option<&std::path::Path>) -> bool {
+    file.is_none_or(|path| path == std::path::Path::new("-"))
+}
+
+/// Where --pick and --pick-dir print the picked path
+///
+/// Inside `$(...)` stdout is a pipe to the shell, so the UI is drawn on the
+/// terminal (/dev/tty) instead and the path goes to the original stdout.
+struct PickOutput {
+    /// Original stdout, when it was moved aside
+    stdout: Option<std::fs::File>,
+}
+
+impl PickOutput {
+    fn redirect_stdout_to_tty() -> io::Result<Self> {
+        use std::io::IsTerminal;
+        if stdout().is_terminal() {
+            return Ok(Self { stdout: None });
+        }
+        #[cfg(unix)]
+        {
+            use std::os::fd::{AsRawFd, FromRawFd};
+            let tty = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open("/dev/tty")?;
+            // SAFETY: dup returns a new descriptor that the File takes ownership of
+            let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+            if saved < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let original = unsafe { std::fs::File::from_raw_fd(saved) };
+            // SAFETY: both descriptors are open; fd 1 now refers to the terminal
+            if unsafe { libc::dup2(tty.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self {
+                stdout: Some(original),
+            })
+        }
+        #[cfg(not(unix))]
+        Ok(Self { stdout: None })
+    }
+
+    fn print(self, path: &std::path::Path) -> io::Result<()> {
+        let line = format!("{}\n", path.display());
+        match self.stdout {
+            Some(mut original) => original.write_all(line.as_bytes()),
+            None => stdout().write_all(line.as_bytes()),
+        }
+    }
+}
+
+/// Time spent in each startup phase, for --bench-startup
+struct StartupTimer {
+    start: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl StartupTimer {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            last: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// End the current phase
+    fn phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.phases.push((name, now - self.last));
+        self.last = now;
+    }
+
+    fn report(&self) -> String {
+        let line = |name: &str, duration: Duration| {
+            format!("{:<16} {:>9.2} ms\n", name, duration.as_secs_f64() * 1000.0)
+        };
+        let mut report: String = self
+            .phases
+            .iter()
+            .map(|(name, duration)| line(name, *duration))
+            .collect();
+        report.push_str(&line("total", self.last - self.start));
+        report
+    }
 }
 
 fn main() -> io::Result<()> {
     // Parse command-line arguments
     let args = Args::parse();
+    let mut timer = StartupTimer::new();
 
     // Handle --script-schema flag
     if args.script_schema {
@@ -108,6 +272,7 @@ fn main() -> io::Result<()> {
     std::panic::set_hook(Box::new(move |panic| {
         let _ = stdout().execute(SetCursorStyle::DefaultUserShape);
         let _ = stdout().execute(PopKeyboardEnhancementFlags);
+        let _ = stdout().execute(DisableBracketedPaste);
         let _ = disable_raw_mode();
         let _ = stdout().execute(LeaveAlternateScreen);
         original_hook(panic);
@@ -129,10 +294,51 @@ fn main() -> io::Result<()> {
     } else {
         config::Config::default()
     };
+    timer.phase("config");
+
+    let pick_mode = if args.pick_dir {
+        Some(PickMode::Directory)
+    } else if args.pick {
+        Some(PickMode::Path)
+    } else {
+        None
+    };
+    let pick_output = match pick_mode {
+        Some(_) => Some(PickOutput::redirect_stdout_to_tty()?),
+        None => None,
+    };
+
+    // Piped input is read from the start, so it can be paged while it arrives
+    let stdin_spool = if args.pager && pager_reads_stdin(args.file.as_deref()) {
+        use std::io::IsTerminal;
+        if io::stdin().is_terminal() {
+            eprintln!("Error: --pager needs a file or piped input");
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "nothing to page",
+            ));
+        }
+        Some(StdinSpool::start()?)
+    } else {
+        None
+    };
+
+    let inline_rows = match args.inline {
+        Some(0) if config.editor.inline_rows > 0 => Some(config.editor.inline_rows),
+        Some(0) => Some(DEFAULT_INLINE_ROWS),
+        Some(rows) => Some(rows),
+        None => (config.editor.inline_rows > 0).then_some(config.editor.inline_rows),
+    };
 
     // Set up terminal first
     enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
+    let inline = match inline_rows {
+        Some(rows) => Some(InlineArea::reserve(rows)?),
+        None => {
+            stdout().execute(EnterAlternateScreen)?;
+            None
+        }
+    };
 
     // Enable keyboard enhancement flags to support Shift+Up/Down and other modifier combinations
     // This uses the Kitty keyboard protocol for better key detection in supported terminals
@@ -145,21 +351,41 @@ fn main() -> io::Result<()> {
     let _ = crossterm::execute!(stdout(), crossterm::event::EnableMouseCapture);
     tracing::info!("Enabled mouse capture");
 
+    // Pastes arrive as one event rather than as typed keys
+    let _ = stdout().execute(EnableBracketedPaste);
+
     // Enable blinking block cursor for the primary cursor in active split
     let _ = stdout().execute(SetCursorStyle::BlinkingBlock);
     tracing::info!("Enabled blinking block cursor");
 
-    let backend = ratatui::backend::CrosstermBackend::new(stdout());
-    let mut terminal = Terminal::new(backend)?;
+    // Only changed cells are written, in one write per frame (matters over SSH)
+    let mut terminal = match inline {
+        Some(area) => Terminal::with_options(
+            DiffBackend::inline(stdout(), area.top),
+            TerminalOptions {
+                viewport: Viewport::Fixed(Rect::new(
+                    0,
+                    0,
+                    crossterm::terminal::size()?.0,
+                    area.rows,
+                )),
+            },
+        )?,
+        None => Terminal::new(DiffBackend::new(stdout()))?,
+    };
 
     // Clear the terminal to ensure proper initialization
     terminal.clear()?;
 
-    let size = terminal.size()?;
+    let size = terminal.get_frame().area().as_size();
     tracing::info!("Terminal size: {}x{}", size.width, size.height);
+    timer.phase("terminal");
 
     // Determine if the provided path is a directory or file
-    let (working_dir, file_to_open, show_file_explorer) = if let Some(path) = &args.file {
+    let (working_dir, file_to_open, show_file_explorer) = if args.pager {
+        // The pager opens its file itself
+        (None, None, false)
+    } else if let Some(path) = &args.file {
         if path.is_dir() {
             // Path is a directory: use as working dir, don't open any file, show file explorer
             (Some(path.clone()), None, true)
@@ -171,6 +397,8 @@ fn main() -> io::Result<()> {
         // No path provided: use current dir, no file, don't auto-show explorer
         (None, None, false)
     };
+    // Picking starts in the file explorer
+    let show_file_explorer = show_file_explorer || pick_mode.is_some();
 
     // Create editor with actual terminal size and working directory
     let mut editor = if args.no_plugins {
@@ -178,6 +406,7 @@ fn main() -> io::Result<()> {
     } else {
         Editor::with_working_dir(config, size.width, size.height, working_dir)?
     };
+    timer.phase("editor");
 
     // Enable event log streaming if requested
     if let Some(log_path) = &args.event_log {
@@ -185,8 +414,13 @@ fn main() -> io::Result<()> {
         editor.enable_event_streaming(log_path)?;
     }
 
+    if let Some(trace_path) = &args.trace_latency {
+        editor.enable_latency_trace(trace_path)?;
+    }
+
     // Try to restore previous session (unless --no-session flag is set or a file was specified)
-    let session_enabled = !args.no_session && file_to_open.is_none();
+    let session_enabled =
+        !args.no_session && file_to_open.is_none() && pick_mode.is_none() && !args.pager;
     if session_enabled {
         match editor.try_restore_session() {
             Ok(true) => {
@@ -199,14 +433,29 @@ fn main() -> io::Result<()> {
                 tracing::warn!("Failed to restore session: {}", e);
             }
         }
+        timer.phase("session");
     }
 
     // Open file if provided (this takes precedence over session)
     if let Some(path) = &file_to_open {
         editor.open_file(path)?;
+        timer.phase("open file");
     }
 
-    // Show file explorer if directory was provided
+    if let Some(mode) = pick_mode {
+        editor.set_pick_mode(mode);
+    }
+
+    if args.pager {
+        let path = match &stdin_spool {
+            Some(spool) => spool.path().to_path_buf(),
+            None => args.file.clone().unwrap_or_default(),
+        };
+        editor.start_pager(&path, stdin_spool)?;
+        timer.phase("open file");
+    }
+
+    // Show file explorer if directory was provided (or a path is being picked)
     if show_file_explorer {
         editor.show_file_explorer();
     }
@@ -227,25 +476,60 @@ fn main() -> io::Result<()> {
         }
     }
 
+    editor.load_command_frecency();
+    editor.init_project_env();
+    editor.init_project_scan();
+    editor.init_symbol_index();
+    editor.init_search_index();
+
     // Start recovery session
     if let Err(e) = editor.start_recovery_session() {
         tracing::warn!("Failed to start recovery session: {}", e);
     }
+    timer.phase("recovery");
 
-    // Run the editor
-    let result = run_event_loop(&mut editor, &mut terminal, session_enabled);
+    // Run the editor (or, when benchmarking startup, just draw the first frame)
+    let result = if args.bench_startup {
+        terminal
+            .draw(|frame| editor.render(frame))
+            .map(|_| timer.phase("first render"))
+    } else {
+        run_event_loop(&mut editor, &mut terminal, session_enabled, inline_rows)
+    };
 
     // End recovery session (clean shutdown)
     if let Err(e) = editor.end_recovery_session() {
         tracing::warn!("Failed to end recovery session: {}", e);
     }
+    editor.save_command_frecency();
+    editor.save_symbol_index();
+    editor.save_search_index();
+    editor.release_file_locks();
 
     // Clean up terminal
+    if inline.is_some() {
+        // Leave the shell prompt where the editor started
+        let _ = terminal.clear();
+        let _ = terminal.set_cursor_position(Position::ORIGIN);
+        let _ = terminal.backend_mut().flush();
+    }
     let _ = crossterm::execute!(stdout(), crossterm::event::DisableMouseCapture);
     let _ = stdout().execute(SetCursorStyle::DefaultUserShape);
     let _ = stdout().execute(PopKeyboardEnhancementFlags);
+    let _ = stdout().execute(DisableBracketedPaste);
     disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
+    if inline.is_none() {
+        stdout().execute(LeaveAlternateScreen)?;
+    }
+
+    if args.bench_startup && result.is_ok() {
+        print!("{}", timer.report());
+    }
+    if let (Some(output), Some(path)) = (pick_output, editor.picked_path()) {
+        if result.is_ok() {
+            output.print(&path)?;
+        }
+    }
 
     result
 }
@@ -276,11 +560,10 @@ fn run_script_control_mode(args: &Args) -> io::Result<()> {
 /// Main event loop
 fn run_event_loop(
     editor: &mut Editor,
-    terminal: &mut Terminal<ratatui::backend::CrosstermBackend<io::Stdout>>,
+    terminal: &mut Terminal<DiffBackend<io::Stdout>>,
     session_enabled: bool,
+    inline_rows: Option<u16>,
 ) -> io::Result<()> {
-    use std::time::Instant;
-
     const FRAME_DURATION: Duration = Duration::from_millis(16); // 60fps
     let mut last_render = Instant::now();
     let mut needs_render = true;
@@ -291,11 +574,61 @@ fn run_event_loop(
             needs_render = true;
         }
 
+        // Status messages go away once their display time is up
+        if editor.expire_status_messages() {
+            needs_render = true;
+        }
+
+        // A half-typed key chord is dropped after a while
+        if editor.expire_chord() {
+            needs_render = true;
+        }
+
+        // So is the Ctrl+Tab popup, as terminals don't report releasing Ctrl
+        if editor.expire_recent_buffer_cycle() {
+            needs_render = true;
+        }
+
+        // The screen locks after `editor.idle_lock_minutes` without input
+        if editor.check_idle_lock() {
+            needs_render = true;
+        }
+
+        // The pager shows text as it arrives
+        if editor.update_pager() {
+            needs_render = true;
+        }
+
+        // The status bar spinner turns while background tasks run
+        if editor.update_task_spinner() {
+            needs_render = true;
+        }
+
+        // The search prompt counts matches once typing pauses
+        if editor.update_search_match_count() {
+            needs_render = true;
+        }
+
+        // Picker previews update once the selection settles
+        if editor.update_suggestion_preview() {
+            needs_render = true;
+        }
+
+        // Tell the screen reader what changed (accessibility mode)
+        editor.update_announcements();
+
         // Periodic auto-save for recovery
         if let Err(e) = editor.auto_save_dirty_buffers() {
             tracing::debug!("Auto-save error: {}", e);
         }
 
+        // Periodic session snapshot
+        if session_enabled {
+            if let Err(e) = editor.snapshot_session_if_due() {
+                tracing::warn!("Failed to snapshot session: {}", e);
+            }
+        }
+
         if editor.should_quit() {
             // Save session before quitting (if enabled)
             if session_enabled {
@@ -311,10 +644,20 @@ fn run_event_loop(
         // Render at most 60fps
         if needs_render && last_render.elapsed() >= FRAME_DURATION {
             terminal.draw(|frame| editor.render(frame))?;
+            editor.frame_flushed();
             last_render = Instant::now();
             needs_render = false;
         }
 
+        // Syntax highlighting that didn't fit in the last frame, unless more
+        // input (e.g. further scrolling) is already waiting
+        if pending_event.is_none()
+            && !event_poll(Duration::ZERO)?
+            && editor.highlight_deferred_regions()
+        {
+            needs_render = true;
+        }
+
         // Get next event
         let event = if let Some(e) = pending_event.take() {
             Some(e)
@@ -346,15 +689,35 @@ fn run_event_loop(
                     needs_render = true;
                 }
             }
-            CrosstermEvent::Mouse(mouse_event) => {
-                if handle_mouse_event(editor, mouse_event)? {
-                    needs_render = true;
+            CrosstermEvent::Mouse(mut mouse_event) => {
+                // Inline, the rows above the editor are the shell's
+                let top = terminal.backend().top();
+                if mouse_event.row >= top {
+                    mouse_event.row -= top;
+                    if handle_mouse_event(editor, mouse_event)? {
+                        needs_render = true;
+                    }
                 }
             }
             CrosstermEvent::Resize(w, h) => {
+                let h = match inline_rows {
+                    Some(rows) => {
+                        // Keep the editor's rows on screen
+                        let rows = rows.min(h);
+                        let top = terminal.backend().top().min(h - rows);
+                        terminal.backend_mut().set_top(top);
+                        terminal.resize(Rect::new(0, 0, w, rows))?;
+                        rows
+                    }
+                    None => h,
+                };
                 editor.resize(w, h);
                 needs_render = true;
             }
+            CrosstermEvent::Paste(text) => {
+                editor.handle_paste(text);
+                needs_render = true;
+            }
             _ => {}
         }
     }
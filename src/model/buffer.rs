@@ -430,6 +430,64 @@ impl TextBuffer {
         Ok(())
     }
 
+    /// Take in what has been appended to the buffer's file since it was
+    /// loaded, as unmodified text. Returns how many bytes the buffer grew by.
+    ///
+    /// Large files reference the new bytes on disk; others read them in, and
+    /// switch to lazy loading once the file reaches `large_file_threshold`,
+    /// so a file that keeps growing (e.g. stdin in the pager) doesn't fill
+    /// memory.
+    pub fn append_from_file(&mut self, large_file_threshold: usize) -> io::Result<usize> {
+        let Some(path) = self.file_path.clone() else {
+            return Ok(0);
+        };
+        let file_size = std::fs::metadata(&path)?.len() as usize;
+        let start = self.saved_file_size.unwrap_or(0);
+        if file_size <= start || self.modified {
+            return Ok(0);
+        }
+
+        let old_len = self.total_bytes();
+        if !self.large_file && file_size >= large_file_threshold {
+            *self = Self::load_large_file(&path, file_size)?;
+            return Ok(self.total_bytes().saturating_sub(old_len));
+        }
+
+        let bytes = file_size - start;
+        let buffer_id = self.next_buffer_id;
+        let (buffer, line_feed_cnt) = if self.large_file {
+            (
+                StringBuffer::new_unloaded(buffer_id, path, start, bytes),
+                None,
+            )
+        } else {
+            let mut file = std::fs::File::open(&path)?;
+            file.seek(SeekFrom::Start(start as u64))?;
+            let mut data = vec![0u8; bytes];
+            file.read_exact(&mut data)?;
+            let data = Self::normalize_line_endings(data);
+            let line_feed_cnt = data.iter().filter(|&&b| b == b'\n').count();
+            (StringBuffer::new(buffer_id, data), Some(line_feed_cnt))
+        };
+        let added = match &buffer.data {
+            BufferData::Loaded { data, .. } => data.len(),
+            BufferData::Unloaded { bytes, .. } => *bytes,
+        };
+        self.next_buffer_id += 1;
+        self.buffers.push(buffer);
+        self.piece_tree.insert(
+            old_len,
+            BufferLocation::Stored(buffer_id),
+            0,
+            added,
+            line_feed_cnt,
+            &self.buffers,
+        );
+        self.saved_file_size = Some(file_size);
+        self.saved_root = self.piece_tree.root();
+        Ok(added)
+    }
+
     /// Get the total number of bytes in the document
     pub fn total_bytes(&self) -> usize {
         self.piece_tree.total_bytes()
@@ -2709,6 +2767,35 @@ mod tests {
             assert!(buffer.is_empty());
         }
 
+        #[test]
+        fn test_append_from_file_switches_to_lazy_loading() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("growing.log");
+            let mut file = File::create(&file_path).unwrap();
+            file.write_all(b"one\n").unwrap();
+
+            let mut buffer = TextBuffer::load_from_file(&file_path, 20).unwrap();
+            assert!(!buffer.large_file);
+
+            file.write_all(b"two\n").unwrap();
+            assert_eq!(buffer.append_from_file(20).unwrap(), 4);
+            assert_eq!(buffer.to_string().unwrap(), "one\ntwo\n");
+            assert_eq!(buffer.line_count(), Some(3));
+            assert!(!buffer.is_modified());
+
+            // Past the threshold the buffer stops holding the text
+            file.write_all(b"three four five\n").unwrap();
+            assert_eq!(buffer.append_from_file(20).unwrap(), 16);
+            assert!(buffer.large_file);
+            assert_eq!(buffer.total_bytes(), 24);
+
+            file.write_all(b"six\n").unwrap();
+            assert_eq!(buffer.append_from_file(20).unwrap(), 4);
+            assert!(!buffer.buffers.last().unwrap().is_loaded());
+            assert_eq!(buffer.get_text_range_mut(24, 4).unwrap(), b"six\n".to_vec());
+            assert_eq!(buffer.append_from_file(20).unwrap(), 0);
+        }
+
         #[test]
         fn test_large_file_basic_api_operations() {
             let temp_dir = TempDir::new().unwrap();
@@ -1,3 +1,17 @@
+//! Buffer revisions and edit transactions
+//!
+//! Every insert or delete bumps a buffer's revision. Async producers (LSP
+//! servers, formatters, plugins) compute their edits against the revision they
+//! read, and commit them as an [`EditTransaction`]: the ranges are rebased over
+//! whatever the user typed in between, or the transaction is rejected when an
+//! edit overlaps a change made since.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// Number of edits kept for rebasing older ranges
+const HISTORY_CAPACITY: usize = 1000;
+
 /// Represents a single edit operation in the buffer's history
 #[derive(Clone, Debug)]
 pub struct Edit {
@@ -31,3 +45,315 @@ impl Edit {
         }
     }
 }
+
+/// A buffer's revision number and its recent edits
+#[derive(Clone, Debug, Default)]
+pub struct EditHistory {
+    revision: u64,
+    /// Edits in order, each tagged with the revision it produced
+    edits: VecDeque<Edit>,
+    /// Oldest revision whose ranges can still be rebased
+    oldest: u64,
+}
+
+impl EditHistory {
+    /// Current revision
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Record that `len` bytes were inserted at `offset`
+    pub fn record_insert(&mut self, offset: usize, len: usize) {
+        self.revision += 1;
+        self.push(Edit::insert(self.revision, offset, len));
+    }
+
+    /// Record that `len` bytes were deleted at `offset`
+    pub fn record_delete(&mut self, offset: usize, len: usize) {
+        self.revision += 1;
+        self.push(Edit::delete(self.revision, offset, len));
+    }
+
+    /// Record that the whole text was replaced (e.g., reloaded from disk)
+    ///
+    /// Ranges from earlier revisions can't be rebased anymore.
+    pub fn reset(&mut self) {
+        self.revision += 1;
+        self.edits.clear();
+        self.oldest = self.revision;
+    }
+
+    fn push(&mut self, edit: Edit) {
+        self.edits.push_back(edit);
+        if self.edits.len() > HISTORY_CAPACITY {
+            if let Some(dropped) = self.edits.pop_front() {
+                self.oldest = dropped.version;
+            }
+        }
+    }
+
+    /// Map a range of revision `from` to the current text
+    ///
+    /// Fails if the text in the range (or, for an empty range, around the
+    /// position) was changed since `from`.
+    pub fn rebase(&self, range: Range<usize>, from: u64) -> Result<Range<usize>, TransactionError> {
+        if from > self.revision || from < self.oldest {
+            return Err(TransactionError::UnknownRevision(from));
+        }
+
+        let original = range.clone();
+        let mut range = range;
+        for edit in self.edits.iter().filter(|edit| edit.version > from) {
+            match edit.kind {
+                EditKind::Insert { offset, len } => {
+                    if offset <= range.start {
+                        range = range.start + len..range.end + len;
+                    } else if offset < range.end {
+                        return Err(TransactionError::Conflict(original));
+                    }
+                }
+                EditKind::Delete { offset, len } => {
+                    if offset + len <= range.start {
+                        range = range.start - len..range.end - len;
+                    } else if offset < range.end || (offset < range.start && range.is_empty()) {
+                        return Err(TransactionError::Conflict(original));
+                    }
+                }
+            }
+        }
+        Ok(range)
+    }
+}
+
+/// Replace `range` with `text`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// What to do with the edits of a transaction that conflict with newer changes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Reject the whole transaction
+    #[default]
+    Reject,
+    /// Drop the conflicting edits and apply the others
+    SkipConflicting,
+}
+
+/// Why a transaction couldn't be committed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionError {
+    /// The revision is newer than the buffer's, or too old to rebase from
+    UnknownRevision(u64),
+    /// An edit's range (as given) overlaps a change made since the revision
+    Conflict(Range<usize>),
+    /// Two edits of the transaction overlap each other
+    OverlappingEdits,
+    /// The buffer was closed
+    BufferNotFound,
+}
+
+impl std::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionError::UnknownRevision(revision) => {
+                write!(f, "revision {revision} is unknown or too old")
+            }
+            TransactionError::Conflict(range) => write!(
+                f,
+                "edit of bytes {}..{} conflicts with a newer change",
+                range.start, range.end
+            ),
+            TransactionError::OverlappingEdits => write!(f, "edits overlap each other"),
+            TransactionError::BufferNotFound => write!(f, "buffer not found"),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+/// Edits computed against one revision of a buffer, applied together
+#[derive(Clone, Debug)]
+pub struct EditTransaction {
+    revision: u64,
+    edits: Vec<TextEdit>,
+}
+
+impl EditTransaction {
+    /// Start a transaction for edits against `revision`
+    pub fn new(revision: u64) -> Self {
+        Self {
+            revision,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Revision the edits' ranges refer to
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Replace `range` with `text`
+    pub fn replace(&mut self, range: Range<usize>, text: impl Into<String>) -> &mut Self {
+        self.edits.push(TextEdit {
+            range,
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Insert `text` at `offset`
+    pub fn insert(&mut self, offset: usize, text: impl Into<String>) -> &mut Self {
+        self.replace(offset..offset, text)
+    }
+
+    /// Delete `range`
+    pub fn delete(&mut self, range: Range<usize>) -> &mut Self {
+        self.replace(range, String::new())
+    }
+
+    /// Map the edits to the current text of `history`
+    ///
+    /// Returns the edits sorted from the end of the text to the start, ready
+    /// to be applied one after the other.
+    pub fn rebase(
+        &self,
+        history: &EditHistory,
+        policy: ConflictPolicy,
+    ) -> Result<Vec<TextEdit>, TransactionError> {
+        let mut edits = self.edits.clone();
+        edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+        if edits
+            .windows(2)
+            .any(|pair| pair[0].range.end > pair[1].range.start)
+        {
+            return Err(TransactionError::OverlappingEdits);
+        }
+
+        let mut rebased = Vec::with_capacity(edits.len());
+        for edit in edits {
+            match history.rebase(edit.range, self.revision) {
+                Ok(range) => rebased.push(TextEdit {
+                    range,
+                    text: edit.text,
+                }),
+                Err(TransactionError::Conflict(_)) if policy == ConflictPolicy::SkipConflicting => {
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        rebased.reverse();
+        Ok(rebased)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebase_over_newer_edits() {
+        let mut history = EditHistory::default();
+        history.record_insert(0, 10); // "0123456789"
+        let base = history.revision();
+
+        // The user types 3 bytes at 2 and deletes 2 bytes at 8 (old 5..7)
+        history.record_insert(2, 3);
+        history.record_delete(8, 2);
+        assert_eq!(history.revision(), base + 2);
+
+        // Before both changes, after the first, between them
+        assert_eq!(history.rebase(0..1, base), Ok(0..1));
+        assert_eq!(history.rebase(3..5, base), Ok(6..8));
+        assert_eq!(history.rebase(7..9, base), Ok(8..10));
+        // Insert at the start of a range shifts it
+        assert_eq!(history.rebase(2..4, base), Ok(5..7));
+
+        // Overlapping either change
+        assert_eq!(
+            history.rebase(1..3, base),
+            Err(TransactionError::Conflict(1..3))
+        );
+        assert_eq!(
+            history.rebase(4..6, base),
+            Err(TransactionError::Conflict(4..6))
+        );
+        assert_eq!(
+            history.rebase(6..6, base),
+            Err(TransactionError::Conflict(6..6))
+        );
+
+        assert_eq!(
+            history.rebase(0..1, base + 5),
+            Err(TransactionError::UnknownRevision(base + 5))
+        );
+        history.reset();
+        assert_eq!(
+            history.rebase(0..1, base),
+            Err(TransactionError::UnknownRevision(base))
+        );
+    }
+
+    #[test]
+    fn test_transaction_policies() {
+        let mut history = EditHistory::default();
+        history.record_insert(0, 20);
+        let mut transaction = EditTransaction::new(history.revision());
+        transaction
+            .replace(2..4, "a")
+            .insert(10, "b")
+            .delete(15..17);
+        history.record_insert(11, 1);
+        history.record_delete(0, 1);
+
+        let rebased = transaction
+            .rebase(&history, ConflictPolicy::Reject)
+            .unwrap();
+        assert_eq!(
+            rebased,
+            vec![
+                TextEdit {
+                    range: 15..17,
+                    text: String::new()
+                },
+                TextEdit {
+                    range: 9..9,
+                    text: "b".to_string()
+                },
+                TextEdit {
+                    range: 1..3,
+                    text: "a".to_string()
+                },
+            ]
+        );
+
+        // A change inside one of the ranges
+        history.record_insert(16, 1);
+        assert_eq!(
+            transaction.rebase(&history, ConflictPolicy::Reject),
+            Err(TransactionError::Conflict(15..17))
+        );
+        let rebased = transaction
+            .rebase(&history, ConflictPolicy::SkipConflicting)
+            .unwrap();
+        assert_eq!(rebased.len(), 2);
+        assert_eq!(rebased[0].range, 9..9);
+
+        let mut overlapping = EditTransaction::new(history.revision());
+        overlapping.delete(0..5).insert(3, "x");
+        assert_eq!(
+            overlapping.rebase(&history, ConflictPolicy::Reject),
+            Err(TransactionError::OverlappingEdits)
+        );
+    }
+}
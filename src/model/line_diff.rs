@@ -299,6 +299,271 @@ pub fn merge_ranges(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
     merged
 }
 
+/// Lines of `text`; empty text has no lines (rather than one empty line)
+fn text_lines(text: &[u8]) -> Vec<&[u8]> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split(|&b| b == b'\n').collect()
+    }
+}
+
+/// Largest number of line pairs compared by `unified_diff` (after trimming the
+/// common prefix and suffix); bigger changes aren't diffed.
+const MAX_UNIFIED_DIFF_CELLS: usize = 4_000_000;
+
+/// Kind of line in a unified diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// Unchanged line shown for context
+    Context,
+    /// Line only in the current buffer
+    Added,
+    /// Line only in the saved version
+    Removed,
+    /// Unchanged lines that were left out
+    Gap,
+}
+
+/// A line of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line text (empty for gaps)
+    pub text: String,
+}
+
+/// Unified diff of `saved` against `current`, for showing pending changes
+///
+/// Unchanged lines more than `context` lines away from a change are collapsed
+/// into a single `Gap` line. Returns None if the changed region is too large
+/// to compare.
+pub fn unified_diff(saved: &[u8], current: &[u8], context: usize) -> Option<Vec<DiffLine>> {
+    let saved_lines = text_lines(saved);
+    let current_lines = text_lines(current);
+
+    // Only the part between the common prefix and suffix needs the LCS
+    let prefix = saved_lines
+        .iter()
+        .zip(&current_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = saved_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(current_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let saved_mid = &saved_lines[prefix..saved_lines.len() - suffix];
+    let current_mid = &current_lines[prefix..current_lines.len() - suffix];
+    if saved_mid.len().saturating_mul(current_mid.len()) > MAX_UNIFIED_DIFF_CELLS {
+        return None;
+    }
+
+    let text = |line: &[u8]| {
+        String::from_utf8_lossy(line)
+            .trim_end_matches('\r')
+            .to_string()
+    };
+    let line = |kind, bytes: &[u8]| DiffLine {
+        kind,
+        text: text(bytes),
+    };
+
+    // Full diff: prefix, then the middle aligned by the LCS, then the suffix
+    let mut lines: Vec<DiffLine> = saved_lines[..prefix]
+        .iter()
+        .map(|l| line(DiffLineKind::Context, l))
+        .collect();
+    let (mut saved_idx, mut current_idx) = (0, 0);
+    for m in longest_common_subsequence(saved_mid, current_mid)
+        .into_iter()
+        .chain(std::iter::once(LineMatch {
+            saved_idx: saved_mid.len(),
+            current_idx: current_mid.len(),
+        }))
+    {
+        lines.extend(
+            saved_mid[saved_idx..m.saved_idx]
+                .iter()
+                .map(|l| line(DiffLineKind::Removed, l)),
+        );
+        lines.extend(
+            current_mid[current_idx..m.current_idx]
+                .iter()
+                .map(|l| line(DiffLineKind::Added, l)),
+        );
+        if m.saved_idx < saved_mid.len() {
+            lines.push(line(DiffLineKind::Context, saved_mid[m.saved_idx]));
+        }
+        saved_idx = m.saved_idx + 1;
+        current_idx = m.current_idx + 1;
+    }
+    lines.extend(
+        saved_lines[saved_lines.len() - suffix..]
+            .iter()
+            .map(|l| line(DiffLineKind::Context, l)),
+    );
+
+    // Keep changes and the context around them
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.kind != DiffLineKind::Context)
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut keep = vec![false; lines.len()];
+    for &idx in &changed {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(lines.len());
+        keep[start..end].iter_mut().for_each(|k| *k = true);
+    }
+
+    let mut result = Vec::new();
+    let mut skipped = false;
+    for (line, keep) in lines.into_iter().zip(keep) {
+        if keep {
+            if skipped && !result.is_empty() {
+                result.push(DiffLine {
+                    kind: DiffLineKind::Gap,
+                    text: String::new(),
+                });
+            }
+            skipped = false;
+            result.push(line);
+        } else {
+            skipped = true;
+        }
+    }
+    Some(result)
+}
+
+/// How a line differs in a side-by-side diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairedChange {
+    /// Line only in the left text
+    Removed,
+    /// Line only in the right text
+    Added,
+    /// Line replaced by the line shown next to it
+    Changed,
+}
+
+/// Two texts aligned line by line, for showing them next to each other
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PairedDiff {
+    /// Changed lines of the left text
+    pub left: Vec<(usize, PairedChange)>,
+    /// Changed lines of the right text
+    pub right: Vec<(usize, PairedChange)>,
+    /// Line pairs (left, right) the texts line up at: where changes and
+    /// unchanged runs start
+    pub anchors: Vec<(usize, usize)>,
+}
+
+impl PairedDiff {
+    /// Whether the texts have the same lines
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+
+    /// The right line shown next to left `line`
+    pub fn right_line(&self, line: usize) -> usize {
+        align_line(&self.anchors, line, |&(left, right)| (left, right))
+    }
+
+    /// The left line shown next to right `line`
+    pub fn left_line(&self, line: usize) -> usize {
+        align_line(&self.anchors, line, |&(left, right)| (right, left))
+    }
+}
+
+/// Map `line` of one side to the other through `anchors`, which `side` turns
+/// into (this side, other side)
+fn align_line(
+    anchors: &[(usize, usize)],
+    line: usize,
+    side: impl Fn(&(usize, usize)) -> (usize, usize),
+) -> usize {
+    let idx = anchors.partition_point(|anchor| side(anchor).0 <= line);
+    let Some((this, other)) = idx.checked_sub(1).map(|i| side(&anchors[i])) else {
+        return line;
+    };
+    let aligned = other + (line - this);
+    match anchors.get(idx).map(&side) {
+        // Past the other side's lines before the next anchor, stay on its last
+        Some((_, next_other)) => aligned.min(next_other.saturating_sub(1).max(other)),
+        None => aligned,
+    }
+}
+
+/// Align `left` and `right` line by line for a side-by-side diff
+///
+/// Lines removed and added at the same place are paired up as changed lines.
+/// Returns None if the changed region is too large to compare.
+pub fn paired_diff(left: &[u8], right: &[u8]) -> Option<PairedDiff> {
+    let left_lines = text_lines(left);
+    let right_lines = text_lines(right);
+
+    // Only the part between the common prefix and suffix needs the LCS
+    let prefix = left_lines
+        .iter()
+        .zip(&right_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = left_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(right_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let left_mid = &left_lines[prefix..left_lines.len() - suffix];
+    let right_mid = &right_lines[prefix..right_lines.len() - suffix];
+    if left_mid.len().saturating_mul(right_mid.len()) > MAX_UNIFIED_DIFF_CELLS {
+        return None;
+    }
+
+    let mut diff = PairedDiff {
+        anchors: vec![(0, 0)],
+        ..PairedDiff::default()
+    };
+    let (mut left_idx, mut right_idx) = (0, 0);
+    for m in longest_common_subsequence(left_mid, right_mid)
+        .into_iter()
+        .chain(std::iter::once(LineMatch {
+            saved_idx: left_mid.len(),
+            current_idx: right_mid.len(),
+        }))
+    {
+        if m.saved_idx > left_idx || m.current_idx > right_idx {
+            let paired = (m.saved_idx - left_idx).min(m.current_idx - right_idx);
+            diff.left.extend((left_idx..m.saved_idx).map(|i| {
+                let change = if i - left_idx < paired {
+                    PairedChange::Changed
+                } else {
+                    PairedChange::Removed
+                };
+                (prefix + i, change)
+            }));
+            diff.right.extend((right_idx..m.current_idx).map(|j| {
+                let change = if j - right_idx < paired {
+                    PairedChange::Changed
+                } else {
+                    PairedChange::Added
+                };
+                (prefix + j, change)
+            }));
+            diff.anchors.push((prefix + left_idx, prefix + right_idx));
+            diff.anchors
+                .push((prefix + m.saved_idx, prefix + m.current_idx));
+        }
+        left_idx = m.saved_idx + 1;
+        right_idx = m.current_idx + 1;
+    }
+    diff.anchors.dedup();
+    Some(diff)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -593,4 +858,76 @@ mod proptests {
             }
         }
     }
+
+    fn kinds_and_text(lines: &[DiffLine]) -> Vec<(DiffLineKind, &str)> {
+        lines.iter().map(|l| (l.kind, l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_unified_diff_modification() {
+        let saved = b"a\nb\nc\nd\ne\nf\ng\n";
+        let current = b"a\nb\nc\nD\ne\nf\ng\n";
+        let diff = unified_diff(saved, current, 1).unwrap();
+        assert_eq!(
+            kinds_and_text(&diff),
+            vec![
+                (DiffLineKind::Context, "c"),
+                (DiffLineKind::Removed, "d"),
+                (DiffLineKind::Added, "D"),
+                (DiffLineKind::Context, "e"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_separate_hunks() {
+        let saved = b"1\n2\n3\n4\n5\n6\n7\n8";
+        let current = b"1\nnew\n2\n3\n4\n5\n6\n7";
+        let diff = unified_diff(saved, current, 1).unwrap();
+        assert_eq!(
+            kinds_and_text(&diff),
+            vec![
+                (DiffLineKind::Context, "1"),
+                (DiffLineKind::Added, "new"),
+                (DiffLineKind::Context, "2"),
+                (DiffLineKind::Gap, ""),
+                (DiffLineKind::Context, "7"),
+                (DiffLineKind::Removed, "8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paired_diff_pairs_changes_and_aligns_lines() {
+        let left = b"a\nb\nc\nd\n";
+        let right = b"a\nB\nc\nx\nd\n";
+        let diff = paired_diff(left, right).unwrap();
+        assert_eq!(diff.left, vec![(1, PairedChange::Changed)]);
+        assert_eq!(
+            diff.right,
+            vec![(1, PairedChange::Changed), (3, PairedChange::Added)]
+        );
+        // "c" and "d" line up on both sides
+        assert_eq!(diff.right_line(2), 2);
+        assert_eq!(diff.right_line(3), 4);
+        assert_eq!(diff.left_line(4), 3);
+        // The added line sits next to the left line after it
+        assert_eq!(diff.left_line(3), 3);
+
+        assert!(paired_diff(left, left).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_identical_and_new_file() {
+        assert!(unified_diff(b"same\n", b"same\n", 3).unwrap().is_empty());
+
+        let diff = unified_diff(b"", b"hello\nworld", 3).unwrap();
+        assert_eq!(
+            kinds_and_text(&diff),
+            vec![
+                (DiffLineKind::Added, "hello"),
+                (DiffLineKind::Added, "world"),
+            ]
+        );
+    }
 }
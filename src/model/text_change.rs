@@ -0,0 +1,68 @@
+//! Structured change notifications
+//!
+//! Every insert or delete applied to a buffer is recorded as a [`TextChange`]:
+//! the range replaced, its old and new text, and the revision it produced.
+//! LSP `didChange`, the highlight cache and plugin hooks all consume this one
+//! stream, instead of each re-deriving changes from events or re-reading the
+//! buffer.
+
+use std::ops::Range;
+
+/// Position as LSP counts it: 0-indexed line and UTF-16 column
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct LspPosition {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// One replacement made to a buffer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextChange {
+    /// Buffer revision after the change
+    pub revision: u64,
+    /// Bytes replaced, in the text before the change
+    pub range: Range<usize>,
+    /// Start of `range`, in the text before the change
+    pub start: LspPosition,
+    /// End of `range`, in the text before the change
+    pub end: LspPosition,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+impl TextChange {
+    /// Byte range of the new text, in the text after the change
+    pub fn new_range(&self) -> Range<usize> {
+        self.range.start..self.range.start + self.new_text.len()
+    }
+
+    /// Number of lines added (negative if lines were removed)
+    pub fn line_delta(&self) -> isize {
+        self.new_text.matches('\n').count() as isize - self.old_text.matches('\n').count() as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_range_and_line_delta() {
+        let change = TextChange {
+            revision: 3,
+            range: 4..11,
+            start: LspPosition {
+                line: 0,
+                character: 4,
+            },
+            end: LspPosition {
+                line: 1,
+                character: 2,
+            },
+            old_text: "abcd\nef".to_string(),
+            new_text: "x".to_string(),
+        };
+        assert_eq!(change.new_range(), 4..5);
+        assert_eq!(change.line_delta(), -1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Telling a file's language when its extension doesn't
+//!
+//! Highlighting is chosen by file extension, so files such as `Makefile`,
+//! `Dockerfile`, `.bashrc` or a script starting with `#!/usr/bin/env
+//! python3` are given the extension of their language instead. The user's
+//! `file_types` rules are tried first, in order, for every file; for files
+//! whose extension isn't known, then well-known file names, the interpreter
+//! of a `#!` line and the first line's content (`<?php`, `<?xml`, an Emacs
+//! `-*- mode: ... -*-` line).
+
+use crate::config::FileTypeRule;
+use regex::Regex;
+
+/// Extension of the first of `rules` matching a file named `name` whose
+/// first line is `first_line`. Rules that don't compile are skipped.
+pub fn matching_rule<'a>(
+    rules: &'a [FileTypeRule],
+    name: &str,
+    first_line: &str,
+) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| {
+            let name_pattern = rule.name.as_deref().map(glob_regex);
+            matches(name_pattern.as_deref(), name)
+                || matches(rule.first_line.as_deref(), first_line)
+        })
+        .map(|rule| rule.language.trim_start_matches('.'))
+}
+
+/// Extension for a file named `name` with first line `first_line`, from
+/// its name, `#!` line or content
+pub fn detect(name: &str, first_line: &str) -> Option<&'static str> {
+    by_name(name)
+        .or_else(|| shebang(first_line))
+        .or_else(|| by_content(first_line))
+}
+
+fn by_name(name: &str) -> Option<&'static str> {
+    let extension = match name {
+        "Makefile" | "makefile" | "GNUmakefile" => "mk",
+        "Dockerfile" | "Containerfile" => "dockerfile",
+        ".bashrc" | ".bash_profile" | ".bash_login" | ".bash_logout" | ".bash_aliases"
+        | ".profile" | ".zshrc" | ".zshenv" | ".zprofile" | ".zlogin" | ".envrc" | "PKGBUILD" => {
+            "sh"
+        }
+        "Gemfile" | "Rakefile" | "Vagrantfile" | "Podfile" | "Brewfile" | "Guardfile" => "rb",
+        "Jenkinsfile" => "groovy",
+        "Pipfile" => "toml",
+        _ if name.starts_with("Dockerfile.") => "dockerfile",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+/// Extension for the interpreter of a `#!` line, e.g. `#!/bin/bash` or
+/// `#!/usr/bin/env -S python3 -u`
+fn shebang(first_line: &str) -> Option<&'static str> {
+    let command = first_line.strip_prefix("#!")?;
+    let mut words = command.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    interpreter(program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.'))
+}
+
+fn interpreter(program: &str) -> Option<&'static str> {
+    let extension = match program {
+        "sh" | "bash" | "zsh" | "ksh" | "dash" | "ash" | "shell-script" => "sh",
+        "python" | "pypy" => "py",
+        "node" | "nodejs" | "deno" | "bun" | "javascript" => "js",
+        "ts-node" | "tsx" | "typescript" => "ts",
+        "ruby" => "rb",
+        "perl" => "pl",
+        "php" => "php",
+        "lua" | "luajit" => "lua",
+        "make" | "makefile" => "mk",
+        "tclsh" | "wish" | "tcl" => "tcl",
+        "Rscript" => "r",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+/// Extension from the first line's content
+fn by_content(first_line: &str) -> Option<&'static str> {
+    let line = first_line.trim_start();
+    let lower = line.to_ascii_lowercase();
+    if line.starts_with("<?php") {
+        Some("php")
+    } else if line.starts_with("<?xml") {
+        Some("xml")
+    } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        Some("html")
+    } else {
+        // Emacs: `-*- mode: python -*-` or `-*- python -*-`
+        let (_, rest) = line.split_once("-*-")?;
+        let (modeline, _) = rest.split_once("-*-")?;
+        let mode = modeline
+            .split(';')
+            .find_map(|part| {
+                let (key, value) = part.split_once(':')?;
+                (key.trim().eq_ignore_ascii_case("mode")).then_some(value)
+            })
+            .unwrap_or(modeline);
+        interpreter(&mode.trim().to_ascii_lowercase())
+    }
+}
+
+/// Anchored regex matching what the glob (with `*` and `?`) matches
+fn glob_regex(glob: &str) -> String {
+    format!(
+        "^{}$",
+        regex::escape(glob).replace(r"\*", ".*").replace(r"\?", ".")
+    )
+}
+
+/// Whether the regex `pattern` is given, compiles and matches `text`
+fn matches(pattern: Option<&str>, text: &str) -> bool {
+    let Some(pattern) = pattern else {
+        return false;
+    };
+    match Regex::new(pattern) {
+        Ok(re) => re.is_match(text),
+        Err(e) => {
+            tracing::warn!("Invalid file type pattern {:?}: {}", pattern, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("Makefile", ""), Some("mk"));
+        assert_eq!(detect("Dockerfile.dev", ""), Some("dockerfile"));
+        assert_eq!(detect(".bashrc", "alias ll='ls -l'"), Some("sh"));
+        assert_eq!(detect("deploy", "#!/bin/bash"), Some("sh"));
+        assert_eq!(
+            detect("tool", "#!/usr/bin/env -S python3.11 -u"),
+            Some("py")
+        );
+        assert_eq!(
+            detect("serve", "#!/usr/bin/env NODE_ENV=prod node"),
+            Some("js")
+        );
+        assert_eq!(detect("index", "<?php echo 1;"), Some("php"));
+        assert_eq!(
+            detect("build", "# -*- mode: Ruby; coding: utf-8 -*-"),
+            Some("rb")
+        );
+        assert_eq!(detect("notes", "Just some text"), None);
+        assert_eq!(detect("run", "#!/usr/bin/unknown"), None);
+    }
+
+    #[test]
+    fn test_matching_rule_in_order() {
+        let rules = vec![
+            FileTypeRule {
+                name: Some("*.conf".to_string()),
+                first_line: None,
+                language: "sh".to_string(),
+            },
+            FileTypeRule {
+                name: Some("nginx.conf".to_string()),
+                first_line: None,
+                language: "nginx".to_string(),
+            },
+            FileTypeRule {
+                name: None,
+                first_line: Some(r"^#!.*\bcrystal\b".to_string()),
+                language: ".cr".to_string(),
+            },
+        ];
+        assert_eq!(matching_rule(&rules, "nginx.conf", ""), Some("sh"));
+        assert_eq!(
+            matching_rule(&rules, "app", "#!/usr/bin/env crystal"),
+            Some("cr")
+        );
+        assert_eq!(matching_rule(&rules, "nginx.confx", ""), None);
+    }
+}
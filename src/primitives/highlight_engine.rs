@@ -12,11 +12,12 @@
 
 use crate::model::buffer::Buffer;
 use crate::primitives::grammar_registry::GrammarRegistry;
-use crate::primitives::highlighter::{HighlightSpan, Highlighter, Language};
+use crate::primitives::highlighter::{HighlightSpan, Highlighter, Language, ParseCost};
 use crate::view::theme::Theme;
 use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use syntect::parsing::SyntaxSet;
 
 /// Preference for which highlighting backend to use
@@ -50,6 +51,7 @@ pub struct TextMateEngine {
     syntax_index: usize,
     cache: Option<TextMateCache>,
     last_buffer_len: usize,
+    cost: ParseCost,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +69,19 @@ struct CachedSpan {
 /// Maximum bytes to parse in a single operation
 const MAX_PARSE_BYTES: usize = 1024 * 1024;
 
+/// Longest a frame waits for highlighting; regions expected to take longer
+/// are drawn plain and highlighted once the editor is idle
+pub const HIGHLIGHT_FRAME_BUDGET: Duration = Duration::from_millis(8);
+
+/// How often frames had to be drawn without highlighting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HighlightStats {
+    /// Frames that drew highlighted text
+    pub frames: u64,
+    /// Frames that drew plain text because highlighting wouldn't fit
+    pub fallbacks: u64,
+}
+
 impl TextMateEngine {
     /// Create a new TextMate engine for the given syntax
     pub fn new(syntax_set: Arc<SyntaxSet>, syntax_index: usize) -> Self {
@@ -75,9 +90,35 @@ impl TextMateEngine {
             syntax_index,
             cache: None,
             last_buffer_len: 0,
+            cost: ParseCost::default(),
         }
     }
 
+    /// Whether highlighting this range would be served from the cache
+    pub fn is_cached(&self, buffer: &Buffer, viewport_start: usize, viewport_end: usize) -> bool {
+        self.cache.as_ref().is_some_and(|cache| {
+            cache.range.start <= viewport_start
+                && cache.range.end >= viewport_end
+                && self.last_buffer_len == buffer.len()
+        })
+    }
+
+    /// Expected time to highlight this range if it isn't cached
+    pub fn parse_estimate(
+        &self,
+        buffer: &Buffer,
+        viewport_start: usize,
+        viewport_end: usize,
+    ) -> Option<Duration> {
+        let parse_start = viewport_start.saturating_sub(1000);
+        let parse_end = (viewport_end + 1000).min(buffer.len());
+        let bytes = parse_end.saturating_sub(parse_start);
+        if bytes > MAX_PARSE_BYTES {
+            return Some(Duration::ZERO);
+        }
+        self.cost.estimate(bytes)
+    }
+
     /// Highlight the visible viewport range
     pub fn highlight_viewport(
         &mut self,
@@ -89,11 +130,8 @@ impl TextMateEngine {
         use syntect::parsing::{ParseState, ScopeStack};
 
         // Check cache validity
-        if let Some(cache) = &self.cache {
-            if cache.range.start <= viewport_start
-                && cache.range.end >= viewport_end
-                && self.last_buffer_len == buffer.len()
-            {
+        if self.is_cached(buffer, viewport_start, viewport_end) {
+            if let Some(cache) = &self.cache {
                 return cache
                     .spans
                     .iter()
@@ -116,6 +154,7 @@ impl TextMateEngine {
             return Vec::new();
         }
 
+        let started = Instant::now();
         let syntax = &self.syntax_set.syntaxes()[self.syntax_index];
         let mut state = ParseState::new(syntax);
         let mut spans = Vec::new();
@@ -181,6 +220,7 @@ impl TextMateEngine {
 
         // Merge adjacent spans
         Self::merge_adjacent_spans(&mut spans);
+        self.cost.record(parse_end - parse_start, started.elapsed());
 
         // Update cache
         self.cache = Some(TextMateCache {
@@ -334,6 +374,33 @@ impl HighlightEngine {
         }
     }
 
+    /// Highlight the visible viewport if that's expected to take at most `budget`
+    ///
+    /// Returns None, without parsing, when the range isn't cached and parsing
+    /// it took longer than `budget` going by the parses so far.
+    pub fn highlight_viewport_within(
+        &mut self,
+        buffer: &Buffer,
+        viewport_start: usize,
+        viewport_end: usize,
+        theme: &Theme,
+        budget: Duration,
+    ) -> Option<Vec<HighlightSpan>> {
+        let estimate = match self {
+            Self::TreeSitter(h) if !h.is_cached(buffer, viewport_start, viewport_end) => {
+                h.parse_estimate(buffer, viewport_start, viewport_end)
+            }
+            Self::TextMate(h) if !h.is_cached(buffer, viewport_start, viewport_end) => {
+                h.parse_estimate(buffer, viewport_start, viewport_end)
+            }
+            _ => None,
+        };
+        if estimate.is_some_and(|estimate| estimate > budget) {
+            return None;
+        }
+        Some(self.highlight_viewport(buffer, viewport_start, viewport_end, theme))
+    }
+
     /// Invalidate cache for an edited range
     pub fn invalidate_range(&mut self, edit_range: Range<usize>) {
         match self {
@@ -450,4 +517,32 @@ mod tests {
         // Just verify it doesn't panic
         let _ = engine.backend_name();
     }
+
+    #[test]
+    fn test_highlight_within_budget() {
+        let registry = GrammarRegistry::load();
+        let mut engine = HighlightEngine::for_file(Path::new("test.rs"), &registry);
+        let content: String = (0..2000)
+            .map(|i| format!("fn function_{i}() {{}}\n"))
+            .collect();
+        let buffer = Buffer::from_str_test(&content);
+        let theme = Theme::dark();
+
+        // Nothing is known about the cost before the first parse
+        assert!(engine
+            .highlight_viewport_within(&buffer, 0, 500, &theme, Duration::ZERO)
+            .is_some());
+        // Cached regions are always highlighted
+        assert!(engine
+            .highlight_viewport_within(&buffer, 100, 400, &theme, Duration::ZERO)
+            .is_some());
+        // Parsing somewhere else is expected to take longer than no time at all
+        let far = buffer.len() - 500;
+        assert!(engine
+            .highlight_viewport_within(&buffer, far, buffer.len(), &theme, Duration::ZERO)
+            .is_none());
+        assert!(engine
+            .highlight_viewport_within(&buffer, far, buffer.len(), &theme, Duration::from_secs(5))
+            .is_some_and(|spans| !spans.is_empty()));
+    }
 }
@@ -14,6 +14,7 @@ use crate::model::buffer::Buffer;
 use crate::view::theme::Theme;
 use ratatui::style::Color;
 use std::ops::Range;
+use std::time::Duration;
 use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent, Highlighter as TSHighlighter};
 
 /// Maximum bytes to parse in a single operation (for viewport highlighting)
@@ -115,6 +116,37 @@ pub struct HighlightSpan {
     pub color: Color,
 }
 
+/// How long parsing takes per byte, learned from the parses done so far
+///
+/// Lets the renderer tell whether highlighting a region that isn't cached
+/// would fit in the frame before starting on it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseCost {
+    /// Running average, in nanoseconds per byte (None until the first parse)
+    nanos_per_byte: Option<f64>,
+}
+
+impl ParseCost {
+    /// Account for a parse of `bytes` that took `elapsed`
+    pub fn record(&mut self, bytes: usize, elapsed: Duration) {
+        if bytes == 0 {
+            return;
+        }
+        let sample = elapsed.as_nanos() as f64 / bytes as f64;
+        // Weighted towards recent parses, which saw the same file and machine load
+        self.nanos_per_byte = Some(match self.nanos_per_byte {
+            Some(average) => average * 0.7 + sample * 0.3,
+            None => sample,
+        });
+    }
+
+    /// Expected time to parse `bytes`, if anything has been parsed yet
+    pub fn estimate(&self, bytes: usize) -> Option<Duration> {
+        self.nanos_per_byte
+            .map(|nanos| Duration::from_nanos((nanos * bytes as f64).round() as u64))
+    }
+}
+
 /// Internal span used for caching (stores category instead of color)
 #[derive(Debug, Clone)]
 struct CachedSpan {
@@ -653,6 +685,8 @@ pub struct Highlighter {
     cache: Option<HighlightCache>,
     /// Last known buffer length (for detecting complete buffer changes)
     last_buffer_len: usize,
+    /// How long parsing has taken so far
+    cost: ParseCost,
 }
 
 impl Highlighter {
@@ -665,9 +699,36 @@ impl Highlighter {
             config,
             cache: None,
             last_buffer_len: 0,
+            cost: ParseCost::default(),
+        })
+    }
+
+    /// Whether highlighting this range would be served from the cache
+    pub fn is_cached(&self, buffer: &Buffer, viewport_start: usize, viewport_end: usize) -> bool {
+        self.cache.as_ref().is_some_and(|cache| {
+            cache.range.start <= viewport_start
+                && cache.range.end >= viewport_end
+                && self.last_buffer_len == buffer.len()
         })
     }
 
+    /// Expected time to highlight this range if it isn't cached
+    pub fn parse_estimate(
+        &self,
+        buffer: &Buffer,
+        viewport_start: usize,
+        viewport_end: usize,
+    ) -> Option<Duration> {
+        let parse_start = viewport_start.saturating_sub(1000);
+        let parse_end = (viewport_end + 1000).min(buffer.len());
+        let bytes = parse_end.saturating_sub(parse_start);
+        if bytes > MAX_PARSE_BYTES {
+            // Too large to parse at all, so there's nothing to wait for
+            return Some(Duration::ZERO);
+        }
+        self.cost.estimate(bytes)
+    }
+
     /// Highlight the visible viewport range
     ///
     /// This only parses the visible lines for instant performance with large files.
@@ -680,11 +741,8 @@ impl Highlighter {
         theme: &Theme,
     ) -> Vec<HighlightSpan> {
         // Check if cache is valid for this range
-        if let Some(cache) = &self.cache {
-            if cache.range.start <= viewport_start
-                && cache.range.end >= viewport_end
-                && self.last_buffer_len == buffer.len()
-            {
+        if self.is_cached(buffer, viewport_start, viewport_end) {
+            if let Some(cache) = &self.cache {
                 // Cache hit! Filter spans to the requested range and resolve colors from theme
                 return cache
                     .spans
@@ -718,6 +776,7 @@ impl Highlighter {
         }
 
         // Extract source bytes from buffer
+        let started = std::time::Instant::now();
         let source = buffer.slice_bytes(parse_range.clone());
 
         // Highlight the source - store categories for theme-independent caching
@@ -766,6 +825,8 @@ impl Highlighter {
             }
         }
 
+        self.cost.record(parse_range.len(), started.elapsed());
+
         // Update cache
         self.cache = Some(HighlightCache {
             range: parse_range,
@@ -957,6 +1018,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_cost_estimate() {
+        let mut cost = ParseCost::default();
+        assert_eq!(cost.estimate(1000), None);
+
+        cost.record(1000, Duration::from_millis(1));
+        assert_eq!(cost.estimate(2000), Some(Duration::from_millis(2)));
+
+        // Later parses move the estimate towards their own speed
+        cost.record(1000, Duration::from_millis(11));
+        assert_eq!(cost.estimate(1000), Some(Duration::from_millis(4)));
+    }
+
     #[test]
     fn test_cache_invalidation() {
         let buffer = Buffer::from_str_test("fn main() {\n    println!(\"Hello\");\n}");
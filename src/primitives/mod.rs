@@ -5,13 +5,18 @@
 
 pub mod ansi;
 pub mod ansi_background;
+pub mod file_type;
 pub mod grammar_registry;
 pub mod highlight_engine;
 pub mod highlighter;
 pub mod indent;
 pub mod line_iterator;
 pub mod line_wrapping;
+pub mod redaction;
+pub mod reflow;
 pub mod semantic_highlight;
+pub mod text_objects;
 pub mod text_property;
 pub mod textmate_highlighter;
+pub mod variables;
 pub mod word_navigation;
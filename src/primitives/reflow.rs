@@ -0,0 +1,291 @@
+//! Hard wrapping: reflowing prose and comments to a width
+//!
+//! Each line is split into its leader and its text. The leader is the
+//! line's indentation, plus a comment marker such as `//`, `///` or `#` (or
+//! `*` inside a block comment, `>` in a quote) and the spaces after it, and
+//! is repeated on every wrapped line. A list bullet (`-`, `*`, `+`, `1.` or
+//! `1)`) starts an item of its own, whose wrapped lines line up after the
+//! bullet. In code, lines without a comment marker are left alone.
+
+use std::ops::Range;
+
+/// Which lines of a file are text to wrap
+#[derive(Debug, Clone, Copy)]
+pub struct WrapStyle {
+    /// Line comment markers, e.g. `["//"]`
+    pub markers: &'static [&'static str],
+    /// Whether a leading `*` continues a block comment
+    pub block_comments: bool,
+    /// Whether lines without a comment marker are text too
+    pub prose: bool,
+}
+
+impl WrapStyle {
+    /// Prose: every line is text, and `>` quotes are kept
+    pub const PROSE: WrapStyle = WrapStyle {
+        markers: &[">"],
+        block_comments: false,
+        prose: true,
+    };
+}
+
+/// A line of text, split after its leader
+struct TextLine<'a> {
+    leader: &'a str,
+    /// The text after the leader, without trailing whitespace
+    body: &'a str,
+}
+
+impl<'a> TextLine<'a> {
+    /// Its leader without trailing spaces: lines with the same key belong
+    /// to the same paragraph
+    fn key(&self) -> &'a str {
+        self.leader.trim_end()
+    }
+}
+
+/// Split `line` after its leader, or None if it isn't text (a code line)
+fn parse<'a>(line: &'a str, style: &WrapStyle) -> Option<TextLine<'a>> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker = style
+        .markers
+        .iter()
+        .copied()
+        .chain(style.block_comments.then_some("*"))
+        .filter(|marker| rest.starts_with(marker))
+        .max_by_key(|marker| marker.len());
+
+    let mut len = indent;
+    match marker {
+        // The end of a block comment
+        Some("*") if rest.starts_with("*/") => return None,
+        Some(marker) => {
+            // Doc comments repeat the marker's last character or add `!`,
+            // like `///` and `//!`
+            let last = marker.chars().last();
+            len += marker.len()
+                + rest[marker.len()..]
+                    .chars()
+                    .take_while(|&c| Some(c) == last || c == '!')
+                    .map(char::len_utf8)
+                    .sum::<usize>();
+        }
+        None if !style.prose => return None,
+        None => {}
+    }
+    len += line[len..]
+        .bytes()
+        .take_while(|&b| b == b' ' || b == b'\t')
+        .count();
+    Some(TextLine {
+        leader: &line[..len],
+        body: line[len..].trim_end(),
+    })
+}
+
+/// Length of the list bullet `body` starts with, including the spaces after
+fn bullet_len(body: &str) -> Option<usize> {
+    let marker = if body.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = body.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || !body[digits..].starts_with(['.', ')']) {
+            return None;
+        }
+        digits + 1
+    };
+    let spaces = body[marker..].bytes().take_while(|&b| b == b' ').count();
+    (spaces > 0).then_some(marker + spaces)
+}
+
+/// Whether `line` is text to wrap, and not blank
+pub fn is_text(line: &str, style: &WrapStyle) -> bool {
+    parse(line, style).is_some_and(|line| !line.body.is_empty())
+}
+
+/// Whether `next` continues the paragraph of the line before it: both are
+/// text with the same leader, and `next` doesn't start a list item
+pub fn continues_paragraph(line: &str, next: &str, style: &WrapStyle) -> bool {
+    let (Some(line), Some(next)) = (parse(line, style), parse(next, style)) else {
+        return false;
+    };
+    !line.body.is_empty()
+        && !next.body.is_empty()
+        && line.key() == next.key()
+        && bullet_len(next.body).is_none()
+}
+
+/// A paragraph or list item being rewrapped
+struct Item<'a> {
+    key: &'a str,
+    first_prefix: String,
+    rest_prefix: String,
+    words: Vec<&'a str>,
+}
+
+impl<'a> Item<'a> {
+    fn new(line: TextLine<'a>) -> Self {
+        let bullet = bullet_len(line.body).unwrap_or(0);
+        Item {
+            key: line.key(),
+            first_prefix: format!("{}{}", line.leader, &line.body[..bullet]),
+            rest_prefix: format!("{}{}", line.leader, " ".repeat(bullet)),
+            words: line.body[bullet..].split_whitespace().collect(),
+        }
+    }
+
+    /// Fill lines of at most `width` columns with the words, except for
+    /// words that are longer on their own
+    fn wrap(self, width: usize, out: &mut Vec<String>) {
+        let mut line = self.first_prefix;
+        let mut empty = true;
+        for word in self.words {
+            if !empty && line.chars().count() + 1 + word.chars().count() > width {
+                out.push(line);
+                line = self.rest_prefix.clone();
+                empty = true;
+            }
+            if !empty {
+                line.push(' ');
+            }
+            line.push_str(word);
+            empty = false;
+        }
+        out.push(line);
+    }
+}
+
+/// Rewrap the paragraphs and list items of `text` to `width` columns.
+/// Blank lines and lines that aren't text are kept as they are.
+pub fn reflow(text: &str, width: usize, style: &WrapStyle) -> String {
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut out = Vec::new();
+    let mut item: Option<Item> = None;
+    for line in text.lines() {
+        match parse(line, style) {
+            Some(line) if !line.body.is_empty() => {
+                let continues = item
+                    .as_ref()
+                    .is_some_and(|item| item.key == line.key() && bullet_len(line.body).is_none());
+                if continues {
+                    if let Some(item) = item.as_mut() {
+                        item.words.extend(line.body.split_whitespace());
+                    }
+                } else {
+                    if let Some(item) = item.take() {
+                        item.wrap(width, &mut out);
+                    }
+                    item = Some(Item::new(line));
+                }
+            }
+            parsed => {
+                if let Some(item) = item.take() {
+                    item.wrap(width, &mut out);
+                }
+                out.push(match parsed {
+                    Some(blank) => blank.key().to_string(),
+                    None => line.to_string(),
+                });
+            }
+        }
+    }
+    if let Some(item) = item {
+        item.wrap(width, &mut out);
+    }
+
+    let mut result = out.join(newline);
+    if text.ends_with('\n') {
+        result.push_str(newline);
+    }
+    result
+}
+
+/// Where to break `line`, typed up to its end, once it runs past `width`
+/// columns: the spaces to replace, and the newline and leader (plus the
+/// indentation after a bullet) replacing them. None if it fits, isn't text
+/// or has no space to break at.
+pub fn wrap_break(line: &str, width: usize, style: &WrapStyle) -> Option<(Range<usize>, String)> {
+    if line.chars().count() <= width {
+        return None;
+    }
+    let parsed = parse(line, style)?;
+    let bullet = bullet_len(parsed.body).unwrap_or(0);
+    let text_start = parsed.leader.len() + bullet;
+
+    let start = line
+        .char_indices()
+        .take(width + 1)
+        .filter(|&(i, c)| i > text_start && c == ' ' && !line[..i].ends_with(' '))
+        .map(|(i, _)| i)
+        .last()?;
+    let end = start + line[start..].bytes().take_while(|&b| b == b' ').count();
+    Some((
+        start..end,
+        format!("\n{}{}", parsed.leader, " ".repeat(bullet)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUST: WrapStyle = WrapStyle {
+        markers: &["//"],
+        block_comments: true,
+        prose: false,
+    };
+
+    #[test]
+    fn test_reflow_prose_paragraphs_and_bullets() {
+        let text = "one two three four five six\nseven\n\n- alpha beta gamma delta\n- epsilon\n";
+        assert_eq!(
+            reflow(text, 12, &WrapStyle::PROSE),
+            "one two\nthree four\nfive six\nseven\n\n- alpha beta\n  gamma\n  delta\n- epsilon\n"
+        );
+    }
+
+    #[test]
+    fn test_reflow_keeps_comment_leaders_and_code() {
+        let text =
+            "    /// Doc comment that is\n    /// long\nfn main() {}\n  // a b\n  //\n  // c\n";
+        assert_eq!(
+            reflow(text, 20, &RUST),
+            "    /// Doc comment\n    /// that is long\nfn main() {}\n  // a b\n  //\n  // c\n"
+        );
+
+        let block = " * one two three\n * four\n */";
+        assert_eq!(
+            reflow(block, 10, &RUST),
+            " * one two\n * three\n * four\n */"
+        );
+    }
+
+    #[test]
+    fn test_continues_paragraph() {
+        assert!(continues_paragraph("// a", "//   b", &RUST));
+        assert!(!continues_paragraph("// a", "// - b", &RUST));
+        assert!(!continues_paragraph("// a", "let b = 1;", &RUST));
+        assert!(!continues_paragraph("a", "", &WrapStyle::PROSE));
+    }
+
+    #[test]
+    fn test_wrap_break() {
+        let style = WrapStyle::PROSE;
+        assert_eq!(wrap_break("short line", 20, &style), None);
+        assert_eq!(
+            wrap_break("one two  three", 10, &style),
+            Some((7..9, "\n".to_string()))
+        );
+        assert_eq!(
+            wrap_break("  - item text", 9, &style),
+            Some((8..9, "\n    ".to_string()))
+        );
+        assert_eq!(
+            wrap_break("// abc defgh", 8, &RUST),
+            Some((6..7, "\n// ".to_string()))
+        );
+        assert_eq!(wrap_break("let value = compute();", 8, &RUST), None);
+    }
+}
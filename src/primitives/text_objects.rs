@@ -0,0 +1,647 @@
+//! Text objects: "inside quotes", "around brackets", "inside function", ...
+//!
+//! A text object is a range of text around a position, found either inside
+//! or around its delimiters. The built-in objects are:
+//!
+//! - `quotes`: a "double", 'single' or `backtick` string on the current line
+//! - `brackets`: the innermost (), [] or {} pair enclosing the position
+//! - `function`: the enclosing function, from the language's
+//!   `textobjects.scm` query (`@function.around` / `@function.inside`),
+//!   falling back to a search for `fn`/`def`/`function`/`func` lines
+//! - `paragraph`: the run of non-blank lines around the position
+//!
+//! Plugins add their own objects, defined by tree-sitter queries per language
+//! (`@around` / `@inside` captures) and/or a regex whose match around the
+//! position is the object (its first group is the inside).
+
+use crate::primitives::highlighter::Language;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use tree_sitter::{Parser, Query, QueryCursor, StreamingIterator};
+
+/// Names of the built-in text objects
+pub const BUILTIN_TEXT_OBJECTS: &[&str] = &["quotes", "brackets", "function", "paragraph"];
+
+/// Largest buffer parsed with tree-sitter; bigger ones use the fallbacks
+const MAX_PARSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// A text object defined by a plugin
+pub struct CustomTextObject {
+    /// Queries by language name ("rust", "python", ...)
+    pub queries: HashMap<String, String>,
+    /// Used when no query applies to the buffer
+    pub pattern: Option<Regex>,
+}
+
+/// Finds text objects, caching tree-sitter parsers and queries per language
+pub struct TextObjectFinder {
+    /// Parser and function query by language name
+    parsers: HashMap<&'static str, Option<(Parser, Query)>>,
+    /// Plugin objects by name
+    custom: HashMap<String, CustomTextObject>,
+}
+
+impl TextObjectFinder {
+    pub fn new() -> Self {
+        Self {
+            parsers: HashMap::new(),
+            custom: HashMap::new(),
+        }
+    }
+
+    /// Add (or replace) a plugin text object
+    ///
+    /// Built-in objects can't be replaced.
+    pub fn register(&mut self, name: String, object: CustomTextObject) -> Result<(), String> {
+        if BUILTIN_TEXT_OBJECTS.contains(&name.as_str()) {
+            return Err(format!("'{}' is a built-in text object", name));
+        }
+        for (language, query) in &object.queries {
+            let (_, ts_language) = ts_language_by_name(language)
+                .ok_or_else(|| format!("unknown language '{}'", language))?;
+            Query::new(&ts_language, query)
+                .map_err(|e| format!("invalid {} query: {}", language, e.message))?;
+        }
+        self.custom.insert(name, object);
+        Ok(())
+    }
+
+    /// Check if `name` is a built-in or registered text object
+    pub fn exists(&self, name: &str) -> bool {
+        BUILTIN_TEXT_OBJECTS.contains(&name) || self.custom.contains_key(name)
+    }
+
+    /// Find the text object `name` at `position` in `text`
+    ///
+    /// Returns None if there is no such object around the position.
+    pub fn find(
+        &mut self,
+        text: &str,
+        position: usize,
+        name: &str,
+        around: bool,
+        language: Option<&Language>,
+    ) -> Option<Range<usize>> {
+        let position = position.min(text.len());
+        match name {
+            "quotes" => quotes(text, position, around),
+            "brackets" => brackets(text, position, around),
+            "paragraph" => paragraph(text, position, around),
+            "function" => self
+                .function_tree_sitter(text, position, around, language)
+                .or_else(|| function_fallback(text, position, around)),
+            _ => self.custom(text, position, name, around, language),
+        }
+    }
+
+    /// Enclosing function from the language's `textobjects.scm`
+    fn function_tree_sitter(
+        &mut self,
+        text: &str,
+        position: usize,
+        around: bool,
+        language: Option<&Language>,
+    ) -> Option<Range<usize>> {
+        if text.len() > MAX_PARSE_BYTES {
+            return None;
+        }
+        let (name, ts_language) = ts_language(language?)?;
+        let config = self.parsers.entry(name).or_insert_with(|| {
+            let query_src = function_query(name)?;
+            let mut parser = Parser::new();
+            parser.set_language(&ts_language).ok()?;
+            match Query::new(&ts_language, query_src) {
+                Ok(query) => Some((parser, query)),
+                Err(e) => {
+                    tracing::error!("Failed to create text object query for {}: {:?}", name, e);
+                    None
+                }
+            }
+        });
+        let (parser, query) = config.as_mut()?;
+        let (outer, inner) = query_at(parser, query, text, position, "function")?;
+        Some(if around {
+            outer
+        } else {
+            strip_delimiters(text, inner.unwrap_or(outer))
+        })
+    }
+
+    /// Plugin object: its query for the buffer's language, else its pattern
+    fn custom(
+        &self,
+        text: &str,
+        position: usize,
+        name: &str,
+        around: bool,
+        language: Option<&Language>,
+    ) -> Option<Range<usize>> {
+        let object = self.custom.get(name)?;
+
+        let with_query = language
+            .and_then(ts_language)
+            .filter(|_| text.len() <= MAX_PARSE_BYTES)
+            .and_then(|(lang_name, ts_language)| {
+                let query = Query::new(&ts_language, object.queries.get(lang_name)?).ok()?;
+                let mut parser = Parser::new();
+                parser.set_language(&ts_language).ok()?;
+                query_at(&mut parser, &query, text, position, "")
+            });
+        if let Some((outer, inner)) = with_query {
+            return Some(if around {
+                outer
+            } else {
+                inner.unwrap_or(outer)
+            });
+        }
+
+        let pattern = object.pattern.as_ref()?;
+        pattern.captures_iter(text).find_map(|captures| {
+            let outer = captures.get(0)?;
+            if outer.start() > position || outer.end() < position {
+                return None;
+            }
+            Some(match captures.get(1) {
+                Some(inner) if !around => inner.range(),
+                _ => outer.range(),
+            })
+        })
+    }
+}
+
+impl Default for TextObjectFinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Smallest `@<prefix>around` capture containing `position`, with the
+/// `@<prefix>inside` capture of the same match
+///
+/// `prefix` is "function" for `@function.around`, or "" for `@around`.
+fn query_at(
+    parser: &mut Parser,
+    query: &Query,
+    text: &str,
+    position: usize,
+    prefix: &str,
+) -> Option<(Range<usize>, Option<Range<usize>>)> {
+    let capture_name = |kind: &str| {
+        if prefix.is_empty() {
+            kind.to_string()
+        } else {
+            format!("{}.{}", prefix, kind)
+        }
+    };
+    let around_index = query.capture_index_for_name(&capture_name("around"))?;
+    let inside_index = query.capture_index_for_name(&capture_name("inside"));
+
+    let tree = parser.parse(text, None)?;
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), text.as_bytes());
+
+    let mut best: Option<(Range<usize>, Option<Range<usize>>)> = None;
+    while let Some(m) = matches.next() {
+        let Some(outer) = m
+            .captures
+            .iter()
+            .find(|c| c.index == around_index)
+            .map(|c| c.node.byte_range())
+        else {
+            continue;
+        };
+        if !(outer.start <= position && position < outer.end) {
+            continue;
+        }
+        if best.as_ref().is_some_and(|(b, _)| b.len() <= outer.len()) {
+            continue;
+        }
+        let inner = m
+            .captures
+            .iter()
+            .find(|c| Some(c.index) == inside_index)
+            .map(|c| c.node.byte_range());
+        best = Some((outer, inner));
+    }
+    best
+}
+
+/// Drop the braces of a `{ ... }` body (and the line breaks next to them)
+fn strip_delimiters(text: &str, range: Range<usize>) -> Range<usize> {
+    let body = &text[range.clone()];
+    if !(body.starts_with('{') && body.ends_with('}') && body.len() >= 2) {
+        return range;
+    }
+    let mut start = range.start + 1;
+    let mut end = range.end - 1;
+    if text[start..end].starts_with('\n') {
+        start += 1;
+    }
+    // Keep the closing brace's indentation out of the body
+    let last_line_start = text[start..end].rfind('\n').map(|i| start + i + 1);
+    if let Some(line_start) = last_line_start {
+        if text[line_start..end].trim().is_empty() {
+            end = line_start;
+        }
+    }
+    start..end.max(start)
+}
+
+/// Quoted string on the line containing `position`
+///
+/// The innermost pair around the position wins; otherwise the first pair
+/// after it on the same line (like vim).
+fn quotes(text: &str, position: usize, around: bool) -> Option<Range<usize>> {
+    let line_start = text[..position].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[position..]
+        .find('\n')
+        .map_or(text.len(), |i| position + i);
+    let line = &text.as_bytes()[line_start..line_end];
+
+    // Pair up quotes left to right, skipping escaped ones
+    let mut pairs = Vec::new();
+    let mut open: Option<(usize, u8)> = None;
+    let mut escaped = false;
+    for (i, &b) in line.iter().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (b, open) {
+            (b'\\', Some(_)) => escaped = true,
+            (b'"' | b'\'' | b'`', None) => open = Some((i, b)),
+            (_, Some((start, quote))) if b == quote => {
+                pairs.push((line_start + start, line_start + i));
+                open = None;
+            }
+            _ => {}
+        }
+    }
+
+    let (start, end) = pairs
+        .iter()
+        .filter(|(start, end)| *start <= position && position <= *end)
+        .min_by_key(|(start, end)| end - start)
+        .or_else(|| pairs.iter().find(|(start, _)| *start > position))
+        .copied()?;
+    Some(if around {
+        start..end + 1
+    } else {
+        start + 1..end
+    })
+}
+
+/// Innermost (), [] or {} pair enclosing `position`
+///
+/// A position on a bracket selects the pair of that bracket.
+fn brackets(text: &str, position: usize, around: bool) -> Option<Range<usize>> {
+    const OPEN: &[u8] = b"([{";
+    const CLOSE: &[u8] = b")]}";
+    let bytes = text.as_bytes();
+
+    let matching_close = |open: usize| {
+        let kind = OPEN.iter().position(|&b| b == bytes[open])?;
+        let mut depth = 0;
+        for (i, &b) in bytes.iter().enumerate().skip(open) {
+            if b == OPEN[kind] {
+                depth += 1;
+            } else if b == CLOSE[kind] {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+        }
+        None
+    };
+
+    let pair = match bytes.get(position) {
+        Some(b) if OPEN.contains(b) => Some((position, matching_close(position)?)),
+        _ => {
+            // Innermost unmatched opening bracket before the position whose
+            // pair closes at or after it (a closing bracket under the
+            // position belongs to the pair)
+            let mut depth = [0usize; 3];
+            let mut found = None;
+            for i in (0..position).rev() {
+                if let Some(kind) = CLOSE.iter().position(|&b| b == bytes[i]) {
+                    depth[kind] += 1;
+                } else if let Some(kind) = OPEN.iter().position(|&b| b == bytes[i]) {
+                    if depth[kind] > 0 {
+                        depth[kind] -= 1;
+                    } else if let Some(close) = matching_close(i) {
+                        if close >= position {
+                            found = Some((i, close));
+                            break;
+                        }
+                    }
+                }
+            }
+            found
+        }
+    };
+
+    let (open, close) = pair?;
+    Some(if around {
+        open..close + 1
+    } else {
+        open + 1..close
+    })
+}
+
+/// Run of non-blank lines around `position` (or of blank lines, on a blank
+/// line); "around" adds the blank lines that follow (or precede, at the end)
+fn paragraph(text: &str, position: usize, around: bool) -> Option<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        lines.push(start..start + line.len());
+        start += line.len();
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    let is_blank = |range: &Range<usize>| text[range.clone()].trim().is_empty();
+    let current = lines
+        .iter()
+        .position(|line| position < line.end)
+        .unwrap_or(lines.len() - 1);
+    let blank = is_blank(&lines[current]);
+
+    let mut first = current;
+    while first > 0 && is_blank(&lines[first - 1]) == blank {
+        first -= 1;
+    }
+    let mut last = current;
+    while last + 1 < lines.len() && is_blank(&lines[last + 1]) == blank {
+        last += 1;
+    }
+
+    if around {
+        if last + 1 < lines.len() {
+            last += 1;
+            while last + 1 < lines.len() && is_blank(&lines[last + 1]) != blank {
+                last += 1;
+            }
+        } else {
+            while first > 0 && is_blank(&lines[first - 1]) != blank {
+                first -= 1;
+            }
+        }
+    }
+    Some(lines[first].start..lines[last].end)
+}
+
+/// Enclosing function found by its definition line
+///
+/// Braced bodies run to the matching `}`; others (Python) to the end of the
+/// indented block.
+fn function_fallback(text: &str, position: usize, around: bool) -> Option<Range<usize>> {
+    let definition = Regex::new(r"(?m)^[ \t]*(?:[\w()]+[ \t]+)*(?:fn|def|func|function)\b").ok()?;
+
+    // Innermost first: the closest definition above the position
+    let candidates: Vec<_> = definition
+        .find_iter(text)
+        .filter(|m| m.start() <= position)
+        .collect();
+    for m in candidates.into_iter().rev() {
+        let def_start = m.start();
+        let line_end = text[def_start..]
+            .find('\n')
+            .map_or(text.len(), |i| def_start + i);
+
+        let header = &text[def_start..line_end];
+        let (outer, inner) = if header.trim_end().ends_with(':') {
+            indented_block(text, def_start, line_end)
+        } else {
+            // Up to the first `{` that isn't closed on the way
+            let open = text[def_start..].find('{').map(|i| def_start + i)?;
+            let close = brackets(text, open, true)?.end;
+            (def_start..close, open..close)
+        };
+        if outer.start <= position && position < outer.end.max(outer.start + 1) {
+            return Some(if around {
+                outer
+            } else {
+                strip_delimiters(text, inner)
+            });
+        }
+    }
+    None
+}
+
+/// Python-style block: the lines after `line_end` indented deeper than the
+/// definition at `def_start`
+fn indented_block(text: &str, def_start: usize, line_end: usize) -> (Range<usize>, Range<usize>) {
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let def_indent = indent_of(&text[def_start..line_end]);
+
+    let body_start = (line_end + 1).min(text.len());
+    let mut end = body_start;
+    let mut pos = body_start;
+    for line in text[body_start..].split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            if indent_of(line) <= def_indent {
+                break;
+            }
+            end = pos + line.len();
+        }
+        pos += line.len();
+    }
+    (def_start..end, body_start..end)
+}
+
+/// Tree-sitter grammar of a language, with the name its queries are stored under
+fn ts_language(language: &Language) -> Option<(&'static str, tree_sitter::Language)> {
+    Some(match language {
+        Language::Rust => ("rust", tree_sitter_rust::LANGUAGE.into()),
+        Language::Python => ("python", tree_sitter_python::LANGUAGE.into()),
+        Language::JavaScript => ("javascript", tree_sitter_javascript::LANGUAGE.into()),
+        Language::TypeScript => (
+            "typescript",
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        ),
+        Language::C => ("c", tree_sitter_c::LANGUAGE.into()),
+        Language::Cpp => ("cpp", tree_sitter_cpp::LANGUAGE.into()),
+        Language::Go => ("go", tree_sitter_go::LANGUAGE.into()),
+        Language::Java => ("java", tree_sitter_java::LANGUAGE.into()),
+        Language::Php => ("php", tree_sitter_php::LANGUAGE_PHP.into()),
+        Language::Ruby => ("ruby", tree_sitter_ruby::LANGUAGE.into()),
+        Language::Bash => ("bash", tree_sitter_bash::LANGUAGE.into()),
+        Language::Lua => ("lua", tree_sitter_lua::LANGUAGE.into()),
+        Language::HTML => ("html", tree_sitter_html::LANGUAGE.into()),
+        Language::CSS => ("css", tree_sitter_css::LANGUAGE.into()),
+        Language::Json => ("json", tree_sitter_json::LANGUAGE.into()),
+        Language::CSharp => ("csharp", tree_sitter_c_sharp::LANGUAGE.into()),
+    })
+}
+
+/// Grammar for a language name used in plugin queries
+fn ts_language_by_name(name: &str) -> Option<(&'static str, tree_sitter::Language)> {
+    [
+        Language::Rust,
+        Language::Python,
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::C,
+        Language::Cpp,
+        Language::Go,
+        Language::Java,
+        Language::Php,
+        Language::Ruby,
+        Language::Bash,
+        Language::Lua,
+        Language::HTML,
+        Language::CSS,
+        Language::Json,
+        Language::CSharp,
+    ]
+    .iter()
+    .filter_map(ts_language)
+    .find(|(lang_name, _)| *lang_name == name)
+}
+
+/// The `textobjects.scm` query of a language
+fn function_query(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "rust" => include_str!("../../queries/rust/textobjects.scm"),
+        "python" => include_str!("../../queries/python/textobjects.scm"),
+        "javascript" => include_str!("../../queries/javascript/textobjects.scm"),
+        "typescript" => include_str!("../../queries/typescript/textobjects.scm"),
+        "c" => include_str!("../../queries/c/textobjects.scm"),
+        "cpp" => include_str!("../../queries/cpp/textobjects.scm"),
+        "go" => include_str!("../../queries/go/textobjects.scm"),
+        "java" => include_str!("../../queries/java/textobjects.scm"),
+        "php" => include_str!("../../queries/php/textobjects.scm"),
+        "ruby" => include_str!("../../queries/ruby/textobjects.scm"),
+        "bash" => include_str!("../../queries/bash/textobjects.scm"),
+        "lua" => include_str!("../../queries/lua/textobjects.scm"),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text selected by an object, with the position marked by `|`
+    fn select(
+        marked: &str,
+        name: &str,
+        around: bool,
+        language: Option<Language>,
+    ) -> Option<String> {
+        let position = marked.find('|').unwrap();
+        let text = marked.replacen('|', "", 1);
+        let range =
+            TextObjectFinder::new().find(&text, position, name, around, language.as_ref())?;
+        Some(text[range].to_string())
+    }
+
+    #[test]
+    fn test_quotes() {
+        let line = r#"let s = "a \"b|\" c"; x"#;
+        assert_eq!(select(line, "quotes", false, None).unwrap(), r#"a \"b\" c"#);
+        assert_eq!(
+            select(line, "quotes", true, None).unwrap(),
+            r#""a \"b\" c""#
+        );
+        // Before any quote: the next string on the line
+        assert_eq!(select("p|rint('hi')", "quotes", false, None).unwrap(), "hi");
+        assert_eq!(select("no| quotes", "quotes", false, None), None);
+    }
+
+    #[test]
+    fn test_brackets() {
+        let text = "f(a, [1, |2], {x})";
+        assert_eq!(select(text, "brackets", false, None).unwrap(), "1, 2");
+        assert_eq!(select(text, "brackets", true, None).unwrap(), "[1, 2]");
+        // Skips pairs that close before the position
+        assert_eq!(
+            select("f(a, [1], |b)", "brackets", false, None).unwrap(),
+            "a, [1], b"
+        );
+        // On a bracket: that bracket's pair
+        assert_eq!(
+            select("f|(a(b))", "brackets", true, None).unwrap(),
+            "(a(b))"
+        );
+    }
+
+    #[test]
+    fn test_paragraph() {
+        let text = "one\ntw|o\n\n\nthree\n";
+        assert_eq!(
+            select(text, "paragraph", false, None).unwrap(),
+            "one\ntwo\n"
+        );
+        assert_eq!(
+            select(text, "paragraph", true, None).unwrap(),
+            "one\ntwo\n\n\n"
+        );
+        // The last paragraph takes the blank lines before it
+        let text = "one\n\nthr|ee\n";
+        assert_eq!(select(text, "paragraph", true, None).unwrap(), "\nthree\n");
+    }
+
+    #[test]
+    fn test_function_tree_sitter() {
+        let source = "fn outer() {\n    fn inner() {}\n    call(|);\n}\n";
+        assert_eq!(
+            select(source, "function", true, Some(Language::Rust)).unwrap(),
+            "fn outer() {\n    fn inner() {}\n    call();\n}"
+        );
+        assert_eq!(
+            select(source, "function", false, Some(Language::Rust)).unwrap(),
+            "    fn inner() {}\n    call();\n"
+        );
+    }
+
+    #[test]
+    fn test_function_fallback() {
+        let source = "def a():\n    return 1\n\ndef b(x):\n    y = x|\n    return y\n\nz = 2\n";
+        assert_eq!(
+            select(source, "function", true, None).unwrap(),
+            "def b(x):\n    y = x\n    return y\n"
+        );
+        let source = "function f() {\n  g(|);\n}\n";
+        assert_eq!(select(source, "function", false, None).unwrap(), "  g();\n");
+    }
+
+    #[test]
+    fn test_custom_object() {
+        let mut finder = TextObjectFinder::new();
+        finder
+            .register(
+                "argument".to_string(),
+                CustomTextObject {
+                    queries: HashMap::from([(
+                        "rust".to_string(),
+                        "(parameter) @around".to_string(),
+                    )]),
+                    pattern: Some(Regex::new(r"<([^>]*)>").unwrap()),
+                },
+            )
+            .unwrap();
+        assert!(finder
+            .register(
+                "quotes".to_string(),
+                CustomTextObject {
+                    queries: HashMap::new(),
+                    pattern: None,
+                }
+            )
+            .is_err());
+
+        let source = "fn f(a: u8, b: u16) {}";
+        let position = source.find("u16").unwrap();
+        let range = finder.find(source, position, "argument", true, Some(&Language::Rust));
+        assert_eq!(&source[range.unwrap()], "b: u16");
+
+        // Without a query for the language, the pattern is used
+        let text = "Vec<String>";
+        let range = finder.find(text, 6, "argument", false, None);
+        assert_eq!(&text[range.unwrap()], "String");
+    }
+}
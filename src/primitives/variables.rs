@@ -0,0 +1,172 @@
+//! `${name}` placeholders in snippets, file templates, tasks and the status bar
+//!
+//! A placeholder is `${name}` or `${name:argument}` (e.g. `${env:HOME}`,
+//! `${date:%Y}`). `$$` is a literal `$`. Placeholders whose value is unknown
+//! are left as written, so a typo shows up in the result instead of silently
+//! disappearing.
+//!
+//! Task commands are run by the shell, so there each value is quoted and `$$`
+//! is left for the shell (see [`expand_command_variables`]). File prompts
+//! expand environment variables written as in a shell, `$NAME` or `${NAME}`
+//! (see [`expand_env_variables`]).
+
+/// Replace the placeholders in `template` with the values from `lookup`
+///
+/// `lookup` receives the text between the braces (e.g. `env:HOME`) and is only
+/// called for placeholders that occur, so expensive values can be computed on
+/// demand.
+pub fn expand_variables(template: &str, lookup: impl FnMut(&str) -> Option<String>) -> String {
+    expand(template, false, true, lookup)
+}
+
+/// Replace the placeholders in the shell command `template`, quoting each
+/// value so it is a single word however it is spelled (spaces, `;`, `$(...)`)
+pub fn expand_command_variables(
+    template: &str,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> String {
+    expand(template, false, false, |name| {
+        lookup(name).map(|value| shell_quote(&value))
+    })
+}
+
+/// Replace `$NAME` and `${NAME}` with the values from `lookup`
+pub fn expand_env_variables(text: &str, lookup: impl FnMut(&str) -> Option<String>) -> String {
+    expand(text, true, false, lookup)
+}
+
+/// Replace `${name}`, and with `bare_names` `$name` too; with `escape` `$$` is
+/// a literal `$`
+fn expand(
+    template: &str,
+    bare_names: bool,
+    escape: bool,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(dollar) = rest.find('$') {
+        out.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        if let Some(after_escape) = after.strip_prefix('$').filter(|_| escape) {
+            out.push('$');
+            rest = after_escape;
+            continue;
+        }
+        let (name, written) = match after.strip_prefix('{') {
+            Some(inner) => match inner.find('}') {
+                Some(end) => (&inner[..end], end + 2),
+                None => ("", 0),
+            },
+            None if bare_names => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+            None => ("", 0),
+        };
+        match lookup(name.trim()).filter(|_| !name.trim().is_empty()) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[dollar..dollar + 1 + written]),
+        }
+        rest = &after[written..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `text` as a single shell word
+fn shell_quote(text: &str) -> String {
+    #[cfg(windows)]
+    {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    }
+    #[cfg(not(windows))]
+    {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+/// Values of the placeholders that don't depend on the editor's state
+///
+/// - `env:NAME`: an environment variable (empty if unset)
+/// - `date` / `date:FORMAT`: today's date (`%Y-%m-%d`, or a strftime format)
+/// - `time`: the current time (`%H:%M:%S`)
+pub fn builtin_variable(name: &str) -> Option<String> {
+    let (name, argument) = match name.split_once(':') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (name, None),
+    };
+    let now = chrono::Local::now;
+    match (name, argument) {
+        ("env", Some(var)) => Some(std::env::var(var).unwrap_or_default()),
+        ("date", None) => Some(now().format("%Y-%m-%d").to_string()),
+        ("date", Some(format)) => {
+            use std::fmt::Write;
+            // Invalid formats make chrono's Display fail; keep the placeholder then
+            let mut formatted = String::new();
+            write!(formatted, "{}", now().format(format)).ok()?;
+            Some(formatted)
+        }
+        ("time", None) => Some(now().format("%H:%M:%S").to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "filename" => Some("main.rs".to_string()),
+            "line" => Some("12".to_string()),
+            _ => builtin_variable(name),
+        }
+    }
+
+    #[test]
+    fn test_expand_variables() {
+        assert_eq!(
+            expand_variables("${filename}:${ line }", lookup),
+            "main.rs:12"
+        );
+        assert_eq!(expand_variables("cost: $$5, $x", lookup), "cost: $5, $x");
+        assert_eq!(
+            expand_variables("${unknown} ${open", lookup),
+            "${unknown} ${open"
+        );
+        assert_eq!(expand_variables("", lookup), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_command_variables() {
+        let lookup = |name: &str| match name {
+            "file" => Some("my notes.md".to_string()),
+            "selection" => Some("it's $(rm -rf ~); done".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_command_variables("wc -l ${file} && echo $$ ${nope}", lookup),
+            "wc -l 'my notes.md' && echo $$ ${nope}"
+        );
+        assert_eq!(
+            expand_command_variables("echo ${selection}", lookup),
+            r"echo 'it'\''s $(rm -rf ~); done'"
+        );
+    }
+
+    #[test]
+    fn test_builtin_variables() {
+        assert_eq!(
+            builtin_variable("env:PATH"),
+            Some(std::env::var("PATH").unwrap_or_default())
+        );
+        assert_eq!(builtin_variable("date:%Y").unwrap().len(), 4);
+        assert_eq!(builtin_variable("date").unwrap().len(), 10);
+        assert_eq!(builtin_variable("date:%Q"), None);
+        assert_eq!(builtin_variable("nope"), None);
+    }
+}
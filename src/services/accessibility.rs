@@ -0,0 +1,179 @@
+//! Screen reader announcements
+//!
+//! A full-screen terminal UI is redrawn cell by cell, which terminal screen
+//! readers can't follow. In accessibility mode the editor sends short textual
+//! announcements (the line under the cursor, mode changes, the selected
+//! completion) to a backend the screen reader can listen to:
+//!
+//! - `pipe`: one announcement per line, appended to a file or named pipe
+//!   (e.g. `mkfifo /tmp/fresh-speech && tail -f /tmp/fresh-speech | espeak`)
+//! - `osc`: an OSC 9 escape sequence written to the terminal, for terminals
+//!   that forward it to the screen reader or the desktop's notifications
+
+use crate::config::{AccessibilityConfig, AnnouncementBackend};
+use std::fs::{File, OpenOptions};
+use std::io::{self, stdout, Write};
+use std::path::PathBuf;
+
+/// Longest announcement sent, in characters (long lines are cut off)
+const MAX_ANNOUNCEMENT_CHARS: usize = 200;
+
+/// Sends announcements to the configured backend
+#[derive(Debug)]
+pub struct Announcer {
+    backend: AnnouncementBackend,
+    pipe_path: Option<PathBuf>,
+    /// Open pipe (opened on first use, reopened after a write error)
+    pipe: Option<File>,
+}
+
+impl Announcer {
+    /// Create an announcer, or None when accessibility mode is off
+    pub fn from_config(config: &AccessibilityConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        if config.backend == AnnouncementBackend::Pipe && config.pipe_path.is_none() {
+            tracing::warn!("Accessibility mode uses the pipe backend but no pipe_path is set");
+            return None;
+        }
+        Some(Self {
+            backend: config.backend,
+            pipe_path: config.pipe_path.clone(),
+            pipe: None,
+        })
+    }
+
+    /// Send an announcement
+    ///
+    /// Failures are logged and otherwise ignored; a missing listener must not
+    /// get in the way of editing.
+    pub fn announce(&mut self, text: &str) {
+        let encoded = encode_announcement(self.backend, text);
+        if encoded.is_empty() {
+            return;
+        }
+        tracing::trace!("Announcing: {}", text);
+
+        let result = match self.backend {
+            AnnouncementBackend::Pipe => self.write_to_pipe(&encoded),
+            AnnouncementBackend::Osc => {
+                let mut out = stdout();
+                out.write_all(encoded.as_bytes()).and_then(|_| out.flush())
+            }
+        };
+        if let Err(e) = result {
+            tracing::debug!("Failed to send announcement: {}", e);
+        }
+    }
+
+    fn write_to_pipe(&mut self, encoded: &str) -> io::Result<()> {
+        if self.pipe.is_none() {
+            let Some(path) = &self.pipe_path else {
+                return Ok(());
+            };
+            self.pipe = Some(open_pipe(path)?);
+        }
+        let result = self
+            .pipe
+            .as_mut()
+            .map_or(Ok(()), |pipe| pipe.write_all(encoded.as_bytes()));
+        if result.is_err() {
+            // The listener went away; try again with the next announcement
+            self.pipe = None;
+        }
+        result
+    }
+}
+
+/// Open a file or named pipe for appending
+///
+/// A named pipe is opened without blocking, so the editor doesn't hang when
+/// nothing is reading from it yet (the open fails instead and is retried).
+fn open_pipe(path: &PathBuf) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NONBLOCK);
+    }
+    options.open(path)
+}
+
+/// Turn an announcement into what is written to the backend
+///
+/// Whitespace runs (including newlines) collapse to single spaces and control
+/// characters are dropped, so an announcement can't break out of its line or
+/// escape sequence. Returns an empty string if nothing is left to say.
+pub fn encode_announcement(backend: AnnouncementBackend, text: &str) -> String {
+    let mut words = text
+        .split_whitespace()
+        .map(|word| word.chars().filter(|c| !c.is_control()).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if words.is_empty() {
+        return String::new();
+    }
+    if let Some((cut, _)) = words.char_indices().nth(MAX_ANNOUNCEMENT_CHARS) {
+        words.truncate(cut);
+    }
+
+    match backend {
+        AnnouncementBackend::Pipe => format!("{}\n", words),
+        AnnouncementBackend::Osc => format!("\x1b]9;{}\x07", words),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_announcement() {
+        assert_eq!(
+            encode_announcement(AnnouncementBackend::Pipe, "  fn main() {\n"),
+            "fn main() {\n"
+        );
+        assert_eq!(
+            encode_announcement(AnnouncementBackend::Osc, "Find file:\x1b]0;x\x07"),
+            "\x1b]9;Find file:]0;x\x07"
+        );
+        assert_eq!(encode_announcement(AnnouncementBackend::Pipe, " \t\n"), "");
+
+        let long = "x".repeat(MAX_ANNOUNCEMENT_CHARS + 10);
+        assert_eq!(
+            encode_announcement(AnnouncementBackend::Pipe, &long).len(),
+            MAX_ANNOUNCEMENT_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn test_pipe_backend_appends_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("speech");
+        let config = AccessibilityConfig {
+            enabled: true,
+            backend: AnnouncementBackend::Pipe,
+            pipe_path: Some(path.clone()),
+        };
+        let mut announcer = Announcer::from_config(&config).unwrap();
+        announcer.announce("Line 1");
+        announcer.announce("File explorer");
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "Line 1\nFile explorer\n");
+    }
+
+    #[test]
+    fn test_disabled_or_missing_pipe_has_no_announcer() {
+        assert!(Announcer::from_config(&AccessibilityConfig::default()).is_none());
+        let config = AccessibilityConfig {
+            enabled: true,
+            backend: AnnouncementBackend::Pipe,
+            pipe_path: None,
+        };
+        assert!(Announcer::from_config(&config).is_none());
+    }
+}
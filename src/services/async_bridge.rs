@@ -23,6 +23,8 @@ pub enum AsyncMessage {
     /// LSP diagnostics received for a file
     LspDiagnostics {
         uri: String,
+        /// Document version (buffer revision) the diagnostics were computed for
+        version: Option<i32>,
         diagnostics: Vec<Diagnostic>,
     },
 
@@ -176,6 +178,82 @@ pub enum AsyncMessage {
 
     /// File open dialog: directory listing completed
     FileOpenDirectoryLoaded(std::io::Result<Vec<crate::services::fs::FsEntry>>),
+
+    /// Project task finished (see `services::project_tasks`)
+    ProjectTaskOutput {
+        /// Name of the task
+        name: String,
+        /// Output of the task, or the error that kept it from running
+        result: std::io::Result<crate::services::project_tasks::TaskResult>,
+    },
+
+    /// "Evaluate Selection" finished (see `services::evaluation`)
+    EvaluationOutput {
+        /// Buffer the evaluated code came from
+        buffer_id: crate::model::event::BufferId,
+        /// Where to insert the result, or None to show it in a popup
+        insert_at: Option<usize>,
+        /// Output of the evaluator, or the error that kept it from finishing
+        result: std::io::Result<crate::services::project_tasks::TaskResult>,
+    },
+
+    /// The project's symbol index was brought up to date in the background
+    SymbolIndexUpdated {
+        index: Box<crate::services::symbol_index::SymbolIndex>,
+        /// Number of files (re)indexed or dropped
+        changed: usize,
+    },
+
+    /// The project's files were scanned in the background
+    ProjectScanned {
+        scan: Box<crate::services::project_scan::ProjectScan>,
+    },
+
+    /// The project's search index was brought up to date in the background
+    SearchIndexUpdated {
+        index: Box<crate::services::search_index::SearchIndex>,
+        /// Number of files (re)indexed or dropped
+        changed: usize,
+    },
+
+    /// The project's lines were counted for "Buffer Statistics"
+    ProjectLinesCounted {
+        stats: Vec<crate::services::text_stats::LanguageStats>,
+    },
+
+    /// The git status of the project's files was read for the file explorer
+    GitStatusRead {
+        /// None if the project isn't in a git work tree
+        statuses: Option<crate::services::git_status::GitStatuses>,
+    },
+
+    /// The project's `.envrc` was read
+    ProjectEnvLoaded {
+        /// None if there is no `.envrc`; an error message if it couldn't be read
+        result: Result<Option<crate::services::project_env::ProjectEnv>, String>,
+    },
+
+    /// A line of output from a plugin job (see `services::plugins::process`)
+    PluginJobOutput {
+        job_id: u64,
+        stream: crate::services::plugins::process::JobStream,
+        line: String,
+    },
+
+    /// A plugin job exited, was killed or failed to start
+    PluginJobExited {
+        job_id: u64,
+        /// Exit code (-1 if killed by a signal or not started)
+        exit_code: i32,
+        /// Whether the job was killed with killJob
+        killed: bool,
+    },
+
+    /// Progress of a running background task (shown in the status bar)
+    TaskProgress { task_id: u64, message: String },
+
+    /// A background task finished (its result was sent before this)
+    TaskFinished { task_id: u64 },
 }
 
 /// LSP progress value types
@@ -397,6 +475,7 @@ mod tests {
         sender
             .send(AsyncMessage::LspDiagnostics {
                 uri: "file:///test.rs".to_string(),
+                version: Some(3),
                 diagnostics: diagnostics.clone(),
             })
             .unwrap();
@@ -407,9 +486,11 @@ mod tests {
         match &messages[0] {
             AsyncMessage::LspDiagnostics {
                 uri,
+                version,
                 diagnostics: diags,
             } => {
                 assert_eq!(uri, "file:///test.rs");
+                assert_eq!(*version, Some(3));
                 assert_eq!(diags.len(), 1);
                 assert_eq!(diags[0].message, "test error");
             }
@@ -0,0 +1,183 @@
+//! Audit log of saved files
+//!
+//! With `editor.audit_log` enabled, every save appends a line of JSON to the
+//! audit file (`save_audit.jsonl` in the data directory, or
+//! `editor.audit_log_path`): the file's path, when and by whom it was saved,
+//! its size before and after and the SHA-256 of its contents before and
+//! after. Lines are only ever appended, never rewritten.
+
+use super::file_lock::host_name;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of the audit file in the data directory
+const AUDIT_FILE_NAME: &str = "save_audit.jsonl";
+
+/// A file's size and content hash at one point in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    pub bytes: u64,
+    /// SHA-256 of the contents, hex encoded
+    pub sha256: String,
+}
+
+impl FileSnapshot {
+    /// Size and hash of the file at `path`, or None if it can't be read
+    /// (e.g. it doesn't exist yet)
+    pub fn of_file(path: &Path) -> Option<Self> {
+        let contents = std::fs::read(path).ok()?;
+        Some(Self {
+            bytes: contents.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&contents)),
+        })
+    }
+}
+
+/// One save, as written to the audit file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveRecord {
+    pub path: PathBuf,
+    /// RFC 3339 time of the save
+    pub timestamp: String,
+    pub user: String,
+    pub host: String,
+    /// The file before the save (None for a new file)
+    pub before: Option<FileSnapshot>,
+    pub after: FileSnapshot,
+}
+
+impl SaveRecord {
+    /// Bytes added (or, if negative, removed) by the save
+    pub fn byte_delta(&self) -> i64 {
+        let before = self.before.as_ref().map_or(0, |s| s.bytes);
+        self.after.bytes as i64 - before as i64
+    }
+}
+
+/// Default location of the audit file
+pub fn default_audit_log_path() -> io::Result<PathBuf> {
+    Ok(crate::input::input_history::get_data_dir()?.join(AUDIT_FILE_NAME))
+}
+
+/// A save being audited: snapshots the file before it is written, and
+/// appends the record once it has been
+#[derive(Debug)]
+pub struct SaveAudit {
+    log_path: PathBuf,
+    path: PathBuf,
+    before: Option<FileSnapshot>,
+}
+
+impl SaveAudit {
+    /// Snapshot `path` before it is saved
+    pub fn begin(log_path: PathBuf, path: &Path) -> Self {
+        Self {
+            log_path,
+            path: path.to_path_buf(),
+            before: FileSnapshot::of_file(path),
+        }
+    }
+
+    /// Snapshot the saved file and append the record to the audit file
+    pub fn finish(self) -> io::Result<SaveRecord> {
+        let after = FileSnapshot::of_file(&self.path)
+            .ok_or_else(|| io::Error::other("saved file can't be read"))?;
+        let record = SaveRecord {
+            path: self.path,
+            timestamp: chrono::Local::now().to_rfc3339(),
+            user: user_name(),
+            host: host_name(),
+            before: self.before,
+            after,
+        };
+        append_record(&self.log_path, &record)?;
+        Ok(record)
+    }
+}
+
+fn append_record(log_path: &Path, record: &SaveRecord) -> io::Result<()> {
+    if let Some(parent) = log_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)?;
+    // One write per record, so concurrent editors don't interleave lines
+    file.write_all(line.as_bytes())
+}
+
+/// The saves of `path` in the audit file, oldest first (lines that don't
+/// parse are skipped)
+pub fn save_history(log_path: &Path, path: &Path) -> io::Result<Vec<SaveRecord>> {
+    let file = match std::fs::File::open(log_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(record) = serde_json::from_str::<SaveRecord>(&line?) {
+            if record.path == path {
+                records.push(record);
+            }
+        }
+    }
+    Ok(records)
+}
+
+fn user_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_saves_are_appended_and_read_back_per_file() {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("audit").join(AUDIT_FILE_NAME);
+        let file = dir.path().join("notes.txt");
+        let other = dir.path().join("other.txt");
+
+        let audit = SaveAudit::begin(log.clone(), &file);
+        std::fs::write(&file, "hello").unwrap();
+        audit.finish().unwrap();
+
+        let audit = SaveAudit::begin(log.clone(), &other);
+        std::fs::write(&other, "x").unwrap();
+        audit.finish().unwrap();
+
+        let audit = SaveAudit::begin(log.clone(), &file);
+        std::fs::write(&file, "hi").unwrap();
+        audit.finish().unwrap();
+
+        let history = save_history(&log, &file).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].before, None);
+        assert_eq!(history[0].byte_delta(), 5);
+        assert_eq!(history[1].before, Some(history[0].after.clone()));
+        assert_eq!(history[1].byte_delta(), -3);
+        assert_eq!(
+            history[1].after.sha256,
+            format!("{:x}", Sha256::digest(b"hi"))
+        );
+        assert_eq!(save_history(&log, &other).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_missing_audit_file_has_no_history() {
+        let dir = TempDir::new().unwrap();
+        let history = save_history(&dir.path().join("none.jsonl"), Path::new("/a")).unwrap();
+        assert!(history.is_empty());
+    }
+}
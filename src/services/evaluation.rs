@@ -0,0 +1,85 @@
+//! Evaluating code snippets with an external evaluator
+//!
+//! Each language can name an evaluator in its configuration: a shell command
+//! that reads the code on stdin and prints the result (e.g. `python3 -` or
+//! `node -`). "Evaluate Selection" pipes the selection through it.
+
+use crate::services::project_env::ProjectEnv;
+use crate::services::project_tasks::{shell_command, TaskResult};
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Run `command` through the shell with `input` on stdin
+///
+/// The evaluator is killed if it hasn't finished after `timeout`, and a
+/// `TimedOut` error is returned.
+pub async fn evaluate(
+    command: &str,
+    input: String,
+    working_dir: &Path,
+    project_env: Option<&ProjectEnv>,
+    timeout: Duration,
+) -> io::Result<TaskResult> {
+    let mut child = shell_command(command, project_env)
+        .current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+
+    let run = async move {
+        // Write while reading the output, so a large result can't fill the
+        // pipe and block the evaluator before it has read all its input
+        let write = async move {
+            // An evaluator that exits without reading its input is not an error
+            let _ = stdin.write_all(input.as_bytes()).await;
+        };
+        let ((), output) = tokio::join!(write, child.wait_with_output());
+        output
+    };
+
+    match tokio::time::timeout(timeout, run).await {
+        Ok(output) => Ok(output?.into()),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("timed out after {} s", timeout.as_secs()),
+        )),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn run(command: &str, input: &str, timeout: Duration) -> io::Result<TaskResult> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(evaluate(
+            command,
+            input.to_string(),
+            Path::new("."),
+            None,
+            timeout,
+        ))
+    }
+
+    #[test]
+    fn test_evaluate_pipes_input() {
+        let result = run("tr a-z A-Z", "print(1)\n", Duration::from_secs(5)).unwrap();
+        assert_eq!(result.stdout, "PRINT(1)\n");
+        assert_eq!(result.exit_code, 0);
+    }
+
+    #[test]
+    fn test_evaluate_times_out() {
+        let err = run("sleep 5", "", Duration::from_millis(100)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+}
@@ -0,0 +1,234 @@
+//! Include/exclude glob sets for project files
+//!
+//! The file finder, project search and the file explorer leave out files
+//! excluded by the `files` section of the config and of the project's
+//! `.fresh/project.toml`:
+//!
+//! ```toml
+//! [files]
+//! include = ["src/", "tests/"]      # only these (everything if empty)
+//! exclude = ["target/", "*.min.js"]
+//! ```
+//!
+//! Patterns use .gitignore syntax, relative to the project root.
+
+use crate::config::FilesConfig;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Location of the project settings file, relative to the project root
+pub const PROJECT_FILE: &str = ".fresh/project.toml";
+
+/// The parts of `.fresh/project.toml` read here
+#[derive(Debug, Default, Deserialize)]
+struct ProjectFile {
+    #[serde(default)]
+    files: FilesConfig,
+}
+
+/// Load the `[files]` section of the project's `.fresh/project.toml`
+///
+/// A missing file is the same as an empty one.
+pub fn load_project_files(working_dir: &Path) -> Result<FilesConfig, String> {
+    let path = working_dir.join(PROJECT_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(FilesConfig::default()),
+        Err(e) => return Err(format!("{}: {}", PROJECT_FILE, e)),
+    };
+    toml::from_str::<ProjectFile>(&content)
+        .map(|file| file.files)
+        .map_err(|e| format!("{}: {}", PROJECT_FILE, e.message()))
+}
+
+/// Decides which files under a project root are left out
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    root: PathBuf,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    /// None when everything is included
+    include_matcher: Option<Gitignore>,
+    exclude_matcher: Gitignore,
+}
+
+impl FileFilter {
+    /// Filter for the files under `root`, from the global and project glob sets
+    pub fn new(root: &Path, global: &FilesConfig, project: &FilesConfig) -> Result<Self, String> {
+        let include: Vec<String> = global
+            .include
+            .iter()
+            .chain(&project.include)
+            .cloned()
+            .collect();
+        let exclude: Vec<String> = global
+            .exclude
+            .iter()
+            .chain(&project.exclude)
+            .cloned()
+            .collect();
+
+        let include_matcher = if include.is_empty() {
+            None
+        } else {
+            Some(build_matcher(root, &include)?)
+        };
+        let exclude_matcher = build_matcher(root, &exclude)?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            include,
+            exclude,
+            include_matcher,
+            exclude_matcher,
+        })
+    }
+
+    /// Include patterns (global ones first)
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    /// Exclude patterns (global ones first)
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Check if `path` (absolute, or relative to the root) is left out
+    ///
+    /// A path is left out if it or one of its directories is excluded, or
+    /// if it is a file that no include pattern covers. Directories are never
+    /// left out just because they aren't included, since they may contain
+    /// included files. Paths outside the root are never left out.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = if path.is_absolute() {
+            match path.strip_prefix(&self.root) {
+                Ok(relative) => relative,
+                Err(_) => return false,
+            }
+        } else {
+            path
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        if matches_or_parent(&self.exclude_matcher, relative, is_dir) {
+            return true;
+        }
+        match &self.include_matcher {
+            Some(include) if !is_dir => !matches_or_parent(include, relative, false),
+            _ => false,
+        }
+    }
+}
+
+impl Default for FileFilter {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("."),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            include_matcher: None,
+            exclude_matcher: Gitignore::empty(),
+        }
+    }
+}
+
+/// Compile .gitignore-style patterns rooted at `root`
+fn build_matcher(root: &Path, patterns: &[String]) -> Result<Gitignore, String> {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|e| format!("invalid pattern '{}': {}", pattern, e))?;
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Whether `relative` or one of its parent directories matches
+fn matches_or_parent(matcher: &Gitignore, relative: &Path, is_dir: bool) -> bool {
+    if matcher.matched(relative, is_dir).is_ignore() {
+        return true;
+    }
+    relative
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| !dir.as_os_str().is_empty())
+        .any(|dir| matcher.matched(dir, true).is_ignore())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(include: &[&str], exclude: &[&str]) -> FilesConfig {
+        FilesConfig {
+            include: include.iter().map(|p| p.to_string()).collect(),
+            exclude: exclude.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let root = Path::new("/project");
+        let filter = FileFilter::new(
+            root,
+            &files(&[], &["target/"]),
+            &files(&[], &["*.min.js", "/build"]),
+        )
+        .unwrap();
+
+        assert!(filter.is_excluded(Path::new("/project/target"), true));
+        assert!(filter.is_excluded(Path::new("/project/target/debug/app"), false));
+        assert!(filter.is_excluded(Path::new("web/app.min.js"), false));
+        assert!(filter.is_excluded(Path::new("build/out.txt"), false));
+        assert!(!filter.is_excluded(Path::new("src/build/mod.rs"), false));
+        assert!(!filter.is_excluded(Path::new("/project/src/main.rs"), false));
+        // Outside the project
+        assert!(!filter.is_excluded(Path::new("/other/target/x"), false));
+        assert_eq!(filter.exclude(), ["target/", "*.min.js", "/build"]);
+    }
+
+    #[test]
+    fn test_include_patterns() {
+        let filter = FileFilter::new(
+            Path::new("/project"),
+            &FilesConfig::default(),
+            &files(&["src/", "*.md"], &["src/generated/"]),
+        )
+        .unwrap();
+
+        assert!(!filter.is_excluded(Path::new("src/lib.rs"), false));
+        assert!(!filter.is_excluded(Path::new("docs/guide.md"), false));
+        assert!(filter.is_excluded(Path::new("Cargo.toml"), false));
+        // Directories stay, they may hold included files
+        assert!(!filter.is_excluded(Path::new("docs"), true));
+        // Excludes win over includes
+        assert!(filter.is_excluded(Path::new("src/generated/api.rs"), false));
+    }
+
+    #[test]
+    fn test_load_project_files() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            load_project_files(dir.path()).unwrap(),
+            FilesConfig::default()
+        );
+
+        std::fs::create_dir(dir.path().join(".fresh")).unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_FILE),
+            "[files]\nexclude = [\"fixtures/\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            load_project_files(dir.path()).unwrap(),
+            files(&[], &["fixtures/"])
+        );
+
+        std::fs::write(dir.path().join(PROJECT_FILE), "[files]\nexclude = 3\n").unwrap();
+        assert!(load_project_files(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Advisory locks on files being edited
+//!
+//! With `editor.lock_files` enabled, Fresh marks each file it edits with a
+//! lock file next to it (`.name.fresh-lock`) holding its process ID and host
+//! name. Nothing stops other programs from writing the file; the lock only
+//! lets other editors notice. On open, a lock from another Fresh instance that
+//! is still running, or a Vim swap file (`.name.swp`), means the file is being
+//! edited elsewhere.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Contents of a Fresh lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockInfo {
+    /// Process ID of the Fresh instance holding the lock
+    pub pid: u32,
+    /// Host the process runs on (PIDs of other hosts can't be checked)
+    pub host: String,
+}
+
+impl LockInfo {
+    /// Lock info for this process
+    pub fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: host_name(),
+        }
+    }
+
+    /// Check if the lock belongs to this process
+    pub fn is_current(&self) -> bool {
+        *self == Self::current()
+    }
+
+    /// Check if the process holding the lock has exited
+    ///
+    /// Only possible for locks of this host; others are never stale.
+    pub fn is_stale(&self) -> bool {
+        self.host == host_name() && !crate::services::recovery::types::is_process_running(self.pid)
+    }
+}
+
+/// Who else is editing a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockHolder {
+    /// Another Fresh instance
+    Fresh(LockInfo),
+    /// Vim (or Neovim), found by its swap file
+    Vim { swap_file: PathBuf },
+}
+
+impl LockHolder {
+    /// Describe the holder for messages ("Fresh (pid 123 on host)")
+    pub fn describe(&self) -> String {
+        match self {
+            LockHolder::Fresh(info) if info.host == host_name() => {
+                format!("another Fresh (pid {})", info.pid)
+            }
+            LockHolder::Fresh(info) => {
+                format!("another Fresh (pid {} on {})", info.pid, info.host)
+            }
+            LockHolder::Vim { swap_file } => format!(
+                "Vim (swap file {})",
+                swap_file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Path of the Fresh lock file for `path`
+pub fn lock_path(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+    Some(path.with_file_name(format!(".{}.fresh-lock", name)))
+}
+
+/// Vim swap files that may exist for `path`
+fn vim_swap_files(path: &Path) -> Vec<PathBuf> {
+    let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+        return Vec::new();
+    };
+    ["swp", "swo"]
+        .iter()
+        .map(|ext| path.with_file_name(format!(".{}.{}", name, ext)))
+        .collect()
+}
+
+/// Find another editor editing `path`
+///
+/// Our own lock and stale Fresh locks (of processes that have exited) are
+/// ignored.
+pub fn find_lock_holder(path: &Path) -> Option<LockHolder> {
+    if let Some(info) = read_lock(path) {
+        if !info.is_current() && !info.is_stale() {
+            return Some(LockHolder::Fresh(info));
+        }
+    }
+    vim_swap_files(path)
+        .into_iter()
+        .find(|swap_file| swap_file.exists())
+        .map(|swap_file| LockHolder::Vim { swap_file })
+}
+
+/// Read the Fresh lock of `path`, if there is one
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let json = std::fs::read_to_string(lock_path(path)?).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Lock `path` for this process (replacing any other Fresh lock)
+pub fn acquire(path: &Path) -> io::Result<()> {
+    let lock_path = lock_path(path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let json = serde_json::to_string(&LockInfo::current()).map_err(io::Error::other)?;
+    std::fs::write(lock_path, json)
+}
+
+/// Remove the lock of `path` if this process holds it
+pub fn release(path: &Path) {
+    if read_lock(path).is_some_and(|info| info.is_current()) {
+        if let Some(lock_path) = lock_path(path) {
+            if let Err(e) = std::fs::remove_file(&lock_path) {
+                tracing::warn!("Failed to remove lock file {:?}: {}", lock_path, e);
+            }
+        }
+    }
+}
+
+/// Name of this host (empty if unknown)
+pub(crate) fn host_name() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        let result = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+        if result == 0 {
+            let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            return String::from_utf8_lossy(&buf[..len]).into_owned();
+        }
+    }
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_acquire_and_release() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "text").unwrap();
+
+        acquire(&path).unwrap();
+        assert!(dir.path().join(".notes.txt.fresh-lock").exists());
+        // Our own lock doesn't count as another editor
+        assert_eq!(find_lock_holder(&path), None);
+
+        release(&path);
+        assert!(!dir.path().join(".notes.txt.fresh-lock").exists());
+    }
+
+    #[test]
+    fn test_other_editors_are_found() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "text").unwrap();
+
+        // A lock of another host can't be checked, so it is respected
+        let other = LockInfo {
+            pid: 1,
+            host: "elsewhere".to_string(),
+        };
+        let lock = lock_path(&path).unwrap();
+        std::fs::write(&lock, serde_json::to_string(&other).unwrap()).unwrap();
+        assert_eq!(find_lock_holder(&path), Some(LockHolder::Fresh(other)));
+
+        // Releasing leaves other instances' locks alone
+        release(&path);
+        assert!(lock.exists());
+        std::fs::remove_file(&lock).unwrap();
+
+        let swap_file = dir.path().join(".notes.txt.swp");
+        std::fs::write(&swap_file, "").unwrap();
+        assert_eq!(find_lock_holder(&path), Some(LockHolder::Vim { swap_file }));
+    }
+}
@@ -0,0 +1,164 @@
+//! Partial file loading for previews
+//!
+//! Pickers and plugins preview files without loading them: only the start of
+//! a file, or a window of lines around a target line, is read. Lines are cut
+//! at `MAX_LINE_BYTES`, so a minified file with one huge line doesn't get
+//! read whole either.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Longest line kept in a preview (the rest of the line is skipped)
+pub const MAX_LINE_BYTES: usize = 1024;
+
+/// Part of a file to load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewRange {
+    /// The first lines, reading at most `max_bytes`
+    Start { max_bytes: u64 },
+    /// The lines around a 0-indexed line, `context` before and after it
+    AroundLine { line: usize, context: usize },
+}
+
+/// Lines loaded for a preview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSlice {
+    /// 0-indexed line number of the first line
+    pub first_line: usize,
+    /// The lines, without line endings
+    pub lines: Vec<String>,
+}
+
+/// Load part of a file, at most `max_lines` lines
+///
+/// The file is read only up to the last line returned. Returns None if the
+/// file is binary (has a NUL byte in the lines read).
+pub fn load_file_slice(
+    path: &Path,
+    range: PreviewRange,
+    max_lines: usize,
+) -> io::Result<Option<FileSlice>> {
+    let (limit, first, end) = match range {
+        PreviewRange::Start { max_bytes } => (max_bytes, 0, max_lines),
+        PreviewRange::AroundLine { line, context } => {
+            let first = line.saturating_sub(context);
+            (u64::MAX, first, (line + context + 1).min(first + max_lines))
+        }
+    };
+
+    let mut reader = BufReader::new(File::open(path)?.take(limit));
+    let mut lines = Vec::new();
+    let mut bytes = Vec::new();
+    for line in 0..end {
+        bytes.clear();
+        if read_line_capped(&mut reader, &mut bytes)? == 0 {
+            break;
+        }
+        if bytes.contains(&0) {
+            return Ok(None);
+        }
+        if line >= first {
+            let text = String::from_utf8_lossy(&bytes);
+            lines.push(text.trim_end_matches(['\n', '\r']).to_string());
+        }
+    }
+    Ok(Some(FileSlice {
+        first_line: first,
+        lines,
+    }))
+}
+
+/// Read a line into `line`, keeping at most `MAX_LINE_BYTES` of it
+///
+/// Returns the number of bytes consumed (0 at the end of the file).
+fn read_line_capped(reader: &mut impl BufRead, line: &mut Vec<u8>) -> io::Result<usize> {
+    let mut consumed = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(consumed);
+        }
+        let (used, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(newline) => (newline + 1, true),
+            None => (buf.len(), false),
+        };
+        let room = MAX_LINE_BYTES.saturating_sub(line.len());
+        line.extend_from_slice(&buf[..used.min(room)]);
+        reader.consume(used);
+        consumed += used;
+        if done {
+            return Ok(consumed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_file(lines: usize) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let text: String = (1..=lines).map(|n| format!("line {}\n", n)).collect();
+        std::fs::write(file.path(), text).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_load_start_and_window() {
+        let file = numbered_file(100);
+
+        let slice = load_file_slice(file.path(), PreviewRange::Start { max_bytes: 20 }, 10)
+            .unwrap()
+            .unwrap();
+        assert_eq!(slice.first_line, 0);
+        assert_eq!(slice.lines, vec!["line 1", "line 2", "line 3"]);
+
+        let slice = load_file_slice(
+            file.path(),
+            PreviewRange::AroundLine {
+                line: 49,
+                context: 2,
+            },
+            100,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(slice.first_line, 47);
+        assert_eq!(
+            slice.lines,
+            vec!["line 48", "line 49", "line 50", "line 51", "line 52"]
+        );
+
+        // Window clipped at both ends of the file and by max_lines
+        let range = PreviewRange::AroundLine {
+            line: 1,
+            context: 5,
+        };
+        let slice = load_file_slice(file.path(), range, 4).unwrap().unwrap();
+        assert_eq!(slice.first_line, 0);
+        assert_eq!(slice.lines.len(), 4);
+        let range = PreviewRange::AroundLine {
+            line: 99,
+            context: 5,
+        };
+        let slice = load_file_slice(file.path(), range, 100).unwrap().unwrap();
+        assert_eq!(slice.lines.last().unwrap(), "line 100");
+    }
+
+    #[test]
+    fn test_long_lines_and_binary_files() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let long = "x".repeat(MAX_LINE_BYTES * 3);
+        std::fs::write(file.path(), format!("{}\nnext\n", long)).unwrap();
+        let range = PreviewRange::Start {
+            max_bytes: u64::MAX,
+        };
+        let slice = load_file_slice(file.path(), range, 10).unwrap().unwrap();
+        assert_eq!(slice.lines[0].len(), MAX_LINE_BYTES);
+        assert_eq!(slice.lines[1], "next");
+
+        std::fs::write(file.path(), b"text\0binary\n").unwrap();
+        assert_eq!(load_file_slice(file.path(), range, 10).unwrap(), None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Git status of a project's files
+//!
+//! `git status --porcelain` is run in the project's root, and its changed,
+//! added, untracked and ignored paths are kept relative to that root. Git
+//! lists an untracked or ignored directory as a whole, so the files below it
+//! take its status. The file explorer shows the statuses as badges (see
+//! `app::file_explorer`), read again in the background after saves.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Git status of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitFileStatus {
+    /// Changed since the last commit, staged or not (renamed and
+    /// conflicting files too)
+    Modified,
+    /// New in the index
+    Added,
+    /// Not tracked and not ignored
+    Untracked,
+    /// Ignored by .gitignore or the repository's excludes
+    Ignored,
+}
+
+impl GitFileStatus {
+    /// Letter shown next to the file in the explorer
+    pub fn badge(self) -> &'static str {
+        match self {
+            GitFileStatus::Modified => "M",
+            GitFileStatus::Added => "A",
+            GitFileStatus::Untracked => "U",
+            GitFileStatus::Ignored => "I",
+        }
+    }
+}
+
+/// The git statuses of the files under a project root
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStatuses {
+    root: PathBuf,
+    /// Paths relative to the root; directories for untracked and ignored
+    /// directories
+    paths: HashMap<PathBuf, GitFileStatus>,
+    /// Directories holding modified or added files, relative to the root
+    changed_dirs: HashSet<PathBuf>,
+}
+
+impl GitStatuses {
+    /// Status of the file or directory at `path`, if git lists it or a
+    /// directory it is in
+    pub fn status_of(&self, path: &Path) -> Option<GitFileStatus> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        relative
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .find_map(|ancestor| self.paths.get(ancestor).copied())
+    }
+
+    /// Whether the directory at `path` holds modified or added files
+    pub fn has_changes(&self, path: &Path) -> bool {
+        path.strip_prefix(&self.root)
+            .is_ok_and(|relative| self.changed_dirs.contains(relative))
+    }
+}
+
+/// Read the statuses of the files under `root`, or None if it isn't in a git
+/// work tree (or git can't be run)
+pub fn read_git_status(root: &Path) -> Option<GitStatuses> {
+    // Porcelain paths are relative to the top of the work tree
+    let prefix = run_git(root, &["rev-parse", "--show-prefix"])?;
+    let status = run_git(
+        root,
+        &["status", "--porcelain=v1", "-z", "--ignored=matching"],
+    )?;
+    Some(parse_porcelain(root, prefix.trim_end(), &status))
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Statuses from `git status --porcelain=v1 -z` output, for the files under
+/// `prefix` (the root's path in the work tree, e.g. "crates/app/")
+fn parse_porcelain(root: &Path, prefix: &str, output: &str) -> GitStatuses {
+    let mut statuses = GitStatuses {
+        root: root.to_path_buf(),
+        ..Default::default()
+    };
+    let mut entries = output.split('\0');
+    while let Some(entry) = entries.next() {
+        let (Some(code), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+            continue;
+        };
+        // A rename or copy is followed by the path it came from
+        if code.contains(['R', 'C']) {
+            entries.next();
+        }
+        let status = match code {
+            "??" => GitFileStatus::Untracked,
+            "!!" => GitFileStatus::Ignored,
+            _ if code.starts_with('A') => GitFileStatus::Added,
+            // Deleted files aren't in the tree to show
+            " D" | "D " => continue,
+            _ => GitFileStatus::Modified,
+        };
+        let Some(relative) = path.strip_prefix(prefix) else {
+            continue;
+        };
+        let relative = PathBuf::from(relative.trim_end_matches('/'));
+        if matches!(status, GitFileStatus::Modified | GitFileStatus::Added) {
+            statuses.changed_dirs.extend(
+                relative
+                    .ancestors()
+                    .skip(1)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map(Path::to_path_buf),
+            );
+        }
+        statuses.paths.insert(relative, status);
+    }
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain() {
+        let output = " M src/main.rs\0A  src/new.rs\0R  src/b.rs\0src/a.rs\0?? notes/\0!! target/\0 D gone.rs\0MM other/x.rs\0";
+        let root = Path::new("/repo/src");
+        let statuses = parse_porcelain(root, "src/", output);
+
+        let status = |path: &str| statuses.status_of(&root.join(path));
+        assert_eq!(status("main.rs"), Some(GitFileStatus::Modified));
+        assert_eq!(status("new.rs"), Some(GitFileStatus::Added));
+        assert_eq!(status("b.rs"), Some(GitFileStatus::Modified));
+        assert_eq!(status("lib.rs"), None);
+        // Outside the root
+        assert_eq!(statuses.status_of(Path::new("/repo/other/x.rs")), None);
+
+        let statuses = parse_porcelain(Path::new("/repo"), "", output);
+        let status = |path: &str| statuses.status_of(&Path::new("/repo").join(path));
+        assert_eq!(status("notes/todo.txt"), Some(GitFileStatus::Untracked));
+        assert_eq!(status("target/debug"), Some(GitFileStatus::Ignored));
+        assert_eq!(status("gone.rs"), None);
+        assert!(statuses.has_changes(Path::new("/repo/src")));
+        assert!(statuses.has_changes(Path::new("/repo/other")));
+        assert!(!statuses.has_changes(Path::new("/repo/notes")));
+    }
+}
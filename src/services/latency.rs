@@ -0,0 +1,355 @@
+//! Typing latency tracing
+//!
+//! Each key is timestamped as it goes from input to paint: when it arrives,
+//! when it has been resolved to an action, when the action (and the buffer
+//! edit it makes) is done, and then, for the next frame, when rendering
+//! starts, how long building the view streams took, when rendering ends and
+//! when the terminal has been flushed. The last few hundred keys are kept
+//! for percentiles (shown in the performance overlay), and with
+//! `--trace-latency FILE` every key is also written out as a line of
+//! tab-separated microseconds.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Keys kept for percentiles
+const MAX_SAMPLES: usize = 500;
+/// Keys waiting for a frame; older ones are dropped if frames never come
+const MAX_PENDING: usize = 256;
+
+thread_local! {
+    /// View stream build time since the frame started
+    static STREAM_BUILD: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Add the time spent building a view stream to the current frame
+///
+/// Called from the renderer, which has no access to the editor's tracer.
+pub fn record_stream_build(elapsed: Duration) {
+    STREAM_BUILD.with(|total| total.set(total.get() + elapsed));
+}
+
+/// A step on the way from a key press to the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyStage {
+    /// Resolving the key to an action
+    Dispatch,
+    /// Running the action, including applying its edits to the buffer
+    Edit,
+    /// Waiting for the next frame
+    Wait,
+    /// Building the view streams of the visible buffers
+    Stream,
+    /// The rest of rendering
+    Render,
+    /// Writing the frame to the terminal
+    Flush,
+}
+
+impl LatencyStage {
+    pub const ALL: [LatencyStage; 6] = [
+        LatencyStage::Dispatch,
+        LatencyStage::Edit,
+        LatencyStage::Wait,
+        LatencyStage::Stream,
+        LatencyStage::Render,
+        LatencyStage::Flush,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LatencyStage::Dispatch => "dispatch",
+            LatencyStage::Edit => "edit",
+            LatencyStage::Wait => "wait",
+            LatencyStage::Stream => "stream",
+            LatencyStage::Render => "render",
+            LatencyStage::Flush => "flush",
+        }
+    }
+}
+
+/// How long one key took to reach the screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencySample {
+    /// The key, e.g. "Ctrl+S"
+    pub key: String,
+    /// Time spent in each stage, in `LatencyStage::ALL` order
+    pub stages: [Duration; 6],
+    /// From the key arriving to the frame showing it being flushed
+    pub total: Duration,
+}
+
+impl LatencySample {
+    pub fn stage(&self, stage: LatencyStage) -> Duration {
+        self.stages[stage as usize]
+    }
+}
+
+/// 50th, 95th and 99th percentiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Percentiles {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+/// A key that hasn't been painted yet
+#[derive(Debug)]
+struct PendingKey {
+    key: String,
+    received: Instant,
+    dispatched: Option<Instant>,
+    handled: Option<Instant>,
+}
+
+/// Marks of the frame being drawn
+#[derive(Debug, Default)]
+struct FrameMarks {
+    started: Option<Instant>,
+    stream: Duration,
+    finished: Option<Instant>,
+}
+
+/// Follows keys from input to paint
+#[derive(Default)]
+pub struct LatencyTracer {
+    pending: Vec<PendingKey>,
+    /// Keys being handled; keys replayed by a macro are part of the key
+    /// that replays them
+    depth: usize,
+    frame: FrameMarks,
+    samples: VecDeque<LatencySample>,
+    dump: Option<BufWriter<File>>,
+}
+
+impl LatencyTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also write every key to `path`, one line each
+    pub fn enable_dump(&mut self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let stages: Vec<String> = LatencyStage::ALL
+            .iter()
+            .map(|stage| format!("{}_us", stage.name()))
+            .collect();
+        writeln!(file, "key\t{}\ttotal_us", stages.join("\t"))?;
+        file.flush()?;
+        self.dump = Some(file);
+        Ok(())
+    }
+
+    /// A key has arrived
+    pub fn key_received(&mut self, key: String) {
+        self.depth += 1;
+        if self.depth > 1 {
+            return;
+        }
+        if self.pending.len() >= MAX_PENDING {
+            self.pending.remove(0);
+        }
+        self.pending.push(PendingKey {
+            key,
+            received: Instant::now(),
+            dispatched: None,
+            handled: None,
+        });
+    }
+
+    /// The key has been resolved and its action is about to run
+    pub fn key_dispatched(&mut self) {
+        if let Some(pending) = self.current_key() {
+            pending.dispatched.get_or_insert_with(Instant::now);
+        }
+    }
+
+    /// The key has been handled
+    pub fn key_handled(&mut self) {
+        if self.depth == 1 {
+            if let Some(pending) = self.current_key() {
+                pending.handled = Some(Instant::now());
+            }
+        }
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// The key being handled, if any
+    fn current_key(&mut self) -> Option<&mut PendingKey> {
+        self.pending.last_mut().filter(|key| key.handled.is_none())
+    }
+
+    /// A frame has started rendering
+    pub fn render_started(&mut self) {
+        STREAM_BUILD.with(|total| total.set(Duration::ZERO));
+        self.frame = FrameMarks {
+            started: Some(Instant::now()),
+            ..FrameMarks::default()
+        };
+    }
+
+    /// The frame has been rendered (but not yet written out)
+    pub fn render_finished(&mut self) {
+        self.frame.stream = STREAM_BUILD.with(|total| total.replace(Duration::ZERO));
+        self.frame.finished = Some(Instant::now());
+    }
+
+    /// The frame has been flushed to the terminal: every key handled before
+    /// it is now on screen
+    pub fn frame_flushed(&mut self) {
+        let frame = std::mem::take(&mut self.frame);
+        let (Some(started), Some(finished)) = (frame.started, frame.finished) else {
+            return;
+        };
+        let flushed = Instant::now();
+
+        let (painted, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|key| key.handled.is_some_and(|handled| handled <= started));
+        self.pending = waiting;
+
+        for key in painted {
+            let handled = key.handled.unwrap_or(started);
+            let dispatched = key.dispatched.unwrap_or(handled);
+            let sample = LatencySample {
+                key: key.key,
+                stages: [
+                    dispatched.saturating_duration_since(key.received),
+                    handled.saturating_duration_since(dispatched),
+                    started.saturating_duration_since(handled),
+                    frame.stream,
+                    finished
+                        .saturating_duration_since(started)
+                        .saturating_sub(frame.stream),
+                    flushed.saturating_duration_since(finished),
+                ],
+                total: flushed.saturating_duration_since(key.received),
+            };
+            self.push_sample(sample);
+        }
+        if let Some(dump) = &mut self.dump {
+            if let Err(e) = dump.flush() {
+                tracing::warn!("Failed to write latency trace: {}", e);
+                self.dump = None;
+            }
+        }
+    }
+
+    fn push_sample(&mut self, sample: LatencySample) {
+        if let Some(dump) = &mut self.dump {
+            let stages: Vec<String> = sample
+                .stages
+                .iter()
+                .map(|d| d.as_micros().to_string())
+                .collect();
+            let _ = writeln!(
+                dump,
+                "{}\t{}\t{}",
+                sample.key,
+                stages.join("\t"),
+                sample.total.as_micros()
+            );
+        }
+        if self.samples.len() >= MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Keys traced so far (at most the last few hundred)
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Percentiles of a stage over the kept keys, or of the total with None
+    pub fn percentiles(&self, stage: Option<LatencyStage>) -> Option<Percentiles> {
+        let mut durations: Vec<Duration> = self
+            .samples
+            .iter()
+            .map(|sample| stage.map_or(sample.total, |stage| sample.stage(stage)))
+            .collect();
+        durations.sort();
+        Some(Percentiles {
+            p50: percentile(&durations, 50)?,
+            p95: percentile(&durations, 95)?,
+            p99: percentile(&durations, 99)?,
+        })
+    }
+}
+
+/// The `p`th percentile (nearest rank) of sorted durations
+fn percentile(sorted: &[Duration], p: usize) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Short form of a duration for the overlay, e.g. "850µs" or "12.3ms"
+pub fn format_latency(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1000 {
+        format!("{}µs", micros)
+    } else {
+        format!("{:.1}ms", micros as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let ms = |n: u64| Duration::from_millis(n);
+        let sorted: Vec<Duration> = (1..=100).map(ms).collect();
+        assert_eq!(percentile(&sorted, 50), Some(ms(50)));
+        assert_eq!(percentile(&sorted, 95), Some(ms(95)));
+        assert_eq!(percentile(&sorted, 99), Some(ms(99)));
+        assert_eq!(percentile(&[ms(7)], 99), Some(ms(7)));
+        assert_eq!(percentile(&[ms(1), ms(9)], 50), Some(ms(1)));
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn test_keys_complete_at_flush() {
+        let mut tracer = LatencyTracer::new();
+        assert_eq!(tracer.percentiles(None), None);
+
+        tracer.key_received("a".to_string());
+        tracer.key_dispatched();
+        // A key replayed while handling "a" isn't traced on its own
+        tracer.key_received("b".to_string());
+        tracer.key_handled();
+        tracer.key_handled();
+        // Flushing without a rendered frame paints nothing
+        tracer.frame_flushed();
+        assert_eq!(tracer.sample_count(), 0);
+
+        tracer.render_started();
+        record_stream_build(Duration::from_micros(3));
+        tracer.render_finished();
+        // A key handled after the frame started waits for the next one
+        tracer.key_received("c".to_string());
+        tracer.key_handled();
+        tracer.frame_flushed();
+
+        assert_eq!(tracer.sample_count(), 1);
+        let sample = &tracer.samples[0];
+        assert_eq!(sample.key, "a");
+        assert_eq!(sample.stage(LatencyStage::Stream), Duration::from_micros(3));
+        assert!(sample.stages.iter().sum::<Duration>() <= sample.total);
+        assert_eq!(tracer.pending.len(), 1);
+    }
+
+    #[test]
+    fn test_format_latency() {
+        assert_eq!(format_latency(Duration::from_micros(850)), "850µs");
+        assert_eq!(format_latency(Duration::from_micros(12_340)), "12.3ms");
+    }
+}
@@ -15,22 +15,22 @@ use crate::services::async_bridge::{
     AsyncBridge, AsyncMessage, LspMessageType, LspProgressValue, LspServerStatus,
 };
 use crate::services::process_limits::ProcessLimits;
+use crate::services::project_env::ProjectEnv;
 use lsp_types::{
     notification::{
-        DidChangeTextDocument, DidOpenTextDocument, DidSaveTextDocument, Initialized, Notification,
-        PublishDiagnostics,
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Initialized, Notification, PublishDiagnostics,
     },
     request::{Initialize, Request, Shutdown},
-    ClientCapabilities, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, InitializeParams, InitializeResult, InitializedParams,
-    PublishDiagnosticsParams, ServerCapabilities, TextDocumentContentChangeEvent,
-    TextDocumentIdentifier, TextDocumentItem, Uri, VersionedTextDocumentIdentifier,
-    WindowClientCapabilities, WorkspaceFolder,
+    ClientCapabilities, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, InitializeParams, InitializeResult,
+    InitializedParams, PublishDiagnosticsParams, ServerCapabilities,
+    TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem, Uri,
+    VersionedTextDocumentIdentifier, WindowClientCapabilities, WorkspaceFolder,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::{mpsc as std_mpsc, Arc, Mutex};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, ChildStdout, Command};
@@ -224,17 +224,22 @@ enum LspCommand {
         uri: Uri,
         text: String,
         language_id: String,
+        version: i32,
     },
 
     /// Notify document changed
     DidChange {
         uri: Uri,
         content_changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
     },
 
     /// Notify document saved
     DidSave { uri: Uri, text: Option<String> },
 
+    /// Notify document closed
+    DidClose { uri: Uri },
+
     /// Request completion at position
     Completion {
         request_id: u64,
@@ -342,9 +347,6 @@ struct LspState {
     /// Server capabilities
     capabilities: Option<ServerCapabilities>,
 
-    /// Document versions
-    document_versions: HashMap<PathBuf, i64>,
-
     /// Whether initialized
     initialized: bool,
 
@@ -379,25 +381,31 @@ impl LspState {
                     uri,
                     text,
                     language_id,
+                    version,
                 } => {
                     tracing::info!("Replaying DidOpen for {}", uri.as_str());
                     let _ = self
-                        .handle_did_open_sequential(uri, text, language_id, pending)
+                        .handle_did_open_sequential(uri, text, language_id, version, pending)
                         .await;
                 }
                 LspCommand::DidChange {
                     uri,
                     content_changes,
+                    version,
                 } => {
                     tracing::info!("Replaying DidChange for {}", uri.as_str());
                     let _ = self
-                        .handle_did_change_sequential(uri, content_changes, pending)
+                        .handle_did_change_sequential(uri, content_changes, version, pending)
                         .await;
                 }
                 LspCommand::DidSave { uri, text } => {
                     tracing::info!("Replaying DidSave for {}", uri.as_str());
                     let _ = self.handle_did_save(uri, text).await;
                 }
+                LspCommand::DidClose { uri } => {
+                    tracing::info!("Replaying DidClose for {}", uri.as_str());
+                    let _ = self.handle_did_close(uri).await;
+                }
                 _ => {}
             }
         }
@@ -571,6 +579,7 @@ impl LspState {
         uri: Uri,
         text: String,
         language_id: String,
+        version: i32,
         _pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
     ) -> Result<(), String> {
         tracing::debug!("LSP: did_open for {}", uri.as_str());
@@ -579,14 +588,11 @@ impl LspState {
             text_document: TextDocumentItem {
                 uri: uri.clone(),
                 language_id,
-                version: 0,
+                version,
                 text,
             },
         };
 
-        self.document_versions
-            .insert(PathBuf::from(uri.path().as_str()), 0);
-
         self.send_notification::<DidOpenTextDocument>(params).await
     }
 
@@ -595,18 +601,15 @@ impl LspState {
         &mut self,
         uri: Uri,
         content_changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
         _pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
     ) -> Result<(), String> {
         tracing::debug!("LSP: did_change for {}", uri.as_str());
 
-        let path = PathBuf::from(uri.path().as_str());
-        let version = self.document_versions.entry(path).or_insert(0);
-        *version += 1;
-
         let params = DidChangeTextDocumentParams {
             text_document: VersionedTextDocumentIdentifier {
                 uri: uri.clone(),
-                version: *version as i32,
+                version,
             },
             content_changes,
         };
@@ -627,6 +630,17 @@ impl LspState {
         self.send_notification::<DidSaveTextDocument>(params).await
     }
 
+    /// Handle did_close command
+    async fn handle_did_close(&mut self, uri: Uri) -> Result<(), String> {
+        tracing::debug!("LSP: did_close for {}", uri.as_str());
+
+        let params = DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri },
+        };
+
+        self.send_notification::<DidCloseTextDocument>(params).await
+    }
+
     /// Handle completion request
     async fn handle_completion(
         &mut self,
@@ -1489,9 +1503,6 @@ struct LspTask {
     /// Server capabilities
     capabilities: Option<ServerCapabilities>,
 
-    /// Document versions
-    document_versions: HashMap<PathBuf, i64>,
-
     /// Whether initialized
     initialized: bool,
 
@@ -1511,6 +1522,7 @@ impl LspTask {
         language: String,
         async_tx: std_mpsc::Sender<AsyncMessage>,
         process_limits: &ProcessLimits,
+        project_env: Option<&ProjectEnv>,
     ) -> Result<Self, String> {
         tracing::info!("Spawning async LSP server: {} {:?}", command, args);
         tracing::info!("Process limits: {:?}", process_limits);
@@ -1521,6 +1533,9 @@ impl LspTask {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
+        if let Some(env) = project_env {
+            env.apply_to_command(&mut cmd);
+        }
 
         // Apply resource limits to the process
         process_limits
@@ -1582,7 +1597,6 @@ impl LspTask {
             next_id: 0,
             pending: HashMap::new(),
             capabilities: None,
-            document_versions: HashMap::new(),
             initialized: false,
             async_tx,
             language,
@@ -1642,7 +1656,6 @@ impl LspTask {
             stdin: self.stdin,
             next_id: self.next_id,
             capabilities: self.capabilities,
-            document_versions: self.document_versions,
             initialized: self.initialized,
             async_tx: self.async_tx.clone(),
             language: self.language.clone(),
@@ -1702,11 +1715,18 @@ impl LspTask {
                             uri,
                             text,
                             language_id,
+                            version,
                         } => {
                             if state.initialized {
                                 tracing::info!("Processing DidOpen for {}", uri.as_str());
                                 let _ = state
-                                    .handle_did_open_sequential(uri, text, language_id, &pending)
+                                    .handle_did_open_sequential(
+                                        uri,
+                                        text,
+                                        language_id,
+                                        version,
+                                        &pending,
+                                    )
                                     .await;
                             } else {
                                 tracing::debug!(
@@ -1717,17 +1737,24 @@ impl LspTask {
                                     uri,
                                     text,
                                     language_id,
+                                    version,
                                 });
                             }
                         }
                         LspCommand::DidChange {
                             uri,
                             content_changes,
+                            version,
                         } => {
                             if state.initialized {
                                 tracing::debug!("Processing DidChange for {}", uri.as_str());
                                 let _ = state
-                                    .handle_did_change_sequential(uri, content_changes, &pending)
+                                    .handle_did_change_sequential(
+                                        uri,
+                                        content_changes,
+                                        version,
+                                        &pending,
+                                    )
                                     .await;
                             } else {
                                 tracing::debug!(
@@ -1737,6 +1764,7 @@ impl LspTask {
                                 pending_commands.push(LspCommand::DidChange {
                                     uri,
                                     content_changes,
+                                    version,
                                 });
                             }
                         }
@@ -1752,6 +1780,18 @@ impl LspTask {
                                 pending_commands.push(LspCommand::DidSave { uri, text });
                             }
                         }
+                        LspCommand::DidClose { uri } => {
+                            if state.initialized {
+                                tracing::info!("Processing DidClose for {}", uri.as_str());
+                                let _ = state.handle_did_close(uri).await;
+                            } else {
+                                tracing::debug!(
+                                    "Queueing DidClose for {} until initialization completes",
+                                    uri.as_str()
+                                );
+                                pending_commands.push(LspCommand::DidClose { uri });
+                            }
+                        }
                         LspCommand::Completion {
                             request_id,
                             uri,
@@ -2097,6 +2137,7 @@ impl LspTask {
         uri: Uri,
         text: String,
         language_id: String,
+        version: i32,
         _pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
     ) -> Result<(), String> {
         tracing::debug!("LSP: did_open for {}", uri.as_str());
@@ -2105,14 +2146,11 @@ impl LspTask {
             text_document: TextDocumentItem {
                 uri: uri.clone(),
                 language_id,
-                version: 0,
+                version,
                 text,
             },
         };
 
-        self.document_versions
-            .insert(PathBuf::from(uri.path().as_str()), 0);
-
         self.send_notification::<DidOpenTextDocument>(params).await
     }
 
@@ -2121,18 +2159,15 @@ impl LspTask {
         &mut self,
         uri: Uri,
         content_changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
         _pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
     ) -> Result<(), String> {
         tracing::debug!("LSP: did_change for {}", uri.as_str());
 
-        let path = PathBuf::from(uri.path().as_str());
-        let version = self.document_versions.entry(path).or_insert(0);
-        *version += 1;
-
         let params = DidChangeTextDocumentParams {
             text_document: VersionedTextDocumentIdentifier {
                 uri: uri.clone(),
-                version: *version as i32,
+                version,
             },
             content_changes,
         };
@@ -2369,6 +2404,7 @@ impl LspTask {
                     // Send to main loop
                     let _ = self.async_tx.send(AsyncMessage::LspDiagnostics {
                         uri: params.uri.to_string(),
+                        version: params.version,
                         diagnostics: params.diagnostics,
                     });
                 }
@@ -2766,6 +2802,7 @@ async fn handle_notification_dispatch(
                 // Send to main loop
                 let _ = async_tx.send(AsyncMessage::LspDiagnostics {
                     uri: params.uri.to_string(),
+                    version: params.version,
                     diagnostics: params.diagnostics,
                 });
             }
@@ -2990,6 +3027,7 @@ impl LspHandle {
         language: String,
         async_bridge: &AsyncBridge,
         process_limits: ProcessLimits,
+        project_env: Option<Arc<ProjectEnv>>,
     ) -> Result<Self, String> {
         let (command_tx, command_rx) = mpsc::channel(100); // Buffer up to 100 commands
         let async_tx = async_bridge.sender();
@@ -3012,6 +3050,7 @@ impl LspHandle {
                 language_clone.clone(),
                 async_tx.clone(),
                 &process_limits,
+                project_env.as_deref(),
             )
             .await
             {
@@ -3122,28 +3161,41 @@ impl LspHandle {
     }
 
     /// Notify document opened
-    pub fn did_open(&self, uri: Uri, text: String, language_id: String) -> Result<(), String> {
+    ///
+    /// `version` is the buffer's revision, which the server echoes back in
+    /// diagnostics so stale ones can be told apart.
+    pub fn did_open(
+        &self,
+        uri: Uri,
+        text: String,
+        language_id: String,
+        version: i32,
+    ) -> Result<(), String> {
         // Send command to LspTask which will queue it if not initialized yet
         self.command_tx
             .try_send(LspCommand::DidOpen {
                 uri,
                 text,
                 language_id,
+                version,
             })
             .map_err(|_| "Failed to send did_open command".to_string())
     }
 
-    /// Notify document changed
+    /// Notify document changed (`version` is the buffer's revision after
+    /// the changes)
     pub fn did_change(
         &self,
         uri: Uri,
         content_changes: Vec<TextDocumentContentChangeEvent>,
+        version: i32,
     ) -> Result<(), String> {
         // Send command to LspTask which will queue it if not initialized yet
         self.command_tx
             .try_send(LspCommand::DidChange {
                 uri,
                 content_changes,
+                version,
             })
             .map_err(|_| "Failed to send did_change command".to_string())
     }
@@ -3155,6 +3207,13 @@ impl LspHandle {
             .map_err(|_| "Failed to send did_save command".to_string())
     }
 
+    /// Send didClose notification
+    pub fn did_close(&self, uri: Uri) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::DidClose { uri })
+            .map_err(|_| "Failed to send did_close command".to_string())
+    }
+
     /// Request completion at position
     pub fn completion(
         &self,
@@ -3569,6 +3628,7 @@ mod tests {
             "test".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         );
 
         // Should succeed in spawning
@@ -3595,6 +3655,7 @@ mod tests {
             "test".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         )
         .unwrap();
 
@@ -3603,6 +3664,7 @@ mod tests {
             "file:///test.rs".parse().unwrap(),
             "fn main() {}".to_string(),
             "rust".to_string(),
+            0,
         );
 
         // Should succeed (command is queued)
@@ -3621,6 +3683,7 @@ mod tests {
             "test".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         )
         .unwrap();
 
@@ -3635,6 +3698,7 @@ mod tests {
                 range_length: None,
                 text: "fn main() {}".to_string(),
             }],
+            1,
         );
 
         // Should succeed (command is queued)
@@ -3653,6 +3717,7 @@ mod tests {
             "test".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         )
         .unwrap();
 
@@ -3667,6 +3732,7 @@ mod tests {
                 range_length: None,
                 text: String::new(), // Empty string for deletion
             }],
+            2,
         );
 
         // Should succeed (command is queued)
@@ -3686,6 +3752,7 @@ mod tests {
             "test".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         );
 
         // Should succeed in creating handle (error happens asynchronously)
@@ -3723,6 +3790,7 @@ mod tests {
                     "test".to_string(),
                     &async_bridge,
                     ProcessLimits::unlimited(),
+                    None,
                 )
                 .unwrap()
             });
@@ -3792,6 +3860,7 @@ mod tests {
             "test".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         )
         .unwrap();
 
@@ -3841,6 +3910,7 @@ mod tests {
             "fake".to_string(),
             &async_bridge,
             ProcessLimits::unlimited(),
+            None,
         )
         .unwrap();
 
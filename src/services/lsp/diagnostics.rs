@@ -67,6 +67,7 @@ pub fn apply_diagnostics_to_state_cached(
     state: &mut EditorState,
     diagnostics: &[Diagnostic],
     theme: &crate::view::theme::Theme,
+    max_diagnostics: usize,
 ) {
     // Compute hash of incoming diagnostics
     let new_hash = compute_diagnostic_hash(diagnostics);
@@ -82,7 +83,7 @@ pub fn apply_diagnostics_to_state_cached(
     }
 
     // Diagnostics have changed, do the expensive update
-    apply_diagnostics_to_state(state, diagnostics, theme);
+    apply_diagnostics_to_state(state, diagnostics, theme, max_diagnostics);
 
     // Update cache
     if let Ok(mut cache) = DIAGNOSTIC_CACHE.lock() {
@@ -141,24 +142,58 @@ pub fn diagnostic_to_overlay(
     Some((start_byte..end_byte, face, priority))
 }
 
+/// Severity rank for keeping the most important diagnostics (lower first)
+fn severity_rank(diagnostic: &Diagnostic) -> u8 {
+    match diagnostic.severity {
+        Some(DiagnosticSeverity::ERROR) => 0,
+        Some(DiagnosticSeverity::WARNING) => 1,
+        Some(DiagnosticSeverity::INFORMATION) => 2,
+        Some(DiagnosticSeverity::HINT) => 3,
+        _ => 4,
+    }
+}
+
+/// The `max` most severe diagnostics, in their original order within each
+/// severity (all of them if there aren't more than `max`)
+fn most_severe(diagnostics: &[Diagnostic], max: usize) -> Vec<&Diagnostic> {
+    let mut kept: Vec<&Diagnostic> = diagnostics.iter().collect();
+    if kept.len() > max {
+        kept.sort_by_key(|diagnostic| severity_rank(diagnostic));
+        kept.truncate(max);
+    }
+    kept
+}
+
 /// Apply LSP diagnostics to editor state as overlays
 ///
 /// This function:
 /// 1. Clears all existing LSP diagnostic overlays (using namespace)
-/// 2. Adds overlays for all current diagnostics
+/// 2. Adds overlays for the current diagnostics, at most `max_diagnostics`
+///    (the most severe); the number left out is kept in `hidden_diagnostics`
 pub fn apply_diagnostics_to_state(
     state: &mut EditorState,
     diagnostics: &[Diagnostic],
     theme: &crate::view::theme::Theme,
+    max_diagnostics: usize,
 ) {
     let ns = lsp_diagnostic_namespace();
 
     // Clear all existing LSP diagnostic overlays using namespace
     state.overlays.clear_namespace(&ns, &mut state.marker_list);
 
-    // Add overlays for all current diagnostics
+    let shown = most_severe(diagnostics, max_diagnostics);
+    state.hidden_diagnostics = diagnostics.len() - shown.len();
+    if state.hidden_diagnostics > 0 {
+        tracing::debug!(
+            "Showing {} of {} diagnostics (limits.max_diagnostics)",
+            shown.len(),
+            diagnostics.len()
+        );
+    }
+
+    // Add overlays for the diagnostics that are shown
     let mut added_count = 0;
-    for diagnostic in diagnostics {
+    for diagnostic in shown {
         if let Some((range, face, priority)) =
             diagnostic_to_overlay(diagnostic, &state.buffer, theme)
         {
@@ -325,4 +360,32 @@ mod tests {
         assert_eq!(range.start, 3);
         assert_eq!(range.end, 8);
     }
+
+    #[test]
+    fn test_most_severe_keeps_errors_first() {
+        let diagnostic = |severity, message: &str| Diagnostic {
+            severity: Some(severity),
+            message: message.to_string(),
+            ..Diagnostic::default()
+        };
+        let diagnostics = vec![
+            diagnostic(DiagnosticSeverity::HINT, "hint"),
+            diagnostic(DiagnosticSeverity::ERROR, "first error"),
+            diagnostic(DiagnosticSeverity::WARNING, "warning"),
+            diagnostic(DiagnosticSeverity::ERROR, "second error"),
+        ];
+        let messages = |kept: Vec<&Diagnostic>| -> Vec<String> {
+            kept.iter().map(|d| d.message.clone()).collect()
+        };
+
+        assert_eq!(
+            messages(most_severe(&diagnostics, 3)),
+            vec!["first error", "second error", "warning"]
+        );
+        // Under the limit, nothing is reordered
+        assert_eq!(
+            messages(most_severe(&diagnostics, 4)),
+            vec!["hint", "first error", "warning", "second error"]
+        );
+    }
 }
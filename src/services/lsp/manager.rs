@@ -9,8 +9,10 @@
 use crate::services::async_bridge::AsyncBridge;
 use crate::services::lsp::async_handler::LspHandle;
 use crate::services::lsp::client::LspServerConfig;
+use crate::services::project_env::ProjectEnv;
 use lsp_types::Uri;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Result of attempting to spawn an LSP server
@@ -63,6 +65,9 @@ pub struct LspManager {
     /// Languages that have been explicitly disabled/stopped by the user
     /// These will not auto-restart until user manually restarts them
     disabled_languages: HashSet<String>,
+
+    /// Environment loaded from the project's `.envrc`, for spawned servers
+    project_env: Option<Arc<ProjectEnv>>,
 }
 
 impl LspManager {
@@ -79,9 +84,15 @@ impl LspManager {
             pending_restarts: HashMap::new(),
             allowed_languages: HashSet::new(),
             disabled_languages: HashSet::new(),
+            project_env: None,
         }
     }
 
+    /// Set the environment servers spawned from now on run with
+    pub fn set_project_env(&mut self, project_env: Option<Arc<ProjectEnv>>) {
+        self.project_env = project_env;
+    }
+
     /// Check if a language has been manually enabled (allowing spawn even if auto_start=false)
     pub fn is_language_allowed(&self, language: &str) -> bool {
         self.allowed_languages.contains(language)
@@ -192,6 +203,7 @@ impl LspManager {
             language.to_string(),
             async_bridge,
             config.process_limits.clone(),
+            self.project_env.clone(),
         ) {
             Ok(handle) => {
                 // Initialize the handle (non-blocking)
@@ -215,6 +227,11 @@ impl LspManager {
         }
     }
 
+    /// Get the handle of a running server, without spawning one
+    pub fn get_handle(&self, language: &str) -> Option<&LspHandle> {
+        self.handles.get(language)
+    }
+
     /// Handle a server crash by scheduling a restart with exponential backoff
     ///
     /// Returns a message describing the action taken (for UI notification)
@@ -3,11 +3,25 @@
 //! This module contains all code that deals with external processes,
 //! I/O, and async operations.
 
+pub mod accessibility;
 pub mod async_bridge;
+pub mod audit_log;
 pub mod clipboard;
+pub mod evaluation;
+pub mod file_filter;
+pub mod file_lock;
+pub mod file_preview;
 pub mod fs;
+pub mod git_status;
+pub mod latency;
 pub mod lsp;
 pub mod plugins;
 pub mod process_limits;
+pub mod project_env;
+pub mod project_scan;
+pub mod project_tasks;
 pub mod recovery;
+pub mod search_index;
 pub mod signal_handler;
+pub mod symbol_index;
+pub mod text_stats;
@@ -3,10 +3,15 @@
 //! This module provides a safe, controlled API for plugins (Lua, WASM, etc.)
 //! to interact with the editor without direct access to internal state.
 
+use crate::config::FuzzyMatcherKind;
 use crate::input::command_registry::CommandRegistry;
 use crate::input::commands::Command;
 use crate::model::event::{BufferId, SplitId};
+use crate::services::file_filter::FileFilter;
 use crate::services::plugins::hooks::{HookCallback, HookRegistry};
+use crate::services::project_env::ProjectEnv;
+use crate::services::project_scan::ProjectScan;
+use crate::services::search_index::SearchIndex;
 use crate::view::overlay::{OverlayHandle, OverlayNamespace};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -29,6 +34,93 @@ pub enum PluginResponse {
         request_id: u64,
         result: Result<Value, String>,
     },
+    /// Answer to a RequestPrompt (None if the prompt was cancelled)
+    PromptAnswered {
+        request_id: u64,
+        value: Option<String>,
+    },
+    /// Result of ApplyEdits: the number of edits applied, or why none were
+    EditsApplied {
+        request_id: u64,
+        result: Result<usize, String>,
+    },
+}
+
+impl PluginResponse {
+    /// ID of the request this response answers
+    pub fn request_id(&self) -> u64 {
+        match self {
+            PluginResponse::VirtualBufferCreated { request_id, .. }
+            | PluginResponse::LspRequest { request_id, .. }
+            | PluginResponse::PromptAnswered { request_id, .. }
+            | PluginResponse::EditsApplied { request_id, .. } => *request_id,
+        }
+    }
+}
+
+/// What a plugin asks the user through [`PluginCommand::RequestPrompt`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginPromptRequest {
+    /// Free text, starting from `default`
+    Input { title: String, default: String },
+    /// One of `items` (answered with the chosen item's value)
+    Select {
+        title: String,
+        items: Vec<crate::input::commands::Suggestion>,
+    },
+    /// Yes or no (answered with "yes" or "no")
+    Confirm { message: String },
+}
+
+/// Where a plugin panel is docked in the editor layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelPosition {
+    Left,
+    Right,
+    Bottom,
+}
+
+/// Text drawn by a plugin panel's render callback
+///
+/// Coordinates are relative to the panel's content area; text running past
+/// its edge is clipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelText {
+    pub x: u16,
+    pub y: u16,
+    pub text: String,
+    pub fg: Option<(u8, u8, u8)>,
+    pub bg: Option<(u8, u8, u8)>,
+    pub bold: bool,
+}
+
+/// A decoration placed by `SetDecorations` in a plugin's namespace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoration {
+    /// Styled byte range
+    Overlay {
+        range: Range<usize>,
+        color: (u8, u8, u8),
+        underline: bool,
+        bold: bool,
+        italic: bool,
+    },
+    /// Inline text before or after the character at `position`
+    VirtualText {
+        position: usize,
+        text: String,
+        color: (u8, u8, u8),
+        before: bool,
+    },
+    /// Full line above or below the line containing `position`
+    VirtualLine {
+        position: usize,
+        text: String,
+        fg_color: (u8, u8, u8),
+        bg_color: Option<(u8, u8, u8)>,
+        above: bool,
+        priority: i32,
+    },
 }
 
 /// Information about a cursor in the editor
@@ -51,6 +143,8 @@ pub struct BufferInfo {
     pub modified: bool,
     /// Length of buffer in bytes
     pub length: usize,
+    /// Revision of the text (see `model::edit`)
+    pub revision: u64,
 }
 
 /// Diff between current buffer content and last saved snapshot
@@ -140,6 +234,9 @@ pub struct ViewTransformPayload {
     pub tokens: Vec<ViewTokenWire>,
     /// Layout hints
     pub layout_hints: Option<LayoutHints>,
+    /// Buffer revision the tokens were computed from; a transform for an
+    /// older revision is dropped
+    pub revision: Option<u64>,
 }
 
 /// Snapshot of editor state for plugin queries
@@ -172,6 +269,16 @@ pub struct EditorStateSnapshot {
     pub clipboard: String,
     /// Editor's working directory (for file operations and spawning processes)
     pub working_dir: PathBuf,
+    /// Project include/exclude globs (for file finders and search)
+    pub file_filter: Arc<FileFilter>,
+    /// Trigram index of the project's files (None until it is built)
+    pub search_index: Option<Arc<RwLock<SearchIndex>>>,
+    /// The project's scanned files, None while they are being scanned
+    pub project_files: Option<Arc<ProjectScan>>,
+    /// Matcher picked by `editor.fuzzy_matcher` (for plugin pickers)
+    pub fuzzy_matcher: FuzzyMatcherKind,
+    /// Environment loaded from the project's `.envrc`, for spawned processes
+    pub project_env: Option<Arc<ProjectEnv>>,
 }
 
 impl EditorStateSnapshot {
@@ -189,6 +296,11 @@ impl EditorStateSnapshot {
             selected_text: None,
             clipboard: String::new(),
             working_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            file_filter: Arc::new(FileFilter::default()),
+            search_index: None,
+            project_files: None,
+            fuzzy_matcher: FuzzyMatcherKind::default(),
+            project_env: None,
         }
     }
 }
@@ -222,6 +334,15 @@ pub enum PluginCommand {
         range: Range<usize>,
     },
 
+    /// Apply edits made against an earlier revision of a buffer
+    /// (answered with PluginResponse::EditsApplied)
+    ApplyEdits {
+        buffer_id: BufferId,
+        transaction: crate::model::edit::EditTransaction,
+        policy: crate::model::edit::ConflictPolicy,
+        request_id: u64,
+    },
+
     /// Add an overlay to a buffer, returns handle via response channel
     AddOverlay {
         buffer_id: BufferId,
@@ -231,6 +352,9 @@ pub enum PluginCommand {
         underline: bool,
         bold: bool,
         italic: bool,
+        /// Buffer revision `range` refers to. The range is rebased over later
+        /// edits, and the overlay dropped if its text has changed since.
+        revision: Option<u64>,
     },
 
     /// Remove an overlay by its opaque handle
@@ -248,6 +372,13 @@ pub enum PluginCommand {
     /// Unregister a command by name
     UnregisterCommand { name: String },
 
+    /// Run `action` when a gutter column ("sign", "line_number" or
+    /// "separator") is clicked; None gives the column back to the config
+    SetGutterClickAction {
+        column: String,
+        action: Option<String>,
+    },
+
     /// Open a file in the editor (in background, without switching focus)
     OpenFileInBackground { path: PathBuf },
 
@@ -262,6 +393,17 @@ pub enum PluginCommand {
         callback_id: u64, // ID to look up callback in _spawn_callbacks Lua table
     },
 
+    /// Start a job whose output is streamed to the `job_output` hook
+    SpawnJob {
+        job_id: u64,
+        spec: crate::services::plugins::process::JobSpec,
+        /// Shown in the status bar while the job runs
+        title: String,
+    },
+
+    /// Kill a running job
+    KillJob { job_id: u64 },
+
     /// Set layout hints for a buffer/viewport
     SetLayoutHints {
         buffer_id: BufferId,
@@ -354,6 +496,23 @@ pub enum PluginCommand {
         namespace: String,
     },
 
+    /// Remove a namespace's overlays and virtual texts that lie in a byte range
+    ClearNamespaceInRange {
+        buffer_id: BufferId,
+        namespace: String,
+        range: Range<usize>,
+    },
+
+    /// Replace a namespace's decorations in one step
+    /// The old decorations in `range` (the whole buffer if None) are removed
+    /// before the new ones are added, so updates don't flicker.
+    SetDecorations {
+        buffer_id: BufferId,
+        namespace: String,
+        range: Option<Range<usize>>,
+        decorations: Vec<Decoration>,
+    },
+
     /// Refresh lines for a buffer (clear seen_lines cache to re-trigger lines_changed hook)
     RefreshLines { buffer_id: BufferId },
 
@@ -397,11 +556,24 @@ pub enum PluginCommand {
         column: Option<usize>, // 1-indexed, None = go to line start
     },
 
+    /// Open a file in a new split next to the active one
+    OpenFileInNewSplit { path: PathBuf, vertical: bool },
+
+    /// Open a file in another pane ("Open to the Right/Below")
+    OpenFileToSide {
+        path: PathBuf,
+        line: Option<usize>,   // 1-indexed, None = keep the cursor
+        column: Option<usize>, // 1-indexed, None = keep the cursor
+        below: bool,
+    },
+
     /// Start a prompt (minibuffer) with a custom type identifier
     /// This allows plugins to create interactive prompts
     StartPrompt {
         label: String,
         prompt_type: String, // e.g., "git-grep", "git-find-file"
+        /// Text the input starts with
+        initial_input: Option<String>,
     },
 
     /// Update the suggestions list for the current prompt
@@ -410,6 +582,36 @@ pub enum PluginCommand {
         suggestions: Vec<crate::input::commands::Suggestion>,
     },
 
+    /// Ask the user for a value in a prompt managed by the editor
+    /// (answered with PluginResponse::PromptAnswered)
+    RequestPrompt {
+        request: PluginPromptRequest,
+        request_id: u64,
+    },
+
+    /// Dock a plugin panel (drawn by the plugin on `panel_render` hooks)
+    RegisterPanel {
+        id: String,
+        title: String,
+        position: PanelPosition,
+        /// Width (left/right) or height (bottom) in cells, borders included
+        size: u16,
+        /// Mode whose keybindings apply while the panel is focused
+        mode: Option<String>,
+    },
+
+    /// Remove a plugin panel
+    UnregisterPanel { id: String },
+
+    /// Replace what a plugin panel shows
+    SetPanelContent { id: String, content: Vec<PanelText> },
+
+    /// Ask for a plugin panel to be drawn again (fires `panel_render`)
+    RefreshPanel { id: String },
+
+    /// Give a plugin panel the keyboard focus
+    FocusPanel { id: String },
+
     /// Add a menu item to an existing menu
     AddMenuItem {
         menu_label: String,
@@ -509,6 +711,18 @@ pub enum PluginCommand {
         read_only: bool,
     },
 
+    /// Bind keys in a single buffer, over its mode and the global keymap
+    SetBufferKeybindings {
+        buffer_id: BufferId,
+        bindings: Vec<(String, String)>, // (key_string, command_name)
+    },
+
+    /// Remove keys bound in a single buffer (all of them if `keys` is None)
+    RemoveBufferKeybindings {
+        buffer_id: BufferId,
+        keys: Option<Vec<String>>,
+    },
+
     /// Switch the current split to display a buffer
     ShowBuffer { buffer_id: BufferId },
 
@@ -583,6 +797,18 @@ pub enum PluginCommand {
     /// Delete the current selection in the active buffer
     /// This deletes all selected text across all cursors
     DeleteSelection,
+
+    /// Add (or replace) a text object for selection commands
+    RegisterTextObject {
+        name: String,
+        /// Regex whose match is the object; its first group is the inside
+        pattern: Option<String>,
+        /// Tree-sitter queries by language name, capturing `@around` and `@inside`
+        queries: HashMap<String, String>,
+    },
+
+    /// Select a text object around each cursor in the active buffer
+    SelectTextObject { name: String, around: bool },
 }
 
 /// Plugin API context - provides safe access to editor functionality
@@ -686,6 +912,7 @@ impl PluginApi {
             underline,
             bold,
             italic,
+            revision: None,
         })
     }
 
@@ -758,7 +985,11 @@ impl PluginApi {
     /// Start a prompt (minibuffer) with a custom type identifier
     /// The prompt_type is used to filter hooks in plugin code
     pub fn start_prompt(&self, label: String, prompt_type: String) -> Result<(), String> {
-        self.send_command(PluginCommand::StartPrompt { label, prompt_type })
+        self.send_command(PluginCommand::StartPrompt {
+            label,
+            prompt_type,
+            initial_input: None,
+        })
     }
 
     /// Set the suggestions for the current prompt
@@ -886,6 +1117,30 @@ impl PluginApi {
         })
     }
 
+    /// Bind keys in a single buffer
+    ///
+    /// These bindings take precedence over the buffer's mode and are removed
+    /// when the buffer closes.
+    pub fn set_buffer_keybindings(
+        &self,
+        buffer_id: BufferId,
+        bindings: Vec<(String, String)>,
+    ) -> Result<(), String> {
+        self.send_command(PluginCommand::SetBufferKeybindings {
+            buffer_id,
+            bindings,
+        })
+    }
+
+    /// Remove keys bound in a single buffer (all of them if `keys` is None)
+    pub fn remove_buffer_keybindings(
+        &self,
+        buffer_id: BufferId,
+        keys: Option<Vec<String>>,
+    ) -> Result<(), String> {
+        self.send_command(PluginCommand::RemoveBufferKeybindings { buffer_id, keys })
+    }
+
     /// Switch the current split to display a buffer
     pub fn show_buffer(&self, buffer_id: BufferId) -> Result<(), String> {
         self.send_command(PluginCommand::ShowBuffer { buffer_id })
@@ -1044,6 +1299,7 @@ mod tests {
                 underline,
                 bold,
                 italic,
+                revision,
             } => {
                 assert_eq!(buffer_id.0, 1);
                 assert_eq!(namespace.as_ref().map(|n| n.as_str()), Some("test-overlay"));
@@ -1052,6 +1308,7 @@ mod tests {
                 assert!(underline);
                 assert!(!bold);
                 assert!(!italic);
+                assert_eq!(revision, None);
             }
             _ => panic!("Wrong command type"),
         }
@@ -1112,6 +1369,7 @@ mod tests {
                 path: Some(std::path::PathBuf::from("/test/file.txt")),
                 modified: true,
                 length: 100,
+                revision: 0,
             };
             snapshot.buffers.insert(BufferId(1), buffer_info);
         }
@@ -1151,6 +1409,7 @@ mod tests {
                     path: Some(std::path::PathBuf::from("/file1.txt")),
                     modified: false,
                     length: 50,
+                    revision: 0,
                 },
             );
             snapshot.buffers.insert(
@@ -1160,6 +1419,7 @@ mod tests {
                     path: Some(std::path::PathBuf::from("/file2.txt")),
                     modified: true,
                     length: 100,
+                    revision: 0,
                 },
             );
             snapshot.buffers.insert(
@@ -1169,6 +1429,7 @@ mod tests {
                     path: None,
                     modified: false,
                     length: 0,
+                    revision: 0,
                 },
             );
         }
@@ -7,6 +7,7 @@ use anyhow::{anyhow, Result};
 
 use crate::input::keybindings::Action;
 use crate::model::event::{BufferId, CursorId, SplitId};
+use crate::model::text_change::TextChange;
 use crate::services::plugins::api::{ViewTokenWire, ViewTokenWireKind};
 use std::collections::HashMap;
 use std::ops::Range;
@@ -30,6 +31,13 @@ pub enum HookArgs {
     /// A buffer was closed
     BufferClosed { buffer_id: BufferId },
 
+    /// A buffer was bound to a new file path (e.g., by "Save As")
+    BufferRenamed {
+        buffer_id: BufferId,
+        old_path: Option<PathBuf>,
+        new_path: PathBuf,
+    },
+
     /// Before text is inserted
     BeforeInsert {
         buffer_id: BufferId,
@@ -77,6 +85,13 @@ pub enum HookArgs {
         lines_removed: usize,
     },
 
+    /// Text of a buffer changed, with every change since the last notification
+    /// (in order; positions of each change are in the text before it)
+    BufferChanged {
+        buffer_id: BufferId,
+        changes: Vec<TextChange>,
+    },
+
     /// Cursor moved to a new position
     CursorMoved {
         buffer_id: BufferId,
@@ -125,6 +140,8 @@ pub enum HookArgs {
     /// Plugins should use this instead of RenderLine for better performance
     LinesChanged {
         buffer_id: BufferId,
+        /// Buffer revision the lines were read at
+        revision: u64,
         lines: Vec<LineInfo>,
     },
 
@@ -147,6 +164,15 @@ pub enum HookArgs {
         selected_index: usize,
     },
 
+    /// A secondary action of the selected suggestion was run with its key
+    PromptAction {
+        prompt_type: String,
+        input: String,
+        selected_index: usize,
+        /// The action's id
+        action: String,
+    },
+
     /// Request keyboard shortcuts data (key, action) for the help buffer
     KeyboardShortcuts { bindings: Vec<(String, String)> },
 
@@ -158,6 +184,14 @@ pub enum HookArgs {
         locations: Vec<LspLocation>,
     },
 
+    /// Search matches sent to the quickfix list (via the search_to_quickfix action)
+    SearchMatches {
+        /// The search query
+        query: String,
+        /// Every match of the query in the searched buffer
+        matches: Vec<SearchMatchLocation>,
+    },
+
     /// View transform request - core pushes base tokens to plugins for transformation
     /// Plugins receive the tokenized viewport content and can transform it
     /// (e.g., converting newlines to soft breaks for markdown compose mode).
@@ -165,6 +199,8 @@ pub enum HookArgs {
     ViewTransformRequest {
         buffer_id: BufferId,
         split_id: SplitId,
+        /// Buffer revision the tokens were produced from
+        revision: u64,
         /// Byte offset of the viewport start
         viewport_start: usize,
         /// Byte offset of the viewport end
@@ -190,6 +226,39 @@ pub enum HookArgs {
         content_y: u16,
     },
 
+    /// A plugin panel needs drawing (new, resized, refreshed or focus changed)
+    PanelRender {
+        panel_id: String,
+        /// Size of the panel's content area in cells
+        width: u16,
+        height: u16,
+        focused: bool,
+    },
+
+    /// A line of output from a plugin job
+    JobOutput {
+        job_id: u64,
+        /// "stdout" or "stderr"
+        stream: String,
+        line: String,
+    },
+
+    /// A plugin job exited, was killed or failed to start
+    JobExit {
+        job_id: u64,
+        exit_code: i32,
+        killed: bool,
+    },
+
+    /// A gutter column was clicked (after the cursor moved to the clicked line)
+    GutterClick {
+        buffer_id: BufferId,
+        /// Clicked line (0-based)
+        line: usize,
+        /// "sign", "line_number" or "separator"
+        column: String,
+    },
+
     /// Mouse move/hover event - fired when mouse moves in the editor viewport
     /// Plugins can use this to implement hover effects
     MouseMove {
@@ -228,6 +297,19 @@ pub struct LspLocation {
     pub column: u32,
 }
 
+/// Location of a single search match for the quickfix list
+#[derive(Debug, Clone)]
+pub struct SearchMatchLocation {
+    /// File path
+    pub file: String,
+    /// Line number (1-based)
+    pub line: usize,
+    /// Column number (1-based, in bytes)
+    pub column: usize,
+    /// Text of the line containing the match (without the trailing newline)
+    pub line_text: String,
+}
+
 /// Type for hook callbacks
 /// Returns `true` to continue execution, `false` to cancel the operation
 pub type HookCallback = Box<dyn Fn(&HookArgs) -> bool + Send + Sync>;
@@ -361,6 +443,17 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
         HookArgs::BufferClosed { buffer_id } => {
             serde_json::json!({ "buffer_id": buffer_id.0 })
         }
+        HookArgs::BufferRenamed {
+            buffer_id,
+            old_path,
+            new_path,
+        } => {
+            serde_json::json!({
+                "buffer_id": buffer_id.0,
+                "old_path": old_path.as_ref().map(|p| p.to_string_lossy()),
+                "new_path": new_path.to_string_lossy(),
+            })
+        }
         HookArgs::CursorMoved {
             buffer_id,
             cursor_id,
@@ -435,6 +528,29 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "lines_removed": lines_removed,
             })
         }
+        HookArgs::BufferChanged { buffer_id, changes } => {
+            let changes: Vec<_> = changes
+                .iter()
+                .map(|change| {
+                    serde_json::json!({
+                        "revision": change.revision,
+                        "start": change.range.start,
+                        "end": change.range.end,
+                        "start_line": change.start.line,
+                        "start_character": change.start.character,
+                        "end_line": change.end.line,
+                        "end_character": change.end.character,
+                        "old_text": change.old_text,
+                        "new_text": change.new_text,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "buffer_id": buffer_id.0,
+                "revision": changes.last().map(|change| change["revision"].clone()),
+                "changes": changes,
+            })
+        }
         HookArgs::BeforeFileOpen { path } => {
             serde_json::json!({ "path": path.to_string_lossy() })
         }
@@ -500,6 +616,19 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "selected_index": selected_index,
             })
         }
+        HookArgs::PromptAction {
+            prompt_type,
+            input,
+            selected_index,
+            action,
+        } => {
+            serde_json::json!({
+                "prompt_type": prompt_type,
+                "input": input,
+                "selected_index": selected_index,
+                "action": action,
+            })
+        }
         HookArgs::KeyboardShortcuts { bindings } => {
             let entries: Vec<serde_json::Value> = bindings
                 .iter()
@@ -520,7 +649,25 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 .collect();
             serde_json::json!({ "symbol": symbol, "locations": locs })
         }
-        HookArgs::LinesChanged { buffer_id, lines } => {
+        HookArgs::SearchMatches { query, matches } => {
+            let matches_json: Vec<serde_json::Value> = matches
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "file": m.file,
+                        "line": m.line,
+                        "column": m.column,
+                        "line_text": m.line_text,
+                    })
+                })
+                .collect();
+            serde_json::json!({ "query": query, "matches": matches_json })
+        }
+        HookArgs::LinesChanged {
+            buffer_id,
+            revision,
+            lines,
+        } => {
             let lines_json: Vec<serde_json::Value> = lines
                 .iter()
                 .map(|line| {
@@ -534,12 +681,14 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 .collect();
             serde_json::json!({
                 "buffer_id": buffer_id.0,
+                "revision": revision,
                 "lines": lines_json,
             })
         }
         HookArgs::ViewTransformRequest {
             buffer_id,
             split_id,
+            revision,
             viewport_start,
             viewport_end,
             tokens,
@@ -563,6 +712,7 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
             serde_json::json!({
                 "buffer_id": buffer_id.0,
                 "split_id": split_id.0,
+                "revision": revision,
                 "viewport_start": viewport_start,
                 "viewport_end": viewport_end,
                 "tokens": tokens_json,
@@ -585,6 +735,52 @@ pub fn hook_args_to_json(args: &HookArgs) -> Result<String> {
                 "content_y": content_y,
             })
         }
+        HookArgs::PanelRender {
+            panel_id,
+            width,
+            height,
+            focused,
+        } => {
+            serde_json::json!({
+                "panel_id": panel_id,
+                "width": width,
+                "height": height,
+                "focused": focused,
+            })
+        }
+        HookArgs::JobOutput {
+            job_id,
+            stream,
+            line,
+        } => {
+            serde_json::json!({
+                "job_id": job_id,
+                "stream": stream,
+                "line": line,
+            })
+        }
+        HookArgs::JobExit {
+            job_id,
+            exit_code,
+            killed,
+        } => {
+            serde_json::json!({
+                "job_id": job_id,
+                "exit_code": exit_code,
+                "killed": killed,
+            })
+        }
+        HookArgs::GutterClick {
+            buffer_id,
+            line,
+            column,
+        } => {
+            serde_json::json!({
+                "buffer_id": buffer_id.0,
+                "line": line,
+                "column": column,
+            })
+        }
         HookArgs::MouseMove {
             column,
             row,
@@ -713,6 +909,11 @@ mod tests {
             HookArgs::BufferClosed {
                 buffer_id: BufferId(1),
             },
+            HookArgs::BufferRenamed {
+                buffer_id: BufferId(1),
+                old_path: None,
+                new_path: PathBuf::from("/test.txt"),
+            },
             HookArgs::BeforeInsert {
                 buffer_id: BufferId(1),
                 position: 0,
@@ -742,6 +943,10 @@ mod tests {
                 end_line: 0,
                 lines_removed: 0,
             },
+            HookArgs::BufferChanged {
+                buffer_id: BufferId(1),
+                changes: vec![],
+            },
             HookArgs::CursorMoved {
                 buffer_id: BufferId(1),
                 cursor_id: CursorId(0),
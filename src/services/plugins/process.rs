@@ -2,12 +2,132 @@
 //!
 //! This module enables plugins to spawn external processes asynchronously,
 //! capturing stdout/stderr and notifying via callbacks when complete.
+//! Jobs (see [`run_plugin_job`]) stream their output line by line instead.
 
 use crate::services::async_bridge::AsyncMessage;
+use crate::services::project_env::ProjectEnv;
 use std::process::Stdio;
-use std::sync::mpsc;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::sync::{mpsc, Arc};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
+use tokio::sync::oneshot;
+
+/// Output stream a job line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStream {
+    Stdout,
+    Stderr,
+}
+
+impl JobStream {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStream::Stdout => "stdout",
+            JobStream::Stderr => "stderr",
+        }
+    }
+}
+
+/// How to start a plugin job
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JobSpec {
+    pub command: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    /// Variables added to the editor's environment
+    pub env: Vec<(String, String)>,
+}
+
+/// Run a plugin job, streaming its output
+///
+/// Each line of stdout and stderr is sent as a `PluginJobOutput` as soon as
+/// it is read, and a `PluginJobExited` follows once the process is done.
+/// Sending on (or dropping) `cancel` kills the process. The job's own `env`
+/// is set over the project's `.envrc` environment.
+pub async fn run_plugin_job(
+    job_id: u64,
+    spec: JobSpec,
+    project_env: Option<Arc<ProjectEnv>>,
+    sender: mpsc::Sender<AsyncMessage>,
+    cancel: oneshot::Receiver<()>,
+) {
+    let mut cmd = Command::new(&spec.command);
+    cmd.args(&spec.args);
+    if let Some(env) = project_env {
+        env.apply_to_command(&mut cmd);
+    }
+    cmd.envs(spec.env.iter().map(|(k, v)| (k, v)));
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.kill_on_drop(true);
+    if let Some(ref dir) = spec.cwd {
+        cmd.current_dir(dir);
+    }
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = sender.send(AsyncMessage::PluginJobOutput {
+                job_id,
+                stream: JobStream::Stderr,
+                line: format!("Failed to spawn process: {}", e),
+            });
+            let _ = sender.send(AsyncMessage::PluginJobExited {
+                job_id,
+                exit_code: -1,
+                killed: false,
+            });
+            return;
+        }
+    };
+
+    let stdout = stream_lines(job_id, JobStream::Stdout, child.stdout.take(), &sender);
+    let stderr = stream_lines(job_id, JobStream::Stderr, child.stderr.take(), &sender);
+    let output = async { tokio::join!(stdout, stderr) };
+
+    // Some(killed) if cancelled before the output ended
+    let cancelled = tokio::select! {
+        _ = output => None,
+        result = cancel => Some(result.is_ok()),
+    };
+    let (exit_code, killed) = match cancelled {
+        Some(killed) => {
+            let _ = child.kill().await;
+            (-1, killed)
+        }
+        None => match child.wait().await {
+            Ok(status) => (status.code().unwrap_or(-1), false),
+            Err(_) => (-1, false),
+        },
+    };
+
+    let _ = sender.send(AsyncMessage::PluginJobExited {
+        job_id,
+        exit_code,
+        killed,
+    });
+}
+
+/// Send each line read from `reader` as job output
+async fn stream_lines<R: AsyncRead + Unpin>(
+    job_id: u64,
+    stream: JobStream,
+    reader: Option<R>,
+    sender: &mpsc::Sender<AsyncMessage>,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let _ = sender.send(AsyncMessage::PluginJobOutput {
+            job_id,
+            stream,
+            line,
+        });
+    }
+}
 
 /// Spawn an external process for a plugin
 ///
@@ -236,4 +356,86 @@ mod tests {
             _ => panic!("Expected PluginProcessOutput"),
         }
     }
+
+    #[tokio::test]
+    async fn test_job_streams_lines() {
+        let (sender, receiver) = mpsc::channel();
+        let (_cancel_tx, cancel_rx) = oneshot::channel();
+
+        run_plugin_job(
+            5,
+            JobSpec {
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    "echo one; echo \"$GREETING\" >&2; echo two; exit 3".to_string(),
+                ],
+                cwd: None,
+                env: vec![("GREETING".to_string(), "hi".to_string())],
+            },
+            None,
+            sender,
+            cancel_rx,
+        )
+        .await;
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut exit = None;
+        for msg in receiver.try_iter() {
+            match msg {
+                AsyncMessage::PluginJobOutput {
+                    job_id,
+                    stream,
+                    line,
+                } => {
+                    assert_eq!(job_id, 5);
+                    match stream {
+                        JobStream::Stdout => stdout.push(line),
+                        JobStream::Stderr => stderr.push(line),
+                    }
+                }
+                AsyncMessage::PluginJobExited {
+                    exit_code, killed, ..
+                } => exit = Some((exit_code, killed)),
+                _ => panic!("Unexpected message"),
+            }
+        }
+        assert_eq!(stdout, vec!["one", "two"]);
+        assert_eq!(stderr, vec!["hi"]);
+        assert_eq!(exit, Some((3, false)));
+    }
+
+    #[tokio::test]
+    async fn test_job_can_be_killed() {
+        let (sender, receiver) = mpsc::channel();
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        cancel_tx.send(()).unwrap();
+
+        run_plugin_job(
+            6,
+            JobSpec {
+                command: "sleep".to_string(),
+                args: vec!["30".to_string()],
+                ..Default::default()
+            },
+            None,
+            sender,
+            cancel_rx,
+        )
+        .await;
+
+        match receiver.recv().unwrap() {
+            AsyncMessage::PluginJobExited {
+                job_id,
+                exit_code,
+                killed,
+            } => {
+                assert_eq!(job_id, 6);
+                assert_eq!(exit_code, -1);
+                assert!(killed);
+            }
+            _ => panic!("Expected PluginJobExited"),
+        }
+    }
 }
@@ -45,6 +45,7 @@ use crate::model::event::SplitId;
 use crate::services::plugins::api::{
     EditorStateSnapshot, LayoutHints, PluginCommand, ViewTokenWire,
 };
+use crate::services::project_env::ProjectEnv;
 use anyhow::{anyhow, Result};
 use deno_core::{
     error::ModuleLoaderError, extension, op2, FastString, JsRuntime, ModuleLoadOptions,
@@ -346,6 +347,104 @@ fn op_fresh_delete_range(state: &mut OpState, buffer_id: u32, start: u32, end: u
     false
 }
 
+/// Edit for applyEdits: replace bytes start..end with text
+#[derive(serde::Deserialize)]
+struct TsTextEdit {
+    /// Start byte offset (inclusive)
+    start: u32,
+    /// End byte offset (exclusive); equal to start to insert
+    end: u32,
+    /// Replacement text (empty to delete)
+    text: String,
+}
+
+/// Apply edits computed against an earlier revision of a buffer
+///
+/// Ranges refer to the text at `revision` (see getBufferInfo). They are moved
+/// over any changes made since, e.g. by the user typing while a formatter ran,
+/// and applied as one undoable change. If an edit overlaps a newer change,
+/// the promise is rejected and nothing is applied (with skip_conflicting,
+/// that edit is dropped instead). Resolves to the number of edits applied.
+/// @param buffer_id - Target buffer ID
+/// @param revision - Revision of the text the ranges refer to
+/// @param edits - Edits in any order (they must not overlap)
+/// @param skip_conflicting - Drop conflicting edits instead of rejecting all
+/// @example
+/// const revision = editor.getBufferInfo(bufferId).revision;
+/// const edits = await computeEdits(bufferId);
+/// await editor.applyEdits(bufferId, revision, edits);
+#[op2(async)]
+async fn op_fresh_apply_edits(
+    state: Rc<RefCell<OpState>>,
+    buffer_id: u32,
+    revision: u32,
+    #[serde] edits: Vec<TsTextEdit>,
+    skip_conflicting: bool,
+) -> Result<u32, JsErrorBox> {
+    use crate::model::edit::{ConflictPolicy, EditTransaction};
+
+    let mut transaction = EditTransaction::new(revision as u64);
+    for edit in edits {
+        transaction.replace(edit.start as usize..edit.end as usize, edit.text);
+    }
+    let policy = if skip_conflicting {
+        ConflictPolicy::SkipConflicting
+    } else {
+        ConflictPolicy::Reject
+    };
+
+    let receiver = {
+        let state = state.borrow();
+        let runtime_state = state
+            .try_borrow::<Rc<RefCell<TsRuntimeState>>>()
+            .ok_or_else(|| JsErrorBox::generic("Failed to get runtime state"))?;
+        let runtime_state = runtime_state.borrow();
+
+        let request_id = {
+            let mut id = runtime_state.next_request_id.borrow_mut();
+            let current = *id;
+            *id += 1;
+            current
+        };
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        {
+            let mut pending = runtime_state.pending_responses.lock().unwrap();
+            pending.insert(request_id, tx);
+        }
+
+        if runtime_state
+            .command_sender
+            .send(PluginCommand::ApplyEdits {
+                buffer_id: BufferId(buffer_id as usize),
+                transaction,
+                policy,
+                request_id,
+            })
+            .is_err()
+        {
+            let mut pending = runtime_state.pending_responses.lock().unwrap();
+            pending.remove(&request_id);
+            return Err(JsErrorBox::generic("Failed to send edits"));
+        }
+
+        rx
+    };
+
+    let response = receiver
+        .await
+        .map_err(|_| JsErrorBox::generic("Edit request cancelled"))?;
+
+    match response {
+        crate::services::plugins::api::PluginResponse::EditsApplied { result, .. } => result
+            .map(|applied| applied as u32)
+            .map_err(|e| JsErrorBox::generic(format!("Edits not applied: {}", e))),
+        _ => Err(JsErrorBox::generic(
+            "Unexpected plugin response for applyEdits",
+        )),
+    }
+}
+
 /// Add a colored highlight overlay to text without modifying content
 ///
 /// Overlays are visual decorations that persist until explicitly removed.
@@ -362,8 +461,11 @@ fn op_fresh_delete_range(state: &mut OpState, buffer_id: u32, start: u32, end: u
 /// @param underline - Add underline decoration
 /// @param bold - Use bold text
 /// @param italic - Use italic text
+/// @param revision - Buffer revision the offsets refer to (e.g. from lines_changed);
+///   they are mapped over later edits, and the overlay is dropped if its text changed
 /// @returns true if overlay was added
-#[op2(fast)]
+#[op2]
+#[allow(clippy::too_many_arguments)]
 fn op_fresh_add_overlay(
     state: &mut OpState,
     buffer_id: u32,
@@ -376,6 +478,7 @@ fn op_fresh_add_overlay(
     underline: bool,
     bold: bool,
     italic: bool,
+    #[serde] revision: Option<u64>,
 ) -> bool {
     if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
         let runtime_state = runtime_state.borrow();
@@ -396,6 +499,7 @@ fn op_fresh_add_overlay(
                 underline,
                 bold,
                 italic,
+                revision,
             });
         return result.is_ok();
     }
@@ -690,6 +794,195 @@ fn op_fresh_clear_virtual_text_namespace(
     false
 }
 
+/// Create a namespace for a plugin's overlays and virtual texts
+///
+/// The returned namespace is unique, so two plugins (or two features of one
+/// plugin) asking for the same name never clear each other's decorations.
+/// Pass it to addOverlay, addVirtualLine, setDecorations and the clear
+/// functions.
+/// @param name - Readable name included in the namespace (e.g. "eslint")
+/// @returns The namespace
+#[op2]
+#[string]
+fn op_fresh_create_namespace(#[string] name: String) -> String {
+    format!(
+        "{}.{}",
+        name,
+        crate::view::overlay::OverlayNamespace::new().as_str()
+    )
+}
+
+/// Clear a namespace's overlays and virtual texts in a byte range
+///
+/// Overlays overlapping the range and virtual texts anchored in it are
+/// removed; the namespace's decorations elsewhere are kept.
+/// @param buffer_id - The buffer ID
+/// @param namespace - The namespace to clear
+/// @param start - Start byte position (inclusive)
+/// @param end - End byte position (exclusive)
+/// @returns true if the request was sent successfully
+#[op2(fast)]
+fn op_fresh_clear_namespace_in_range(
+    state: &mut OpState,
+    buffer_id: u32,
+    #[string] namespace: String,
+    start: u32,
+    end: u32,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::ClearNamespaceInRange {
+                buffer_id: BufferId(buffer_id as usize),
+                namespace,
+                range: (start as usize)..(end as usize),
+            });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Decorations set in one call by setDecorations
+#[derive(serde::Deserialize)]
+struct TsDecorations {
+    /// Only replace the namespace's decorations from this byte position
+    start: Option<u32>,
+    /// Only replace the namespace's decorations up to this byte position
+    end: Option<u32>,
+    /// Styled byte ranges
+    overlays: Option<Vec<TsOverlayDecoration>>,
+    /// Inline texts
+    virtual_texts: Option<Vec<TsVirtualTextDecoration>>,
+    /// Full lines above or below source lines
+    virtual_lines: Option<Vec<TsVirtualLineDecoration>>,
+}
+
+/// Styled byte range
+#[derive(serde::Deserialize)]
+struct TsOverlayDecoration {
+    /// Start byte offset
+    start: u32,
+    /// End byte offset
+    end: u32,
+    /// Color as [r, g, b]
+    color: (u8, u8, u8),
+    /// Add underline decoration
+    underline: Option<bool>,
+    /// Use bold text
+    bold: Option<bool>,
+    /// Use italic text
+    italic: Option<bool>,
+}
+
+/// Inline text that isn't part of the buffer
+#[derive(serde::Deserialize)]
+struct TsVirtualTextDecoration {
+    /// Byte position of the character the text is attached to
+    position: u32,
+    /// Text to display
+    text: String,
+    /// Color as [r, g, b]
+    color: (u8, u8, u8),
+    /// Show before the character instead of after it
+    before: Option<bool>,
+}
+
+/// Full line shown above or below a source line
+#[derive(serde::Deserialize)]
+struct TsVirtualLineDecoration {
+    /// Byte position in the source line
+    position: u32,
+    /// Line content
+    text: String,
+    /// Foreground color as [r, g, b]
+    fg: (u8, u8, u8),
+    /// Background color as [r, g, b] (transparent if omitted)
+    bg: Option<(u8, u8, u8)>,
+    /// Show above the source line instead of below it
+    above: Option<bool>,
+    /// Ordering of lines at the same position (higher = later)
+    priority: Option<i32>,
+}
+
+/// Replace a namespace's decorations in one step
+///
+/// The namespace's overlays and virtual texts are removed (only those
+/// between `start` and `end` when given) and the new ones added in the same
+/// frame, so a linter or test runner can publish fresh results without
+/// flicker and without touching other namespaces.
+/// @param buffer_id - The buffer ID
+/// @param namespace - Namespace of the decorations (see createNamespace)
+/// @param decorations - The new decorations
+/// @returns true if the request was sent successfully
+/// @example
+/// const ns = editor.createNamespace("lint");
+/// editor.setDecorations(bufferId, ns, {
+///   overlays: [{ start: 10, end: 14, color: [255, 80, 80], underline: true }],
+///   virtual_texts: [{ position: 14, text: " unused variable", color: [150, 150, 150] }],
+/// });
+#[op2]
+fn op_fresh_set_decorations(
+    state: &mut OpState,
+    buffer_id: u32,
+    #[string] namespace: String,
+    #[serde] decorations: TsDecorations,
+) -> bool {
+    use crate::services::plugins::api::Decoration;
+
+    let range = match (decorations.start, decorations.end) {
+        (None, None) => None,
+        (start, end) => Some(start.unwrap_or(0) as usize..end.unwrap_or(u32::MAX) as usize),
+    };
+    let overlays = decorations
+        .overlays
+        .unwrap_or_default()
+        .into_iter()
+        .map(|o| Decoration::Overlay {
+            range: (o.start as usize)..(o.end as usize),
+            color: o.color,
+            underline: o.underline.unwrap_or(false),
+            bold: o.bold.unwrap_or(false),
+            italic: o.italic.unwrap_or(false),
+        });
+    let virtual_texts = decorations
+        .virtual_texts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| Decoration::VirtualText {
+            position: t.position as usize,
+            text: t.text,
+            color: t.color,
+            before: t.before.unwrap_or(false),
+        });
+    let virtual_lines = decorations
+        .virtual_lines
+        .unwrap_or_default()
+        .into_iter()
+        .map(|l| Decoration::VirtualLine {
+            position: l.position as usize,
+            text: l.text,
+            fg_color: l.fg,
+            bg_color: l.bg,
+            above: l.above.unwrap_or(false),
+            priority: l.priority.unwrap_or(0),
+        });
+
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::SetDecorations {
+                buffer_id: BufferId(buffer_id as usize),
+                namespace,
+                range,
+                decorations: overlays.chain(virtual_texts).chain(virtual_lines).collect(),
+            });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Force a refresh of line display for a buffer
 /// @param buffer_id - The buffer ID
 /// @returns true if refresh was triggered
@@ -777,6 +1070,8 @@ fn op_fresh_clear_line_indicators(
 /// @param tokens - Array of tokens with source offsets
 /// @param source_map - Array of source offsets (null for injected)
 /// @param layout_hints - Optional layout hints (compose width, column guides)
+/// @param revision - Buffer revision the tokens came from (from view_transform_request);
+///   the transform is dropped if the buffer has been edited since
 #[op2]
 fn op_fresh_submit_view_transform(
     state: &mut OpState,
@@ -786,6 +1081,7 @@ fn op_fresh_submit_view_transform(
     end: u32,
     #[serde] tokens: Vec<ViewTokenWire>,
     #[serde] layout_hints: Option<LayoutHints>,
+    #[serde] revision: Option<u64>,
 ) -> bool {
     if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
         let runtime_state = runtime_state.borrow();
@@ -812,6 +1108,7 @@ fn op_fresh_submit_view_transform(
                     range: start as usize..end as usize,
                     tokens,
                     layout_hints: Some(hints),
+                    revision,
                 },
             });
         return result.is_ok();
@@ -863,8 +1160,9 @@ fn op_fresh_insert_at_cursor(state: &mut OpState, #[string] text: String) -> boo
 /// @param description - Human-readable description
 /// @param action - JavaScript function name to call when command is triggered
 /// @param contexts - Comma-separated list of contexts (e.g., "normal,prompt")
+/// @param when - When clause the command is enabled under (e.g., "has_selection && !read_only"; empty for always)
 /// @param source - Plugin source name (empty string for builtin)
-/// @returns true if command was registered
+/// @returns true if command was registered (false if the when clause is invalid)
 #[op2(fast)]
 fn op_fresh_register_command(
     state: &mut OpState,
@@ -872,11 +1170,24 @@ fn op_fresh_register_command(
     #[string] description: String,
     #[string] action: String,
     #[string] contexts: String,
+    #[string] when: String,
     #[string] source: String,
 ) -> bool {
     if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
         let runtime_state = runtime_state.borrow();
 
+        let when = if when.trim().is_empty() {
+            None
+        } else {
+            match crate::input::when_clause::WhenClause::parse(&when) {
+                Ok(clause) => Some(clause),
+                Err(e) => {
+                    tracing::warn!("Invalid when clause for command '{}': {}", name, e);
+                    return false;
+                }
+            }
+        };
+
         // Parse contexts string (comma-separated, e.g., "normal,prompt,popup")
         let context_list: Vec<crate::input::keybindings::KeyContext> = if contexts.trim().is_empty()
         {
@@ -893,6 +1204,12 @@ fn op_fresh_register_command(
                         Some(crate::input::keybindings::KeyContext::FileExplorer)
                     }
                     "menu" => Some(crate::input::keybindings::KeyContext::Menu),
+                    "vimnormal" | "vim_normal" => {
+                        Some(crate::input::keybindings::KeyContext::VimNormal)
+                    }
+                    "vimvisual" | "vim_visual" => {
+                        Some(crate::input::keybindings::KeyContext::VimVisual)
+                    }
                     _ => None,
                 })
                 .collect()
@@ -911,6 +1228,8 @@ fn op_fresh_register_command(
             action: crate::input::keybindings::Action::PluginAction(action),
             contexts: context_list,
             source: command_source,
+            when,
+            argument: None,
         };
 
         let result = runtime_state
@@ -936,6 +1255,32 @@ fn op_fresh_unregister_command(state: &mut OpState, #[string] name: String) -> b
     false
 }
 
+/// Run an action when a gutter column is clicked, instead of the configured one
+///
+/// The cursor moves to the clicked line before the action runs. The
+/// "gutter_click" hook fires for every gutter click either way.
+/// @param column - "sign", "line_number" or "separator"
+/// @param action - Action name or plugin command to run (null to restore the default)
+/// @returns true if the request was sent successfully
+/// @example
+/// editor.registerCommand("Toggle Breakpoint", "Toggle a breakpoint", "toggle_breakpoint");
+/// editor.setGutterClickAction("sign", "toggle_breakpoint");
+#[op2]
+fn op_fresh_set_gutter_click_action(
+    state: &mut OpState,
+    #[string] column: String,
+    #[string] action: Option<String>,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::SetGutterClickAction { column, action });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Open a file in the editor, optionally at a specific location
 /// @param path - File path to open
 /// @param line - Line number to jump to (0 for no jump)
@@ -1050,6 +1395,63 @@ fn op_fresh_open_file_in_split(
     false
 }
 
+/// Open a file in a new split next to the focused one
+/// @param path - File path to open
+/// @param vertical - Split side by side (true) or one above the other (false)
+/// @returns true if file was opened
+#[op2(fast)]
+fn op_fresh_open_file_in_new_split(
+    state: &mut OpState,
+    #[string] path: String,
+    vertical: bool,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::OpenFileInNewSplit {
+                path: std::path::PathBuf::from(path),
+                vertical,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Open a file in another pane, as "Open to the Right/Below" does: in a new
+/// split if there is one pane, else in the other pane or one the user picks
+/// @param path - File path to open
+/// @param line - Line number to jump to (0 for no jump)
+/// @param column - Column number to jump to (0 for no jump)
+/// @param below - Split one above the other (true) or side by side (false)
+/// @returns true if the request was sent
+#[op2(fast)]
+fn op_fresh_open_file_to_side(
+    state: &mut OpState,
+    #[string] path: String,
+    line: u32,
+    column: u32,
+    below: bool,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::OpenFileToSide {
+                path: std::path::PathBuf::from(path),
+                line: if line == 0 { None } else { Some(line as usize) },
+                column: if column == 0 {
+                    None
+                } else {
+                    Some(column as usize)
+                },
+                below,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Result from spawnProcess
 #[derive(serde::Serialize)]
 struct SpawnResult {
@@ -1061,6 +1463,16 @@ struct SpawnResult {
     exit_code: i32,
 }
 
+/// Environment loaded from the project's `.envrc`, for the processes
+/// plugins start
+fn project_env(state: &Rc<RefCell<OpState>>) -> Option<Arc<ProjectEnv>> {
+    let op_state = state.borrow();
+    let runtime_state = op_state.try_borrow::<Rc<RefCell<TsRuntimeState>>>()?;
+    let runtime_state = runtime_state.borrow();
+    let snapshot = runtime_state.state_snapshot.read().ok()?;
+    snapshot.project_env.clone()
+}
+
 /// Run an external command and capture its output
 ///
 /// Waits for process to complete before returning. For long-running processes,
@@ -1077,6 +1489,7 @@ struct SpawnResult {
 #[op2(async)]
 #[serde]
 async fn op_fresh_spawn_process(
+    state: Rc<RefCell<OpState>>,
     #[string] command: String,
     #[serde] args: Vec<String>,
     #[string] cwd: Option<String>,
@@ -1095,6 +1508,9 @@ async fn op_fresh_spawn_process(
     // Build the command
     let mut cmd = Command::new(&command);
     cmd.args(&args);
+    if let Some(env) = project_env(&state) {
+        env.apply_to_command(&mut cmd);
+    }
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
@@ -1162,6 +1578,162 @@ async fn op_fresh_spawn_process(
     })
 }
 
+/// Line found by searchProject
+#[derive(serde::Serialize)]
+struct TsSearchMatch {
+    /// File path, relative to the working directory
+    file: String,
+    /// Line number (1-indexed)
+    line: usize,
+    /// Byte column of the match (1-indexed)
+    column: usize,
+    /// Text of the line
+    content: String,
+}
+
+/// Search the project's files for plain text using the search index
+///
+/// Only files containing every 3-character sequence of the query are read,
+/// plus files changed on disk since they were indexed, so results come back
+/// quickly even on large projects. Matching uses smart case, like `rg -S`.
+/// Returns null if the project isn't indexed (see the `editor.search_index`
+/// setting and "Rebuild Search Index") or the query has regex characters;
+/// run a grep tool then.
+/// @param query - Text to search for
+/// @param max_results - Stop after this many matching lines
+/// @param dir - Only search under this directory, relative to the working directory
+/// @example
+/// const matches = await editor.searchProject("TODO", 100);
+/// if (matches === null) {
+///   // Fall back to: editor.spawnProcess("rg", ["--column", "TODO"])
+/// }
+#[op2(async)]
+#[serde]
+async fn op_fresh_search_project(
+    state: Rc<RefCell<OpState>>,
+    #[string] query: String,
+    max_results: u32,
+    #[string] dir: Option<String>,
+) -> Result<Option<Vec<TsSearchMatch>>, JsErrorBox> {
+    use crate::services::search_index;
+
+    let (index, root) = {
+        let op_state = state.borrow();
+        let Some(runtime_state) = op_state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() else {
+            return Ok(None);
+        };
+        let runtime_state = runtime_state.borrow();
+        let Ok(snapshot) = runtime_state.state_snapshot.read() else {
+            return Ok(None);
+        };
+        (snapshot.search_index.clone(), snapshot.working_dir.clone())
+    };
+    let Some(index) = index.filter(|_| search_index::is_plain_text(&query)) else {
+        return Ok(None);
+    };
+
+    // The index is only locked while picking the files to read
+    let matches = tokio::task::spawn_blocking(move || {
+        let files = match index.read() {
+            Ok(index) => {
+                index.files_to_search(&root, &query, dir.as_deref().map(std::path::Path::new))
+            }
+            Err(_) => return Vec::new(),
+        };
+        search_index::search_files(&root, &files, &query, max_results as usize)
+    })
+    .await
+    .map_err(|e| JsErrorBox::generic(e.to_string()))?;
+
+    Ok(Some(
+        matches
+            .into_iter()
+            .map(|m| TsSearchMatch {
+                file: m.path.to_string_lossy().into_owned(),
+                line: m.line,
+                column: m.column,
+                content: m.content,
+            })
+            .collect(),
+    ))
+}
+
+/// List the project's files, relative to the working directory
+///
+/// The list comes from the editor's scan of the project, which leaves out
+/// hidden files and files ignored by .gitignore, .ignore or the project's
+/// file globs, and is scanned again when files are added or removed.
+/// Returns null while the project is being scanned.
+/// @example
+/// const files = editor.listProjectFiles();
+/// if (files === null) {
+///   // Fall back to: editor.spawnProcess("git", ["ls-files"])
+/// }
+#[op2]
+#[serde]
+fn op_fresh_list_project_files(state: &mut OpState) -> Option<Vec<String>> {
+    let runtime_state = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>()?;
+    let runtime_state = runtime_state.borrow();
+    let snapshot = runtime_state.state_snapshot.read().ok()?;
+    let scan = snapshot.project_files.as_ref()?;
+    Some(
+        scan.files
+            .iter()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect(),
+    )
+}
+
+/// Item matched by fuzzyFilter
+#[derive(serde::Serialize)]
+struct TsFuzzyMatch {
+    /// Index of the item in the list given to fuzzyFilter
+    index: usize,
+    /// Match quality (higher is better)
+    score: i32,
+    /// Character indices of the item that matched the query
+    positions: Vec<usize>,
+}
+
+/// Fuzzy match a query against a list of strings
+///
+/// Uses the matcher picked by the `editor.fuzzy_matcher` setting, so
+/// plugin pickers rank like the command palette. Items that don't match are
+/// left out; the others are sorted best first.
+/// @param query - Text typed by the user
+/// @param items - Strings to match
+/// @param max_results - Most matches returned
+/// @example
+/// const best = editor.fuzzyFilter("main", files, 100).map(m => files[m.index]);
+#[op2]
+#[serde]
+fn op_fresh_fuzzy_filter(
+    state: &mut OpState,
+    #[string] query: String,
+    #[serde] items: Vec<String>,
+    max_results: u32,
+) -> Vec<TsFuzzyMatch> {
+    let kind = state
+        .try_borrow::<Rc<RefCell<TsRuntimeState>>>()
+        .and_then(|runtime_state| {
+            let runtime_state = runtime_state.borrow();
+            let snapshot = runtime_state.state_snapshot.read().ok()?;
+            Some(snapshot.fuzzy_matcher)
+        })
+        .unwrap_or_default();
+    let mut matches =
+        crate::input::fuzzy::fuzzy_filter(kind.matcher(), &query, &items, |s| s.as_str());
+    matches.truncate(max_results as usize);
+    matches
+        .into_iter()
+        .map(|(index, m)| TsFuzzyMatch {
+            index,
+            score: m.score,
+            positions: m.match_positions,
+        })
+        .collect()
+}
+
 /// Result from spawnBackgroundProcess - just the process ID
 #[derive(serde::Serialize)]
 struct BackgroundProcessResult {
@@ -1198,6 +1770,9 @@ async fn op_fresh_spawn_background_process(
     // Build the command
     let mut cmd = Command::new(&command);
     cmd.args(&args);
+    if let Some(env) = project_env(&state) {
+        env.apply_to_command(&mut cmd);
+    }
     // For background processes, we don't capture output (it runs independently)
     cmd.stdout(Stdio::null());
     cmd.stderr(Stdio::null());
@@ -1271,6 +1846,83 @@ async fn op_fresh_kill_process(
     }
 }
 
+/// Options for spawnJob
+#[derive(serde::Deserialize)]
+struct TsJobOptions {
+    /// Working directory; null uses editor's cwd
+    cwd: Option<String>,
+    /// Environment variables to add for the process
+    env: Option<HashMap<String, String>>,
+    /// Shown in the status bar while the job runs (defaults to the command)
+    title: Option<String>,
+}
+
+/// Start a job whose output is streamed to plugins
+///
+/// Unlike spawnProcess, output arrives line by line as the job runs: each line
+/// fires the "job_output" hook with `{ job_id, stream, line }` (stream is
+/// "stdout" or "stderr"), and "job_exit" follows with
+/// `{ job_id, exit_code, killed }`. Running jobs are shown in the status bar.
+/// @param command - Program name (searched in PATH) or absolute path
+/// @param args - Command arguments (each array element is one argument)
+/// @param options - Working directory, environment and status bar title
+/// @returns Job ID, passed to the hooks and to killJob
+/// @example
+/// globalThis.onJobOutput = (e) => { if (e.job_id === job) log.push(e.line); };
+/// editor.on("job_output", "onJobOutput");
+/// const job = editor.spawnJob("cargo", ["test"], { env: { RUST_BACKTRACE: "1" }, title: "cargo test" });
+#[op2]
+fn op_fresh_spawn_job(
+    state: &mut OpState,
+    #[string] command: String,
+    #[serde] args: Vec<String>,
+    #[serde] options: TsJobOptions,
+) -> u32 {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let job_id = {
+            let mut id = runtime_state.next_process_id.borrow_mut();
+            let job_id = *id;
+            *id += 1;
+            job_id
+        };
+        let mut env: Vec<(String, String)> = options.env.unwrap_or_default().into_iter().collect();
+        env.sort();
+        let title = options.title.unwrap_or_else(|| command.clone());
+        let result = runtime_state.command_sender.send(PluginCommand::SpawnJob {
+            job_id,
+            spec: crate::services::plugins::process::JobSpec {
+                command,
+                args,
+                cwd: options.cwd,
+                env,
+            },
+            title,
+        });
+        if result.is_ok() {
+            return job_id as u32;
+        }
+    }
+    0
+}
+
+/// Kill a job started with spawnJob
+///
+/// The "job_exit" hook still fires, with `killed` set.
+/// @param job_id - ID returned from spawnJob
+/// @returns true if the request was sent successfully
+#[op2(fast)]
+fn op_fresh_kill_job(state: &mut OpState, job_id: u32) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state.command_sender.send(PluginCommand::KillJob {
+            job_id: job_id as u64,
+        });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Check if a background process is still running
 ///
 /// @param process_id - ID returned from spawnBackgroundProcess
@@ -1413,6 +2065,8 @@ struct TsBufferInfo {
     modified: bool,
     /// Buffer length in bytes
     length: u32,
+    /// Revision of the text, increased by every change (for applyEdits)
+    revision: u32,
 }
 
 /// Diff vs last save for a buffer
@@ -1473,6 +2127,7 @@ fn op_fresh_get_buffer_info(state: &mut OpState, buffer_id: u32) -> Option<TsBuf
                         .unwrap_or_default(),
                     modified: info.modified,
                     length: info.length as u32,
+                    revision: info.revision as u32,
                 });
             }
         };
@@ -1533,6 +2188,7 @@ fn op_fresh_list_buffers(state: &mut OpState) -> Vec<TsBufferInfo> {
                         .unwrap_or_default(),
                     modified: info.modified,
                     length: info.length as u32,
+                    revision: info.revision as u32,
                 })
                 .collect();
         };
@@ -1620,23 +2276,55 @@ struct TsSuggestion {
     disabled: Option<bool>,
     /// Optional keybinding hint
     keybinding: Option<String>,
+    /// Actions run with their own keys while the suggestion is selected
+    actions: Option<Vec<TsSuggestionAction>>,
+}
+
+/// Secondary action of a prompt suggestion, reported by the prompt_action hook
+#[derive(serde::Deserialize)]
+struct TsSuggestionAction {
+    /// Key that runs it (e.g. "ctrl+enter")
+    key: String,
+    /// What it does, shown below the suggestions
+    label: String,
+    /// Passed to the prompt_action hook as `action`
+    id: String,
+    /// Leave the prompt open after running it
+    keep_open: Option<bool>,
+}
+
+impl TsSuggestionAction {
+    fn into_action(self) -> crate::input::commands::SuggestionAction {
+        crate::input::commands::SuggestionAction {
+            key: self.key,
+            label: self.label,
+            id: self.id,
+            keep_open: self.keep_open.unwrap_or(false),
+        }
+    }
 }
 
 /// Start an interactive prompt
 /// @param label - Label to display (e.g., "Git grep: ")
 /// @param prompt_type - Type identifier (e.g., "git-grep")
+/// @param initial_input - Text the input starts with (empty if omitted)
 /// @returns true if prompt was started successfully
-#[op2(fast)]
+#[op2]
 fn op_fresh_start_prompt(
     state: &mut OpState,
     #[string] label: String,
     #[string] prompt_type: String,
+    #[string] initial_input: Option<String>,
 ) -> bool {
     if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
         let runtime_state = runtime_state.borrow();
         let result = runtime_state
             .command_sender
-            .send(PluginCommand::StartPrompt { label, prompt_type });
+            .send(PluginCommand::StartPrompt {
+                label,
+                prompt_type,
+                initial_input,
+            });
         return result.is_ok();
     }
     false
@@ -1661,6 +2349,13 @@ fn op_fresh_set_prompt_suggestions(
                 disabled: s.disabled.unwrap_or(false),
                 keybinding: s.keybinding,
                 source: None,
+                match_indices: Vec::new(),
+                actions: s
+                    .actions
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(TsSuggestionAction::into_action)
+                    .collect(),
             })
             .collect();
         let result = runtime_state
@@ -1670,7 +2365,133 @@ fn op_fresh_set_prompt_suggestions(
             });
         return result.is_ok();
     }
-    false
+    false
+}
+
+/// Open a prompt for `request` and wait for the user's answer
+async fn request_prompt(
+    state: Rc<RefCell<OpState>>,
+    request: crate::services::plugins::api::PluginPromptRequest,
+) -> Result<Option<String>, JsErrorBox> {
+    let receiver = {
+        let state = state.borrow();
+        let runtime_state = state
+            .try_borrow::<Rc<RefCell<TsRuntimeState>>>()
+            .ok_or_else(|| JsErrorBox::generic("Failed to get runtime state"))?;
+        let runtime_state = runtime_state.borrow();
+
+        let request_id = {
+            let mut id = runtime_state.next_request_id.borrow_mut();
+            let current = *id;
+            *id += 1;
+            current
+        };
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        {
+            let mut pending = runtime_state.pending_responses.lock().unwrap();
+            pending.insert(request_id, tx);
+        }
+
+        if runtime_state
+            .command_sender
+            .send(PluginCommand::RequestPrompt {
+                request,
+                request_id,
+            })
+            .is_err()
+        {
+            let mut pending = runtime_state.pending_responses.lock().unwrap();
+            pending.remove(&request_id);
+            return Err(JsErrorBox::generic("Failed to send prompt request"));
+        }
+
+        rx
+    };
+
+    let response = receiver
+        .await
+        .map_err(|_| JsErrorBox::generic("Prompt request cancelled"))?;
+
+    match response {
+        crate::services::plugins::api::PluginResponse::PromptAnswered { value, .. } => Ok(value),
+        _ => Err(JsErrorBox::generic(
+            "Unexpected plugin response for prompt request",
+        )),
+    }
+}
+
+/// Ask the user for text in the prompt line
+///
+/// Opening a prompt cancels any prompt the user is typing in.
+/// @param title - Label to display (e.g., "Branch name")
+/// @param default_value - Initial text of the input
+/// @returns Promise resolving to the entered text, or null if cancelled
+/// @example
+/// const name = await editor.promptInput("Branch name", "main");
+/// if (name !== null) { ... }
+#[op2(async)]
+#[string]
+async fn op_fresh_prompt_input(
+    state: Rc<RefCell<OpState>>,
+    #[string] title: String,
+    #[string] default_value: String,
+) -> Result<Option<String>, JsErrorBox> {
+    request_prompt(
+        state,
+        crate::services::plugins::api::PluginPromptRequest::Input {
+            title,
+            default: default_value,
+        },
+    )
+    .await
+}
+
+/// Ask the user to pick one of `items` (filtered as they type)
+/// @param title - Label to display (e.g., "Remote")
+/// @param items - Choices; each resolves to its value (or text if it has none)
+/// @returns Promise resolving to the chosen item's value, or null if cancelled or no item matches
+#[op2(async)]
+#[string]
+async fn op_fresh_prompt_select(
+    state: Rc<RefCell<OpState>>,
+    #[string] title: String,
+    #[serde] items: Vec<TsSuggestion>,
+) -> Result<Option<String>, JsErrorBox> {
+    let items = items
+        .into_iter()
+        .map(|s| Suggestion {
+            text: s.text,
+            description: s.description,
+            value: s.value,
+            disabled: s.disabled.unwrap_or(false),
+            keybinding: s.keybinding,
+            source: None,
+            match_indices: Vec::new(),
+            actions: Vec::new(),
+        })
+        .collect();
+    request_prompt(
+        state,
+        crate::services::plugins::api::PluginPromptRequest::Select { title, items },
+    )
+    .await
+}
+
+/// Ask the user a yes/no question
+/// @param message - Question to display (e.g., "Discard local changes?")
+/// @returns Promise resolving to true for yes, false for no or if cancelled
+#[op2(async)]
+async fn op_fresh_prompt_confirm(
+    state: Rc<RefCell<OpState>>,
+    #[string] message: String,
+) -> Result<bool, JsErrorBox> {
+    let answer = request_prompt(
+        state,
+        crate::services::plugins::api::PluginPromptRequest::Confirm { message },
+    )
+    .await?;
+    Ok(answer.as_deref() == Some("yes"))
 }
 
 /// Read entire file contents as UTF-8 string
@@ -1686,6 +2507,65 @@ async fn op_fresh_read_file(#[string] path: String) -> Result<String, JsErrorBox
         .map_err(|e| JsErrorBox::generic(format!("Failed to read file {}: {}", path, e)))
 }
 
+/// Lines read by readFilePreview
+#[derive(serde::Serialize)]
+struct TsFilePreview {
+    /// Line number of the first line (1-indexed)
+    first_line: usize,
+    /// The lines, without line endings (very long lines are cut short)
+    lines: Vec<String>,
+}
+
+/// Read the lines around a line of a file, without reading the whole file
+///
+/// Meant for previews of large files: the file is only read up to the last
+/// line returned. Returns null for binary files; throws if the file can't
+/// be read.
+/// @param path - File path (absolute or relative to cwd)
+/// @param line - Line to show (1-indexed); 0 reads the start of the file
+/// @param context - Number of lines before and after the line (the number of lines for line 0)
+/// @example
+/// const preview = await editor.readFilePreview("src/main.rs", 120, 5);
+/// // preview.lines[120 - preview.first_line] is line 120
+#[op2(async)]
+#[serde]
+async fn op_fresh_read_file_preview(
+    #[string] path: String,
+    line: u32,
+    context: u32,
+) -> Result<Option<TsFilePreview>, JsErrorBox> {
+    use crate::services::file_preview::{self, PreviewRange};
+
+    let context = context.min(1000) as usize;
+    let (range, max_lines) = match line {
+        0 => (
+            PreviewRange::Start {
+                max_bytes: u64::MAX,
+            },
+            context,
+        ),
+        line => (
+            PreviewRange::AroundLine {
+                line: line as usize - 1,
+                context,
+            },
+            context * 2 + 1,
+        ),
+    };
+    let slice = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || file_preview::load_file_slice(std::path::Path::new(&path), range, max_lines)
+    })
+    .await
+    .map_err(|e| JsErrorBox::generic(e.to_string()))?
+    .map_err(|e| JsErrorBox::generic(format!("Failed to read file {}: {}", path, e)))?;
+
+    Ok(slice.map(|slice| TsFilePreview {
+        first_line: slice.first_line + 1,
+        lines: slice.lines,
+    }))
+}
+
 /// Write string content to a file, creating or overwriting
 ///
 /// Creates parent directories if they don't exist (behavior may vary).
@@ -1702,6 +2582,17 @@ async fn op_fresh_write_file(
         .map_err(|e| JsErrorBox::generic(format!("Failed to write file {}: {}", path, e)))
 }
 
+/// Delete a file
+///
+/// Buffers showing the file are left open.
+/// @param path - File to delete (absolute or relative to cwd)
+#[op2(async)]
+async fn op_fresh_remove_file(#[string] path: String) -> Result<(), JsErrorBox> {
+    tokio::fs::remove_file(&path)
+        .await
+        .map_err(|e| JsErrorBox::generic(format!("Failed to delete file {}: {}", path, e)))
+}
+
 /// Check if a path exists (file, directory, or symlink)
 ///
 /// Does not follow symlinks; returns true for broken symlinks.
@@ -1769,6 +2660,64 @@ fn op_fresh_get_cwd(state: &mut OpState) -> String {
         .unwrap_or_else(|_| ".".to_string())
 }
 
+/// Include/exclude globs of the project
+#[derive(serde::Serialize)]
+struct TsFileFilter {
+    /// Only files matching one of these are part of the project (all if empty)
+    include: Vec<String>,
+    /// Files and directories matching these are left out
+    exclude: Vec<String>,
+}
+
+/// Get the project's include/exclude globs
+///
+/// Combines the `files` section of the config with the project's
+/// `.fresh/project.toml`. Patterns use .gitignore syntax, relative to the
+/// working directory. Tools that take globs (like `rg -g`) can use them
+/// directly; otherwise use filterPaths.
+#[op2]
+#[serde]
+fn op_fresh_get_file_filter(state: &mut OpState) -> TsFileFilter {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        if let Ok(snapshot) = runtime_state.state_snapshot.read() {
+            return TsFileFilter {
+                include: snapshot.file_filter.include().to_vec(),
+                exclude: snapshot.file_filter.exclude().to_vec(),
+            };
+        };
+    }
+    TsFileFilter {
+        include: Vec::new(),
+        exclude: Vec::new(),
+    }
+}
+
+/// Remove the paths left out by the project's include/exclude globs
+///
+/// Paths are files, absolute or relative to the working directory; the
+/// order of the others is kept.
+/// @param paths - Paths to filter
+/// @returns The paths that are part of the project
+#[op2]
+#[serde]
+fn op_fresh_filter_paths(state: &mut OpState, #[serde] paths: Vec<String>) -> Vec<String> {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        if let Ok(snapshot) = runtime_state.state_snapshot.read() {
+            return paths
+                .into_iter()
+                .filter(|path| {
+                    !snapshot
+                        .file_filter
+                        .is_excluded(std::path::Path::new(path), false)
+                })
+                .collect();
+        };
+    }
+    paths
+}
+
 /// Join path segments using the OS path separator
 ///
 /// Handles empty segments and normalizes separators.
@@ -2377,6 +3326,60 @@ fn op_fresh_define_mode(
     false
 }
 
+/// Bind keys in a single buffer
+///
+/// Buffer bindings take precedence over the buffer's mode and the global
+/// keymap, and are removed when the buffer closes.
+/// @param buffer_id - ID of the buffer
+/// @param bindings - Array of [key_string, command_name] pairs
+/// @returns true if the bindings were sent successfully
+/// @example
+/// editor.setBufferKeybindings(bufferId, [
+///   ["q", "close-buffer"],
+///   ["Return", "results_goto"]
+/// ]);
+#[op2]
+fn op_fresh_set_buffer_keybindings(
+    state: &mut OpState,
+    buffer_id: u32,
+    #[serde] bindings: Vec<(String, String)>,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::SetBufferKeybindings {
+                buffer_id: BufferId(buffer_id as usize),
+                bindings,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Remove keys bound in a single buffer
+/// @param buffer_id - ID of the buffer
+/// @param keys - Key strings to unbind, or null to remove all of the buffer's bindings
+/// @returns true if the request was sent successfully
+#[op2]
+fn op_fresh_remove_buffer_keybindings(
+    state: &mut OpState,
+    buffer_id: u32,
+    #[serde] keys: Option<Vec<String>>,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::RemoveBufferKeybindings {
+                buffer_id: BufferId(buffer_id as usize),
+                keys,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Switch the current split to display a buffer
 /// @param buffer_id - ID of the buffer to show
 /// @returns true if buffer was shown successfully
@@ -2519,6 +3522,64 @@ fn op_fresh_set_buffer_cursor(state: &mut OpState, buffer_id: u32, position: u32
     false
 }
 
+/// How a custom text object is found
+#[derive(serde::Deserialize)]
+struct TsTextObjectOptions {
+    /// Regex whose match around the cursor is the object; its first group is the inside
+    pattern: Option<String>,
+    /// Tree-sitter queries by language name ("rust", "python", ...), capturing @around and @inside
+    queries: Option<HashMap<String, String>>,
+}
+
+/// Add a text object that selection commands can use
+/// For a buffer whose language has a query, the smallest @around capture
+/// containing the cursor is the object and the @inside capture of the same
+/// match its inside. Otherwise the pattern is used. The built-in objects
+/// ("quotes", "brackets", "function", "paragraph") can't be replaced.
+/// @param name - Name of the text object
+/// @param options - Its query per language and/or pattern
+/// @returns true if the request was sent successfully
+/// @example
+/// editor.registerTextObject("argument", {
+///   pattern: "[(,]\\s*([^,()]*)",
+///   queries: { rust: "(parameter) @around" },
+/// });
+#[op2]
+fn op_fresh_register_text_object(
+    state: &mut OpState,
+    #[string] name: String,
+    #[serde] options: TsTextObjectOptions,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::RegisterTextObject {
+                name,
+                pattern: options.pattern,
+                queries: options.queries.unwrap_or_default(),
+            });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Select a text object around each cursor in the active buffer
+/// @param name - Built-in ("quotes", "brackets", "function", "paragraph") or registered text object
+/// @param around - Include the delimiters (quotes, brackets, function signature, trailing blank lines)
+/// @returns true if the request was sent successfully
+#[op2(fast)]
+fn op_fresh_select_text_object(state: &mut OpState, #[string] name: String, around: bool) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::SelectTextObject { name, around });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Get text properties at the cursor position in a buffer
 /// @param buffer_id - ID of the buffer to query
 /// @returns Array of property objects for text ranges containing the cursor
@@ -2594,6 +3655,166 @@ fn op_fresh_set_virtual_buffer_content(
     false
 }
 
+/// Options for registerPanel
+#[derive(serde::Deserialize)]
+struct TsPanelOptions {
+    /// Unique panel ID (registering an existing ID updates that panel)
+    id: String,
+    /// Title shown in the panel border (defaults to the ID)
+    title: Option<String>,
+    /// Where to dock the panel: "left", "right" or "bottom"
+    position: String,
+    /// Width (left/right) or height (bottom) in cells, borders included
+    size: u16,
+    /// Mode whose keybindings apply while the panel is focused; define with defineMode first
+    mode: Option<String>,
+    /// Name of the globalThis function that draws the panel, called with (rect, draw)
+    ///
+    /// Read by the JS wrapper, which keeps the renderer names
+    #[allow(dead_code)]
+    render: String,
+}
+
+/// Text drawn in a panel
+#[derive(serde::Deserialize)]
+struct TsPanelText {
+    /// Column within the panel's content area (0-based)
+    x: u16,
+    /// Row within the panel's content area (0-based)
+    y: u16,
+    /// Text to draw (clipped at the panel's edge)
+    text: String,
+    /// Foreground color as [r, g, b]
+    fg: Option<(u8, u8, u8)>,
+    /// Background color as [r, g, b]
+    bg: Option<(u8, u8, u8)>,
+    /// Draw in bold
+    bold: Option<bool>,
+}
+
+/// Dock a panel drawn by the plugin
+///
+/// The render function is called with the size of the panel's content area
+/// (`rect`) and a draw handle whenever the panel is shown, resized, focused
+/// or refreshed. `draw.text(x, y, text, style)` and
+/// `draw.fill(x, y, width, height, char, style)` place text (style has
+/// optional `fg`/`bg` [r, g, b] colors and `bold`); `draw.focused` tells if
+/// the panel has the keyboard focus. A focused panel gets keys through its
+/// mode; Escape returns the focus to the buffer.
+/// @param options - Panel configuration
+/// @returns true if the panel was registered
+/// @example
+/// globalThis.renderPlayer = (rect, draw) => {
+///   draw.text(0, 0, "Now playing", { bold: true });
+///   draw.text(0, 1, track.slice(0, rect.width));
+/// };
+/// editor.registerPanel({ id: "player", title: "Music", position: "bottom", size: 4, render: "renderPlayer" });
+#[op2]
+fn op_fresh_register_panel(state: &mut OpState, #[serde] options: TsPanelOptions) -> bool {
+    let position = match options.position.as_str() {
+        "left" => crate::services::plugins::api::PanelPosition::Left,
+        "right" => crate::services::plugins::api::PanelPosition::Right,
+        "bottom" => crate::services::plugins::api::PanelPosition::Bottom,
+        other => {
+            tracing::warn!("registerPanel: unknown position '{}'", other);
+            return false;
+        }
+    };
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::RegisterPanel {
+                title: options.title.unwrap_or_else(|| options.id.clone()),
+                id: options.id,
+                position,
+                size: options.size,
+                mode: options.mode,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Remove a panel
+/// @param id - ID the panel was registered with
+/// @returns true if the request was sent successfully
+#[op2(fast)]
+fn op_fresh_unregister_panel(state: &mut OpState, #[string] id: String) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::UnregisterPanel { id });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Replace what a panel shows
+///
+/// Called with the draw handle's text after each render; use refreshPanel
+/// to redraw instead of calling this directly.
+/// @param id - ID of the panel
+/// @param content - Text to draw
+/// @returns true if the content was sent successfully
+#[op2]
+fn op_fresh_set_panel_content(
+    state: &mut OpState,
+    #[string] id: String,
+    #[serde] content: Vec<TsPanelText>,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let content = content
+            .into_iter()
+            .map(|t| crate::services::plugins::api::PanelText {
+                x: t.x,
+                y: t.y,
+                text: t.text,
+                fg: t.fg,
+                bg: t.bg,
+                bold: t.bold.unwrap_or(false),
+            })
+            .collect();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::SetPanelContent { id, content });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Draw a panel again (e.g., after its data changed)
+/// @param id - ID of the panel
+/// @returns true if the request was sent successfully
+#[op2(fast)]
+fn op_fresh_refresh_panel(state: &mut OpState, #[string] id: String) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::RefreshPanel { id });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Give a panel the keyboard focus
+/// @param id - ID of the panel
+/// @returns true if the request was sent successfully
+#[op2(fast)]
+fn op_fresh_focus_panel(state: &mut OpState, #[string] id: String) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::FocusPanel { id });
+        return result.is_ok();
+    }
+    false
+}
+
 // Define the extension with our ops
 extension!(
     fresh_runtime,
@@ -2609,6 +3830,7 @@ extension!(
         op_fresh_is_buffer_modified,
         op_fresh_insert_text,
         op_fresh_delete_range,
+        op_fresh_apply_edits,
         op_fresh_add_overlay,
         op_fresh_remove_overlay,
         op_fresh_clear_namespace,
@@ -2621,6 +3843,9 @@ extension!(
         op_fresh_clear_virtual_texts,
         op_fresh_add_virtual_line,
         op_fresh_clear_virtual_text_namespace,
+        op_fresh_create_namespace,
+        op_fresh_clear_namespace_in_range,
+        op_fresh_set_decorations,
         op_fresh_submit_view_transform,
         op_fresh_clear_view_transform,
         op_fresh_refresh_lines,
@@ -2629,13 +3854,21 @@ extension!(
         op_fresh_insert_at_cursor,
         op_fresh_register_command,
         op_fresh_unregister_command,
+        op_fresh_set_gutter_click_action,
         op_fresh_open_file,
         op_fresh_get_active_split_id,
         op_fresh_open_file_in_split,
+        op_fresh_open_file_in_new_split,
+        op_fresh_open_file_to_side,
         op_fresh_get_cursor_line,
         op_fresh_get_all_cursor_positions,
         op_fresh_spawn_process,
+        op_fresh_search_project,
+        op_fresh_list_project_files,
+        op_fresh_fuzzy_filter,
         op_fresh_spawn_background_process,
+        op_fresh_spawn_job,
+        op_fresh_kill_job,
         op_fresh_kill_process,
         op_fresh_is_process_running,
         op_fresh_get_buffer_info,
@@ -2645,12 +3878,19 @@ extension!(
         op_fresh_get_viewport,
         op_fresh_start_prompt,
         op_fresh_set_prompt_suggestions,
+        op_fresh_prompt_input,
+        op_fresh_prompt_select,
+        op_fresh_prompt_confirm,
         op_fresh_read_file,
+        op_fresh_read_file_preview,
         op_fresh_write_file,
+        op_fresh_remove_file,
         op_fresh_file_exists,
         op_fresh_file_stat,
         op_fresh_get_env,
         op_fresh_get_cwd,
+        op_fresh_get_file_filter,
+        op_fresh_filter_paths,
         op_fresh_path_join,
         op_fresh_path_dirname,
         op_fresh_path_basename,
@@ -2666,6 +3906,8 @@ extension!(
         op_fresh_create_virtual_buffer,
         op_fresh_send_lsp_request,
         op_fresh_define_mode,
+        op_fresh_set_buffer_keybindings,
+        op_fresh_remove_buffer_keybindings,
         op_fresh_show_buffer,
         op_fresh_close_buffer,
         op_fresh_focus_split,
@@ -2674,8 +3916,15 @@ extension!(
         op_fresh_set_split_ratio,
         op_fresh_distribute_splits_evenly,
         op_fresh_set_buffer_cursor,
+        op_fresh_register_text_object,
+        op_fresh_select_text_object,
         op_fresh_get_text_properties_at_cursor,
         op_fresh_set_virtual_buffer_content,
+        op_fresh_register_panel,
+        op_fresh_unregister_panel,
+        op_fresh_set_panel_content,
+        op_fresh_refresh_panel,
+        op_fresh_focus_panel,
     ],
 );
 
@@ -2791,12 +4040,16 @@ impl TypeScriptRuntime {
                     deleteRange(bufferId, start, end) {
                         return core.ops.op_fresh_delete_range(bufferId, start, end);
                     },
+                    applyEdits(bufferId, revision, edits, skipConflicting = false) {
+                        return core.ops.op_fresh_apply_edits(bufferId, revision, edits, skipConflicting);
+                    },
 
                     // Overlays
                     // namespace: group overlays together for efficient batch removal
                     // Use empty string for no namespace
-                    addOverlay(bufferId, namespace, start, end, r, g, b, underline, bold = false, italic = false) {
-                        return core.ops.op_fresh_add_overlay(bufferId, namespace, start, end, r, g, b, underline, bold, italic);
+                    // revision: buffer revision the offsets were computed at (from lines_changed)
+                    addOverlay(bufferId, namespace, start, end, r, g, b, underline, bold = false, italic = false, revision = null) {
+                        return core.ops.op_fresh_add_overlay(bufferId, namespace, start, end, r, g, b, underline, bold, italic, revision);
                     },
                     removeOverlay(bufferId, handle) {
                         return core.ops.op_fresh_remove_overlay(bufferId, handle);
@@ -2838,9 +4091,20 @@ impl TypeScriptRuntime {
                         return core.ops.op_fresh_clear_virtual_text_namespace(bufferId, namespace);
                     },
 
+                    // Namespaced decorations (overlays and virtual texts)
+                    createNamespace(name) {
+                        return core.ops.op_fresh_create_namespace(name);
+                    },
+                    clearNamespaceInRange(bufferId, namespace, start, end) {
+                        return core.ops.op_fresh_clear_namespace_in_range(bufferId, namespace, start, end);
+                    },
+                    setDecorations(bufferId, namespace, decorations) {
+                        return core.ops.op_fresh_set_decorations(bufferId, namespace, decorations);
+                    },
+
                     // View transforms (for compose mode)
-                    submitViewTransform(bufferId, splitId, start, end, tokens, layoutHints) {
-                        return core.ops.op_fresh_submit_view_transform(bufferId, splitId, start, end, tokens, layoutHints);
+                    submitViewTransform(bufferId, splitId, start, end, tokens, layoutHints, revision = null) {
+                        return core.ops.op_fresh_submit_view_transform(bufferId, splitId, start, end, tokens, layoutHints, revision);
                     },
                     clearViewTransform(bufferId, splitId = null) {
                         return core.ops.op_fresh_clear_view_transform(bufferId, splitId);
@@ -2864,16 +4128,20 @@ impl TypeScriptRuntime {
                     },
 
                     // Command registration
-                    registerCommand(name, description, action, contexts = "") {
+                    registerCommand(name, description, action, contexts = "", when = "") {
                         // Pass the current plugin source (set by load_module_with_source)
                         const source = globalThis.__PLUGIN_SOURCE__ || "";
-                        return core.ops.op_fresh_register_command(name, description, action, contexts, source);
+                        return core.ops.op_fresh_register_command(name, description, action, contexts, when, source);
                     },
 
                     unregisterCommand(name) {
                         return core.ops.op_fresh_unregister_command(name);
                     },
 
+                    setGutterClickAction(column, action = null) {
+                        return core.ops.op_fresh_set_gutter_click_action(column, action);
+                    },
+
                     // File operations
                     openFile(path, line = 0, column = 0) {
                         return core.ops.op_fresh_open_file(path, line, column);
@@ -2886,6 +4154,12 @@ impl TypeScriptRuntime {
                     openFileInSplit(splitId, path, line = 0, column = 0) {
                         return core.ops.op_fresh_open_file_in_split(splitId, path, line, column);
                     },
+                    openFileInNewSplit(path, vertical = true) {
+                        return core.ops.op_fresh_open_file_in_new_split(path, vertical);
+                    },
+                    openFileToSide(path, line = 0, column = 0, below = false) {
+                        return core.ops.op_fresh_open_file_to_side(path, line, column, below);
+                    },
 
                     // Cursor operations
                     getCursorLine() {
@@ -2913,20 +4187,44 @@ impl TypeScriptRuntime {
                     },
 
                     // Prompt operations
-                    startPrompt(label, promptType) {
-                        return core.ops.op_fresh_start_prompt(label, promptType);
+                    startPrompt(label, promptType, initialInput = null) {
+                        return core.ops.op_fresh_start_prompt(label, promptType, initialInput);
                     },
                     setPromptSuggestions(suggestions) {
                         return core.ops.op_fresh_set_prompt_suggestions(suggestions);
                     },
+                    promptInput(title, defaultValue = "") {
+                        return core.ops.op_fresh_prompt_input(title, defaultValue);
+                    },
+                    promptSelect(title, items) {
+                        return core.ops.op_fresh_prompt_select(title, items);
+                    },
+                    promptConfirm(message) {
+                        return core.ops.op_fresh_prompt_confirm(message);
+                    },
 
                     // Async operations
                     spawnProcess(command, args = [], cwd = null) {
                         return core.ops.op_fresh_spawn_process(command, args, cwd);
                     },
+                    searchProject(query, maxResults = 100, dir = null) {
+                        return core.ops.op_fresh_search_project(query, maxResults, dir);
+                    },
+                    listProjectFiles() {
+                        return core.ops.op_fresh_list_project_files();
+                    },
+                    fuzzyFilter(query, items, maxResults = 100) {
+                        return core.ops.op_fresh_fuzzy_filter(query, items, maxResults);
+                    },
                     spawnBackgroundProcess(command, args = [], cwd = null) {
                         return core.ops.op_fresh_spawn_background_process(command, args, cwd);
                     },
+                    spawnJob(command, args = [], options = {}) {
+                        return core.ops.op_fresh_spawn_job(command, args, options);
+                    },
+                    killJob(jobId) {
+                        return core.ops.op_fresh_kill_job(jobId);
+                    },
                     killProcess(processId) {
                         return core.ops.op_fresh_kill_process(processId);
                     },
@@ -2941,9 +4239,15 @@ impl TypeScriptRuntime {
                     readFile(path) {
                         return core.ops.op_fresh_read_file(path);
                     },
+                    readFilePreview(path, line = 0, context = 5) {
+                        return core.ops.op_fresh_read_file_preview(path, line, context);
+                    },
                     writeFile(path, content) {
                         return core.ops.op_fresh_write_file(path, content);
                     },
+                    removeFile(path) {
+                        return core.ops.op_fresh_remove_file(path);
+                    },
                     fileExists(path) {
                         return core.ops.op_fresh_file_exists(path);
                     },
@@ -2958,6 +4262,12 @@ impl TypeScriptRuntime {
                     getCwd() {
                         return core.ops.op_fresh_get_cwd();
                     },
+                    getFileFilter() {
+                        return core.ops.op_fresh_get_file_filter();
+                    },
+                    filterPaths(paths) {
+                        return core.ops.op_fresh_filter_paths(paths);
+                    },
 
                     // Path operations
                     pathJoin(...parts) {
@@ -3003,6 +4313,12 @@ impl TypeScriptRuntime {
                     defineMode(name, parent, bindings, readOnly = false) {
                         return core.ops.op_fresh_define_mode(name, parent, bindings, readOnly);
                     },
+                    setBufferKeybindings(bufferId, bindings) {
+                        return core.ops.op_fresh_set_buffer_keybindings(bufferId, bindings);
+                    },
+                    removeBufferKeybindings(bufferId, keys = null) {
+                        return core.ops.op_fresh_remove_buffer_keybindings(bufferId, keys);
+                    },
                     showBuffer(bufferId) {
                         return core.ops.op_fresh_show_buffer(bufferId);
                     },
@@ -3027,12 +4343,74 @@ impl TypeScriptRuntime {
                     setBufferCursor(bufferId, position) {
                         return core.ops.op_fresh_set_buffer_cursor(bufferId, position);
                     },
+                    registerTextObject(name, options) {
+                        return core.ops.op_fresh_register_text_object(name, options);
+                    },
+                    selectTextObject(name, around) {
+                        return core.ops.op_fresh_select_text_object(name, around);
+                    },
                     getTextPropertiesAtCursor(bufferId) {
                         return core.ops.op_fresh_get_text_properties_at_cursor(bufferId);
                     },
                     setVirtualBufferContent(bufferId, entries) {
                         return core.ops.op_fresh_set_virtual_buffer_content(bufferId, entries);
                     },
+
+                    // Panel operations
+                    registerPanel(options) {
+                        panelRenderers.set(options.id, options.render);
+                        if (!panelHookRegistered) {
+                            core.ops.op_fresh_on("panel_render", "__panelRender");
+                            panelHookRegistered = true;
+                        }
+                        return core.ops.op_fresh_register_panel(options);
+                    },
+                    unregisterPanel(id) {
+                        panelRenderers.delete(id);
+                        return core.ops.op_fresh_unregister_panel(id);
+                    },
+                    setPanelContent(id, content) {
+                        return core.ops.op_fresh_set_panel_content(id, content);
+                    },
+                    refreshPanel(id) {
+                        return core.ops.op_fresh_refresh_panel(id);
+                    },
+                    focusPanel(id) {
+                        return core.ops.op_fresh_focus_panel(id);
+                    },
+                };
+
+                // Panel ID -> name of the globalThis function that draws it
+                const panelRenderers = new Map();
+                let panelHookRegistered = false;
+
+                // Run a panel's render function with a draw handle and send what it drew
+                globalThis.__panelRender = async function(data) {
+                    const render = globalThis[panelRenderers.get(data.panel_id)];
+                    if (typeof render !== 'function') {
+                        return true;
+                    }
+                    const content = [];
+                    const draw = {
+                        focused: data.focused,
+                        text(x, y, text, style = {}) {
+                            content.push({
+                                x, y, text: String(text),
+                                fg: style.fg ?? null, bg: style.bg ?? null, bold: style.bold ?? false,
+                            });
+                        },
+                        fill(x, y, width, height, char = " ", style = {}) {
+                            for (let row = y; row < y + height; row++) {
+                                this.text(x, row, char.repeat(width), style);
+                            }
+                        },
+                    };
+                    const result = render({ x: 0, y: 0, width: data.width, height: data.height }, draw);
+                    if (result instanceof Promise) {
+                        await result;
+                    }
+                    core.ops.op_fresh_set_panel_content(data.panel_id, content);
+                    return true;
                 };
 
                 // Make editor globally available
@@ -3068,15 +4446,7 @@ impl TypeScriptRuntime {
     ///
     /// This is called by the editor after processing a command that requires a response.
     pub fn deliver_response(&self, response: crate::services::plugins::api::PluginResponse) {
-        let request_id = match &response {
-            crate::services::plugins::api::PluginResponse::VirtualBufferCreated {
-                request_id,
-                ..
-            } => *request_id,
-            crate::services::plugins::api::PluginResponse::LspRequest { request_id, .. } => {
-                *request_id
-            }
-        };
+        let request_id = response.request_id();
 
         let sender = {
             let mut pending = self.pending_responses.lock().unwrap();
@@ -3700,6 +5070,7 @@ mod tests {
                     path: Some(PathBuf::from("/test/file.rs")),
                     modified: true,
                     length: 1000,
+                    revision: 0,
                 },
             );
             snapshot.primary_cursor = Some(CursorInfo {
@@ -3828,6 +5199,7 @@ mod tests {
                 underline,
                 bold,
                 italic,
+                ..
             } => {
                 assert_eq!(buffer_id.0, 42);
                 assert_eq!(namespace.as_ref().map(|n| n.as_str()), Some("test-overlay"));
@@ -384,12 +384,7 @@ fn respond_to_pending(
     pending_responses: &crate::services::plugins::runtime::PendingResponses,
     response: crate::services::plugins::api::PluginResponse,
 ) {
-    let request_id = match &response {
-        crate::services::plugins::api::PluginResponse::VirtualBufferCreated {
-            request_id, ..
-        } => *request_id,
-        crate::services::plugins::api::PluginResponse::LspRequest { request_id, .. } => *request_id,
-    };
+    let request_id = response.request_id();
 
     let sender = {
         let mut pending = pending_responses.lock().unwrap();
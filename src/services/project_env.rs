@@ -0,0 +1,120 @@
+//! The environment a project's `.envrc` exports
+//!
+//! Projects using direnv keep the variables their tools need (a `PATH` with
+//! the project's toolchain, `DATABASE_URL`, ...) in an `.envrc`. It is read
+//! with `direnv export json`, so only an `.envrc` allowed with `direnv allow`
+//! is ever run; without direnv nothing is loaded. The variables are not set
+//! in the editor's own environment but given to the processes it starts (see
+//! [`ProjectEnv::apply_to_command`]).
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+
+/// The file the environment is read from
+pub const ENVRC: &str = ".envrc";
+
+/// The variables a project's `.envrc` changes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectEnv {
+    /// Values to set, or None to unset, sorted by name
+    pub vars: Vec<(String, Option<String>)>,
+}
+
+impl ProjectEnv {
+    /// Set (or unset) the variables in the environment `cmd` runs with
+    pub fn apply_to_command(&self, cmd: &mut tokio::process::Command) {
+        cmd.envs(
+            self.vars
+                .iter()
+                .filter_map(|(name, value)| Some((name, value.as_ref()?))),
+        );
+        for (name, _) in self.vars.iter().filter(|(_, value)| value.is_none()) {
+            cmd.env_remove(name);
+        }
+    }
+}
+
+/// Read what `dir`'s `.envrc` changes in the editor's environment. Ok(None)
+/// if there is no `.envrc`; an error is the message to show, e.g. direnv
+/// refusing an `.envrc` that wasn't allowed.
+pub fn load_project_env(dir: &Path) -> Result<Option<ProjectEnv>, String> {
+    if !dir.join(ENVRC).is_file() {
+        return Ok(None);
+    }
+    let output = std::process::Command::new("direnv")
+        .args(["export", "json"])
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => "direnv is not installed".to_string(),
+            _ => format!("direnv: {}", e),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("direnv failed");
+        return Err(message.trim().to_string());
+    }
+    let vars = parse_direnv(&String::from_utf8_lossy(&output.stdout))?;
+    Ok(Some(ProjectEnv { vars }))
+}
+
+/// Variables from `direnv export json`: `{"NAME": "value", "OLD": null}`,
+/// or nothing when there is nothing to change
+fn parse_direnv(output: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    if output.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let vars: HashMap<String, Option<String>> = serde_json::from_str(output)
+        .map_err(|e| format!("Unexpected output from direnv: {}", e))?;
+    let mut vars: Vec<_> = vars.into_iter().collect();
+    vars.sort();
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_direnv() {
+        let output = r#"{"PATH": "/p/bin:/usr/bin", "OLD_VAR": null}"#;
+        assert_eq!(
+            parse_direnv(output).unwrap(),
+            vec![
+                ("OLD_VAR".to_string(), None),
+                ("PATH".to_string(), Some("/p/bin:/usr/bin".to_string())),
+            ]
+        );
+        assert_eq!(parse_direnv("\n").unwrap(), vec![]);
+        assert!(parse_direnv("direnv: loading").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_direnv_export_to_command() {
+        let env = ProjectEnv {
+            vars: parse_direnv(r#"{"FRESH_TEST_GREETING": "from envrc", "HOME": null}"#).unwrap(),
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c")
+            .arg(r#"echo "$FRESH_TEST_GREETING, ${HOME-no home}""#);
+        env.apply_to_command(&mut cmd);
+        let output = runtime.block_on(cmd.output()).unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "from envrc, no home\n"
+        );
+    }
+}
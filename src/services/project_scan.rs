@@ -0,0 +1,222 @@
+//! Listing the files of a project
+//!
+//! The project is walked on all cores. Files ignored by .gitignore, .ignore
+//! or the project's file filter are left out, hidden files too, and symbolic
+//! links are followed; a link back into one of its own ancestors is recorded
+//! as a cycle instead of being walked forever. The editor keeps the last
+//! scan (see `app::project_scan`), which the search and symbol indexes and
+//! plugins' `listProjectFiles` read, and drops it when the file watcher sees
+//! a file appear or disappear.
+
+use super::file_filter::FileFilter;
+use ignore::{WalkBuilder, WalkState};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Progress is reported every this many files
+const PROGRESS_INTERVAL: usize = 1000;
+
+/// The files of a project
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProjectScan {
+    /// Files, relative to the project root, sorted
+    pub files: Vec<PathBuf>,
+    /// Directories that were walked, relative to the root (the root is ""),
+    /// sorted
+    pub dirs: Vec<PathBuf>,
+    /// Symbolic links that lead back to one of their ancestors, relative to
+    /// the root
+    pub cycles: Vec<PathBuf>,
+}
+
+impl ProjectScan {
+    /// Whether `relative` is one of the scanned files
+    pub fn contains_file(&self, relative: &Path) -> bool {
+        self.files
+            .binary_search_by(|file| file.as_path().cmp(relative))
+            .is_ok()
+    }
+
+    /// Whether `relative` is one of the walked directories
+    pub fn contains_dir(&self, relative: &Path) -> bool {
+        self.dirs
+            .binary_search_by(|dir| dir.as_path().cmp(relative))
+            .is_ok()
+    }
+}
+
+/// What one walker thread found
+#[derive(Default)]
+struct Found {
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+    cycles: Vec<PathBuf>,
+}
+
+/// List the files under `root`
+///
+/// `progress` is called with the number of files found so far, every
+/// thousand files, from the walker threads.
+pub fn scan_project(
+    root: &Path,
+    filter: &FileFilter,
+    progress: &(dyn Fn(usize) + Sync),
+) -> ProjectScan {
+    let found = Mutex::new(Found::default());
+    let count = AtomicUsize::new(0);
+
+    WalkBuilder::new(root)
+        .follow_links(true)
+        // .gitignore applies outside git repositories too
+        .require_git(false)
+        .filter_entry({
+            let filter = filter.clone();
+            move |entry| {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                !filter.is_excluded(entry.path(), is_dir)
+            }
+        })
+        .build_parallel()
+        .run(|| {
+            let count = &count;
+            let mut collector = Collector {
+                found: &found,
+                local: Found::default(),
+            };
+            Box::new(move |result| {
+                let local = &mut collector.local;
+                match result {
+                    Ok(entry) => {
+                        let relative = entry.path().strip_prefix(root).ok().map(Path::to_path_buf);
+                        match (relative, entry.file_type()) {
+                            (Some(relative), Some(t)) if t.is_dir() => local.dirs.push(relative),
+                            (Some(relative), Some(t)) if t.is_file() => {
+                                local.files.push(relative);
+                                let n = count.fetch_add(1, Ordering::Relaxed) + 1;
+                                if n.is_multiple_of(PROGRESS_INTERVAL) {
+                                    progress(n);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    Err(e) => match loop_link(&e) {
+                        Some(link) => local
+                            .cycles
+                            .push(link.strip_prefix(root).unwrap_or(link).to_path_buf()),
+                        None => tracing::debug!("Skipped while scanning the project: {}", e),
+                    },
+                }
+                WalkState::Continue
+            })
+        });
+
+    let mut found = found.into_inner().unwrap_or_default();
+    found.files.sort();
+    found.dirs.sort();
+    found.cycles.sort();
+    ProjectScan {
+        files: found.files,
+        dirs: found.dirs,
+        cycles: found.cycles,
+    }
+}
+
+/// Collects what one walker thread finds, and adds it to the result when
+/// the thread is done with it
+struct Collector<'a> {
+    found: &'a Mutex<Found>,
+    local: Found,
+}
+
+impl Drop for Collector<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut found) = self.found.lock() {
+            found.files.append(&mut self.local.files);
+            found.dirs.append(&mut self.local.dirs);
+            found.cycles.append(&mut self.local.cycles);
+        }
+    }
+}
+
+/// The link of a symbolic link cycle, if that is the error
+fn loop_link(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => loop_link(err),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FilesConfig;
+
+    fn write(root: &Path, relative: &str) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "x").unwrap();
+    }
+
+    #[test]
+    fn test_scan_honors_ignore_files_and_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/main.rs",
+            "src/gen/api.rs",
+            "target/debug/app",
+            "notes.tmp",
+            "vendor/lib.js",
+            ".hidden/config",
+        ] {
+            write(root, file);
+        }
+        std::fs::write(root.join(".gitignore"), "target/\n*.tmp\n").unwrap();
+        std::fs::write(root.join(".ignore"), "vendor/\n").unwrap();
+        let exclude = FilesConfig {
+            include: vec![],
+            exclude: vec!["src/gen/".to_string()],
+        };
+        let filter = FileFilter::new(root, &exclude, &FilesConfig::default()).unwrap();
+
+        let reported = AtomicUsize::new(0);
+        let scan = scan_project(root, &filter, &|n| reported.store(n, Ordering::Relaxed));
+        // The ignore files are hidden too
+        assert_eq!(scan.files, vec![PathBuf::from("src/main.rs")]);
+        assert_eq!(scan.dirs, vec![PathBuf::new(), PathBuf::from("src")]);
+        assert!(scan.contains_file(Path::new("src/main.rs")));
+        assert!(!scan.contains_file(Path::new("notes.tmp")));
+        assert!(scan.contains_dir(Path::new("src")));
+        assert!(scan.cycles.is_empty());
+        // Fewer files than the progress interval
+        assert_eq!(reported.load(Ordering::Relaxed), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_detects_symlink_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "a/file.txt");
+        write(root, "shared/lib.rs");
+        std::os::unix::fs::symlink(root, root.join("a/back")).unwrap();
+        std::os::unix::fs::symlink(root.join("shared"), root.join("a/shared")).unwrap();
+
+        let scan = scan_project(root, &FileFilter::default(), &|_| {});
+        assert_eq!(scan.cycles, vec![PathBuf::from("a/back")]);
+        // Links to other directories are followed
+        assert_eq!(
+            scan.files,
+            vec![
+                PathBuf::from("a/file.txt"),
+                PathBuf::from("a/shared/lib.rs"),
+                PathBuf::from("shared/lib.rs"),
+            ]
+        );
+    }
+}
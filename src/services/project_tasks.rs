@@ -0,0 +1,273 @@
+//! Project tasks from `.fresh/tasks.toml`
+//!
+//! A project can define its own command palette entries. Each `[[task]]` runs a
+//! shell command in the project root and appears in the palette as
+//! "Project: <name>":
+//!
+//! ```toml
+//! [[task]]
+//! name = "Build"
+//! command = "cargo build"
+//! description = "Build the workspace"   # optional
+//! key = "ctrl+alt+b"                    # optional key binding
+//! output = "buffer"                     # buffer | status | insert | none
+//!
+//! [[task]]
+//! name = "Test File"
+//! command = "cargo test --test ${file_stem}"
+//! ```
+
+use crate::services::project_env::ProjectEnv;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+/// Location of the tasks file, relative to the project root
+pub const TASKS_FILE: &str = ".fresh/tasks.toml";
+
+/// Prefix of the command palette entries created for project tasks
+pub const COMMAND_PREFIX: &str = "Project: ";
+
+/// What to do with a task's output once it finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskOutput {
+    /// Show stdout and stderr in a read-only buffer
+    #[default]
+    Buffer,
+    /// Show the last line of output in the status bar
+    Status,
+    /// Insert stdout at the cursor
+    Insert,
+    /// Discard the output (failures are still reported)
+    None,
+}
+
+/// A task defined by the project
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectTask {
+    /// Name shown in the command palette (after the "Project: " prefix)
+    pub name: String,
+    /// Shell command, run with the project root as working directory
+    /// (`${file}`, `${line}` and other variables are expanded first)
+    pub command: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Key binding (e.g., "ctrl+alt+b" or "f5")
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub output: TaskOutput,
+}
+
+impl ProjectTask {
+    /// Name of the command palette entry for this task
+    pub fn command_name(&self) -> String {
+        format!("{}{}", COMMAND_PREFIX, self.name)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TasksFile {
+    #[serde(default)]
+    task: Vec<ProjectTask>,
+}
+
+/// Path of the tasks file for a project
+pub fn tasks_path(working_dir: &Path) -> PathBuf {
+    working_dir.join(TASKS_FILE)
+}
+
+/// Load the tasks defined for a project
+///
+/// A project without a tasks file has no tasks.
+pub fn load_tasks(working_dir: &Path) -> Result<Vec<ProjectTask>, String> {
+    let path = tasks_path(working_dir);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("{}: {}", TASKS_FILE, e)),
+    };
+    parse_tasks(&content).map_err(|e| format!("{}: {}", TASKS_FILE, e))
+}
+
+/// Parse the contents of a tasks file
+pub fn parse_tasks(content: &str) -> Result<Vec<ProjectTask>, String> {
+    let file: TasksFile = toml::from_str(content).map_err(|e| e.message().to_string())?;
+
+    let mut names = HashSet::new();
+    for task in &file.task {
+        if task.name.trim().is_empty() {
+            return Err("task name cannot be empty".to_string());
+        }
+        if task.command.trim().is_empty() {
+            return Err(format!("task '{}' has no command", task.name));
+        }
+        if !names.insert(task.name.as_str()) {
+            return Err(format!("task '{}' is defined more than once", task.name));
+        }
+    }
+
+    Ok(file.task)
+}
+
+/// Output of a finished task
+#[derive(Debug, Clone)]
+pub struct TaskResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl From<std::process::Output> for TaskResult {
+    fn from(output: std::process::Output) -> Self {
+        Self {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code().unwrap_or(-1),
+        }
+    }
+}
+
+/// Command that runs `command` through the shell, with the project's
+/// `.envrc` environment if one is loaded
+pub(crate) fn shell_command(
+    command: &str,
+    project_env: Option<&ProjectEnv>,
+) -> tokio::process::Command {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    if let Some(env) = project_env {
+        env.apply_to_command(&mut cmd);
+    }
+    cmd
+}
+
+/// Run a task's command through the shell in `working_dir`
+///
+/// `on_line` is called with each line of stdout as it is printed. Dropping
+/// the future kills the command.
+pub async fn run_task(
+    command: &str,
+    working_dir: &Path,
+    project_env: Option<&ProjectEnv>,
+    mut on_line: impl FnMut(&str),
+) -> std::io::Result<TaskResult> {
+    let mut child = shell_command(command, project_env)
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+
+    let read_stdout = async {
+        let mut output = Vec::new();
+        loop {
+            let start = output.len();
+            if stdout.read_until(b'\n', &mut output).await? == 0 {
+                break;
+            }
+            on_line(String::from_utf8_lossy(&output[start..]).trim_end());
+        }
+        Ok::<_, std::io::Error>(output)
+    };
+    let read_stderr = async {
+        let mut output = Vec::new();
+        stderr.read_to_end(&mut output).await?;
+        Ok(output)
+    };
+    let (stdout, stderr) = tokio::try_join!(read_stdout, read_stderr)?;
+    let status = child.wait().await?;
+
+    Ok(TaskResult {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_code: status.code().unwrap_or(-1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tasks() {
+        let tasks = parse_tasks(
+            r#"
+            [[task]]
+            name = "Build"
+            command = "cargo build"
+            key = "ctrl+alt+b"
+
+            [[task]]
+            name = "Date"
+            command = "date"
+            description = "Insert the date"
+            output = "insert"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].command_name(), "Project: Build");
+        assert_eq!(tasks[0].key.as_deref(), Some("ctrl+alt+b"));
+        assert_eq!(tasks[0].output, TaskOutput::Buffer);
+        assert_eq!(tasks[1].description.as_deref(), Some("Insert the date"));
+        assert_eq!(tasks[1].output, TaskOutput::Insert);
+
+        assert!(parse_tasks("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_tasks_errors() {
+        assert!(parse_tasks("[[task]]\nname = \"Build\"\n").is_err());
+        assert!(parse_tasks("[[task]]\nname = \"\"\ncommand = \"make\"\n").is_err());
+        assert!(
+            parse_tasks("[[task]]\nname = \"A\"\ncommand = \"x\"\noutput = \"popup\"\n").is_err()
+        );
+
+        let duplicate =
+            "[[task]]\nname = \"A\"\ncommand = \"x\"\n\n[[task]]\nname = \"A\"\ncommand = \"y\"\n";
+        let err = parse_tasks(duplicate).unwrap_err();
+        assert!(err.contains("more than once"));
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_run_task_reports_lines() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut lines = Vec::new();
+        let result = runtime
+            .block_on(run_task(
+                "echo one; echo two; echo oops >&2; exit 3",
+                Path::new("."),
+                None,
+                |line| lines.push(line.to_string()),
+            ))
+            .unwrap();
+
+        assert_eq!(lines, ["one", "two"]);
+        assert_eq!(result.stdout, "one\ntwo\n");
+        assert_eq!(result.stderr, "oops\n");
+        assert_eq!(result.exit_code, 3);
+    }
+}
@@ -342,7 +342,7 @@ pub enum RecoveryResult {
 
 /// Check if a process with the given PID is running
 #[cfg(unix)]
-fn is_process_running(pid: u32) -> bool {
+pub(crate) fn is_process_running(pid: u32) -> bool {
     // On Unix, we can use kill with signal 0 to check if process exists
     // Returns 0 if process exists and we can signal it
     // Returns -1 with EPERM if process exists but we can't signal it
@@ -357,7 +357,7 @@ fn is_process_running(pid: u32) -> bool {
 }
 
 #[cfg(not(unix))]
-fn is_process_running(_pid: u32) -> bool {
+pub(crate) fn is_process_running(_pid: u32) -> bool {
     // On non-Unix platforms, we can't easily check if a process is running
     // without platform-specific APIs. For safety in recovery scenarios,
     // we assume the process is not running (which will prompt recovery).
@@ -0,0 +1,564 @@
+//! Trigram index of the project's files for fast project-wide search
+//!
+//! Every file is indexed by the distinct 3-byte sequences (trigrams) of its
+//! ASCII-lowercased content. Text can only occur in a file that has all of
+//! its trigrams, so a search only reads the few files the index points to.
+//!
+//! Files are still searched directly when the index can't narrow them down:
+//! files too large to index, files modified on disk since they were indexed,
+//! and every file for queries shorter than a trigram. Like the symbol index,
+//! the index is stored per project in the data directory and updated
+//! incrementally.
+
+use super::project_scan::ProjectScan;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Files larger than this aren't indexed but searched directly
+const MAX_INDEXED_SIZE: u64 = 1024 * 1024;
+
+/// Files with a NUL byte in their first bytes are binary and left out
+const BINARY_CHECK_LEN: usize = 8 * 1024;
+
+/// First bytes of a saved index (the last one is the format version)
+const MAGIC: &[u8] = b"FRESHTRI1";
+
+/// An indexed file, with the modification time it was read at
+#[derive(Debug, Clone)]
+struct IndexedFile {
+    modified: u64,
+    /// Sorted, distinct trigrams (None if the file is too large to index)
+    trigrams: Option<Vec<u32>>,
+}
+
+/// A line containing the searched text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    /// 1-indexed line number
+    pub line: usize,
+    /// 1-indexed byte column of the match
+    pub column: usize,
+    /// The line, without its line ending
+    pub content: String,
+}
+
+/// Trigrams of all the files in a project, by path relative to its root
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    files: HashMap<PathBuf, IndexedFile>,
+    /// Id of each indexed path (ids of removed files are not reused)
+    ids: HashMap<PathBuf, u32>,
+    next_id: u32,
+    /// Sorted ids of the files containing each trigram
+    postings: HashMap<u32, Vec<u32>>,
+    /// Changed since it was loaded or saved
+    dirty: bool,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the index up to date with the scanned files of the project at
+    /// `root`
+    ///
+    /// Returns the number of files that were (re)indexed or dropped.
+    pub fn update(&mut self, root: &Path, scan: &ProjectScan) -> usize {
+        let mut seen = HashSet::new();
+        let mut changed = 0;
+
+        for relative in &scan.files {
+            seen.insert(relative.clone());
+            if self.update_file(root, relative) {
+                changed += 1;
+            }
+        }
+
+        let removed: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect();
+        for path in &removed {
+            self.remove_file(root, path);
+        }
+        changed + removed.len()
+    }
+
+    /// Re-read one file (absolute, or relative to `root`) if it changed
+    ///
+    /// Files that no longer exist or are binary are dropped. Returns true if
+    /// the index changed.
+    pub fn update_file(&mut self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        let full_path = root.join(&relative);
+        let metadata = match std::fs::metadata(&full_path) {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return self.remove_file(root, &relative),
+        };
+        let modified = modified_time(&metadata);
+        if self
+            .files
+            .get(&relative)
+            .is_some_and(|file| file.modified == modified)
+        {
+            return false;
+        }
+
+        let trigrams = if metadata.len() > MAX_INDEXED_SIZE {
+            None
+        } else {
+            match std::fs::read(&full_path) {
+                Ok(bytes) if !is_binary(&bytes) => Some(trigrams(&bytes)),
+                _ => return self.remove_file(root, &relative),
+            }
+        };
+        self.insert(relative, IndexedFile { modified, trigrams });
+        true
+    }
+
+    /// Drop a file (absolute, or relative to `root`); true if it was indexed
+    pub fn remove_file(&mut self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let Some(file) = self.files.remove(relative) else {
+            return false;
+        };
+        if let (Some(id), Some(trigrams)) = (self.ids.remove(relative), file.trigrams) {
+            self.remove_postings(id, &trigrams);
+        }
+        self.dirty = true;
+        true
+    }
+
+    fn insert(&mut self, path: PathBuf, file: IndexedFile) {
+        if let Some(old) = self.files.remove(&path) {
+            if let (Some(&id), Some(trigrams)) = (self.ids.get(&path), old.trigrams) {
+                self.remove_postings(id, &trigrams);
+            }
+        }
+        let id = match self.ids.get(&path) {
+            Some(&id) => id,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.ids.insert(path.clone(), id);
+                id
+            }
+        };
+        for &trigram in file.trigrams.iter().flatten() {
+            let ids = self.postings.entry(trigram).or_default();
+            if let Err(pos) = ids.binary_search(&id) {
+                ids.insert(pos, id);
+            }
+        }
+        self.files.insert(path, file);
+        self.dirty = true;
+    }
+
+    fn remove_postings(&mut self, id: u32, trigrams: &[u32]) {
+        for trigram in trigrams {
+            if let Some(ids) = self.postings.get_mut(trigram) {
+                if let Ok(pos) = ids.binary_search(&id) {
+                    ids.remove(pos);
+                }
+                if ids.is_empty() {
+                    self.postings.remove(trigram);
+                }
+            }
+        }
+    }
+
+    /// Files (relative to `root`) that may contain `query`, sorted
+    ///
+    /// These are the indexed files with all of the query's trigrams, plus
+    /// the files that have to be searched directly. Only files under `dir`
+    /// (absolute, or relative to `root`) are considered if it is given.
+    pub fn files_to_search(&self, root: &Path, query: &str, dir: Option<&Path>) -> Vec<PathBuf> {
+        let dir: Option<PathBuf> = dir.map(|dir| {
+            dir.strip_prefix(root)
+                .unwrap_or(dir)
+                .components()
+                .filter(|c| !matches!(c, Component::CurDir))
+                .collect()
+        });
+        let query_trigrams = trigrams(query.as_bytes());
+        let candidates: Option<HashSet<u32>> = if query.len() < 3 {
+            None
+        } else {
+            let mut lists: Vec<&Vec<u32>> = Vec::new();
+            for trigram in &query_trigrams {
+                match self.postings.get(trigram) {
+                    Some(ids) => lists.push(ids),
+                    None => {
+                        lists.clear();
+                        break;
+                    }
+                }
+            }
+            lists.sort_by_key(|ids| ids.len());
+            let ids = match lists.split_first() {
+                Some((first, rest)) => first
+                    .iter()
+                    .filter(|id| rest.iter().all(|ids| ids.binary_search(id).is_ok()))
+                    .copied()
+                    .collect(),
+                None => HashSet::new(),
+            };
+            Some(ids)
+        };
+
+        let mut files: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(path, _)| dir.as_ref().is_none_or(|dir| path.starts_with(dir)))
+            .filter(|(path, file)| {
+                let indexed_match = match (&candidates, file.trigrams.is_some()) {
+                    (None, _) | (_, false) => return true,
+                    (Some(ids), true) => self.ids.get(*path).is_some_and(|id| ids.contains(id)),
+                };
+                // Files changed on disk since they were indexed
+                indexed_match
+                    || std::fs::metadata(root.join(path))
+                        .is_ok_and(|metadata| modified_time(&metadata) != file.modified)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Number of indexed files
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the index changed since it was loaded or saved
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Save the index to a file
+    ///
+    /// The trigrams of a file are stored as variable-length deltas, which
+    /// keeps the index a fraction of the size of the project.
+    pub fn save_to_file(&mut self, path: &Path) -> std::io::Result<()> {
+        let mut out = MAGIC.to_vec();
+        write_varint(&mut out, self.files.len() as u64);
+        for (file_path, file) in &self.files {
+            let name = file_path.to_string_lossy();
+            write_varint(&mut out, name.len() as u64);
+            out.extend_from_slice(name.as_bytes());
+            write_varint(&mut out, file.modified);
+            match &file.trigrams {
+                Some(trigrams) => {
+                    write_varint(&mut out, trigrams.len() as u64 + 1);
+                    let mut previous = 0;
+                    for &trigram in trigrams {
+                        write_varint(&mut out, (trigram - previous) as u64);
+                        previous = trigram;
+                    }
+                }
+                None => write_varint(&mut out, 0),
+            }
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, out)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Load the index from a file (empty if the file doesn't exist)
+    pub fn load_from_file(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let bytes = std::fs::read(path)?;
+        let invalid =
+            || std::io::Error::new(std::io::ErrorKind::InvalidData, "corrupt search index");
+        let mut data = bytes.strip_prefix(MAGIC).ok_or_else(invalid)?;
+
+        let mut index = Self::new();
+        let count = read_varint(&mut data).ok_or_else(invalid)?;
+        for _ in 0..count {
+            let len = read_varint(&mut data).ok_or_else(invalid)? as usize;
+            if data.len() < len {
+                return Err(invalid());
+            }
+            let (name, rest) = data.split_at(len);
+            data = rest;
+            let file_path = PathBuf::from(String::from_utf8_lossy(name).into_owned());
+            let modified = read_varint(&mut data).ok_or_else(invalid)?;
+            let trigrams = match read_varint(&mut data).ok_or_else(invalid)? {
+                0 => None,
+                n => {
+                    let mut trigrams = Vec::with_capacity(n as usize - 1);
+                    let mut previous = 0u32;
+                    for _ in 1..n {
+                        let delta = read_varint(&mut data).ok_or_else(invalid)? as u32;
+                        previous = previous.checked_add(delta).ok_or_else(invalid)?;
+                        trigrams.push(previous);
+                    }
+                    Some(trigrams)
+                }
+            };
+            index.insert(file_path, IndexedFile { modified, trigrams });
+        }
+        index.dirty = false;
+        Ok(index)
+    }
+}
+
+/// Search files (relative to `root`) for lines containing `query`
+///
+/// Smart case, like the other searches: the query matches case-insensitively
+/// unless it has uppercase letters. Files are read line by line, and the
+/// search stops after `max_results` matches.
+pub fn search_files(
+    root: &Path,
+    files: &[PathBuf],
+    query: &str,
+    max_results: usize,
+) -> Vec<SearchMatch> {
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let needle = if case_sensitive {
+        query.to_string()
+    } else {
+        query.to_ascii_lowercase()
+    };
+
+    let mut matches = Vec::new();
+    if needle.is_empty() {
+        return matches;
+    }
+    for path in files {
+        let Ok(file) = std::fs::File::open(root.join(path)) else {
+            continue;
+        };
+        let mut reader = BufReader::new(file);
+        let mut bytes = Vec::new();
+        let mut line = 0;
+        loop {
+            bytes.clear();
+            match reader.read_until(b'\n', &mut bytes) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            line += 1;
+            if bytes[..bytes.len().min(BINARY_CHECK_LEN)].contains(&0) {
+                break;
+            }
+            let text = String::from_utf8_lossy(&bytes);
+            let text = text.trim_end_matches(['\n', '\r']);
+            let found = if case_sensitive {
+                text.find(&needle)
+            } else {
+                text.to_ascii_lowercase().find(&needle)
+            };
+            if let Some(column) = found {
+                matches.push(SearchMatch {
+                    path: path.clone(),
+                    line,
+                    column: column + 1,
+                    content: text.to_string(),
+                });
+                if matches.len() >= max_results {
+                    return matches;
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Whether the index can answer a query: plain text, with no regex syntax
+pub fn is_plain_text(query: &str) -> bool {
+    !query.is_empty() && regex::escape(query) == query
+}
+
+/// Get the file holding the search index of a working directory
+pub fn get_search_index_path(working_dir: &Path) -> std::io::Result<PathBuf> {
+    let canonical = working_dir
+        .canonicalize()
+        .unwrap_or_else(|_| working_dir.to_path_buf());
+    let filename = format!(
+        "{}.idx",
+        crate::session::encode_path_for_filename(&canonical)
+    );
+    Ok(crate::input::input_history::get_data_dir()?
+        .join("search")
+        .join(filename))
+}
+
+/// Sorted, distinct trigrams of the ASCII-lowercased bytes
+fn trigrams(bytes: &[u8]) -> Vec<u32> {
+    let mut trigrams: Vec<u32> = bytes
+        .windows(3)
+        .map(|w| {
+            u32::from_be_bytes([
+                0,
+                w[0].to_ascii_lowercase(),
+                w[1].to_ascii_lowercase(),
+                w[2].to_ascii_lowercase(),
+            ])
+        })
+        .collect();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_CHECK_LEN)].contains(&0)
+}
+
+fn modified_time(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "fn main() {\n    println!(\"Hello\");\n}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "say hello to everyone\n").unwrap();
+        std::fs::write(dir.path().join("image.bin"), b"hello\0world").unwrap();
+        dir
+    }
+
+    fn scan(root: &Path) -> ProjectScan {
+        crate::services::project_scan::scan_project(root, &Default::default(), &|_| {})
+    }
+
+    fn search(index: &SearchIndex, root: &Path, query: &str) -> Vec<(String, usize, usize)> {
+        let files = index.files_to_search(root, query, None);
+        search_files(root, &files, query, 100)
+            .into_iter()
+            .map(|m| (m.path.to_string_lossy().into_owned(), m.line, m.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_search_uses_trigrams() {
+        let dir = project();
+        let root = dir.path();
+        let mut index = SearchIndex::new();
+        assert_eq!(index.update(root, &scan(root)), 2);
+        assert_eq!(index.file_count(), 2);
+
+        // Smart case
+        assert_eq!(
+            search(&index, root, "hello"),
+            vec![
+                ("notes.txt".to_string(), 1, 5),
+                ("src/main.rs".to_string(), 2, 15)
+            ]
+        );
+        assert_eq!(
+            search(&index, root, "Hello"),
+            vec![("src/main.rs".to_string(), 2, 15)]
+        );
+        // Only files with all the trigrams are read
+        assert_eq!(
+            index.files_to_search(root, "println", None),
+            vec![PathBuf::from("src/main.rs")]
+        );
+        assert!(index.files_to_search(root, "missing", None).is_empty());
+        assert_eq!(
+            index.files_to_search(root, "hello", Some(Path::new("./src"))),
+            vec![PathBuf::from("src/main.rs")]
+        );
+    }
+
+    #[test]
+    fn test_files_changed_on_disk_are_searched() {
+        let dir = project();
+        let root = dir.path();
+        let mut index = SearchIndex::new();
+        index.update(root, &scan(root));
+
+        // Not re-indexed, but its modification time changed
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write(root.join("notes.txt"), "a new word\n").unwrap();
+        assert_eq!(
+            search(&index, root, "new word"),
+            vec![("notes.txt".to_string(), 1, 3)]
+        );
+
+        // Re-indexing updates the trigrams
+        assert!(index.update_file(root, &root.join("notes.txt")));
+        assert!(search(&index, root, "hello to").is_empty());
+        assert_eq!(
+            index.files_to_search(root, "new word", None),
+            vec![PathBuf::from("notes.txt")]
+        );
+
+        std::fs::remove_file(root.join("notes.txt")).unwrap();
+        assert_eq!(index.update(root, &scan(root)), 1);
+        assert!(index.files_to_search(root, "new word", None).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = project();
+        let root = dir.path();
+        let mut index = SearchIndex::new();
+        index.update(root, &scan(root));
+
+        let data_dir = tempfile::tempdir().unwrap();
+        let path = data_dir.path().join("search/project.idx");
+        index.save_to_file(&path).unwrap();
+        assert!(!index.is_dirty());
+
+        let mut loaded = SearchIndex::load_from_file(&path).unwrap();
+        assert_eq!(loaded.file_count(), 2);
+        assert!(!loaded.is_dirty());
+        assert_eq!(
+            search(&loaded, root, "println"),
+            vec![("src/main.rs".to_string(), 2, 5)]
+        );
+        // Nothing changed on disk
+        assert_eq!(loaded.update(root, &scan(root)), 0);
+    }
+}
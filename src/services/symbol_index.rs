@@ -0,0 +1,441 @@
+//! Project-wide symbol index (like ctags)
+//!
+//! Definitions (functions, types, modules, ...) are found with per-language
+//! patterns, so "Go To Symbol in Workspace" and go-to-definition work without
+//! a language server. The index is stored per project in the data directory
+//! and updated incrementally: files whose modification time didn't change
+//! aren't read again, and files that disappeared are dropped.
+
+use super::project_scan::ProjectScan;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+/// Files larger than this aren't indexed (generated code, bundles, ...)
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// What a symbol defines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Type,
+    Module,
+    Constant,
+    Macro,
+}
+
+impl SymbolKind {
+    /// Name shown next to the symbol
+    pub fn label(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Type => "type",
+            SymbolKind::Module => "module",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Macro => "macro",
+        }
+    }
+}
+
+/// A definition found in a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 0-indexed line of the definition
+    pub line: usize,
+    /// Byte offset of the name within its line
+    pub column: usize,
+}
+
+/// Symbols of one file, with the modification time they were read at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileSymbols {
+    modified: u64,
+    symbols: Vec<Symbol>,
+}
+
+/// Symbols of all the files in a project, by path relative to its root
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    files: HashMap<PathBuf, FileSymbols>,
+    /// Changed since it was loaded or saved
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl SymbolIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the index up to date with the scanned files of the project at
+    /// `root`
+    ///
+    /// Returns the number of files that were (re)indexed or dropped.
+    pub fn update(&mut self, root: &Path, scan: &ProjectScan) -> usize {
+        let mut seen = std::collections::HashSet::new();
+        let mut changed = 0;
+
+        for relative in &scan.files {
+            if rules_for(relative).is_none() {
+                continue;
+            }
+            seen.insert(relative.clone());
+            if self.update_file(root, relative) {
+                changed += 1;
+            }
+        }
+
+        let before = self.files.len();
+        self.files.retain(|path, _| seen.contains(path));
+        let removed = before - self.files.len();
+        if removed > 0 {
+            self.dirty = true;
+        }
+        changed + removed
+    }
+
+    /// Re-read one file (absolute, or relative to `root`) if it changed
+    ///
+    /// A file that no longer exists is dropped. Returns true if the index
+    /// changed.
+    pub fn update_file(&mut self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        let Some(rules) = rules_for(&relative) else {
+            return false;
+        };
+
+        let metadata = match std::fs::metadata(root.join(&relative)) {
+            Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_FILE_SIZE => metadata,
+            _ => return self.remove_file(root, &relative),
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        if self
+            .files
+            .get(&relative)
+            .is_some_and(|file| file.modified == modified)
+        {
+            return false;
+        }
+
+        let Ok(text) = std::fs::read_to_string(root.join(&relative)) else {
+            return self.remove_file(root, &relative);
+        };
+        let symbols = extract(&text, rules);
+        self.files
+            .insert(relative, FileSymbols { modified, symbols });
+        self.dirty = true;
+        true
+    }
+
+    /// Drop a file (absolute, or relative to `root`); true if it was indexed
+    pub fn remove_file(&mut self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let removed = self.files.remove(relative).is_some();
+        self.dirty |= removed;
+        removed
+    }
+
+    /// Definitions named `name`, as (relative path, symbol)
+    pub fn find(&self, name: &str) -> Vec<(&Path, &Symbol)> {
+        let mut found: Vec<_> = self
+            .symbols()
+            .filter(|(_, symbol)| symbol.name == name)
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(b.0).then(a.1.line.cmp(&b.1.line)));
+        found
+    }
+
+    /// All symbols, as (relative path, symbol)
+    pub fn symbols(&self) -> impl Iterator<Item = (&Path, &Symbol)> {
+        self.files.iter().flat_map(|(path, file)| {
+            file.symbols
+                .iter()
+                .map(move |symbol| (path.as_path(), symbol))
+        })
+    }
+
+    /// Number of indexed files
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the index changed since it was loaded or saved
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Save the index to a file
+    pub fn save_to_file(&mut self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Load the index from a file (empty if the file doesn't exist)
+    pub fn load_from_file(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(std::io::Error::other)
+    }
+}
+
+/// Get the file holding the symbol index of a working directory
+pub fn get_symbol_index_path(working_dir: &Path) -> std::io::Result<PathBuf> {
+    let canonical = working_dir
+        .canonicalize()
+        .unwrap_or_else(|_| working_dir.to_path_buf());
+    let filename = format!(
+        "{}.json",
+        crate::session::encode_path_for_filename(&canonical)
+    );
+    Ok(crate::input::input_history::get_data_dir()?
+        .join("symbols")
+        .join(filename))
+}
+
+/// Patterns finding definitions in the files with some extensions
+struct LanguageRules {
+    extensions: &'static [&'static str],
+    /// Each pattern captures the symbol's name as `name`
+    patterns: Vec<(Regex, SymbolKind)>,
+}
+
+/// Definition patterns of the supported languages
+fn languages() -> &'static [LanguageRules] {
+    static LANGUAGES: OnceLock<Vec<LanguageRules>> = OnceLock::new();
+    LANGUAGES.get_or_init(|| {
+        let rules = |extensions, patterns: &[(&str, SymbolKind)]| LanguageRules {
+            extensions,
+            patterns: patterns
+                .iter()
+                .map(|(pattern, kind)| (Regex::new(&format!("(?m){}", pattern)).unwrap(), *kind))
+                .collect(),
+        };
+        use SymbolKind::*;
+        vec![
+            rules(
+                &["rs"],
+                &[
+                    (r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*fn\s+(?P<name>\w+)"#, Function),
+                    (r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:struct|enum|union|trait|type)\s+(?P<name>\w+)", Type),
+                    (r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(?P<name>\w+)", Module),
+                    (r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const|static)\s+(?:mut\s+)?(?P<name>\w+)\s*:", Constant),
+                    (r"^\s*macro_rules!\s*(?P<name>\w+)", Macro),
+                ],
+            ),
+            rules(
+                &["py", "pyi"],
+                &[
+                    (r"^\s*(?:async\s+)?def\s+(?P<name>\w+)", Function),
+                    (r"^\s*class\s+(?P<name>\w+)", Type),
+                ],
+            ),
+            rules(
+                &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"],
+                &[
+                    (r"^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\*?\s+(?P<name>[\w$]+)", Function),
+                    (r"^\s*(?:export\s+)?(?:const|let)\s+(?P<name>[\w$]+)\s*=\s*(?:async\s*)?(?:\([^)]*\)|[\w$]+)\s*=>", Function),
+                    (r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:class|interface|enum|type)\s+(?P<name>[\w$]+)", Type),
+                    (r"^\s*(?:export\s+)?(?:declare\s+)?namespace\s+(?P<name>[\w$.]+)", Module),
+                ],
+            ),
+            rules(
+                &["go"],
+                &[
+                    (r"^func\s+(?:\([^)]*\)\s*)?(?P<name>\w+)", Function),
+                    (r"^type\s+(?P<name>\w+)", Type),
+                ],
+            ),
+            rules(
+                &["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"],
+                &[
+                    (r"^(?:[\w*&:<>]+[ \t]+)+[*&]*(?P<name>[A-Za-z_][\w:]*)\s*\([^;]*$", Function),
+                    (r"^\s*(?:typedef\s+)?(?:struct|class|enum(?:\s+class)?|union)\s+(?P<name>\w+)\s*(?:[:{]|$)", Type),
+                    (r"^\s*namespace\s+(?P<name>\w+)", Module),
+                    (r"^\s*#\s*define\s+(?P<name>\w+)", Macro),
+                ],
+            ),
+            rules(
+                &["java", "cs", "kt"],
+                &[
+                    (r"^\s*(?:(?:public|private|protected|internal|static|abstract|sealed|final|partial|data)\s+)*(?:class|interface|enum|record|struct|object)\s+(?P<name>\w+)", Type),
+                    (r"^\s*fun\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(?P<name>\w+)", Function),
+                ],
+            ),
+            rules(
+                &["rb"],
+                &[
+                    (r"^\s*def\s+(?:self\.)?(?P<name>[\w?!=]+)", Function),
+                    (r"^\s*class\s+(?P<name>[\w:]+)", Type),
+                    (r"^\s*module\s+(?P<name>[\w:]+)", Module),
+                ],
+            ),
+            rules(
+                &["lua"],
+                &[(r"^\s*(?:local\s+)?function\s+(?P<name>[\w.:]+)", Function)],
+            ),
+            rules(
+                &["sh", "bash", "zsh"],
+                &[(r"^\s*(?:function\s+)?(?P<name>[\w-]+)\s*\(\)", Function)],
+            ),
+        ]
+    })
+}
+
+/// Rules for a file, by its extension (None if its language isn't indexed)
+fn rules_for(path: &Path) -> Option<&'static LanguageRules> {
+    let extension = path.extension()?.to_str()?;
+    languages()
+        .iter()
+        .find(|rules| rules.extensions.contains(&extension))
+}
+
+/// Find the definitions in `text`, in order
+fn extract(text: &str, rules: &LanguageRules) -> Vec<Symbol> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut symbols: Vec<Symbol> = rules
+        .patterns
+        .iter()
+        .flat_map(|(pattern, kind)| {
+            pattern.captures_iter(text).filter_map(|captures| {
+                let name = captures.name("name")?;
+                let line = line_starts.partition_point(|&start| start <= name.start()) - 1;
+                Some(Symbol {
+                    name: name.as_str().to_string(),
+                    kind: *kind,
+                    line,
+                    column: name.start() - line_starts[line],
+                })
+            })
+        })
+        .collect();
+    symbols.sort_by_key(|symbol| (symbol.line, symbol.column));
+    symbols.dedup_by_key(|symbol| (symbol.line, symbol.column));
+    symbols
+}
+
+/// Find the definitions in the text of `path` (none if its language isn't indexed)
+pub fn extract_symbols(path: &Path, text: &str) -> Vec<Symbol> {
+    rules_for(path)
+        .map(|rules| extract(text, rules))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::file_filter::FileFilter;
+    use crate::services::project_scan::scan_project;
+
+    fn names(symbols: &[Symbol]) -> Vec<(&str, SymbolKind, usize)> {
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_rust() {
+        let text = "pub mod util;\n\npub(crate) struct Point {\n    x: i32,\n}\n\nimpl Point {\n    pub async fn len(&self) -> i32 { 0 }\n}\n\nconst MAX: usize = 3;\nmacro_rules! square { () => {} }\n";
+        let symbols = extract_symbols(Path::new("lib.rs"), text);
+        assert_eq!(
+            names(&symbols),
+            [
+                ("util", SymbolKind::Module, 0),
+                ("Point", SymbolKind::Type, 2),
+                ("len", SymbolKind::Function, 7),
+                ("MAX", SymbolKind::Constant, 10),
+                ("square", SymbolKind::Macro, 11),
+            ]
+        );
+        assert_eq!(symbols[2].column, "    pub async fn ".len());
+    }
+
+    #[test]
+    fn test_extract_other_languages() {
+        let python = "class Shape:\n    def area(self):\n        pass\n";
+        assert_eq!(
+            names(&extract_symbols(Path::new("shape.py"), python)),
+            [
+                ("Shape", SymbolKind::Type, 0),
+                ("area", SymbolKind::Function, 1)
+            ]
+        );
+
+        let typescript = "export interface Options {}\nexport const run = async (x) => x;\nfunction helper() {}\n";
+        assert_eq!(
+            names(&extract_symbols(Path::new("main.ts"), typescript)),
+            [
+                ("Options", SymbolKind::Type, 0),
+                ("run", SymbolKind::Function, 1),
+                ("helper", SymbolKind::Function, 2)
+            ]
+        );
+
+        assert!(extract_symbols(Path::new("notes.txt"), "fn main() {}").is_empty());
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.rs"), "fn alpha() {}\n").unwrap();
+        std::fs::write(root.join("b.rs"), "fn beta() {}\n").unwrap();
+        std::fs::write(root.join("c.min.js"), "function gamma() {}\n").unwrap();
+
+        let filter = FileFilter::new(
+            root,
+            &crate::config::FilesConfig {
+                include: Vec::new(),
+                exclude: vec!["*.min.js".to_string()],
+            },
+            &crate::config::FilesConfig::default(),
+        )
+        .unwrap();
+        let mut index = SymbolIndex::new();
+        assert_eq!(index.update(root, &scan_project(root, &filter, &|_| {})), 2);
+        assert_eq!(index.find("alpha")[0].0, Path::new("a.rs"));
+        assert!(index.find("gamma").is_empty());
+
+        // Unchanged files aren't read again
+        assert_eq!(index.update(root, &scan_project(root, &filter, &|_| {})), 0);
+
+        // A saved file is re-read, a deleted one dropped
+        std::fs::write(root.join("a.rs"), "fn alpha2() {}\n").unwrap();
+        index.files.get_mut(Path::new("a.rs")).unwrap().modified = 0;
+        assert!(index.update_file(root, &root.join("a.rs")));
+        assert!(index.find("alpha").is_empty());
+        assert_eq!(index.find("alpha2").len(), 1);
+
+        std::fs::remove_file(root.join("b.rs")).unwrap();
+        assert_eq!(index.update(root, &scan_project(root, &filter, &|_| {})), 1);
+        assert_eq!(index.file_count(), 1);
+
+        // Saved and loaded again
+        let path = root.join("index/symbols.json");
+        index.save_to_file(&path).unwrap();
+        assert!(!index.is_dirty());
+        let loaded = SymbolIndex::load_from_file(&path).unwrap();
+        assert_eq!(loaded.find("alpha2").len(), 1);
+    }
+}
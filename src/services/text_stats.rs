@@ -0,0 +1,306 @@
+//! Counting text: lines, words and characters, and lines of code
+//!
+//! "Buffer Statistics" counts the words and characters of a buffer or
+//! selection with [`text_counts`], and breaks the project's files down into
+//! code, comment and blank lines per language with [`project_code_stats`].
+//! Comments are recognized by their delimiters only, so a comment marker
+//! inside a string is taken for a comment.
+
+use std::path::{Path, PathBuf};
+
+/// Files larger than this aren't counted
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Lines, words and characters of some text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCounts {
+    pub lines: usize,
+    /// Runs of non-whitespace characters
+    pub words: usize,
+    pub chars: usize,
+}
+
+/// Count the lines, words and characters of `text`
+pub fn text_counts(text: &str) -> TextCounts {
+    TextCounts {
+        lines: text.lines().count(),
+        words: text.split_whitespace().count(),
+        chars: text.chars().count(),
+    }
+}
+
+/// How a language writes comments
+#[derive(Debug, Clone, Copy)]
+pub struct CommentSyntax {
+    /// Prefixes of line comments
+    pub line: &'static [&'static str],
+    /// Delimiters of block comments
+    pub block: Option<(&'static str, &'static str)>,
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: Some(("/*", "*/")),
+};
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: None,
+};
+const NONE: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: None,
+};
+
+/// Languages counted, with their extensions
+const LANGUAGES: &[(&str, &[&str], CommentSyntax)] = &[
+    ("Rust", &["rs"], C_LIKE),
+    ("C", &["c", "h"], C_LIKE),
+    ("C++", &["cpp", "cc", "cxx", "hpp", "hxx"], C_LIKE),
+    ("C#", &["cs"], C_LIKE),
+    ("Go", &["go"], C_LIKE),
+    ("Java", &["java"], C_LIKE),
+    ("Kotlin", &["kt", "kts"], C_LIKE),
+    ("Swift", &["swift"], C_LIKE),
+    ("JavaScript", &["js", "jsx", "mjs", "cjs"], C_LIKE),
+    ("TypeScript", &["ts", "tsx"], C_LIKE),
+    (
+        "CSS",
+        &["css"],
+        CommentSyntax {
+            line: &[],
+            block: Some(("/*", "*/")),
+        },
+    ),
+    (
+        "HTML",
+        &["html", "htm"],
+        CommentSyntax {
+            line: &[],
+            block: Some(("<!--", "-->")),
+        },
+    ),
+    ("Python", &["py", "pyi"], HASH),
+    ("Ruby", &["rb"], HASH),
+    ("Shell", &["sh", "bash", "zsh"], HASH),
+    ("TOML", &["toml"], HASH),
+    ("YAML", &["yaml", "yml"], HASH),
+    (
+        "Lua",
+        &["lua"],
+        CommentSyntax {
+            line: &["--"],
+            block: Some(("--[[", "]]")),
+        },
+    ),
+    (
+        "SQL",
+        &["sql"],
+        CommentSyntax {
+            line: &["--"],
+            block: Some(("/*", "*/")),
+        },
+    ),
+    (
+        "Haskell",
+        &["hs"],
+        CommentSyntax {
+            line: &["--"],
+            block: Some(("{-", "-}")),
+        },
+    ),
+    ("JSON", &["json"], NONE),
+    ("Markdown", &["md", "markdown"], NONE),
+];
+
+/// Name and comment syntax of the language of `path`, by its extension
+pub fn language_for_path(path: &Path) -> Option<(&'static str, CommentSyntax)> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(_, extensions, _)| extensions.contains(&extension.as_str()))
+        .map(|&(name, _, syntax)| (name, syntax))
+}
+
+/// Code, comment and blank lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+}
+
+impl LineCounts {
+    pub fn total(&self) -> usize {
+        self.code + self.comment + self.blank
+    }
+
+    fn add(&mut self, other: LineCounts) {
+        self.code += other.code;
+        self.comment += other.comment;
+        self.blank += other.blank;
+    }
+}
+
+/// Split the lines of `text` into code, comment and blank lines. A line with
+/// both code and a comment counts as code.
+pub fn count_lines(text: &str, syntax: &CommentSyntax) -> LineCounts {
+    let mut counts = LineCounts::default();
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if in_block {
+            counts.comment += 1;
+            if let Some((_, close)) = syntax.block {
+                in_block = !line.contains(close);
+            }
+            continue;
+        }
+        if line.is_empty() {
+            counts.blank += 1;
+            continue;
+        }
+        if let Some((open, close)) = syntax.block {
+            if let Some(rest) = line.strip_prefix(open) {
+                counts.comment += 1;
+                in_block = !rest.contains(close);
+                continue;
+            }
+        }
+        if syntax.line.iter().any(|prefix| line.starts_with(prefix)) {
+            counts.comment += 1;
+            continue;
+        }
+        counts.code += 1;
+        // A block comment opened after code
+        if let Some((open, close)) = syntax.block {
+            if let Some(start) = line.rfind(open) {
+                in_block = !line[start + open.len()..].contains(close);
+            }
+        }
+    }
+    counts
+}
+
+/// Lines of one language in the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageStats {
+    pub language: &'static str,
+    pub files: usize,
+    pub lines: LineCounts,
+}
+
+/// Count the lines of the project's `files` (relative to `root`) per
+/// language, most code first. Files in no known language, unreadable files
+/// and files over 1 MiB are skipped.
+pub fn project_code_stats(root: &Path, files: &[PathBuf]) -> Vec<LanguageStats> {
+    let mut stats: Vec<LanguageStats> = Vec::new();
+    for relative in files {
+        let Some((language, syntax)) = language_for_path(relative) else {
+            continue;
+        };
+        let path = root.join(relative);
+        if !std::fs::metadata(&path).is_ok_and(|m| m.is_file() && m.len() <= MAX_FILE_SIZE) {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let lines = count_lines(&text, &syntax);
+        match stats.iter_mut().find(|s| s.language == language) {
+            Some(entry) => {
+                entry.files += 1;
+                entry.lines.add(lines);
+            }
+            None => stats.push(LanguageStats {
+                language,
+                files: 1,
+                lines,
+            }),
+        }
+    }
+    stats.sort_by(|a, b| {
+        b.lines
+            .code
+            .cmp(&a.lines.code)
+            .then(a.language.cmp(b.language))
+    });
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_text_counts() {
+        assert_eq!(text_counts(""), TextCounts::default());
+        assert_eq!(
+            text_counts("hello  world\n\tfoo-bar é\n"),
+            TextCounts {
+                lines: 2,
+                words: 4,
+                chars: 24,
+            }
+        );
+    }
+
+    #[test]
+    fn test_count_lines_splits_code_comments_and_blanks() {
+        let text = "\
+// header
+fn main() {
+    /* one
+       two */
+
+    let x = 1; /* trailing
+    still comment */
+    println!(); // not a comment line
+}
+";
+        let (_, syntax) = language_for_path(Path::new("main.rs")).unwrap();
+        assert_eq!(
+            count_lines(text, &syntax),
+            LineCounts {
+                code: 4,
+                comment: 4,
+                blank: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_project_code_stats_groups_by_language() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.py"), "# c\nx = 1\n\n").unwrap();
+        std::fs::write(dir.path().join("b.py"), "y = 2\n").unwrap();
+        std::fs::write(dir.path().join("c.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(dir.path().join("notes.xyz"), "hello\n").unwrap();
+        let files = ["a.py", "b.py", "c.rs", "notes.xyz"].map(PathBuf::from);
+
+        let stats = project_code_stats(dir.path(), &files);
+        assert_eq!(
+            stats,
+            vec![
+                LanguageStats {
+                    language: "Python",
+                    files: 2,
+                    lines: LineCounts {
+                        code: 2,
+                        comment: 1,
+                        blank: 1,
+                    },
+                },
+                LanguageStats {
+                    language: "Rust",
+                    files: 1,
+                    lines: LineCounts {
+                        code: 2,
+                        comment: 0,
+                        blank: 0,
+                    },
+                },
+            ]
+        );
+    }
+}
@@ -18,6 +18,19 @@
 //!
 //! The encoding is fully reversible using `decode_filename_to_path()`.
 //!
+//! ## Snapshots
+//!
+//! Besides the session saved on exit, the session is snapshotted periodically
+//! to `$XDG_DATA_HOME/fresh/sessions/snapshots/{encoded_path}/{saved_at}.json`.
+//! Only the most recent snapshots are kept, so the session can be restored
+//! after a crash even though the exit-time save never happened.
+//!
+//! ## Named Sessions
+//!
+//! A project can also have named sessions (e.g., "work", "review"), stored in
+//! `$XDG_DATA_HOME/fresh/sessions/named/{encoded_path}/{encoded_name}.json`.
+//! The name is encoded the same way as the working directory.
+//!
 //! ## Crash Resistance
 //!
 //! Uses atomic writes: write to temp file, then rename.
@@ -71,6 +84,10 @@ pub struct Session {
     #[serde(default)]
     pub bookmarks: HashMap<char, SerializedBookmark>,
 
+    /// Name of the session, for named sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
     /// Timestamp when session was saved (Unix epoch seconds)
     pub saved_at: u64,
 }
@@ -263,7 +280,7 @@ pub fn get_sessions_dir() -> io::Result<PathBuf> {
 /// Percent-encodes other special characters as %XX.
 ///
 /// Example: `/home/user/my project` -> `home_user_my%20project`
-fn encode_path_for_filename(path: &Path) -> String {
+pub(crate) fn encode_path_for_filename(path: &Path) -> String {
     let path_str = path.to_string_lossy();
     let mut result = String::with_capacity(path_str.len() * 2);
 
@@ -351,6 +368,78 @@ pub fn get_session_path(working_dir: &Path) -> io::Result<PathBuf> {
     Ok(get_sessions_dir()?.join(filename))
 }
 
+/// Get the directory holding the session snapshots for a working directory
+pub fn get_snapshots_dir(working_dir: &Path) -> io::Result<PathBuf> {
+    let canonical = working_dir
+        .canonicalize()
+        .unwrap_or_else(|_| working_dir.to_path_buf());
+    Ok(get_sessions_dir()?
+        .join("snapshots")
+        .join(encode_path_for_filename(&canonical)))
+}
+
+/// Get the directory holding the named sessions for a working directory
+pub fn get_named_sessions_dir(working_dir: &Path) -> io::Result<PathBuf> {
+    let canonical = working_dir
+        .canonicalize()
+        .unwrap_or_else(|_| working_dir.to_path_buf());
+    Ok(get_sessions_dir()?
+        .join("named")
+        .join(encode_path_for_filename(&canonical)))
+}
+
+/// Get the file path of a named session
+pub fn get_named_session_path(working_dir: &Path, name: &str) -> io::Result<PathBuf> {
+    let filename = format!("{}.json", encode_path_for_filename(Path::new(name)));
+    Ok(get_named_sessions_dir(working_dir)?.join(filename))
+}
+
+/// Check that a session name can be used (returns the reason if not)
+pub fn validate_session_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Session name cannot be empty".to_string());
+    }
+    if name.contains(['/', '\\']) {
+        return Err("Session name cannot contain path separators".to_string());
+    }
+    if name != name.trim() {
+        return Err("Session name cannot start or end with spaces".to_string());
+    }
+    Ok(())
+}
+
+/// A named session on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedSessionInfo {
+    pub name: String,
+    /// When the session was last used (Unix epoch seconds)
+    pub saved_at: u64,
+}
+
+/// A session snapshot on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    /// Path of the snapshot file
+    pub path: PathBuf,
+    /// When the snapshot was taken (Unix epoch seconds)
+    pub saved_at: u64,
+}
+
+/// Write a file atomically: write to a temp file, sync, then rename
+fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = path.with_extension("json.tmp");
+    {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?; // Ensure data is on disk before rename
+    }
+    std::fs::rename(&temp_path, path)
+}
+
 /// Session error types
 #[derive(Debug)]
 pub enum SessionError {
@@ -419,8 +508,18 @@ impl Session {
             return Ok(None);
         }
 
+        Self::load_file(&path, working_dir).map(Some)
+    }
+
+    /// Load a snapshot taken by `save_snapshot`
+    pub fn load_snapshot(path: &Path, working_dir: &Path) -> Result<Session, SessionError> {
+        Self::load_file(path, working_dir)
+    }
+
+    /// Load a session file and check that it belongs to `working_dir`
+    fn load_file(path: &Path, working_dir: &Path) -> Result<Session, SessionError> {
         tracing::debug!("Loading session from {:?}", path);
-        let content = std::fs::read_to_string(&path)?;
+        let content = std::fs::read_to_string(path)?;
         let session: Session = serde_json::from_str(&content)?;
 
         tracing::debug!(
@@ -461,7 +560,7 @@ impl Session {
             });
         }
 
-        Ok(Some(session))
+        Ok(session)
     }
 
     /// Save session to file using atomic write (temp file + rename)
@@ -474,32 +573,107 @@ impl Session {
         let path = get_session_path(&self.working_dir)?;
         tracing::debug!("Saving session to {:?}", path);
 
-        // Ensure directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
         // Serialize to JSON
         let content = serde_json::to_string_pretty(self)?;
         tracing::trace!("Session JSON size: {} bytes", content.len());
 
-        // Write atomically: temp file + rename
-        let temp_path = path.with_extension("json.tmp");
+        write_atomic(&path, content.as_bytes())?;
+        tracing::info!("Session saved to {:?}", path);
+
+        Ok(())
+    }
+
+    /// Save the session as a snapshot, keeping only the `keep` most recent ones
+    ///
+    /// Returns the path of the new snapshot.
+    pub fn save_snapshot(&self, keep: usize) -> Result<PathBuf, SessionError> {
+        let dir = get_snapshots_dir(&self.working_dir)?;
+        let path = dir.join(format!("{}.json", self.saved_at));
+        tracing::debug!("Saving session snapshot to {:?}", path);
 
-        // Write to temp file
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(&path, content.as_bytes())?;
+
+        // Rotate: drop the oldest snapshots beyond `keep`
+        for old in Self::list_snapshots(&self.working_dir)?
+            .into_iter()
+            .skip(keep.max(1))
         {
-            let mut file = std::fs::File::create(&temp_path)?;
-            file.write_all(content.as_bytes())?;
-            file.sync_all()?; // Ensure data is on disk before rename
+            if let Err(e) = std::fs::remove_file(&old.path) {
+                tracing::warn!("Failed to remove old snapshot {:?}: {}", old.path, e);
+            }
         }
 
-        // Atomic rename
-        std::fs::rename(&temp_path, &path)?;
-        tracing::info!("Session saved to {:?}", path);
+        Ok(path)
+    }
+
+    /// Save the session under `name` for its working directory
+    pub fn save_named(&self, name: &str) -> Result<(), SessionError> {
+        let path = get_named_session_path(&self.working_dir, name)?;
+        tracing::debug!("Saving session '{}' to {:?}", name, path);
 
+        let mut session = self.clone();
+        session.name = Some(name.to_string());
+        let content = serde_json::to_string_pretty(&session)?;
+        write_atomic(&path, content.as_bytes())?;
         Ok(())
     }
 
+    /// Load the session named `name` for a working directory (if it exists)
+    pub fn load_named(working_dir: &Path, name: &str) -> Result<Option<Session>, SessionError> {
+        let path = get_named_session_path(working_dir, name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::load_file(&path, working_dir).map(Some)
+    }
+
+    /// Named sessions for a working directory, most recently used first
+    pub fn list_named(working_dir: &Path) -> Result<Vec<NamedSessionInfo>, SessionError> {
+        let dir = get_named_sessions_dir(working_dir)?;
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut sessions: Vec<NamedSessionInfo> = std::fs::read_dir(&dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                let content = std::fs::read_to_string(&path).ok()?;
+                let session: Session = serde_json::from_str(&content).ok()?;
+                Some(NamedSessionInfo {
+                    name: session.name?,
+                    saved_at: session.saved_at,
+                })
+            })
+            .collect();
+        sessions.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then(a.name.cmp(&b.name)));
+        Ok(sessions)
+    }
+
+    /// Snapshots for a working directory, newest first
+    pub fn list_snapshots(working_dir: &Path) -> Result<Vec<SnapshotInfo>, SessionError> {
+        let dir = get_snapshots_dir(working_dir)?;
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots: Vec<SnapshotInfo> = std::fs::read_dir(&dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                let saved_at = path.file_stem()?.to_str()?.parse().ok()?;
+                Some(SnapshotInfo { path, saved_at })
+            })
+            .collect();
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+        Ok(snapshots)
+    }
+
     /// Delete session for a working directory
     pub fn delete(working_dir: &Path) -> Result<(), SessionError> {
         let path = get_session_path(working_dir)?;
@@ -525,6 +699,7 @@ impl Session {
             histories: SessionHistories::default(),
             search_options: SearchOptions::default(),
             bookmarks: HashMap::new(),
+            name: None,
             saved_at: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or_default()
@@ -846,6 +1021,68 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_snapshot_rotation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let working_dir = temp_dir.path().to_path_buf();
+
+        let mut session = Session::new(working_dir.clone());
+        for saved_at in 100..104 {
+            session.saved_at = saved_at;
+            session.save_snapshot(3).unwrap();
+        }
+
+        // Only the three newest are kept, newest first
+        let snapshots = Session::list_snapshots(&working_dir).unwrap();
+        let times: Vec<u64> = snapshots.iter().map(|s| s.saved_at).collect();
+        assert_eq!(times, vec![103, 102, 101]);
+
+        let loaded = Session::load_snapshot(&snapshots[0].path, &working_dir).unwrap();
+        assert_eq!(loaded.saved_at, 103);
+
+        // Snapshots belong to their working directory
+        let other = tempfile::TempDir::new().unwrap();
+        assert!(matches!(
+            Session::load_snapshot(&snapshots[0].path, other.path()),
+            Err(SessionError::WorkdirMismatch { .. })
+        ));
+
+        std::fs::remove_dir_all(get_snapshots_dir(&working_dir).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_named_sessions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let working_dir = temp_dir.path().to_path_buf();
+
+        let mut session = Session::new(working_dir.clone());
+        session.saved_at = 100;
+        session.save_named("work").unwrap();
+        session.saved_at = 200;
+        session.save_named("side project").unwrap();
+
+        let names: Vec<String> = Session::list_named(&working_dir)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["side project", "work"]);
+
+        let loaded = Session::load_named(&working_dir, "work").unwrap().unwrap();
+        assert_eq!(loaded.name.as_deref(), Some("work"));
+        assert_eq!(loaded.saved_at, 100);
+        assert!(Session::load_named(&working_dir, "missing")
+            .unwrap()
+            .is_none());
+
+        assert!(validate_session_name("work").is_ok());
+        assert!(validate_session_name("").is_err());
+        assert!(validate_session_name("a/b").is_err());
+        assert!(validate_session_name(" work").is_err());
+
+        std::fs::remove_dir_all(get_named_sessions_dir(&working_dir).unwrap()).unwrap();
+    }
+
     #[test]
     fn test_session_version_check() {
         let session = Session::new(PathBuf::from("/test"));
@@ -3,17 +3,21 @@ use crate::model::cursor::{Cursor, Cursors};
 use crate::model::document_model::{
     DocumentCapabilities, DocumentModel, DocumentPosition, ViewportContent, ViewportLine,
 };
+use crate::model::edit::EditHistory;
 use crate::model::event::{
     Event, MarginContentData, MarginPositionData, OverlayFace as EventOverlayFace, PopupData,
     PopupPositionData,
 };
 use crate::model::marker::MarkerList;
+use crate::model::text_change::{LspPosition, TextChange};
+use crate::primitives::file_type;
 use crate::primitives::grammar_registry::GrammarRegistry;
-use crate::primitives::highlight_engine::HighlightEngine;
+use crate::primitives::highlight_engine::{HighlightEngine, HighlightStats};
 use crate::primitives::highlighter::Language;
 use crate::primitives::indent::IndentCalculator;
 use crate::primitives::semantic_highlight::SemanticHighlighter;
 use crate::primitives::text_property::TextPropertyManager;
+use crate::services::text_stats::{text_counts, TextCounts};
 use crate::view::margin::{MarginAnnotation, MarginContent, MarginManager, MarginPosition};
 use crate::view::overlay::{Overlay, OverlayFace, OverlayManager, UnderlineStyle};
 use crate::view::popup::{Popup, PopupContent, PopupListItem, PopupManager, PopupPosition};
@@ -23,6 +27,9 @@ use anyhow::Result;
 use ratatui::style::{Color, Style};
 use std::cell::RefCell;
 
+/// Most bytes read as the first line when detecting a file's language
+const FIRST_LINE_MAX_BYTES: usize = 1024;
+
 /// Display mode for a buffer
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ViewMode {
@@ -46,6 +53,13 @@ pub struct EditorState {
     /// Syntax highlighter (tree-sitter or TextMate based on language)
     pub highlighter: HighlightEngine,
 
+    /// Region the last frame drew without highlighting, to be highlighted
+    /// when the editor is idle
+    pub deferred_highlight: Option<std::ops::Range<usize>>,
+
+    /// How often frames fell back to drawing without highlighting
+    pub highlight_stats: HighlightStats,
+
     /// Auto-indent calculator for smart indentation (RefCell for interior mutability)
     pub indent_calculator: RefCell<IndentCalculator>,
 
@@ -99,8 +113,25 @@ pub struct EditorState {
     /// Optional column guides (e.g., for tables) supplied by layout hints
     pub compose_column_guides: Option<Vec<u16>>,
 
+    /// Columns to draw a ruler at behind the text (e.g., 50 and 72 in a
+    /// commit message)
+    pub rulers: Vec<u16>,
+
     /// Optional transformed view payload for current viewport (tokens + map)
     pub view_transform: Option<crate::services::plugins::api::ViewTransformPayload>,
+
+    /// Revision of the text and its recent edits, for edit transactions
+    pub edit_history: EditHistory,
+
+    /// Diagnostics left out by the `limits.max_diagnostics` cap
+    pub hidden_diagnostics: usize,
+
+    /// Positions of closing brackets and quotes inserted by auto-close;
+    /// typing the same character there steps over it
+    pub auto_closed: Vec<usize>,
+
+    /// Changes made since they were last taken (see `take_changes`)
+    changes: Vec<TextChange>,
 }
 
 impl EditorState {
@@ -135,7 +166,14 @@ impl EditorState {
             compose_width: None,
             compose_prev_line_numbers: None,
             compose_column_guides: None,
+            rulers: Vec::new(),
             view_transform: None,
+            edit_history: EditHistory::default(),
+            hidden_diagnostics: 0,
+            auto_closed: Vec::new(),
+            deferred_highlight: None,
+            highlight_stats: HighlightStats::default(),
+            changes: Vec::new(),
         }
     }
 
@@ -154,6 +192,36 @@ impl EditorState {
         );
     }
 
+    /// Highlight the buffer as the language the `file_types` rules give the
+    /// file at `path`, or if its extension isn't known, the language its
+    /// name, `#!` line or content tell (see `primitives::file_type`)
+    pub fn detect_file_type(
+        &mut self,
+        path: &std::path::Path,
+        rules: &[crate::config::FileTypeRule],
+        registry: &GrammarRegistry,
+    ) {
+        let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+            return;
+        };
+        let end = self.buffer.len().min(FIRST_LINE_MAX_BYTES);
+        let start_text = self.get_text_range(0, end);
+        let first_line = start_text.lines().next().unwrap_or("");
+
+        let extension = match file_type::matching_rule(rules, &name, first_line) {
+            Some(extension) => extension,
+            None if matches!(self.highlighter, HighlightEngine::None) => {
+                match file_type::detect(&name, first_line) {
+                    Some(extension) => extension,
+                    None => return,
+                }
+            }
+            None => return,
+        };
+        let language_name = format!("{}.{}", name, extension);
+        self.set_language_from_name(&language_name, registry);
+    }
+
     /// Create an editor state from a file
     pub fn from_file(
         path: &std::path::Path,
@@ -212,10 +280,70 @@ impl EditorState {
             compose_width: None,
             compose_prev_line_numbers: None,
             compose_column_guides: None,
+            rulers: Vec::new(),
             view_transform: None,
+            edit_history: EditHistory::default(),
+            hidden_diagnostics: 0,
+            auto_closed: Vec::new(),
+            deferred_highlight: None,
+            highlight_stats: HighlightStats::default(),
+            changes: Vec::new(),
         })
     }
 
+    fn lsp_position(&self, byte_pos: usize) -> LspPosition {
+        let (line, character) = self.buffer.position_to_lsp_position(byte_pos);
+        LspPosition { line, character }
+    }
+
+    /// Record a change made to the text
+    ///
+    /// The highlight cache is invalidated here; other consumers (LSP,
+    /// plugins) get the change from `take_changes`.
+    fn record_change(&mut self, change: TextChange) {
+        // Invalidate highlight cache for edited range
+        let invalidated = if change.new_text.is_empty() {
+            change.range.clone()
+        } else {
+            change.new_range()
+        };
+        self.highlighter.invalidate_range(invalidated);
+        self.changes.push(change);
+    }
+
+    /// Take in what has been appended to the buffer's file (see
+    /// `TextBuffer::append_from_file`). Returns how many bytes were added.
+    pub fn append_from_file(&mut self, large_file_threshold: usize) -> std::io::Result<usize> {
+        let position = self.buffer.len();
+        let added = self.buffer.append_from_file(large_file_threshold)?;
+        if added > 0 {
+            self.marker_list.adjust_for_insert(position, added);
+            self.margins.adjust_for_insert(position, added);
+            self.highlighter
+                .invalidate_range(position.saturating_sub(1)..position + added);
+            self.viewport.mark_needs_sync();
+        }
+        Ok(added)
+    }
+
+    /// Take the changes made since the last call, oldest first
+    pub fn take_changes(&mut self) -> Vec<TextChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Whether changes were made since `take_changes` was last called
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Document version sent to LSP servers: the buffer's revision, so the
+    /// version a server reports back tells whether its results are current.
+    /// LSP versions are `i32`; past `i32::MAX` edits the version stays there,
+    /// and results are no longer dropped as stale rather than wrongly dropped.
+    pub fn lsp_version(&self) -> i32 {
+        i32::try_from(self.edit_history.revision()).unwrap_or(i32::MAX)
+    }
+
     /// Handle an Insert event - adjusts markers, buffer, highlighter, cursors, and line numbers
     fn apply_insert(
         &mut self,
@@ -225,16 +353,29 @@ impl EditorState {
     ) {
         let newlines_inserted = text.matches('\n').count();
 
+        // Positions of the change are taken from the text before it
+        let start = self.lsp_position(position);
+
         // CRITICAL: Adjust markers BEFORE modifying buffer
         self.marker_list.adjust_for_insert(position, text.len());
         self.margins.adjust_for_insert(position, text.len());
+        for pos in &mut self.auto_closed {
+            if *pos >= position {
+                *pos += text.len();
+            }
+        }
 
         // Insert text into buffer
         self.buffer.insert(position, text);
-
-        // Invalidate highlight cache for edited range
-        self.highlighter
-            .invalidate_range(position..position + text.len());
+        self.edit_history.record_insert(position, text.len());
+        self.record_change(TextChange {
+            revision: self.edit_history.revision(),
+            range: position..position,
+            start,
+            end: start,
+            old_text: String::new(),
+            new_text: text.to_string(),
+        });
 
         // Adjust all cursors after the edit
         self.cursors.adjust_for_edit(position, 0, text.len());
@@ -272,15 +413,31 @@ impl EditorState {
         let len = range.len();
         let newlines_deleted = deleted_text.matches('\n').count();
 
+        // Positions of the change are taken from the text before it
+        let start = self.lsp_position(range.start);
+        let end = self.lsp_position(range.end);
+
         // CRITICAL: Adjust markers BEFORE modifying buffer
         self.marker_list.adjust_for_delete(range.start, len);
         self.margins.adjust_for_delete(range.start, len);
+        self.auto_closed.retain(|pos| !range.contains(pos));
+        for pos in &mut self.auto_closed {
+            if *pos >= range.end {
+                *pos -= len;
+            }
+        }
 
         // Delete from buffer
         self.buffer.delete(range.clone());
-
-        // Invalidate highlight cache for edited range
-        self.highlighter.invalidate_range(range.clone());
+        self.edit_history.record_delete(range.start, len);
+        self.record_change(TextChange {
+            revision: self.edit_history.revision(),
+            range: range.clone(),
+            start,
+            end,
+            old_text: deleted_text.to_string(),
+            new_text: String::new(),
+        });
 
         // Adjust all cursors after the edit
         self.cursors.adjust_for_edit(range.start, len, 0);
@@ -598,11 +755,9 @@ impl EditorState {
     pub fn resize(&mut self, width: u16, height: u16) {
         // Account for tab bar (1 line) and status bar (1 line)
         let content_height = height.saturating_sub(2);
-        self.viewport.resize(width, content_height);
-
-        // Ensure primary cursor is still visible after resize
         let primary = *self.cursors.primary();
-        self.viewport.ensure_visible(&mut self.buffer, &primary);
+        self.viewport
+            .resize_keeping_cursor(&mut self.buffer, &primary, width, content_height);
     }
 }
 
@@ -829,6 +984,28 @@ impl EditorState {
             Ok(String::new())
         }
     }
+
+    /// Lines, words and characters of all the cursors' selections, or None
+    /// without a selection or if the selections span over `max_bytes`
+    pub fn selection_counts(&mut self, max_bytes: usize) -> Option<TextCounts> {
+        let ranges: Vec<_> = self
+            .cursors
+            .iter()
+            .filter_map(|(_, cursor)| cursor.selection_range())
+            .filter(|range| !range.is_empty())
+            .collect();
+        if ranges.is_empty() || ranges.iter().map(|r| r.len()).sum::<usize>() > max_bytes {
+            return None;
+        }
+        let mut counts = TextCounts::default();
+        for range in ranges {
+            let selected = text_counts(&self.get_text_range(range.start, range.end));
+            counts.lines += selected.lines;
+            counts.words += selected.words;
+            counts.chars += selected.chars;
+        }
+        Some(counts)
+    }
 }
 
 /// Implement DocumentModel trait for EditorState
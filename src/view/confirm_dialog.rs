@@ -0,0 +1,200 @@
+//! Modal confirmation dialog
+//!
+//! A centered box with a message and a row of buttons. While it is open it
+//! takes all keyboard and mouse input: Left/Right/Tab move between the buttons,
+//! Enter presses the focused one, each button's shortcut key presses it
+//! directly, and Escape cancels.
+
+/// Result of a confirmation dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmChoice {
+    /// Go ahead (e.g., "Save", "Delete", "Overwrite")
+    Yes,
+    /// The alternative to going ahead (e.g., "Discard")
+    No,
+    /// Leave things as they are
+    Cancel,
+    /// Go through the affected items one at a time before deciding
+    Review,
+}
+
+/// How a detail line is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailStyle {
+    Normal,
+    /// Added text (e.g., a "+" line of a diff)
+    Added,
+    /// Removed text (e.g., a "-" line of a diff)
+    Removed,
+    /// Secondary information
+    Muted,
+}
+
+/// A line of extra detail below the message (e.g., a list or a diff)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetailLine {
+    pub text: String,
+    pub style: DetailStyle,
+}
+
+impl DetailLine {
+    pub fn new(text: impl Into<String>, style: DetailStyle) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+/// A button in a confirmation dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogButton {
+    pub label: String,
+    /// Shortcut key (matched case-insensitively)
+    pub key: char,
+    pub choice: ConfirmChoice,
+}
+
+impl DialogButton {
+    pub fn new(label: impl Into<String>, key: char, choice: ConfirmChoice) -> Self {
+        Self {
+            label: label.into(),
+            key,
+            choice,
+        }
+    }
+}
+
+/// A confirmation dialog
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    pub title: String,
+    /// Message lines (long lines are wrapped when rendered)
+    pub message: Vec<String>,
+    /// Extra lines shown below the message (not wrapped)
+    pub details: Vec<DetailLine>,
+    pub buttons: Vec<DialogButton>,
+    /// Index of the focused button
+    pub focused: usize,
+}
+
+impl ConfirmDialog {
+    /// Create a dialog with custom buttons, focusing the first one
+    pub fn new(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        buttons: Vec<DialogButton>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into().lines().map(str::to_string).collect(),
+            details: Vec::new(),
+            buttons,
+            focused: 0,
+        }
+    }
+
+    /// Yes/No dialog
+    pub fn yes_no(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(
+            title,
+            message,
+            vec![
+                DialogButton::new("Yes", 'y', ConfirmChoice::Yes),
+                DialogButton::new("No", 'n', ConfirmChoice::Cancel),
+            ],
+        )
+    }
+
+    /// Yes/No/Cancel dialog
+    pub fn yes_no_cancel(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(
+            title,
+            message,
+            vec![
+                DialogButton::new("Yes", 'y', ConfirmChoice::Yes),
+                DialogButton::new("No", 'n', ConfirmChoice::No),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+    }
+
+    /// Show extra lines below the message
+    pub fn with_details(mut self, details: Vec<DetailLine>) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Focus the button with the given choice (e.g., Cancel for destructive actions)
+    pub fn with_focus(mut self, choice: ConfirmChoice) -> Self {
+        if let Some(index) = self.buttons.iter().position(|b| b.choice == choice) {
+            self.focused = index;
+        }
+        self
+    }
+
+    /// Move focus to the next button (wraps around)
+    pub fn focus_next(&mut self) {
+        if !self.buttons.is_empty() {
+            self.focused = (self.focused + 1) % self.buttons.len();
+        }
+    }
+
+    /// Move focus to the previous button (wraps around)
+    pub fn focus_prev(&mut self) {
+        if !self.buttons.is_empty() {
+            self.focused = (self.focused + self.buttons.len() - 1) % self.buttons.len();
+        }
+    }
+
+    /// Choice of the focused button
+    pub fn focused_choice(&self) -> ConfirmChoice {
+        self.buttons
+            .get(self.focused)
+            .map(|b| b.choice)
+            .unwrap_or(ConfirmChoice::Cancel)
+    }
+
+    /// Choice of the button whose shortcut is `key`, if any
+    pub fn choice_for_key(&self, key: char) -> Option<ConfirmChoice> {
+        let key = key.to_ascii_lowercase();
+        self.buttons
+            .iter()
+            .find(|b| b.key.to_ascii_lowercase() == key)
+            .map(|b| b.choice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_wraps_around() {
+        let mut dialog = ConfirmDialog::yes_no_cancel("Close", "Save changes?");
+        assert_eq!(dialog.focused_choice(), ConfirmChoice::Yes);
+        dialog.focus_prev();
+        assert_eq!(dialog.focused_choice(), ConfirmChoice::Cancel);
+        dialog.focus_next();
+        dialog.focus_next();
+        assert_eq!(dialog.focused_choice(), ConfirmChoice::No);
+    }
+
+    #[test]
+    fn test_shortcut_keys() {
+        let dialog = ConfirmDialog::new(
+            "Delete",
+            "Delete 'notes.txt'?",
+            vec![
+                DialogButton::new("Delete", 'd', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        )
+        .with_focus(ConfirmChoice::Cancel);
+
+        assert_eq!(dialog.focused_choice(), ConfirmChoice::Cancel);
+        assert_eq!(dialog.choice_for_key('D'), Some(ConfirmChoice::Yes));
+        assert_eq!(dialog.choice_for_key('c'), Some(ConfirmChoice::Cancel));
+        assert_eq!(dialog.choice_for_key('x'), None);
+    }
+}
@@ -0,0 +1,536 @@
+//! Terminal backend that keeps output small
+//!
+//! ratatui passes the backend only the cells that changed since the previous
+//! frame. `DiffBackend` writes them with as few bytes as it can, which is what
+//! makes the editor feel responsive over SSH and other slow connections:
+//! - a short run of unchanged cells between two changes is reprinted when that
+//!   is shorter than moving the cursor over it
+//! - other cursor moves use the shortest sequence (relative, CR LF or absolute)
+//! - colors and attributes are sent only when they change
+//! - showing, hiding or moving the cursor to where it already is sends nothing
+//! - a frame is written in one go rather than in 1KB pieces
+//!
+//! In inline mode (`editor.inline_rows`) the editor is drawn on the main
+//! screen below the shell's output rather than on the alternate screen; the
+//! backend then draws every row `top` rows down and never clears above it.
+
+use crossterm::cursor::{Hide, MoveRight, MoveTo, Show};
+use crossterm::style::{
+    Attribute, Color as CColor, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    SetUnderlineColor,
+};
+use crossterm::terminal::{self, Clear};
+use crossterm::{execute, queue};
+use ratatui::backend::{Backend, ClearType, WindowSize};
+use ratatui::buffer::Cell;
+use ratatui::layout::{Position, Size};
+use ratatui::style::{Color, Modifier};
+use std::io::{self, BufWriter, Write};
+
+/// Large enough for a full redraw of a big terminal
+const OUTPUT_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Attributes for each modifier bit
+const ATTRIBUTES: [(Modifier, Attribute); 9] = [
+    (Modifier::BOLD, Attribute::Bold),
+    (Modifier::DIM, Attribute::Dim),
+    (Modifier::ITALIC, Attribute::Italic),
+    (Modifier::UNDERLINED, Attribute::Underlined),
+    (Modifier::SLOW_BLINK, Attribute::SlowBlink),
+    (Modifier::RAPID_BLINK, Attribute::RapidBlink),
+    (Modifier::REVERSED, Attribute::Reverse),
+    (Modifier::HIDDEN, Attribute::Hidden),
+    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+];
+
+/// Colors and attributes set on the terminal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Pen {
+    fg: Color,
+    bg: Color,
+    underline_color: Color,
+    modifier: Modifier,
+}
+
+impl Pen {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            underline_color: cell.underline_color,
+            modifier: cell.modifier,
+        }
+    }
+}
+
+/// Cells on the terminal, as far as the backend knows
+///
+/// Cells that were never drawn are blank, since the screen was cleared.
+#[derive(Debug, Default)]
+struct Screen {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Screen {
+    fn get(&self, x: u16, y: u16) -> Cell {
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn set(&mut self, x: u16, y: u16, cell: Cell) {
+        let (x, y) = (x as usize, y as usize);
+        if self.rows.len() <= y {
+            self.rows.resize_with(y + 1, Vec::new);
+        }
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize_with(x + 1, Cell::default);
+        }
+        row[x] = cell;
+    }
+}
+
+/// Whether a symbol is a single printable ASCII character, so printing it
+/// moves the cursor exactly one column on every terminal
+fn is_narrow_ascii(symbol: &str) -> bool {
+    matches!(symbol.as_bytes(), [b' '..=b'~'])
+}
+
+/// The crossterm color for a ratatui color (ratatui's own conversion is for
+/// the crossterm version it depends on)
+fn crossterm_color(color: Color) -> CColor {
+    match color {
+        Color::Reset => CColor::Reset,
+        Color::Black => CColor::Black,
+        Color::Red => CColor::DarkRed,
+        Color::Green => CColor::DarkGreen,
+        Color::Yellow => CColor::DarkYellow,
+        Color::Blue => CColor::DarkBlue,
+        Color::Magenta => CColor::DarkMagenta,
+        Color::Cyan => CColor::DarkCyan,
+        Color::Gray => CColor::Grey,
+        Color::DarkGray => CColor::DarkGrey,
+        Color::LightRed => CColor::Red,
+        Color::LightGreen => CColor::Green,
+        Color::LightYellow => CColor::Yellow,
+        Color::LightBlue => CColor::Blue,
+        Color::LightMagenta => CColor::Magenta,
+        Color::LightCyan => CColor::Cyan,
+        Color::White => CColor::White,
+        Color::Indexed(i) => CColor::AnsiValue(i),
+        Color::Rgb(r, g, b) => CColor::Rgb { r, g, b },
+    }
+}
+
+fn digits(n: u16) -> usize {
+    n.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+/// Terminal backend that writes only what changed, as compactly as it can
+pub struct DiffBackend<W: Write> {
+    writer: BufWriter<W>,
+    /// What is on screen, if known (from the last full clear on)
+    screen: Option<Screen>,
+    /// Colors and attributes set on the terminal
+    pen: Pen,
+    /// Where the terminal cursor is, if known exactly
+    cursor: Option<Position>,
+    /// Where ratatui's backend would assume the cursor is after the last cell
+    /// (one column right of it, whatever its width)
+    after_last_cell: Option<Position>,
+    cursor_visible: Option<bool>,
+    /// Terminal row that row 0 is drawn at (0 unless inline)
+    top: u16,
+}
+
+impl<W: Write> DiffBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(OUTPUT_BUFFER_SIZE, writer),
+            screen: None,
+            pen: Pen::default(),
+            cursor: None,
+            after_last_cell: None,
+            cursor_visible: None,
+            top: 0,
+        }
+    }
+
+    /// A backend that draws from terminal row `top` down, leaving the rows
+    /// above it alone
+    pub fn inline(writer: W, top: u16) -> Self {
+        Self {
+            top,
+            ..Self::new(writer)
+        }
+    }
+
+    /// Terminal row that row 0 is drawn at
+    pub fn top(&self) -> u16 {
+        self.top
+    }
+
+    /// Draw from another terminal row on (after the terminal was resized)
+    pub fn set_top(&mut self, top: u16) {
+        self.top = top;
+        self.forget_screen();
+    }
+
+    /// Forget what is on screen and where the cursor is
+    fn forget_screen(&mut self) {
+        self.screen = None;
+        self.cursor = None;
+        self.after_last_cell = None;
+    }
+
+    /// Move the cursor to a cell about to be drawn
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        if self.after_last_cell == Some(Position { x, y }) {
+            return Ok(());
+        }
+
+        match self.cursor {
+            Some(cursor) if cursor.y == y && cursor.x < x => {
+                let gap = x - cursor.x;
+                if !self.reprint_gap(cursor.x, x, y, 3 + digits(gap))? {
+                    queue!(self.writer, MoveRight(gap))?;
+                }
+            }
+            Some(cursor) if x == 0 && y == cursor.y + 1 => {
+                queue!(self.writer, Print("\r\n"))?;
+            }
+            _ => queue!(self.writer, MoveTo(x, y + self.top))?,
+        }
+        Ok(())
+    }
+
+    /// Reprint the unchanged cells from `start` up to `end` on row `y`, if
+    /// that takes at most `budget` bytes and needs no color or attribute
+    /// changes
+    fn reprint_gap(&mut self, start: u16, end: u16, y: u16, budget: usize) -> io::Result<bool> {
+        let Some(screen) = &self.screen else {
+            return Ok(false);
+        };
+        if (end - start) as usize > budget {
+            return Ok(false);
+        }
+
+        let mut text = String::with_capacity(budget);
+        for x in start..end {
+            let cell = screen.get(x, y);
+            if cell.skip || !is_narrow_ascii(cell.symbol()) || Pen::of(&cell) != self.pen {
+                return Ok(false);
+            }
+            text.push_str(cell.symbol());
+        }
+        queue!(self.writer, Print(text))?;
+        Ok(true)
+    }
+
+    /// Switch the terminal to the colors and attributes of a cell
+    fn set_pen(&mut self, pen: Pen) -> io::Result<()> {
+        let writer = &mut self.writer;
+        let mut current = self.pen;
+
+        if !(current.modifier - pen.modifier).is_empty() {
+            // Attributes can't be turned off one by one on all terminals
+            queue!(writer, SetAttribute(Attribute::Reset))?;
+            current = Pen::default();
+        }
+        for (modifier, attribute) in ATTRIBUTES {
+            if pen.modifier.contains(modifier) && !current.modifier.contains(modifier) {
+                queue!(writer, SetAttribute(attribute))?;
+            }
+        }
+        if pen.fg != current.fg {
+            queue!(writer, SetForegroundColor(crossterm_color(pen.fg)))?;
+        }
+        if pen.bg != current.bg {
+            queue!(writer, SetBackgroundColor(crossterm_color(pen.bg)))?;
+        }
+        if pen.underline_color != current.underline_color {
+            queue!(
+                writer,
+                SetUnderlineColor(crossterm_color(pen.underline_color))
+            )?;
+        }
+
+        self.pen = pen;
+        Ok(())
+    }
+}
+
+impl<W: Write> Backend for DiffBackend<W> {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        for (x, y, cell) in content {
+            self.move_to(x, y)?;
+            let pen = Pen::of(cell);
+            if pen != self.pen {
+                self.set_pen(pen)?;
+            }
+            queue!(self.writer, Print(cell.symbol()))?;
+
+            let narrow = is_narrow_ascii(cell.symbol());
+            self.cursor = narrow.then_some(Position { x: x + 1, y });
+            self.after_last_cell = Some(Position { x: x + 1, y });
+            if let Some(screen) = &mut self.screen {
+                screen.set(x, y, cell.clone());
+                if !narrow {
+                    // May be covered by a wide character
+                    let mut covered = screen.get(x + 1, y);
+                    covered.set_skip(true);
+                    screen.set(x + 1, y, covered);
+                }
+            }
+        }
+
+        // Leave the terminal with default colors between frames
+        if self.pen != Pen::default() {
+            queue!(self.writer, SetAttribute(Attribute::Reset))?;
+            self.pen = Pen::default();
+        }
+        Ok(())
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.forget_screen();
+        for _ in 0..n {
+            queue!(self.writer, Print("\n"))?;
+        }
+        self.writer.flush()
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        if self.cursor_visible != Some(false) {
+            queue!(self.writer, Hide)?;
+            self.cursor_visible = Some(false);
+        }
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        if self.cursor_visible != Some(true) {
+            queue!(self.writer, Show)?;
+            self.cursor_visible = Some(true);
+        }
+        Ok(())
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.writer.flush()?;
+        let (x, y) = crossterm::cursor::position()?;
+        Ok(Position {
+            x,
+            y: y.saturating_sub(self.top),
+        })
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        let position = position.into();
+        if self.cursor != Some(position) {
+            queue!(self.writer, MoveTo(position.x, position.y + self.top))?;
+            self.cursor = Some(position);
+        }
+        self.after_last_cell = None;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.clear_region(ClearType::All)
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        self.forget_screen();
+        let clear_type = match clear_type {
+            ClearType::All => terminal::ClearType::All,
+            ClearType::AfterCursor => terminal::ClearType::FromCursorDown,
+            ClearType::BeforeCursor => terminal::ClearType::FromCursorUp,
+            ClearType::CurrentLine => terminal::ClearType::CurrentLine,
+            ClearType::UntilNewLine => terminal::ClearType::UntilNewLine,
+        };
+        if clear_type == terminal::ClearType::All && self.top > 0 {
+            // Keep the shell's output above the editor
+            execute!(
+                self.writer,
+                MoveTo(0, self.top),
+                Clear(terminal::ClearType::FromCursorDown)
+            )?;
+        } else {
+            execute!(self.writer, Clear(clear_type))?;
+        }
+        if clear_type == terminal::ClearType::All {
+            self.screen = Some(Screen::default());
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        let (width, height) = terminal::size()?;
+        Ok(Size { width, height })
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        let size = terminal::window_size()?;
+        Ok(WindowSize {
+            columns_rows: Size {
+                width: size.columns,
+                height: size.rows,
+            },
+            pixels: Size {
+                width: size.width,
+                height: size.height,
+            },
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::CrosstermBackend;
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const WIDTH: u16 = 30;
+    const HEIGHT: u16 = 4;
+
+    fn frame(lines: &[&str], status: &str) -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, WIDTH, HEIGHT));
+        for (y, line) in lines.iter().enumerate() {
+            buffer.set_string(0, y as u16, line, Style::default());
+        }
+        let status_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+        buffer.set_style(Rect::new(0, HEIGHT - 1, WIDTH, 1), status_style);
+        buffer.set_string(0, HEIGHT - 1, status, status_style);
+        buffer
+    }
+
+    /// Output that stays readable after being handed to a backend
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Output {
+        fn len(&self) -> usize {
+            self.0.borrow().len()
+        }
+
+        fn screen(&self) -> vt100::Parser {
+            let mut parser = vt100::Parser::new(HEIGHT, WIDTH, 0);
+            parser.process(&self.0.borrow());
+            parser
+        }
+    }
+
+    /// Draw frames the way `Terminal::draw` does, returning the bytes written
+    /// for each
+    fn draw_frames(mut backend: impl Backend, output: &Output, frames: &[Buffer]) -> Vec<usize> {
+        backend.clear_region(ClearType::All).unwrap();
+        backend.flush().unwrap();
+        let mut previous = Buffer::empty(Rect::new(0, 0, WIDTH, HEIGHT));
+        let mut sizes = Vec::new();
+        for frame in frames {
+            let before = output.len();
+            backend.draw(previous.diff(frame).into_iter()).unwrap();
+            backend.show_cursor().unwrap();
+            backend.set_cursor_position((5, 1)).unwrap();
+            backend.flush().unwrap();
+            sizes.push(output.len() - before);
+            previous = frame.clone();
+        }
+        sizes
+    }
+
+    #[test]
+    fn test_output_matches_frames_and_is_smaller() {
+        let frames = [
+            frame(&["fn main() {", "    let x = 1;", "}"], "main.rs"),
+            frame(&["fn main() {", "    let y = 2;", "}"], "main.rs [+]"),
+            frame(&["fn main() {", "    let y = 2;", "}"], "main.rs [+]"),
+        ];
+
+        let diff = Output::default();
+        let diff_sizes = draw_frames(DiffBackend::new(diff.clone()), &diff, &frames);
+        let crossterm = Output::default();
+        let crossterm_sizes = draw_frames(
+            CrosstermBackend::new(crossterm.clone()),
+            &crossterm,
+            &frames,
+        );
+
+        let parser = diff.screen();
+        let screen = parser.screen();
+        assert_eq!(
+            screen.contents().trim_end(),
+            "fn main() {\n    let y = 2;\n}\nmain.rs [+]"
+        );
+        assert_eq!(screen.cursor_position(), (1, 5));
+        let status = screen.cell(HEIGHT - 1, WIDTH - 1).unwrap();
+        assert_eq!(status.bgcolor(), vt100::Color::Idx(6));
+        assert_eq!(screen.cell(0, 0).unwrap().bgcolor(), vt100::Color::Default);
+
+        // Small edits reprint the unchanged cells between them instead of
+        // moving the cursor, and an unchanged frame writes nothing
+        assert!(diff_sizes[1] < crossterm_sizes[1]);
+        assert!(String::from_utf8_lossy(&diff.0.borrow()).contains("y = 2"));
+        assert_eq!(diff_sizes[2], 0);
+        assert!(crossterm_sizes[2] > 0);
+    }
+
+    #[test]
+    fn test_gap_with_other_style_is_skipped() {
+        let mut first = frame(&["abcdef"], "");
+        first.set_style(Rect::new(2, 0, 1, 1), Style::default().fg(Color::Red));
+        let mut second = first.clone();
+        second.set_string(1, 0, "X", Style::default());
+        second.set_string(4, 0, "Y", Style::default());
+
+        let output = Output::default();
+        draw_frames(DiffBackend::new(output.clone()), &output, &[first, second]);
+
+        let parser = output.screen();
+        let screen = parser.screen();
+        assert_eq!(screen.contents().lines().next(), Some("aXcdYf"));
+        assert_eq!(screen.cell(0, 2).unwrap().fgcolor(), vt100::Color::Idx(1));
+        assert_eq!(screen.cell(0, 3).unwrap().fgcolor(), vt100::Color::Default);
+    }
+
+    #[test]
+    fn test_inline_frames_are_drawn_below_the_shell_output() {
+        let output = Output::default();
+        output.clone().write_all(b"$ ls\r\nnotes.txt\r\n").unwrap();
+        let frames = [frame(&["hello"], "main.rs")];
+        draw_frames(DiffBackend::inline(output.clone(), 2), &output, &frames);
+
+        let mut parser = vt100::Parser::new(HEIGHT + 2, WIDTH, 0);
+        parser.process(&output.0.borrow());
+        let screen = parser.screen();
+        assert_eq!(
+            screen.contents().trim_end(),
+            "$ ls\nnotes.txt\nhello\n\n\nmain.rs"
+        );
+        assert_eq!(screen.cursor_position(), (3, 5));
+    }
+}
@@ -0,0 +1,193 @@
+//! Export of highlighted text as HTML or ANSI-colored text
+//!
+//! Both formats take the text together with the syntax highlight spans of the
+//! exported range and inline the colors, so the result can be pasted into
+//! documents, emails or a terminal without the editor.
+
+use crate::primitives::highlighter::HighlightSpan;
+use crate::view::theme::{color_to_rgb, Theme};
+use ratatui::style::Color;
+
+/// Format of an exported buffer or selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Standalone HTML page with inline styles
+    Html,
+    /// Text with 24-bit ANSI color escape codes
+    Ansi,
+}
+
+impl ExportFormat {
+    /// File extension of exported files
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Ansi => "ans",
+        }
+    }
+
+    /// Name shown in prompts and status messages
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Html => "HTML",
+            Self::Ansi => "ANSI text",
+        }
+    }
+}
+
+/// Split `text` into pieces, each with the color of the span covering it
+///
+/// `spans` are byte ranges in the buffer and `offset` is the buffer position
+/// of the start of `text`. Uncovered text has no color; spans overlapping
+/// text already covered by an earlier span are cut off.
+fn colored_segments<'a>(
+    text: &'a str,
+    offset: usize,
+    spans: &[HighlightSpan],
+) -> Vec<(&'a str, Option<Color>)> {
+    let mut spans: Vec<&HighlightSpan> = spans.iter().collect();
+    spans.sort_by_key(|span| span.range.start);
+
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for span in spans {
+        let start = span.range.start.saturating_sub(offset).max(pos);
+        let end = span.range.end.saturating_sub(offset).min(text.len());
+        if start >= end || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            continue;
+        }
+        if start > pos {
+            segments.push((&text[pos..start], None));
+        }
+        segments.push((&text[start..end], Some(span.color)));
+        pos = end;
+    }
+    if pos < text.len() {
+        segments.push((&text[pos..], None));
+    }
+    segments
+}
+
+/// CSS hex color (e.g. `#1e1e1e`)
+fn css_color(color: Color) -> Option<String> {
+    color_to_rgb(color).map(|(r, g, b)| format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Standalone HTML page showing `text` in the theme's colors
+pub fn export_html(
+    text: &str,
+    offset: usize,
+    spans: &[HighlightSpan],
+    theme: &Theme,
+    title: &str,
+) -> String {
+    let mut pre_style = String::from("font-family: monospace; padding: 1em;");
+    if let Some(bg) = css_color(theme.editor_bg) {
+        pre_style.push_str(&format!(" background-color: {};", bg));
+    }
+    if let Some(fg) = css_color(theme.editor_fg) {
+        pre_style.push_str(&format!(" color: {};", fg));
+    }
+
+    let mut out =
+        String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>");
+    escape_html(title, &mut out);
+    out.push_str("</title>\n</head>\n<body>\n<pre style=\"");
+    out.push_str(&pre_style);
+    out.push_str("\">");
+    for (segment, color) in colored_segments(text, offset, spans) {
+        match color.and_then(css_color) {
+            Some(color) => {
+                out.push_str(&format!("<span style=\"color: {}\">", color));
+                escape_html(segment, &mut out);
+                out.push_str("</span>");
+            }
+            None => escape_html(segment, &mut out),
+        }
+    }
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+/// `text` with ANSI foreground colors
+///
+/// Colors are reset at the end of each line, so lines can be copied (or
+/// shown by pagers) on their own.
+pub fn export_ansi(text: &str, offset: usize, spans: &[HighlightSpan]) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (segment, color) in colored_segments(text, offset, spans) {
+        let Some((r, g, b)) = color.and_then(color_to_rgb) else {
+            out.push_str(segment);
+            continue;
+        };
+        for (i, line) in segment.split('\n').enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            if !line.is_empty() {
+                out.push_str(&format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, line));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(range: std::ops::Range<usize>, color: Color) -> HighlightSpan {
+        HighlightSpan { range, color }
+    }
+
+    #[test]
+    fn test_export_html_escapes_and_colors() {
+        let theme = Theme::dark();
+        let spans = [span(10..12, Color::Rgb(255, 0, 0))];
+        let html = export_html("a<b> && c\n", 5, &spans, &theme, "x.rs");
+
+        assert!(html.contains("<title>x.rs</title>"));
+        assert!(
+            html.contains("a&lt;b&gt; <span style=\"color: #ff0000\">&amp;&amp;</span> c\n</pre>")
+        );
+    }
+
+    #[test]
+    fn test_export_ansi_resets_each_line() {
+        let spans = [span(0..9, Color::Rgb(1, 2, 3))];
+        assert_eq!(
+            export_ansi("/* a\nb */ x", 0, &spans),
+            "\x1b[38;2;1;2;3m/* a\x1b[0m\n\x1b[38;2;1;2;3mb */\x1b[0m x"
+        );
+    }
+
+    #[test]
+    fn test_overlapping_spans_are_cut_off() {
+        let spans = [
+            span(0..4, Color::Red),
+            span(2..6, Color::Blue),
+            span(8..20, Color::Green),
+        ];
+        assert_eq!(
+            colored_segments("abcdefghij", 0, &spans),
+            vec![
+                ("abcd", Some(Color::Red)),
+                ("ef", Some(Color::Blue)),
+                ("gh", None),
+                ("ij", Some(Color::Green)),
+            ]
+        );
+    }
+}
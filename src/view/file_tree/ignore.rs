@@ -9,6 +9,7 @@
 //! compatible with git's ignore rules.
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 /// Status of a file/directory with respect to ignore patterns
@@ -31,6 +32,12 @@ pub struct IgnorePatterns {
     /// Key: directory path, Value: gitignore rules for that directory
     gitignores: Vec<(PathBuf, Gitignore)>,
 
+    /// Directories whose .gitignore was looked for
+    checked_dirs: HashSet<PathBuf>,
+
+    /// Whether .gitignore rules apply at all
+    respect_gitignore: bool,
+
     /// Custom glob patterns to ignore
     custom_patterns: Vec<String>,
 
@@ -49,6 +56,8 @@ impl IgnorePatterns {
     pub fn new() -> Self {
         Self {
             gitignores: Vec::new(),
+            checked_dirs: HashSet::new(),
+            respect_gitignore: true,
             custom_patterns: Vec::new(),
             show_hidden: false,
             show_gitignored: false,
@@ -60,6 +69,7 @@ impl IgnorePatterns {
     ///
     /// This should be called when expanding a directory to load its .gitignore
     pub fn load_gitignore(&mut self, dir: &Path) -> std::io::Result<()> {
+        self.checked_dirs.insert(dir.to_path_buf());
         let gitignore_path = dir.join(".gitignore");
 
         if !gitignore_path.exists() {
@@ -84,6 +94,11 @@ impl IgnorePatterns {
         }
     }
 
+    /// Whether the .gitignore of `dir` was looked for
+    pub fn is_checked(&self, dir: &Path) -> bool {
+        self.checked_dirs.contains(dir)
+    }
+
     /// Add a custom glob pattern to ignore
     ///
     /// Examples: "*.o", "target/", "node_modules/"
@@ -138,14 +153,18 @@ impl IgnorePatterns {
         IgnoreStatus::Visible
     }
 
-    /// Check if path matches any .gitignore rules
+    /// Check if path matches any .gitignore rules, or is inside a directory
+    /// that does
     fn matches_gitignore(&self, path: &Path, is_dir: bool) -> bool {
+        if !self.respect_gitignore {
+            return false;
+        }
         // Find the most specific .gitignore (deepest directory)
         // that could apply to this path
         for (gitignore_dir, gitignore) in &self.gitignores {
             if path.starts_with(gitignore_dir) {
                 let relative_path = path.strip_prefix(gitignore_dir).unwrap_or(path);
-                let matched = gitignore.matched(relative_path, is_dir);
+                let matched = gitignore.matched_path_or_any_parents(relative_path, is_dir);
 
                 if matched.is_ignore() {
                     return true;
@@ -204,6 +223,11 @@ impl IgnorePatterns {
         self.show_gitignored
     }
 
+    /// Set whether .gitignore rules are applied
+    pub fn set_respect_gitignore(&mut self, respect: bool) {
+        self.respect_gitignore = respect;
+    }
+
     /// Set whether to show custom ignored files
     pub fn set_show_custom_ignored(&mut self, show: bool) {
         self.show_custom_ignored = show;
@@ -222,6 +246,7 @@ impl IgnorePatterns {
     /// Clear all gitignore rules
     pub fn clear_gitignores(&mut self) {
         self.gitignores.clear();
+        self.checked_dirs.clear();
     }
 
     /// Clear all custom patterns
@@ -343,6 +368,44 @@ mod tests {
         assert!(!patterns.show_gitignored());
     }
 
+    #[test]
+    fn test_gitignore_status() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::write(root.join(".gitignore"), "*.log\nbuild/\n!important.log\n")?;
+
+        let mut patterns = IgnorePatterns::new();
+        patterns.load_gitignore(root)?;
+        assert!(patterns.is_checked(root));
+
+        let status = |patterns: &IgnorePatterns, path: &str, is_dir| {
+            patterns.get_status(&root.join(path), is_dir)
+        };
+        assert_eq!(
+            status(&patterns, "debug.log", false),
+            IgnoreStatus::GitIgnored
+        );
+        assert_eq!(
+            status(&patterns, "important.log", false),
+            IgnoreStatus::Visible
+        );
+        assert_eq!(status(&patterns, "build", true), IgnoreStatus::GitIgnored);
+        // Inside an ignored directory
+        assert_eq!(
+            status(&patterns, "build/out.txt", false),
+            IgnoreStatus::GitIgnored
+        );
+        assert_eq!(
+            status(&patterns, "src/main.rs", false),
+            IgnoreStatus::Visible
+        );
+        assert!(patterns.is_ignored(&root.join("debug.log"), false));
+
+        patterns.set_respect_gitignore(false);
+        assert_eq!(status(&patterns, "debug.log", false), IgnoreStatus::Visible);
+        Ok(())
+    }
+
     #[test]
     fn test_multiple_gitignores() -> std::io::Result<()> {
         let temp_root = TempDir::new()?;
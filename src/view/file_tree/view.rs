@@ -1,7 +1,10 @@
 use super::ignore::IgnorePatterns;
 use super::node::NodeId;
 use super::tree::FileTree;
+use crate::services::file_filter::FileFilter;
 use crate::services::fs::FsEntry;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 /// View state for file tree navigation and filtering
 #[derive(Debug)]
@@ -16,10 +19,33 @@ pub struct FileTreeView {
     sort_mode: SortMode,
     /// Ignore patterns for filtering
     ignore_patterns: IgnorePatterns,
+    /// Project include/exclude globs; excluded entries aren't listed
+    file_filter: FileFilter,
+    /// The "/" filter narrowing the tree, if one is typed
+    filter: Option<TreeFilter>,
     /// Last known viewport height (for scrolling calculations)
     pub(crate) viewport_height: usize,
 }
 
+/// Entries matching the explorer's filter query
+#[derive(Debug)]
+struct TreeFilter {
+    query: String,
+    /// Matching files and directories
+    matches: HashSet<PathBuf>,
+    /// The directories holding them
+    ancestors: HashSet<PathBuf>,
+    /// Directories that were expanded before filtering
+    expanded: HashSet<PathBuf>,
+}
+
+impl TreeFilter {
+    /// Whether the entry at `path` is shown while filtering
+    fn shows(&self, path: &Path) -> bool {
+        self.ancestors.contains(path) || path.ancestors().any(|dir| self.matches.contains(dir))
+    }
+}
+
 /// Sort mode for file tree entries
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortMode {
@@ -41,6 +67,8 @@ impl FileTreeView {
             scroll_offset: 0,
             sort_mode: SortMode::Type,
             ignore_patterns: IgnorePatterns::new(),
+            file_filter: FileFilter::default(),
+            filter: None,
             viewport_height: 10, // Default, will be updated during rendering
         }
     }
@@ -60,11 +88,116 @@ impl FileTreeView {
         &mut self.tree
     }
 
+    /// Set the include/exclude globs used to leave entries out
+    pub fn set_file_filter(&mut self, file_filter: FileFilter) {
+        self.file_filter = file_filter;
+    }
+
+    /// Get the nodes shown in the explorer, in display order
+    ///
+    /// Like `FileTree::get_visible_nodes`, without entries left out by the
+    /// project's file filter or hidden by the ignore patterns (hidden and
+    /// gitignored files, unless shown). While a filter query is typed only its
+    /// matches, the directories holding them and what is inside matching
+    /// directories are shown.
+    pub fn visible_nodes(&self) -> Vec<NodeId> {
+        self.tree
+            .get_visible_nodes()
+            .into_iter()
+            .filter(|&id| {
+                self.tree.get_node(id).is_some_and(|node| {
+                    id == self.tree.root_id()
+                        || (!self
+                            .file_filter
+                            .is_excluded(&node.entry.path, node.is_dir())
+                            && self.is_node_visible(id)
+                            && self
+                                .filter
+                                .as_ref()
+                                .is_none_or(|filter| filter.shows(&node.entry.path)))
+                })
+            })
+            .collect()
+    }
+
+    /// Narrow the tree to `matches` (full paths) for the filter `query`.
+    /// The directories holding them must be expanded by the caller; the
+    /// first match shown is selected.
+    pub fn set_filter(&mut self, query: String, matches: HashSet<PathBuf>) {
+        let expanded = match self.filter.take() {
+            Some(filter) => filter.expanded,
+            None => self
+                .tree
+                .all_nodes()
+                .filter(|node| node.is_expanded())
+                .map(|node| node.entry.path.clone())
+                .collect(),
+        };
+        let root = self.tree.root_path();
+        let ancestors = matches
+            .iter()
+            .flat_map(|path| path.ancestors().skip(1).take_while(|dir| *dir != root))
+            .map(Path::to_path_buf)
+            .collect();
+        self.filter = Some(TreeFilter {
+            query,
+            matches,
+            ancestors,
+            expanded,
+        });
+
+        let visible = self.visible_nodes();
+        let first_match = visible.iter().copied().find(|&id| {
+            self.tree.get_node(id).is_some_and(|node| {
+                self.filter
+                    .as_ref()
+                    .is_some_and(|filter| filter.matches.contains(&node.entry.path))
+            })
+        });
+        self.selected_node = first_match.or(visible.first().copied());
+        self.scroll_offset = 0;
+        self.update_scroll_for_selection();
+    }
+
+    /// Show the whole tree again, collapsing the directories expanded for
+    /// the filter. The selection is kept, or moves to the closest directory
+    /// still shown.
+    pub fn clear_filter(&mut self) {
+        let Some(filter) = self.filter.take() else {
+            return;
+        };
+        let selected_path = self.get_selected_entry().map(|entry| entry.path.clone());
+        // Parents come first, and collapsing one drops what is inside it
+        for id in self.tree.get_visible_nodes() {
+            let expanded_for_filter = self.tree.get_node(id).is_some_and(|node| {
+                node.is_expanded()
+                    && id != self.tree.root_id()
+                    && !filter.expanded.contains(&node.entry.path)
+            });
+            if expanded_for_filter {
+                self.tree.collapse_node(id);
+            }
+        }
+        if let Some(path) = selected_path {
+            self.selected_node = path
+                .ancestors()
+                .find_map(|path| self.tree.get_node_by_path(path))
+                .map(|node| node.id)
+                .or(Some(self.tree.root_id()));
+        }
+        self.update_scroll_for_selection();
+    }
+
+    /// The filter query narrowing the tree, if any
+    pub fn filter_query(&self) -> Option<&str> {
+        self.filter.as_ref().map(|filter| filter.query.as_str())
+    }
+
     /// Get currently visible nodes with their indent levels
     ///
     /// Returns a list of (NodeId, indent_level) tuples for rendering.
     pub fn get_display_nodes(&self) -> Vec<(NodeId, usize)> {
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         visible
             .into_iter()
             .map(|id| {
@@ -86,7 +219,7 @@ impl FileTreeView {
 
     /// Select the next visible node
     pub fn select_next(&mut self) {
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         if visible.is_empty() {
             return;
         }
@@ -104,7 +237,7 @@ impl FileTreeView {
 
     /// Select the previous visible node
     pub fn select_prev(&mut self) {
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         if visible.is_empty() {
             return;
         }
@@ -126,7 +259,7 @@ impl FileTreeView {
             return;
         }
 
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         if visible.is_empty() {
             return;
         }
@@ -147,7 +280,7 @@ impl FileTreeView {
             return;
         }
 
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         if visible.is_empty() {
             return;
         }
@@ -175,7 +308,7 @@ impl FileTreeView {
         }
 
         if let Some(selected) = self.selected_node {
-            let visible = self.tree.get_visible_nodes();
+            let visible = self.visible_nodes();
             if let Some(pos) = visible.iter().position(|&id| id == selected) {
                 // Only scroll if cursor goes PAST the viewport edges
                 // This implements symmetric scrolling behavior
@@ -195,7 +328,7 @@ impl FileTreeView {
 
     /// Select the first visible node
     pub fn select_first(&mut self) {
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         if !visible.is_empty() {
             self.selected_node = Some(visible[0]);
         }
@@ -203,7 +336,7 @@ impl FileTreeView {
 
     /// Select the last visible node
     pub fn select_last(&mut self) {
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         if !visible.is_empty() {
             self.selected_node = Some(*visible.last().unwrap());
         }
@@ -243,7 +376,7 @@ impl FileTreeView {
         }
 
         if let Some(selected) = self.selected_node {
-            let visible = self.tree.get_visible_nodes();
+            let visible = self.visible_nodes();
             if let Some(pos) = visible.iter().position(|&id| id == selected) {
                 // If selection is above viewport, scroll up
                 if pos < self.scroll_offset {
@@ -285,7 +418,7 @@ impl FileTreeView {
     /// Get the index of the selected node in the visible list
     pub fn get_selected_index(&self) -> Option<usize> {
         if let Some(selected) = self.selected_node {
-            let visible = self.tree.get_visible_nodes();
+            let visible = self.visible_nodes();
             visible.iter().position(|&id| id == selected)
         } else {
             None
@@ -294,13 +427,13 @@ impl FileTreeView {
 
     /// Get visible node at index (accounting for scroll offset)
     pub fn get_node_at_index(&self, index: usize) -> Option<NodeId> {
-        let visible = self.tree.get_visible_nodes();
+        let visible = self.visible_nodes();
         visible.get(index).copied()
     }
 
     /// Get the number of visible nodes
     pub fn visible_count(&self) -> usize {
-        self.tree.get_visible_nodes().len()
+        self.visible_nodes().len()
     }
 
     /// Get reference to ignore patterns
@@ -316,11 +449,31 @@ impl FileTreeView {
     /// Toggle showing hidden files
     pub fn toggle_show_hidden(&mut self) {
         self.ignore_patterns.toggle_show_hidden();
+        self.select_shown_ancestor();
     }
 
     /// Toggle showing gitignored files
     pub fn toggle_show_gitignored(&mut self) {
         self.ignore_patterns.toggle_show_gitignored();
+        self.select_shown_ancestor();
+    }
+
+    /// If the selected entry was just hidden, select the closest directory
+    /// holding it that is shown
+    fn select_shown_ancestor(&mut self) {
+        let visible = self.visible_nodes();
+        while let Some(selected) = self.selected_node {
+            if visible.contains(&selected) {
+                break;
+            }
+            self.selected_node = self
+                .tree
+                .get_node(selected)
+                .and_then(|node| node.parent)
+                .or(Some(self.tree.root_id()))
+                .filter(|&parent| parent != selected);
+        }
+        self.update_scroll_for_selection();
     }
 
     /// Check if a node should be visible (not filtered by ignore patterns)
@@ -341,6 +494,23 @@ impl FileTreeView {
         self.ignore_patterns.load_gitignore(dir_path)
     }
 
+    /// Load the .gitignore of every expanded directory not looked at yet,
+    /// e.g. after directories were expanded to reveal a file
+    pub fn load_expanded_gitignores(&mut self) {
+        let dirs: Vec<PathBuf> = self
+            .tree
+            .all_nodes()
+            .filter(|node| node.is_expanded())
+            .map(|node| node.entry.path.clone())
+            .filter(|dir| !self.ignore_patterns.is_checked(dir))
+            .collect();
+        for dir in dirs {
+            if let Err(e) = self.ignore_patterns.load_gitignore(&dir) {
+                tracing::warn!("Failed to load .gitignore from {:?}: {}", dir, e);
+            }
+        }
+    }
+
     /// Expand all parent directories and select the given file path
     ///
     /// This is useful for revealing a specific file in the tree when switching
@@ -523,6 +693,33 @@ mod tests {
         assert_eq!(selected_entry.name, "dir1");
     }
 
+    #[tokio::test]
+    async fn test_filter_and_clear() {
+        let (_temp_dir, mut view) = create_test_view().await;
+
+        let root_id = view.tree().root_id();
+        view.tree_mut().expand_node(root_id).await.unwrap();
+        let file2 = view.tree().root_path().join("dir1/file2.txt");
+        view.tree_mut().expand_to_path(&file2).await.unwrap();
+        view.set_filter("f2".to_string(), HashSet::from([file2.clone()]));
+
+        let names: Vec<&str> = view
+            .visible_nodes()
+            .into_iter()
+            .skip(1)
+            .map(|id| view.tree().get_node(id).unwrap().entry.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["dir1", "file2.txt"]);
+        assert_eq!(view.get_selected_entry().unwrap().path, file2);
+        assert_eq!(view.filter_query(), Some("f2"));
+
+        // dir1 was expanded for the filter
+        view.clear_filter();
+        assert_eq!(view.filter_query(), None);
+        assert_eq!(view.visible_count(), 4);
+        assert_eq!(view.get_selected_entry().unwrap().name, "dir1");
+    }
+
     #[tokio::test]
     async fn test_get_selected_index() {
         let (_temp_dir, mut view) = create_test_view().await;
@@ -568,4 +765,37 @@ mod tests {
         view.set_sort_mode(SortMode::Modified);
         assert_eq!(view.get_sort_mode(), SortMode::Modified);
     }
+
+    #[tokio::test]
+    async fn test_file_filter_hides_excluded() {
+        let (temp_dir, mut view) = create_test_view().await;
+
+        let root_id = view.tree().root_id();
+        view.tree_mut().expand_node(root_id).await.unwrap();
+        assert_eq!(view.visible_count(), 4);
+
+        let exclude = crate::config::FilesConfig {
+            include: Vec::new(),
+            exclude: vec!["dir1/".to_string()],
+        };
+        let filter = FileFilter::new(
+            temp_dir.path(),
+            &exclude,
+            &crate::config::FilesConfig::default(),
+        )
+        .unwrap();
+        view.set_file_filter(filter);
+
+        // root + dir2 + file3.txt
+        assert_eq!(view.visible_count(), 3);
+        assert!(view.visible_nodes().iter().all(|&id| {
+            !view
+                .tree()
+                .get_node(id)
+                .unwrap()
+                .entry
+                .path
+                .ends_with("dir1")
+        }));
+    }
 }
@@ -121,6 +121,38 @@ impl MarginContent {
     }
 }
 
+/// A column of the left margin, as hit by a mouse click
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GutterColumn {
+    /// Indicator column (diagnostics, git changes, breakpoints)
+    Sign,
+    /// Line numbers
+    LineNumber,
+    /// Separator between the margin and the text
+    Separator,
+}
+
+impl GutterColumn {
+    /// Name used in config and plugin hooks
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sign => "sign",
+            Self::LineNumber => "line_number",
+            Self::Separator => "separator",
+        }
+    }
+
+    /// Parse a name returned by `name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sign" => Some(Self::Sign),
+            "line_number" => Some(Self::LineNumber),
+            "separator" => Some(Self::Separator),
+            _ => None,
+        }
+    }
+}
+
 /// Configuration for a margin
 #[derive(Debug, Clone, PartialEq)]
 pub struct MarginConfig {
@@ -624,6 +656,22 @@ impl MarginManager {
         self.left_config.total_width()
     }
 
+    /// The left margin column at `col` (0-based from the margin's start)
+    ///
+    /// Returns None if `col` is past the margin or the margin is hidden.
+    pub fn left_column_at(&self, col: usize) -> Option<GutterColumn> {
+        let config = &self.left_config;
+        if col >= config.total_width() {
+            None
+        } else if col == 0 {
+            Some(GutterColumn::Sign)
+        } else if col <= config.width {
+            Some(GutterColumn::LineNumber)
+        } else {
+            Some(GutterColumn::Separator)
+        }
+    }
+
     /// Get the total width of the right margin (including separator)
     pub fn right_total_width(&self) -> usize {
         self.right_config.total_width()
@@ -678,6 +726,29 @@ mod tests {
         assert!(style.is_some());
     }
 
+    #[test]
+    fn test_left_column_at() {
+        let mut manager = MarginManager::new();
+        manager.left_config.width = 4;
+        assert_eq!(manager.left_column_at(0), Some(GutterColumn::Sign));
+        assert_eq!(manager.left_column_at(1), Some(GutterColumn::LineNumber));
+        assert_eq!(manager.left_column_at(4), Some(GutterColumn::LineNumber));
+        assert_eq!(manager.left_column_at(5), Some(GutterColumn::Separator));
+        assert_eq!(manager.left_column_at(7), Some(GutterColumn::Separator));
+        assert_eq!(manager.left_column_at(8), None);
+
+        manager.set_line_numbers(false);
+        assert_eq!(manager.left_column_at(0), None);
+
+        for column in [
+            GutterColumn::Sign,
+            GutterColumn::LineNumber,
+            GutterColumn::Separator,
+        ] {
+            assert_eq!(GutterColumn::from_name(column.name()), Some(column));
+        }
+    }
+
     #[test]
     fn test_margin_config_total_width() {
         let mut config = MarginConfig::left_default();
@@ -2,12 +2,16 @@
 //!
 //! This module contains all presentation and rendering components.
 
+pub mod confirm_dialog;
+pub mod diff_backend;
+pub mod export;
 pub mod file_tree;
 pub mod margin;
 pub mod overlay;
 pub mod popup;
 pub mod prompt;
 pub mod split;
+pub mod status_message;
 pub mod stream;
 pub mod theme;
 pub mod ui;
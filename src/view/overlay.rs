@@ -271,6 +271,33 @@ impl OverlayManager {
         }
     }
 
+    /// Remove the overlays of a namespace that overlap a range
+    pub fn clear_namespace_in_range(
+        &mut self,
+        namespace: &OverlayNamespace,
+        range: &Range<usize>,
+        marker_list: &mut MarkerList,
+    ) {
+        let in_scope =
+            |o: &Overlay| o.namespace.as_ref() == Some(namespace) && o.overlaps(range, marker_list);
+
+        // Collect markers to delete
+        let markers_to_delete: Vec<_> = self
+            .overlays
+            .iter()
+            .filter(|o| in_scope(o))
+            .flat_map(|o| vec![o.start_marker, o.end_marker])
+            .collect();
+
+        // Remove overlays
+        self.overlays.retain(|o| !in_scope(o));
+
+        // Delete markers
+        for marker_id in markers_to_delete {
+            marker_list.delete(marker_id);
+        }
+    }
+
     /// Remove all overlays in a range and clean up their markers
     pub fn remove_in_range(&mut self, range: &Range<usize>, marker_list: &mut MarkerList) {
         // Collect markers to delete
@@ -445,27 +445,23 @@ impl Popup {
     }
 
     /// Calculate the area where this popup should be rendered
+    ///
+    /// The popup is kept inside the terminal area, so it moves back into view
+    /// when the terminal shrinks.
     pub fn calculate_area(&self, terminal_area: Rect, cursor_pos: Option<(u16, u16)>) -> Rect {
-        match self.position {
+        let width = self.width.min(terminal_area.width);
+        // Use the minimum of max_height, actual content height, and terminal height
+        let height = self
+            .content_height()
+            .min(self.max_height)
+            .min(terminal_area.height);
+
+        let (x, y) = match self.position {
             PopupPosition::AtCursor | PopupPosition::BelowCursor | PopupPosition::AboveCursor => {
                 let (cursor_x, cursor_y) =
                     cursor_pos.unwrap_or((terminal_area.width / 2, terminal_area.height / 2));
 
-                let width = self.width.min(terminal_area.width);
-                // Use the minimum of max_height, actual content height, and terminal height
-                let height = self
-                    .content_height()
-                    .min(self.max_height)
-                    .min(terminal_area.height);
-
-                let x = if cursor_x + width > terminal_area.width {
-                    terminal_area.width.saturating_sub(width)
-                } else {
-                    cursor_x
-                };
-
                 let y = match self.position {
-                    PopupPosition::AtCursor => cursor_y,
                     PopupPosition::BelowCursor => {
                         if cursor_y + 2 + height > terminal_area.height {
                             // Not enough space below, put above cursor
@@ -482,42 +478,20 @@ impl Popup {
                     }
                     _ => cursor_y,
                 };
-
-                Rect {
-                    x,
-                    y,
-                    width,
-                    height,
-                }
-            }
-            PopupPosition::Fixed { x, y } => {
-                let width = self.width.min(terminal_area.width);
-                let height = self
-                    .content_height()
-                    .min(self.max_height)
-                    .min(terminal_area.height);
-                Rect {
-                    x,
-                    y,
-                    width,
-                    height,
-                }
-            }
-            PopupPosition::Centered => {
-                let width = self.width.min(terminal_area.width);
-                let height = self
-                    .content_height()
-                    .min(self.max_height)
-                    .min(terminal_area.height);
-                let x = (terminal_area.width.saturating_sub(width)) / 2;
-                let y = (terminal_area.height.saturating_sub(height)) / 2;
-                Rect {
-                    x,
-                    y,
-                    width,
-                    height,
-                }
+                (cursor_x, y)
             }
+            PopupPosition::Fixed { x, y } => (x, y),
+            PopupPosition::Centered => (
+                (terminal_area.width.saturating_sub(width)) / 2,
+                (terminal_area.height.saturating_sub(height)) / 2,
+            ),
+        };
+
+        Rect {
+            x: x.min(terminal_area.width.saturating_sub(width)),
+            y: y.min(terminal_area.height.saturating_sub(height)),
+            width,
+            height,
         }
     }
 
@@ -806,5 +780,16 @@ mod tests {
         let area = popup_below.calculate_area(terminal_area, Some((20, 10)));
         assert_eq!(area.x, 20);
         assert_eq!(area.y, 12); // Two rows below cursor (allows space for cursor line)
+
+        // Kept on screen when the terminal is smaller than its position
+        let small_area = Rect::new(0, 0, 40, 12);
+        let popup_fixed = popup
+            .clone()
+            .with_position(PopupPosition::Fixed { x: 35, y: 11 });
+        let area = popup_fixed.calculate_area(small_area, None);
+        assert_eq!((area.x, area.y, area.width), (10, 9, 30));
+        let popup_at = popup.clone().with_position(PopupPosition::AtCursor);
+        let area = popup_at.calculate_area(small_area, Some((39, 11)));
+        assert_eq!((area.x, area.y), (10, 9));
     }
 }
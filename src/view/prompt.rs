@@ -1,9 +1,10 @@
 //! Prompt/minibuffer system for user input
 
-use crate::input::commands::Suggestion;
+use crate::input::commands::{Suggestion, SuggestionAction};
 use crate::primitives::word_navigation::{
     find_word_end_bytes, find_word_start_bytes, is_word_char,
 };
+use crossterm::event::{KeyCode, KeyModifiers};
 
 /// Type of prompt - determines what action to take when user confirms
 #[derive(Debug, Clone, PartialEq)]
@@ -35,6 +36,10 @@ pub enum PromptType {
     /// Plugin-controlled prompt with custom type identifier
     /// The string identifier is used to filter hooks in plugin code
     Plugin { custom_type: String },
+    /// Prompt opened by a plugin's promptInput, promptSelect or promptConfirm
+    /// (the answer resolves the plugin's promise). With `choice` only a
+    /// listed item can be the answer, not the typed text.
+    PluginRequest { request_id: u64, choice: bool },
     /// LSP Rename operation
     /// Stores the original text, start/end positions in buffer, and overlay handle
     LspRename {
@@ -53,29 +58,89 @@ pub enum PromptType {
     JumpToBookmark,
     /// Set compose width (empty clears to viewport)
     SetComposeWidth,
+    /// Width to reflow the paragraph or selection to
+    ReflowToColumns,
     /// Stop a running LSP server (select from list)
     StopLspServer,
     /// Select a theme (select from list)
     SelectTheme,
-    /// Confirm reverting a modified file
-    ConfirmRevert,
-    /// Confirm saving over a file that changed on disk
-    ConfirmSaveConflict,
-    /// Confirm closing a modified buffer (save/discard/cancel)
-    /// Stores buffer_id to close after user confirms
-    ConfirmCloseBuffer {
-        buffer_id: crate::model::event::BufferId,
-    },
-    /// Confirm quitting with modified buffers
-    ConfirmQuitWithModified,
     /// File Explorer rename operation
     /// Stores the original path and name for the file/directory being renamed
     FileExplorerRename {
         original_path: std::path::PathBuf,
         original_name: String,
     },
+    /// File Explorer: name of a file to create in `parent`
+    FileExplorerNewFile { parent: std::path::PathBuf },
+    /// File Explorer: name of a directory to create in `parent`
+    FileExplorerNewDirectory { parent: std::path::PathBuf },
+    /// File Explorer: query narrowing the tree as it is typed
+    FileExplorerFilter,
     /// Switch to a tab by name (from the current split's open buffers)
     SwitchToTab,
+    /// Restore a session snapshot (select from list)
+    RestoreSessionSnapshot,
+    /// Name for saving the current session
+    SaveSessionAs,
+    /// Switch to a named session (select from list)
+    SwitchSession,
+    /// Name of a new buffer group
+    CreateBufferGroup,
+    /// Buffer group to move the active buffer to (select from list)
+    MoveToBufferGroup,
+    /// Buffer group to limit the tabs to (select from list)
+    SwitchBufferGroup,
+    /// Language of a new scratch buffer (select from list)
+    NewScratchBuffer,
+    /// Buffer to compare the active buffer with (select from list)
+    DiffBuffers,
+    /// Insert a snippet (select from list)
+    InsertSnippet,
+    /// Go to a symbol of the project (select from the symbol index)
+    WorkspaceSymbol,
+    /// Browse key bindings (select a binding to run its command)
+    Keybindings,
+    /// "Bind Key": keys pressed are captured literally as the chord to bind
+    BindKey,
+    /// "Bind Key": the command to bind `chord` to (select from list)
+    BindKeyCommand { chord: String },
+    /// File to export the buffer (or selection) to
+    Export {
+        format: crate::view::export::ExportFormat,
+    },
+}
+
+/// Inline validation error for the current prompt input
+///
+/// Shown next to the prompt while the user types (e.g., an invalid regex in the
+/// search prompt), so problems are visible before the input is confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptError {
+    /// Byte offset into the input where the error was detected (if known)
+    pub position: Option<usize>,
+    /// Human-readable description of the error
+    pub message: String,
+}
+
+/// Match counter for an incremental search prompt ("3 of 47")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchCount {
+    /// 1-based index of the match the search would jump to (None if there are no matches)
+    pub current: Option<usize>,
+    /// Total number of matches
+    pub total: usize,
+    /// Whether counting stopped at the search match limit (`total` is a
+    /// lower bound)
+    pub truncated: bool,
+}
+
+/// A row of the suggestions popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionRow<'a> {
+    /// Section header (can't be selected)
+    Header(&'a str),
+    /// The suggestion at this index
+    Suggestion(usize),
 }
 
 /// Prompt state for the minibuffer
@@ -95,9 +160,18 @@ pub struct Prompt {
     pub original_suggestions: Option<Vec<Suggestion>>,
     /// Currently selected suggestion index
     pub selected_suggestion: Option<usize>,
+    /// Section headers shown in the suggestions popup:
+    /// (index of the section's first suggestion, title), in order
+    pub sections: Vec<(usize, String)>,
     /// Selection anchor position (for Shift+Arrow selection)
     /// When Some(pos), there's a selection from anchor to cursor_pos
     pub selection_anchor: Option<usize>,
+    /// Validation error for the current input (e.g., regex syntax error)
+    pub error: Option<PromptError>,
+    /// Match counter shown at the right of search prompts
+    pub match_count: Option<MatchCount>,
+    /// Whether path completions include hidden files (file prompts)
+    pub show_hidden_files: bool,
 }
 
 impl Prompt {
@@ -111,7 +185,11 @@ impl Prompt {
             suggestions: Vec::new(),
             original_suggestions: None,
             selected_suggestion: None,
+            sections: Vec::new(),
             selection_anchor: None,
+            error: None,
+            match_count: None,
+            show_hidden_files: false,
         }
     }
 
@@ -137,7 +215,11 @@ impl Prompt {
             original_suggestions: Some(suggestions.clone()),
             suggestions,
             selected_suggestion,
+            sections: Vec::new(),
             selection_anchor: None,
+            error: None,
+            match_count: None,
+            show_hidden_files: false,
         }
     }
 
@@ -156,8 +238,44 @@ impl Prompt {
             suggestions: Vec::new(),
             original_suggestions: None,
             selected_suggestion: None,
+            sections: Vec::new(),
             selection_anchor: None,
+            error: None,
+            match_count: None,
+            show_hidden_files: false,
+        }
+    }
+
+    /// Rows of the suggestions popup: the suggestions with their section headers
+    pub fn suggestion_rows(&self) -> Vec<SuggestionRow<'_>> {
+        let mut rows = Vec::with_capacity(self.suggestions.len() + self.sections.len());
+        let mut sections = self.sections.iter().peekable();
+        for idx in 0..self.suggestions.len() {
+            while let Some((_, title)) = sections.next_if(|(start, _)| *start <= idx) {
+                rows.push(SuggestionRow::Header(title));
+            }
+            rows.push(SuggestionRow::Suggestion(idx));
         }
+        rows
+    }
+
+    /// The selected suggestion's action that a key press runs, if any
+    pub fn selected_action(
+        &self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<&SuggestionAction> {
+        let selected = self.suggestions.get(self.selected_suggestion?)?;
+        selected
+            .actions
+            .iter()
+            .find(|action| action.matches(code, modifiers))
+    }
+
+    /// Whether any suggestion has secondary actions, so the suggestions
+    /// popup has a row listing them
+    pub fn has_action_hints(&self) -> bool {
+        self.suggestions.iter().any(|s| !s.actions.is_empty())
     }
 
     /// Move cursor left
@@ -192,6 +192,22 @@ impl SplitViewState {
     pub fn has_buffer(&self, buffer_id: BufferId) -> bool {
         self.open_buffers.contains(&buffer_id)
     }
+
+    /// Move a buffer's tab `offset` places to the right (left if negative),
+    /// stopping at either end. Returns whether it moved.
+    pub fn move_buffer(&mut self, buffer_id: BufferId, offset: isize) -> bool {
+        let Some(from) = self.open_buffers.iter().position(|&id| id == buffer_id) else {
+            return false;
+        };
+        let last = self.open_buffers.len() as isize - 1;
+        let to = (from as isize + offset).clamp(0, last) as usize;
+        if to == from {
+            return false;
+        }
+        let id = self.open_buffers.remove(from);
+        self.open_buffers.insert(to, id);
+        true
+    }
 }
 
 impl SplitNode {
@@ -445,6 +461,9 @@ pub struct SplitManager {
 
     /// Next split ID to assign
     next_split_id: usize,
+
+    /// Whether the active split fills the whole area, hiding the others
+    zoomed: bool,
 }
 
 impl SplitManager {
@@ -455,6 +474,7 @@ impl SplitManager {
             root: SplitNode::leaf(buffer_id, split_id),
             active_split: split_id,
             next_split_id: 1,
+            zoomed: false,
         }
     }
 
@@ -535,8 +555,9 @@ impl SplitManager {
         let result = self.replace_split_with_split(active_id, direction, new_buffer_id, ratio);
 
         if let Ok(new_split_id) = result {
-            // Set the new split as active
+            // Set the new split as active, next to the others again
             self.active_split = new_split_id;
+            self.zoomed = false;
             Ok(new_split_id)
         } else {
             result
@@ -607,7 +628,9 @@ impl SplitManager {
         let result = self.remove_split_node(split_id);
 
         // If we closed the active split, update active_split to another split
+        // and show all splits again
         if result.is_ok() && self.active_split == split_id {
+            self.zoomed = false;
             let leaf_ids = self.root.leaf_split_ids();
             if let Some(&first_leaf) = leaf_ids.first() {
                 self.active_split = first_leaf;
@@ -670,14 +693,35 @@ impl SplitManager {
         }
     }
 
+    /// Whether the active split is zoomed to fill the whole area
+    pub fn is_zoomed(&self) -> bool {
+        self.zoomed
+    }
+
+    /// Zoom the active split to fill the whole area, or go back to showing
+    /// all splits. The layout itself is kept as it was. Returns whether it is
+    /// now zoomed.
+    pub fn toggle_zoom(&mut self) -> bool {
+        self.zoomed = !self.zoomed && self.root.count_leaves() > 1;
+        self.zoomed
+    }
+
     /// Get all visible buffer views with their rectangles
     pub fn get_visible_buffers(&self, viewport_rect: Rect) -> Vec<(SplitId, BufferId, Rect)> {
+        if self.zoomed {
+            if let Some(buffer_id) = self.active_buffer_id() {
+                return vec![(self.active_split, buffer_id, viewport_rect)];
+            }
+        }
         self.root.get_leaves_with_rects(viewport_rect)
     }
 
     /// Get all split separator positions for rendering borders
     /// Returns (direction, x, y, length) tuples
     pub fn get_separators(&self, viewport_rect: Rect) -> Vec<(SplitDirection, u16, u16, u16)> {
+        if self.zoomed {
+            return Vec::new();
+        }
         self.root.get_separators(viewport_rect)
     }
 
@@ -687,6 +731,9 @@ impl SplitManager {
         &self,
         viewport_rect: Rect,
     ) -> Vec<(SplitId, SplitDirection, u16, u16, u16)> {
+        if self.zoomed {
+            return Vec::new();
+        }
         self.root.get_separators_with_ids(viewport_rect)
     }
 
@@ -803,6 +850,29 @@ impl SplitManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_move_buffer_tab() {
+        let mut view_state = SplitViewState::new(80, 24);
+        for id in 0..3 {
+            view_state.add_buffer(BufferId(id));
+        }
+
+        assert!(view_state.move_buffer(BufferId(0), 1));
+        assert_eq!(
+            view_state.open_buffers,
+            vec![BufferId(1), BufferId(0), BufferId(2)]
+        );
+        assert!(view_state.move_buffer(BufferId(2), -2));
+        assert_eq!(
+            view_state.open_buffers,
+            vec![BufferId(2), BufferId(1), BufferId(0)]
+        );
+        // Tabs stop at the ends
+        assert!(!view_state.move_buffer(BufferId(2), -1));
+        assert!(!view_state.move_buffer(BufferId(0), 1));
+        assert!(!view_state.move_buffer(BufferId(7), 1));
+    }
+
     #[test]
     fn test_create_split_manager() {
         let buffer_id = BufferId(0);
@@ -884,6 +954,37 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_zoom_shows_only_the_active_split() {
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: 80,
+            height: 24,
+        };
+        let mut manager = SplitManager::new(BufferId(0));
+        assert!(!manager.toggle_zoom(), "a single split can't be zoomed");
+
+        let second = manager
+            .split_active(SplitDirection::Vertical, BufferId(1), 0.5)
+            .unwrap();
+        assert!(manager.toggle_zoom());
+        assert_eq!(
+            manager.get_visible_buffers(area),
+            vec![(second, BufferId(1), area)]
+        );
+        assert!(manager.get_separators(area).is_empty());
+
+        // Focusing the other split keeps it zoomed, showing that split
+        manager.prev_split();
+        assert_eq!(manager.get_visible_buffers(area).len(), 1);
+        assert_eq!(manager.get_visible_buffers(area)[0].1, BufferId(0));
+
+        assert!(!manager.toggle_zoom());
+        assert_eq!(manager.get_visible_buffers(area).len(), 2);
+        assert_eq!(manager.get_separators(area).len(), 1);
+    }
+
     #[test]
     fn test_split_rect_horizontal() {
         let rect = Rect {
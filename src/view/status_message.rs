@@ -0,0 +1,208 @@
+//! Status bar message queue
+//!
+//! Several messages can be active at once and are shown side by side, newest
+//! first, so a warning or error isn't lost when the next action posts a message
+//! of its own. Each message goes away when its display duration is up; messages
+//! without a duration (sticky errors by default) stay until they are dismissed.
+//! Info messages are transient: a newer info message replaces the previous one.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Maximum number of messages kept at once (the oldest are dropped first)
+const MAX_MESSAGES: usize = 8;
+
+/// Severity of a status message (determines its color)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatusLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A message in the status bar
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub level: StatusLevel,
+    /// How long the message stays up (None = until dismissed)
+    pub duration: Option<Duration>,
+    /// When the message was posted
+    posted: Instant,
+}
+
+impl StatusMessage {
+    /// Whether the message stays up until it is dismissed
+    pub fn is_sticky(&self) -> bool {
+        self.duration.is_none()
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.duration
+            .is_some_and(|duration| now.saturating_duration_since(self.posted) >= duration)
+    }
+}
+
+/// Active status messages, newest first
+#[derive(Debug, Default)]
+pub struct StatusMessageQueue {
+    messages: VecDeque<StatusMessage>,
+}
+
+impl StatusMessageQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Post a message
+    ///
+    /// `duration` is how long it stays up; None keeps it until dismissed.
+    pub fn push(&mut self, text: String, level: StatusLevel, duration: Option<Duration>) {
+        // Posting the same message again moves it to the front and restarts it
+        self.messages
+            .retain(|m| !(m.text == text && m.level == level));
+        if level == StatusLevel::Info {
+            // Only the latest info message is kept
+            self.messages.retain(|m| m.level != StatusLevel::Info);
+        }
+
+        self.messages.push_front(StatusMessage {
+            text,
+            level,
+            duration,
+            posted: Instant::now(),
+        });
+        self.messages.truncate(MAX_MESSAGES);
+    }
+
+    /// The most recent message
+    pub fn latest(&self) -> Option<&StatusMessage> {
+        self.messages.front()
+    }
+
+    /// The newest error posted at or after `since`
+    pub fn error_since(&self, since: Instant) -> Option<&StatusMessage> {
+        self.messages
+            .iter()
+            .find(|m| m.level == StatusLevel::Error && m.posted >= since)
+    }
+
+    /// All active messages, newest first
+    pub fn iter(&self) -> impl Iterator<Item = &StatusMessage> {
+        self.messages.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Remove messages whose display time is up
+    ///
+    /// Returns true if any message was removed.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let count = self.messages.len();
+        self.messages.retain(|m| !m.is_expired(now));
+        self.messages.len() != count
+    }
+
+    /// Dismiss the most important message (the newest of the highest level)
+    ///
+    /// Returns false if there was nothing to dismiss.
+    pub fn dismiss(&mut self) -> bool {
+        let Some(max_level) = self.messages.iter().map(|m| m.level).max() else {
+            return false;
+        };
+        let index = self
+            .messages
+            .iter()
+            .position(|m| m.level == max_level)
+            .unwrap_or(0);
+        self.messages.remove(index).is_some()
+    }
+
+    /// Remove all messages
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORT: Option<Duration> = Some(Duration::from_secs(1));
+
+    fn texts(queue: &StatusMessageQueue) -> Vec<&str> {
+        queue.iter().map(|m| m.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_info_replaces_info() {
+        let mut queue = StatusMessageQueue::new();
+        queue.push("Copied".to_string(), StatusLevel::Info, SHORT);
+        queue.push("Pasted".to_string(), StatusLevel::Info, SHORT);
+        assert_eq!(texts(&queue), vec!["Pasted"]);
+    }
+
+    #[test]
+    fn test_error_is_not_overwritten() {
+        let mut queue = StatusMessageQueue::new();
+        queue.push("Save failed".to_string(), StatusLevel::Error, None);
+        queue.push("Copied".to_string(), StatusLevel::Info, SHORT);
+        queue.push("Pasted".to_string(), StatusLevel::Info, SHORT);
+
+        // The error stays up next to the newest info message
+        assert_eq!(texts(&queue), vec!["Pasted", "Save failed"]);
+        assert_eq!(queue.latest().unwrap().text, "Pasted");
+
+        // Sticky errors don't expire
+        let later = Instant::now() + Duration::from_secs(3600);
+        assert!(queue.expire(later));
+        assert_eq!(texts(&queue), vec!["Save failed"]);
+        assert!(queue.latest().unwrap().is_sticky());
+
+        assert!(queue.dismiss());
+        assert!(queue.is_empty());
+        assert!(!queue.dismiss());
+    }
+
+    #[test]
+    fn test_dismiss_most_important_first() {
+        let mut queue = StatusMessageQueue::new();
+        queue.push("Low disk space".to_string(), StatusLevel::Warning, SHORT);
+        queue.push("Save failed".to_string(), StatusLevel::Error, None);
+        queue.push("Saved".to_string(), StatusLevel::Info, SHORT);
+
+        assert!(queue.dismiss());
+        assert_eq!(texts(&queue), vec!["Saved", "Low disk space"]);
+        assert!(queue.dismiss());
+        assert_eq!(texts(&queue), vec!["Saved"]);
+    }
+
+    #[test]
+    fn test_messages_expire() {
+        let mut queue = StatusMessageQueue::new();
+        queue.push(
+            "Disk almost full".to_string(),
+            StatusLevel::Warning,
+            Some(Duration::from_secs(5)),
+        );
+        queue.push("Saved".to_string(), StatusLevel::Info, SHORT);
+
+        let now = Instant::now();
+        assert!(!queue.expire(now));
+        assert!(queue.expire(now + Duration::from_secs(2)));
+        assert_eq!(texts(&queue), vec!["Disk almost full"]);
+        assert!(queue.expire(now + Duration::from_secs(6)));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_repeated_message_is_not_duplicated() {
+        let mut queue = StatusMessageQueue::new();
+        queue.push("Failed".to_string(), StatusLevel::Error, None);
+        queue.push("Saved".to_string(), StatusLevel::Info, SHORT);
+        queue.push("Failed".to_string(), StatusLevel::Error, None);
+        assert_eq!(texts(&queue), vec!["Failed", "Saved"]);
+    }
+}
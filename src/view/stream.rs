@@ -47,13 +47,17 @@ pub struct ViewStream {
     pub tokens: Vec<ViewToken>,
     /// Mapping from view token index to source offset (if present)
     pub source_map: Vec<Option<usize>>,
+    /// Buffer revision the tokens were built from; the source offsets only
+    /// hold for that revision
+    pub revision: u64,
 }
 
 impl ViewStream {
-    pub fn new() -> Self {
+    pub fn new(revision: u64) -> Self {
         Self {
             tokens: Vec::new(),
             source_map: Vec::new(),
+            revision,
         }
     }
 
@@ -67,7 +71,7 @@ impl ViewStream {
 /// This stream contains plain text and newline tokens only; overlays and virtual
 /// text are not included here (they remain applied during rendering).
 pub fn build_base_stream(state: &mut EditorState, start: usize, end: usize) -> ViewStream {
-    let mut stream = ViewStream::new();
+    let mut stream = ViewStream::new(state.edit_history.revision());
 
     if start >= end {
         return stream;
@@ -108,6 +108,14 @@ struct UiColors {
     menu_disabled_bg: ColorDef,
     status_bar_fg: ColorDef,
     status_bar_bg: ColorDef,
+    #[serde(default = "default_status_warning_fg")]
+    status_warning_fg: ColorDef,
+    #[serde(default = "default_status_warning_bg")]
+    status_warning_bg: ColorDef,
+    #[serde(default = "default_status_error_fg")]
+    status_error_fg: ColorDef,
+    #[serde(default = "default_status_error_bg")]
+    status_error_bg: ColorDef,
     prompt_fg: ColorDef,
     prompt_bg: ColorDef,
     prompt_selection_fg: ColorDef,
@@ -116,8 +124,18 @@ struct UiColors {
     popup_bg: ColorDef,
     popup_selection_bg: ColorDef,
     popup_text_fg: ColorDef,
+    #[serde(default = "default_diff_added_fg")]
+    diff_added_fg: ColorDef,
+    #[serde(default = "default_diff_removed_fg")]
+    diff_removed_fg: ColorDef,
     suggestion_bg: ColorDef,
     suggestion_selected_bg: ColorDef,
+    #[serde(default = "default_suggestion_name_fg")]
+    suggestion_name_fg: ColorDef,
+    #[serde(default = "default_suggestion_keybinding_fg")]
+    suggestion_keybinding_fg: ColorDef,
+    #[serde(default = "default_suggestion_description_fg")]
+    suggestion_description_fg: ColorDef,
     help_bg: ColorDef,
     help_fg: ColorDef,
     help_key_fg: ColorDef,
@@ -219,6 +237,35 @@ fn default_semantic_highlight_bg() -> ColorDef {
     ColorDef::Rgb(60, 60, 80) // Subtle dark highlight for word occurrences
 }
 
+// Default suggestion column colors (for backward compatibility with existing themes)
+fn default_status_warning_fg() -> ColorDef {
+    ColorDef::Named("Black".to_string())
+}
+fn default_status_warning_bg() -> ColorDef {
+    ColorDef::Named("Yellow".to_string())
+}
+fn default_status_error_fg() -> ColorDef {
+    ColorDef::Named("White".to_string())
+}
+fn default_status_error_bg() -> ColorDef {
+    ColorDef::Named("Red".to_string())
+}
+fn default_diff_added_fg() -> ColorDef {
+    ColorDef::Named("Green".to_string())
+}
+fn default_diff_removed_fg() -> ColorDef {
+    ColorDef::Named("Red".to_string())
+}
+fn default_suggestion_name_fg() -> ColorDef {
+    ColorDef::Named("White".to_string())
+}
+fn default_suggestion_keybinding_fg() -> ColorDef {
+    ColorDef::Named("Cyan".to_string())
+}
+fn default_suggestion_description_fg() -> ColorDef {
+    ColorDef::Rgb(170, 170, 170) // Dimmer than the name column
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SearchColors {
     match_bg: ColorDef,
@@ -293,6 +340,11 @@ pub struct Theme {
 
     pub status_bar_fg: Color,
     pub status_bar_bg: Color,
+    /// Status bar colors for warning and error messages
+    pub status_warning_fg: Color,
+    pub status_warning_bg: Color,
+    pub status_error_fg: Color,
+    pub status_error_bg: Color,
     pub prompt_fg: Color,
     pub prompt_bg: Color,
     pub prompt_selection_fg: Color,
@@ -303,8 +355,15 @@ pub struct Theme {
     pub popup_selection_bg: Color,
     pub popup_text_fg: Color,
 
+    // Diff colors (added/removed lines in change previews)
+    pub diff_added_fg: Color,
+    pub diff_removed_fg: Color,
+
     pub suggestion_bg: Color,
     pub suggestion_selected_bg: Color,
+    pub suggestion_name_fg: Color,
+    pub suggestion_keybinding_fg: Color,
+    pub suggestion_description_fg: Color,
 
     pub help_bg: Color,
     pub help_fg: Color,
@@ -392,6 +451,10 @@ impl From<ThemeFile> for Theme {
             menu_disabled_bg: file.ui.menu_disabled_bg.into(),
             status_bar_fg: file.ui.status_bar_fg.into(),
             status_bar_bg: file.ui.status_bar_bg.into(),
+            status_warning_fg: file.ui.status_warning_fg.into(),
+            status_warning_bg: file.ui.status_warning_bg.into(),
+            status_error_fg: file.ui.status_error_fg.into(),
+            status_error_bg: file.ui.status_error_bg.into(),
             prompt_fg: file.ui.prompt_fg.into(),
             prompt_bg: file.ui.prompt_bg.into(),
             prompt_selection_fg: file.ui.prompt_selection_fg.into(),
@@ -400,8 +463,13 @@ impl From<ThemeFile> for Theme {
             popup_bg: file.ui.popup_bg.into(),
             popup_selection_bg: file.ui.popup_selection_bg.into(),
             popup_text_fg: file.ui.popup_text_fg.into(),
+            diff_added_fg: file.ui.diff_added_fg.into(),
+            diff_removed_fg: file.ui.diff_removed_fg.into(),
             suggestion_bg: file.ui.suggestion_bg.into(),
             suggestion_selected_bg: file.ui.suggestion_selected_bg.into(),
+            suggestion_name_fg: file.ui.suggestion_name_fg.into(),
+            suggestion_keybinding_fg: file.ui.suggestion_keybinding_fg.into(),
+            suggestion_description_fg: file.ui.suggestion_description_fg.into(),
             help_bg: file.ui.help_bg.into(),
             help_fg: file.ui.help_fg.into(),
             help_key_fg: file.ui.help_key_fg.into(),
@@ -510,6 +578,10 @@ impl Theme {
 
             status_bar_fg: Color::White,
             status_bar_bg: Color::Rgb(30, 30, 30), // Darker than DarkGray
+            status_warning_fg: Color::Black,
+            status_warning_bg: Color::Yellow,
+            status_error_fg: Color::White,
+            status_error_bg: Color::Red,
             prompt_fg: Color::White,
             prompt_bg: Color::Rgb(20, 20, 20), // Very dark
             prompt_selection_fg: Color::White,
@@ -519,9 +591,14 @@ impl Theme {
             popup_bg: Color::Rgb(30, 30, 30),
             popup_selection_bg: Color::Rgb(58, 79, 120),
             popup_text_fg: Color::White,
+            diff_added_fg: Color::Green,
+            diff_removed_fg: Color::Red,
 
             suggestion_bg: Color::Rgb(30, 30, 30),
             suggestion_selected_bg: Color::Rgb(58, 79, 120),
+            suggestion_name_fg: Color::White,
+            suggestion_keybinding_fg: Color::Cyan,
+            suggestion_description_fg: Color::Rgb(170, 170, 170),
 
             help_bg: Color::Black,
             help_fg: Color::White,
@@ -616,6 +693,10 @@ impl Theme {
 
             status_bar_fg: Color::Black,
             status_bar_bg: Color::Rgb(220, 220, 220), // Light grey
+            status_warning_fg: Color::Black,
+            status_warning_bg: Color::Rgb(255, 220, 100),
+            status_error_fg: Color::White,
+            status_error_bg: Color::Rgb(200, 40, 40),
             prompt_fg: Color::Black,
             prompt_bg: Color::Rgb(230, 240, 250), // Very light blue
             prompt_selection_fg: Color::Black,
@@ -625,9 +706,14 @@ impl Theme {
             popup_bg: Color::Rgb(232, 238, 245), // Light blue-gray
             popup_selection_bg: Color::Rgb(209, 226, 243),
             popup_text_fg: Color::Rgb(30, 30, 30),
+            diff_added_fg: Color::Rgb(0, 130, 0),
+            diff_removed_fg: Color::Rgb(190, 30, 30),
 
             suggestion_bg: Color::Rgb(232, 238, 245), // Light blue-gray
             suggestion_selected_bg: Color::Rgb(209, 226, 243),
+            suggestion_name_fg: Color::Black,
+            suggestion_keybinding_fg: Color::Blue,
+            suggestion_description_fg: Color::Rgb(90, 90, 90),
 
             help_bg: Color::White,
             help_fg: Color::Black,
@@ -722,6 +808,10 @@ impl Theme {
 
             status_bar_fg: Color::White,
             status_bar_bg: Color::Rgb(20, 20, 20), // Darker for high contrast
+            status_warning_fg: Color::Black,
+            status_warning_bg: Color::Yellow,
+            status_error_fg: Color::White,
+            status_error_bg: Color::Red,
             prompt_fg: Color::White,
             prompt_bg: Color::Rgb(10, 10, 10), // Very dark
             prompt_selection_fg: Color::White,
@@ -731,9 +821,14 @@ impl Theme {
             popup_bg: Color::Black,
             popup_selection_bg: Color::Rgb(0, 100, 200),
             popup_text_fg: Color::White,
+            diff_added_fg: Color::Green,
+            diff_removed_fg: Color::Red,
 
             suggestion_bg: Color::Black,
             suggestion_selected_bg: Color::Rgb(0, 100, 200),
+            suggestion_name_fg: Color::White,
+            suggestion_keybinding_fg: Color::LightCyan,
+            suggestion_description_fg: Color::White,
 
             help_bg: Color::Black,
             help_fg: Color::White,
@@ -779,9 +874,9 @@ impl Theme {
             syntax_string: Color::Green,
             syntax_comment: Color::Gray,
             syntax_function: Color::Yellow,
-            syntax_type: Color::Magenta,
+            syntax_type: Color::LightMagenta,
             syntax_variable: Color::White,
-            syntax_constant: Color::LightBlue,
+            syntax_constant: Color::Rgb(120, 160, 255),
             syntax_operator: Color::White,
         }
     }
@@ -801,13 +896,22 @@ impl Theme {
             "light" => Self::light(),
             "high-contrast" => Self::high_contrast(),
             "nostalgia" => Self::nostalgia(),
+            "deuteranopia" => Self::deuteranopia(),
+            "protanopia" => Self::protanopia(),
             _ => Self::dark(),
         }
     }
 
     /// Get all available theme names
     pub fn available_themes() -> Vec<&'static str> {
-        vec!["dark", "light", "high-contrast", "nostalgia"]
+        vec![
+            "dark",
+            "light",
+            "high-contrast",
+            "nostalgia",
+            "deuteranopia",
+            "protanopia",
+        ]
     }
 
     /// Nostalgia theme (Turbo Pascal 5 / WordPerfect 5 inspired)
@@ -852,8 +956,12 @@ impl Theme {
 
             status_bar_fg: Color::Rgb(0, 0, 0),
             status_bar_bg: Color::Rgb(0, 170, 170), // Cyan status bar
-            prompt_fg: Color::Rgb(255, 255, 85),    // Yellow text
-            prompt_bg: Color::Rgb(0, 0, 170),       // Blue background
+            status_warning_fg: Color::Rgb(0, 0, 0),
+            status_warning_bg: Color::Rgb(255, 255, 85),
+            status_error_fg: Color::Rgb(255, 255, 255),
+            status_error_bg: Color::Rgb(170, 0, 0),
+            prompt_fg: Color::Rgb(255, 255, 85), // Yellow text
+            prompt_bg: Color::Rgb(0, 0, 170),    // Blue background
             prompt_selection_fg: Color::Rgb(0, 0, 0),
             prompt_selection_bg: Color::Rgb(170, 170, 170),
 
@@ -861,9 +969,14 @@ impl Theme {
             popup_bg: Color::Rgb(0, 0, 170),
             popup_selection_bg: Color::Rgb(0, 170, 0),
             popup_text_fg: Color::Rgb(255, 255, 85),
+            diff_added_fg: Color::Rgb(85, 255, 85),
+            diff_removed_fg: Color::Rgb(255, 85, 85),
 
             suggestion_bg: Color::Rgb(0, 0, 170),
             suggestion_selected_bg: Color::Rgb(0, 170, 0),
+            suggestion_name_fg: Color::Rgb(255, 255, 85),
+            suggestion_keybinding_fg: Color::Rgb(85, 255, 255),
+            suggestion_description_fg: Color::Rgb(255, 255, 255),
 
             help_bg: Color::Rgb(0, 0, 170),
             help_fg: Color::Rgb(255, 255, 85),
@@ -915,6 +1028,139 @@ impl Theme {
             syntax_operator: Color::Rgb(170, 170, 170), // Light gray operators
         }
     }
+
+    /// Deuteranopia-friendly theme (red-green color blindness)
+    ///
+    /// Built on the Okabe-Ito palette: meaning is carried by blue, orange and
+    /// yellow, never by red against green. Added/removed lines are blue and
+    /// orange, errors are vermillion and warnings yellow.
+    pub fn deuteranopia() -> Self {
+        Self {
+            name: "deuteranopia".to_string(),
+
+            // Editor colors
+            editor_bg: Color::Rgb(18, 18, 18),
+            editor_fg: Color::Rgb(230, 230, 230),
+            cursor: Color::Rgb(240, 228, 66), // Yellow
+            inactive_cursor: Color::Rgb(110, 110, 110),
+            selection_bg: Color::Rgb(0, 70, 130),
+            current_line_bg: Color::Rgb(35, 35, 35),
+            line_number_fg: Color::Rgb(140, 140, 140),
+            line_number_bg: Color::Rgb(18, 18, 18),
+
+            // UI element colors
+            tab_active_fg: Color::Black,
+            tab_active_bg: Color::Rgb(86, 180, 233), // Sky blue
+            tab_inactive_fg: Color::Rgb(200, 200, 200),
+            tab_inactive_bg: Color::Rgb(18, 18, 18),
+            tab_separator_bg: Color::Rgb(40, 40, 40),
+            tab_close_hover_fg: Color::Rgb(230, 159, 0), // Orange
+            tab_hover_bg: Color::Rgb(50, 50, 50),
+
+            // Menu bar colors
+            menu_bg: Color::Rgb(45, 45, 45),
+            menu_fg: Color::Rgb(230, 230, 230),
+            menu_active_bg: Color::Rgb(86, 180, 233),
+            menu_active_fg: Color::Black,
+            menu_dropdown_bg: Color::Rgb(30, 30, 30),
+            menu_dropdown_fg: Color::Rgb(230, 230, 230),
+            menu_highlight_bg: Color::Rgb(0, 90, 160),
+            menu_highlight_fg: Color::White,
+            menu_border_fg: Color::Rgb(86, 180, 233),
+            menu_separator_fg: Color::Rgb(120, 120, 120),
+            menu_hover_bg: Color::Rgb(60, 60, 60),
+            menu_hover_fg: Color::Rgb(240, 228, 66),
+            menu_disabled_fg: Color::Rgb(110, 110, 110),
+            menu_disabled_bg: Color::Rgb(30, 30, 30),
+
+            status_bar_fg: Color::Rgb(230, 230, 230),
+            status_bar_bg: Color::Rgb(45, 45, 45),
+            status_warning_fg: Color::Black,
+            status_warning_bg: Color::Rgb(240, 228, 66), // Yellow
+            status_error_fg: Color::Black,
+            status_error_bg: Color::Rgb(213, 94, 0), // Vermillion
+            prompt_fg: Color::Rgb(230, 230, 230),
+            prompt_bg: Color::Rgb(18, 18, 18),
+            prompt_selection_fg: Color::White,
+            prompt_selection_bg: Color::Rgb(0, 90, 160),
+
+            popup_border_fg: Color::Rgb(86, 180, 233),
+            popup_bg: Color::Rgb(30, 30, 30),
+            popup_selection_bg: Color::Rgb(0, 90, 160),
+            popup_text_fg: Color::Rgb(230, 230, 230),
+            diff_added_fg: Color::Rgb(86, 180, 233), // Blue instead of green
+            diff_removed_fg: Color::Rgb(230, 159, 0), // Orange instead of red
+
+            suggestion_bg: Color::Rgb(30, 30, 30),
+            suggestion_selected_bg: Color::Rgb(0, 90, 160),
+            suggestion_name_fg: Color::Rgb(230, 230, 230),
+            suggestion_keybinding_fg: Color::Rgb(86, 180, 233),
+            suggestion_description_fg: Color::Rgb(180, 180, 180),
+
+            help_bg: Color::Rgb(30, 30, 30),
+            help_fg: Color::Rgb(230, 230, 230),
+            help_key_fg: Color::Rgb(86, 180, 233),
+            help_separator_fg: Color::Rgb(120, 120, 120),
+
+            help_indicator_fg: Color::Rgb(230, 159, 0),
+            help_indicator_bg: Color::Rgb(18, 18, 18),
+
+            inline_code_bg: Color::Rgb(45, 45, 45),
+
+            split_separator_fg: Color::Rgb(100, 100, 100),
+            split_separator_hover_fg: Color::Rgb(86, 180, 233),
+
+            // Scrollbar colors
+            scrollbar_track_fg: Color::Rgb(45, 45, 45),
+            scrollbar_thumb_fg: Color::Rgb(120, 120, 120),
+            scrollbar_track_hover_fg: Color::Rgb(60, 60, 60),
+            scrollbar_thumb_hover_fg: Color::Rgb(86, 180, 233),
+
+            // Compose mode colors
+            compose_margin_bg: Color::Rgb(10, 10, 10),
+
+            // Semantic highlighting (word under cursor)
+            semantic_highlight_bg: Color::Rgb(0, 60, 100),
+
+            // Search colors
+            search_match_bg: Color::Rgb(240, 228, 66),
+            search_match_fg: Color::Black,
+
+            // Diagnostic colors
+            diagnostic_error_fg: Color::Rgb(213, 94, 0),
+            diagnostic_error_bg: Color::Rgb(70, 35, 0),
+            diagnostic_warning_fg: Color::Rgb(240, 228, 66),
+            diagnostic_warning_bg: Color::Rgb(60, 55, 0),
+            diagnostic_info_fg: Color::Rgb(86, 180, 233),
+            diagnostic_info_bg: Color::Rgb(0, 45, 75),
+            diagnostic_hint_fg: Color::Rgb(180, 180, 180),
+            diagnostic_hint_bg: Color::Rgb(45, 45, 45),
+
+            // Syntax highlighting colors (Okabe-Ito)
+            syntax_keyword: Color::Rgb(86, 180, 233), // Sky blue
+            syntax_string: Color::Rgb(230, 159, 0),   // Orange
+            syntax_comment: Color::Rgb(150, 150, 150), // Gray
+            syntax_function: Color::Rgb(240, 228, 66), // Yellow
+            syntax_type: Color::Rgb(0, 158, 115),     // Bluish green
+            syntax_variable: Color::Rgb(230, 230, 230),
+            syntax_constant: Color::Rgb(204, 121, 167), // Reddish purple
+            syntax_operator: Color::Rgb(200, 200, 200),
+        }
+    }
+
+    /// Protanopia-friendly theme (red-blind color blindness)
+    ///
+    /// Same palette as the deuteranopia theme, except that errors are orange:
+    /// vermillion looks almost black to protanopes.
+    pub fn protanopia() -> Self {
+        Self {
+            name: "protanopia".to_string(),
+            status_error_bg: Color::Rgb(230, 159, 0),
+            diagnostic_error_fg: Color::Rgb(230, 159, 0),
+            diagnostic_error_bg: Color::Rgb(75, 50, 0),
+            ..Self::deuteranopia()
+        }
+    }
 }
 
 impl Default for Theme {
@@ -923,6 +1169,203 @@ impl Default for Theme {
     }
 }
 
+/// Minimum contrast ratio for text (WCAG AA for normal text)
+pub const MIN_TEXT_CONTRAST: f64 = 4.5;
+
+/// Minimum contrast ratio for secondary text and markers such as line numbers,
+/// comments and diagnostics (WCAG AA for large text and UI components)
+pub const MIN_SECONDARY_CONTRAST: f64 = 3.0;
+
+/// A foreground/background pair of a theme with too little contrast
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    /// Field name of the foreground color (e.g., "editor_fg")
+    pub fg: &'static str,
+    /// Field name of the background color (e.g., "editor_bg")
+    pub bg: &'static str,
+    /// Contrast ratio between the two colors
+    pub ratio: f64,
+    /// Minimum ratio the pair needs
+    pub required: f64,
+}
+
+impl std::fmt::Display for ContrastIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} on {}: {:.2}:1 (needs {:.1}:1)",
+            self.fg, self.bg, self.ratio, self.required
+        )
+    }
+}
+
+/// A named foreground/background pair of theme colors and its minimum contrast
+macro_rules! pair {
+    ($theme:expr, $fg:ident on $bg:ident, $required:expr) => {
+        (
+            stringify!($fg),
+            $theme.$fg,
+            stringify!($bg),
+            $theme.$bg,
+            $required,
+        )
+    };
+}
+
+impl Theme {
+    /// Foreground/background pairs text is drawn with, and the contrast each needs
+    fn contrast_pairs(&self) -> Vec<(&'static str, Color, &'static str, Color, f64)> {
+        const TEXT: f64 = MIN_TEXT_CONTRAST;
+        const SECONDARY: f64 = MIN_SECONDARY_CONTRAST;
+        vec![
+            pair!(self, editor_fg on editor_bg, TEXT),
+            pair!(self, editor_fg on selection_bg, TEXT),
+            pair!(self, editor_fg on current_line_bg, TEXT),
+            pair!(self, line_number_fg on line_number_bg, SECONDARY),
+            pair!(self, tab_active_fg on tab_active_bg, TEXT),
+            pair!(self, tab_inactive_fg on tab_inactive_bg, TEXT),
+            pair!(self, menu_fg on menu_bg, TEXT),
+            pair!(self, menu_active_fg on menu_active_bg, TEXT),
+            pair!(self, menu_dropdown_fg on menu_dropdown_bg, TEXT),
+            pair!(self, menu_highlight_fg on menu_highlight_bg, TEXT),
+            pair!(self, menu_hover_fg on menu_hover_bg, TEXT),
+            pair!(self, status_bar_fg on status_bar_bg, TEXT),
+            pair!(self, status_warning_fg on status_warning_bg, TEXT),
+            pair!(self, status_error_fg on status_error_bg, TEXT),
+            pair!(self, prompt_fg on prompt_bg, TEXT),
+            pair!(self, prompt_selection_fg on prompt_selection_bg, TEXT),
+            pair!(self, popup_text_fg on popup_bg, TEXT),
+            pair!(self, popup_text_fg on popup_selection_bg, TEXT),
+            pair!(self, suggestion_name_fg on suggestion_bg, TEXT),
+            pair!(self, suggestion_name_fg on suggestion_selected_bg, TEXT),
+            pair!(self, suggestion_keybinding_fg on suggestion_bg, SECONDARY),
+            pair!(self, suggestion_description_fg on suggestion_bg, SECONDARY),
+            pair!(self, help_fg on help_bg, TEXT),
+            pair!(self, help_key_fg on help_bg, TEXT),
+            pair!(self, search_match_fg on search_match_bg, TEXT),
+            pair!(self, diagnostic_error_fg on editor_bg, SECONDARY),
+            pair!(self, diagnostic_warning_fg on editor_bg, SECONDARY),
+            pair!(self, diagnostic_info_fg on editor_bg, SECONDARY),
+            pair!(self, diff_added_fg on popup_bg, SECONDARY),
+            pair!(self, diff_removed_fg on popup_bg, SECONDARY),
+            pair!(self, syntax_keyword on editor_bg, TEXT),
+            pair!(self, syntax_string on editor_bg, TEXT),
+            pair!(self, syntax_comment on editor_bg, SECONDARY),
+            pair!(self, syntax_function on editor_bg, TEXT),
+            pair!(self, syntax_type on editor_bg, TEXT),
+            pair!(self, syntax_variable on editor_bg, TEXT),
+            pair!(self, syntax_constant on editor_bg, TEXT),
+            pair!(self, syntax_operator on editor_bg, TEXT),
+        ]
+    }
+
+    /// Check the contrast of every foreground/background pair text is drawn with
+    ///
+    /// Pairs involving the terminal's default color (`Reset`) can't be checked
+    /// and are skipped. Named colors are taken at their xterm values.
+    pub fn contrast_issues(&self) -> Vec<ContrastIssue> {
+        self.contrast_pairs()
+            .into_iter()
+            .filter_map(|(fg, fg_color, bg, bg_color, required)| {
+                let ratio = contrast_ratio(fg_color, bg_color)?;
+                (ratio < required).then_some(ContrastIssue {
+                    fg,
+                    bg,
+                    ratio,
+                    required,
+                })
+            })
+            .collect()
+    }
+
+    /// Number of foreground/background pairs checked by `contrast_issues`
+    pub fn contrast_pair_count(&self) -> usize {
+        self.contrast_pairs().len()
+    }
+}
+
+/// WCAG contrast ratio between two colors (1.0 to 21.0)
+///
+/// Returns None if either color depends on the terminal (`Reset`).
+pub fn contrast_ratio(a: Color, b: Color) -> Option<f64> {
+    let a = relative_luminance(color_to_rgb(a)?);
+    let b = relative_luminance(color_to_rgb(b)?);
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    Some((lighter + 0.05) / (darker + 0.05))
+}
+
+/// WCAG relative luminance of an sRGB color
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let channel = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// RGB value of a color, using xterm's defaults for the 16 named colors
+pub(crate) fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),       // Black
+        (205, 0, 0),     // Red
+        (0, 205, 0),     // Green
+        (205, 205, 0),   // Yellow
+        (0, 0, 238),     // Blue
+        (205, 0, 205),   // Magenta
+        (0, 205, 205),   // Cyan
+        (229, 229, 229), // Gray
+        (127, 127, 127), // DarkGray
+        (255, 0, 0),     // LightRed
+        (0, 255, 0),     // LightGreen
+        (255, 255, 0),   // LightYellow
+        (92, 92, 255),   // LightBlue
+        (255, 0, 255),   // LightMagenta
+        (0, 255, 255),   // LightCyan
+        (255, 255, 255), // White
+    ];
+
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        Color::Indexed(i) => i,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+
+    Some(match index {
+        0..=15 => ANSI[index as usize],
+        // 6x6x6 color cube
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let i = index - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        // Grayscale ramp
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -954,11 +1397,65 @@ mod tests {
     #[test]
     fn test_available_themes() {
         let themes = Theme::available_themes();
-        assert_eq!(themes.len(), 4);
+        assert_eq!(themes.len(), 6);
         assert!(themes.contains(&"dark"));
         assert!(themes.contains(&"light"));
         assert!(themes.contains(&"high-contrast"));
         assert!(themes.contains(&"nostalgia"));
+        assert!(themes.contains(&"deuteranopia"));
+        assert!(themes.contains(&"protanopia"));
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert_eq!(contrast_ratio(Color::Black, Color::White), Some(21.0));
+        assert_eq!(
+            contrast_ratio(Color::Rgb(8, 8, 8), Color::Indexed(232)),
+            Some(1.0)
+        );
+        assert_eq!(contrast_ratio(Color::Reset, Color::White), None);
+    }
+
+    #[test]
+    fn test_accessible_themes_pass_contrast_checks() {
+        for theme in [
+            Theme::high_contrast(),
+            Theme::from_name("high-contrast"),
+            Theme::deuteranopia(),
+            Theme::from_name("deuteranopia"),
+            Theme::protanopia(),
+            Theme::from_name("protanopia"),
+        ] {
+            let issues = theme.contrast_issues();
+            assert!(
+                issues.is_empty(),
+                "{} has contrast issues: {:?}",
+                theme.name,
+                issues
+            );
+        }
+    }
+
+    #[test]
+    fn test_color_blind_theme_files_match_builtin() {
+        for (name, builtin) in [
+            ("deuteranopia", Theme::deuteranopia()),
+            ("protanopia", Theme::protanopia()),
+        ] {
+            let from_file = Theme::load_builtin_theme(name).expect("theme file exists");
+            assert_eq!(format!("{:?}", from_file), format!("{:?}", builtin));
+        }
+    }
+
+    #[test]
+    fn test_contrast_issues_reports_failing_pairs() {
+        let mut theme = Theme::high_contrast();
+        theme.syntax_comment = Color::Rgb(40, 40, 40);
+        let issues = theme.contrast_issues();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].fg, "syntax_comment");
+        assert_eq!(issues[0].bg, "editor_bg");
+        assert_eq!(issues[0].required, MIN_SECONDARY_CONTRAST);
     }
 
     #[test]
@@ -0,0 +1,339 @@
+//! Confirmation dialog rendering
+
+use crate::view::confirm_dialog::{ConfirmDialog, DetailLine, DetailStyle};
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// Widest the dialog gets, including borders
+const MAX_DIALOG_WIDTH: u16 = 70;
+/// Narrowest the dialog gets, including borders
+const MIN_DIALOG_WIDTH: u16 = 30;
+/// Space between buttons
+const BUTTON_GAP: u16 = 2;
+/// Most detail lines shown (the rest are summarized in one line)
+const MAX_DETAIL_LINES: usize = 15;
+
+/// Position of a dialog and its parts on screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmDialogLayout {
+    /// The whole dialog, including borders
+    pub area: Rect,
+    /// Message lines after wrapping
+    pub lines: Vec<String>,
+    /// Detail lines that fit, truncated to the dialog's width
+    pub details: Vec<DetailLine>,
+    /// Area of each button, in the same order as the dialog's buttons
+    pub buttons: Vec<Rect>,
+}
+
+/// Renders confirmation dialogs
+pub struct ConfirmDialogRenderer;
+
+impl ConfirmDialogRenderer {
+    /// Compute where the dialog goes, centered on the screen
+    pub fn layout(dialog: &ConfirmDialog, screen: Rect) -> ConfirmDialogLayout {
+        let button_widths: Vec<u16> = dialog
+            .buttons
+            .iter()
+            .map(|b| Self::button_text(&b.label).chars().count() as u16)
+            .collect();
+        let buttons_width = button_widths.iter().sum::<u16>()
+            + BUTTON_GAP * button_widths.len().saturating_sub(1) as u16;
+
+        // Borders plus one column of padding on each side
+        let message_width = dialog
+            .message
+            .iter()
+            .map(|line| line.chars().count() as u16)
+            .max()
+            .unwrap_or(0);
+        let details_width = dialog
+            .details
+            .iter()
+            .map(|line| line.text.chars().count() as u16)
+            .max()
+            .unwrap_or(0);
+        let title_width = dialog.title.chars().count() as u16 + 2;
+        let width = (message_width
+            .max(details_width)
+            .max(buttons_width)
+            .max(title_width)
+            + 4)
+        .clamp(MIN_DIALOG_WIDTH, MAX_DIALOG_WIDTH)
+        .min(screen.width);
+        let inner_width = width.saturating_sub(4) as usize;
+
+        let lines: Vec<String> = dialog
+            .message
+            .iter()
+            .flat_map(|line| wrap(line, inner_width))
+            .collect();
+
+        // Details get whatever room is left (plus a blank line before them)
+        let room = (screen.height as usize).saturating_sub(lines.len() + 6);
+        let details = Self::fit_details(&dialog.details, room.min(MAX_DETAIL_LINES), inner_width);
+        let details_height = if details.is_empty() {
+            0
+        } else {
+            details.len() + 1
+        };
+
+        // Border, blank, message, [blank, details], blank, buttons, border
+        let height = ((lines.len() + details_height) as u16 + 5).min(screen.height);
+        let area = Rect {
+            x: screen.x + (screen.width - width) / 2,
+            y: screen.y + (screen.height - height) / 2,
+            width,
+            height,
+        };
+
+        let buttons_row = area.bottom().saturating_sub(2).max(area.y);
+        let mut x = area.x + width.saturating_sub(buttons_width) / 2;
+        let buttons = button_widths
+            .iter()
+            .map(|&w| {
+                let rect = Rect {
+                    x,
+                    y: buttons_row,
+                    width: w.min(area.right().saturating_sub(x)),
+                    height: 1,
+                };
+                x = x.saturating_add(w + BUTTON_GAP).min(area.right());
+                rect
+            })
+            .collect();
+
+        ConfirmDialogLayout {
+            area,
+            lines,
+            details,
+            buttons,
+        }
+    }
+
+    /// Truncate detail lines to `width` and keep at most `max_lines` of them
+    ///
+    /// If some are left out, the last line says how many.
+    fn fit_details(details: &[DetailLine], max_lines: usize, width: usize) -> Vec<DetailLine> {
+        let truncate = |line: &DetailLine| {
+            let text = if line.text.chars().count() > width {
+                let mut text: String = line.text.chars().take(width.saturating_sub(1)).collect();
+                text.push('…');
+                text
+            } else {
+                line.text.clone()
+            };
+            DetailLine::new(text, line.style)
+        };
+
+        if details.len() <= max_lines {
+            return details.iter().map(truncate).collect();
+        }
+        if max_lines == 0 {
+            return Vec::new();
+        }
+
+        let shown = max_lines - 1;
+        let mut fitted: Vec<DetailLine> = details[..shown].iter().map(truncate).collect();
+        fitted.push(DetailLine::new(
+            format!("… {} more lines", details.len() - shown),
+            DetailStyle::Muted,
+        ));
+        fitted
+    }
+
+    /// Render the dialog and return its layout (for mouse hit testing)
+    pub fn render(
+        frame: &mut Frame,
+        screen: Rect,
+        dialog: &ConfirmDialog,
+        theme: &Theme,
+    ) -> ConfirmDialogLayout {
+        let layout = Self::layout(dialog, screen);
+        let base = Style::default().fg(theme.popup_text_fg).bg(theme.popup_bg);
+
+        frame.render_widget(Clear, layout.area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(theme.popup_border_fg)
+                    .bg(theme.popup_bg),
+            )
+            .style(base)
+            .title(Span::styled(
+                format!(" {} ", dialog.title),
+                base.add_modifier(Modifier::BOLD),
+            ));
+
+        let mut text = vec![Line::default()];
+        text.extend(
+            layout
+                .lines
+                .iter()
+                .map(|line| Line::from(Span::styled(format!(" {}", line), base))),
+        );
+        if !layout.details.is_empty() {
+            text.push(Line::default());
+            text.extend(layout.details.iter().map(|line| {
+                let style = match line.style {
+                    DetailStyle::Normal => base,
+                    DetailStyle::Added => base.fg(theme.diff_added_fg),
+                    DetailStyle::Removed => base.fg(theme.diff_removed_fg),
+                    DetailStyle::Muted => base.fg(theme.line_number_fg),
+                };
+                Line::from(Span::styled(format!(" {}", line.text), style))
+            }));
+        }
+        frame.render_widget(Paragraph::new(text).block(block), layout.area);
+
+        for (index, (button, rect)) in dialog.buttons.iter().zip(&layout.buttons).enumerate() {
+            let style = if index == dialog.focused {
+                base.bg(theme.popup_selection_bg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                base
+            };
+            frame.render_widget(
+                Paragraph::new(Line::from(Self::button_spans(
+                    &button.label,
+                    button.key,
+                    style,
+                ))),
+                *rect,
+            );
+        }
+
+        layout
+    }
+
+    /// Text of a button, e.g. "[ Save ]"
+    fn button_text(label: &str) -> String {
+        format!("[ {} ]", label)
+    }
+
+    /// Button text with its shortcut key underlined
+    fn button_spans(label: &str, key: char, style: Style) -> Vec<Span<'static>> {
+        let key = key.to_ascii_lowercase();
+        let Some(pos) = label.chars().position(|c| c.to_ascii_lowercase() == key) else {
+            return vec![Span::styled(Self::button_text(label), style)];
+        };
+
+        let before: String = label.chars().take(pos).collect();
+        let shortcut: String = label.chars().skip(pos).take(1).collect();
+        let after: String = label.chars().skip(pos + 1).collect();
+        vec![
+            Span::styled(format!("[ {}", before), style),
+            Span::styled(shortcut, style.add_modifier(Modifier::UNDERLINED)),
+            Span::styled(format!("{} ]", after), style),
+        ]
+    }
+}
+
+/// Word-wrap a line to `width` columns (words longer than a line are split)
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![line.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        let needed =
+            current.chars().count() + usize::from(!current.is_empty()) + word.chars().count();
+        if needed > width && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::confirm_dialog::{ConfirmChoice, DialogButton};
+
+    #[test]
+    fn test_layout_is_centered_with_buttons_inside() {
+        let dialog = ConfirmDialog::new(
+            "Delete",
+            "Delete 'notes.txt'?",
+            vec![
+                DialogButton::new("Delete", 'd', ConfirmChoice::Yes),
+                DialogButton::new("Cancel", 'c', ConfirmChoice::Cancel),
+            ],
+        );
+        let screen = Rect::new(0, 0, 80, 24);
+        let layout = ConfirmDialogRenderer::layout(&dialog, screen);
+
+        assert_eq!(layout.area.width, MIN_DIALOG_WIDTH);
+        assert_eq!(layout.area.height, 6);
+        assert_eq!(layout.area.x, 25);
+        assert_eq!(layout.area.y, 9);
+
+        assert_eq!(layout.buttons.len(), 2);
+        assert_eq!(layout.buttons[0].width, "[ Delete ]".len() as u16);
+        assert_eq!(layout.buttons[1].x, layout.buttons[0].right() + BUTTON_GAP);
+        for button in &layout.buttons {
+            assert_eq!(button.y, layout.area.bottom() - 2);
+            assert!(button.x > layout.area.x && button.right() < layout.area.right());
+        }
+    }
+
+    #[test]
+    fn test_long_message_wraps() {
+        let message = "word ".repeat(40);
+        let dialog = ConfirmDialog::yes_no("Confirm", message.trim());
+        let layout = ConfirmDialogRenderer::layout(&dialog, Rect::new(0, 0, 100, 30));
+
+        assert_eq!(layout.area.width, MAX_DIALOG_WIDTH);
+        assert!(layout.lines.len() > 1);
+        assert!(layout
+            .lines
+            .iter()
+            .all(|line| line.chars().count() <= (MAX_DIALOG_WIDTH - 4) as usize));
+    }
+
+    #[test]
+    fn test_details_are_limited_to_the_screen() {
+        let details: Vec<DetailLine> = (0..40)
+            .map(|i| DetailLine::new(format!("+ line {}", i), DetailStyle::Added))
+            .collect();
+        let dialog = ConfirmDialog::yes_no("Save", "Save changes?").with_details(details);
+
+        let layout = ConfirmDialogRenderer::layout(&dialog, Rect::new(0, 0, 80, 40));
+        assert_eq!(layout.details.len(), MAX_DETAIL_LINES);
+        assert_eq!(layout.details.last().unwrap().text, "… 26 more lines");
+        assert_eq!(layout.area.height, (1 + MAX_DETAIL_LINES + 1 + 5) as u16);
+
+        // A short screen leaves room for fewer lines
+        let layout = ConfirmDialogRenderer::layout(&dialog, Rect::new(0, 0, 80, 12));
+        assert_eq!(layout.details.len(), 5);
+        assert!(layout.area.height <= 12);
+    }
+
+    #[test]
+    fn test_wrap_splits_long_words() {
+        assert_eq!(wrap("abcdefgh ij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::view::file_tree::{FileTreeView, NodeId};
+use crate::services::git_status::{GitFileStatus, GitStatuses};
+use crate::view::file_tree::{FileTreeView, IgnoreStatus, NodeId};
 use crate::view::theme::Theme;
 use ratatui::{
     layout::Rect,
@@ -21,6 +22,7 @@ impl FileExplorerRenderer {
         area: Rect,
         is_focused: bool,
         files_with_unsaved_changes: &HashSet<PathBuf>,
+        git_statuses: Option<&GitStatuses>,
         keybinding_resolver: &crate::input::keybindings::KeybindingResolver,
         current_context: crate::input::keybindings::KeyContext,
         theme: &Theme,
@@ -58,14 +60,17 @@ impl FileExplorerRenderer {
                     is_selected,
                     is_focused,
                     files_with_unsaved_changes,
+                    git_statuses,
                     theme,
                     content_width,
                 )
             })
             .collect();
 
-        // Build the title with keybinding
-        let title = if let Some(keybinding) = keybinding_resolver.get_keybinding_for_action(
+        // Build the title with the filter query or keybinding
+        let title = if let Some(query) = view.filter_query() {
+            format!(" File Explorer: /{} ", query)
+        } else if let Some(keybinding) = keybinding_resolver.get_keybinding_for_action(
             &crate::input::keybindings::Action::ToggleFileExplorer,
             current_context,
         ) {
@@ -161,11 +166,24 @@ impl FileExplorerRenderer {
         is_selected: bool,
         is_focused: bool,
         files_with_unsaved_changes: &HashSet<PathBuf>,
+        git_statuses: Option<&GitStatuses>,
         theme: &Theme,
         content_width: usize,
     ) -> ListItem<'static> {
         let node = view.tree().get_node(node_id).expect("Node should exist");
 
+        // Git status badge, or a dot for directories holding changes
+        let git_status = git_statuses.and_then(|statuses| statuses.status_of(&node.entry.path));
+        let git_badge = match git_status {
+            Some(status) => Some((status.badge(), Self::git_status_color(status, theme))),
+            None if node.is_dir()
+                && git_statuses.is_some_and(|statuses| statuses.has_changes(&node.entry.path)) =>
+            {
+                Some(("•", theme.diagnostic_warning_fg))
+            }
+            None => None,
+        };
+
         // Build the line with indentation and tree structure
         let mut spans = Vec::new();
 
@@ -210,13 +228,14 @@ impl FileExplorerRenderer {
         // Name styling using theme colors
         let name_style = if is_selected && is_focused {
             Style::default().fg(theme.editor_fg)
-        } else if node
-            .entry
-            .metadata
-            .as_ref()
-            .map(|m| m.is_hidden)
-            .unwrap_or(false)
+        } else if let Some(status) = git_status {
+            Style::default().fg(Self::git_status_color(status, theme))
+        } else if view
+            .ignore_patterns()
+            .get_status(&node.entry.path, node.is_dir())
+            != IgnoreStatus::Visible
         {
+            // Hidden and ignored entries, when shown, are dimmed
             Style::default().fg(theme.line_number_fg)
         } else if node.is_dir() {
             Style::default().fg(theme.syntax_keyword)
@@ -234,7 +253,11 @@ impl FileExplorerRenderer {
                 .and_then(|m| m.size)
                 .map(|size| Self::format_size(size))
         } else if node.is_expanded() {
-            let count = node.children.len();
+            let count = node
+                .children
+                .iter()
+                .filter(|&&child| view.is_node_visible(child))
+                .count();
             Some(if count == 1 {
                 "1 item".to_string()
             } else {
@@ -244,22 +267,37 @@ impl FileExplorerRenderer {
             None
         };
 
+        // Size and git badge, right-aligned
+        let mut right_spans = Vec::new();
+        let mut right_width = 0;
         if let Some(size_text) = size_str {
-            let size_display_width = size_text.chars().count();
+            right_width += size_text.chars().count();
+            right_spans.push(Span::styled(
+                size_text,
+                Style::default().fg(theme.line_number_fg),
+            ));
+        }
+        if let Some((badge, color)) = git_badge {
+            if !right_spans.is_empty() {
+                right_spans.push(Span::raw(" "));
+                right_width += 1;
+            }
+            right_width += badge.chars().count();
+            right_spans.push(Span::styled(badge, Style::default().fg(color)));
+        }
+
+        if !right_spans.is_empty() {
             // Calculate padding needed for right-alignment
             // We need at least 1 space between name and size
             let min_gap = 1;
-            let padding = if left_side_width + min_gap + size_display_width < content_width {
-                content_width - left_side_width - size_display_width
+            let padding = if left_side_width + min_gap + right_width < content_width {
+                content_width - left_side_width - right_width
             } else {
                 min_gap
             };
 
             spans.push(Span::raw(" ".repeat(padding)));
-            spans.push(Span::styled(
-                size_text,
-                Style::default().fg(theme.line_number_fg),
-            ));
+            spans.extend(right_spans);
         }
 
         // Error indicator
@@ -273,6 +311,14 @@ impl FileExplorerRenderer {
         ListItem::new(Line::from(spans)).style(Style::default().bg(theme.editor_bg))
     }
 
+    fn git_status_color(status: GitFileStatus, theme: &Theme) -> ratatui::style::Color {
+        match status {
+            GitFileStatus::Modified => theme.diagnostic_warning_fg,
+            GitFileStatus::Added | GitFileStatus::Untracked => theme.diff_added_fg,
+            GitFileStatus::Ignored => theme.line_number_fg,
+        }
+    }
+
     /// Format file size for display
     /// - Uses 1 decimal place max
     /// - All sizes shown in KB/MB/GB (no bytes) for alignment
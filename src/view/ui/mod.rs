@@ -6,27 +6,48 @@
 //! - `tabs` - Tab bar rendering for multiple buffers
 //! - `status_bar` - Status bar and prompt/minibuffer display
 //! - `suggestions` - Autocomplete and command palette UI
+//! - `preview` - Preview pane for the theme, tab, file and symbol pickers
 //! - `split_rendering` - Split pane layout and rendering
 //! - `file_explorer` - File tree explorer rendering
+//! - `plugin_panel` - Panels drawn by plugins
+//! - `performance_overlay` - Typing latency percentiles
 //! - `scrollbar` - Reusable scrollbar widget
 //! - `file_browser` - File open dialog popup
+//! - `confirm_dialog` - Modal confirmation dialog
+//! - `which_key` - Keys that can follow a pending chord
+//! - `pane_labels` - Letters for choosing a pane
+//! - `recent_buffers` - Recently used buffers while cycling with Ctrl+Tab
 
+pub mod confirm_dialog;
 pub mod file_browser;
 pub mod file_explorer;
 pub mod menu;
+pub mod pane_labels;
+pub mod performance_overlay;
+pub mod plugin_panel;
+pub mod preview;
+pub mod recent_buffers;
 pub mod scrollbar;
 pub mod split_rendering;
 pub mod status_bar;
 pub mod suggestions;
 pub mod tabs;
 pub mod view_pipeline;
+pub mod which_key;
 
 // Re-export main types for convenience
+pub use confirm_dialog::{ConfirmDialogLayout, ConfirmDialogRenderer};
 pub use file_browser::{FileBrowserLayout, FileBrowserRenderer};
 pub use file_explorer::FileExplorerRenderer;
 pub use menu::{context_keys, MenuContext, MenuRenderer, MenuState};
+pub use pane_labels::PaneLabelsRenderer;
+pub use performance_overlay::{PerformanceOverlayRenderer, PerformanceOverlayRow};
+pub use plugin_panel::PluginPanelRenderer;
+pub use preview::{PreviewContent, PreviewLine, PreviewRenderer, SuggestionPreview};
+pub use recent_buffers::RecentBuffersRenderer;
 pub use scrollbar::{render_scrollbar, ScrollbarColors, ScrollbarState};
 pub use split_rendering::SplitRenderer;
 pub use status_bar::StatusBarRenderer;
 pub use suggestions::SuggestionsRenderer;
 pub use tabs::TabsRenderer;
+pub use which_key::{WhichKeyEntry, WhichKeyLayout, WhichKeyRenderer};
@@ -0,0 +1,82 @@
+//! Letters drawn over panes while choosing one
+//!
+//! "Open to the Right/Below" asks which pane to open in when there are more
+//! than two; each pane it can open in shows its letter in a box at its
+//! center.
+
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// Width of a label box, borders included
+const LABEL_WIDTH: u16 = 5;
+/// Height of a label box, borders included
+const LABEL_HEIGHT: u16 = 3;
+
+/// Renders pane labels
+pub struct PaneLabelsRenderer;
+
+impl PaneLabelsRenderer {
+    /// Where the label of a pane goes: centered, or at its top left corner
+    /// if the pane is too small
+    pub fn label_area(pane: Rect) -> Rect {
+        let width = LABEL_WIDTH.min(pane.width);
+        let height = LABEL_HEIGHT.min(pane.height);
+        Rect {
+            x: pane.x + (pane.width - width) / 2,
+            y: pane.y + (pane.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Draw each letter over its pane
+    pub fn render(frame: &mut Frame, labels: &[(char, Rect)], theme: &Theme) {
+        let border = Style::default()
+            .fg(theme.popup_border_fg)
+            .bg(theme.popup_bg);
+        let letter = Style::default()
+            .fg(theme.help_key_fg)
+            .bg(theme.popup_bg)
+            .add_modifier(Modifier::BOLD);
+        for &(label, pane) in labels {
+            let area = Self::label_area(pane);
+            if area.width < LABEL_WIDTH || area.height < LABEL_HEIGHT {
+                continue;
+            }
+            frame.render_widget(Clear, area);
+            let paragraph = Paragraph::new(Line::styled(label.to_string(), letter))
+                .alignment(ratatui::layout::Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).border_style(border))
+                .style(border);
+            frame.render_widget(paragraph, area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_labels_are_centered_on_their_panes() {
+        let backend = TestBackend::new(40, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        let left = Rect::new(0, 0, 20, 10);
+        let right = Rect::new(20, 0, 20, 10);
+        terminal
+            .draw(|frame| PaneLabelsRenderer::render(frame, &[('a', left), ('b', right)], &theme))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer.cell((9, 4)).unwrap().symbol(), "a");
+        assert_eq!(buffer.cell((29, 4)).unwrap().symbol(), "b");
+        assert_eq!(PaneLabelsRenderer::label_area(left), Rect::new(7, 3, 5, 3));
+    }
+}
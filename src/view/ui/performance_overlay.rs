@@ -0,0 +1,111 @@
+//! Performance overlay: typing latency percentiles in the top right corner,
+//! with a note on how often highlighting was put off
+
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// Width of the stage name column
+const LABEL_WIDTH: usize = 9;
+/// Width of each percentile column
+const VALUE_WIDTH: usize = 8;
+
+/// One line of the overlay, ready to display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerformanceOverlayRow {
+    /// The stage, or "total"
+    pub label: String,
+    pub p50: String,
+    pub p95: String,
+    pub p99: String,
+}
+
+/// Renders the performance overlay
+pub struct PerformanceOverlayRenderer;
+
+impl PerformanceOverlayRenderer {
+    /// Render the percentiles of the last `keys` keys over the top right of
+    /// `area`, with `note` below them
+    pub fn render(
+        frame: &mut Frame,
+        area: Rect,
+        keys: usize,
+        rows: &[PerformanceOverlayRow],
+        note: Option<&str>,
+        theme: &Theme,
+    ) {
+        let inner_width = (LABEL_WIDTH + 3 * VALUE_WIDTH + 2) as u16;
+        let inner_height =
+            (if rows.is_empty() { 1 } else { rows.len() + 1 } + usize::from(note.is_some())) as u16;
+        let width = (inner_width + 2).min(area.width);
+        let height = (inner_height + 2).min(area.height);
+        if width < 10 || height < 3 {
+            return;
+        }
+        let overlay_area = Rect {
+            x: area.right() - width,
+            y: area.y,
+            width,
+            height,
+        };
+
+        let base = Style::default().fg(theme.popup_text_fg).bg(theme.popup_bg);
+        let header_style = base.fg(theme.help_key_fg).add_modifier(Modifier::BOLD);
+        let total_style = base.add_modifier(Modifier::BOLD);
+
+        let mut text: Vec<Line> = if rows.is_empty() {
+            vec![Line::from(Span::styled(" No keys traced yet", base))]
+        } else {
+            let header = Line::from(Span::styled(
+                format_row("stage", "p50", "p95", "p99"),
+                header_style,
+            ));
+            std::iter::once(header)
+                .chain(rows.iter().map(|row| {
+                    let style = if row.label == "total" {
+                        total_style
+                    } else {
+                        base
+                    };
+                    Line::from(Span::styled(
+                        format_row(&row.label, &row.p50, &row.p95, &row.p99),
+                        style,
+                    ))
+                }))
+                .collect()
+        };
+        if let Some(note) = note {
+            text.push(Line::from(Span::styled(format!(" {}", note), base)));
+        }
+
+        frame.render_widget(Clear, overlay_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(theme.popup_border_fg)
+                    .bg(theme.popup_bg),
+            )
+            .style(base)
+            .title(Span::styled(
+                format!(" Latency ({} keys) ", keys),
+                base.add_modifier(Modifier::BOLD),
+            ));
+        frame.render_widget(Paragraph::new(text).block(block), overlay_area);
+    }
+}
+
+fn format_row(label: &str, p50: &str, p95: &str, p99: &str) -> String {
+    format!(
+        " {:<label$}{:>value$}{:>value$}{:>value$}",
+        label,
+        p50,
+        p95,
+        p99,
+        label = LABEL_WIDTH,
+        value = VALUE_WIDTH
+    )
+}
@@ -0,0 +1,133 @@
+//! Plugin panel rendering
+//!
+//! Panels are docked areas whose content is drawn by a plugin. The editor
+//! draws the border and title; the plugin's text is placed inside it.
+
+use crate::services::plugins::api::PanelText;
+use crate::view::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders},
+    Frame,
+};
+
+pub struct PluginPanelRenderer;
+
+impl PluginPanelRenderer {
+    /// Render a panel in `area` and return its content area
+    pub fn render(
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        content: &[PanelText],
+        is_focused: bool,
+        theme: &Theme,
+    ) -> Rect {
+        // Same title and border styles as the file explorer
+        let (title_style, border_style) = if is_focused {
+            (
+                Style::default()
+                    .fg(theme.editor_bg)
+                    .bg(theme.editor_fg)
+                    .add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.cursor),
+            )
+        } else {
+            (
+                Style::default().fg(theme.line_number_fg),
+                Style::default().fg(theme.split_separator_fg),
+            )
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", title))
+            .title_style(title_style)
+            .border_style(border_style)
+            .style(Style::default().bg(theme.editor_bg).fg(theme.editor_fg));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let buffer = frame.buffer_mut();
+        for item in content {
+            if item.y >= inner.height || item.x >= inner.width {
+                continue;
+            }
+            let mut style = Style::default();
+            if let Some((r, g, b)) = item.fg {
+                style = style.fg(Color::Rgb(r, g, b));
+            }
+            if let Some((r, g, b)) = item.bg {
+                style = style.bg(Color::Rgb(r, g, b));
+            }
+            if item.bold {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            buffer.set_stringn(
+                inner.x + item.x,
+                inner.y + item.y,
+                &item.text,
+                (inner.width - item.x) as usize,
+                style,
+            );
+        }
+
+        inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_content_is_clipped_to_the_panel() {
+        let backend = TestBackend::new(12, 4);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        let content = vec![
+            PanelText {
+                x: 0,
+                y: 0,
+                text: "now playing".to_string(),
+                fg: None,
+                bg: None,
+                bold: false,
+            },
+            PanelText {
+                x: 2,
+                y: 5,
+                text: "below".to_string(),
+                fg: None,
+                bg: None,
+                bold: false,
+            },
+        ];
+        let mut inner = Rect::default();
+        terminal
+            .draw(|frame| {
+                inner = PluginPanelRenderer::render(
+                    frame,
+                    Rect::new(0, 0, 12, 4),
+                    "Music",
+                    &content,
+                    false,
+                    &theme,
+                );
+            })
+            .unwrap();
+
+        assert_eq!(inner, Rect::new(1, 1, 10, 2));
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..12)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect()
+        };
+        assert!(row(0).contains("Music"));
+        // Text past the right border is cut off
+        assert_eq!(row(1), "│now playin│");
+    }
+}
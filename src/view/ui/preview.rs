@@ -0,0 +1,317 @@
+//! Preview pane for picker prompts
+//!
+//! Shown next to the theme, tab, file and symbol pickers with the effect or
+//! content of the highlighted item.
+
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+use std::ops::Range;
+
+/// Width of the preview pane when there is room for it
+const PREVIEW_WIDTH: u16 = 60;
+/// Narrowest preview pane worth showing
+const MIN_PREVIEW_WIDTH: u16 = 20;
+/// Preferred height of the preview pane (including borders)
+const PREVIEW_HEIGHT: u16 = 12;
+
+/// What the preview pane shows
+#[derive(Debug, Clone)]
+pub enum PreviewContent {
+    /// Lines of text (buffer or file contents)
+    Text(Vec<PreviewLine>),
+    /// A code sample drawn with the highlighted theme
+    Theme(Box<Theme>),
+    /// Nothing to preview, with a short explanation (e.g., "Binary file")
+    Message(String),
+}
+
+/// A line of previewed text
+#[derive(Debug, Clone, Default)]
+pub struct PreviewLine {
+    pub text: String,
+    /// Syntax highlighting, as byte ranges of `text`
+    pub highlights: Vec<(Range<usize>, Color)>,
+    /// The line the picker item points at (e.g., a symbol's definition)
+    pub is_target: bool,
+}
+
+/// Preview of the highlighted picker item
+#[derive(Debug, Clone)]
+pub struct SuggestionPreview {
+    /// Shown in the pane's border
+    pub title: String,
+    pub content: PreviewContent,
+}
+
+/// Renders the preview pane
+pub struct PreviewRenderer;
+
+impl PreviewRenderer {
+    /// Compute the area of the preview pane next to a suggestions popup
+    ///
+    /// The pane goes to the right of the popup, aligned with its edge nearest
+    /// the prompt line. Returns None if there isn't enough room beside it.
+    pub fn area_beside(popup: Rect, prompt_area: Rect, screen: Rect) -> Option<Rect> {
+        let x = popup.right();
+        let width = screen.right().saturating_sub(x).min(PREVIEW_WIDTH);
+        if width < MIN_PREVIEW_WIDTH {
+            return None;
+        }
+
+        let height = popup.height.max(PREVIEW_HEIGHT);
+        let (y, height) = if popup.y >= prompt_area.bottom() {
+            // Popup opens below the prompt: share its top edge
+            (popup.y, height.min(screen.bottom().saturating_sub(popup.y)))
+        } else {
+            // Popup opens above the prompt: share its bottom edge
+            let height = height.min(popup.bottom().saturating_sub(screen.y));
+            (popup.bottom() - height, height)
+        };
+
+        Some(Rect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Split a full-width popup into (popup, preview) columns
+    ///
+    /// Returns the popup unchanged and no preview area if it is too narrow to share.
+    pub fn split(popup: Rect) -> (Rect, Option<Rect>) {
+        let width = (popup.width * 2 / 5).min(PREVIEW_WIDTH);
+        if width < MIN_PREVIEW_WIDTH || popup.width - width < MIN_PREVIEW_WIDTH * 2 {
+            return (popup, None);
+        }
+
+        let list = Rect {
+            width: popup.width - width,
+            ..popup
+        };
+        let preview = Rect {
+            x: list.right(),
+            width,
+            ..popup
+        };
+        (list, Some(preview))
+    }
+
+    /// Render the preview pane
+    pub fn render(frame: &mut Frame, area: Rect, preview: &SuggestionPreview, theme: &Theme) {
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.popup_border_fg))
+            .style(Style::default().bg(theme.suggestion_bg))
+            .title(format!(" {} ", preview.title));
+        let inner_width = block.inner(area).width as usize;
+
+        let lines = match &preview.content {
+            PreviewContent::Text(lines) => lines
+                .iter()
+                .map(|line| Self::text_line(line, inner_width, theme))
+                .collect(),
+            PreviewContent::Theme(sample) => Self::theme_sample(sample, inner_width),
+            PreviewContent::Message(message) => vec![Line::from(Span::styled(
+                message.clone(),
+                Style::default()
+                    .fg(theme.line_number_fg)
+                    .bg(theme.suggestion_bg)
+                    .add_modifier(Modifier::ITALIC),
+            ))],
+        };
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// A line of text cut to `width`, with its highlighting
+    fn text_line(line: &PreviewLine, width: usize, theme: &Theme) -> Line<'static> {
+        let bg = if line.is_target {
+            theme.current_line_bg
+        } else {
+            theme.suggestion_bg
+        };
+
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        let mut current = String::new();
+        let mut current_fg = theme.editor_fg;
+        let mut used = 0;
+        for (offset, ch) in line.text.char_indices() {
+            if used >= width {
+                break;
+            }
+            let fg = line
+                .highlights
+                .iter()
+                .find(|(range, _)| range.contains(&offset))
+                .map_or(theme.editor_fg, |(_, color)| *color);
+            if fg != current_fg && !current.is_empty() {
+                spans.push(Span::styled(
+                    std::mem::take(&mut current),
+                    Style::default().fg(current_fg).bg(bg),
+                ));
+            }
+            current_fg = fg;
+            if ch == '\t' {
+                let spaces = 4.min(width - used);
+                current.push_str(&" ".repeat(spaces));
+                used += spaces;
+            } else {
+                current.push(ch);
+                used += 1;
+            }
+        }
+        if !current.is_empty() {
+            spans.push(Span::styled(
+                current,
+                Style::default().fg(current_fg).bg(bg),
+            ));
+        }
+        if line.is_target {
+            spans.push(Span::styled(
+                " ".repeat(width.saturating_sub(used)),
+                Style::default().bg(bg),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    /// A short code sample drawn with the colors of `sample`
+    fn theme_sample(sample: &Theme, width: usize) -> Vec<Line<'static>> {
+        let code: [&[(&str, Color)]; 6] = [
+            &[("// Greet someone by name", sample.syntax_comment)],
+            &[
+                ("fn ", sample.syntax_keyword),
+                ("greet", sample.syntax_function),
+                ("(name: ", sample.editor_fg),
+                ("&str", sample.syntax_type),
+                (") {", sample.editor_fg),
+            ],
+            &[
+                ("    let ", sample.syntax_keyword),
+                ("count", sample.syntax_variable),
+                (" = ", sample.syntax_operator),
+                ("42", sample.syntax_constant),
+                (";", sample.editor_fg),
+            ],
+            &[
+                ("    println!", sample.syntax_function),
+                ("(", sample.editor_fg),
+                ("\"Hello, {name}!\"", sample.syntax_string),
+                (");", sample.editor_fg),
+            ],
+            &[("}", sample.editor_fg)],
+            &[],
+        ];
+
+        let mut lines: Vec<Line<'static>> = code
+            .iter()
+            .enumerate()
+            .map(|(idx, tokens)| {
+                // Highlight the line the cursor would be on
+                let bg = if idx == 2 {
+                    sample.current_line_bg
+                } else {
+                    sample.editor_bg
+                };
+                let mut spans = vec![Span::styled(
+                    format!("{:>3} ", idx + 1),
+                    Style::default().fg(sample.line_number_fg).bg(bg),
+                )];
+                let mut used = 4;
+                for (text, fg) in tokens.iter() {
+                    spans.push(Span::styled(
+                        text.to_string(),
+                        Style::default().fg(*fg).bg(bg),
+                    ));
+                    used += text.chars().count();
+                }
+                spans.push(Span::styled(
+                    " ".repeat(width.saturating_sub(used)),
+                    Style::default().bg(bg),
+                ));
+                Line::from(spans)
+            })
+            .collect();
+
+        // Status bar, as it would appear below the editor
+        let status = " main.rs | Ln 3, Col 9";
+        lines.push(Line::from(Span::styled(
+            format!("{:<width$}", status, width = width),
+            Style::default()
+                .fg(sample.status_bar_fg)
+                .bg(sample.status_bar_bg),
+        )));
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_area_beside_popup() {
+        let screen = Rect::new(0, 0, 120, 40);
+        let prompt_area = Rect::new(0, 39, 120, 1);
+
+        // Popup above the prompt: the preview shares its bottom edge
+        let popup = Rect::new(0, 34, 30, 5);
+        let area = PreviewRenderer::area_beside(popup, prompt_area, screen).unwrap();
+        assert_eq!(area.x, 30);
+        assert_eq!(area.width, PREVIEW_WIDTH);
+        assert_eq!(area.bottom(), popup.bottom());
+        assert_eq!(area.height, PREVIEW_HEIGHT);
+
+        // Popup below the prompt: the preview shares its top edge
+        let prompt_area = Rect::new(0, 2, 120, 1);
+        let popup = Rect::new(0, 3, 30, 5);
+        let area = PreviewRenderer::area_beside(popup, prompt_area, screen).unwrap();
+        assert_eq!(area.y, 3);
+
+        // No room beside the popup
+        let popup = Rect::new(0, 3, 110, 5);
+        assert!(PreviewRenderer::area_beside(popup, prompt_area, screen).is_none());
+    }
+
+    #[test]
+    fn test_text_line_highlights() {
+        let theme = Theme::dark();
+        let line = PreviewLine {
+            text: "let x\t= 1;".to_string(),
+            highlights: vec![(0..3, theme.syntax_keyword), (8..9, theme.syntax_constant)],
+            is_target: false,
+        };
+        let rendered = PreviewRenderer::text_line(&line, 80, &theme);
+        let texts: Vec<&str> = rendered.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["let", " x    = ", "1", ";"]);
+        assert_eq!(rendered.spans[0].style.fg, Some(theme.syntax_keyword));
+        assert_eq!(rendered.spans[2].style.fg, Some(theme.syntax_constant));
+
+        // Cut to the pane's width
+        let rendered = PreviewRenderer::text_line(&line, 4, &theme);
+        let text: String = rendered.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "let ");
+    }
+
+    #[test]
+    fn test_split_full_width_popup() {
+        let (list, preview) = PreviewRenderer::split(Rect::new(0, 10, 120, 20));
+        let preview = preview.unwrap();
+        assert_eq!(list.width + preview.width, 120);
+        assert_eq!(preview.x, list.right());
+        assert_eq!(preview.height, 20);
+
+        // Too narrow to share
+        let (list, preview) = PreviewRenderer::split(Rect::new(0, 10, 50, 20));
+        assert_eq!(list.width, 50);
+        assert!(preview.is_none());
+    }
+}
@@ -0,0 +1,106 @@
+//! Recently used buffers shown while cycling with Ctrl+Tab
+//!
+//! A box in the middle of the editor lists the buffers from the most to the
+//! least recently used, with the one Ctrl+Tab has got to highlighted.
+
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+const TITLE: &str = " Recent Buffers ";
+
+/// Renders the recent buffers popup
+pub struct RecentBuffersRenderer;
+
+impl RecentBuffersRenderer {
+    /// Where the popup goes for `names` in `area`: centered, as wide as the
+    /// longest name and as tall as the list, within the area
+    pub fn popup_area(area: Rect, names: &[String]) -> Rect {
+        let widest = names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(TITLE.len());
+        // Borders plus a space either side of the names
+        let width = (widest as u16).saturating_add(4).min(area.width);
+        let height = (names.len() as u16).saturating_add(2).min(area.height);
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Draw `names` with the `selected` one highlighted, scrolled so it shows
+    pub fn render(frame: &mut Frame, area: Rect, names: &[String], selected: usize, theme: &Theme) {
+        let area = Self::popup_area(area, names);
+        if area.width < 3 || area.height < 3 {
+            return;
+        }
+        let border = Style::default()
+            .fg(theme.popup_border_fg)
+            .bg(theme.popup_bg);
+        let base = Style::default().fg(theme.popup_text_fg).bg(theme.popup_bg);
+        let highlight = base
+            .bg(theme.popup_selection_bg)
+            .add_modifier(Modifier::BOLD);
+
+        let rows = usize::from(area.height - 2);
+        let skip = (selected + 1).saturating_sub(rows);
+        let inner_width = usize::from(area.width - 2);
+        let lines: Vec<Line> = names
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(rows)
+            .map(|(index, name)| {
+                let style = if index == selected { highlight } else { base };
+                Line::styled(format!(" {:<width$}", name, width = inner_width - 1), style)
+            })
+            .collect();
+
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(TITLE)
+            .border_style(border)
+            .style(base);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_selected_buffer_is_scrolled_into_view() {
+        let backend = TestBackend::new(40, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        let names: Vec<String> = (1..=6).map(|n| format!("file{}.txt", n)).collect();
+        terminal
+            .draw(|frame| {
+                RecentBuffersRenderer::render(frame, Rect::new(0, 0, 40, 5), &names, 4, &theme)
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..40)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol())
+                .collect()
+        };
+        assert!(row(0).contains("Recent Buffers"));
+        assert!(row(1).contains("file3.txt"));
+        assert!(row(3).contains("file5.txt"));
+        assert!(!row(1).contains("file1.txt"));
+    }
+}
@@ -8,6 +8,8 @@ use crate::model::cursor::SelectionMode;
 use crate::model::event::{BufferId, EventLog, SplitDirection};
 use crate::primitives::ansi::AnsiParser;
 use crate::primitives::ansi_background::AnsiBackground;
+use crate::primitives::highlight_engine::HIGHLIGHT_FRAME_BUDGET;
+use crate::primitives::redaction::{SecretRedactor, REDACTION_CHAR};
 use crate::services::plugins::api::ViewTransformPayload;
 use crate::state::{EditorState, ViewMode};
 use crate::view::split::SplitManager;
@@ -72,6 +74,8 @@ struct DecorationContext {
     diagnostic_lines: HashSet<usize>,
     /// Line indicators indexed by line number (highest priority indicator per line)
     line_indicators: BTreeMap<usize, crate::view::margin::LineIndicator>,
+    /// Secrets drawn masked ("Redact Secrets")
+    redacted_ranges: Vec<Range<usize>>,
 }
 
 struct LineRenderOutput {
@@ -391,6 +395,8 @@ impl SplitRenderer {
     /// * `line_wrap` - Whether line wrapping is enabled
     /// * `estimated_line_length` - Estimated average line length for large file line estimation
     /// * `hide_cursor` - Whether to hide the hardware cursor (e.g., when menu is open)
+    /// * `redactor` - Masks secrets when "Redact Secrets" is on
+    /// * `tab_group` - Buffers of the active buffer group (other tabs are hidden)
     ///
     /// # Returns
     /// * Vec of (split_id, buffer_id, content_rect, scrollbar_rect, thumb_start, thumb_end) for mouse handling
@@ -414,6 +420,8 @@ impl SplitRenderer {
         hide_cursor: bool,
         hovered_tab: Option<(BufferId, crate::model::event::SplitId, bool)>, // (buffer_id, split_id, is_close_button)
         hovered_close_split: Option<crate::model::event::SplitId>,
+        redactor: Option<&SecretRedactor>,
+        tab_group: Option<&[BufferId]>,
     ) -> (
         Vec<(
             crate::model::event::SplitId,
@@ -444,7 +452,7 @@ impl SplitRenderer {
 
             let layout = Self::split_layout(split_area);
             let (split_buffers, tab_scroll_offset) =
-                Self::split_buffers_for_tabs(split_view_states, split_id, buffer_id);
+                Self::split_buffers_for_tabs(split_view_states, split_id, buffer_id, tab_group);
 
             // Determine hover state for this split's tabs
             let tab_hover_for_split = hovered_tab.and_then(|(hover_buf, hover_split, is_close)| {
@@ -518,6 +526,7 @@ impl SplitRenderer {
                     estimated_line_length,
                     buffer_id,
                     hide_cursor,
+                    redactor,
                 );
 
                 // For small files, count actual lines for accurate scrollbar
@@ -625,13 +634,19 @@ impl SplitRenderer {
         >,
         split_id: crate::model::event::SplitId,
         buffer_id: BufferId,
+        tab_group: Option<&[BufferId]>,
     ) -> (Vec<BufferId>, usize) {
         if let Some(view_states) = split_view_states {
             if let Some(view_state) = view_states.get(&split_id) {
-                return (
-                    view_state.open_buffers.clone(),
-                    view_state.tab_scroll_offset,
-                );
+                let tabs = view_state
+                    .open_buffers
+                    .iter()
+                    .copied()
+                    .filter(|id| {
+                        *id == buffer_id || tab_group.is_none_or(|group| group.contains(id))
+                    })
+                    .collect();
+                return (tabs, view_state.tab_scroll_offset);
             }
         }
         (vec![buffer_id], 0)
@@ -690,9 +705,14 @@ impl SplitRenderer {
             || state.viewport.height != content_rect.height;
 
         if size_changed {
-            state
-                .viewport
-                .resize(content_rect.width, content_rect.height);
+            // Reflow for the new size, keeping the cursor where it was on screen
+            let primary = *state.cursors.primary();
+            state.viewport.resize_keeping_cursor(
+                &mut state.buffer,
+                &primary,
+                content_rect.width,
+                content_rect.height,
+            );
         }
 
         // Sync viewport with cursor if size changed or if marked for sync (cursor moved)
@@ -873,6 +893,8 @@ impl SplitRenderer {
         content_width: usize,
         gutter_width: usize,
     ) -> ViewData {
+        let started = std::time::Instant::now();
+
         // Check if buffer is binary before building tokens
         let is_binary = state.buffer.is_binary();
 
@@ -903,6 +925,7 @@ impl SplitRenderer {
         // Inject virtual lines (LineAbove/LineBelow) from VirtualTextManager
         let lines = Self::inject_virtual_lines(source_lines, state);
 
+        crate::services::latency::record_stream_build(started.elapsed());
         ViewData { lines }
     }
 
@@ -1610,6 +1633,7 @@ impl SplitRenderer {
         viewport_end: usize,
         primary_cursor_position: usize,
         theme: &crate::view::theme::Theme,
+        redactor: Option<&SecretRedactor>,
     ) -> DecorationContext {
         // Extend highlighting range by ~1 viewport size before/after for better context.
         // This helps tree-sitter parse multi-line constructs that span viewport boundaries.
@@ -1619,12 +1643,25 @@ impl SplitRenderer {
             .saturating_add(viewport_size)
             .min(state.buffer.len());
 
-        let highlight_spans = state.highlighter.highlight_viewport(
-            &state.buffer,
-            highlight_start,
-            highlight_end,
-            theme,
-        );
+        // Highlighting a region that isn't cached can take longer than a frame
+        // in huge files; draw it plain now and highlight it once idle
+        let highlight_spans = if state.highlighter.has_highlighting() {
+            let spans = state.highlighter.highlight_viewport_within(
+                &state.buffer,
+                highlight_start,
+                highlight_end,
+                theme,
+                HIGHLIGHT_FRAME_BUDGET,
+            );
+            state.highlight_stats.frames += 1;
+            spans.unwrap_or_else(|| {
+                state.highlight_stats.fallbacks += 1;
+                state.deferred_highlight = Some(highlight_start..highlight_end);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
 
         // Update semantic highlighter color from theme
         state.semantic_highlighter.highlight_color = theme.semantic_highlight_bg;
@@ -1670,6 +1707,23 @@ impl SplitRenderer {
             |byte_offset| state.buffer.get_line_number(byte_offset),
         );
 
+        let redacted_ranges = match redactor {
+            Some(redactor) => {
+                let end = viewport_end.min(state.buffer.len());
+                match state
+                    .buffer
+                    .get_text_range_mut(viewport_start, end.saturating_sub(viewport_start))
+                {
+                    Ok(text) => redactor.secret_ranges(&text, viewport_start),
+                    Err(e) => {
+                        tracing::warn!("Failed to read the viewport to redact secrets: {}", e);
+                        Vec::new()
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
+
         DecorationContext {
             highlight_spans,
             semantic_spans,
@@ -1677,6 +1731,7 @@ impl SplitRenderer {
             virtual_text_lookup,
             diagnostic_lines,
             line_indicators,
+            redacted_ranges,
         }
     }
 
@@ -1729,6 +1784,7 @@ impl SplitRenderer {
         let virtual_text_lookup = &decorations.virtual_text_lookup;
         let diagnostic_lines = &decorations.diagnostic_lines;
         let line_indicators = &decorations.line_indicators;
+        let redacted_ranges = &decorations.redacted_ranges;
 
         let mut lines = Vec::new();
         let mut lines_rendered = 0usize;
@@ -1972,6 +2028,11 @@ impl SplitRenderer {
                         ""
                     } else if ch == '\n' {
                         ""
+                    } else if byte_pos
+                        .is_some_and(|bp| redacted_ranges.iter().any(|r| r.contains(&bp)))
+                    {
+                        tab_indicator = REDACTION_CHAR.to_string();
+                        &tab_indicator
                     } else if is_tab_start {
                         // Visual indicator for tab: show → at the first position
                         tab_indicator = "→".to_string();
@@ -2328,6 +2389,7 @@ impl SplitRenderer {
         estimated_line_length: usize,
         _buffer_id: BufferId,
         hide_cursor: bool,
+        redactor: Option<&SecretRedactor>,
     ) {
         let _span = tracing::trace_span!("render_buffer_in_split").entered();
 
@@ -2428,6 +2490,7 @@ impl SplitRenderer {
             viewport_end,
             selection.primary_cursor_position,
             theme,
+            redactor,
         );
 
         // Apply top_view_line_offset to skip virtual lines when scrolling through them
@@ -2502,6 +2565,30 @@ impl SplitRenderer {
             }
         }
 
+        // Rulers only show in blank cells, so they never hide text
+        let ruler_height = render_output
+            .content_lines_rendered
+            .min(render_area.height as usize) as u16;
+        for &col in &state.rulers {
+            let Some(col) = (col as usize).checked_sub(state.viewport.left_column) else {
+                continue;
+            };
+            let ruler_x = render_area.x as usize + gutter_width + col;
+            if ruler_x >= (render_area.x + render_area.width) as usize {
+                continue;
+            }
+            for row in 0..ruler_height {
+                if let Some(cell) = frame
+                    .buffer_mut()
+                    .cell_mut((ruler_x as u16, render_area.y + row))
+                {
+                    if cell.symbol() == " " {
+                        cell.set_symbol("│").set_fg(theme.line_number_fg);
+                    }
+                }
+            }
+        }
+
         let buffer_ends_with_newline = if state.buffer.len() > 0 {
             let last_char = state.get_text_range(state.buffer.len() - 1, state.buffer.len());
             last_char == "\n"
@@ -2686,6 +2773,7 @@ mod tests {
             viewport_end,
             selection.primary_cursor_position,
             &theme,
+            None,
         );
 
         let output = SplitRenderer::render_view_lines(LineRenderInput {
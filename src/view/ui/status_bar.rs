@@ -2,12 +2,17 @@
 
 use crate::state::EditorState;
 use crate::view::prompt::Prompt;
+use crate::view::status_message::{StatusLevel, StatusMessage};
 use ratatui::layout::Rect;
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
+/// Largest selection (in bytes) whose words and characters are counted in
+/// the status bar
+const MAX_COUNTED_SELECTION: usize = 256 * 1024;
+
 /// Renders the status bar and prompt/minibuffer
 pub struct StatusBarRenderer;
 
@@ -18,34 +23,43 @@ impl StatusBarRenderer {
     /// * `frame` - The ratatui frame to render to
     /// * `area` - The rectangular area to render in
     /// * `state` - The active buffer's editor state
-    /// * `status_message` - Optional status message to display
+    /// * `status_messages` - Active status messages, newest first
+    /// * `plugin_status_message` - Optional plugin-provided status message
     /// * `lsp_status` - LSP status indicator
     /// * `theme` - The active theme for colors
     /// * `display_name` - The display name for the file (project-relative path)
     /// * `chord_state` - Current chord sequence state (for multi-key bindings)
+    /// * `mode_indicator` - Modal editing state (e.g. "NORMAL 2d"), shown in
+    ///   place of the selection mode indicator
+    /// * `custom_status` - Expanded `editor.status_format`, replacing the file
+    ///   name and cursor position
     pub fn render_status_bar(
         frame: &mut Frame,
         area: Rect,
         state: &mut EditorState,
-        status_message: &Option<String>,
+        status_messages: &[StatusMessage],
         plugin_status_message: &Option<String>,
         lsp_status: &str,
         theme: &crate::view::theme::Theme,
         display_name: &str,
         keybindings: &crate::input::keybindings::KeybindingResolver,
         chord_state: &[(crossterm::event::KeyCode, crossterm::event::KeyModifiers)],
+        mode_indicator: Option<&str>,
+        custom_status: Option<&str>,
     ) {
         Self::render_status(
             frame,
             area,
             state,
-            status_message,
+            status_messages,
             plugin_status_message,
             lsp_status,
             theme,
             display_name,
             keybindings,
             chord_state,
+            mode_indicator,
+            custom_status,
         );
     }
 
@@ -61,34 +75,82 @@ impl StatusBarRenderer {
         // Create spans for the prompt
         let mut spans = vec![Span::styled(prompt.message.clone(), base_style)];
 
-        // If there's a selection, split the input into parts
-        if let Some((sel_start, sel_end)) = prompt.selection_range() {
-            let input = &prompt.input;
-
-            // Text before selection
-            if sel_start > 0 {
-                spans.push(Span::styled(input[..sel_start].to_string(), base_style));
-            }
+        // Use theme colors for selection to ensure consistency across themes
+        let selection_style = Style::default()
+            .fg(theme.prompt_selection_fg)
+            .bg(theme.prompt_selection_bg);
+        let error_style = Style::default()
+            .fg(theme.diagnostic_error_fg)
+            .bg(theme.diagnostic_error_bg);
+
+        let input = &prompt.input;
+        let selection = prompt.selection_range();
+
+        // Byte range of the character an inline error points at (if it's inside the input)
+        let error_range = prompt
+            .error
+            .as_ref()
+            .and_then(|e| e.position)
+            .filter(|&pos| pos < input.len() && input.is_char_boundary(pos))
+            .map(|pos| {
+                let ch_len = input[pos..].chars().next().map_or(1, |c| c.len_utf8());
+                (pos, pos + ch_len)
+            });
+
+        // Split the input at selection and error boundaries, styling each segment.
+        // Selection takes precedence so the selected text (and cursor) stays visible.
+        let mut boundaries = vec![0, input.len()];
+        if let Some((start, end)) = selection {
+            boundaries.extend([start, end]);
+        }
+        if let Some((start, end)) = error_range {
+            boundaries.extend([start, end]);
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            let in_range = |range: Option<(usize, usize)>| {
+                range.is_some_and(|(range_start, range_end)| {
+                    start >= range_start && end <= range_end
+                })
+            };
+            let style = if in_range(selection) {
+                selection_style
+            } else if in_range(error_range) {
+                error_style
+            } else {
+                base_style
+            };
+            spans.push(Span::styled(input[start..end].to_string(), style));
+        }
 
-            // Selected text (blue background for visibility, cursor remains visible)
-            if sel_start < sel_end {
-                // Use theme colors for selection to ensure consistency across themes
-                let selection_style = Style::default()
-                    .fg(theme.prompt_selection_fg)
-                    .bg(theme.prompt_selection_bg);
+        // Right-align the match counter of search prompts ("3 of 47"), if it fits
+        if let Some(count) = &prompt.match_count {
+            let counter = match count.current {
+                Some(current) => format!(
+                    " {} of {}{} ",
+                    current,
+                    count.total,
+                    if count.truncated { "+" } else { "" }
+                ),
+                None => " No matches ".to_string(),
+            };
+            let used_width: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+            let available_width = area.width as usize;
+            if used_width + counter.len() < available_width {
                 spans.push(Span::styled(
-                    input[sel_start..sel_end].to_string(),
-                    selection_style,
+                    " ".repeat(available_width - used_width - counter.len()),
+                    base_style,
                 ));
+                let counter_style = if count.current.is_some() {
+                    base_style
+                } else {
+                    error_style
+                };
+                spans.push(Span::styled(counter, counter_style));
             }
-
-            // Text after selection
-            if sel_end < input.len() {
-                spans.push(Span::styled(input[sel_end..].to_string(), base_style));
-            }
-        } else {
-            // No selection, render entire input normally
-            spans.push(Span::styled(prompt.input.clone(), base_style));
         }
 
         let line = Line::from(spans);
@@ -104,6 +166,37 @@ impl StatusBarRenderer {
         }
     }
 
+    /// Render the inline error line for a prompt (e.g., an invalid search regex)
+    ///
+    /// Shows the column of the offending character (matching the highlight in the
+    /// prompt input) followed by the error message.
+    pub fn render_prompt_error(
+        frame: &mut Frame,
+        area: Rect,
+        prompt: &Prompt,
+        theme: &crate::view::theme::Theme,
+    ) {
+        let Some(error) = &prompt.error else {
+            return;
+        };
+
+        let error_style = Style::default()
+            .fg(theme.diagnostic_error_fg)
+            .bg(theme.prompt_bg);
+
+        let text = match error.position {
+            Some(pos) => {
+                let column = prompt.input.get(..pos).map_or(pos, |s| s.chars().count()) + 1;
+                format!(" Error at column {}: {}", column, error.message)
+            }
+            None => format!(" Error: {}", error.message),
+        };
+
+        let error_line = Paragraph::new(Line::from(Span::styled(text, error_style)))
+            .style(Style::default().bg(theme.prompt_bg));
+        frame.render_widget(error_line, area);
+    }
+
     /// Render the file open prompt with colorized path
     /// Shows: "Open: /path/to/current/dir/filename" where the directory part is dimmed
     pub fn render_file_open_prompt(
@@ -154,13 +247,15 @@ impl StatusBarRenderer {
         frame: &mut Frame,
         area: Rect,
         state: &mut EditorState,
-        status_message: &Option<String>,
+        status_messages: &[StatusMessage],
         plugin_status_message: &Option<String>,
         lsp_status: &str,
         theme: &crate::view::theme::Theme,
         display_name: &str,
         keybindings: &crate::input::keybindings::KeybindingResolver,
         chord_state: &[(crossterm::event::KeyCode, crossterm::event::KeyModifiers)],
+        mode_indicator: Option<&str>,
+        custom_status: Option<&str>,
     ) {
         // Use the pre-computed display name from buffer metadata
         let filename = display_name;
@@ -193,6 +288,14 @@ impl StatusBarRenderer {
 
         let cursor = *state.primary_cursor();
 
+        // Selection mode indicator (movement extends the selection), or the
+        // modal editing state when vim mode is on
+        let selection_mode_indicator = match mode_indicator {
+            Some(mode) => format!(" | {}", mode),
+            None if cursor.deselect_on_move => String::new(),
+            None => " | Select".to_string(),
+        };
+
         // Get line number and column efficiently using cached values
         let (line, col) = {
             // Find the start of the line containing the cursor
@@ -237,6 +340,9 @@ impl StatusBarRenderer {
             if info_count > 0 {
                 parts.push(format!("I:{}", info_count));
             }
+            if state.hidden_diagnostics > 0 {
+                parts.push(format!("(+{} not shown)", state.hidden_diagnostics));
+            }
             format!(" | {}", parts.join(" "))
         } else {
             String::new()
@@ -249,6 +355,13 @@ impl StatusBarRenderer {
             String::new()
         };
 
+        // Words and characters selected, counted live for selections that
+        // aren't too large to read on every frame
+        let selection_counts = match state.selection_counts(MAX_COUNTED_SELECTION) {
+            Some(counts) => format!(" | {} words, {} chars selected", counts.words, counts.chars),
+            None => String::new(),
+        };
+
         // Build the status string with optional LSP status and status message
         let lsp_indicator = if !lsp_status.is_empty() {
             format!(" | {}", lsp_status)
@@ -256,29 +369,43 @@ impl StatusBarRenderer {
             String::new()
         };
 
-        let mut message_parts: Vec<&str> = Vec::new();
-        if let Some(msg) = status_message {
-            if !msg.is_empty() {
-                message_parts.push(msg);
+        let status_style = Style::default()
+            .fg(theme.status_bar_fg)
+            .bg(theme.status_bar_bg);
+
+        let position_status = match custom_status {
+            Some(custom) => custom.to_string(),
+            None => format!("{filename}{modified} | Ln {line}, Col {col}"),
+        };
+        let base_status = format!(
+            "{position_status}{diagnostics_summary}{cursor_count_indicator}{selection_counts}{selection_mode_indicator}{lsp_indicator}"
+        );
+
+        // Left side: position info, then the status messages (newest first), each
+        // colored by its level
+        let mut left_segments = vec![(format!("{base_status}{chord_display}"), status_style)];
+        for message in status_messages {
+            if message.text.is_empty() {
+                continue;
             }
+            left_segments.push((" | ".to_string(), status_style));
+            let message_style = match message.level {
+                StatusLevel::Info => status_style,
+                StatusLevel::Warning => Style::default()
+                    .fg(theme.status_warning_fg)
+                    .bg(theme.status_warning_bg),
+                StatusLevel::Error => Style::default()
+                    .fg(theme.status_error_fg)
+                    .bg(theme.status_error_bg),
+            };
+            left_segments.push((message.text.clone(), message_style));
         }
         if let Some(msg) = plugin_status_message {
             if !msg.is_empty() {
-                message_parts.push(msg);
+                left_segments.push((format!(" | {}", msg), status_style));
             }
         }
 
-        let message_suffix = if message_parts.is_empty() {
-            String::new()
-        } else {
-            format!(" | {}", message_parts.join(" | "))
-        };
-
-        let base_status = format!(
-            "{filename}{modified} | Ln {line}, Col {col}{diagnostics_summary}{cursor_count_indicator}{lsp_indicator}"
-        );
-        let left_status = format!("{base_status}{chord_display}{message_suffix}");
-
         // Build Command Palette indicator for right side
         // Always show Command Palette indicator on the right side
         let cmd_palette_shortcut = keybindings
@@ -303,46 +430,20 @@ impl StatusBarRenderer {
                 1 // Minimal space
             };
 
-            let mut spans = vec![];
-
             // Truncate left status if it's too long
-            let displayed_left = if left_status.len() > left_max_width {
-                let truncate_at = left_max_width.saturating_sub(3); // -3 for "..."
-                if truncate_at > 0 {
-                    format!("{}...", &left_status[..truncate_at])
-                } else {
-                    String::from("...")
-                }
-            } else {
-                left_status.clone()
-            };
-
-            spans.push(Span::styled(
-                displayed_left.clone(),
-                Style::default()
-                    .fg(theme.status_bar_fg)
-                    .bg(theme.status_bar_bg),
-            ));
-
-            let displayed_left_len = displayed_left.len();
+            let (mut spans, displayed_left_len) =
+                Self::truncate_segments(&left_segments, left_max_width);
 
             // Add spacing to push command palette indicator to the right
-            if displayed_left_len + cmd_palette_width < available_width {
-                let padding_len = available_width - displayed_left_len - cmd_palette_width;
-                spans.push(Span::styled(
-                    " ".repeat(padding_len),
-                    Style::default()
-                        .fg(theme.status_bar_fg)
-                        .bg(theme.status_bar_bg),
-                ));
+            let padding_len = if displayed_left_len + cmd_palette_width < available_width {
+                available_width - displayed_left_len - cmd_palette_width
             } else if displayed_left_len < available_width {
-                // Add minimal space
-                spans.push(Span::styled(
-                    " ",
-                    Style::default()
-                        .fg(theme.status_bar_fg)
-                        .bg(theme.status_bar_bg),
-                ));
+                1 // Add minimal space
+            } else {
+                0
+            };
+            if padding_len > 0 {
+                spans.push(Span::styled(" ".repeat(padding_len), status_style));
             }
 
             // Add command palette indicator with distinct styling and padding
@@ -353,56 +454,26 @@ impl StatusBarRenderer {
                     .bg(theme.help_indicator_bg),
             ));
 
-            // Calculate total width covered by spans
-            let total_width = displayed_left_len
-                + if displayed_left_len + cmd_palette_width < available_width {
-                    available_width - displayed_left_len - cmd_palette_width
-                } else if displayed_left_len < available_width {
-                    1
-                } else {
-                    0
-                }
-                + cmd_palette_width;
-
             // Add final padding to fill exactly to area width if needed
+            let total_width = displayed_left_len + padding_len + cmd_palette_width;
             if total_width < available_width {
                 spans.push(Span::styled(
                     " ".repeat(available_width - total_width),
-                    Style::default()
-                        .fg(theme.status_bar_fg)
-                        .bg(theme.status_bar_bg),
+                    status_style,
                 ));
             }
 
             spans
         } else {
             // Terminal too narrow or no command palette indicator - fill entire width with left status
-            let mut spans = vec![];
-            let displayed_left = if left_status.len() > available_width {
-                let truncate_at = available_width.saturating_sub(3);
-                if truncate_at > 0 {
-                    format!("{}...", &left_status[..truncate_at])
-                } else {
-                    left_status.chars().take(available_width).collect()
-                }
-            } else {
-                left_status.clone()
-            };
-
-            spans.push(Span::styled(
-                displayed_left.clone(),
-                Style::default()
-                    .fg(theme.status_bar_fg)
-                    .bg(theme.status_bar_bg),
-            ));
+            let (mut spans, displayed_left_len) =
+                Self::truncate_segments(&left_segments, available_width);
 
             // Fill remaining width
-            if displayed_left.len() < available_width {
+            if displayed_left_len < available_width {
                 spans.push(Span::styled(
-                    " ".repeat(available_width - displayed_left.len()),
-                    Style::default()
-                        .fg(theme.status_bar_fg)
-                        .bg(theme.status_bar_bg),
+                    " ".repeat(available_width - displayed_left_len),
+                    status_style,
                 ));
             }
 
@@ -414,19 +485,57 @@ impl StatusBarRenderer {
         frame.render_widget(status_line, area);
     }
 
+    /// Turn styled text segments into spans that fit in `max_width` columns
+    ///
+    /// Text that doesn't fit is cut with "..." (counting chars, so multi-byte
+    /// text is never split). Returns the spans and their total width.
+    fn truncate_segments(
+        segments: &[(String, Style)],
+        max_width: usize,
+    ) -> (Vec<Span<'static>>, usize) {
+        let total: usize = segments.iter().map(|(text, _)| text.chars().count()).sum();
+        let (limit, ellipsis) = if total <= max_width {
+            (total, false)
+        } else if max_width > 3 {
+            (max_width - 3, true)
+        } else {
+            (max_width, false)
+        };
+
+        let mut spans = Vec::new();
+        let mut used = 0;
+        let mut last_style = Style::default();
+        for (text, style) in segments {
+            if used >= limit {
+                break;
+            }
+            let part: String = text.chars().take(limit - used).collect();
+            used += part.chars().count();
+            last_style = *style;
+            spans.push(Span::styled(part, *style));
+        }
+        if ellipsis {
+            spans.push(Span::styled("...", last_style));
+            used += 3;
+        }
+        (spans, used)
+    }
+
     /// Render the search options bar (shown when search prompt is active)
     ///
     /// Displays checkboxes for search options with their keyboard shortcuts:
     /// - Case Sensitive (Alt+C)
     /// - Whole Word (Alt+W)
     /// - Regex (Alt+R)
-    /// - Confirm Each (Alt+I) - only shown in replace mode
+    /// - In Selection (Alt+I) - only shown when the search started with a selection
+    /// - Confirm Each (Alt+E) - only shown in replace mode
     pub fn render_search_options(
         frame: &mut Frame,
         area: Rect,
         case_sensitive: bool,
         whole_word: bool,
         use_regex: bool,
+        in_selection: Option<bool>, // None = don't show, Some(value) = show with this state
         confirm_each: Option<bool>, // None = don't show, Some(value) = show with this state
         theme: &crate::view::theme::Theme,
         keybindings: &crate::input::keybindings::KeybindingResolver,
@@ -514,6 +623,29 @@ impl StatusBarRenderer {
             spans.push(Span::styled(format!(" ({})", shortcut), shortcut_style));
         }
 
+        // In Selection option (only shown when there is a selection to search in)
+        if let Some(selection_value) = in_selection {
+            let selection_shortcut =
+                get_shortcut(&crate::input::keybindings::Action::ToggleSearchInSelection);
+            let selection_checkbox = if selection_value { "[x]" } else { "[ ]" };
+
+            // Separator
+            spans.push(Span::styled("   ", base_style));
+
+            spans.push(Span::styled(
+                selection_checkbox,
+                if selection_value {
+                    active_style
+                } else {
+                    base_style
+                },
+            ));
+            spans.push(Span::styled(" In Selection", base_style));
+            if let Some(shortcut) = &selection_shortcut {
+                spans.push(Span::styled(format!(" ({})", shortcut), shortcut_style));
+            }
+        }
+
         // Confirm Each option (only shown in replace mode)
         if let Some(confirm_value) = confirm_each {
             let confirm_shortcut =
@@ -1,7 +1,7 @@
 //! Autocomplete suggestions and command palette UI rendering
 
-use crate::input::commands::CommandSource;
-use crate::view::prompt::Prompt;
+use crate::input::commands::{CommandSource, Suggestion, SuggestionAction};
+use crate::view::prompt::{Prompt, SuggestionRow};
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
@@ -11,7 +11,223 @@ use ratatui::Frame;
 /// Renders the autocomplete suggestions popup
 pub struct SuggestionsRenderer;
 
+// Column layout: "  Name  Keybinding  Description  Source"
+const LEFT_MARGIN: usize = 2;
+const COLUMN_SPACING: usize = 2;
+const MAX_NAME_COLUMN_WIDTH: usize = 40;
+const MIN_NAME_COLUMN_WIDTH: usize = 12; // Names only shrink this far when space is tight
+const MAX_KEYBINDING_COLUMN_WIDTH: usize = 16; // e.g., "Ctrl+Shift+Alt+P"
+const MAX_SOURCE_COLUMN_WIDTH: usize = 15; // e.g., "builtin", "live_grep"
+
+/// Widths of the suggestion columns
+///
+/// Computed from all suggestions (not just the visible ones) so the columns
+/// stay aligned while scrolling. Columns that no suggestion uses are zero wide
+/// and take no space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ColumnLayout {
+    name: usize,
+    keybinding: usize,
+    description: usize,
+    source: usize,
+}
+
+impl ColumnLayout {
+    /// Each column as wide as its longest entry (capped for name, keybinding and source)
+    fn natural(suggestions: &[Suggestion]) -> Self {
+        let widest = |width: fn(&Suggestion) -> Option<usize>| {
+            suggestions.iter().filter_map(width).max().unwrap_or(0)
+        };
+        Self {
+            name: widest(|s| Some(s.text.chars().count())).min(MAX_NAME_COLUMN_WIDTH),
+            keybinding: widest(|s| s.keybinding.as_ref().map(|k| k.chars().count()))
+                .min(MAX_KEYBINDING_COLUMN_WIDTH),
+            description: widest(|s| s.description.as_ref().map(|d| d.chars().count())),
+            source: widest(|s| {
+                s.source
+                    .as_ref()
+                    .map(|src| source_label(src).chars().count())
+            })
+            .min(MAX_SOURCE_COLUMN_WIDTH),
+        }
+    }
+
+    /// Width of a row laid out with these columns
+    fn width(&self) -> usize {
+        LEFT_MARGIN
+            + self.name
+            + [self.keybinding, self.description, self.source]
+                .iter()
+                .filter(|&&width| width > 0)
+                .map(|width| COLUMN_SPACING + width)
+                .sum::<usize>()
+    }
+
+    /// Fit the columns to `available` columns
+    ///
+    /// The description absorbs any difference (so the source column stays
+    /// right-aligned); if that isn't enough, the name shrinks down to a minimum.
+    fn fit(mut self, available: usize) -> Self {
+        let width = self.width();
+        if width < available {
+            if self.description > 0 {
+                self.description += available - width;
+            }
+        } else {
+            self.description = self.description.saturating_sub(width - available);
+            let width = self.width();
+            if width > available {
+                let min_name = MIN_NAME_COLUMN_WIDTH.min(self.name);
+                self.name = self.name.saturating_sub(width - available).max(min_name);
+            }
+        }
+        self
+    }
+}
+
+/// Display label for the source of a command
+fn source_label(source: &CommandSource) -> String {
+    match source {
+        CommandSource::Builtin => "builtin".to_string(),
+        CommandSource::Plugin(name) => name.clone(),
+        CommandSource::Project => "project".to_string(),
+    }
+}
+
+/// Truncate `text` to at most `width` chars, marking the cut with an ellipsis
+///
+/// Counts chars rather than bytes so multi-byte UTF-8 text is never split.
+pub(super) fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut truncated: String = text.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Separator between the actions of the hint row
+const HINT_SEPARATOR: &str = " · ";
+
+/// The hint row text of some actions, as (key, label) pairs
+fn action_hints(actions: &[SuggestionAction]) -> Vec<(String, String)> {
+    actions
+        .iter()
+        .map(|action| (action.key_label(), action.label.clone()))
+        .collect()
+}
+
+/// Width of the hint row for some actions
+fn action_hints_width(actions: &[SuggestionAction]) -> usize {
+    let hints = action_hints(actions);
+    if hints.is_empty() {
+        return 0;
+    }
+    LEFT_MARGIN
+        + hints
+            .iter()
+            .map(|(key, label)| key.chars().count() + 1 + label.chars().count())
+            .sum::<usize>()
+        + HINT_SEPARATOR.chars().count() * (hints.len() - 1)
+}
+
+/// Split `text` into spans, drawing the chars at `match_indices` in bold
+///
+/// Indices past the end (e.g. cut off by `truncate`) are ignored.
+fn highlight_matches(text: &str, match_indices: &[usize], style: Style) -> Vec<Span<'static>> {
+    let styled = |run: String, matched: bool| {
+        if matched {
+            Span::styled(run, style.add_modifier(Modifier::BOLD))
+        } else {
+            Span::styled(run, style)
+        }
+    };
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, ch) in text.chars().enumerate() {
+        let matched = match_indices.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            spans.push(styled(std::mem::take(&mut run), run_matched));
+        }
+        run_matched = matched;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        spans.push(styled(run, run_matched));
+    }
+    spans
+}
+
 impl SuggestionsRenderer {
+    /// Compute the area of the suggestions popup for a prompt line
+    ///
+    /// The popup is sized to its content, up to `max_height` suggestion rows and
+    /// `max_width` columns (0 = no limit beyond the screen). It opens below the
+    /// prompt when the full popup fits there, otherwise above it, falling back to
+    /// whichever side has more room. When suggestions have secondary actions, a
+    /// row at the bottom lists the selected one's.
+    pub fn popup_area(
+        prompt: &Prompt,
+        prompt_area: Rect,
+        screen: Rect,
+        max_height: usize,
+        max_width: usize,
+    ) -> Rect {
+        let rows = prompt.suggestion_rows().len().min(max_height.max(1));
+        let hint_rows = usize::from(prompt.has_action_hints());
+        let wanted_height = (rows + hint_rows) as u16 + 2; // +2 for borders
+
+        let space_above = prompt_area.y.saturating_sub(screen.y);
+        let space_below = screen.bottom().saturating_sub(prompt_area.bottom());
+        let (below, height) = if space_below >= wanted_height {
+            (true, wanted_height)
+        } else if space_above >= wanted_height {
+            (false, wanted_height)
+        } else if space_below > space_above {
+            (true, space_below)
+        } else {
+            (false, space_above)
+        };
+
+        let hints_width = prompt
+            .suggestions
+            .iter()
+            .map(|s| action_hints_width(&s.actions))
+            .max()
+            .unwrap_or(0);
+        // +2 for borders
+        let mut width = (ColumnLayout::natural(&prompt.suggestions)
+            .width()
+            .max(hints_width)
+            + 2) as u16;
+        if max_width > 0 {
+            width = width.min(max_width as u16);
+        }
+        width = width.min(screen.width);
+        let x = prompt_area
+            .x
+            .min(screen.right().saturating_sub(width))
+            .max(screen.x);
+
+        let y = if below {
+            prompt_area.bottom()
+        } else {
+            prompt_area.y.saturating_sub(height)
+        };
+
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
     /// Render the suggestions popup (autocomplete/command palette)
     ///
     /// Displays a list of suggestions with the selected one highlighted.
@@ -35,8 +251,15 @@ impl SuggestionsRenderer {
 
     /// Render the suggestions popup with hover highlighting
     ///
+    /// Name, keybinding, description and source are drawn as aligned columns,
+    /// each with its own theme color.
+    ///
+    /// Section headers (see [`Prompt::sections`]) take a row each, so the
+    /// scroll position and counts returned are in rows, not suggestions; map
+    /// them back with [`Prompt::suggestion_rows`].
+    ///
     /// # Returns
-    /// * Optional tuple of (inner_rect, scroll_start_idx, visible_count, total_count) for mouse hit testing
+    /// * Optional tuple of (inner_rect, scroll_start_row, visible_count, total_rows) for mouse hit testing
     pub fn render_with_hover(
         frame: &mut Frame,
         area: Rect,
@@ -54,20 +277,30 @@ impl SuggestionsRenderer {
             .border_style(Style::default().fg(theme.popup_border_fg))
             .style(Style::default().bg(theme.suggestion_bg));
 
-        let inner_area = block.inner(area);
+        let mut inner_area = block.inner(area);
+        // The bottom row lists the selected suggestion's secondary actions
+        let hint_row = prompt.has_action_hints() && inner_area.height > 1;
+        if hint_row {
+            inner_area.height -= 1;
+        }
 
         let mut lines = Vec::new();
         let visible_count = inner_area.height as usize;
+        let rows = prompt.suggestion_rows();
 
         // Calculate scroll position to keep selected item visible
-        let start_idx = if let Some(selected) = prompt.selected_suggestion {
+        let selected_row = prompt.selected_suggestion.and_then(|selected| {
+            rows.iter()
+                .position(|row| *row == SuggestionRow::Suggestion(selected))
+        });
+        let start_idx = if let Some(selected) = selected_row {
             // Try to center the selected item, or at least keep it visible
             if selected < visible_count / 2 {
                 // Near the top, start from beginning
                 0
-            } else if selected >= prompt.suggestions.len() - visible_count / 2 {
+            } else if selected >= rows.len() - visible_count / 2 {
                 // Near the bottom, show last page
-                prompt.suggestions.len().saturating_sub(visible_count)
+                rows.len().saturating_sub(visible_count)
             } else {
                 // In the middle, center the selected item
                 selected.saturating_sub(visible_count / 2)
@@ -76,225 +309,126 @@ impl SuggestionsRenderer {
             0
         };
 
-        let end_idx = (start_idx + visible_count).min(prompt.suggestions.len());
+        let end_idx = (start_idx + visible_count).min(rows.len());
 
-        let visible_suggestions = &prompt.suggestions[start_idx..end_idx];
-
-        // Fixed column layout: "  Name  |  Keybinding  |  Description  |  Source"
-        let left_margin = 2;
-        let column_spacing = 2;
         let available_width = inner_area.width as usize;
-
-        // Fixed column widths for consistent layout
-        let name_column_width = 30; // Fixed width for command names
-        let keybinding_column_width = 12; // Fixed width for keybindings (e.g., "Ctrl+Shift+P")
-        let source_column_width = 15; // Fixed width for source (e.g., "builtin", "live_grep")
-
-        for (idx, suggestion) in visible_suggestions.iter().enumerate() {
-            let actual_idx = start_idx + idx;
+        let columns = ColumnLayout::natural(&prompt.suggestions).fit(available_width);
+        let spacing = " ".repeat(COLUMN_SPACING);
+
+        for row in &rows[start_idx..end_idx] {
+            let actual_idx = match *row {
+                SuggestionRow::Header(title) => {
+                    let header = truncate(title, available_width.saturating_sub(1));
+                    let padding = available_width.saturating_sub(header.chars().count() + 1);
+                    lines.push(Line::from(vec![
+                        Span::styled(" ", Style::default().bg(theme.suggestion_bg)),
+                        Span::styled(
+                            header,
+                            Style::default()
+                                .fg(theme.suggestion_description_fg)
+                                .bg(theme.suggestion_bg)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            " ".repeat(padding),
+                            Style::default().bg(theme.suggestion_bg),
+                        ),
+                    ]));
+                    continue;
+                }
+                SuggestionRow::Suggestion(idx) => idx,
+            };
+            let suggestion = &prompt.suggestions[actual_idx];
             let is_selected = prompt.selected_suggestion == Some(actual_idx);
             let is_hovered = matches!(
                 hover_target,
                 Some(crate::app::HoverTarget::SuggestionItem(hovered_idx)) if *hovered_idx == actual_idx
             );
 
-            let base_style = if suggestion.disabled {
-                // Greyed out disabled commands
-                if is_selected {
+            let bg = if is_selected {
+                theme.suggestion_selected_bg
+            } else if is_hovered {
+                theme.menu_hover_bg
+            } else {
+                theme.suggestion_bg
+            };
+            let base_style = Style::default().bg(bg);
+
+            // Each column has its own color; disabled commands are greyed out entirely
+            let column_style = |fg: Color| {
+                if suggestion.disabled {
                     Style::default()
                         .fg(Color::DarkGray)
-                        .bg(theme.suggestion_selected_bg)
+                        .bg(bg)
                         .add_modifier(Modifier::DIM)
                 } else {
-                    Style::default()
-                        .fg(Color::DarkGray)
-                        .bg(theme.suggestion_bg)
-                        .add_modifier(Modifier::DIM)
+                    Style::default().fg(fg).bg(bg)
                 }
-            } else if is_selected {
-                // Highlight selected suggestion with theme colors
-                Style::default()
-                    .fg(theme.popup_text_fg)
-                    .bg(theme.suggestion_selected_bg)
-            } else if is_hovered {
-                // Hover highlight
-                Style::default()
-                    .fg(theme.menu_hover_fg)
-                    .bg(theme.menu_hover_bg)
-            } else {
-                // Normal suggestion with theme colors
-                Style::default()
-                    .fg(theme.popup_text_fg)
-                    .bg(theme.suggestion_bg)
-            };
-
-            // Build the line with three columns
-            let mut spans = Vec::new();
-
-            // Left margin
-            spans.push(Span::styled(" ".repeat(left_margin), base_style));
-
-            // Column 1: Command name (fixed width, truncate if too long)
-            let name = &suggestion.text;
-            let name_char_count = name.chars().count();
-            let name_text = if name_char_count > name_column_width {
-                // Truncate name if too long
-                let truncated: String = name.chars().take(name_column_width - 1).collect();
-                format!("{}…", truncated)
-            } else {
-                name.clone()
             };
-            spans.push(Span::styled(name_text.clone(), base_style));
-            let name_display_width = name_text.chars().count();
-            let name_padding = name_column_width.saturating_sub(name_display_width);
-            if name_padding > 0 {
-                spans.push(Span::styled(" ".repeat(name_padding), base_style));
-            }
 
-            // Spacing before keybinding column
-            spans.push(Span::styled(" ".repeat(column_spacing), base_style));
-
-            // Column 2: Keyboard shortcut (fixed width)
-            let keybinding_style = if suggestion.disabled {
-                base_style
-            } else if is_selected {
-                Style::default()
-                    .fg(theme.help_key_fg)
-                    .bg(theme.suggestion_selected_bg)
-            } else if is_hovered {
-                Style::default()
-                    .fg(theme.help_key_fg)
-                    .bg(theme.menu_hover_bg)
-            } else {
-                Style::default()
-                    .fg(theme.line_number_fg)
-                    .bg(theme.suggestion_bg)
+            // Left-aligned cell: truncated to the column width and padded to fill it
+            let cell = |text: &str, width: usize| {
+                let text = truncate(text, width);
+                let padding = width.saturating_sub(text.chars().count());
+                (text, " ".repeat(padding))
             };
 
-            if let Some(keybinding) = &suggestion.keybinding {
-                let kb_char_count = keybinding.chars().count();
-                let kb_text = if kb_char_count > keybinding_column_width {
-                    // Truncate keybinding if too long (unlikely but safe)
-                    keybinding.chars().take(keybinding_column_width).collect()
-                } else {
-                    keybinding.clone()
-                };
-                spans.push(Span::styled(kb_text.clone(), keybinding_style));
-                let kb_display_width = kb_text.chars().count();
-                let kb_padding = keybinding_column_width.saturating_sub(kb_display_width);
-                if kb_padding > 0 {
-                    spans.push(Span::styled(" ".repeat(kb_padding), base_style));
-                }
-            } else {
-                // No keybinding for this command, pad the column
+            let mut spans = vec![Span::styled(" ".repeat(LEFT_MARGIN), base_style)];
+
+            // Column 1: name
+            let (name, padding) = cell(&suggestion.text, columns.name);
+            spans.extend(highlight_matches(
+                &name,
+                &suggestion.match_indices,
+                column_style(theme.suggestion_name_fg),
+            ));
+            spans.push(Span::styled(padding, base_style));
+
+            // Column 2: keyboard shortcut
+            if columns.keybinding > 0 {
+                let keybinding = suggestion.keybinding.as_deref().unwrap_or("");
+                let (keybinding, padding) = cell(keybinding, columns.keybinding);
+                spans.push(Span::styled(spacing.clone(), base_style));
                 spans.push(Span::styled(
-                    " ".repeat(keybinding_column_width),
-                    base_style,
+                    keybinding,
+                    column_style(theme.suggestion_keybinding_fg),
                 ));
+                spans.push(Span::styled(padding, base_style));
             }
 
-            // Spacing before description column
-            spans.push(Span::styled(" ".repeat(column_spacing), base_style));
-
-            // Calculate space used by fixed columns
-            let fixed_columns_width = left_margin
-                + name_column_width
-                + column_spacing
-                + keybinding_column_width
-                + column_spacing;
-
-            // Reserve space for source column at the end
-            let source_reserved = column_spacing + source_column_width;
-
-            // Column 3: Description (flexible width, leaves room for source)
-            if let Some(desc) = &suggestion.description {
-                // Only show description if we have enough space
-                if fixed_columns_width + source_reserved < available_width {
-                    let desc_width = available_width
-                        .saturating_sub(fixed_columns_width)
-                        .saturating_sub(source_reserved);
-                    // Use char count for truncation to avoid slicing in the middle of
-                    // multi-byte UTF-8 characters (e.g., fancy quotes like ")
-                    let desc_text = if desc.chars().count() > desc_width {
-                        // Truncate description if it's too long
-                        let truncated: String =
-                            desc.chars().take(desc_width.saturating_sub(3)).collect();
-                        format!("{}...", truncated)
-                    } else {
-                        desc.clone()
-                    };
-                    let desc_display_width = desc_text.chars().count();
-                    spans.push(Span::styled(desc_text, base_style));
-                    // Pad description to fill its allocated space
-                    let desc_padding = desc_width.saturating_sub(desc_display_width);
-                    if desc_padding > 0 {
-                        spans.push(Span::styled(" ".repeat(desc_padding), base_style));
-                    }
-                }
-            } else {
-                // No description, but still need to pad to align source column
-                let desc_width = available_width
-                    .saturating_sub(fixed_columns_width)
-                    .saturating_sub(source_reserved);
-                if desc_width > 0 {
-                    spans.push(Span::styled(" ".repeat(desc_width), base_style));
-                }
+            // Column 3: description (absorbs the remaining width)
+            if columns.description > 0 {
+                let description = suggestion.description.as_deref().unwrap_or("");
+                let (description, padding) = cell(description, columns.description);
+                spans.push(Span::styled(spacing.clone(), base_style));
+                spans.push(Span::styled(
+                    description,
+                    column_style(theme.suggestion_description_fg),
+                ));
+                spans.push(Span::styled(padding, base_style));
             }
 
-            // Spacing before source column
-            spans.push(Span::styled(" ".repeat(column_spacing), base_style));
-
-            // Column 4: Source (right-aligned, fixed width)
-            let source_style = if suggestion.disabled {
-                base_style
-            } else if is_selected {
-                Style::default()
-                    .fg(theme.line_number_fg)
-                    .bg(theme.suggestion_selected_bg)
-                    .add_modifier(Modifier::DIM)
-            } else if is_hovered {
-                Style::default()
-                    .fg(theme.line_number_fg)
-                    .bg(theme.menu_hover_bg)
-                    .add_modifier(Modifier::DIM)
-            } else {
-                Style::default()
-                    .fg(theme.line_number_fg)
-                    .bg(theme.suggestion_bg)
-                    .add_modifier(Modifier::DIM)
-            };
-
-            if let Some(source) = &suggestion.source {
-                let source_text = match source {
-                    CommandSource::Builtin => "builtin".to_string(),
-                    CommandSource::Plugin(name) => name.clone(),
-                };
-                let source_char_count = source_text.chars().count();
-                let source_display = if source_char_count > source_column_width {
-                    // Truncate source if too long
-                    let truncated: String =
-                        source_text.chars().take(source_column_width - 1).collect();
-                    format!("{}…", truncated)
-                } else {
-                    source_text
-                };
-                let source_display_width = source_display.chars().count();
-                // Right-align the source text within its column
-                let source_padding = source_column_width.saturating_sub(source_display_width);
-                if source_padding > 0 {
-                    spans.push(Span::styled(" ".repeat(source_padding), base_style));
-                }
-                spans.push(Span::styled(source_display, source_style));
-            } else {
-                // No source info, just pad
-                spans.push(Span::styled(" ".repeat(source_column_width), base_style));
+            // Column 4: source (right-aligned)
+            if columns.source > 0 {
+                let source = suggestion
+                    .source
+                    .as_ref()
+                    .map(source_label)
+                    .unwrap_or_default();
+                let (source, padding) = cell(&source, columns.source);
+                spans.push(Span::styled(spacing.clone(), base_style));
+                spans.push(Span::styled(padding, base_style));
+                spans.push(Span::styled(
+                    source,
+                    column_style(theme.line_number_fg).add_modifier(Modifier::DIM),
+                ));
             }
 
-            // Fill any remaining space with background (shouldn't be needed but safe)
-            let current_width: usize = spans.iter().map(|s| s.content.len()).sum();
+            // Fill any remaining space with background
+            let current_width: usize = spans.iter().map(|s| s.content.chars().count()).sum();
             if current_width < available_width {
                 spans.push(Span::styled(
-                    " ".repeat(available_width.saturating_sub(current_width)),
+                    " ".repeat(available_width - current_width),
                     base_style,
                 ));
             }
@@ -310,16 +444,54 @@ impl SuggestionsRenderer {
             )));
         }
 
+        if hint_row {
+            let actions = prompt
+                .selected_suggestion
+                .and_then(|idx| prompt.suggestions.get(idx))
+                .map(|s| s.actions.as_slice())
+                .unwrap_or_default();
+            lines.push(Self::hint_line(actions, available_width, theme));
+        }
+
         let paragraph = Paragraph::new(lines).block(block);
         frame.render_widget(paragraph, area);
 
         // Return area info for mouse hit testing
-        Some((
-            inner_area,
-            start_idx,
-            visible_count,
-            prompt.suggestions.len(),
-        ))
+        Some((inner_area, start_idx, visible_count, rows.len()))
+    }
+
+    /// The hint row: each action's key and what it does, cut to `width`
+    fn hint_line(
+        actions: &[SuggestionAction],
+        width: usize,
+        theme: &crate::view::theme::Theme,
+    ) -> Line<'static> {
+        let base_style = Style::default().bg(theme.suggestion_bg);
+        let key_style = base_style.fg(theme.suggestion_keybinding_fg);
+        let label_style = base_style.fg(theme.suggestion_description_fg);
+
+        let mut spans = vec![Span::styled(" ".repeat(LEFT_MARGIN), base_style)];
+        let mut used = LEFT_MARGIN;
+        for (i, (key, label)) in action_hints(actions).into_iter().enumerate() {
+            let parts = [
+                (
+                    if i > 0 { HINT_SEPARATOR } else { "" }.to_string(),
+                    label_style,
+                ),
+                (key, key_style),
+                (format!(" {}", label), label_style),
+            ];
+            for (text, style) in parts {
+                let text = truncate(&text, width.saturating_sub(used));
+                used += text.chars().count();
+                spans.push(Span::styled(text, style));
+            }
+        }
+        spans.push(Span::styled(
+            " ".repeat(width.saturating_sub(used)),
+            base_style,
+        ));
+        Line::from(spans)
     }
 }
 
@@ -393,6 +565,198 @@ mod tests {
             .unwrap();
     }
 
+    fn prompt_with_suggestions(count: usize) -> Prompt {
+        let mut prompt = Prompt::new(
+            "Test: ".to_string(),
+            crate::view::prompt::PromptType::Command,
+        );
+        prompt.suggestions = (0..count)
+            .map(|i| Suggestion::new(format!("Command {}", i)))
+            .collect();
+        prompt
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Close buffer", 20), "Close buffer");
+        assert_eq!(truncate("Close buffer", 6), "Close…");
+        assert_eq!(truncate("Close buffer", 0), "");
+    }
+
+    #[test]
+    fn test_popup_area_sizes_to_content() {
+        let screen = Rect::new(0, 0, 120, 40);
+        let prompt_area = Rect::new(0, 39, 120, 1);
+
+        // Three suggestions: three rows plus borders, placed above the bottom prompt
+        let prompt = prompt_with_suggestions(3);
+        let area = SuggestionsRenderer::popup_area(&prompt, prompt_area, screen, 10, 0);
+        assert_eq!(area.height, 5);
+        assert_eq!(area.bottom(), prompt_area.y);
+        assert!(area.width < screen.width, "Width should fit the content");
+
+        // Many suggestions are capped at the max height, and width at the max width
+        let prompt = prompt_with_suggestions(50);
+        let area = SuggestionsRenderer::popup_area(&prompt, prompt_area, screen, 8, 10);
+        assert_eq!(area.height, 10);
+        assert_eq!(area.width, 10);
+    }
+
+    #[test]
+    fn test_action_hint_row() {
+        let mut prompt = prompt_with_suggestions(2);
+        prompt.suggestions[0].actions = vec![
+            SuggestionAction::new("ctrl+w", "close", "close"),
+            SuggestionAction::new("ctrl+enter", "open in split", "split"),
+        ];
+        prompt.selected_suggestion = Some(0);
+
+        // The hint row takes a row of its own
+        let screen = Rect::new(0, 0, 120, 40);
+        let prompt_area = Rect::new(0, 39, 120, 1);
+        let area = SuggestionsRenderer::popup_area(&prompt, prompt_area, screen, 10, 0);
+        assert_eq!(area.height, 5);
+
+        let width = area.width;
+        let backend = TestBackend::new(width, area.height);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        let mut hit_area = None;
+        terminal
+            .draw(|frame| {
+                hit_area = SuggestionsRenderer::render(
+                    frame,
+                    Rect::new(0, 0, width, area.height),
+                    &prompt,
+                    &theme,
+                );
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let hint: String = (0..width)
+            .map(|x| buffer.cell((x, 3)).unwrap().symbol().to_string())
+            .collect();
+        let expected = format!(
+            "{} close · {} open in split",
+            prompt.suggestions[0].actions[0].key_label(),
+            prompt.suggestions[0].actions[1].key_label()
+        );
+        assert!(hint.contains(&expected), "hint row: {:?}", hint);
+        // Clicks on the hint row don't pick a suggestion
+        let (inner, _, visible, _) = hit_area.unwrap();
+        assert_eq!((inner.height, visible), (2, 2));
+
+        // A suggestion without actions leaves the row empty
+        prompt.selected_suggestion = Some(1);
+        terminal
+            .draw(|frame| {
+                SuggestionsRenderer::render(
+                    frame,
+                    Rect::new(0, 0, width, area.height),
+                    &prompt,
+                    &theme,
+                );
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let hint: String = (0..width)
+            .map(|x| buffer.cell((x, 3)).unwrap().symbol().to_string())
+            .collect();
+        assert!(!hint.contains("close"), "hint row: {:?}", hint);
+    }
+
+    #[test]
+    fn test_popup_area_anchors_below_when_room() {
+        let screen = Rect::new(0, 0, 80, 40);
+        let prompt = prompt_with_suggestions(5);
+
+        // Prompt near the top: the popup opens below it
+        let prompt_area = Rect::new(0, 2, 80, 1);
+        let area = SuggestionsRenderer::popup_area(&prompt, prompt_area, screen, 10, 0);
+        assert_eq!(area.y, 3);
+        assert_eq!(area.height, 7);
+
+        // Not enough room on either side: use the larger side, shrunk to fit
+        let screen = Rect::new(0, 0, 80, 8);
+        let prompt_area = Rect::new(0, 5, 80, 1);
+        let area = SuggestionsRenderer::popup_area(&prompt, prompt_area, screen, 10, 0);
+        assert_eq!(area.y, 0);
+        assert_eq!(area.height, 5);
+    }
+
+    #[test]
+    fn test_suggestion_columns_are_aligned() {
+        let mut short = Suggestion::new("Save".to_string());
+        short.keybinding = Some("Ctrl+S".to_string());
+        short.description = Some("Save the file".to_string());
+        let mut long = Suggestion::new("Toggle Line Numbers".to_string());
+        long.keybinding = Some("Ctrl+Shift+L".to_string());
+        long.description = Some("Show or hide the gutter line numbers".to_string());
+
+        let mut prompt = Prompt::new(
+            "Command: ".to_string(),
+            crate::view::prompt::PromptType::Command,
+        );
+        prompt.suggestions = vec![short, long];
+
+        let width = 60;
+        let backend = TestBackend::new(width, 4);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        terminal
+            .draw(|frame| {
+                SuggestionsRenderer::render(frame, Rect::new(0, 0, width, 4), &prompt, &theme);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect()
+        };
+        let (first, second) = (row(1), row(2));
+        let column_of =
+            |line: &str, needle: &str| line.find(needle).map(|byte| line[..byte].chars().count());
+
+        // Keybindings and descriptions start at the same column on every row
+        assert_eq!(
+            column_of(&first, "Ctrl+S"),
+            column_of(&second, "Ctrl+Shift+L")
+        );
+        assert_eq!(
+            column_of(&first, "Save the file"),
+            column_of(&second, "Show or hide")
+        );
+        // The long description doesn't fit and is cut with an ellipsis
+        assert!(second.contains('…'), "Expected ellipsis in: {}", second);
+        assert!(first.contains("Save the file"));
+    }
+
+    #[test]
+    fn test_column_layout_fit() {
+        let layout = ColumnLayout {
+            name: 30,
+            keybinding: 6,
+            description: 40,
+            source: 0,
+        };
+        assert_eq!(layout.width(), 2 + 30 + 2 + 6 + 2 + 40);
+
+        // The description shrinks first, then the name down to its minimum
+        assert_eq!(layout.fit(70).description, 28);
+        assert_eq!(layout.fit(70).width(), 70);
+        let tight = layout.fit(20);
+        assert_eq!(tight.description, 0);
+        assert_eq!(tight.name, MIN_NAME_COLUMN_WIDTH);
+
+        // Spare room goes to the description
+        assert_eq!(layout.fit(100).width(), 100);
+
+        assert_eq!(truncate("Description", 6), "Descr…");
+        assert_eq!(truncate("Short", 6), "Short");
+    }
+
     /// Test that truncation produces valid UTF-8 output
     #[test]
     fn test_truncation_preserves_valid_utf8() {
@@ -443,4 +807,87 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_section_headers_take_a_row() {
+        let mut prompt = prompt_with_suggestions(3);
+        prompt.sections = vec![
+            (0, "Recently Used".to_string()),
+            (1, "Other Commands".to_string()),
+        ];
+        prompt.selected_suggestion = Some(1);
+
+        let width = 40;
+        let backend = TestBackend::new(width, 7);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        let mut hit_area = None;
+        terminal
+            .draw(|frame| {
+                hit_area =
+                    SuggestionsRenderer::render(frame, Rect::new(0, 0, width, 7), &prompt, &theme);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..width)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect()
+        };
+        assert!(row(1).contains("Recently Used"));
+        assert!(row(2).contains("Command 0"));
+        assert!(row(3).contains("Other Commands"));
+        assert!(row(4).contains("Command 1"));
+
+        // Counts are in rows: 3 suggestions and 2 headers
+        assert_eq!(
+            hit_area.map(|(_, start, _, total)| (start, total)),
+            Some((0, 5))
+        );
+        assert_eq!(
+            prompt.suggestion_rows()[3],
+            crate::view::prompt::SuggestionRow::Suggestion(1)
+        );
+    }
+
+    #[test]
+    fn test_matched_chars_are_bold() {
+        let mut suggestion = Suggestion::new("Save File".to_string());
+        suggestion.match_indices = vec![0, 5];
+        let mut prompt = Prompt::new(
+            "Command: ".to_string(),
+            crate::view::prompt::PromptType::Command,
+        );
+        prompt.suggestions = vec![suggestion];
+
+        let width = 30;
+        let backend = TestBackend::new(width, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let theme = Theme::default();
+        terminal
+            .draw(|frame| {
+                SuggestionsRenderer::render(frame, Rect::new(0, 0, width, 3), &prompt, &theme);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let start = (0..width)
+            .find(|&x| buffer.cell((x, 1)).unwrap().symbol() == "S")
+            .unwrap();
+        let bold: Vec<bool> = (start..start + 9)
+            .map(|x| {
+                buffer
+                    .cell((x, 1))
+                    .unwrap()
+                    .modifier
+                    .contains(Modifier::BOLD)
+            })
+            .collect();
+        // Only the "S" of "Save" and the "F" of "File"
+        assert_eq!(
+            bold,
+            vec![true, false, false, false, false, true, false, false, false]
+        );
+    }
 }
@@ -1,4 +1,11 @@
 //! Tab bar rendering for multiple buffers
+//!
+//! Tabs are drawn in the order of the split's `open_buffers`, which "Move Tab
+//! Left" and "Move Tab Right" rearrange. When they don't all fit, a window of
+//! whole tabs that always includes the active one is shown, with "‹" and "›"
+//! where tabs are hidden; "Scroll Tabs Left/Right" move it a tab at a time.
+//! Files with the same name are told apart by their parent directories,
+//! e.g. "editor/mod.rs" and "ui/mod.rs".
 
 use crate::app::BufferMetadata;
 use crate::model::event::BufferId;
@@ -9,12 +16,145 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Paragraph};
 use ratatui::Frame;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Renders the tab bar showing open buffers
 pub struct TabsRenderer;
 
+/// Close button at the end of every tab
+const CLOSE_BUTTON: &str = "× ";
+/// Width of the separator between tabs
+const TAB_SEPARATOR_WIDTH: usize = 1;
+/// Shown when tabs are hidden to the left
+const SCROLL_INDICATOR_LEFT: &str = "‹";
+/// Shown when tabs are hidden to the right
+const SCROLL_INDICATOR_RIGHT: &str = "›";
+/// Width of "‹" or "›"
+const SCROLL_INDICATOR_WIDTH: usize = 1;
+
+/// The part of a split's tab strip that is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TabWindow {
+    /// Column of the strip the window starts at
+    pub offset: usize,
+    /// Whether tabs are hidden to the left
+    pub show_left: bool,
+    /// Whether tabs are hidden to the right
+    pub show_right: bool,
+}
+
+/// The shortest trailing part of each path that no other path ends with:
+/// the file name, with parent directories added while other paths share it
+fn distinguishing_names(paths: &[&Path]) -> Vec<String> {
+    // File name first
+    let components: Vec<Vec<String>> = paths
+        .iter()
+        .map(|path| {
+            path.iter()
+                .rev()
+                .map(|c| c.to_string_lossy().into_owned())
+                .collect()
+        })
+        .collect();
+    components
+        .iter()
+        .map(|own| {
+            let shared = |depth: usize| {
+                components.iter().any(|other| {
+                    other != own && other.iter().take(depth).eq(own.iter().take(depth))
+                })
+            };
+            let mut depth = 1;
+            while depth < own.len() && shared(depth) {
+                depth += 1;
+            }
+            let mut name: Vec<&str> = own.iter().take(depth).map(String::as_str).collect();
+            name.reverse();
+            name.join("/")
+        })
+        .collect()
+}
+
+/// Names on the tabs of the file buffers in `split_buffers`
+fn tab_file_names(
+    split_buffers: &[BufferId],
+    buffers: &HashMap<BufferId, EditorState>,
+) -> HashMap<BufferId, String> {
+    let files: Vec<(BufferId, &Path)> = split_buffers
+        .iter()
+        .filter_map(|id| Some((*id, buffers.get(id)?.buffer.file_path()?)))
+        .filter(|(_, path)| path.file_name().is_some())
+        .collect();
+    let paths: Vec<&Path> = files.iter().map(|&(_, path)| path).collect();
+    files
+        .iter()
+        .map(|&(id, _)| id)
+        .zip(distinguishing_names(&paths))
+        .collect()
+}
+
+/// A buffer's tab without its close button, e.g. " main.rs* "
+fn tab_label(
+    id: BufferId,
+    state: &EditorState,
+    buffer_metadata: &HashMap<BufferId, BufferMetadata>,
+    file_names: &HashMap<BufferId, String>,
+) -> String {
+    let name = file_names
+        .get(&id)
+        .map(String::as_str)
+        .or_else(|| buffer_metadata.get(&id).map(|m| m.display_name.as_str()))
+        .unwrap_or("[No Name]");
+    let scratch = buffer_metadata.get(&id).is_some_and(|m| m.scratch);
+    let modified = if state.buffer.is_modified() && !scratch {
+        "*"
+    } else {
+        ""
+    };
+    let binary_indicator = if buffer_metadata.get(&id).is_some_and(|m| m.binary) {
+        " [BIN]"
+    } else {
+        ""
+    };
+    format!(" {name}{modified}{binary_indicator} ")
+}
+
+/// Widths of the tabs drawn for `split_buffers`, close buttons included
+pub fn tab_widths(
+    split_buffers: &[BufferId],
+    buffers: &HashMap<BufferId, EditorState>,
+    buffer_metadata: &HashMap<BufferId, BufferMetadata>,
+) -> Vec<usize> {
+    let file_names = tab_file_names(split_buffers, buffers);
+    split_buffers
+        .iter()
+        .filter_map(|id| {
+            let state = buffers.get(id)?;
+            let label = tab_label(*id, state, buffer_metadata, &file_names);
+            Some(label.chars().count() + CLOSE_BUTTON.chars().count())
+        })
+        .collect()
+}
+
+/// Column of the strip each tab starts at
+fn tab_starts(tab_widths: &[usize], padding_between_tabs: usize) -> Vec<usize> {
+    tab_widths
+        .iter()
+        .scan(0, |pos, width| {
+            let start = *pos;
+            *pos += width + padding_between_tabs;
+            Some(start)
+        })
+        .collect()
+}
+
 /// Compute a scroll offset that keeps the active tab fully visible.
-/// `tab_widths` should include separators; `active_idx` refers to the tab index (not counting separators).
+///
+/// The offset is the start of the first tab shown: the one at
+/// `current_offset` if the active tab still fits after it, and no further
+/// right than needed to fill the view. An active tab wider than `max_width`
+/// is shown from its end, where its close button is. `tab_widths` don't
+/// include the padding between tabs; `active_idx` is a tab index.
 pub fn compute_tab_scroll_offset(
     tab_widths: &[usize],
     active_idx: usize,
@@ -28,38 +168,123 @@ pub fn compute_tab_scroll_offset(
 
     let total_width: usize = tab_widths.iter().sum::<usize>()
         + padding_between_tabs.saturating_mul(tab_widths.len().saturating_sub(1));
-    let mut tab_start = 0usize;
-    let mut tab_end = 0usize;
-
-    // Walk through widths to locate active tab boundaries.
-    let mut tab_counter = 0usize;
-    for w in tab_widths {
-        let next = tab_start + *w;
-        if tab_counter == active_idx {
-            tab_end = next;
-            break;
-        }
-        tab_start = next + padding_between_tabs;
-        tab_counter += 1;
-    }
+    let starts = tab_starts(tab_widths, padding_between_tabs);
 
     // If we didn't find the tab, keep current offset.
-    if tab_end == 0 {
+    let Some(&active_start) = starts.get(active_idx) else {
         return current_offset.min(total_width.saturating_sub(max_width));
+    };
+    let active_end = active_start + tab_widths[active_idx];
+    if active_end - active_start > max_width {
+        return active_end - max_width;
+    }
+
+    // Keep the first tab shown unless the active tab doesn't fit after it
+    let mut first = starts
+        .iter()
+        .rposition(|&start| start <= current_offset)
+        .unwrap_or(0)
+        .min(active_idx);
+    while active_end - starts[first] > max_width {
+        first += 1;
+    }
+    // Show hidden tabs rather than empty space after the last one
+    while first > 0 && total_width - starts[first - 1] <= max_width {
+        first -= 1;
     }
+    starts[first]
+}
 
-    // Basic rule: stick the active tab into view, prefer left-aligned start unless it overflows.
-    let mut offset = tab_start;
-    if tab_end.saturating_sub(offset) > max_width {
-        offset = tab_end.saturating_sub(max_width);
+/// The part of the tab strip to show in `max_width` columns: the window at
+/// `current_offset`, moved so the active tab fits between the "‹" and "›"
+pub fn compute_tab_window(
+    tab_widths: &[usize],
+    active_idx: Option<usize>,
+    max_width: usize,
+    current_offset: usize,
+) -> TabWindow {
+    let total_width: usize =
+        tab_widths.iter().sum::<usize>() + TAB_SEPARATOR_WIDTH * tab_widths.len().saturating_sub(1);
+    if total_width <= max_width {
+        return TabWindow {
+            offset: 0,
+            show_left: false,
+            show_right: false,
+        };
     }
 
-    offset.min(total_width.saturating_sub(max_width))
+    let window_at = |offset: usize| {
+        let show_left = offset > 0;
+        let after_left = max_width.saturating_sub(show_left as usize * SCROLL_INDICATOR_WIDTH);
+        let show_right = total_width.saturating_sub(offset) > after_left;
+        let available = after_left.saturating_sub(show_right as usize * SCROLL_INDICATOR_WIDTH);
+        (
+            TabWindow {
+                offset,
+                show_left,
+                show_right,
+            },
+            available,
+        )
+    };
+
+    // Moving the window can show or hide an indicator, which changes the
+    // room left for the tabs; that settles within a few rounds
+    let mut offset = current_offset;
+    for _ in 0..3 {
+        let (_, available) = window_at(offset);
+        let next = match active_idx {
+            Some(idx) => {
+                compute_tab_scroll_offset(tab_widths, idx, available, offset, TAB_SEPARATOR_WIDTH)
+            }
+            None => offset.min(total_width.saturating_sub(available)),
+        };
+        if next == offset {
+            break;
+        }
+        offset = next;
+    }
+    window_at(offset).0
+}
+
+/// Offset of the tab window moved `tabs` tabs right (left if negative)
+pub fn scroll_tab_offset(tab_widths: &[usize], current_offset: usize, tabs: isize) -> usize {
+    let starts = tab_starts(tab_widths, TAB_SEPARATOR_WIDTH);
+    let Some(first) = starts.iter().rposition(|&start| start <= current_offset) else {
+        return 0;
+    };
+    let target = first.saturating_add_signed(tabs).min(starts.len() - 1);
+    starts[target]
 }
 
 #[cfg(test)]
 mod tests {
-    use super::compute_tab_scroll_offset;
+    use super::{
+        compute_tab_scroll_offset, compute_tab_window, distinguishing_names, scroll_tab_offset,
+        TabWindow,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn same_named_files_show_their_parent_directories() {
+        let paths = [
+            Path::new("/src/editor/mod.rs"),
+            Path::new("/src/ui/mod.rs"),
+            Path::new("/src/main.rs"),
+            Path::new("/a/x/lib.rs"),
+            Path::new("/b/x/lib.rs"),
+        ];
+        assert_eq!(
+            distinguishing_names(&paths),
+            vec![
+                "editor/mod.rs",
+                "ui/mod.rs",
+                "main.rs",
+                "a/x/lib.rs",
+                "b/x/lib.rs"
+            ]
+        );
+    }
 
     #[test]
     fn offset_clamped_to_zero_when_active_first() {
@@ -80,9 +305,63 @@ mod tests {
     fn offset_respects_total_width_bounds() {
         let widths = vec![3, 3, 3, 3];
         let offset = compute_tab_scroll_offset(&widths, 3, 4, 100, 1);
-        let total: usize = widths.iter().sum();
-        let total_with_padding = total + 3; // three gaps of width 1
-        assert!(offset <= total_with_padding.saturating_sub(4));
+        // Only the last tab fits; the window starts at it rather than inside
+        // the tab before
+        assert_eq!(offset, 12);
+    }
+
+    #[test]
+    fn offset_keeps_the_window_while_the_active_tab_is_in_it() {
+        let widths = vec![4, 4, 4, 4, 4]; // tabs start at 0, 5, 10, 15, 20
+        assert_eq!(compute_tab_scroll_offset(&widths, 2, 9, 5, 1), 5);
+        assert_eq!(compute_tab_scroll_offset(&widths, 2, 9, 10, 1), 10);
+        // Inside a tab: the window starts at that tab
+        assert_eq!(compute_tab_scroll_offset(&widths, 2, 9, 7, 1), 5);
+        // The active tab is left of the window
+        assert_eq!(compute_tab_scroll_offset(&widths, 1, 9, 15, 1), 5);
+    }
+
+    #[test]
+    fn window_leaves_room_for_the_indicators() {
+        let widths = vec![4, 4, 4, 4, 4];
+        assert_eq!(
+            compute_tab_window(&widths, Some(0), 12, 0),
+            TabWindow {
+                offset: 0,
+                show_left: false,
+                show_right: true,
+            }
+        );
+        // Tab 2 (10..14) doesn't fit in 0..11 next to "›", so the window
+        // starts at tab 1, after "‹"
+        assert_eq!(
+            compute_tab_window(&widths, Some(2), 12, 0),
+            TabWindow {
+                offset: 5,
+                show_left: true,
+                show_right: true,
+            }
+        );
+        assert_eq!(
+            compute_tab_window(&widths, Some(4), 12, 0),
+            TabWindow {
+                offset: 15,
+                show_left: true,
+                show_right: false,
+            }
+        );
+        // Everything fits
+        assert_eq!(compute_tab_window(&widths, Some(4), 30, 15).offset, 0);
+    }
+
+    #[test]
+    fn scrolling_moves_the_window_by_whole_tabs() {
+        let widths = vec![4, 4, 4];
+        assert_eq!(scroll_tab_offset(&widths, 0, 1), 5);
+        assert_eq!(scroll_tab_offset(&widths, 7, 1), 10);
+        assert_eq!(scroll_tab_offset(&widths, 10, 1), 10);
+        assert_eq!(scroll_tab_offset(&widths, 5, -1), 0);
+        assert_eq!(scroll_tab_offset(&widths, 0, -1), 0);
     }
 }
 
@@ -115,36 +394,18 @@ impl TabsRenderer {
         tab_scroll_offset: usize,
         hovered_tab: Option<(BufferId, bool)>, // (buffer_id, is_close_button)
     ) -> Vec<(BufferId, u16, u16, u16)> {
-        const SCROLL_INDICATOR_LEFT: &str = "<";
-        const SCROLL_INDICATOR_RIGHT: &str = ">";
-        const SCROLL_INDICATOR_WIDTH: usize = 1; // Width of "<" or ">"
-
         let mut all_tab_spans: Vec<(Span, usize)> = Vec::new(); // Store (Span, display_width)
         let mut tab_ranges: Vec<(usize, usize, usize)> = Vec::new(); // (start, end, close_start) positions for each tab
         let mut rendered_buffer_ids: Vec<BufferId> = Vec::new(); // Track which buffers actually got rendered
 
         // First, build all spans and calculate their display widths
+        let file_names = tab_file_names(split_buffers, buffers);
         for (idx, id) in split_buffers.iter().enumerate() {
             let Some(state) = buffers.get(id) else {
                 continue;
             };
             rendered_buffer_ids.push(*id);
 
-            let name = state
-                .buffer
-                .file_path()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .or_else(|| buffer_metadata.get(id).map(|m| m.display_name.as_str()))
-                .unwrap_or("[No Name]");
-
-            let modified = if state.buffer.is_modified() { "*" } else { "" };
-            let binary_indicator = if buffer_metadata.get(id).map(|m| m.binary).unwrap_or(false) {
-                " [BIN]"
-            } else {
-                ""
-            };
-
             let is_active = *id == active_buffer;
 
             // Check hover state for this tab
@@ -186,12 +447,10 @@ impl TabsRenderer {
             };
 
             // Build tab content: " {name}{modified}{binary_indicator} "
-            let tab_name_text = format!(" {name}{modified}{binary_indicator} ");
+            let tab_name_text = tab_label(*id, state, buffer_metadata, &file_names);
             let tab_name_width = tab_name_text.chars().count();
 
-            // Close button: "× "
-            let close_text = "× ";
-            let close_width = close_text.chars().count();
+            let close_width = CLOSE_BUTTON.chars().count();
 
             let total_width = tab_name_width + close_width;
 
@@ -203,10 +462,7 @@ impl TabsRenderer {
             // Add name span
             all_tab_spans.push((Span::styled(tab_name_text, base_style), tab_name_width));
             // Add close button span (can have different style when hovered)
-            all_tab_spans.push((
-                Span::styled(close_text.to_string(), close_style),
-                close_width,
-            ));
+            all_tab_spans.push((Span::styled(CLOSE_BUTTON, close_style), close_width));
 
             // Add a small separator between tabs if it's not the last tab
             if idx < split_buffers.len() - 1 {
@@ -220,60 +476,24 @@ impl TabsRenderer {
         let mut current_spans: Vec<Span> = Vec::new();
         let max_width = area.width as usize;
 
-        let total_width: usize = all_tab_spans.iter().map(|(_, w)| w).sum();
         // Use rendered_buffer_ids (not split_buffers) to find active index,
         // since some buffers may have been skipped if not in buffers HashMap
         let active_tab_idx = rendered_buffer_ids
             .iter()
             .position(|id| *id == active_buffer);
 
-        let mut tab_widths: Vec<usize> = Vec::new();
-        for (start, end, _close_start) in &tab_ranges {
-            tab_widths.push(end.saturating_sub(*start));
-        }
-
-        let mut offset = tab_scroll_offset.min(total_width.saturating_sub(max_width));
-        if let Some(active_idx) = active_tab_idx {
-            offset = compute_tab_scroll_offset(
-                &tab_widths,
-                active_idx,
-                max_width,
-                tab_scroll_offset,
-                1, // separator width between tabs
-            );
-        }
-
-        // Indicators reserve space; adjust once so the active tab still fits.
-        let mut show_left = offset > 0;
-        let mut show_right = total_width.saturating_sub(offset) > max_width;
-        let mut available = max_width
+        let tab_widths: Vec<usize> = tab_ranges
+            .iter()
+            .map(|(start, end, _close_start)| end - start)
+            .collect();
+        let TabWindow {
+            offset,
+            show_left,
+            show_right,
+        } = compute_tab_window(&tab_widths, active_tab_idx, max_width, tab_scroll_offset);
+        let available = max_width
             .saturating_sub((show_left as usize + show_right as usize) * SCROLL_INDICATOR_WIDTH);
 
-        if let Some(active_idx) = active_tab_idx {
-            let (start, end, _close_start) = tab_ranges[active_idx];
-            let active_width = end.saturating_sub(start);
-            if start == 0 && active_width >= max_width {
-                show_left = false;
-                show_right = false;
-                available = max_width;
-            }
-
-            if end.saturating_sub(offset) > available {
-                offset = end.saturating_sub(available);
-                offset = offset.min(total_width.saturating_sub(available));
-                show_left = offset > 0;
-                show_right = total_width.saturating_sub(offset) > available;
-                available = max_width.saturating_sub(
-                    (show_left as usize + show_right as usize) * SCROLL_INDICATOR_WIDTH,
-                );
-            }
-            if start < offset {
-                offset = start;
-                show_left = offset > 0;
-                show_right = total_width.saturating_sub(offset) > available;
-            }
-        }
-
         let mut rendered_width = 0;
         let mut skip_chars_count = offset;
 
@@ -403,33 +623,4 @@ impl TabsRenderer {
 
         hit_areas
     }
-
-    /// Legacy render function for backward compatibility
-    /// Renders all buffers as tabs (used during transition)
-    #[allow(dead_code)]
-    pub fn render(
-        frame: &mut Frame,
-        area: Rect,
-        buffers: &HashMap<BufferId, EditorState>,
-        buffer_metadata: &HashMap<BufferId, BufferMetadata>,
-        active_buffer: BufferId,
-        theme: &crate::view::theme::Theme,
-    ) {
-        // Sort buffer IDs to ensure consistent tab order
-        let mut buffer_ids: Vec<_> = buffers.keys().copied().collect();
-        buffer_ids.sort_by_key(|id| id.0);
-
-        Self::render_for_split(
-            frame,
-            area,
-            &buffer_ids,
-            buffers,
-            buffer_metadata,
-            active_buffer,
-            theme,
-            true, // Legacy behavior: always treat as active
-            0,    // Default tab_scroll_offset for legacy render
-            None, // No hover state for legacy render
-        );
-    }
 }
@@ -0,0 +1,235 @@
+//! Which-key panel: the keys that can follow a pending chord
+
+use super::suggestions::truncate;
+use crate::input::keybindings::{format_keybinding, ChordContinuation, KeybindingResolver};
+use crate::view::theme::Theme;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// Widest an entry's column gets, including the key
+const MAX_COLUMN_WIDTH: usize = 40;
+/// Space between columns
+const COLUMN_GAP: usize = 2;
+/// Most rows of entries shown
+const MAX_ROWS: usize = 10;
+/// Separates a key from what it runs
+const ARROW: &str = " → ";
+
+/// One key in the panel, ready to display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhichKeyEntry {
+    /// The key, e.g. "Ctrl+W"
+    pub key: String,
+    /// What it runs, or "+prefix" for keys that lead to longer chords
+    pub description: String,
+    /// Whether the key only leads to longer chords
+    pub is_prefix: bool,
+}
+
+impl WhichKeyEntry {
+    /// Describe a chord continuation
+    pub fn from_continuation(continuation: &ChordContinuation) -> Self {
+        let (code, modifiers) = &continuation.key;
+        Self {
+            key: format_keybinding(code, modifiers),
+            description: continuation
+                .action
+                .as_ref()
+                .map(KeybindingResolver::format_action)
+                .unwrap_or_else(|| "+prefix".to_string()),
+            is_prefix: continuation.action.is_none(),
+        }
+    }
+
+    /// Width of "key → description" in columns
+    fn width(&self) -> usize {
+        self.key.chars().count() + ARROW.chars().count() + self.description.chars().count()
+    }
+}
+
+/// Position of the panel and how its entries are laid out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhichKeyLayout {
+    /// The whole panel, including borders
+    pub area: Rect,
+    /// Width of each column of entries
+    pub column_width: usize,
+    /// Entries shown in each column, top to bottom
+    pub columns: Vec<Vec<WhichKeyEntry>>,
+    /// Entries that didn't fit
+    pub hidden: usize,
+}
+
+/// Renders the which-key panel
+pub struct WhichKeyRenderer;
+
+impl WhichKeyRenderer {
+    /// Compute the panel, full width along the bottom of `area`
+    ///
+    /// Returns None if there is nothing to show or no room for it.
+    pub fn layout(entries: &[WhichKeyEntry], area: Rect) -> Option<WhichKeyLayout> {
+        // Borders plus at least one row of entries
+        if entries.is_empty() || area.height < 3 || area.width < 10 {
+            return None;
+        }
+
+        let inner_width = area.width.saturating_sub(4) as usize;
+        let column_width = entries
+            .iter()
+            .map(WhichKeyEntry::width)
+            .max()
+            .unwrap_or(0)
+            .min(MAX_COLUMN_WIDTH)
+            .min(inner_width);
+        let column_count = ((inner_width + COLUMN_GAP) / (column_width + COLUMN_GAP)).max(1);
+
+        // Keep the rows for the entries and, if some don't fit, a summary line
+        let max_rows = (area.height as usize - 2).min(MAX_ROWS);
+        let needed_rows = entries.len().div_ceil(column_count);
+        let (rows, shown) = if needed_rows <= max_rows {
+            (needed_rows, entries.len())
+        } else if max_rows > 1 {
+            (max_rows - 1, (max_rows - 1) * column_count)
+        } else {
+            (1, column_count)
+        };
+        let hidden = entries.len() - shown;
+
+        let columns = entries[..shown]
+            .chunks(rows)
+            .map(|column| column.to_vec())
+            .collect();
+
+        let height = (rows + usize::from(hidden > 0 && max_rows > 1) + 2) as u16;
+        Some(WhichKeyLayout {
+            area: Rect {
+                x: area.x,
+                y: area.bottom() - height,
+                width: area.width,
+                height,
+            },
+            column_width,
+            columns,
+            hidden,
+        })
+    }
+
+    /// Render the panel for the pending `chord` over the bottom of `area`
+    pub fn render(
+        frame: &mut Frame,
+        area: Rect,
+        chord: &[(KeyCode, KeyModifiers)],
+        entries: &[WhichKeyEntry],
+        theme: &Theme,
+    ) {
+        let Some(layout) = Self::layout(entries, area) else {
+            return;
+        };
+        let base = Style::default().fg(theme.popup_text_fg).bg(theme.popup_bg);
+        let key_style = base.fg(theme.help_key_fg).add_modifier(Modifier::BOLD);
+        let prefix_style = base.fg(theme.line_number_fg);
+
+        let title = chord
+            .iter()
+            .map(|(code, modifiers)| format_keybinding(code, modifiers))
+            .collect::<Vec<_>>()
+            .join(" ");
+        frame.render_widget(Clear, layout.area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(theme.popup_border_fg)
+                    .bg(theme.popup_bg),
+            )
+            .style(base)
+            .title(Span::styled(
+                format!(" {} ", title),
+                base.add_modifier(Modifier::BOLD),
+            ));
+
+        let rows = layout.columns.first().map_or(0, Vec::len);
+        let mut text: Vec<Line> = (0..rows)
+            .map(|row| {
+                let mut spans = vec![Span::styled(" ", base)];
+                for column in &layout.columns {
+                    let Some(entry) = column.get(row) else {
+                        continue;
+                    };
+                    let key_width = entry.key.chars().count();
+                    let room = layout
+                        .column_width
+                        .saturating_sub(key_width + ARROW.chars().count());
+                    let description = truncate(&entry.description, room);
+                    let padding = layout.column_width.saturating_sub(
+                        key_width + ARROW.chars().count() + description.chars().count(),
+                    ) + COLUMN_GAP;
+                    spans.push(Span::styled(entry.key.clone(), key_style));
+                    spans.push(Span::styled(ARROW, base));
+                    spans.push(Span::styled(
+                        description,
+                        if entry.is_prefix { prefix_style } else { base },
+                    ));
+                    spans.push(Span::styled(" ".repeat(padding), base));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        if layout.hidden > 0 && layout.area.height as usize > rows + 2 {
+            text.push(Line::from(Span::styled(
+                format!(" … {} more not shown", layout.hidden),
+                prefix_style,
+            )));
+        }
+        frame.render_widget(Paragraph::new(text).block(block), layout.area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(count: usize) -> Vec<WhichKeyEntry> {
+        (0..count)
+            .map(|i| WhichKeyEntry {
+                key: format!("Ctrl+{}", i),
+                description: "Close buffer".to_string(),
+                is_prefix: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_layout_fills_columns_along_the_bottom() {
+        let area = Rect::new(0, 1, 80, 20);
+        let layout = WhichKeyRenderer::layout(&entries(5), area).unwrap();
+
+        // "Ctrl+0 → Close buffer" fits three times in 76 columns
+        assert_eq!(layout.column_width, 21);
+        assert_eq!(layout.columns.len(), 3);
+        assert_eq!(layout.columns[0].len(), 2);
+        assert_eq!(layout.hidden, 0);
+        assert_eq!(layout.area.height, 4);
+        assert_eq!(layout.area.bottom(), area.bottom());
+        assert_eq!(layout.area.width, area.width);
+    }
+
+    #[test]
+    fn test_layout_summarizes_entries_that_dont_fit() {
+        let area = Rect::new(0, 0, 30, 6);
+        let layout = WhichKeyRenderer::layout(&entries(10), area).unwrap();
+
+        // One column, four rows: three entries and the summary line
+        assert_eq!(layout.columns.len(), 1);
+        assert_eq!(layout.columns[0].len(), 3);
+        assert_eq!(layout.hidden, 7);
+        assert_eq!(layout.area.height, 6);
+
+        assert!(WhichKeyRenderer::layout(&entries(10), Rect::new(0, 0, 30, 2)).is_none());
+        assert!(WhichKeyRenderer::layout(&[], area).is_none());
+    }
+}
@@ -34,6 +34,14 @@ pub struct Viewport {
     /// When true, horizontal scrolling is disabled
     pub line_wrap_enabled: bool,
 
+    /// Whether the cursor's line is kept in the middle of the viewport
+    /// (`editor.typewriter_scrolling`)
+    pub typewriter: bool,
+
+    /// Whether the last line may scroll up to the top of the viewport
+    /// (`editor.scroll_past_end`)
+    pub scroll_past_end: bool,
+
     /// Whether viewport needs synchronization with cursor positions
     /// When true, ensure_visible needs to be called before rendering
     /// This allows batching multiple cursor movements into a single viewport update
@@ -57,6 +65,8 @@ impl Viewport {
             scroll_offset: 3,
             horizontal_scroll_offset: 5,
             line_wrap_enabled: false,
+            typewriter: false,
+            scroll_past_end: false,
             needs_sync: false,
             skip_resize_sync: false,
         }
@@ -75,6 +85,16 @@ impl Viewport {
         skip
     }
 
+    /// Rows the last screen of the buffer shows at least: the whole viewport,
+    /// or just the last line when scrolling past the end
+    fn last_top_rows(&self, viewport_height: usize) -> usize {
+        if self.scroll_past_end {
+            1
+        } else {
+            viewport_height
+        }
+    }
+
     /// Set the scroll offset
     pub fn set_scroll_offset(&mut self, offset: usize) {
         self.scroll_offset = offset;
@@ -86,6 +106,119 @@ impl Viewport {
         self.height = height;
     }
 
+    /// Update dimensions, keeping the cursor on the same screen row
+    ///
+    /// Wrapped lines reflow for the new width. The cursor moves up only when
+    /// the new height can't fit it at its old row, and the top is clamped so a
+    /// taller viewport doesn't show empty rows past the end of the buffer. A
+    /// cursor that was scrolled out of view stays out of view.
+    pub fn resize_keeping_cursor(
+        &mut self,
+        buffer: &mut Buffer,
+        cursor: &Cursor,
+        width: u16,
+        height: u16,
+    ) {
+        let cursor_row = self.cursor_row(buffer, cursor);
+        self.resize(width, height);
+
+        let previous_top = self.top_byte;
+        let top_byte = match cursor_row {
+            Some(row) => {
+                let row = row.min(self.visible_line_count().saturating_sub(1));
+                self.top_byte_for_cursor_row(buffer, cursor, row)
+            }
+            None => self.top_byte,
+        };
+        self.set_top_byte_with_limit(buffer, top_byte);
+        if self.top_byte != previous_top {
+            self.top_view_line_offset = 0;
+        }
+
+        if cursor_row.is_some() {
+            self.ensure_cursor_column_visible(buffer, cursor);
+        } else if self.line_wrap_enabled {
+            self.left_column = 0;
+        }
+    }
+
+    /// How lines wrap at the current width (None without line wrapping)
+    fn wrap_config(&self, buffer: &Buffer) -> Option<WrapConfig> {
+        self.line_wrap_enabled
+            .then(|| WrapConfig::new(self.width as usize, self.gutter_width(buffer), true))
+    }
+
+    /// Screen rows a line takes
+    fn line_rows(line: &str, wrap_config: Option<&WrapConfig>) -> usize {
+        match wrap_config {
+            Some(config) => wrap_line(line.trim_end_matches('\n'), config).len().max(1),
+            None => 1,
+        }
+    }
+
+    /// Rows between the start of the cursor's line and the cursor
+    fn cursor_segment(
+        buffer: &mut Buffer,
+        cursor: &Cursor,
+        line_start: usize,
+        wrap_config: Option<&WrapConfig>,
+    ) -> usize {
+        let Some(config) = wrap_config else {
+            return 0;
+        };
+        let line = buffer
+            .line_iterator(line_start, 80)
+            .next()
+            .map(|(_, content)| content)
+            .unwrap_or_default();
+        let segments = wrap_line(line.trim_end_matches('\n'), config);
+        char_position_to_segment(cursor.position.saturating_sub(line_start), &segments).0
+    }
+
+    /// Screen row of the cursor, if it is in view
+    fn cursor_row(&self, buffer: &mut Buffer, cursor: &Cursor) -> Option<usize> {
+        let wrap_config = self.wrap_config(buffer);
+        let line_start = buffer.line_iterator(cursor.position, 80).current_position();
+        if line_start < self.top_byte {
+            return None;
+        }
+
+        let visible_rows = self.visible_line_count();
+        let mut row = 0;
+        let mut iter = buffer.line_iterator(self.top_byte, 80);
+        while iter.current_position() < line_start {
+            let (_, line) = iter.next()?;
+            row += Self::line_rows(&line, wrap_config.as_ref());
+            if row >= visible_rows {
+                return None;
+            }
+        }
+        row += Self::cursor_segment(buffer, cursor, line_start, wrap_config.as_ref());
+        (row < visible_rows).then_some(row)
+    }
+
+    /// Top byte that puts the cursor on the given screen row, or as close
+    /// below it as whole lines allow
+    fn top_byte_for_cursor_row(&self, buffer: &mut Buffer, cursor: &Cursor, row: usize) -> usize {
+        let wrap_config = self.wrap_config(buffer);
+        let line_start = buffer.line_iterator(cursor.position, 80).current_position();
+        let mut rows_above = Self::cursor_segment(buffer, cursor, line_start, wrap_config.as_ref());
+
+        let mut top = line_start;
+        let mut iter = buffer.line_iterator(line_start, 80);
+        while rows_above < row {
+            let Some((start, line)) = iter.prev() else {
+                break;
+            };
+            rows_above += Self::line_rows(&line, wrap_config.as_ref());
+            if rows_above > row {
+                break;
+            }
+            top = start;
+        }
+        top
+    }
+
     /// Get the number of visible lines
     pub fn visible_line_count(&self) -> usize {
         self.height as usize
@@ -166,7 +299,9 @@ impl Viewport {
         };
 
         // Apply scroll limit: don't scroll past the point where viewport can't be filled
-        let max_top_idx = view_lines.len().saturating_sub(viewport_height);
+        let max_top_idx = view_lines
+            .len()
+            .saturating_sub(self.last_top_rows(viewport_height));
         let clamped_idx = target_idx.min(max_top_idx);
 
         // Get the source byte for the target view line
@@ -274,7 +409,9 @@ impl Viewport {
             };
 
             // Apply scroll limit
-            let max_top = view_lines.len().saturating_sub(viewport_height);
+            let max_top = view_lines
+                .len()
+                .saturating_sub(self.last_top_rows(viewport_height));
             let new_offset = target_top.min(max_top);
 
             tracing::trace!(
@@ -402,6 +539,10 @@ impl Viewport {
             self.top_byte = 0;
             return;
         }
+        if self.scroll_past_end {
+            self.top_byte = proposed_top_byte.min(buffer_len);
+            return;
+        }
 
         // Try to iterate viewport_height lines from proposed_top_byte
         // If we can't reach viewport_height lines before hitting EOF,
@@ -521,6 +662,23 @@ impl Viewport {
         self.set_top_byte_with_limit(buffer, target_position);
     }
 
+    /// Keep showing the same text after an edit made elsewhere (e.g. in
+    /// another split showing this buffer): `top_byte` moves along with the
+    /// text before it. Returns true if the edit removed the text `top_byte`
+    /// was in, leaving it at the edit's position, maybe mid-line.
+    pub fn adjust_for_edit(&mut self, edit_pos: usize, old_len: usize, new_len: usize) -> bool {
+        if edit_pos >= self.top_byte {
+            return false;
+        }
+        if edit_pos + old_len <= self.top_byte {
+            self.top_byte = self.top_byte - old_len + new_len;
+            false
+        } else {
+            self.top_byte = edit_pos;
+            true
+        }
+    }
+
     /// Mark viewport as needing synchronization with cursor positions
     /// This defers the actual viewport update until sync_with_cursor is called
     pub fn mark_needs_sync(&mut self) {
@@ -582,8 +740,8 @@ impl Viewport {
         // Apply scroll_offset to keep cursor away from edges
         let effective_offset = self.scroll_offset.min(viewport_lines / 2);
 
-        let cursor_is_visible = if cursor_line_start < self.top_byte {
-            // Cursor is above viewport
+        let cursor_is_visible = if self.typewriter || cursor_line_start < self.top_byte {
+            // Cursor is above viewport, or is re-centered on every move
             false
         } else {
             if self.line_wrap_enabled {
@@ -754,9 +912,15 @@ impl Viewport {
             }
         }
 
+        self.ensure_cursor_column_visible(buffer, cursor);
+    }
+
+    /// Scroll horizontally so the cursor's column is visible
+    fn ensure_cursor_column_visible(&mut self, buffer: &mut Buffer, cursor: &Cursor) {
         // Horizontal scrolling - skip if line wrapping is enabled
         // When wrapping is enabled, all columns are always visible via wrapping
         if !self.line_wrap_enabled {
+            let cursor_line_start = buffer.line_iterator(cursor.position, 80).current_position();
             let cursor_column = cursor.position.saturating_sub(cursor_line_start);
 
             // Get the line content to know its length (for limiting horizontal scroll)
@@ -1212,4 +1376,24 @@ mod tests {
             lines_from_top
         );
     }
+
+    #[test]
+    fn test_adjust_for_edit_keeps_top_text() {
+        let mut vp = Viewport::new(80, 24);
+        vp.top_byte = 100;
+
+        // Edits after the top don't move it
+        assert!(!vp.adjust_for_edit(100, 0, 5));
+        assert_eq!(vp.top_byte, 100);
+
+        // Text inserted or deleted before it does
+        assert!(!vp.adjust_for_edit(10, 0, 7));
+        assert_eq!(vp.top_byte, 107);
+        assert!(!vp.adjust_for_edit(0, 7, 0));
+        assert_eq!(vp.top_byte, 100);
+
+        // Deleting across it leaves it where the deletion was
+        assert!(vp.adjust_for_edit(90, 20, 0));
+        assert_eq!(vp.top_byte, 90);
+    }
 }
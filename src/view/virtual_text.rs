@@ -242,6 +242,25 @@ impl VirtualTextManager {
         id
     }
 
+    /// Add an inline virtual text (BeforeChar or AfterChar) in a namespace
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_in_namespace(
+        &mut self,
+        marker_list: &mut MarkerList,
+        position: usize,
+        text: String,
+        style: Style,
+        vtext_position: VirtualTextPosition,
+        namespace: VirtualTextNamespace,
+        priority: i32,
+    ) -> VirtualTextId {
+        let id = self.add(marker_list, position, text, style, vtext_position, priority);
+        if let Some(vtext) = self.texts.get_mut(&id) {
+            vtext.namespace = Some(namespace);
+        }
+        id
+    }
+
     /// Remove a virtual text entry by its string identifier
     pub fn remove_by_id(&mut self, marker_list: &mut MarkerList, string_id: &str) -> bool {
         // Find the entry with matching string_id
@@ -408,6 +427,37 @@ impl VirtualTextManager {
         }
     }
 
+    /// Clear the virtual texts of a namespace anchored in a byte range
+    ///
+    /// Lets a plugin refresh part of the buffer (e.g. the lines that changed)
+    /// without rebuilding its other virtual texts.
+    pub fn clear_namespace_in_range(
+        &mut self,
+        marker_list: &mut MarkerList,
+        namespace: &VirtualTextNamespace,
+        start: usize,
+        end: usize,
+    ) {
+        let to_remove: Vec<VirtualTextId> = self
+            .texts
+            .iter()
+            .filter_map(|(id, vtext)| {
+                let pos = marker_list.get_position(vtext.marker_id)?;
+                if vtext.namespace.as_ref() == Some(namespace) && pos >= start && pos < end {
+                    Some(*id)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for id in to_remove {
+            if let Some(vtext) = self.texts.remove(&id) {
+                marker_list.delete(vtext.marker_id);
+            }
+        }
+    }
+
     /// Query only virtual LINES (LineAbove/LineBelow) in a byte range
     ///
     /// Used by the render pipeline to inject header/footer lines.
@@ -837,4 +887,36 @@ mod tests {
         assert_eq!(before.unwrap().text, "/*param=*/");
         assert_eq!(after.unwrap().text, ": Type");
     }
+
+    #[test]
+    fn test_clear_namespace_in_range() {
+        let mut marker_list = MarkerList::new();
+        let mut manager = VirtualTextManager::new();
+        let lint = VirtualTextNamespace::from_string("lint".to_string());
+        let tests = VirtualTextNamespace::from_string("tests".to_string());
+
+        for (position, namespace) in [(5, &lint), (15, &lint), (5, &tests)] {
+            manager.add_in_namespace(
+                &mut marker_list,
+                position,
+                "!".to_string(),
+                hint_style(),
+                VirtualTextPosition::AfterChar,
+                namespace.clone(),
+                0,
+            );
+        }
+
+        manager.clear_namespace_in_range(&mut marker_list, &lint, 0, 10);
+
+        // Only the lint text at 5 is gone
+        let remaining = manager.query_range(&marker_list, 0, 20);
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining
+            .iter()
+            .any(|(pos, vt)| *pos == 15 && vt.namespace.as_ref() == Some(&lint)));
+        assert!(remaining
+            .iter()
+            .any(|(pos, vt)| *pos == 5 && vt.namespace.as_ref() == Some(&tests)));
+    }
 }
@@ -359,6 +359,18 @@ impl EditorTestHarness {
         Ok(())
     }
 
+    /// Open the command palette and type `name` into it
+    pub fn type_command(&mut self, name: &str) -> io::Result<()> {
+        self.send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)?;
+        self.type_text(name)
+    }
+
+    /// Run a command from the command palette by name
+    pub fn run_command(&mut self, name: &str) -> io::Result<()> {
+        self.type_command(name)?;
+        self.send_key(KeyCode::Enter, KeyModifiers::NONE)
+    }
+
     /// Simulate a key press
     pub fn send_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> io::Result<()> {
         // Update shadow string to mirror the operation (only if validation is enabled)
@@ -515,9 +527,20 @@ impl EditorTestHarness {
 
     /// Force a render cycle and capture output
     pub fn render(&mut self) -> io::Result<()> {
+        // Like the event loop: pick up work that waits for input to pause
+        self.editor.update_search_match_count();
+        self.editor.update_suggestion_preview();
         self.terminal.draw(|frame| {
             self.editor.render(frame);
         })?;
+        self.editor.frame_flushed();
+        // Like the event loop once idle: fill in highlighting that was put off
+        if self.editor.highlight_deferred_regions() {
+            self.terminal.draw(|frame| {
+                self.editor.render(frame);
+            })?;
+            self.editor.frame_flushed();
+        }
         Ok(())
     }
 
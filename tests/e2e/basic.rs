@@ -100,30 +100,30 @@ fn test_buffer_switching() {
     harness.assert_screen_contains("alpha.txt");
     harness.assert_screen_contains("beta.txt");
 
-    // Switch to previous buffer (alpha) using Ctrl+PageUp
+    // Switch to previous buffer (alpha) using Alt+PageUp
     harness
-        .send_key(KeyCode::PageUp, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageUp, KeyModifiers::ALT)
         .unwrap();
     harness.render().unwrap();
     harness.assert_buffer_content("Content of alpha");
 
-    // Switch to next buffer (beta) using Ctrl+PageDown
+    // Switch to next buffer (beta) using Alt+PageDown
     harness
-        .send_key(KeyCode::PageDown, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageDown, KeyModifiers::ALT)
         .unwrap();
     harness.render().unwrap();
     harness.assert_buffer_content("Content of beta");
 
     // Test cycling: next from beta should go to alpha
     harness
-        .send_key(KeyCode::PageDown, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageDown, KeyModifiers::ALT)
         .unwrap();
     harness.render().unwrap();
     harness.assert_buffer_content("Content of alpha");
 
     // Test cycling backwards: prev from alpha should go to beta
     harness
-        .send_key(KeyCode::PageUp, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageUp, KeyModifiers::ALT)
         .unwrap();
     harness.render().unwrap();
     harness.assert_buffer_content("Content of beta");
@@ -352,3 +352,55 @@ fn test_append_at_end_of_file() {
 
     harness.assert_buffer_content("Line 1\nLine 2\nLine 3!!\nLine 4");
 }
+
+/// Errors stay in the status bar next to newer messages until dismissed
+#[test]
+fn test_status_error_stays_until_dismissed() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+
+    harness
+        .editor_mut()
+        .set_status_error("Error saving file: disk full".to_string());
+    harness
+        .editor_mut()
+        .set_status_message("Copied".to_string());
+    harness.render().unwrap();
+
+    // The newer message doesn't overwrite the error
+    let status = harness.get_status_bar();
+    assert!(status.contains("Copied"), "Status bar: {}", status);
+    assert!(status.contains("disk full"), "Status bar: {}", status);
+
+    // The error is drawn with the theme's error colors
+    let row = harness.terminal_height() as u16 - 2; // status bar row
+    let col = status.find("Error saving").unwrap();
+    let col = status[..col].chars().count() as u16;
+    let style = harness.get_cell_style(col, row).unwrap();
+    assert_eq!(style.bg, Some(harness.editor().theme().status_error_bg));
+
+    // Escape (with no cursors or selection to cancel) dismisses it
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    let status = harness.get_status_bar();
+    assert!(!status.contains("disk full"), "Status bar: {}", status);
+    assert!(status.contains("Copied"), "Status bar: {}", status);
+}
+
+/// Info messages go away after the configured duration
+#[test]
+fn test_status_message_expires() {
+    let mut config = fresh::config::Config::default();
+    config.editor.status_message_duration_ms = 50;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+
+    harness
+        .editor_mut()
+        .set_status_message("Copied".to_string());
+    harness.render().unwrap();
+    assert!(harness.get_status_bar().contains("Copied"));
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    harness.render().unwrap();
+    assert!(!harness.get_status_bar().contains("Copied"));
+}
@@ -3,6 +3,7 @@
 use crate::common::fixtures::TestFixture;
 use crate::common::harness::EditorTestHarness;
 use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
 
 /// Test that saving an unnamed buffer triggers SaveAs prompt (fix for issue #154)
 #[test]
@@ -69,7 +70,7 @@ fn test_quit_without_modified_buffers() {
     );
 }
 
-/// Test that quitting with confirmation (y) works
+/// Test that quitting with confirmation (discard all) works
 #[test]
 fn test_quit_with_confirmation_yes() {
     let mut harness = EditorTestHarness::new(80, 24).unwrap();
@@ -84,12 +85,9 @@ fn test_quit_with_confirmation_yes() {
         .unwrap();
     harness.render().unwrap();
 
-    // Confirm with 'y' and Enter
+    // Discard all changes with 'd'
     harness
-        .send_key(KeyCode::Char('y'), KeyModifiers::NONE)
-        .unwrap();
-    harness
-        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .send_key(KeyCode::Char('d'), KeyModifiers::NONE)
         .unwrap();
     harness.render().unwrap();
 
@@ -97,7 +95,7 @@ fn test_quit_with_confirmation_yes() {
     assert!(harness.should_quit(), "Editor should quit after confirming");
 }
 
-/// Test that quitting with confirmation (n) cancels quit
+/// Test that cancelling the quit confirmation keeps the editor open
 #[test]
 fn test_quit_with_confirmation_no() {
     let mut harness = EditorTestHarness::new(80, 24).unwrap();
@@ -112,12 +110,9 @@ fn test_quit_with_confirmation_no() {
         .unwrap();
     harness.render().unwrap();
 
-    // Cancel with 'n' and Enter
-    harness
-        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
-        .unwrap();
+    // Cancel with 'c'
     harness
-        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .send_key(KeyCode::Char('c'), KeyModifiers::NONE)
         .unwrap();
     harness.render().unwrap();
 
@@ -320,8 +315,11 @@ fn test_click_tab_close_button_modified_buffer() {
                 .unwrap();
             harness.render().unwrap();
 
-            // Should show confirmation prompt for modified buffer
-            harness.assert_screen_contains("modified. (s)ave, (d)iscard, (C)ancel");
+            // Should show confirmation dialog for modified buffer
+            harness.assert_screen_contains("has unsaved changes. Save before closing?");
+            harness.assert_screen_contains("[ Save ]");
+            harness.assert_screen_contains("[ Discard ]");
+            harness.assert_screen_contains("[ Cancel ]");
         } else {
             panic!("Could not find × close button after * in tab bar");
         }
@@ -360,16 +358,13 @@ fn test_click_tab_close_modified_discard() {
                 .unwrap();
             harness.render().unwrap();
 
-            // Should show prompt
-            harness.assert_screen_contains("modified. (s)ave, (d)iscard, (C)ancel");
+            // Should show dialog
+            harness.assert_screen_contains("Save before closing?");
 
-            // Press 'd' to discard and Enter to confirm
+            // Press 'd' to discard
             harness
                 .send_key(KeyCode::Char('d'), KeyModifiers::NONE)
                 .unwrap();
-            harness
-                .send_key(KeyCode::Enter, KeyModifiers::NONE)
-                .unwrap();
             harness.render().unwrap();
 
             // Should show discarded message
@@ -412,16 +407,13 @@ fn test_click_tab_close_modified_cancel() {
                 .unwrap();
             harness.render().unwrap();
 
-            // Should show prompt
-            harness.assert_screen_contains("modified. (s)ave, (d)iscard, (C)ancel");
+            // Should show dialog
+            harness.assert_screen_contains("Save before closing?");
 
-            // Press 'c' to cancel and Enter to confirm
+            // Press 'c' to cancel
             harness
                 .send_key(KeyCode::Char('c'), KeyModifiers::NONE)
                 .unwrap();
-            harness
-                .send_key(KeyCode::Enter, KeyModifiers::NONE)
-                .unwrap();
             harness.render().unwrap();
 
             // Should show cancelled message
@@ -435,3 +427,68 @@ fn test_click_tab_close_modified_cancel() {
         panic!("Could not find * modified indicator in tab bar");
     }
 }
+
+/// Test that a scratch buffer's text doesn't hold up quitting
+#[test]
+fn test_quit_with_modified_scratch_buffer() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    harness.editor_mut().new_scratch_buffer("rs");
+    harness.type_text("fn main() {}").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("*scratch*");
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert!(
+        harness.should_quit(),
+        "Editor should quit without asking about scratch buffers"
+    );
+}
+
+/// Test that closing a scratch buffer discards its text without asking
+#[test]
+fn test_close_modified_scratch_buffer() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    let first = harness.editor_mut().new_scratch_buffer("");
+    harness.type_text("notes").unwrap();
+    let second = harness.editor_mut().new_scratch_buffer("");
+    harness.render().unwrap();
+    harness.assert_screen_contains("*scratch 2*");
+
+    harness.editor_mut().close_buffer(second).unwrap();
+    harness.editor_mut().close_buffer(first).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("*scratch");
+}
+
+/// Test that saves are recorded in the audit log and listed by "Show Save History"
+#[test]
+fn test_save_history_lists_audited_saves() {
+    let fixture = TestFixture::new("test.txt", "initial").unwrap();
+    let audit_path = fixture.path.with_file_name("audit.jsonl");
+    let mut config = Config::default();
+    config.editor.audit_log = true;
+    config.editor.audit_log_path = Some(audit_path.clone());
+    let mut harness = EditorTestHarness::with_config(100, 24, config).unwrap();
+    harness.open_file(&fixture.path).unwrap();
+
+    for text in ["X", "YZ"] {
+        harness.type_text(text).unwrap();
+        harness
+            .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+            .unwrap();
+    }
+    let audit = std::fs::read_to_string(&audit_path).unwrap();
+    assert_eq!(audit.lines().count(), 2);
+
+    harness.editor_mut().show_save_history();
+    harness.render().unwrap();
+    harness.assert_screen_contains("*Save History*");
+    harness.assert_screen_contains("+2 bytes (8 -> 10)");
+    harness.assert_screen_contains("+1 bytes (7 -> 8)");
+}
@@ -501,8 +501,9 @@ fn test_command_palette_from_file_explorer() {
     // Should show the command palette
     harness.assert_screen_contains("Command:");
 
-    // Should show commands
-    harness.assert_screen_contains("Open File");
+    // Editor commands stay available beneath the explorer, so they are listed
+    // first instead of being sorted to the end as disabled
+    harness.assert_screen_contains("Add Cursor Above");
 
     // Should be able to execute a command
     harness.type_text("toggle hidden").unwrap();
@@ -674,6 +675,31 @@ fn test_command_palette_pagedown_no_wraparound() {
     harness.assert_screen_not_contains("Unknown command");
 }
 
+/// Test that Ctrl+End jumps to the last suggestion
+#[test]
+fn test_command_palette_select_last() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    // Trigger the command palette
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    // Filter to get only two commands ("Save File" and "Save File As")
+    harness.type_text("save f").unwrap();
+    harness.render().unwrap();
+
+    // Jump to the last suggestion and accept it
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("Command: Save File As");
+}
+
 /// Test that keyboard shortcuts are displayed in the command palette
 #[test]
 fn test_command_palette_shows_shortcuts() {
@@ -876,3 +902,148 @@ fn test_show_keyboard_shortcuts_open_close_reopen() {
         screen
     );
 }
+
+/// Test Up on an empty palette input recalls the mode's history, and
+/// "Repeat Last Palette Query" reopens the last mode with its query
+#[test]
+fn test_command_palette_history_and_repeat() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("one").unwrap();
+
+    // Run "Go to Line" from the palette and go to line 1
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Go to Line").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("1").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    // Up on the empty palette input recalls the last command
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.send_key(KeyCode::Up, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert!(
+        harness.get_prompt_line().contains("Command: Go to Line"),
+        "Prompt: {}",
+        harness.get_prompt_line()
+    );
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+
+    // Repeating reopens the go to line prompt with "1"
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Repeat Last Palette Query").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(
+        harness.get_prompt_line().contains("Go to line: 1"),
+        "Prompt: {}",
+        harness.get_prompt_line()
+    );
+}
+
+/// Test typing a command's argument after its name in the palette
+#[test]
+fn test_command_palette_command_arguments() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("one\ntwo\nthree").unwrap();
+
+    // "Go to Line 2" jumps straight to the line
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Go to Line 2").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(harness.cursor_position(), 4);
+
+    // Accepting "Select Theme" with Tab moves on to completing the theme
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Select Theme").unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.type_text("light").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Select Theme light");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(harness.editor().theme().name, "light");
+}
+
+/// Test binding a chord to a command with "Bind Key" for the session
+#[test]
+fn test_bind_key_for_session() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Bind Key").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    // Keys are captured as they are; Backspace takes the last one back
+    harness
+        .send_key(
+            KeyCode::Char('j'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        )
+        .unwrap();
+    harness
+        .send_key(KeyCode::Backspace, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('k'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('x'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("ctrl+k x");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness.type_text("Select All").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("This Session Only");
+    // Keep it for this session only
+    harness
+        .send_key(KeyCode::Char('t'), KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('k'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('x'), KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(
+        harness
+            .editor()
+            .active_state()
+            .cursors
+            .primary()
+            .selection_range(),
+        Some(0..5)
+    );
+}
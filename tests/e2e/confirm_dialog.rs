@@ -0,0 +1,329 @@
+// End-to-end tests for modal confirmation dialogs
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use tempfile::TempDir;
+
+/// Find the screen position (col, row) of some text
+fn find_on_screen(harness: &EditorTestHarness, text: &str) -> Option<(u16, u16)> {
+    harness
+        .screen_to_string()
+        .lines()
+        .enumerate()
+        .find_map(|(row, line)| {
+            line.find(text)
+                .map(|byte| (line[..byte].chars().count() as u16, row as u16))
+        })
+}
+
+/// The dialog takes every key until it is answered
+#[test]
+fn test_confirm_dialog_traps_focus() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("1 buffer has unsaved changes:");
+    harness.assert_screen_contains("[ Discard All ]");
+    harness.assert_screen_contains("[ Cancel ]");
+
+    // Typing doesn't reach the buffer, and unbound keys leave the dialog open
+    harness.type_text("xz").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "hello");
+    assert!(harness.editor().confirm_dialog().is_some());
+
+    // Escape cancels
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert!(harness.editor().confirm_dialog().is_none());
+    assert!(!harness.should_quit());
+    harness.assert_screen_contains("Quit cancelled");
+    harness.assert_screen_not_contains("[ Discard All ]");
+}
+
+/// Tab/arrow keys move focus between buttons and Enter presses the focused one
+#[test]
+fn test_confirm_dialog_keyboard_focus() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    // "Cancel" is focused, so the destructive choices aren't one key away
+    assert_eq!(harness.editor().confirm_dialog().unwrap().focused, 3);
+
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.editor().confirm_dialog().unwrap().focused, 0);
+    harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.editor().confirm_dialog().unwrap().focused, 3);
+    harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.editor().confirm_dialog().unwrap().focused, 1);
+
+    // Enter presses "Discard All"
+
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert!(harness.should_quit());
+}
+
+/// Clicking a button presses it; clicks elsewhere are ignored
+#[test]
+fn test_confirm_dialog_mouse() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+
+    // Click outside the dialog
+    harness.mouse_click(1, 3).unwrap();
+    assert!(harness.editor().confirm_dialog().is_some());
+
+    let (col, row) = find_on_screen(&harness, "[ Cancel ]").unwrap();
+    harness.mouse_click(col + 2, row).unwrap();
+    assert!(harness.editor().confirm_dialog().is_none());
+    assert!(!harness.should_quit());
+}
+
+/// Replace-all shows how many occurrences will change before changing them
+#[test]
+fn test_replace_all_asks_for_confirmation() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.txt");
+    fs::write(&file_path, "foo bar foo baz foo").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("foo").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("qux").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("Replace 3 occurrences of 'foo' with 'qux'?");
+    assert_eq!(harness.get_buffer_content().unwrap(), "foo bar foo baz foo");
+
+    // "Replace All" is focused
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "qux bar qux baz qux");
+    harness.assert_screen_contains("Replaced 3 occ");
+}
+
+/// Cancelling replace-all leaves the buffer alone
+#[test]
+fn test_replace_all_cancel() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.txt");
+    fs::write(&file_path, "foo bar foo").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("foo").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("qux").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('c'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "foo bar foo");
+    harness.assert_screen_contains("Replace cancelled");
+}
+
+/// "Save As" onto another existing file asks before overwriting it
+#[test]
+fn test_save_as_existing_file_asks_to_overwrite() {
+    let temp_dir = TempDir::new().unwrap();
+    let existing = temp_dir.path().join("existing.txt");
+    fs::write(&existing, "keep me").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("new content").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(existing.to_str().unwrap()).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Overwrite it?");
+
+    // Cancel keeps the file
+    harness
+        .send_key(KeyCode::Char('c'), KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(fs::read_to_string(&existing).unwrap(), "keep me");
+
+    // Try again and overwrite
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(existing.to_str().unwrap()).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('o'), KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(fs::read_to_string(&existing).unwrap(), "new content");
+}
+
+/// Deleting from the file explorer asks first
+#[test]
+fn test_file_explorer_delete_asks_for_confirmation() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 30).unwrap();
+    let project_root = harness.project_dir().unwrap();
+    let path = project_root.join("doomed.txt");
+    fs::write(&path, "bye").unwrap();
+
+    harness.editor_mut().focus_file_explorer();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("doomed.txt"))
+        .unwrap();
+    harness.editor_mut().file_explorer_navigate_down();
+    harness.render().unwrap();
+
+    harness
+        .send_key(KeyCode::Char('d'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Delete 'doomed.txt'?");
+    harness.assert_screen_contains("[ Delete ]");
+
+    // Cancel is focused for destructive actions
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(path.exists());
+    harness.assert_screen_contains("Delete cancelled");
+
+    // Confirm with the shortcut key
+    harness
+        .send_key(KeyCode::Char('d'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('d'), KeyModifiers::NONE)
+        .unwrap();
+    assert!(!path.exists());
+}
+
+/// The palette shows what "Close All Buffers" would close, and it asks once
+/// before discarding unsaved changes
+#[test]
+fn test_close_all_buffers_dry_run_and_confirm() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+    for name in ["one.txt", "two.txt", "three.txt"] {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, name).unwrap();
+        harness.open_file(&path).unwrap();
+    }
+    harness.type_text("changed ").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Close All Buffers").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("will close 3 buffers, 1 unsaved");
+
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Close 3 buffers? Unsaved changes in 1 of them will be lost:");
+    harness.assert_screen_contains("• three.txt");
+    harness
+        .send_key(KeyCode::Char('d'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(harness.editor().confirm_dialog().is_none());
+    harness.assert_screen_contains("Closed 3 buffers");
+    for name in ["one.txt", "two.txt", "three.txt"] {
+        harness.assert_screen_not_contains(name);
+    }
+}
+
+/// Test Close to the Right, Close Others and Close Saved
+#[test]
+fn test_close_others_to_the_right_and_saved() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+    for name in ["one.txt", "two.txt", "three.txt"] {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, name).unwrap();
+        harness.open_file(&path).unwrap();
+        if name == "one.txt" {
+            harness.type_text("changed ").unwrap();
+        }
+    }
+    // Back to two.txt, between the others
+    harness
+        .send_key(KeyCode::PageUp, KeyModifiers::ALT)
+        .unwrap();
+
+    let run_command = |harness: &mut EditorTestHarness, name: &str, dry_run: &str| {
+        harness.type_command(name).unwrap();
+        harness.assert_screen_contains(dry_run);
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+        harness.render().unwrap();
+    };
+
+    run_command(&mut harness, "Close to the Right", "will close three.txt");
+    harness.assert_screen_contains("Closed 1 buffer");
+    harness.assert_screen_not_contains("three.txt");
+
+    // one.txt has unsaved changes, so Close Others asks first
+    run_command(&mut harness, "Close Others", "will close one.txt, unsaved");
+    harness.assert_screen_contains("Close 1 buffer? Its unsaved changes will be lost:");
+    harness
+        .send_key(KeyCode::Char('c'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("one.txt");
+
+    // Close Saved leaves it alone
+    run_command(&mut harness, "Close Saved", "will close two.txt");
+    harness.assert_screen_contains("Closed 1 buffer");
+    harness.assert_screen_contains("one.txt");
+    harness.assert_screen_not_contains("two.txt");
+}
@@ -402,8 +402,6 @@ fn test_explorer_menu_new_file_action() {
     let _ = harness.editor_mut().process_async_messages();
     harness.render().unwrap();
 
-    let initial_count = fs::read_dir(&project_root).unwrap().count();
-
     // Open Explorer menu with Alt+X
     harness
         .send_key(KeyCode::Char('x'), KeyModifiers::ALT)
@@ -414,18 +412,18 @@ fn test_explorer_menu_new_file_action() {
     harness
         .send_key(KeyCode::Enter, KeyModifiers::NONE)
         .unwrap();
-    std::thread::sleep(Duration::from_millis(200));
-    let _ = harness.editor_mut().process_async_messages();
     harness.render().unwrap();
+    harness.assert_screen_contains("New file:");
 
-    // Verify a new file was created
-    let final_count = fs::read_dir(&project_root).unwrap().count();
-    assert!(
-        final_count > initial_count,
-        "A new file should have been created via menu. Initial: {}, Final: {}",
-        initial_count,
-        final_count
-    );
+    harness.type_text("menu_file.txt").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    // Verify the named file was created and shown in the tree
+    assert!(project_root.join("menu_file.txt").is_file());
+    harness.assert_screen_contains("menu_file.txt");
 }
 
 /// Test executing New Folder action from Explorer menu
@@ -440,13 +438,6 @@ fn test_explorer_menu_new_folder_action() {
     let _ = harness.editor_mut().process_async_messages();
     harness.render().unwrap();
 
-    let initial_dirs: Vec<_> = fs::read_dir(&project_root)
-        .unwrap()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_dir())
-        .collect();
-    let initial_dir_count = initial_dirs.len();
-
     // Open Explorer menu with Alt+X
     harness
         .send_key(KeyCode::Char('x'), KeyModifiers::ALT)
@@ -461,24 +452,17 @@ fn test_explorer_menu_new_folder_action() {
     harness
         .send_key(KeyCode::Enter, KeyModifiers::NONE)
         .unwrap();
-    std::thread::sleep(Duration::from_millis(200));
-    let _ = harness.editor_mut().process_async_messages();
     harness.render().unwrap();
+    harness.assert_screen_contains("New directory:");
 
-    // Verify a new directory was created
-    let final_dirs: Vec<_> = fs::read_dir(&project_root)
-        .unwrap()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_dir())
-        .collect();
-    let final_dir_count = final_dirs.len();
+    harness.type_text("menu_folder").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
 
-    assert!(
-        final_dir_count > initial_dir_count,
-        "A new directory should have been created via menu. Initial: {}, Final: {}",
-        initial_dir_count,
-        final_dir_count
-    );
+    // Verify the named directory was created
+    assert!(project_root.join("menu_folder").is_dir());
 }
 
 /// Test that Explorer menu appears in the menu bar
@@ -603,9 +587,9 @@ fn test_explorer_menu_items_disabled_when_not_focused() {
     );
 }
 
-/// Test that New Folder action creates a folder and enters rename mode
+/// Test that New Folder asks for the name, and ESC creates nothing
 #[test]
-fn test_new_folder_enters_rename_mode() {
+fn test_new_folder_prompts_for_name() {
     let mut harness = EditorTestHarness::with_temp_project(100, 30).unwrap();
     let project_root = harness.project_dir().unwrap();
 
@@ -615,31 +599,15 @@ fn test_new_folder_enters_rename_mode() {
     let _ = harness.editor_mut().process_async_messages();
     harness.render().unwrap();
 
-    // Create new folder directly using the method
     harness.editor_mut().file_explorer_new_directory();
-    std::thread::sleep(Duration::from_millis(200));
-    let _ = harness.editor_mut().process_async_messages();
     harness.render().unwrap();
 
-    // Should be in rename mode - prompt should appear
     assert!(
         harness.editor().is_prompting(),
-        "Should be in rename mode (prompting) after creating new folder"
+        "Should prompt for the new folder's name"
     );
 
-    // Verify a folder was created on the filesystem
-    let dirs: Vec<_> = fs::read_dir(&project_root)
-        .unwrap()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_dir())
-        .collect();
-
-    assert!(
-        !dirs.is_empty(),
-        "A new folder should have been created on the filesystem"
-    );
-
-    // Cancel the rename (ESC) and verify folder still exists with default name
+    // Cancel the prompt (ESC): no folder is created
     harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
     harness.render().unwrap();
 
@@ -647,17 +615,49 @@ fn test_new_folder_enters_rename_mode() {
         !harness.editor().is_prompting(),
         "Should not be prompting after ESC"
     );
+    assert_eq!(
+        fs::read_dir(&project_root).unwrap().count(),
+        0,
+        "Nothing should be created when the prompt is cancelled"
+    );
+}
 
-    // Folder should still exist
-    let dirs_after: Vec<_> = fs::read_dir(&project_root)
-        .unwrap()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_dir())
-        .collect();
+/// Test that a new file's name can create directories on the way, and
+/// that an existing name is refused
+#[test]
+fn test_new_file_in_new_subdirectory() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 30).unwrap();
+    let project_root = harness.project_dir().unwrap();
+    fs::write(project_root.join("taken.txt"), "keep me").unwrap();
 
-    assert!(
-        !dirs_after.is_empty(),
-        "Folder should still exist after cancelling rename"
+    harness.editor_mut().focus_file_explorer();
+    std::thread::sleep(Duration::from_millis(100));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.render().unwrap();
+
+    // "n" creates a file in the selected (root) directory
+    harness
+        .send_key(KeyCode::Char('n'), KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("src/lib.rs").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert!(project_root.join("src").join("lib.rs").is_file());
+    harness.assert_screen_contains("lib.rs");
+
+    harness.editor_mut().file_explorer_new_file();
+    harness.type_text("../taken.txt").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("already exists");
+    assert_eq!(
+        fs::read_to_string(project_root.join("taken.txt")).unwrap(),
+        "keep me"
     );
 }
 
@@ -903,3 +903,53 @@ fn test_file_browser_prompt_shows_buffer_directory() {
         "Should show the current file in the list"
     );
 }
+
+/// Test that the highlighted file is previewed beside the file list
+#[test]
+fn test_file_browser_preview() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().to_path_buf();
+
+    fs::write(
+        project_root.join("notes.txt"),
+        "Remember the milk\nand eggs",
+    )
+    .unwrap();
+    fs::write(project_root.join("other.txt"), "Something else").unwrap();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        100,
+        30,
+        Default::default(),
+        project_root.clone(),
+    )
+    .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('o'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("notes.txt"))
+        .expect("Files should load");
+
+    harness.type_text("notes").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Remember the milk"))
+        .expect("Preview of the highlighted file should appear");
+    harness.assert_screen_contains("and eggs");
+
+    // Previews can be turned off
+    let mut config = Config::default();
+    config.editor.suggestion_preview = false;
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 30, config, project_root).unwrap();
+    harness
+        .send_key(KeyCode::Char('o'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("notes.txt"))
+        .expect("Files should load");
+    harness.type_text("notes").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("Remember the milk");
+}
@@ -532,6 +532,65 @@ fn test_file_explorer_toggle_gitignored_smoke() {
     // Test passes if no panic occurs
 }
 
+/// Test that dotfiles and gitignored entries are left out until their
+/// toggle shows them, dimmed
+#[test]
+fn test_file_explorer_hidden_and_gitignored() {
+    let mut harness = EditorTestHarness::with_temp_project(120, 40).unwrap();
+    let project_root = harness.project_dir().unwrap();
+    fs::write(project_root.join(".gitignore"), "build/\n*.log\n").unwrap();
+    fs::write(project_root.join(".env"), "KEY=1").unwrap();
+    fs::create_dir(project_root.join("build")).unwrap();
+    fs::write(project_root.join("debug.log"), "").unwrap();
+    fs::write(project_root.join("visible.txt"), "").unwrap();
+
+    harness.editor_mut().toggle_file_explorer();
+    let opened = harness
+        .wait_for_async(|h| h.screen_to_string().contains("visible.txt"), 5000)
+        .unwrap();
+    assert!(
+        opened,
+        "Explorer not shown:\n{}",
+        harness.screen_to_string()
+    );
+    harness.assert_screen_not_contains(".env");
+    harness.assert_screen_not_contains("debug.log");
+    harness.assert_screen_not_contains("build");
+
+    harness.editor_mut().file_explorer_toggle_hidden();
+    harness.render().unwrap();
+    harness.assert_screen_contains(".env");
+    harness.assert_screen_contains(".gitignore");
+    harness.assert_screen_not_contains("debug.log");
+
+    harness.editor_mut().file_explorer_toggle_gitignored();
+    harness.render().unwrap();
+    harness.assert_screen_contains("debug.log");
+    harness.assert_screen_contains("build");
+
+    // Shown ignored entries are dimmed
+    let name_fg = |harness: &EditorTestHarness, name: &str| {
+        let screen = harness.screen_to_string();
+        let (row, line) = screen
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.contains(name))
+            .unwrap();
+        let col = line[..line.find(name).unwrap()].chars().count();
+        harness
+            .get_cell_style(col as u16, row as u16)
+            .and_then(|style| style.fg)
+    };
+    let plain = name_fg(&harness, "visible.txt");
+    assert_ne!(name_fg(&harness, "debug.log"), plain);
+    assert_ne!(name_fg(&harness, ".env"), plain);
+
+    // Hiding them again
+    harness.editor_mut().file_explorer_toggle_gitignored();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("debug.log");
+}
+
 /// Test that file_explorer_new_file can be called (smoke test)
 #[test]
 fn test_file_explorer_new_file_smoke() {
@@ -1445,3 +1504,104 @@ fn test_file_explorer_keybinding_matches_behavior() {
         "File explorer should toggle off when the keybinding is pressed again"
     );
 }
+
+/// Test "Open Below" from the explorer: it splits a single pane, and with
+/// three panes it asks which one to open in
+#[test]
+fn test_file_explorer_open_to_side() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut harness = EditorTestHarness::with_temp_project(120, 40).unwrap();
+    let project_root = harness.project_dir().unwrap();
+    fs::write(project_root.join("simple.txt"), "Hello World").unwrap();
+
+    harness.editor_mut().toggle_file_explorer();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.editor_mut().file_explorer_toggle_expand();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = harness.editor_mut().process_async_messages();
+    harness.editor_mut().file_explorer_navigate_down();
+
+    // A single pane is split and the file opens in the new one
+    harness.send_key(KeyCode::Enter, KeyModifiers::ALT).unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("Hello World");
+    harness.assert_screen_contains("[No Name]");
+
+    // A third pane: the other two get letters
+    harness.editor_mut().split_pane_vertical();
+    // Reopening the explorer selects the active file again
+    harness.editor_mut().toggle_file_explorer();
+    harness.editor_mut().toggle_file_explorer();
+    harness.send_key(KeyCode::Enter, KeyModifiers::ALT).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Open in which pane?");
+
+    // Picking the first pane (the [No Name] one) opens the file there
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("Open in which pane?");
+    harness.assert_buffer_content("Hello World");
+}
+
+/// Test "/" in the explorer: the tree narrows to the matches as the query is
+/// typed, expanding their directories, and Escape shows the whole tree again
+#[test]
+fn test_file_explorer_filter() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut harness = EditorTestHarness::with_temp_project(120, 40).unwrap();
+    let project_root = harness.project_dir().unwrap();
+    fs::create_dir_all(project_root.join("src/parser")).unwrap();
+    fs::create_dir_all(project_root.join("docs")).unwrap();
+    fs::write(project_root.join("src/parser/lexer.rs"), "").unwrap();
+    fs::write(project_root.join("src/main.rs"), "").unwrap();
+    fs::write(project_root.join("docs/guide.md"), "").unwrap();
+
+    harness.editor_mut().toggle_file_explorer();
+    let opened = harness
+        .wait_for_async(|h| h.screen_to_string().contains("docs"), 5000)
+        .unwrap();
+    assert!(
+        opened,
+        "Explorer not shown:\n{}",
+        harness.screen_to_string()
+    );
+
+    harness
+        .send_key(KeyCode::Char('/'), KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("lexer").unwrap();
+    let filtered = harness
+        .wait_for_async(|h| h.screen_to_string().contains("lexer.rs"), 5000)
+        .unwrap();
+    assert!(filtered, "No match shown:\n{}", harness.screen_to_string());
+    harness.assert_screen_contains("parser");
+    harness.assert_screen_not_contains("docs");
+    harness.assert_screen_not_contains("main.rs");
+
+    // Escape in the prompt restores the tree, with parser collapsed again
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("docs");
+    harness.assert_screen_not_contains("lexer.rs");
+
+    // Enter keeps the tree narrowed; Escape in the explorer then restores it
+    harness
+        .send_key(KeyCode::Char('/'), KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("guide").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("guide.md");
+    harness.assert_screen_not_contains("src");
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("src");
+    harness.assert_screen_not_contains("guide.md");
+}
@@ -2378,3 +2378,108 @@ fn test_git_blame_original_buffer_not_decorated() {
         "Original file should NOT have blame headers after closing blame"
     );
 }
+
+/// Opening COMMIT_EDITMSG (as git commit does) shows the staged diff next to
+/// the message, rulers and the part of the subject past 50 characters
+#[test]
+fn test_commit_message_mode() {
+    let repo = GitTestRepo::new();
+    repo.create_file("hello.txt", "hello world\n");
+    repo.git_add(&["hello.txt"]);
+    let subject = "Add a greeting file with a rather long subject line here";
+    let message_path = repo.create_file(
+        ".git/COMMIT_EDITMSG",
+        &format!("{}\n\n# Please enter the commit message\n", subject),
+    );
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        200,
+        30,
+        Config::default(),
+        repo.path.clone(),
+    )
+    .unwrap();
+    harness.open_file(&message_path).unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("*Staged Changes*");
+    harness.assert_screen_contains("+hello world");
+    // The message keeps the focus
+    assert_eq!(
+        harness.editor().active_state().buffer.file_path(),
+        Some(message_path.canonicalize().unwrap().as_path())
+    );
+
+    let screen = harness.screen_to_string();
+    let (row, col) = screen
+        .lines()
+        .enumerate()
+        .find_map(|(row, line)| line.find(subject).map(|col| (row, col)))
+        .expect("subject on screen");
+    let warning_bg = harness.editor().theme().diagnostic_warning_bg;
+    let bg_at = |offset: usize| {
+        harness
+            .get_cell_style((col + offset) as u16, row as u16)
+            .and_then(|style| style.bg)
+    };
+    assert_ne!(bg_at(49), Some(warning_bg));
+    assert_eq!(bg_at(50), Some(warning_bg));
+
+    // The blank line below the subject shows both rulers
+    let blank_line: Vec<char> = screen.lines().nth(row + 1).unwrap().chars().collect();
+    assert_eq!(blank_line[col + 50], '│');
+    assert_eq!(blank_line[col + 72], '│');
+}
+
+/// The file explorer marks modified and untracked files, and picks up a
+/// file modified by a save
+#[test]
+fn test_file_explorer_git_status_badges() {
+    let repo = GitTestRepo::new();
+    repo.create_file("changed.txt", "one\n");
+    repo.create_file("clean.txt", "two\n");
+    repo.git_add_all();
+    repo.git_commit("Initial commit");
+    repo.create_file("changed.txt", "one more\n");
+    repo.create_file("new.txt", "three\n");
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        120,
+        30,
+        Config::default(),
+        repo.path.clone(),
+    )
+    .unwrap();
+    harness.editor_mut().toggle_file_explorer();
+    let row_of = |harness: &EditorTestHarness, name: &str| {
+        harness
+            .screen_to_string()
+            .lines()
+            .find(|line| line.contains(name))
+            .map(str::to_string)
+            .unwrap_or_default()
+    };
+    let shown = harness
+        .wait_for_async(
+            |h| row_of(h, "changed.txt").contains("KB M") && row_of(h, "new.txt").contains("KB U"),
+            5000,
+        )
+        .unwrap();
+    assert!(shown, "Badges missing:\n{}", harness.screen_to_string());
+    assert!(!row_of(&harness, "clean.txt").contains("KB M"));
+
+    // Saving a change marks the file
+    harness.open_file(&repo.path.join("clean.txt")).unwrap();
+    harness.type_text("x").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    let updated = harness
+        .wait_for_async(|h| row_of(h, "clean.txt").contains("KB M"), 5000)
+        .unwrap();
+    assert!(
+        updated,
+        "clean.txt not marked:\n{}",
+        harness.screen_to_string()
+    );
+}
@@ -1450,6 +1450,7 @@ fn test_lsp_typing_performance_with_many_diagnostics() -> std::io::Result<()> {
         state,
         &diag_params.diagnostics,
         &fresh::view::theme::Theme::dark(),
+        usize::MAX,
     );
 
     let apply_duration = start.elapsed();
@@ -1486,6 +1487,7 @@ fn test_lsp_typing_performance_with_many_diagnostics() -> std::io::Result<()> {
             state,
             &diag_params.diagnostics,
             &fresh::view::theme::Theme::dark(),
+            usize::MAX,
         );
         let reapply_duration = start.elapsed();
         total_reapply_time += reapply_duration;
@@ -1630,6 +1632,61 @@ fn test_handle_rename_response_with_document_changes() -> std::io::Result<()> {
     Ok(())
 }
 
+/// Test that a rename touching several places is undone in one step
+#[test]
+fn test_undo_reverts_lsp_rename_as_one_step() -> std::io::Result<()> {
+    use lsp_types::{Position, Range, TextEdit, Uri, WorkspaceEdit};
+    use std::collections::HashMap;
+
+    let mut harness = EditorTestHarness::new(80, 30)?;
+
+    let temp_dir = tempfile::tempdir()?;
+    let test_file = temp_dir.path().join("test.rs");
+    let original = "fn f(value: i32) -> i32 {\n    value + value\n}\n";
+    std::fs::write(&test_file, original)?;
+    harness.open_file(&test_file)?;
+    harness.render()?;
+
+    let uri = url::Url::from_file_path(&test_file)
+        .unwrap()
+        .as_str()
+        .parse::<Uri>()
+        .unwrap();
+    let edit = |line: u32, character: u32| TextEdit {
+        range: Range {
+            start: Position { line, character },
+            end: Position {
+                line,
+                character: character + 5,
+            },
+        },
+        new_text: "n".to_string(),
+    };
+    let workspace_edit = WorkspaceEdit {
+        changes: Some(HashMap::from([(
+            uri,
+            vec![edit(0, 5), edit(1, 4), edit(1, 12)],
+        )])),
+        document_changes: None,
+        change_annotations: None,
+    };
+
+    harness
+        .editor_mut()
+        .handle_rename_response(0, Ok(workspace_edit))?;
+    harness.render()?;
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "fn f(n: i32) -> i32 {\n    n + n\n}\n"
+    );
+
+    harness.send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)?;
+    harness.render()?;
+    assert_eq!(harness.get_buffer_content().unwrap(), original);
+
+    Ok(())
+}
+
 /// Test that editor remains responsive while LSP is completely stuck
 ///
 /// This test verifies that the UI doesn't block when the LSP server is unresponsive.
@@ -3063,6 +3120,17 @@ fn test_lsp_find_references() -> std::io::Result<()> {
     let plugin_dest = plugins_dir.join("find_references.ts");
     std::fs::copy(&plugin_source, &plugin_dest)?;
 
+    // Copy the lib directory that the plugin depends on
+    let lib_source_dir = std::env::current_dir()?.join("plugins/lib");
+    let lib_dest_dir = plugins_dir.join("lib");
+    std::fs::create_dir(&lib_dest_dir)?;
+    for entry in std::fs::read_dir(&lib_source_dir)? {
+        let entry = entry?;
+        if entry.path().extension().is_some_and(|e| e == "ts") {
+            std::fs::copy(entry.path(), lib_dest_dir.join(entry.file_name()))?;
+        }
+    }
+
     // Create a fake LSP server script that responds to references requests
     let fake_lsp_script = r#"#!/bin/bash
 
@@ -3314,6 +3382,17 @@ fn main() {
     let plugin_dest = plugins_dir.join("find_references.ts");
     std::fs::copy(&plugin_source, &plugin_dest)?;
 
+    // Copy the lib directory that the plugin depends on
+    let lib_source_dir = std::env::current_dir()?.join("plugins/lib");
+    let lib_dest_dir = plugins_dir.join("lib");
+    std::fs::create_dir(&lib_dest_dir)?;
+    for entry in std::fs::read_dir(&lib_source_dir)? {
+        let entry = entry?;
+        if entry.path().extension().is_some_and(|e| e == "ts") {
+            std::fs::copy(entry.path(), lib_dest_dir.join(entry.file_name()))?;
+        }
+    }
+
     // Use default config (which includes rust-analyzer)
     let config = fresh::config::Config::default();
 
@@ -3706,3 +3785,173 @@ fn test_stopped_lsp_does_not_auto_restart_on_edit() -> std::io::Result<()> {
 
     Ok(())
 }
+
+/// Test that the buffer_changed hook and LSP didChange notifications receive
+/// the same changes in the same order
+#[test]
+#[cfg(unix)]
+fn test_buffer_changed_matches_lsp_did_change() -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempfile::TempDir::new()?;
+    let project_root = temp_dir.path().to_path_buf();
+    let lsp_log = project_root.join("lsp_changes.log");
+    let plugin_log = project_root.join("plugin_changes.json");
+
+    // Fake LSP server that logs every didOpen and didChange message
+    let fake_lsp_script = format!(
+        r#"#!/bin/bash
+
+read_message() {{
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}}
+
+send_message() {{
+    local message="$1"
+    local length=${{#message}}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}}
+
+while true; do
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+
+    case "$method" in
+        "initialize")
+            send_message '{{"jsonrpc":"2.0","id":'$msg_id',"result":{{"capabilities":{{"textDocumentSync":2}}}}}}'
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange")
+            echo "$msg" >> "{log}"
+            ;;
+        "shutdown")
+            send_message '{{"jsonrpc":"2.0","id":'$msg_id',"result":null}}'
+            break
+            ;;
+    esac
+done
+"#,
+        log = lsp_log.display()
+    );
+    let script_path = project_root.join("fake_lsp.sh");
+    std::fs::write(&script_path, fake_lsp_script)?;
+    let mut perms = std::fs::metadata(&script_path)?.permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&script_path, perms)?;
+
+    // Plugin that records every change it is told about
+    let plugins_dir = project_root.join("plugins");
+    std::fs::create_dir(&plugins_dir)?;
+    let plugin = format!(
+        r#"
+const changes: unknown[] = [];
+let writing: Promise<unknown> = Promise.resolve();
+
+globalThis.onRecordBufferChanged = function (args: {{
+    changes: Array<{{
+        start_line: number;
+        start_character: number;
+        end_line: number;
+        end_character: number;
+        new_text: string;
+    }}>;
+}}): boolean {{
+    for (const c of args.changes) {{
+        changes.push([c.start_line, c.start_character, c.end_line, c.end_character, c.new_text]);
+    }}
+    const content = JSON.stringify(changes);
+    writing = writing.then(() => editor.writeFile({log}, content));
+    return true;
+}};
+
+editor.on("buffer_changed", "onRecordBufferChanged");
+"#,
+        log = serde_json::to_string(&plugin_log.to_string_lossy()).unwrap()
+    );
+    std::fs::write(plugins_dir.join("record_changes.ts"), plugin)?;
+
+    let test_file = project_root.join("test.rs");
+    std::fs::write(&test_file, "fn main() {\n    let x = 5;\n}\n")?;
+
+    let mut config = fresh::config::Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        fresh::services::lsp::client::LspServerConfig {
+            command: script_path.to_string_lossy().to_string(),
+            args: vec![],
+            enabled: true,
+            auto_start: true,
+            process_limits: fresh::services::process_limits::ProcessLimits::default(),
+        },
+    );
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, config, project_root.clone())?;
+    harness.open_file(&test_file)?;
+    harness.wait_until(|_| lsp_log.exists())?;
+
+    // Typing, a newline, deleting and an undo of several edits at once
+    harness.type_text("// hi")?;
+    harness.send_key(KeyCode::Enter, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::Backspace, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::End, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::Backspace, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)?;
+
+    let lsp_changes = |path: &std::path::Path| -> Vec<serde_json::Value> {
+        let log = std::fs::read_to_string(path).unwrap_or_default();
+        log.lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|msg| msg["method"] == "textDocument/didChange")
+            .flat_map(|msg| msg["params"]["contentChanges"].as_array().cloned().unwrap())
+            .map(|change| {
+                let range = &change["range"];
+                serde_json::json!([
+                    range["start"]["line"],
+                    range["start"]["character"],
+                    range["end"]["line"],
+                    range["end"]["character"],
+                    change["text"]
+                ])
+            })
+            .collect()
+    };
+    let plugin_changes = |path: &std::path::Path| -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|log| serde_json::from_str(&log).ok())
+            .unwrap_or_default()
+    };
+
+    // "// hi", the newline, two deletions and the undo
+    let expected_count = 9;
+    harness.wait_until(|_| {
+        let count = lsp_changes(&lsp_log).len();
+        count >= expected_count && plugin_changes(&plugin_log).len() == count
+    })?;
+
+    let from_lsp = lsp_changes(&lsp_log);
+    assert_eq!(plugin_changes(&plugin_log), from_lsp);
+    assert_eq!(from_lsp[0], serde_json::json!([0, 0, 0, 0, "/"]));
+
+    Ok(())
+}
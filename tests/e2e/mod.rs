@@ -5,6 +5,7 @@ pub mod basic;
 pub mod binary_file;
 pub mod buffer_lifecycle;
 pub mod command_palette;
+pub mod confirm_dialog;
 pub mod crlf_rendering;
 pub mod document_model;
 pub mod emacs_actions;
@@ -25,13 +26,16 @@ pub mod merge_conflict;
 pub mod mouse;
 pub mod movement;
 pub mod multicursor;
+pub mod pager;
 pub mod plugin;
 pub mod position_history;
 pub mod position_history_bugs;
 pub mod position_history_debug;
 pub mod position_history_truncate_debug;
+pub mod project_tasks;
 pub mod prompt;
 pub mod prompt_editing;
+pub mod quit_review;
 pub mod recovery;
 pub mod rendering;
 pub mod scroll_clearing;
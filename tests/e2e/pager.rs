@@ -0,0 +1,57 @@
+//! E2E tests for the pager (`fresh --pager`)
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::io::Write;
+use tempfile::TempDir;
+
+/// Test that the pager is read-only, takes in text appended to its file and
+/// follows the end with F
+#[test]
+fn test_pager_follows_appended_text() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("app.log");
+    let mut file = std::fs::File::create(&path).unwrap();
+    file.write_all(b"first line\nsecond line\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.editor_mut().start_pager(&path, None).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("first line");
+
+    // Letters are the pager's commands, not text
+    harness
+        .send_key(KeyCode::Char('G'), KeyModifiers::SHIFT)
+        .unwrap();
+    harness.assert_buffer_content("first line\nsecond line\n");
+    let end = harness.cursor_position();
+    assert_eq!(end, 23);
+
+    file.write_all(b"third line\n").unwrap();
+    assert!(harness.editor_mut().update_pager());
+    harness.render().unwrap();
+    harness.assert_screen_contains("third line");
+    assert_eq!(harness.cursor_position(), end);
+
+    // Following, the cursor stays at the end as text arrives
+    harness
+        .send_key(KeyCode::Char('F'), KeyModifiers::SHIFT)
+        .unwrap();
+    file.write_all(b"fourth line\n").unwrap();
+    assert!(harness.editor_mut().update_pager());
+    assert_eq!(harness.cursor_position(), 46);
+    assert!(!harness.editor_mut().update_pager());
+
+    // Moving back up stops following
+    harness
+        .send_key(KeyCode::Char('g'), KeyModifiers::NONE)
+        .unwrap();
+    file.write_all(b"fifth line\n").unwrap();
+    harness.editor_mut().update_pager();
+    assert_eq!(harness.cursor_position(), 0);
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::NONE)
+        .unwrap();
+    assert!(harness.should_quit());
+}
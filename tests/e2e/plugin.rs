@@ -1780,3 +1780,149 @@ editor.setStatus("Test source plugin loaded!");
         screen2
     );
 }
+
+/// Test that keys a plugin binds in one buffer override the keymap only there
+/// and go away when the buffer closes
+#[test]
+fn test_plugin_buffer_keybindings() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("project_root");
+    fs::create_dir(&project_root).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+
+    let test_plugin = r#"
+editor.registerCommand("Bind Preview Keys", "Bind keys in this buffer", "bind_preview_keys", "normal");
+
+globalThis.bind_preview_keys = function(): void {
+    editor.setBufferKeybindings(editor.getActiveBufferId(), [
+        ["q", "close-buffer"],
+        ["x", "preview_ping"],
+    ]);
+};
+
+globalThis.preview_ping = function(): void {
+    editor.setStatus("Preview ping");
+};
+"#;
+    fs::write(plugins_dir.join("test_buffer_keys.ts"), test_plugin).unwrap();
+
+    let notes = TestFixture::new("notes.txt", "Notes\n").unwrap();
+    let preview = TestFixture::new("preview.txt", "Preview\n").unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Default::default(), project_root)
+            .unwrap();
+    harness.open_file(&notes.path).unwrap();
+    harness.open_file(&preview.path).unwrap();
+    for _ in 0..5 {
+        harness.process_async_and_render().unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Bind Preview Keys").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| {
+            h.editor()
+                .resolve_buffer_keybinding(KeyCode::Char('x'), KeyModifiers::NONE)
+                .is_some()
+        })
+        .unwrap();
+
+    // The buffer's own binding runs instead of inserting the character
+    harness
+        .send_key(KeyCode::Char('x'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Preview ping"))
+        .unwrap();
+    harness.assert_buffer_content("Preview\n");
+
+    // "q" closes the buffer, and types normally in the buffer shown instead
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("Notes\n");
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("qNotes\n");
+}
+
+/// Harness in a project whose plugin asks with promptInput, promptSelect and
+/// promptConfirm, then shows the answers
+fn prompt_answers_harness(temp_dir: &tempfile::TempDir) -> EditorTestHarness {
+    let project_root = temp_dir.path().join("project_root");
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir_all(&plugins_dir).unwrap();
+    let plugin = r###"
+globalThis.test_ask = async function(): Promise<void> {
+    const name = await editor.promptInput("Name", "anon");
+    const remote = await editor.promptSelect("Remote", [
+        { text: "origin", value: "git@origin" },
+        { text: "upstream", value: "git@upstream" },
+    ]);
+    const sure = await editor.promptConfirm("Push?");
+    editor.setStatus(`answers: ${JSON.stringify([name, remote, sure])}`);
+};
+
+editor.registerCommand("Test: Ask", "Ask three questions", "test_ask", "normal");
+"###;
+    fs::write(plugins_dir.join("ask.ts"), plugin).unwrap();
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Default::default(), project_root)
+            .unwrap();
+    harness.run_command("Test: Ask").unwrap();
+    harness
+}
+
+/// Answer the prompt labelled `label` once it is shown
+fn answer_prompt(harness: &mut EditorTestHarness, label: &str, text: &str, key: KeyCode) {
+    harness
+        .wait_until(|h| h.screen_to_string().contains(label))
+        .unwrap();
+    harness.type_text(text).unwrap();
+    harness.send_key(key, KeyModifiers::NONE).unwrap();
+}
+
+/// Plugin prompts resolve to the typed text, the selected item's value and
+/// true for "Yes"
+#[test]
+fn test_plugin_prompts_resolve_to_answers() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut harness = prompt_answers_harness(&temp_dir);
+
+    answer_prompt(&mut harness, "Name: anon", "-2", KeyCode::Enter);
+    answer_prompt(&mut harness, "Remote:", "up", KeyCode::Enter);
+    answer_prompt(&mut harness, "Push?", "", KeyCode::Enter);
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains(r#"answers: ["anon-2","git@upstream",true]"#)
+        })
+        .unwrap();
+}
+
+/// Cancelled plugin prompts resolve to null (false for promptConfirm), and
+/// promptSelect doesn't resolve to typed text matching no item
+#[test]
+fn test_plugin_prompts_resolve_to_null_when_cancelled() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut harness = prompt_answers_harness(&temp_dir);
+
+    answer_prompt(&mut harness, "Name: anon", "", KeyCode::Esc);
+    answer_prompt(&mut harness, "Remote:", "zzz", KeyCode::Enter);
+    answer_prompt(&mut harness, "Push?", "", KeyCode::Esc);
+    harness
+        .wait_until(|h| h.screen_to_string().contains("answers: [null,null,false]"))
+        .unwrap();
+}
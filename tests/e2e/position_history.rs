@@ -82,7 +82,7 @@ fn test_navigate_back_forward_across_buffers() {
     assert_eq!(harness.cursor_position(), buffer3_pos);
 }
 
-/// Test that switching buffers with Ctrl+PageUp/PageDown creates history entries
+/// Test that switching buffers with Alt+PageUp/PageDown creates history entries
 #[test]
 fn test_buffer_switching_creates_history() {
     let mut harness = EditorTestHarness::new(80, 24).unwrap();
@@ -102,12 +102,12 @@ fn test_buffer_switching_creates_history() {
         .unwrap();
     harness.type_text("Third").unwrap();
 
-    // Switch back to first buffer using Ctrl+PageUp twice
+    // Switch back to first buffer using Alt+PageUp twice
     harness
-        .send_key(KeyCode::PageUp, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageUp, KeyModifiers::ALT)
         .unwrap();
     harness
-        .send_key(KeyCode::PageUp, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageUp, KeyModifiers::ALT)
         .unwrap();
     harness.assert_buffer_content("First");
 
@@ -232,14 +232,14 @@ fn test_position_history_with_next_prev_buffer() {
         .unwrap();
     harness.type_text("Third").unwrap();
 
-    // Use Ctrl+PageDown to cycle through buffers
+    // Use Alt+PageDown to cycle through buffers
     harness
-        .send_key(KeyCode::PageDown, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageDown, KeyModifiers::ALT)
         .unwrap();
     harness.assert_buffer_content("First");
 
     harness
-        .send_key(KeyCode::PageDown, KeyModifiers::CONTROL)
+        .send_key(KeyCode::PageDown, KeyModifiers::ALT)
         .unwrap();
     harness.assert_buffer_content("Second");
 
@@ -0,0 +1,165 @@
+//! E2E tests for project tasks defined in `.fresh/tasks.toml`
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use tempfile::TempDir;
+
+/// Create a project directory with the given tasks file
+fn project_with_tasks(temp_dir: &TempDir, tasks: &str) -> std::path::PathBuf {
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir_all(project_dir.join(".fresh")).unwrap();
+    std::fs::write(project_dir.join(".fresh/tasks.toml"), tasks).unwrap();
+    project_dir
+}
+
+/// Tasks show up in the command palette with the "Project:" prefix
+#[test]
+fn test_project_tasks_in_command_palette() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = project_with_tasks(
+        &temp_dir,
+        r#"
+[[task]]
+name = "Greet"
+command = "echo hello"
+description = "Say hello"
+"#,
+    );
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(100, 24, Config::default(), project_dir)
+            .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Project:").unwrap();
+    harness.assert_screen_contains("Project: Greet");
+    harness.assert_screen_contains("Say hello");
+}
+
+/// Running a task shows its output in the status bar or inserts it
+#[test]
+fn test_run_project_task_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = project_with_tasks(
+        &temp_dir,
+        r#"
+[[task]]
+name = "Status"
+command = "echo first; echo task-done"
+output = "status"
+
+[[task]]
+name = "Stamp"
+command = "echo stamped"
+output = "insert"
+key = "ctrl+alt+t"
+"#,
+    );
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Config::default(), project_dir)
+            .unwrap();
+
+    harness.run_command("Project: Status").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("task-done"))
+        .unwrap();
+
+    // The key binding runs the task too
+    harness
+        .send_key(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        )
+        .unwrap();
+    harness
+        .wait_until(|h| h.get_buffer_content().unwrap_or_default() == "stamped")
+        .unwrap();
+}
+
+/// Failed tasks are reported, and the default output goes to a buffer
+#[test]
+fn test_project_task_output_buffer() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = project_with_tasks(
+        &temp_dir,
+        r#"
+[[task]]
+name = "Check"
+command = "echo checking; exit 3"
+"#,
+    );
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(80, 24, Config::default(), project_dir)
+            .unwrap();
+
+    harness.run_command("Project: Check").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("[exit code 3]"))
+        .unwrap();
+    harness.assert_screen_contains("*Project: Check*");
+    harness.assert_screen_contains("checking");
+    harness.assert_screen_contains("'Check' failed");
+}
+
+/// Reloading replaces the tasks of the previous tasks file
+#[test]
+fn test_reload_project_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = project_with_tasks(
+        &temp_dir,
+        "[[task]]\nname = \"Old Task\"\ncommand = \"true\"\n",
+    );
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        80,
+        24,
+        Config::default(),
+        project_dir.clone(),
+    )
+    .unwrap();
+
+    std::fs::write(
+        project_dir.join(".fresh/tasks.toml"),
+        "[[task]]\nname = \"New Task\"\ncommand = \"true\"\n",
+    )
+    .unwrap();
+    harness.run_command("Reload Project Tasks").unwrap();
+    harness.assert_screen_contains("Loaded 1 project task");
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Project:").unwrap();
+    harness.assert_screen_contains("Project: New Task");
+    harness.assert_screen_not_contains("Project: Old Task");
+}
+
+/// An `.envrc` is only run by direnv once allowed with `direnv allow`; without
+/// direnv it is never run
+#[test]
+fn test_envrc_not_run_unless_allowed() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    std::fs::write(project_dir.join(".envrc"), "touch envrc-ran\n").unwrap();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        100,
+        24,
+        Config::default(),
+        project_dir.clone(),
+    )
+    .unwrap();
+
+    harness.run_command("Reload Environment").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Failed to load environment"))
+        .unwrap();
+    harness.assert_screen_not_contains("env: direnv");
+    assert!(!project_dir.join("envrc-ran").exists());
+}
@@ -426,12 +426,54 @@ fn test_save_as_nested_path() {
         .unwrap();
     harness.render().unwrap();
 
-    // Note: This test verifies the error case since we don't auto-create parent dirs
-    // The file won't be created because the parent directory doesn't exist
-    // This documents current behavior - if we want to auto-create dirs, update this test
-    if !nested_path.exists() {
-        harness.assert_screen_contains("Error saving file");
-    }
+    // The missing parent directory is created
+    assert_eq!(
+        fs::read_to_string(&nested_path).unwrap(),
+        "Nested file content"
+    );
+    harness.assert_screen_contains("Saved as:");
+}
+
+/// Test that Enter on a directory in Save As lists its contents instead of saving
+#[test]
+fn test_save_as_enter_on_directory() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::fs;
+
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    let project_dir = harness.project_dir().unwrap();
+    fs::create_dir_all(project_dir.join("docs/guides")).unwrap();
+    fs::write(project_dir.join("docs/index.md"), "").unwrap();
+
+    harness.type_text("Guide").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("docs").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    // Still prompting, now inside the directory
+    harness.assert_screen_contains("Save as: docs/");
+    harness.assert_screen_contains("guides/");
+    harness.assert_screen_contains("index.md");
+
+    // Selecting a directory completion and pressing Enter goes into it
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("Save as: docs/guides/");
+
+    harness.type_text("new/intro.md").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(project_dir.join("docs/guides/new/intro.md")).unwrap(),
+        "Guide"
+    );
 }
 
 /// Test that Open File prompt shows completions popup immediately when opened (issue #193)
@@ -518,3 +560,134 @@ fn test_open_file_prompt_shows_completions_immediately() {
     let screen = harness.screen_to_string();
     println!("Screen after opening prompt:\n{}", screen);
 }
+
+/// Test Tab completion and the hidden files toggle in the Save As prompt
+#[test]
+fn test_save_as_path_completion() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::fs;
+
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    let project_dir = harness.project_dir().unwrap();
+    fs::create_dir(project_dir.join("notes")).unwrap();
+    fs::write(project_dir.join("notes/todo.txt"), "").unwrap();
+    fs::write(project_dir.join("notes/.draft.txt"), "").unwrap();
+
+    harness.type_text("Some text").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_screen_contains("Save as:");
+
+    // Tab completes the directory name
+    harness.type_text("no").unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.assert_screen_contains("Save as: notes/");
+    harness.assert_screen_contains("todo.txt");
+    harness.assert_screen_not_contains(".draft.txt");
+
+    // Hidden files can be shown
+    harness
+        .send_key(KeyCode::Char('.'), KeyModifiers::ALT)
+        .unwrap();
+    harness.assert_screen_contains(".draft.txt");
+
+    // Enter keeps the typed name rather than a completion
+    harness.type_text("new.txt").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(project_dir.join("notes/new.txt")).unwrap(),
+        "Some text"
+    );
+}
+
+/// Test that plugins still get prompt_changed for Save As and that their
+/// suggestions are listed with the built-in completions
+#[test]
+fn test_save_as_lists_plugin_suggestions() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::fs;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let project_root = temp_dir.path().to_path_buf();
+    fs::create_dir(project_root.join("notes")).unwrap();
+
+    let plugins_dir = project_root.join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    fs::copy(
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("plugins/path_complete.ts"),
+        plugins_dir.join("path_complete.ts"),
+    )
+    .unwrap();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        80,
+        24,
+        Default::default(),
+        project_root.clone(),
+    )
+    .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("not").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("not (new file)"))
+        .unwrap();
+    harness.assert_screen_contains("notes/");
+}
+
+/// Test that environment variables are expanded in Save As and Open File paths
+///
+/// Uses CARGO_MANIFEST_DIR, which cargo sets for test processes, so the test
+/// doesn't have to change the environment shared with other tests.
+#[test]
+fn test_file_prompts_expand_env_vars() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let manifest = std::fs::read_to_string(
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"),
+    )
+    .unwrap();
+
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+
+    // Tab in the Save As prompt completes inside the expanded directory
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("${CARGO_MANIFEST_DIR}/Cargo.to").unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.assert_screen_contains("Save as: ${CARGO_MANIFEST_DIR}/Cargo.toml");
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+
+    // Tab in the file browser goes to the directory and filters by the rest
+    harness
+        .send_key(KeyCode::Char('o'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("$CARGO_MANIFEST_DIR/Cargo.to").unwrap();
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Cargo.toml"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content(&manifest);
+
+    // Enter opens an expanded path directly
+    harness.new_buffer().unwrap();
+    harness
+        .send_key(KeyCode::Char('o'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .type_text("${CARGO_MANIFEST_DIR}/Cargo.toml")
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content(&manifest);
+}
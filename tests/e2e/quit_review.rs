@@ -0,0 +1,140 @@
+// End-to-end tests for reviewing unsaved buffers when quitting
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use tempfile::TempDir;
+
+/// Open a file and append `suffix` to its second line
+fn open_and_edit(harness: &mut EditorTestHarness, path: &std::path::Path, suffix: &str) {
+    harness.open_file(path).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    harness.type_text(suffix).unwrap();
+}
+
+fn press(harness: &mut EditorTestHarness, c: char) {
+    harness
+        .send_key(KeyCode::Char(c), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// The quit dialog lists the modified buffers and can save them all
+#[test]
+fn test_quit_save_all() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.txt");
+    let second = temp_dir.path().join("second.txt");
+    fs::write(&first, "one\ntwo\n").unwrap();
+    fs::write(&second, "alpha\nbeta\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    open_and_edit(&mut harness, &first, "!");
+    open_and_edit(&mut harness, &second, "?");
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("2 buffers have unsaved changes:");
+    let screen = harness.screen_to_string();
+    for name in ["first.txt", "second.txt"] {
+        assert!(
+            screen
+                .lines()
+                .any(|line| line.contains('•') && line.contains(name)),
+            "{} should be listed in:\n{}",
+            name,
+            screen
+        );
+    }
+
+    press(&mut harness, 's');
+    assert!(harness.should_quit());
+    assert_eq!(fs::read_to_string(&first).unwrap(), "one\ntwo!\n");
+    assert_eq!(fs::read_to_string(&second).unwrap(), "alpha\nbeta?\n");
+}
+
+/// Reviewing steps through each buffer with a preview of its changes
+#[test]
+fn test_quit_review_steps_through_buffers() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.txt");
+    let second = temp_dir.path().join("second.txt");
+    fs::write(&first, "one\ntwo\n").unwrap();
+    fs::write(&second, "alpha\nbeta\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    open_and_edit(&mut harness, &first, "!");
+    open_and_edit(&mut harness, &second, "?");
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    press(&mut harness, 'r');
+    harness.assert_screen_contains("Unsaved Changes (1 of 2)");
+    harness.assert_screen_contains("first.txt'?");
+    harness.assert_screen_contains("- two");
+    harness.assert_screen_contains("+ two!");
+
+    // Discard the first buffer's changes
+    press(&mut harness, 'd');
+    assert!(!harness.should_quit());
+    harness.assert_screen_contains("Unsaved Changes (2 of 2)");
+    harness.assert_screen_contains("- beta");
+    harness.assert_screen_contains("+ beta?");
+
+    // Save the second one; that was the last, so the editor quits
+    press(&mut harness, 's');
+    assert!(harness.should_quit());
+    assert_eq!(fs::read_to_string(&first).unwrap(), "one\ntwo\n");
+    assert_eq!(fs::read_to_string(&second).unwrap(), "alpha\nbeta?\n");
+}
+
+/// Cancelling partway through the review keeps the editor open
+#[test]
+fn test_quit_review_cancel() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.txt");
+    fs::write(&first, "one\ntwo\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    open_and_edit(&mut harness, &first, "!");
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    press(&mut harness, 'r');
+    press(&mut harness, 'c');
+    assert!(!harness.should_quit());
+    assert!(harness.editor().confirm_dialog().is_none());
+    harness.assert_screen_contains("Quit cancelled");
+    assert_eq!(harness.get_buffer_content().unwrap(), "one\ntwo!\n");
+}
+
+/// Saving a buffer without a file asks for a name, then carries on quitting
+#[test]
+fn test_quit_review_unnamed_buffer() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("notes.txt");
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        .unwrap();
+    press(&mut harness, 'r');
+    harness.assert_screen_contains("+ hello");
+
+    press(&mut harness, 's');
+    harness.assert_screen_contains("Save as:");
+    harness.type_text(path.to_str().unwrap()).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    assert!(harness.should_quit());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+}
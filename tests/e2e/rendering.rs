@@ -1,4 +1,6 @@
 use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
 use tempfile::TempDir;
 
 /// Test rendering of empty buffer
@@ -591,3 +593,148 @@ fn test_ansi_rgb_color_rendering() {
         );
     }
 }
+
+/// Test that the performance overlay shows typing latency percentiles and
+/// that every key is written to the latency trace
+#[test]
+fn test_performance_overlay_and_latency_trace() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let temp_dir = TempDir::new().unwrap();
+    let trace_path = temp_dir.path().join("latency.tsv");
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness
+        .editor_mut()
+        .enable_latency_trace(&trace_path)
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Toggle Performance Overlay").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("Performance overlay shown");
+
+    harness
+        .send_key(KeyCode::Char('x'), KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_contains("Latency (");
+    harness.assert_screen_contains("p99");
+    harness.assert_screen_contains("dispatch");
+    harness.assert_screen_contains("total");
+
+    let trace = std::fs::read_to_string(&trace_path).unwrap();
+    let mut lines = trace.lines();
+    assert_eq!(
+        lines.next(),
+        Some("key\tdispatch_us\tedit_us\twait_us\tstream_us\trender_us\tflush_us\ttotal_us")
+    );
+    let last = lines.last().unwrap();
+    assert!(last.starts_with("X\t"), "last traced key: {last}");
+    assert_eq!(last.split('\t').count(), 8);
+}
+
+/// Test that "Redact Secrets" masks secrets on screen only
+#[test]
+fn test_redact_secrets_masks_values_on_screen() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("user=alice\npassword=hunter2\n").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("password=hunter2");
+
+    harness.editor_mut().toggle_redact_secrets();
+    harness.render().unwrap();
+    harness.assert_screen_contains("password=███████");
+    harness.assert_screen_contains("user=alice");
+    harness.assert_buffer_content("user=alice\npassword=hunter2\n");
+
+    harness.editor_mut().toggle_redact_secrets();
+    harness.render().unwrap();
+    harness.assert_screen_contains("password=hunter2");
+}
+
+/// Test that the lock screen hides the buffers until a key is pressed
+#[test]
+fn test_lock_screen_hides_buffers_until_key() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("private notes").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("private notes");
+
+    harness.editor_mut().lock_screen();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Press any key to unlock");
+    harness.assert_screen_not_contains("private notes");
+
+    // The unlocking key isn't typed into the buffer
+    harness
+        .send_key(KeyCode::Char('x'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("private notes");
+    harness.assert_buffer_content("private notes");
+}
+
+/// Test that a configured passphrase has to be typed to unlock the screen
+#[test]
+fn test_lock_screen_passphrase() {
+    let mut config = Config::default();
+    config.editor.idle_lock_passphrase = Some("open".to_string());
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.type_text("private notes").unwrap();
+
+    harness.editor_mut().lock_screen();
+    harness.type_text("nope").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Wrong passphrase");
+    harness.assert_screen_not_contains("private notes");
+
+    harness.type_text("open").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("private notes");
+    harness.assert_buffer_content("private notes");
+}
+
+/// Extensionless scripts are highlighted by their `#!` line, and the
+/// `file_types` rules give other files a language
+#[test]
+fn test_file_type_detection() {
+    let temp_dir = TempDir::new().unwrap();
+    let script = temp_dir.path().join("deploy");
+    std::fs::write(&script, "#!/usr/bin/env bash\necho hi\n").unwrap();
+    let notes = temp_dir.path().join("notes");
+    std::fs::write(&notes, "Just some text\n").unwrap();
+    let conf = temp_dir.path().join("app.conf");
+    std::fs::write(&conf, "export A=1\n").unwrap();
+
+    let mut config = Config::default();
+    config.file_types.push(fresh::config::FileTypeRule {
+        name: Some("*.conf".to_string()),
+        first_line: None,
+        language: "sh".to_string(),
+    });
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    let backend = |harness: &EditorTestHarness| {
+        harness
+            .editor()
+            .active_state()
+            .highlighter
+            .backend_name()
+            .to_string()
+    };
+
+    harness.open_file(&script).unwrap();
+    assert_eq!(backend(&harness), "tree-sitter");
+    harness.open_file(&notes).unwrap();
+    assert_eq!(backend(&harness), "none");
+    harness.open_file(&conf).unwrap();
+    assert_eq!(backend(&harness), "tree-sitter");
+}
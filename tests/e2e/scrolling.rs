@@ -2316,3 +2316,87 @@ fn test_enter_at_bottom_scrolls_immediately() {
     );
     println!("✓ Cursor always stayed in content area (never moved to status bar)");
 }
+
+/// Typewriter scrolling keeps the cursor's line centered, and scrolling past
+/// the end lets the last line be centered too
+#[test]
+fn test_typewriter_scrolling_centers_cursor_line() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("prose.txt");
+    let content: String = (1..=100).map(|n| format!("L{:03}\n", n)).collect();
+    std::fs::write(&file_path, content).unwrap();
+
+    let mut config = fresh::config::Config::default();
+    config.editor.typewriter_scrolling = true;
+    config.editor.scroll_past_end = true;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    for _ in 0..40 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness.render().unwrap();
+    let (_, centered_row) = harness.screen_cursor_position();
+    assert!(
+        (8..=14).contains(&centered_row),
+        "cursor row {} isn't centered",
+        centered_row
+    );
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.screen_cursor_position().1, centered_row);
+    harness.assert_screen_contains("L042");
+
+    // At the end, the last line stays in the middle with empty rows below
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.screen_cursor_position().1, centered_row);
+    harness.assert_screen_contains("L100");
+}
+
+/// Focus mode dims the paragraphs the cursor isn't in, and follows it
+#[test]
+fn test_focus_mode_dims_other_paragraphs() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("prose.txt");
+    std::fs::write(&file_path, "Alpha one\nalpha two\n\nBravo three\n").unwrap();
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.editor_mut().toggle_focus_mode();
+    harness.render().unwrap();
+    let dim = harness.editor().theme().line_number_fg;
+    let fg_of = |harness: &EditorTestHarness, text: &str| {
+        let screen = harness.screen_to_string();
+        let (row, col) = screen
+            .lines()
+            .enumerate()
+            .find_map(|(row, line)| line.find(text).map(|col| (row, col)))
+            .unwrap_or_else(|| panic!("{} not on screen", text));
+        harness
+            .get_cell_style(col as u16, row as u16)
+            .and_then(|style| style.fg)
+    };
+    assert_ne!(fg_of(&harness, "alpha two"), Some(dim));
+    assert_eq!(fg_of(&harness, "Bravo"), Some(dim));
+
+    // Moving into the second paragraph moves the focus along
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.send_key(KeyCode::Up, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert_eq!(fg_of(&harness, "alpha two"), Some(dim));
+    assert_ne!(fg_of(&harness, "Bravo"), Some(dim));
+
+    harness.editor_mut().toggle_focus_mode();
+    harness.render().unwrap();
+    assert_ne!(fg_of(&harness, "alpha two"), Some(dim));
+}
@@ -825,6 +825,13 @@ fn test_replace_history_separate_from_search() {
         .unwrap();
     harness.render().unwrap();
 
+    // Confirm the replace-all
+    harness.assert_screen_contains("Replace 1 occurrence of 'hello' with 'goodbye'?");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
     // Open replace again - pre-fills with "hello" (last search history item)
     harness
         .send_key(KeyCode::Char('r'), KeyModifiers::CONTROL)
@@ -1428,3 +1435,107 @@ fn test_status_bar_hidden_during_suggestions() {
         "Status bar should show 'Palette:' indicator after closing command palette"
     );
 }
+
+/// Test that an invalid regex is reported inline while typing, pointing at the
+/// offending column, and that the error disappears once the pattern is fixed
+#[test]
+fn test_search_regex_error_shown_inline() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.txt");
+    std::fs::write(&file_path, "foo(bar)\nfoo baz").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    // Open search prompt and enable regex mode
+    harness
+        .send_key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::ALT)
+        .unwrap();
+    harness.render().unwrap();
+
+    // Type a pattern with an unclosed group
+    harness.type_text("foo(ba").unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("Error at column 4: unclosed group");
+
+    // Closing the group makes the error go away
+    harness.type_text(")").unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_not_contains("unclosed group");
+}
+
+/// Test that Alt+I restricts the search to the selection the prompt was opened with
+#[test]
+fn test_search_in_selection_toggle() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.txt");
+    std::fs::write(&file_path, "foo\nfoo\nfoo\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(120, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    // Select the first two lines
+    harness
+        .send_key(KeyCode::Down, KeyModifiers::SHIFT)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Down, KeyModifiers::SHIFT)
+        .unwrap();
+
+    // Open search prompt - the option is offered but not enabled
+    harness
+        .send_key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("[ ] In Selection");
+
+    // Enable searching in the selection
+    harness
+        .send_key(KeyCode::Char('i'), KeyModifiers::ALT)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("[x] In Selection");
+
+    harness.type_text("foo").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("Found 2 matches for 'foo' in selection");
+}
+
+/// Test that the search prompt shows which match Enter will jump to
+#[test]
+fn test_search_prompt_shows_match_count() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.txt");
+    std::fs::write(&file_path, "foo\nbar\nfoo\nfoo\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    // Move to the second line so the next match is the second one
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("foo").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("2 of 3"))
+        .unwrap();
+
+    harness.type_text("x").unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("No matches"))
+        .unwrap();
+}
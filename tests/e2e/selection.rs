@@ -1579,3 +1579,96 @@ fn test_select_at_file_boundaries() {
     // Just verify we can get selected text without panicking
     // The test validates that boundary operations don't crash
 }
+
+/// Test that selection mode extends the selection with plain movement keys
+#[test]
+fn test_selection_mode_extends_with_plain_movement() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello world test").unwrap();
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+
+    // F7 turns selection mode on, shown in the status bar
+    harness.send_key(KeyCode::F(7), KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("| Select");
+
+    // Word and character motions extend the selection without Shift
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::NONE)
+        .unwrap();
+    assert_eq!(harness.get_selected_text(), "hello ");
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.get_selected_text(), "hello world test");
+
+    // Escape ends the mode and clears the selection
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert!(!harness.has_selection());
+    harness.assert_screen_not_contains("| Select");
+}
+
+/// Test that an edit ends selection mode
+#[test]
+fn test_selection_mode_ends_on_edit() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.type_text("hello world").unwrap();
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+
+    harness.send_key(KeyCode::F(7), KeyModifiers::NONE).unwrap();
+    for _ in 0..5 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::NONE)
+            .unwrap();
+    }
+    assert_eq!(harness.get_selected_text(), "hello");
+
+    // Typing replaces the selection and leaves selection mode
+    harness.type_text("bye").unwrap();
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::NONE)
+        .unwrap();
+    assert!(!harness.has_selection());
+    assert_eq!(harness.get_buffer_content().unwrap(), "bye world");
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("| Select");
+}
+
+/// The selection's words and characters are counted in the status bar, and
+/// "Buffer Statistics" counts the buffer and the project's lines
+#[test]
+fn test_selection_counts_and_buffer_statistics() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+    let file = project_dir.join("main.py");
+    std::fs::write(&file, "# greet\nprint('hello world')\n\n").unwrap();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        100,
+        24,
+        fresh::config::Config::default(),
+        project_dir,
+    )
+    .unwrap();
+    harness.open_file(&file).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::SHIFT).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("2 words, 7 chars selected");
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Buffer Statistics").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Python"))
+        .unwrap();
+    harness.assert_screen_contains("main.py: 3 lines, 4 words, 30 chars");
+    harness.assert_screen_contains("Selection: 1 line, 2 words, 7 chars");
+    harness.assert_screen_contains("Session:");
+}
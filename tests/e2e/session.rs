@@ -6,7 +6,7 @@
 use crate::common::harness::EditorTestHarness;
 use crossterm::event::{KeyCode, KeyModifiers};
 use fresh::config::Config;
-use fresh::session::get_session_path;
+use fresh::session::{get_named_sessions_dir, get_session_path, get_snapshots_dir};
 use tempfile::TempDir;
 
 /// Test that session saves and restores open files
@@ -1017,3 +1017,179 @@ fn test_session_restores_splits() {
         harness.assert_buffer_content("Left split content");
     }
 }
+
+/// Test that a periodic snapshot can be restored from the command palette
+#[test]
+fn test_session_snapshot_restore() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+
+    let file1 = project_dir.join("a.txt");
+    let file2 = project_dir.join("b.txt");
+    std::fs::write(&file1, "Content of file A").unwrap();
+    std::fs::write(&file2, "Content of file B").unwrap();
+
+    // First session: open files and snapshot (no exit-time save, as in a crash)
+    {
+        let mut harness = EditorTestHarness::with_config_and_working_dir(
+            80,
+            24,
+            Config::default(),
+            project_dir.clone(),
+        )
+        .unwrap();
+
+        harness.open_file(&file1).unwrap();
+        harness.open_file(&file2).unwrap();
+
+        assert!(harness.editor_mut().snapshot_session().unwrap());
+        // Nothing changed, so there's nothing new to snapshot
+        assert!(!harness.editor_mut().snapshot_session().unwrap());
+    }
+
+    // Second session: restore the snapshot
+    {
+        let mut harness = EditorTestHarness::with_config_and_working_dir(
+            80,
+            24,
+            Config::default(),
+            project_dir.clone(),
+        )
+        .unwrap();
+        harness.assert_buffer_content("");
+
+        harness
+            .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.type_text("Restore Session from Snapshot").unwrap();
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+        harness.render().unwrap();
+        harness.assert_screen_contains("Restore snapshot:");
+        harness.assert_screen_contains("2 files");
+
+        harness
+            .send_key(KeyCode::Enter, KeyModifiers::NONE)
+            .unwrap();
+        harness.render().unwrap();
+        harness.assert_buffer_content("Content of file B");
+        harness.assert_screen_contains("Restored session from");
+    }
+
+    std::fs::remove_dir_all(get_snapshots_dir(&project_dir).unwrap()).unwrap();
+}
+
+/// Test that snapshots are only taken once the interval has passed
+#[test]
+fn test_session_snapshot_interval() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+
+    for minutes in [0, 5] {
+        let mut config = Config::default();
+        config.editor.session_snapshot_interval_mins = minutes;
+        let mut harness =
+            EditorTestHarness::with_config_and_working_dir(80, 24, config, project_dir.clone())
+                .unwrap();
+
+        // Disabled, or the interval hasn't passed yet
+        assert!(!harness.editor_mut().snapshot_session_if_due().unwrap());
+    }
+    assert!(!get_snapshots_dir(&project_dir).unwrap().exists());
+}
+
+/// Test saving named sessions and switching between them
+#[test]
+fn test_named_sessions_switch() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+
+    let file1 = project_dir.join("a.txt");
+    let file2 = project_dir.join("b.txt");
+    std::fs::write(&file1, "Content of file A").unwrap();
+    std::fs::write(&file2, "Content of file B").unwrap();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        80,
+        24,
+        Config::default(),
+        project_dir.clone(),
+    )
+    .unwrap();
+
+    // "work" has a.txt open
+    harness.open_file(&file1).unwrap();
+    harness.run_command("Save Session As").unwrap();
+    harness.assert_screen_contains("Save session as:");
+    harness.type_text("work").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Session saved as 'work'");
+    assert_eq!(harness.editor().session_name(), Some("work"));
+
+    // "blog" has b.txt open as well
+    harness.open_file(&file2).unwrap();
+    harness.editor_mut().save_session_as("blog").unwrap();
+
+    // The picker lists both, with when they were last used
+    harness.run_command("Switch Session").unwrap();
+    harness.assert_screen_contains("Switch session:");
+    harness.assert_screen_contains("(current) last used just now");
+    harness.type_text("work").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Switch to session 'work'?");
+
+    // Close the current session rather than stashing it
+    harness
+        .send_key(KeyCode::Char('l'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.editor().session_name(), Some("work"));
+    harness.assert_buffer_content("Content of file A");
+    harness.assert_screen_contains("Switched to session 'work'");
+    harness.assert_screen_not_contains("b.txt");
+
+    std::fs::remove_dir_all(get_named_sessions_dir(&project_dir).unwrap()).unwrap();
+}
+
+/// Test that switching sessions asks for unsaved changes to be dealt with first
+#[test]
+fn test_switch_session_with_unsaved_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir(&project_dir).unwrap();
+
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        80,
+        24,
+        Config::default(),
+        project_dir.clone(),
+    )
+    .unwrap();
+    harness.editor_mut().save_session_as("other").unwrap();
+    harness.editor_mut().save_session_as("current").unwrap();
+
+    harness.type_text("unsaved").unwrap();
+    harness.run_command("Switch Session").unwrap();
+    harness.type_text("other").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert!(harness.editor().confirm_dialog().is_none());
+    assert_eq!(harness.editor().session_name(), Some("current"));
+    harness.assert_buffer_content("unsaved");
+    harness.assert_screen_contains("Save or discard");
+
+    std::fs::remove_dir_all(get_named_sessions_dir(&project_dir).unwrap()).unwrap();
+}
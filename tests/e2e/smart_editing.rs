@@ -348,6 +348,72 @@ fn test_skip_over_closing_quote() {
     assert_eq!(harness.cursor_position(), 2);
 }
 
+/// Test that a closer typed by hand isn't skipped over, only auto-inserted ones
+#[test]
+fn test_no_skip_over_existing_closer() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.rs");
+    std::fs::write(&file_path, "f(x)").unwrap();
+
+    let mut harness = harness_with_auto_indent();
+    harness.open_file(&file_path).unwrap();
+
+    // Put the cursor before the existing ")"
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+
+    harness.type_text(")").unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "f(x))");
+}
+
+/// Test that typing a bracket or quote with a selection wraps the selection
+#[test]
+fn test_surround_selection_with_pair() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.rs");
+    std::fs::write(&file_path, "let s = hello;").unwrap();
+
+    let mut harness = harness_with_auto_indent();
+    harness.open_file(&file_path).unwrap();
+
+    // Select "hello"
+    harness
+        .send_key_repeat(KeyCode::Right, KeyModifiers::NONE, 8)
+        .unwrap();
+    harness
+        .send_key_repeat(KeyCode::Right, KeyModifiers::SHIFT, 5)
+        .unwrap();
+
+    harness.type_text("\"").unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "let s = \"hello\";");
+
+    // The text stays selected, so pairs can be stacked
+    harness.type_text("(").unwrap();
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "let s = \"(hello)\";"
+    );
+}
+
+/// Test that auto-surround can be turned off, so typing replaces the selection
+#[test]
+fn test_surround_selection_disabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.rs");
+    std::fs::write(&file_path, "hello").unwrap();
+
+    let mut config = Config::default();
+    config.editor.auto_surround = false;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key_repeat(KeyCode::Right, KeyModifiers::SHIFT, 5)
+        .unwrap();
+    harness.type_text("[").unwrap();
+    assert_eq!(harness.get_buffer_content().unwrap(), "[]");
+}
+
 /// Test that typing a closing delimiter does NOT skip if the char isn't a match
 #[test]
 fn test_no_skip_when_different_char() {
@@ -789,6 +855,48 @@ fn test_toggle_macro_recording() {
     );
 }
 
+/// Test recording into a letter register from the palette and replaying it
+/// with a repeat count; the keys typed into the prompts aren't recorded
+#[test]
+fn test_macro_replay_with_count() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.txt");
+    std::fs::write(&file_path, "a\nb\nc\nd").unwrap();
+
+    let mut harness = harness_with_auto_indent();
+    harness.open_file(&file_path).unwrap();
+
+    harness.run_command("Start Recording Macro").unwrap();
+    harness.type_text("q").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    harness.type_text(";").unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+
+    harness.run_command("Stop Recording").unwrap();
+    let status = harness
+        .editor()
+        .get_status_message()
+        .cloned()
+        .unwrap_or_default();
+    assert!(
+        status.contains("Macro 'q' saved (3 keys)"),
+        "Only the keys between the prompts should be recorded, got: {}",
+        status
+    );
+
+    harness.run_command("Replay Macro").unwrap();
+    harness.type_text("q 2").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    assert_eq!(harness.get_buffer_content().unwrap(), "a;\nb;\nc;\nd");
+}
+
 // =============================================================================
 // Jump to Next/Previous Error Tests
 // =============================================================================
@@ -800,7 +908,12 @@ fn apply_test_diagnostics(
 ) {
     let state = harness.editor_mut().active_state_mut();
     let theme = fresh::view::theme::Theme::dark();
-    fresh::services::lsp::diagnostics::apply_diagnostics_to_state(state, &diagnostics, &theme);
+    fresh::services::lsp::diagnostics::apply_diagnostics_to_state(
+        state,
+        &diagnostics,
+        &theme,
+        usize::MAX,
+    );
 }
 
 /// Create a simple diagnostic at a given position
@@ -1240,3 +1353,79 @@ fn test_block_selection_up() {
         .get_line_number(cursor.position);
     assert_eq!(cur_line, 1, "Cursor should be on line 1 after moving up");
 }
+
+// =============================================================================
+// Reflow and Hard Wrap Tests
+// =============================================================================
+
+/// Test "Reflow to Columns" rewraps the paragraph at the cursor, and not the
+/// next one
+#[test]
+fn test_reflow_to_columns_rewraps_paragraph() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(
+        &file_path,
+        "one two three four five six\nseven\n\nlast para here\n",
+    )
+    .unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Reflow to Columns 12").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    harness.assert_buffer_content("one two\nthree four\nfive six\nseven\n\nlast para here\n");
+}
+
+/// Test "Reflow Paragraph" rewraps a comment block keeping its leader,
+/// leaves code alone, and undoes in one step
+#[test]
+fn test_reflow_paragraph_in_code_comment() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("lib.rs");
+    let original = "fn main() {\n    // alpha beta gamma\n    // delta\n    let x = 1;\n}\n";
+    std::fs::write(&file_path, original).unwrap();
+
+    let mut config = Config::default();
+    config.editor.hard_wrap_column = 20;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.editor_mut().reflow_paragraph();
+    harness.assert_buffer_content(
+        "fn main() {\n    // alpha beta\n    // gamma delta\n    let x = 1;\n}\n",
+    );
+
+    // On a line of code there is nothing to reflow
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.editor_mut().reflow_paragraph();
+    harness.assert_buffer_content(
+        "fn main() {\n    // alpha beta\n    // gamma delta\n    let x = 1;\n}\n",
+    );
+
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content(original);
+}
+
+/// Test auto hard wrap breaks the line at the last space while typing
+#[test]
+fn test_auto_hard_wrap_while_typing() {
+    let mut config = Config::default();
+    config.editor.auto_hard_wrap = true;
+    config.editor.hard_wrap_column = 10;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+
+    harness.type_text("one two three four").unwrap();
+    harness.assert_buffer_content("one two\nthree four");
+    assert_eq!(harness.cursor_position(), "one two\nthree four".len());
+}
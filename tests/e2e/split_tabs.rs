@@ -197,6 +197,123 @@ fn test_buffer_cycling_within_split() {
     harness.assert_buffer_content("Content 2");
 }
 
+/// Test that Move Tab Left/Right reorder the tab bar, and buffer cycling
+/// follows the new order
+#[test]
+fn test_move_tab_reorders_tabs() {
+    let temp_dir = TempDir::new().unwrap();
+    let file1 = temp_dir.path().join("file1.txt");
+    let file2 = temp_dir.path().join("file2.txt");
+    let file3 = temp_dir.path().join("file3.txt");
+    std::fs::write(&file1, "Content 1").unwrap();
+    std::fs::write(&file2, "Content 2").unwrap();
+    std::fs::write(&file3, "Content 3").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file1).unwrap();
+    harness.open_file(&file2).unwrap();
+    harness.open_file(&file3).unwrap();
+    harness.render().unwrap();
+
+    let tab_columns = |harness: &EditorTestHarness| {
+        let screen = harness.screen_to_string();
+        let tab_row = screen
+            .lines()
+            .find(|line| line.contains("file1.txt"))
+            .unwrap()
+            .to_string();
+        ["file1.txt", "file2.txt", "file3.txt"].map(|name| tab_row.find(name).unwrap())
+    };
+    let [one, two, three] = tab_columns(&harness);
+    assert!(one < two && two < three);
+
+    // file3 moves to the front, past file2 and file1
+    for _ in 0..2 {
+        harness
+            .send_key(KeyCode::PageUp, KeyModifiers::ALT | KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    // A third move stops at the left end
+    harness
+        .send_key(KeyCode::PageUp, KeyModifiers::ALT | KeyModifiers::SHIFT)
+        .unwrap();
+    harness.render().unwrap();
+    let [one, two, three] = tab_columns(&harness);
+    assert!(three < one && one < two);
+    harness.assert_buffer_content("Content 3");
+
+    // The next buffer after file3 is now file1
+    harness
+        .send_key(KeyCode::PageDown, KeyModifiers::ALT)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("Content 1");
+
+    // Move file1 right, past file2
+    harness
+        .send_key(
+            KeyCode::PageDown,
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        )
+        .unwrap();
+    harness.render().unwrap();
+    let [one, two, three] = tab_columns(&harness);
+    assert!(three < two && two < one);
+}
+
+/// Test the buffer picker's keys for closing a tab and opening it in a split
+#[test]
+fn test_switch_to_tab_suggestion_actions() {
+    let temp_dir = TempDir::new().unwrap();
+    let file1 = temp_dir.path().join("file1.txt");
+    let file2 = temp_dir.path().join("file2.txt");
+    let file3 = temp_dir.path().join("file3.txt");
+    std::fs::write(&file1, "Content 1").unwrap();
+    std::fs::write(&file2, "Content 2").unwrap();
+    std::fs::write(&file3, "Content 3").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 30).unwrap();
+    harness.open_file(&file1).unwrap();
+    harness.open_file(&file2).unwrap();
+    harness.open_file(&file3).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("switch to tab by name").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    // The actions are listed below the suggestions
+    harness.assert_screen_contains("close");
+    harness.assert_screen_contains("open in split");
+
+    // file3 is selected; select file2 and close it
+    harness.send_key(KeyCode::Up, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Char('w'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("file2.txt");
+    // The picker stays open
+    harness.assert_screen_contains("Switch to tab:");
+
+    // The selection moved to file3, which opens in a new split
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("Switch to tab:");
+    harness.assert_buffer_content("Content 3");
+    let screen = harness.screen_to_string();
+    assert!(
+        screen.matches("file3.txt").count() >= 2,
+        "Expected file3.txt in both splits. Screen:\n{}",
+        screen
+    );
+}
+
 /// Test that tab bar appears within each split area
 #[test]
 fn test_tab_bar_in_split_area() {
@@ -375,3 +492,274 @@ fn test_debug_split_tabs_rendering() {
         screen
     );
 }
+
+/// Test that Ctrl+Tab cycles through buffers in most recently used order
+#[test]
+fn test_ctrl_tab_cycles_recent_buffers() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    for name in ["a", "b", "c"] {
+        let file_path = temp_dir.path().join(format!("{}.txt", name));
+        std::fs::write(&file_path, format!("Content {}", name)).unwrap();
+        harness.open_file(&file_path).unwrap();
+    }
+
+    // Each press goes one buffer further back, with the list shown meanwhile
+    harness
+        .send_key(KeyCode::Tab, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("Content b");
+    harness.assert_screen_contains("Recent Buffers");
+    harness
+        .send_key(KeyCode::Tab, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("Content a");
+
+    // Any other key ends the cycle and is handled as usual
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_screen_not_contains("Recent Buffers");
+    assert_eq!(harness.cursor_position(), 1);
+
+    // a is now the most recent, so the next buffer is c
+    harness
+        .send_key(KeyCode::Tab, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("Content c");
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.assert_buffer_content("Content a");
+    harness.assert_screen_not_contains("Recent Buffers");
+
+    // Ctrl+Shift+Tab goes back up the list
+    harness
+        .send_key(KeyCode::Tab, KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Tab, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("Content b");
+    harness
+        .send_key(
+            KeyCode::BackTab,
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        )
+        .unwrap();
+    harness.assert_buffer_content("Content c");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.assert_buffer_content("Content c");
+    harness.assert_screen_not_contains("Recent Buffers");
+}
+
+/// Test that Ctrl+Shift+T reopens closed buffers where they were left
+#[test]
+fn test_reopen_closed_buffer_restores_cursor() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    let a = temp_dir.path().join("a.txt");
+    let b = temp_dir.path().join("b.txt");
+    std::fs::write(&a, "Content a").unwrap();
+    std::fs::write(&b, "Content b").unwrap();
+    harness.open_file(&a).unwrap();
+    harness.open_file(&b).unwrap();
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::NONE)
+            .unwrap();
+    }
+
+    harness
+        .send_key(KeyCode::Char('w'), KeyModifiers::ALT)
+        .unwrap();
+    harness.assert_buffer_content("Content a");
+    harness
+        .send_key(KeyCode::Char('w'), KeyModifiers::ALT)
+        .unwrap();
+    harness.assert_buffer_content("");
+
+    // The most recently closed comes back first, at its cursor
+    harness
+        .send_key(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        )
+        .unwrap();
+    harness.assert_buffer_content("Content a");
+    harness
+        .send_key(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        )
+        .unwrap();
+    harness.assert_buffer_content("Content b");
+    assert_eq!(harness.cursor_position(), 3);
+
+    harness
+        .send_key(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        )
+        .unwrap();
+    harness.assert_screen_contains("No closed buffers to reopen");
+}
+
+/// Test that tabs of files with the same name show their parent directories
+#[test]
+fn test_same_named_files_have_distinct_tabs() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    for dir in ["editor", "ui"] {
+        let dir_path = temp_dir.path().join(dir);
+        std::fs::create_dir(&dir_path).unwrap();
+        let file_path = dir_path.join("mod.rs");
+        std::fs::write(&file_path, "").unwrap();
+        harness.open_file(&file_path).unwrap();
+    }
+    harness.render().unwrap();
+
+    let tab_bar = harness.get_tab_bar();
+    assert!(tab_bar.contains(" editor/mod.rs "), "tab bar: {tab_bar}");
+    assert!(tab_bar.contains(" ui/mod.rs "), "tab bar: {tab_bar}");
+}
+
+/// Run a command from the command palette and answer its prompt with `input`
+fn run_command_with_input(harness: &mut EditorTestHarness, name: &str, input: &str) {
+    harness.run_command(name).unwrap();
+    harness.type_text(input).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Test that buffer groups limit the tab bar to the active group
+#[test]
+fn test_buffer_groups_filter_tabs() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    let open = |harness: &mut EditorTestHarness, name: &str| {
+        let path = temp_dir.path().join(name);
+        std::fs::write(&path, format!("Content {}", name)).unwrap();
+        harness.open_file(&path).unwrap();
+    };
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        open(&mut harness, name);
+    }
+
+    run_command_with_input(&mut harness, "Create Buffer Group", "tests");
+    harness.assert_screen_contains("c.txt");
+    harness.assert_screen_not_contains("a.txt");
+    harness.assert_screen_not_contains("b.txt");
+
+    // Buffers opened meanwhile join the group
+    open(&mut harness, "d.txt");
+    harness.render().unwrap();
+    harness.assert_screen_contains("c.txt");
+    harness.assert_screen_contains("d.txt");
+
+    run_command_with_input(&mut harness, "Switch Buffer Group", "All Buffers");
+    harness.assert_screen_contains("a.txt");
+    harness.assert_screen_contains("b.txt");
+
+    // Move a.txt into the group; b.txt stays out of it
+    open(&mut harness, "a.txt");
+    run_command_with_input(&mut harness, "Move Buffer to Group", "tests");
+    run_command_with_input(&mut harness, "Switch Buffer Group", "tests");
+    harness.assert_screen_contains("a.txt");
+    harness.assert_screen_contains("d.txt");
+    harness.assert_screen_not_contains("b.txt");
+}
+
+/// Test that "Diff Buffers" shows the other buffer alongside, coloring changed lines
+#[test]
+fn test_diff_buffers_side_by_side() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    let old = temp_dir.path().join("old.txt");
+    let new = temp_dir.path().join("new.txt");
+    std::fs::write(&old, "one\ntwo\nthree\n").unwrap();
+    std::fs::write(&new, "one\n2\nthree\nfour\n").unwrap();
+    harness.open_file(&old).unwrap();
+    harness.open_file(&new).unwrap();
+
+    run_command_with_input(&mut harness, "Diff Buffers", "old");
+    harness.assert_screen_contains("four");
+    harness.assert_screen_contains("two");
+
+    // "four" is only in new.txt, the left side
+    let screen = harness.screen_to_string();
+    let (row, col) = screen
+        .lines()
+        .enumerate()
+        .find_map(|(y, line)| line.find("four").map(|x| (y, line[..x].chars().count())))
+        .unwrap();
+    let style = harness.get_cell_style(col as u16, row as u16).unwrap();
+    assert_eq!(style.fg, Some(harness.editor().theme().diff_removed_fg));
+}
+
+/// Test that locked panes scroll together, keeping their distance
+#[test]
+fn test_scroll_lock_keeps_panes_offset() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    let path = temp_dir.path().join("long.txt");
+    let content: String = (1..=200).map(|n| format!("L{:03}\n", n)).collect();
+    std::fs::write(&path, content).unwrap();
+    harness.open_file(&path).unwrap();
+
+    // The same file in two panes, the right one scrolled further down
+    harness.editor_mut().split_pane_vertical();
+    for _ in 0..2 {
+        harness
+            .send_key(KeyCode::PageDown, KeyModifiers::NONE)
+            .unwrap();
+    }
+    harness.render().unwrap();
+    harness.assert_screen_contains("L001");
+
+    harness.editor_mut().toggle_scroll_lock();
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Down, KeyModifiers::CONTROL)
+            .unwrap();
+    }
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("L003");
+    harness.assert_screen_contains("L004");
+
+    // Unlocked, the left pane stays put
+    harness.editor_mut().toggle_scroll_lock();
+    harness
+        .send_key(KeyCode::Down, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("L004");
+}
+
+/// Test that zooming a pane hides the others until it is toggled again
+#[test]
+fn test_zoom_pane_hides_and_restores_other_panes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    let left = temp_dir.path().join("left.txt");
+    let right = temp_dir.path().join("right.txt");
+    std::fs::write(&left, "left pane text").unwrap();
+    std::fs::write(&right, "right pane text").unwrap();
+    harness.open_file(&left).unwrap();
+    harness.editor_mut().split_pane_vertical();
+    harness.open_file(&right).unwrap();
+    harness.assert_screen_contains("left pane text");
+    harness.assert_screen_contains("right pane text");
+
+    harness.editor_mut().toggle_zoom_pane();
+    harness.render().unwrap();
+    harness.assert_screen_contains("right pane text");
+    harness.assert_screen_not_contains("left pane text");
+
+    harness.editor_mut().toggle_zoom_pane();
+    harness.render().unwrap();
+    harness.assert_screen_contains("left pane text");
+    harness.assert_screen_contains("right pane text");
+}
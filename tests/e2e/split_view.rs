@@ -142,6 +142,36 @@ fn test_close_split() {
     harness.assert_screen_contains("Closed split");
 }
 
+/// Test that closing the focused split moves editing to the pane that gets
+/// focus, with that pane's buffer and cursor
+#[test]
+fn test_close_split_focuses_remaining_pane() {
+    let temp_dir = TempDir::new().unwrap();
+    let file1 = temp_dir.path().join("file1.txt");
+    let file2 = temp_dir.path().join("file2.txt");
+    std::fs::write(&file1, "Content 1").unwrap();
+    std::fs::write(&file2, "Content 2").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 30).unwrap();
+    harness.open_file(&file1).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+
+    // The new pane opens another file
+    harness.run_command("split vert").unwrap();
+    harness.open_file(&file2).unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("Content 2");
+
+    harness.run_command("close split").unwrap();
+    harness.assert_screen_contains("Closed split");
+    harness.assert_buffer_content("Content 1");
+    assert_eq!(harness.cursor_position(), 9);
+
+    // Typing goes to the remaining pane's buffer
+    harness.type_text("!").unwrap();
+    harness.assert_buffer_content("Content 1!");
+}
+
 /// Test cannot close last split
 #[test]
 fn test_cannot_close_last_split() {
@@ -265,6 +265,43 @@ fn test_independent_scroll_positions_same_buffer() {
     );
 }
 
+/// Test that editing above what another split of the same buffer shows
+/// doesn't scroll that split's text
+#[test]
+fn test_edit_in_one_split_keeps_other_split_text_in_place() {
+    let mut harness = EditorTestHarness::new(80, 30).unwrap();
+    let long_text = (1..=50)
+        .map(|i| format!("Line {}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    harness.type_text(&long_text).unwrap();
+
+    // Read the end of the buffer in the second split
+    split_horizontal(&mut harness);
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    let row_of = |harness: &EditorTestHarness, text: &str| {
+        harness
+            .screen_to_string()
+            .lines()
+            .position(|line| line.contains(text))
+    };
+    let last_line_row = row_of(&harness, "Line 50");
+    assert!(last_line_row.is_some());
+
+    // Write at the top of the buffer in the first split
+    prev_split(&mut harness);
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Intro\n\n\n\n\n").unwrap();
+    harness.assert_screen_contains("Intro");
+
+    assert_eq!(row_of(&harness, "Line 50"), last_line_row);
+}
+
 /// Test next_split and prev_split circular navigation
 #[test]
 fn test_split_navigation_circular() {
@@ -143,3 +143,48 @@ fn test_viewport_updates_after_resize_with_open_file() {
         content_lines_after.len()
     );
 }
+
+/// Test that the cursor stays on the same screen row when the terminal resizes
+#[test]
+fn test_cursor_keeps_screen_row_after_resize() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test_file.txt");
+    let content: String = (1..=100).map(|i| format!("Line {}\n", i)).collect();
+    std::fs::write(&file_path, content).unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 30).unwrap();
+    harness.open_file(&file_path).unwrap();
+    for _ in 0..50 {
+        harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    }
+    harness.render().unwrap();
+    let (_, row_before) = harness.screen_cursor_position();
+
+    // Growing and shrinking (while the row still fits) keeps the cursor's row
+    harness.resize(60, 40).unwrap();
+    assert_eq!(harness.screen_cursor_position().1, row_before);
+    harness.resize(100, 25).unwrap();
+    assert_eq!(harness.screen_cursor_position().1, row_before);
+
+    // Shrinking past the cursor's row keeps it in view
+    harness.resize(80, 10).unwrap();
+    let (_, row_after) = harness.screen_cursor_position();
+    assert!(row_after < 10);
+    harness.assert_screen_contains("Line 51");
+}
+
+/// Test that a terminal too small for the editor shows a message instead
+#[test]
+fn test_terminal_too_small_shows_message() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.render().unwrap();
+
+    harness.resize(15, 4).unwrap();
+    harness.assert_screen_contains("small");
+
+    // Back to a usable size brings the editor back
+    harness.resize(80, 24).unwrap();
+    harness.assert_screen_not_contains("Terminal too small");
+}
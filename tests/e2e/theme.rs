@@ -260,3 +260,36 @@ fn test_theme_with_underscore_variant() {
     // Should still load high-contrast theme (accepts both - and _)
     assert_eq!(theme.name, "high-contrast");
 }
+
+#[test]
+fn test_theme_picker_shows_preview() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut harness = EditorTestHarness::new(100, 30).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Select theme").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+
+    // The highlighted theme is previewed beside the picker once the selection settles
+    harness
+        .wait_until(|h| h.screen_to_string().contains("fn greet(name: &str)"))
+        .expect("Theme preview should appear");
+    harness.assert_screen_contains(" high-contrast ");
+
+    // Moving the selection updates the preview
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    let selected = harness.editor().prompt_input().unwrap().to_string();
+    assert_ne!(selected, "high-contrast");
+    harness
+        .wait_until(|h| h.screen_to_string().contains(&format!(" {} ", selected)))
+        .expect("Preview should follow the selection");
+
+    // Closing the picker closes the preview
+    harness.send_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("fn greet(name: &str)");
+}